@@ -0,0 +1,273 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    fs, io,
+    path::PathBuf,
+};
+
+use chrono::{Date, Utc};
+use semver::Version;
+
+use crate::{changelog_path, git, is_dry_run, regex_data::CONVENTIONAL_COMMIT_REGEX, root_dir};
+
+const TITLE: &str = "# Changelog";
+const BREAKING_CHANGE_MARKER: &str = "BREAKING CHANGE";
+
+/// Errors which can occur while updating the changelog.
+#[derive(Debug)]
+pub(crate) enum Error {
+    /// Failed to read the commits from the git log.
+    Git(git::Error),
+    /// Failed to read the existing changelog file.
+    Io(io::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Git(error) => write!(formatter, "{}", error),
+            Error::Io(error) => write!(formatter, "{}", error),
+        }
+    }
+}
+
+/// The section of a changelog entry under which a given commit is listed.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum Section {
+    Added,
+    Changed,
+    Fixed,
+    Breaking,
+}
+
+impl Section {
+    /// All sections, in the order in which they're rendered.
+    const ALL: [Section; 4] = [
+        Section::Added,
+        Section::Changed,
+        Section::Fixed,
+        Section::Breaking,
+    ];
+
+    fn heading(self) -> &'static str {
+        match self {
+            Section::Added => "### Added",
+            Section::Changed => "### Changed",
+            Section::Fixed => "### Fixed",
+            Section::Breaking => "### Breaking",
+        }
+    }
+}
+
+/// Parses a single line of `git log --oneline` output into the section it belongs to and the
+/// description to list.
+///
+/// Subjects following the Conventional Commits format are categorized by their type: `feat` under
+/// "Added", `fix` under "Fixed" and any other type under "Changed".  A `!` after the type or scope,
+/// or a "BREAKING CHANGE" marker, puts the commit under "Breaking".  Subjects not following the
+/// format are listed verbatim under "Changed".
+fn parse_log_line(log_line: &str) -> Option<(Section, String)> {
+    // Strip the abbreviated commit hash.
+    let subject = log_line.trim().splitn(2, ' ').nth(1)?.trim();
+    if subject.is_empty() {
+        return None;
+    }
+
+    let captures = match CONVENTIONAL_COMMIT_REGEX.captures(subject) {
+        Some(captures) => captures,
+        None => return Some((Section::Changed, subject.to_string())),
+    };
+
+    let description = captures[3].to_string();
+    let section = if captures.get(2).is_some() || subject.contains(BREAKING_CHANGE_MARKER) {
+        Section::Breaking
+    } else {
+        match &captures[1] {
+            "feat" => Section::Added,
+            "fix" => Section::Fixed,
+            _ => Section::Changed,
+        }
+    };
+    Some((section, description))
+}
+
+/// Renders a changelog entry for `version` released on `date` from the given lines of
+/// `git log --oneline` output.
+fn render_entry(version: &Version, date: &str, log_lines: &[String]) -> String {
+    let parsed: Vec<_> = log_lines
+        .iter()
+        .filter_map(|log_line| parse_log_line(log_line))
+        .collect();
+
+    let mut entry = format!("## [{}] - {}\n", version, date);
+    for section in Section::ALL.iter() {
+        let descriptions: Vec<_> = parsed
+            .iter()
+            .filter(|(parsed_section, _)| parsed_section == section)
+            .map(|(_, description)| description)
+            .collect();
+        if descriptions.is_empty() {
+            continue;
+        }
+        entry.push('\n');
+        entry.push_str(section.heading());
+        entry.push('\n');
+        for description in descriptions {
+            entry.push_str(&format!("- {}\n", description));
+        }
+    }
+    entry
+}
+
+/// Inserts `entry` into the existing changelog contents, above all previous entries but below the
+/// title and any preamble.  If there are no existing contents, a new changelog is started.
+pub(crate) fn prepend_entry(existing: Option<&str>, entry: &str) -> String {
+    let existing = match existing {
+        Some(existing) if !existing.trim().is_empty() => existing,
+        _ => return format!("{}\n\n{}", TITLE, entry),
+    };
+
+    if !existing.starts_with("# ") {
+        return format!("{}\n{}", entry, existing);
+    }
+
+    let insert_at = existing
+        .match_indices("\n## ")
+        .next()
+        .map(|(index, _)| index + 1)
+        .unwrap_or_else(|| existing.len());
+    let (head, tail) = existing.split_at(insert_at);
+    let separator = if head.ends_with("\n\n") {
+        ""
+    } else if head.ends_with('\n') {
+        "\n"
+    } else {
+        "\n\n"
+    };
+    if tail.is_empty() {
+        format!("{}{}{}", head, separator, entry)
+    } else {
+        format!("{}{}{}\n{}", head, separator, entry, tail)
+    }
+}
+
+/// Formats `date` as used in changelog entry headings, i.e. `YYYY-MM-DD`.
+pub(crate) fn release_date(date: Date<Utc>) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// Returns the path and updated contents of the changelog, with an entry for `version` listing all
+/// commits since the previous tag prepended.  If the changelog doesn't already exist, the contents
+/// start a new one.
+///
+/// On a dry run, prints the entry and returns `None`.
+pub(crate) fn updated_changelog(version: &Version) -> Result<Option<(PathBuf, String)>, Error> {
+    let previous_tag = git::previous_tag(root_dir()).map_err(Error::Git)?;
+    let log_lines = git::log_since(root_dir(), previous_tag.as_deref()).map_err(Error::Git)?;
+    let date = release_date(Utc::today());
+    let entry = render_entry(version, &date, &log_lines);
+
+    let path = root_dir().join(changelog_path());
+    if is_dry_run() {
+        println!("Will prepend to {}:\n\n{}", path.display(), entry);
+        return Ok(None);
+    }
+
+    let existing = match fs::read_to_string(&path) {
+        Ok(contents) => Some(contents),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+        Err(error) => return Err(Error::Io(error)),
+    };
+    let updated_contents = prepend_entry(existing.as_deref(), &entry);
+    Ok(Some((path, updated_contents)))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    const DUMMY_LOG: &str = "\
+a1b2c3d feat(node): add gossip peer saturation
+b2c3d4e fix: handle missing config file
+c3d4e5f refactor(types)!: rename auction entry points
+d4e5f6a docs: update README
+e5f6a7b Bump dependencies
+f6a7b8c feat: new step request BREAKING CHANGE
+";
+
+    fn dummy_log_lines() -> Vec<String> {
+        DUMMY_LOG.lines().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn should_parse_conventional_commits() {
+        assert_eq!(
+            parse_log_line("a1b2c3d feat(node): add thing"),
+            Some((Section::Added, "add thing".to_string()))
+        );
+        assert_eq!(
+            parse_log_line("a1b2c3d fix: broken thing"),
+            Some((Section::Fixed, "broken thing".to_string()))
+        );
+        assert_eq!(
+            parse_log_line("a1b2c3d chore!: drop thing"),
+            Some((Section::Breaking, "drop thing".to_string()))
+        );
+        assert_eq!(
+            parse_log_line("a1b2c3d Merge the thing"),
+            Some((Section::Changed, "Merge the thing".to_string()))
+        );
+        assert_eq!(parse_log_line("a1b2c3d"), None);
+    }
+
+    #[test]
+    fn should_render_entry_from_git_log() {
+        let entry = render_entry(&Version::new(1, 2, 3), "2020-10-01", &dummy_log_lines());
+        let expected = "\
+## [1.2.3] - 2020-10-01
+
+### Added
+- add gossip peer saturation
+
+### Changed
+- update README
+- Bump dependencies
+
+### Fixed
+- handle missing config file
+
+### Breaking
+- rename auction entry points
+- new step request BREAKING CHANGE
+";
+        assert_eq!(entry, expected);
+    }
+
+    #[test]
+    fn should_create_changelog_if_missing() {
+        let entry = "## [1.0.0] - 2020-10-01\n";
+        assert_eq!(
+            prepend_entry(None, entry),
+            "# Changelog\n\n## [1.0.0] - 2020-10-01\n"
+        );
+    }
+
+    #[test]
+    fn should_prepend_entry_below_title() {
+        let existing = "# Changelog\n\nAll notable changes.\n\n## [1.0.0] - 2020-09-01\n- old\n";
+        let entry = "## [1.1.0] - 2020-10-01\n- new\n";
+        assert_eq!(
+            prepend_entry(Some(existing), entry),
+            "# Changelog\n\nAll notable changes.\n\n## [1.1.0] - 2020-10-01\n- new\n\n## [1.0.0] \
+            - 2020-09-01\n- old\n"
+        );
+    }
+
+    #[test]
+    fn should_format_release_date_with_zero_padding() {
+        assert_eq!(release_date(Utc.ymd(2020, 1, 5)), "2020-01-05");
+        assert_eq!(release_date(Utc.ymd(2020, 12, 31)), "2020-12-31");
+        assert_eq!(release_date(Utc.ymd(2021, 2, 28)), "2021-02-28");
+    }
+}
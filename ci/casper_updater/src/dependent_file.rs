@@ -5,14 +5,19 @@ use std::{
 
 use regex::Regex;
 
+use crate::regex_data;
+
 /// A file which is dependent on the version of a certain CasperLabs crate.
 pub struct DependentFile {
     /// Full path to the file.
     path: PathBuf,
     /// Current contents of the file.
     contents: String,
-    /// Regex applicable to the portion to be updated.
-    regex: Regex,
+    /// Regexes applicable to the portions to be updated.  Each must get a match in the file.
+    regexes: Vec<Regex>,
+    /// Regexes applicable to portions which only exist in some versions of the file's format, e.g.
+    /// the `packages` section of a v2 `package-lock.json`.  These are only applied if they match.
+    optional_regexes: Vec<Regex>,
     /// Function which generates the replacement string once the updated version is known.
     replacement: fn(&str) -> String,
 }
@@ -22,31 +27,103 @@ impl DependentFile {
         relative_path: P,
         regex: Regex,
         replacement: fn(&str) -> String,
+    ) -> Self {
+        Self::with_regexes(relative_path, vec![regex], vec![], replacement)
+    }
+
+    /// Constructs a `DependentFile` where several portions of the file need to be updated.
+    pub fn with_regexes<P: AsRef<Path>>(
+        relative_path: P,
+        regexes: Vec<Regex>,
+        optional_regexes: Vec<Regex>,
+        replacement: fn(&str) -> String,
     ) -> Self {
         let path = crate::root_dir().join(relative_path);
         let contents = fs::read_to_string(&path)
             .unwrap_or_else(|error| panic!("should read {}: {:?}", path.display(), error));
-        assert!(
-            regex.find(&contents).is_some(),
-            "regex '{}' failed to get a match in {}",
-            regex,
-            path.display()
-        );
+        Self::from_contents(path, contents, regexes, optional_regexes, replacement)
+    }
+
+    /// Constructs a `DependentFile` for an npm manifest which may list the scoped package
+    /// `scoped_name`, e.g. `@casperlabs/types`, as a dependency.
+    ///
+    /// The dependency is optional, since not every version of the manifest lists it.  Any `^` or
+    /// `~` range prefix on the dependency's version is excluded from the captured version and
+    /// preserved when the version is updated.
+    pub fn npm_scoped<P: AsRef<Path>>(relative_path: P, scoped_name: &str) -> Self {
+        Self::with_regexes(
+            relative_path,
+            vec![],
+            vec![regex_data::npm_scoped_dependency_regex(scoped_name)],
+            regex_data::npm_scoped_replacement,
+        )
+    }
+
+    fn from_contents(
+        path: PathBuf,
+        contents: String,
+        regexes: Vec<Regex>,
+        optional_regexes: Vec<Regex>,
+        replacement: fn(&str) -> String,
+    ) -> Self {
+        for regex in &regexes {
+            assert!(
+                regex.find(&contents).is_some(),
+                "regex '{}' failed to get a match in {}",
+                regex,
+                path.display()
+            );
+        }
 
         DependentFile {
             path,
             contents,
-            regex,
+            regexes,
+            optional_regexes,
             replacement,
         }
     }
 
-    pub fn update(&self, updated_version: &str) {
-        let updated_contents = self
-            .regex
-            .replace(&self.contents, (self.replacement)(updated_version).as_str());
-        fs::write(&self.path, updated_contents.as_ref())
-            .unwrap_or_else(|error| panic!("should write {}: {:?}", self.path.display(), error));
+    /// Returns this file's path and its contents with every matching portion updated to
+    /// `updated_version`.
+    ///
+    /// If the file already has pending changes from updating a different package, these should be
+    /// passed as `pending_contents` so that the update is applied on top of them.
+    pub fn update(
+        &self,
+        pending_contents: Option<&str>,
+        updated_version: &str,
+    ) -> (PathBuf, String) {
+        let contents = pending_contents.unwrap_or(&self.contents);
+        (
+            self.path.clone(),
+            self.updated_contents(contents, updated_version),
+        )
+    }
+
+    /// Returns `contents` with every matching portion updated to `updated_version`.
+    fn updated_contents(&self, contents: &str, updated_version: &str) -> String {
+        let replacement = (self.replacement)(updated_version);
+        self.regexes
+            .iter()
+            .chain(self.optional_regexes.iter())
+            .fold(contents.to_string(), |contents, regex| {
+                regex.replace(&contents, replacement.as_str()).into_owned()
+            })
+    }
+
+    /// Returns the number of optional regexes which get a match in this file.
+    pub fn optional_match_count(&self) -> usize {
+        self.optional_regexes
+            .iter()
+            .filter(|regex| regex.find(&self.contents).is_some())
+            .count()
+    }
+
+    /// Returns whether this file will be affected by an update, i.e. whether any of its regexes
+    /// get a match.
+    pub fn is_affected(&self) -> bool {
+        !self.regexes.is_empty() || self.optional_match_count() > 0
     }
 
     pub fn path(&self) -> &Path {
@@ -57,3 +134,128 @@ impl DependentFile {
         &self.contents
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regex_data::{
+        PACKAGE_JSON_VERSION_REGEX, PACKAGE_LOCK_JSON_ROOT_PACKAGE_VERSION_REGEX,
+    };
+
+    const PACKAGE_JSON: &str = include_str!("../fixtures/package.json");
+    const UPDATED_PACKAGE_JSON: &str = include_str!("../fixtures/updated/package.json");
+    const PACKAGE_LOCK_JSON_V1: &str = include_str!("../fixtures/package-lock-v1.json");
+    const UPDATED_PACKAGE_LOCK_JSON_V1: &str =
+        include_str!("../fixtures/updated/package-lock-v1.json");
+    const PACKAGE_LOCK_JSON_V2: &str = include_str!("../fixtures/package-lock-v2.json");
+    const UPDATED_PACKAGE_LOCK_JSON_V2: &str =
+        include_str!("../fixtures/updated/package-lock-v2.json");
+    const SCOPED_PACKAGE_JSON: &str = include_str!("../fixtures/package-scoped.json");
+    const UPDATED_SCOPED_PACKAGE_JSON: &str =
+        include_str!("../fixtures/updated/package-scoped.json");
+    const UPDATED_VERSION: &str = "1.2.3";
+
+    fn npm_scoped(contents: &str, scoped_name: &str) -> DependentFile {
+        DependentFile::from_contents(
+            PathBuf::from("package.json"),
+            contents.to_string(),
+            vec![],
+            vec![regex_data::npm_scoped_dependency_regex(scoped_name)],
+            regex_data::npm_scoped_replacement,
+        )
+    }
+
+    fn package_lock_json(contents: &str) -> DependentFile {
+        DependentFile::from_contents(
+            PathBuf::from("package-lock.json"),
+            contents.to_string(),
+            vec![PACKAGE_JSON_VERSION_REGEX.clone()],
+            vec![PACKAGE_LOCK_JSON_ROOT_PACKAGE_VERSION_REGEX.clone()],
+            regex_data::replacement,
+        )
+    }
+
+    #[test]
+    fn should_update_package_json() {
+        let package_json = DependentFile::from_contents(
+            PathBuf::from("package.json"),
+            PACKAGE_JSON.to_string(),
+            vec![PACKAGE_JSON_VERSION_REGEX.clone()],
+            vec![],
+            regex_data::replacement,
+        );
+        assert_eq!(
+            package_json.update(None, UPDATED_VERSION).1,
+            UPDATED_PACKAGE_JSON
+        );
+    }
+
+    #[test]
+    fn should_update_v1_package_lock_json() {
+        let package_lock_json = package_lock_json(PACKAGE_LOCK_JSON_V1);
+        assert_eq!(package_lock_json.optional_match_count(), 0);
+        assert_eq!(
+            package_lock_json.update(None, UPDATED_VERSION).1,
+            UPDATED_PACKAGE_LOCK_JSON_V1
+        );
+    }
+
+    #[test]
+    fn should_update_v2_package_lock_json() {
+        let package_lock_json = package_lock_json(PACKAGE_LOCK_JSON_V2);
+        assert_eq!(package_lock_json.optional_match_count(), 1);
+        assert_eq!(
+            package_lock_json.update(None, UPDATED_VERSION).1,
+            UPDATED_PACKAGE_LOCK_JSON_V2
+        );
+    }
+
+    #[test]
+    fn should_apply_update_on_top_of_pending_contents() {
+        let package_json = DependentFile::from_contents(
+            PathBuf::from("package.json"),
+            "{}".to_string(),
+            vec![],
+            vec![PACKAGE_JSON_VERSION_REGEX.clone()],
+            regex_data::replacement,
+        );
+        let (_, updated_contents) = package_json.update(Some(PACKAGE_JSON), UPDATED_VERSION);
+        assert_eq!(updated_contents, UPDATED_PACKAGE_JSON);
+    }
+
+    #[test]
+    fn should_update_scoped_npm_dependencies_preserving_range_prefixes() {
+        let contract = npm_scoped(SCOPED_PACKAGE_JSON, "@casperlabs/contract");
+        let types = npm_scoped(SCOPED_PACKAGE_JSON, "@casperlabs/types");
+        assert_eq!(contract.optional_match_count(), 1);
+        assert_eq!(types.optional_match_count(), 1);
+        assert!(types.is_affected());
+
+        let (_, pending_contents) = contract.update(None, UPDATED_VERSION);
+        let (_, updated_contents) = types.update(Some(&pending_contents), UPDATED_VERSION);
+        assert_eq!(updated_contents, UPDATED_SCOPED_PACKAGE_JSON);
+    }
+
+    #[test]
+    fn should_capture_scoped_npm_dependency_version_without_range_prefix() {
+        let regex = regex_data::npm_scoped_dependency_regex("@casperlabs/types");
+        let captures = regex
+            .captures(SCOPED_PACKAGE_JSON)
+            .expect("should match scoped dependency");
+        assert_eq!(&captures[2], "~");
+        assert_eq!(&captures[3], "0.5.0");
+    }
+
+    #[test]
+    fn should_not_update_unlisted_scoped_npm_dependency() {
+        let package_json = npm_scoped(PACKAGE_JSON, "@casperlabs/types");
+        assert!(!package_json.is_affected());
+        assert_eq!(package_json.update(None, UPDATED_VERSION).1, PACKAGE_JSON);
+    }
+
+    #[test]
+    #[should_panic(expected = "failed to get a match")]
+    fn should_panic_if_required_regex_fails_to_match() {
+        let _ = package_lock_json("{}");
+    }
+}
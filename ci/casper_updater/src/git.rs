@@ -0,0 +1,412 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    io::{self, ErrorKind},
+    path::Path,
+    process::{Command, Output},
+};
+
+use semver::Version;
+
+const GIT: &str = "git";
+
+/// Errors which can occur while running `git`.
+#[derive(Debug)]
+pub(crate) enum Error {
+    /// The `git` executable couldn't be found.
+    GitNotFound,
+    /// A tag with the given name already exists.
+    TagExists(String),
+    /// Failed to run `git`.
+    Io(io::Error),
+    /// `git` ran, but exited unsuccessfully.
+    Git { args: String, stderr: String },
+}
+
+impl Display for Error {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::GitNotFound => write!(formatter, "git executable not found"),
+            Error::TagExists(tag) => write!(formatter, "tag {} already exists", tag),
+            Error::Io(error) => write!(formatter, "failed to run git: {}", error),
+            Error::Git { args, stderr } => {
+                write!(formatter, "'git {}' failed: {}", args, stderr.trim_end())
+            }
+        }
+    }
+}
+
+/// Runs `git` with the given args in `repo_dir`.
+fn run_git(repo_dir: &Path, args: &[&str]) -> Result<Output, Error> {
+    Command::new(GIT)
+        .args(args)
+        .current_dir(repo_dir)
+        .output()
+        .map_err(|error| match error.kind() {
+            ErrorKind::NotFound => Error::GitNotFound,
+            _ => Error::Io(error),
+        })
+}
+
+/// Runs `git` with the given args in `repo_dir`, returning its stdout if it exited successfully.
+fn git_stdout(repo_dir: &Path, args: &[&str]) -> Result<String, Error> {
+    let output = run_git(repo_dir, args)?;
+    if !output.status.success() {
+        return Err(Error::Git {
+            args: args.join(" "),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Returns the most recent tag reachable from `HEAD`, or `None` if there is no such tag.
+pub(crate) fn previous_tag(repo_dir: &Path) -> Result<Option<String>, Error> {
+    let output = run_git(repo_dir, &["describe", "--tags", "--abbrev=0"])?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    let tag = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok(Some(tag).filter(|tag| !tag.is_empty()))
+}
+
+/// Returns the output of `git log` with the given format args for all commits after `since_tag` up
+/// to `HEAD`, one entry per commit.  If `since_tag` is `None`, the full history is included.
+fn log(
+    repo_dir: &Path,
+    since_tag: Option<&str>,
+    format_args: &[&str],
+) -> Result<Vec<String>, Error> {
+    let range = since_tag.map(|tag| format!("{}..HEAD", tag));
+    let mut args = vec!["log"];
+    args.extend_from_slice(format_args);
+    if let Some(range) = range.as_ref() {
+        args.push(range);
+    }
+    let stdout = git_stdout(repo_dir, &args)?;
+    Ok(stdout
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(ToString::to_string)
+        .collect())
+}
+
+/// Returns the output of `git log --oneline --no-merges` for all commits after `since_tag` up to
+/// `HEAD`, one entry per commit.  If `since_tag` is `None`, the full history is included.
+pub(crate) fn log_since(repo_dir: &Path, since_tag: Option<&str>) -> Result<Vec<String>, Error> {
+    log(repo_dir, since_tag, &["--oneline", "--no-merges"])
+}
+
+/// Returns the subject line of every commit after `since_tag` up to `HEAD`.  If `since_tag` is
+/// `None`, the full history is included.
+pub(crate) fn subjects_since(
+    repo_dir: &Path,
+    since_tag: Option<&str>,
+) -> Result<Vec<String>, Error> {
+    log(repo_dir, since_tag, &["--format=%s"])
+}
+
+/// Creates an annotated tag named `<tag_prefix><version>` in the git repo at `repo_dir`, returning
+/// the name of the new tag.
+pub(crate) fn tag_release(
+    repo_dir: &Path,
+    tag_prefix: &str,
+    version: &Version,
+) -> Result<String, Error> {
+    let tag = format!("{}{}", tag_prefix, version);
+
+    let tag_ref = format!("refs/tags/{}", tag);
+    if run_git(repo_dir, &["rev-parse", "--quiet", "--verify", &tag_ref])?
+        .status
+        .success()
+    {
+        return Err(Error::TagExists(tag));
+    }
+
+    let message = format!("Release {}", tag);
+    let _ = git_stdout(repo_dir, &["tag", "-a", &tag, "-m", &message])?;
+
+    Ok(tag)
+}
+
+/// Returns the paths of all files with uncommitted changes in the working tree of the repo at
+/// `repo_dir`, including untracked files, as listed by `git status --porcelain -z`.  Paths are
+/// relative to the root of the working tree and sorted.  Both the old and the new path of a renamed
+/// or copied file are included.
+///
+/// `repo_dir` may also be a linked worktree created by `git worktree add`, in which case only the
+/// changes in that worktree are listed.
+pub(crate) fn dirty_paths(repo_dir: &Path) -> Result<Vec<String>, Error> {
+    let stdout = git_stdout(repo_dir, &["status", "--porcelain", "-z"])?;
+    let mut paths = parse_porcelain_z(&stdout);
+    paths.sort();
+    paths.dedup();
+    Ok(paths)
+}
+
+/// Parses the NUL-separated records of `git status --porcelain -z`, returning every path listed.
+///
+/// Each record is `XY <path>`.  For renames and copies, the record is followed by a further one
+/// holding just the original path.  Paths are never quoted in this format.
+fn parse_porcelain_z(output: &str) -> Vec<String> {
+    let mut paths = vec![];
+    let mut records = output.split('\0').filter(|record| !record.is_empty());
+    while let Some(record) = records.next() {
+        let (status, path) = match (record.get(..2), record.get(3..)) {
+            (Some(status), Some(path)) => (status, path),
+            _ => continue,
+        };
+        paths.push(path.to_string());
+        if status.contains('R') || status.contains('C') {
+            if let Some(original_path) = records.next() {
+                paths.push(original_path.to_string());
+            }
+        }
+    }
+    paths
+}
+
+/// Stages exactly the given files and commits them with `message`, returning the hash of the new
+/// commit.  Any other changes in the repo at `repo_dir`, staged or not, are left uncommitted.
+pub(crate) fn commit_files<P: AsRef<Path>>(
+    repo_dir: &Path,
+    paths: &[P],
+    message: &str,
+) -> Result<String, Error> {
+    let paths: Vec<_> = paths
+        .iter()
+        .map(|path| path.as_ref().to_string_lossy())
+        .collect();
+    let paths: Vec<&str> = paths.iter().map(AsRef::as_ref).collect();
+
+    let mut add_args = vec!["add", "--"];
+    add_args.extend_from_slice(&paths);
+    let _ = git_stdout(repo_dir, &add_args)?;
+
+    let mut commit_args = vec!["commit", "--quiet", "--only", "-m", message, "--"];
+    commit_args.extend_from_slice(&paths);
+    let _ = git_stdout(repo_dir, &commit_args)?;
+
+    let commit = git_stdout(repo_dir, &["rev-parse", "HEAD"])?;
+    Ok(commit.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn git(repo_dir: &Path, args: &[&str]) {
+        let output = run_git(repo_dir, args).expect("should run git");
+        assert!(
+            output.status.success(),
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    fn temp_repo() -> TempDir {
+        let repo = tempfile::tempdir().expect("should create temp dir");
+        git(repo.path(), &["init", "--quiet"]);
+        git(repo.path(), &["config", "user.name", "Casper Updater"]);
+        git(
+            repo.path(),
+            &["config", "user.email", "updater@example.com"],
+        );
+        git(
+            repo.path(),
+            &["commit", "--quiet", "--allow-empty", "-m", "Initial commit"],
+        );
+        repo
+    }
+
+    const PACKAGE_JSON: &str = include_str!("../fixtures/package.json");
+    const UPDATED_PACKAGE_JSON: &str = include_str!("../fixtures/updated/package.json");
+    const CHANGELOG: &str = include_str!("../fixtures/CHANGELOG.md");
+    const UPDATED_CHANGELOG: &str = include_str!("../fixtures/updated/CHANGELOG.md");
+
+    /// Creates a temp repo with a committed fixture package and changelog.
+    fn temp_repo_with_package() -> TempDir {
+        let repo = temp_repo();
+        fs::write(repo.path().join("package.json"), PACKAGE_JSON).unwrap();
+        fs::write(repo.path().join("CHANGELOG.md"), CHANGELOG).unwrap();
+        git(repo.path(), &["add", "."]);
+        git(repo.path(), &["commit", "--quiet", "-m", "Add package"]);
+        repo
+    }
+
+    #[test]
+    fn should_list_dirty_paths() {
+        let repo = temp_repo_with_package();
+        assert!(dirty_paths(repo.path()).unwrap().is_empty());
+
+        fs::write(repo.path().join("package.json"), UPDATED_PACKAGE_JSON).unwrap();
+        fs::write(repo.path().join("notes.txt"), "local notes").unwrap();
+        assert_eq!(
+            dirty_paths(repo.path()).unwrap(),
+            ["notes.txt", "package.json"]
+        );
+    }
+
+    #[test]
+    fn should_list_renamed_paths_and_paths_with_spaces() {
+        let repo = temp_repo_with_package();
+        git(repo.path(), &["mv", "CHANGELOG.md", "OLD CHANGELOG.md"]);
+        fs::write(repo.path().join("release notes.txt"), "local notes").unwrap();
+        assert_eq!(
+            dirty_paths(repo.path()).unwrap(),
+            ["CHANGELOG.md", "OLD CHANGELOG.md", "release notes.txt"]
+        );
+    }
+
+    #[test]
+    fn should_parse_porcelain_z_records() {
+        let output = " M package.json\0R  new name.md\0old name.md\0?? notes.txt\0";
+        assert_eq!(
+            parse_porcelain_z(output),
+            ["package.json", "new name.md", "old name.md", "notes.txt"]
+        );
+    }
+
+    #[test]
+    fn should_list_dirty_paths_and_commit_in_linked_worktree() {
+        let repo = temp_repo_with_package();
+        let worktree_parent = tempfile::tempdir().expect("should create temp dir");
+        let worktree = worktree_parent.path().join("release");
+        git(
+            repo.path(),
+            &[
+                "worktree",
+                "add",
+                "--quiet",
+                "-b",
+                "release",
+                &worktree.to_string_lossy(),
+            ],
+        );
+        assert!(dirty_paths(&worktree).unwrap().is_empty());
+
+        // Changes in the main working tree don't make the linked worktree dirty, and vice versa.
+        fs::write(repo.path().join("notes.txt"), "local notes").unwrap();
+        fs::write(worktree.join("package.json"), UPDATED_PACKAGE_JSON).unwrap();
+        assert_eq!(dirty_paths(&worktree).unwrap(), ["package.json"]);
+        assert_eq!(dirty_paths(repo.path()).unwrap(), ["notes.txt"]);
+
+        let commit = commit_files(&worktree, &[worktree.join("package.json")], "Release 1.2.3")
+            .expect("should commit");
+        let release_head = git_stdout(repo.path(), &["rev-parse", "release"]).unwrap();
+        assert_eq!(commit, release_head.trim());
+        assert!(dirty_paths(&worktree).unwrap().is_empty());
+    }
+
+    #[test]
+    fn should_commit_only_given_files() {
+        let repo = temp_repo_with_package();
+
+        // An unrelated staged change, which shouldn't be committed.
+        fs::write(repo.path().join("notes.txt"), "local notes").unwrap();
+        git(repo.path(), &["add", "notes.txt"]);
+
+        // The files updated by the tool, including a newly created one.
+        fs::write(repo.path().join("package.json"), UPDATED_PACKAGE_JSON).unwrap();
+        fs::write(repo.path().join("CHANGELOG.md"), UPDATED_CHANGELOG).unwrap();
+        fs::create_dir(repo.path().join("types")).unwrap();
+        fs::write(repo.path().join("types/CHANGELOG.md"), UPDATED_CHANGELOG).unwrap();
+
+        let paths = [
+            repo.path().join("package.json"),
+            repo.path().join("CHANGELOG.md"),
+            repo.path().join("types/CHANGELOG.md"),
+        ];
+        let commit = commit_files(repo.path(), &paths, "Release 1.2.3").expect("should commit");
+
+        let head = git_stdout(repo.path(), &["rev-parse", "HEAD"]).unwrap();
+        assert_eq!(commit, head.trim());
+        let show =
+            git_stdout(repo.path(), &["show", "--name-only", "--format=%s", "HEAD"]).unwrap();
+        assert_eq!(
+            show.lines()
+                .filter(|line| !line.is_empty())
+                .collect::<Vec<_>>(),
+            [
+                "Release 1.2.3",
+                "CHANGELOG.md",
+                "package.json",
+                "types/CHANGELOG.md"
+            ]
+        );
+        assert_eq!(dirty_paths(repo.path()).unwrap(), ["notes.txt"]);
+    }
+
+    #[test]
+    fn should_tag_release() {
+        let repo = temp_repo();
+        let version = Version::new(1, 2, 3);
+
+        let tag = tag_release(repo.path(), "v", &version).expect("should tag");
+        assert_eq!(tag, "v1.2.3");
+
+        let output = run_git(repo.path(), &["tag", "-n1", "--list", &tag]).unwrap();
+        let listed = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(
+            listed.split_whitespace().collect::<Vec<_>>(),
+            ["v1.2.3", "Release", "v1.2.3"]
+        );
+    }
+
+    #[test]
+    fn should_use_tag_prefix() {
+        let repo = temp_repo();
+        let version = Version::new(1, 2, 3);
+
+        let tag = tag_release(repo.path(), "release-", &version).expect("should tag");
+        assert_eq!(tag, "release-1.2.3");
+    }
+
+    #[test]
+    fn should_log_commits_since_previous_tag() {
+        let repo = temp_repo();
+        assert_eq!(previous_tag(repo.path()).unwrap(), None);
+
+        let _ = tag_release(repo.path(), "v", &Version::new(1, 0, 0)).expect("should tag");
+        assert_eq!(
+            previous_tag(repo.path()).unwrap().as_deref(),
+            Some("v1.0.0")
+        );
+        git(
+            repo.path(),
+            &[
+                "commit",
+                "--quiet",
+                "--allow-empty",
+                "-m",
+                "feat: add thing",
+            ],
+        );
+
+        let log = log_since(repo.path(), Some("v1.0.0")).expect("should log");
+        assert_eq!(log.len(), 1);
+        assert!(log[0].ends_with(" feat: add thing"));
+
+        let full_log = log_since(repo.path(), None).expect("should log");
+        assert_eq!(full_log.len(), 2);
+
+        let subjects = subjects_since(repo.path(), Some("v1.0.0")).expect("should log");
+        assert_eq!(subjects, ["feat: add thing"]);
+    }
+
+    #[test]
+    fn should_fail_if_tag_exists() {
+        let repo = temp_repo();
+        let version = Version::new(1, 2, 3);
+
+        let _ = tag_release(repo.path(), "v", &version).expect("should tag");
+        match tag_release(repo.path(), "v", &version) {
+            Err(Error::TagExists(tag)) => assert_eq!(tag, "v1.2.3"),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+}
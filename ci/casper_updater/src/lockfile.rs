@@ -0,0 +1,133 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+    process::{Command, ExitStatus},
+};
+
+use tracing::debug;
+
+const UPDATE_ARGS: [&str; 2] = ["update", "--workspace"];
+
+/// Errors which can occur while regenerating the workspace's `Cargo.lock`.
+#[derive(Debug)]
+pub(crate) enum Error {
+    /// The `cargo` executable couldn't be found at the given path.
+    CargoNotFound(PathBuf),
+    /// Failed to run `cargo`.
+    Io(io::Error),
+    /// `cargo` ran, but exited unsuccessfully.
+    Cargo { status: ExitStatus, stderr: String },
+}
+
+impl Display for Error {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::CargoNotFound(path) => write!(
+                formatter,
+                "cargo executable not found at {}",
+                path.display()
+            ),
+            Error::Io(error) => write!(formatter, "failed to run cargo: {}", error),
+            Error::Cargo { status, stderr } => write!(
+                formatter,
+                "'cargo {}' failed with {}: {}",
+                UPDATE_ARGS.join(" "),
+                status,
+                stderr.trim_end()
+            ),
+        }
+    }
+}
+
+/// Runs `cargo update --workspace` in `root_dir` using the `cargo` executable at `cargo_path`, so
+/// that `Cargo.lock` reflects the updated package versions.
+///
+/// The command's stdout and stderr are logged at debug level.
+pub(crate) fn update(cargo_path: &Path, root_dir: &Path) -> Result<(), Error> {
+    let output = Command::new(cargo_path)
+        .args(&UPDATE_ARGS)
+        .current_dir(root_dir)
+        .output()
+        .map_err(|error| match error.kind() {
+            ErrorKind::NotFound => Error::CargoNotFound(cargo_path.to_path_buf()),
+            _ => Error::Io(error),
+        })?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    debug!(%stdout, %stderr, "ran cargo {}", UPDATE_ARGS.join(" "));
+
+    if !output.status.success() {
+        return Err(Error::Cargo {
+            status: output.status,
+            stderr: stderr.into_owned(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::{fs, os::unix::fs::PermissionsExt};
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    const ARGS_FILE: &str = "args.txt";
+
+    /// Creates a mock `cargo` script which records its args and working directory to `args.txt`
+    /// and exits with `exit_code`.
+    fn mock_cargo(dir: &TempDir, exit_code: i32) -> PathBuf {
+        let path = dir.path().join("cargo");
+        let script = format!(
+            "#!/bin/sh\necho \"$@\" > {}\npwd >> {}\necho failure >&2\nexit {}\n",
+            ARGS_FILE, ARGS_FILE, exit_code
+        );
+        fs::write(&path, script).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[test]
+    fn should_run_cargo_update_in_root_dir() {
+        let dir = tempfile::tempdir().expect("should create temp dir");
+        let cargo_path = mock_cargo(&dir, 0);
+
+        update(&cargo_path, dir.path()).expect("should update lockfile");
+
+        let recorded = fs::read_to_string(dir.path().join(ARGS_FILE)).unwrap();
+        let mut lines = recorded.lines();
+        assert_eq!(lines.next(), Some("update --workspace"));
+        assert_eq!(
+            lines.next().map(|pwd| fs::canonicalize(pwd).unwrap()),
+            Some(fs::canonicalize(dir.path()).unwrap())
+        );
+    }
+
+    #[test]
+    fn should_fail_if_cargo_fails() {
+        let dir = tempfile::tempdir().expect("should create temp dir");
+        let cargo_path = mock_cargo(&dir, 3);
+
+        match update(&cargo_path, dir.path()) {
+            Err(Error::Cargo { status, stderr }) => {
+                assert_eq!(status.code(), Some(3));
+                assert_eq!(stderr.trim(), "failure");
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn should_fail_if_cargo_not_found() {
+        let dir = tempfile::tempdir().expect("should create temp dir");
+        let cargo_path = dir.path().join("missing-cargo");
+
+        match update(&cargo_path, dir.path()) {
+            Err(Error::CargoNotFound(path)) => assert_eq!(path, cargo_path),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+}
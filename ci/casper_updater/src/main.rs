@@ -33,20 +33,30 @@
     unknown_crate_types
 )]
 
+mod changelog;
 mod dependent_file;
+mod git;
+mod lockfile;
 mod package;
 mod regex_data;
+mod release_notes;
+mod transaction;
 
 use std::{
     env,
+    fmt::{self, Display, Formatter},
     path::{Path, PathBuf},
+    process,
     str::FromStr,
 };
 
 use clap::{crate_version, App, Arg};
 use lazy_static::lazy_static;
+use semver::Version;
 
-use package::Package;
+use package::{FileChanges, Package};
+use regex_data::CONVENTIONAL_COMMIT_REGEX;
+use transaction::Transaction;
 
 const APP_NAME: &str = "Casper Updater";
 
@@ -61,27 +71,159 @@ const BUMP_ARG_SHORT: &str = "b";
 const BUMP_ARG_VALUE_NAME: &str = "VERSION-COMPONENT";
 const BUMP_ARG_HELP: &str =
     "Increase all crates' versions automatically without asking for user input.  For a crate at \
-    version x.y.z, the version will be bumped to (x+1).0.0, x.(y+1).0, or x.y.(z+1) depending on \
-    which version component is specified";
+    version x.y.z, the version will be bumped to (x+1).0.0, x.(y+1).0, x.y.(z+1) or \
+    x.y.z-<label>.1 depending on which version component is specified.  If the crate is already at \
+    version x.y.z-<label>.N, 'pre' bumps it to x.y.z-<label>.(N+1).  'auto' derives the component \
+    from the subjects of all commits since the previous git tag: major if any marks a breaking \
+    change, minor if any adds a feature, else patch";
 const MAJOR: &str = "major";
 const MINOR: &str = "minor";
 const PATCH: &str = "patch";
+const PRE: &str = "pre";
+const AUTO: &str = "auto";
+
+const PRE_LABEL_ARG_NAME: &str = "pre-label";
+const PRE_LABEL_ARG_SHORT: &str = "p";
+const PRE_LABEL_ARG_VALUE_NAME: &str = "LABEL";
+const PRE_LABEL_ARG_HELP: &str =
+    "Label of the pre-release version, e.g. 'alpha', 'beta' or 'rc'.  Required if --bump=pre";
 
 const DRY_RUN_ARG_NAME: &str = "dry-run";
 const DRY_RUN_ARG_SHORT: &str = "d";
 const DRY_RUN_ARG_HELP: &str = "Check all regexes get matches in current casper-node repo";
 
-#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
+const TAG_ARG_NAME: &str = "tag";
+const TAG_ARG_SHORT: &str = "t";
+const TAG_ARG_HELP: &str =
+    "Create an annotated git tag for the release once all files have been updated.  The tag is \
+    named after the updated version of the types crate";
+
+const TAG_PREFIX_ARG_NAME: &str = "tag-prefix";
+const TAG_PREFIX_ARG_VALUE_NAME: &str = "PREFIX";
+const TAG_PREFIX_ARG_HELP: &str = "Prefix of the git tag created via --tag";
+const DEFAULT_TAG_PREFIX: &str = "v";
+
+const CHANGELOG_ARG_NAME: &str = "changelog";
+const CHANGELOG_ARG_SHORT: &str = "l";
+const CHANGELOG_ARG_HELP: &str =
+    "Prepend an entry for the updated version of the types crate to the changelog, listing all \
+    non-merge commits since the previous git tag.  Commits following the Conventional Commits \
+    format are grouped under Added, Changed, Fixed and Breaking";
+
+const CHANGELOG_PATH_ARG_NAME: &str = "changelog-path";
+const CHANGELOG_PATH_ARG_VALUE_NAME: &str = "PATH";
+const CHANGELOG_PATH_ARG_HELP: &str =
+    "Path to the changelog updated via --changelog, relative to the casper-node root directory.  \
+    The file is created if it doesn't exist";
+const DEFAULT_CHANGELOG_PATH: &str = "CHANGELOG.md";
+
+const RELEASE_NOTES_ARG_NAME: &str = "emit-release-notes";
+const RELEASE_NOTES_ARG_VALUE_NAME: &str = "DIR";
+const RELEASE_NOTES_ARG_HELP: &str =
+    "For every package whose version is updated, insert a stub section headed '## [X.Y.Z] - \
+    YYYY-MM-DD' into the CHANGELOG.md in the package's directory under DIR, and print suggested \
+    git tag commands for the updated packages.  DIR is relative to the casper-node root \
+    directory, so '.' updates the changelogs alongside the packages' manifests.  Changelogs are \
+    created if they don't exist";
+
+const NO_ROLLBACK_ARG_NAME: &str = "no-rollback";
+const NO_ROLLBACK_ARG_HELP: &str =
+    "Don't restore the original contents of updated files if a later step fails, e.g. writing a \
+    different file, updating the changelog or tagging the release";
+
+const UPDATE_LOCKFILE_ARG_NAME: &str = "update-lockfile";
+const UPDATE_LOCKFILE_ARG_SHORT: &str = "u";
+const UPDATE_LOCKFILE_ARG_HELP: &str =
+    "Run 'cargo update --workspace' in the casper-node root directory once all files have been \
+    updated, so that Cargo.lock reflects the updated versions";
+
+const CARGO_PATH_ARG_NAME: &str = "cargo-path";
+const CARGO_PATH_ARG_VALUE_NAME: &str = "PATH";
+const CARGO_PATH_ARG_HELP: &str =
+    "Path to the cargo executable used via --update-lockfile.  If not supplied, cargo is found via \
+    PATH";
+const DEFAULT_CARGO_PATH: &str = "cargo";
+
+const ALLOW_DIRTY_ARG_NAME: &str = "allow-dirty";
+const ALLOW_DIRTY_ARG_HELP: &str =
+    "Run even if the casper-node working tree has uncommitted changes.  By default the tool aborts \
+    without modifying any files, listing the dirty paths";
+
+const COMMIT_ARG_NAME: &str = "commit";
+const COMMIT_ARG_HELP: &str =
+    "Once all files have been updated, stage exactly the files modified by the tool and commit \
+    them with a standard message giving the version bump and the updated versions.  If --tag is \
+    also passed, the tag points at the new commit";
+
+const LOCKFILE: &str = "Cargo.lock";
+
+const BREAKING_CHANGE_PREFIX: &str = "BREAKING CHANGE:";
+const FEATURE_TYPE: &str = "feat";
+
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Debug)]
 pub(crate) enum BumpVersion {
     Major,
     Minor,
     Patch,
+    Prerelease { label: String },
+}
+
+impl Display for BumpVersion {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BumpVersion::Major => write!(formatter, "{}", MAJOR),
+            BumpVersion::Minor => write!(formatter, "{}", MINOR),
+            BumpVersion::Patch => write!(formatter, "{}", PATCH),
+            BumpVersion::Prerelease { label } => write!(formatter, "{}-{}", PRE, label),
+        }
+    }
+}
+
+impl BumpVersion {
+    /// Derives the version component to bump from the subjects of all commits since the previous
+    /// git tag in the repo at `root`.
+    pub(crate) fn derive(root: &Path) -> Result<BumpVersion, git::Error> {
+        let previous_tag = git::previous_tag(root)?;
+        let subjects = git::subjects_since(root, previous_tag.as_deref())?;
+        Ok(Self::from_commit_subjects(&subjects))
+    }
+
+    /// Returns `Major` if any subject marks a breaking change, i.e. starts with "BREAKING CHANGE:"
+    /// or has a `!` at its end or after its Conventional Commits type or scope.  Otherwise returns
+    /// `Minor` if any subject is a `feat`, else `Patch`.
+    fn from_commit_subjects<S: AsRef<str>>(subjects: &[S]) -> BumpVersion {
+        let mut bump_version = BumpVersion::Patch;
+        for subject in subjects.iter().map(|subject| subject.as_ref().trim()) {
+            let captures = CONVENTIONAL_COMMIT_REGEX.captures(subject);
+            if subject.starts_with(BREAKING_CHANGE_PREFIX)
+                || subject.ends_with('!')
+                || captures
+                    .as_ref()
+                    .map_or(false, |captures| captures.get(2).is_some())
+            {
+                return BumpVersion::Major;
+            }
+            if captures.map_or(false, |captures| &captures[1] == FEATURE_TYPE) {
+                bump_version = BumpVersion::Minor;
+            }
+        }
+        bump_version
+    }
 }
 
 struct Args {
     root_dir: PathBuf,
     bump_version: Option<BumpVersion>,
     dry_run: bool,
+    tag_prefix: Option<String>,
+    changelog: bool,
+    changelog_path: PathBuf,
+    release_notes_dir: Option<PathBuf>,
+    no_rollback: bool,
+    update_lockfile: bool,
+    cargo_path: PathBuf,
+    allow_dirty: bool,
+    commit: bool,
 }
 
 /// The full path to the casper-node root directory.
@@ -90,8 +232,8 @@ pub(crate) fn root_dir() -> &'static Path {
 }
 
 /// The version component to bump, if any.
-pub(crate) fn bump_version() -> Option<BumpVersion> {
-    ARGS.bump_version
+pub(crate) fn bump_version() -> Option<&'static BumpVersion> {
+    ARGS.bump_version.as_ref()
 }
 
 /// Whether we're doing a dry run or not.
@@ -99,6 +241,52 @@ pub(crate) fn is_dry_run() -> bool {
     ARGS.dry_run
 }
 
+/// The prefix of the git tag to create once all packages are updated, if tagging was requested.
+fn tag_prefix() -> Option<&'static str> {
+    ARGS.tag_prefix.as_deref()
+}
+
+/// Whether to prepend an entry for the release to the changelog.
+fn is_changelog_requested() -> bool {
+    ARGS.changelog
+}
+
+/// The path to the changelog, relative to the casper-node root directory.
+pub(crate) fn changelog_path() -> &'static Path {
+    &ARGS.changelog_path
+}
+
+/// The directory under which to update the changelogs of updated packages, relative to the
+/// casper-node root directory, if release notes were requested.
+fn release_notes_dir() -> Option<&'static Path> {
+    ARGS.release_notes_dir.as_deref()
+}
+
+/// Whether to restore the original contents of updated files if a later step fails.
+fn is_rollback_enabled() -> bool {
+    !ARGS.no_rollback
+}
+
+/// Whether to regenerate `Cargo.lock` once all files have been updated.
+fn is_lockfile_update_requested() -> bool {
+    ARGS.update_lockfile
+}
+
+/// The path to the `cargo` executable used to regenerate `Cargo.lock`.
+fn cargo_path() -> &'static Path {
+    &ARGS.cargo_path
+}
+
+/// Whether to run even if the working tree has uncommitted changes.
+fn is_dirty_allowed() -> bool {
+    ARGS.allow_dirty
+}
+
+/// Whether to commit the updated files once they have all been written.
+fn is_commit_requested() -> bool {
+    ARGS.commit
+}
+
 lazy_static! {
     static ref ARGS: Args = get_args();
 }
@@ -121,7 +309,16 @@ fn get_args() -> Args {
                 .value_name(BUMP_ARG_VALUE_NAME)
                 .help(BUMP_ARG_HELP)
                 .takes_value(true)
-                .possible_values(&[MAJOR, MINOR, PATCH]),
+                .possible_values(&[MAJOR, MINOR, PATCH, PRE, AUTO]),
+        )
+        .arg(
+            Arg::with_name(PRE_LABEL_ARG_NAME)
+                .long(PRE_LABEL_ARG_NAME)
+                .short(PRE_LABEL_ARG_SHORT)
+                .value_name(PRE_LABEL_ARG_VALUE_NAME)
+                .help(PRE_LABEL_ARG_HELP)
+                .takes_value(true)
+                .required_if(BUMP_ARG_NAME, PRE),
         )
         .arg(
             Arg::with_name(DRY_RUN_ARG_NAME)
@@ -129,6 +326,70 @@ fn get_args() -> Args {
                 .short(DRY_RUN_ARG_SHORT)
                 .help(DRY_RUN_ARG_HELP),
         )
+        .arg(
+            Arg::with_name(TAG_ARG_NAME)
+                .long(TAG_ARG_NAME)
+                .short(TAG_ARG_SHORT)
+                .help(TAG_ARG_HELP),
+        )
+        .arg(
+            Arg::with_name(TAG_PREFIX_ARG_NAME)
+                .long(TAG_PREFIX_ARG_NAME)
+                .value_name(TAG_PREFIX_ARG_VALUE_NAME)
+                .help(TAG_PREFIX_ARG_HELP)
+                .takes_value(true)
+                .default_value(DEFAULT_TAG_PREFIX),
+        )
+        .arg(
+            Arg::with_name(CHANGELOG_ARG_NAME)
+                .long(CHANGELOG_ARG_NAME)
+                .short(CHANGELOG_ARG_SHORT)
+                .help(CHANGELOG_ARG_HELP),
+        )
+        .arg(
+            Arg::with_name(CHANGELOG_PATH_ARG_NAME)
+                .long(CHANGELOG_PATH_ARG_NAME)
+                .value_name(CHANGELOG_PATH_ARG_VALUE_NAME)
+                .help(CHANGELOG_PATH_ARG_HELP)
+                .takes_value(true)
+                .default_value(DEFAULT_CHANGELOG_PATH),
+        )
+        .arg(
+            Arg::with_name(RELEASE_NOTES_ARG_NAME)
+                .long(RELEASE_NOTES_ARG_NAME)
+                .value_name(RELEASE_NOTES_ARG_VALUE_NAME)
+                .help(RELEASE_NOTES_ARG_HELP)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(NO_ROLLBACK_ARG_NAME)
+                .long(NO_ROLLBACK_ARG_NAME)
+                .help(NO_ROLLBACK_ARG_HELP),
+        )
+        .arg(
+            Arg::with_name(UPDATE_LOCKFILE_ARG_NAME)
+                .long(UPDATE_LOCKFILE_ARG_NAME)
+                .short(UPDATE_LOCKFILE_ARG_SHORT)
+                .help(UPDATE_LOCKFILE_ARG_HELP),
+        )
+        .arg(
+            Arg::with_name(CARGO_PATH_ARG_NAME)
+                .long(CARGO_PATH_ARG_NAME)
+                .value_name(CARGO_PATH_ARG_VALUE_NAME)
+                .help(CARGO_PATH_ARG_HELP)
+                .takes_value(true)
+                .default_value(DEFAULT_CARGO_PATH),
+        )
+        .arg(
+            Arg::with_name(ALLOW_DIRTY_ARG_NAME)
+                .long(ALLOW_DIRTY_ARG_NAME)
+                .help(ALLOW_DIRTY_ARG_HELP),
+        )
+        .arg(
+            Arg::with_name(COMMIT_ARG_NAME)
+                .long(COMMIT_ARG_NAME)
+                .help(COMMIT_ARG_HELP),
+        )
         .get_matches();
 
     let root_dir = match arg_matches.value_of(ROOT_DIR_ARG_NAME) {
@@ -148,58 +409,411 @@ fn get_args() -> Args {
             MAJOR => BumpVersion::Major,
             MINOR => BumpVersion::Minor,
             PATCH => BumpVersion::Patch,
+            PRE => BumpVersion::Prerelease {
+                label: arg_matches
+                    .value_of(PRE_LABEL_ARG_NAME)
+                    .expect("pre-label should be required for pre-release bump")
+                    .to_string(),
+            },
+            AUTO => BumpVersion::derive(&root_dir).unwrap_or_else(|error| {
+                eprintln!("Failed to derive version bump: {}.", error);
+                process::exit(1);
+            }),
             _ => unreachable!(),
         });
 
     let dry_run = arg_matches.is_present(DRY_RUN_ARG_NAME);
 
+    let tag_prefix = if arg_matches.is_present(TAG_ARG_NAME) {
+        arg_matches
+            .value_of(TAG_PREFIX_ARG_NAME)
+            .map(ToString::to_string)
+    } else {
+        None
+    };
+
+    let changelog = arg_matches.is_present(CHANGELOG_ARG_NAME);
+
+    let changelog_path = PathBuf::from_str(
+        arg_matches
+            .value_of(CHANGELOG_PATH_ARG_NAME)
+            .expect("changelog-path should have a default value"),
+    )
+    .expect("should be a valid unicode path");
+
+    let release_notes_dir = arg_matches
+        .value_of(RELEASE_NOTES_ARG_NAME)
+        .map(|path| PathBuf::from_str(path).expect("should be a valid unicode path"));
+
+    let no_rollback = arg_matches.is_present(NO_ROLLBACK_ARG_NAME);
+
+    let update_lockfile = arg_matches.is_present(UPDATE_LOCKFILE_ARG_NAME);
+
+    let cargo_path = PathBuf::from_str(
+        arg_matches
+            .value_of(CARGO_PATH_ARG_NAME)
+            .expect("cargo-path should have a default value"),
+    )
+    .expect("should be a valid unicode path");
+
+    let allow_dirty = arg_matches.is_present(ALLOW_DIRTY_ARG_NAME);
+
+    let commit = arg_matches.is_present(COMMIT_ARG_NAME);
+
     Args {
         root_dir,
         bump_version,
         dry_run,
+        tag_prefix,
+        changelog,
+        changelog_path,
+        release_notes_dir,
+        no_rollback,
+        update_lockfile,
+        cargo_path,
+        allow_dirty,
+        commit,
     }
 }
 
 fn main() {
-    let types = Package::cargo("types", &*regex_data::types::DEPENDENT_FILES);
-    types.update();
+    tracing_subscriber::fmt::init();
 
-    let execution_engine = Package::cargo(
-        "execution_engine",
-        &*regex_data::execution_engine::DEPENDENT_FILES,
-    );
-    execution_engine.update();
+    // Check the working tree is clean before asking for any versions, so that the release changes
+    // can't get mixed up with unrelated local edits.
+    if !is_dry_run() && !is_dirty_allowed() {
+        match git::dirty_paths(root_dir()) {
+            Ok(dirty_paths) if dirty_paths.is_empty() => (),
+            Ok(dirty_paths) => {
+                eprintln!("{}", dirty_tree_message(root_dir(), &dirty_paths));
+                process::exit(1);
+            }
+            Err(error) => {
+                eprintln!("Failed to check the working tree is clean: {}.", error);
+                process::exit(1);
+            }
+        }
+    }
 
-    let node = Package::cargo("node", &*regex_data::node::DEPENDENT_FILES);
-    node.update();
+    // The types package must be first, as its version is used for the changelog and release tag.
+    let packages = vec![
+        Package::cargo("types", &*regex_data::types::DEPENDENT_FILES),
+        Package::cargo(
+            "execution_engine",
+            &*regex_data::execution_engine::DEPENDENT_FILES,
+        ),
+        Package::cargo("node", &*regex_data::node::DEPENDENT_FILES),
+        Package::cargo("grpc/server", &*regex_data::grpc_server::DEPENDENT_FILES),
+        Package::cargo("client", &*regex_data::client::DEPENDENT_FILES),
+        Package::cargo(
+            "smart_contracts/contract",
+            &*regex_data::smart_contracts_contract::DEPENDENT_FILES,
+        ),
+        Package::assembly_script(
+            "smart_contracts/contract_as",
+            &*regex_data::smart_contracts_contract_as::DEPENDENT_FILES,
+        ),
+        Package::cargo(
+            "grpc/test_support",
+            &*regex_data::grpc_test_support::DEPENDENT_FILES,
+        ),
+        Package::cargo(
+            "grpc/cargo_casper",
+            &*regex_data::grpc_cargo_casper::DEPENDENT_FILES,
+        ),
+    ];
 
-    let grpc_server = Package::cargo("grpc/server", &*regex_data::grpc_server::DEPENDENT_FILES);
-    grpc_server.update();
+    // Versions are chosen and file changes computed in dependency order, so that changes to files
+    // shared by several packages build on each other.  Only then are all files written.
+    let mut updated_versions = vec![None; packages.len()];
+    let mut file_changes = FileChanges::new();
+    let mut updated_packages = vec![];
+    for index in package::dependency_order(&packages) {
+        let package = &packages[index];
+        let updated_version = package.updated_version();
+        file_changes.extend(package.update(&updated_version, &file_changes));
+        if updated_version != *package.current_version() {
+            updated_packages.push((package, updated_version.clone()));
+        }
+        updated_versions[index] = Some(updated_version);
+    }
 
-    let client = Package::cargo("client", &*regex_data::client::DEPENDENT_FILES);
-    client.update();
+    let types_version = updated_versions[0]
+        .take()
+        .expect("types package should have been updated");
 
-    let smart_contracts_contract = Package::cargo(
-        "smart_contracts/contract",
-        &*regex_data::smart_contracts_contract::DEPENDENT_FILES,
-    );
-    smart_contracts_contract.update();
+    if is_changelog_requested() {
+        match changelog::updated_changelog(&types_version) {
+            Ok(Some((path, contents))) => {
+                let _ = file_changes.insert(path, contents);
+            }
+            Ok(None) => (),
+            Err(error) => {
+                eprintln!("Failed to update changelog: {}.", error);
+                process::exit(1);
+            }
+        }
+    }
 
-    let smart_contracts_contract_as = Package::assembly_script(
-        "smart_contracts/contract_as",
-        &*regex_data::smart_contracts_contract_as::DEPENDENT_FILES,
-    );
-    smart_contracts_contract_as.update();
+    if let Some(release_notes_dir) = release_notes_dir() {
+        for (package, updated_version) in &updated_packages {
+            let package_dir = root_dir()
+                .join(release_notes_dir)
+                .join(package.relative_dir());
+            match release_notes::updated_changelog(&package_dir, updated_version) {
+                Ok(Some((path, contents))) => {
+                    let _ = file_changes.insert(path, contents);
+                }
+                Ok(None) => (),
+                Err(error) => {
+                    eprintln!(
+                        "Failed to update changelog of {}: {}.",
+                        package.name(),
+                        error
+                    );
+                    process::exit(1);
+                }
+            }
+        }
+    }
 
-    let grpc_test_support = Package::cargo(
-        "grpc/test_support",
-        &*regex_data::grpc_test_support::DEPENDENT_FILES,
-    );
-    grpc_test_support.update();
+    // Snapshot all files before writing any, so that they can be restored if a later step fails.
+    // `Cargo.lock` is included if it's to be regenerated.
+    let lockfile_path = root_dir().join(LOCKFILE);
+    let lockfile_update = if is_lockfile_update_requested() && !is_dry_run() {
+        Some(&lockfile_path)
+    } else {
+        None
+    };
+    let paths = file_changes.keys().chain(lockfile_update);
+    let transaction = match Transaction::begin(paths, is_rollback_enabled()) {
+        Ok(transaction) => transaction,
+        Err(error) => {
+            eprintln!("Failed to update files: {}.", error);
+            process::exit(1);
+        }
+    };
+    if let Err(error) = transaction.write_all(&file_changes) {
+        abort(
+            Some(transaction),
+            &format!("Failed to update files: {}", error),
+        );
+    }
+
+    if is_lockfile_update_requested() {
+        if is_dry_run() {
+            println!("Will run '{} update --workspace'", cargo_path().display());
+        } else {
+            match lockfile::update(cargo_path(), root_dir()) {
+                Ok(()) => println!("Updated {}.", lockfile_path.display()),
+                Err(error) => abort(
+                    Some(transaction),
+                    &format!("Failed to update {}: {}", LOCKFILE, error),
+                ),
+            }
+        }
+    }
 
-    let grpc_cargo_casper = Package::cargo(
-        "grpc/cargo_casper",
-        &*regex_data::grpc_cargo_casper::DEPENDENT_FILES,
+    // Once the updated files have been committed, there is nothing left to roll back.
+    let transaction = if is_commit_requested() {
+        let package_versions: Vec<_> = updated_packages
+            .iter()
+            .map(|(package, updated_version)| (package.name(), updated_version))
+            .collect();
+        let message = release_commit_message(bump_version(), &types_version, &package_versions);
+        if is_dry_run() {
+            println!("Will commit updated files with message:\n{}", message);
+            Some(transaction)
+        } else {
+            let paths: Vec<_> = file_changes.keys().chain(lockfile_update).collect();
+            if paths.is_empty() {
+                println!("No files were updated, so there is nothing to commit.");
+                Some(transaction)
+            } else {
+                match git::commit_files(root_dir(), &paths, &message) {
+                    Ok(commit) => {
+                        println!("Committed updated files as {}.", commit);
+                        transaction.commit();
+                        None
+                    }
+                    Err(error) => abort(
+                        Some(transaction),
+                        &format!("Failed to commit updated files: {}", error),
+                    ),
+                }
+            }
+        }
+    } else {
+        Some(transaction)
+    };
+
+    if let Some(tag_prefix) = tag_prefix() {
+        if is_dry_run() {
+            println!("Will tag release as {}{}", tag_prefix, types_version);
+        } else {
+            match git::tag_release(root_dir(), tag_prefix, &types_version) {
+                Ok(tag) => println!("Tagged release as {}.", tag),
+                Err(error) => abort(transaction, &format!("Failed to tag release: {}", error)),
+            }
+        }
+    }
+
+    if let Some(transaction) = transaction {
+        transaction.commit();
+    }
+
+    if release_notes_dir().is_some() && !updated_packages.is_empty() {
+        println!("Suggested git tag commands:");
+        for (package, updated_version) in &updated_packages {
+            let tag = release_notes::tag_name(package.name(), updated_version);
+            println!("\tgit tag -a {} -m \"Release {}\"", tag, tag);
+        }
+    }
+}
+
+/// Prints `message`, rolls back the transaction if there is one (unless rollback is disabled) and
+/// exits.
+fn abort(transaction: Option<Transaction>, message: &str) -> ! {
+    eprintln!("{}.", message);
+    drop(transaction);
+    process::exit(1);
+}
+
+/// Returns the message printed when aborting due to uncommitted changes in the working tree at
+/// `root`.
+fn dirty_tree_message<S: AsRef<str>>(root: &Path, dirty_paths: &[S]) -> String {
+    let mut message = format!(
+        "The working tree at {} has uncommitted changes.  Commit or stash them, or pass --{} to \
+        run anyway.  Dirty paths:",
+        root.display(),
+        ALLOW_DIRTY_ARG_NAME
     );
-    grpc_cargo_casper.update();
+    for path in dirty_paths {
+        message.push_str("\n\t");
+        message.push_str(path.as_ref());
+    }
+    message
+}
+
+/// Returns the message of the commit created via --commit.  The subject gives the release version,
+/// i.e. the updated version of the types crate, and the version bump, which is "manual" if the
+/// versions were entered interactively.  The body lists the updated version of every package.
+fn release_commit_message(
+    bump_version: Option<&BumpVersion>,
+    types_version: &Version,
+    updated_versions: &[(&str, &Version)],
+) -> String {
+    let bump = bump_version.map_or_else(|| "manual".to_string(), ToString::to_string);
+    let mut message = format!("Release {} ({} version bump)\n", types_version, bump);
+    if !updated_versions.is_empty() {
+        message.push_str("\nUpdated versions:\n");
+        for (name, version) in updated_versions {
+            message.push_str(&format!("- {} {}\n", name, version));
+        }
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_derive_major_bump_from_breaking_changes() {
+        let subjects = [
+            "feat(node): add gossip peer saturation",
+            "BREAKING CHANGE: remove legacy config",
+            "fix: handle missing config file",
+        ];
+        assert_eq!(
+            BumpVersion::from_commit_subjects(&subjects),
+            BumpVersion::Major
+        );
+        assert_eq!(
+            BumpVersion::from_commit_subjects(&["fix: drop support for old peers!"]),
+            BumpVersion::Major
+        );
+        assert_eq!(
+            BumpVersion::from_commit_subjects(&["refactor(types)!: rename auction entry points"]),
+            BumpVersion::Major
+        );
+    }
+
+    #[test]
+    fn should_derive_minor_bump_from_features() {
+        let subjects = [
+            "fix: handle missing config file",
+            "feat: add step request",
+            "docs: update README",
+        ];
+        assert_eq!(
+            BumpVersion::from_commit_subjects(&subjects),
+            BumpVersion::Minor
+        );
+        assert_eq!(
+            BumpVersion::from_commit_subjects(&["feat(node): add gossip peer saturation"]),
+            BumpVersion::Minor
+        );
+    }
+
+    #[test]
+    fn should_list_dirty_paths_in_message() {
+        let message = dirty_tree_message(Path::new("/casper-node"), &["Cargo.lock", "notes.txt"]);
+        assert_eq!(
+            message,
+            "The working tree at /casper-node has uncommitted changes.  Commit or stash them, or \
+            pass --allow-dirty to run anyway.  Dirty paths:\n\tCargo.lock\n\tnotes.txt"
+        );
+    }
+
+    #[test]
+    fn should_give_bump_and_versions_in_commit_message() {
+        let types_version = Version::new(1, 2, 0);
+        let node_version = Version::new(0, 3, 0);
+        let updated_versions = [
+            ("casper-types", &types_version),
+            ("casper-node", &node_version),
+        ];
+        assert_eq!(
+            release_commit_message(Some(&BumpVersion::Minor), &types_version, &updated_versions),
+            "Release 1.2.0 (minor version bump)\n\nUpdated versions:\n- casper-types 1.2.0\n- \
+            casper-node 0.3.0\n"
+        );
+
+        let prerelease = BumpVersion::Prerelease {
+            label: "rc".to_string(),
+        };
+        let types_version = Version::parse("1.2.0-rc.1").unwrap();
+        assert_eq!(
+            release_commit_message(
+                Some(&prerelease),
+                &types_version,
+                &[("casper-types", &types_version)]
+            ),
+            "Release 1.2.0-rc.1 (pre-rc version bump)\n\nUpdated versions:\n- casper-types \
+            1.2.0-rc.1\n"
+        );
+
+        assert_eq!(
+            release_commit_message(None, &types_version, &[]),
+            "Release 1.2.0-rc.1 (manual version bump)\n"
+        );
+    }
+
+    #[test]
+    fn should_derive_patch_bump_otherwise() {
+        let subjects = [
+            "fix: handle missing config file",
+            "Bump dependencies",
+            "features are documented",
+        ];
+        assert_eq!(
+            BumpVersion::from_commit_subjects(&subjects),
+            BumpVersion::Patch
+        );
+        assert_eq!(
+            BumpVersion::from_commit_subjects::<&str>(&[]),
+            BumpVersion::Patch
+        );
+    }
 }
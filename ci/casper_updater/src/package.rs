@@ -1,6 +1,7 @@
 use std::{
+    collections::BTreeMap,
     io::{self, Write},
-    path::Path,
+    path::{Path, PathBuf},
 };
 
 use regex::Regex;
@@ -10,13 +11,16 @@ use crate::{
     dependent_file::DependentFile,
     regex_data::{
         MANIFEST_NAME_REGEX, MANIFEST_VERSION_REGEX, PACKAGE_JSON_NAME_REGEX,
-        PACKAGE_JSON_VERSION_REGEX,
+        PACKAGE_JSON_VERSION_REGEX, PRE_RELEASE_REGEX,
     },
     BumpVersion,
 };
 
 const CAPTURE_INDEX: usize = 2;
 
+/// The pending updated contents of files, keyed by full path.
+pub type FileChanges = BTreeMap<PathBuf, String>;
+
 /// Represents a published CasperLabs crate or AssemblyScript package which may need its version
 /// updated.
 pub struct Package {
@@ -24,6 +28,8 @@ pub struct Package {
     name: String,
     /// This package's current version as specified in its manifest.
     current_version: Version,
+    /// Full path to this package's manifest file.
+    manifest_path: PathBuf,
     /// Files which must be updated if this package's version is changed, including this package's
     /// own manifest file.  The other files will often be from a different package.
     dependent_files: &'static Vec<DependentFile>,
@@ -124,64 +130,115 @@ impl Package {
         Package {
             name,
             current_version,
+            manifest_path,
             dependent_files,
         }
     }
 
-    pub fn update(&self) {
+    /// Returns this package's name as specified in its manifest.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns this package's current version as specified in its manifest.
+    pub fn current_version(&self) -> &Version {
+        &self.current_version
+    }
+
+    /// Returns the path of this package's directory, relative to the casper-node root directory.
+    pub fn relative_dir(&self) -> &Path {
+        self.manifest_path
+            .parent()
+            .expect("manifest should have parent")
+            .strip_prefix(crate::root_dir())
+            .expect("should strip prefix")
+    }
+
+    /// Returns the version this package should be updated to, either by bumping the current version
+    /// or by asking the user.  Returns the current version if it should remain unchanged.
+    ///
+    /// On a dry run, also lists the files affected by this package's version.
+    pub fn updated_version(&self) -> Version {
         if crate::is_dry_run() {
             println!(
                 "Current version of {} is {}",
                 self.name, self.current_version
             );
-            if let Some(bump_version) = crate::bump_version() {
-                let updated_version = self.get_updated_version_from_bump(bump_version);
-                println!("Will be updated to {}", updated_version);
-            }
+            let updated_version = match crate::bump_version() {
+                Some(bump_version) => {
+                    let updated_version = self.get_updated_version_from_bump(bump_version);
+                    println!("Will be updated to {}", updated_version);
+                    updated_version
+                }
+                None => self.current_version.clone(),
+            };
             println!("Files affected by this package's version:");
-            for dependent_file in self.dependent_files {
+            for dependent_file in self
+                .dependent_files
+                .iter()
+                .filter(|dependent_file| dependent_file.is_affected())
+            {
                 let relative_path = dependent_file
                     .path()
                     .strip_prefix(crate::root_dir())
                     .expect("should strip prefix");
-                println!("\t* {}", relative_path.display());
+                match dependent_file.optional_match_count() {
+                    0 => println!("\t* {}", relative_path.display()),
+                    count => println!(
+                        "\t* {} (including {} optional section(s))",
+                        relative_path.display(),
+                        count
+                    ),
+                }
             }
             println!();
-            return;
+            return updated_version;
         }
 
-        let updated_version = match crate::bump_version() {
-            None => match self.get_updated_version_from_user() {
-                Some(version) => version,
-                None => return,
-            },
+        match crate::bump_version() {
+            None => self
+                .get_updated_version_from_user()
+                .unwrap_or_else(|| self.current_version.clone()),
             Some(bump_version) => self.get_updated_version_from_bump(bump_version),
-        };
+        }
+    }
 
+    /// Returns the changes required to update this package's version and all its dependent files
+    /// to `updated_version`.  Changes are applied on top of any `pending_changes` to the same
+    /// files.
+    ///
+    /// On a dry run, or if the version is unchanged, returns no changes.
+    pub fn update(
+        &self,
+        updated_version: &Version,
+        pending_changes: &FileChanges,
+    ) -> Vec<(PathBuf, String)> {
+        if crate::is_dry_run() || *updated_version == self.current_version {
+            return vec![];
+        }
+
+        // Several dependent files may refer to different portions of the same file, so each
+        // update is applied on top of any earlier ones from this package.
+        let updated_version_string = updated_version.to_string();
+        let mut file_changes = FileChanges::new();
         for dependent_file in self.dependent_files {
-            dependent_file.update(&updated_version.to_string());
+            let pending_contents = file_changes
+                .get(dependent_file.path())
+                .or_else(|| pending_changes.get(dependent_file.path()))
+                .map(String::as_str);
+            let (path, contents) = dependent_file.update(pending_contents, &updated_version_string);
+            let _ = file_changes.insert(path, contents);
         }
 
         println!(
-            "Updated {} from {} to {}.",
+            "Updating {} from {} to {}.",
             self.name, self.current_version, updated_version
         );
+        file_changes.into_iter().collect()
     }
 
-    fn get_updated_version_from_bump(&self, bump_version: BumpVersion) -> Version {
-        match bump_version {
-            BumpVersion::Major => Version::new(self.current_version.major + 1, 0, 0),
-            BumpVersion::Minor => Version::new(
-                self.current_version.major,
-                self.current_version.minor + 1,
-                0,
-            ),
-            BumpVersion::Patch => Version::new(
-                self.current_version.major,
-                self.current_version.minor,
-                self.current_version.patch + 1,
-            ),
-        }
+    fn get_updated_version_from_bump(&self, bump_version: &BumpVersion) -> Version {
+        bumped_version(&self.current_version, bump_version)
     }
 
     fn get_updated_version_from_user(&self) -> Option<Version> {
@@ -226,3 +283,167 @@ impl Package {
         }
     }
 }
+
+/// Returns the indices of `packages` ordered such that if a package's manifest is a dependent file
+/// of another package, the other package comes first.
+pub fn dependency_order(packages: &[Package]) -> Vec<usize> {
+    let manifest_paths: Vec<&Path> = packages
+        .iter()
+        .map(|package| package.manifest_path.as_path())
+        .collect();
+    let dependent_paths: Vec<Vec<&Path>> = packages
+        .iter()
+        .map(|package| {
+            package
+                .dependent_files
+                .iter()
+                .map(DependentFile::path)
+                .collect()
+        })
+        .collect();
+    topological_order(&manifest_paths, &dependent_paths)
+}
+
+/// Topologically sorts the packages described by `manifest_paths` and `dependent_paths` (each
+/// indexed by package), where package `i` must precede package `j` if `dependent_paths[i]`
+/// contains `manifest_paths[j]`.  Where there is a choice, lower indices come first.
+///
+/// Panics if the packages' dependencies form a cycle.
+fn topological_order(manifest_paths: &[&Path], dependent_paths: &[Vec<&Path>]) -> Vec<usize> {
+    let package_count = manifest_paths.len();
+    let dependants = |index: usize| {
+        (0..package_count).filter(move |&other| {
+            other != index && dependent_paths[index].contains(&manifest_paths[other])
+        })
+    };
+
+    let mut in_degrees = vec![0_usize; package_count];
+    for index in 0..package_count {
+        for dependant in dependants(index) {
+            in_degrees[dependant] += 1;
+        }
+    }
+
+    let mut order = Vec::with_capacity(package_count);
+    while order.len() < package_count {
+        let next = (0..package_count)
+            .find(|&index| in_degrees[index] == 0 && !order.contains(&index))
+            .unwrap_or_else(|| {
+                panic!(
+                    "dependencies between packages form a cycle involving {:?}",
+                    (0..package_count)
+                        .filter(|index| !order.contains(index))
+                        .map(|index| manifest_paths[index])
+                        .collect::<Vec<_>>()
+                )
+            });
+        order.push(next);
+        for dependant in dependants(next) {
+            in_degrees[dependant] -= 1;
+        }
+    }
+    order
+}
+
+/// Returns `current_version` with the given component bumped.
+fn bumped_version(current_version: &Version, bump_version: &BumpVersion) -> Version {
+    match bump_version {
+        BumpVersion::Major => Version::new(current_version.major + 1, 0, 0),
+        BumpVersion::Minor => Version::new(current_version.major, current_version.minor + 1, 0),
+        BumpVersion::Patch => Version::new(
+            current_version.major,
+            current_version.minor,
+            current_version.patch + 1,
+        ),
+        BumpVersion::Prerelease { label } => {
+            let current_pre_release = current_version
+                .pre
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(".");
+            let number = match PRE_RELEASE_REGEX.captures(&current_pre_release) {
+                Some(captures) if captures[1] == *label => {
+                    let current_number: u64 = captures[2]
+                        .parse()
+                        .expect("should parse pre-release number");
+                    current_number + 1
+                }
+                _ => 1,
+            };
+            let updated_version = format!(
+                "{}.{}.{}-{}.{}",
+                current_version.major, current_version.minor, current_version.patch, label, number
+            );
+            Version::parse(&updated_version).unwrap_or_else(|error| {
+                panic!("{} is not a valid version: {}", updated_version, error)
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bump_pre_release(version: &str, label: &str) -> String {
+        let bump_version = BumpVersion::Prerelease {
+            label: label.to_string(),
+        };
+        bumped_version(&Version::parse(version).unwrap(), &bump_version).to_string()
+    }
+
+    #[test]
+    fn should_bump_pre_release() {
+        let alpha_1 = bump_pre_release("1.2.3", "alpha");
+        assert_eq!(alpha_1, "1.2.3-alpha.1");
+        let alpha_2 = bump_pre_release(&alpha_1, "alpha");
+        assert_eq!(alpha_2, "1.2.3-alpha.2");
+        assert_eq!(bump_pre_release(&alpha_2, "beta"), "1.2.3-beta.1");
+    }
+
+    #[test]
+    fn should_order_packages_by_dependency() {
+        let types = Path::new("types/Cargo.toml");
+        let engine = Path::new("execution_engine/Cargo.toml");
+        let node = Path::new("node/Cargo.toml");
+        let client = Path::new("client/Cargo.toml");
+
+        // Listed with dependants ahead of their dependencies.
+        let manifest_paths = [client, node, engine, types];
+        let dependent_paths = vec![
+            vec![client],
+            vec![node, client],
+            vec![engine, node],
+            vec![types, engine, node, client],
+        ];
+
+        let order = topological_order(&manifest_paths, &dependent_paths);
+        assert_eq!(order, vec![3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn should_keep_listed_order_for_independent_packages() {
+        let first = Path::new("first/Cargo.toml");
+        let second = Path::new("second/Cargo.toml");
+        let order = topological_order(&[first, second], &[vec![first], vec![second]]);
+        assert_eq!(order, vec![0, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "form a cycle")]
+    fn should_panic_on_dependency_cycle() {
+        let first = Path::new("first/Cargo.toml");
+        let second = Path::new("second/Cargo.toml");
+        let _ = topological_order(&[first, second], &[vec![second], vec![first]]);
+    }
+
+    #[test]
+    fn should_drop_pre_release_when_bumping_patch() {
+        let version = Version::parse("1.2.3-rc.4").unwrap();
+        assert_eq!(
+            bumped_version(&version, &BumpVersion::Patch).to_string(),
+            "1.2.4"
+        );
+    }
+}
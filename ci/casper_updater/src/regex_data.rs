@@ -13,12 +13,39 @@ lazy_static! {
         Regex::new(r#"(?m)(^  "name": )"([^"]+)"#).unwrap();
     pub static ref PACKAGE_JSON_VERSION_REGEX: Regex =
         Regex::new(r#"(?m)(^  "version": )"([^"]+)"#).unwrap();
+    /// Matches the root package's self-reference in the `packages` section of a v2
+    /// `package-lock.json`.
+    pub static ref PACKAGE_LOCK_JSON_ROOT_PACKAGE_VERSION_REGEX: Regex =
+        Regex::new(r#"(?m)(^  "packages": \{\s*"": \{[^\}]*?"version": )"([^"]+)"#).unwrap();
+    /// Matches the pre-release part of a version of the form `<label>.<N>`, e.g. `alpha.1`.
+    pub static ref PRE_RELEASE_REGEX: Regex = Regex::new(r#"^(.+)\.(\d+)$"#).unwrap();
+    /// Matches a Conventional Commits subject line, e.g. `feat(node)!: drop legacy config`.
+    pub static ref CONVENTIONAL_COMMIT_REGEX: Regex =
+        Regex::new(r#"^(\w+)(?:\([^)]*\))?(!)?:\s*(.+)$"#).unwrap();
 }
 
-fn replacement(updated_version: &str) -> String {
+pub(crate) fn replacement(updated_version: &str) -> String {
     format!(r#"$1"{}"#, updated_version)
 }
 
+/// Returns a regex matching the version of the scoped npm package `scoped_name` in a
+/// `dependencies`, `devDependencies` or `peerDependencies` section of a `package.json`.
+///
+/// The captures are the portion preceding the version's opening quote, the `^` or `~` range
+/// prefix (possibly empty) and the bare version.
+pub(crate) fn npm_scoped_dependency_regex(scoped_name: &str) -> Regex {
+    Regex::new(&format!(
+        r#"(?m)(^  "(?:dependencies|devDependencies|peerDependencies)": \{{[^\}}]*?"{}":\s*)"([\^~]?)([^"]+)"#,
+        regex::escape(scoped_name)
+    ))
+    .unwrap()
+}
+
+/// Generates the replacement for a scoped npm dependency's version, keeping its range prefix.
+pub(crate) fn npm_scoped_replacement(updated_version: &str) -> String {
+    format!(r#"$1"${{2}}{}"#, updated_version)
+}
+
 fn replacement_with_slash(updated_version: &str) -> String {
     format!(r#"$1/{}"#, updated_version)
 }
@@ -236,9 +263,14 @@ pub mod smart_contracts_contract_as {
                     PACKAGE_JSON_VERSION_REGEX.clone(),
                     replacement,
                 ),
-                DependentFile::new(
+                DependentFile::npm_scoped(
+                    "smart_contracts/contract_as/package.json",
+                    "@casperlabs/types",
+                ),
+                DependentFile::with_regexes(
                     "smart_contracts/contract_as/package-lock.json",
-                    PACKAGE_JSON_VERSION_REGEX.clone(),
+                    vec![PACKAGE_JSON_VERSION_REGEX.clone()],
+                    vec![PACKAGE_LOCK_JSON_ROOT_PACKAGE_VERSION_REGEX.clone()],
                     replacement,
                 ),
             ]
@@ -0,0 +1,147 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use chrono::Utc;
+use semver::Version;
+
+use crate::{changelog, is_dry_run};
+
+const CHANGELOG: &str = "CHANGELOG.md";
+const HEADER: &str = "\
+# Changelog
+
+All notable changes to this project will be documented in this file.  The format is based on \
+[Keep a Changelog](https://keepachangelog.com/en/1.0.0/).
+";
+
+/// Renders the stub section for `version` released on `date`, to be filled in by hand.
+fn render_section(version: &Version, date: &str) -> String {
+    format!("## [{}] - {}\n", version, date)
+}
+
+/// Inserts a stub section for `version` released on `date` into the existing changelog contents,
+/// above any previous sections.  If there are no existing contents, a new changelog is started
+/// with the standard header.
+///
+/// Returns `None` if the changelog already has a section for `version`.
+fn insert_section(existing: Option<&str>, version: &Version, date: &str) -> Option<String> {
+    let existing = existing.unwrap_or(HEADER);
+    let heading = format!("## [{}]", version);
+    if existing
+        .lines()
+        .any(|line| line.trim_end().starts_with(&heading))
+    {
+        return None;
+    }
+    Some(changelog::prepend_entry(
+        Some(existing),
+        &render_section(version, date),
+    ))
+}
+
+/// Returns the path and updated contents of the `CHANGELOG.md` in `package_dir`, with a stub
+/// section for `version` inserted.  Returns `None` if the changelog already has such a section.
+///
+/// On a dry run, prints the section which would be inserted and returns `None`.
+pub(crate) fn updated_changelog(
+    package_dir: &Path,
+    version: &Version,
+) -> Result<Option<(PathBuf, String)>, io::Error> {
+    let path = package_dir.join(CHANGELOG);
+    let existing = match fs::read_to_string(&path) {
+        Ok(contents) => Some(contents),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+        Err(error) => return Err(error),
+    };
+
+    let date = changelog::release_date(Utc::today());
+    let updated_contents = match insert_section(existing.as_deref(), version, &date) {
+        Some(updated_contents) => updated_contents,
+        None => return Ok(None),
+    };
+
+    if is_dry_run() {
+        let action = if existing.is_some() {
+            "insert into"
+        } else {
+            "create"
+        };
+        println!(
+            "Will {} {}:\n\n{}",
+            action,
+            path.display(),
+            render_section(version, &date)
+        );
+        return Ok(None);
+    }
+    Ok(Some((path, updated_contents)))
+}
+
+/// Returns the name of the git tag suggested for `version` of the package called `package_name`,
+/// e.g. `casper-node-v0.2.1`.  Scoped AssemblyScript package names are flattened, so
+/// `@casperlabs/contract` becomes `casperlabs-contract`.
+pub(crate) fn tag_name(package_name: &str, version: &Version) -> String {
+    format!(
+        "{}-v{}",
+        package_name.trim_start_matches('@').replace('/', "-"),
+        version
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXISTING_CHANGELOG: &str = include_str!("../fixtures/CHANGELOG.md");
+    const UPDATED_CHANGELOG: &str = include_str!("../fixtures/updated/CHANGELOG.md");
+    const CHANGELOG_NO_SECTIONS: &str = include_str!("../fixtures/CHANGELOG-no-sections.md");
+    const UPDATED_CHANGELOG_NO_SECTIONS: &str =
+        include_str!("../fixtures/updated/CHANGELOG-no-sections.md");
+
+    const DATE: &str = "2020-10-05";
+
+    fn version() -> Version {
+        Version::new(0, 2, 1)
+    }
+
+    #[test]
+    fn should_insert_section_above_previous_sections() {
+        let updated = insert_section(Some(EXISTING_CHANGELOG), &version(), DATE);
+        assert_eq!(updated.as_deref(), Some(UPDATED_CHANGELOG));
+    }
+
+    #[test]
+    fn should_insert_section_below_header_if_no_previous_sections() {
+        let updated = insert_section(Some(CHANGELOG_NO_SECTIONS), &version(), DATE);
+        assert_eq!(updated.as_deref(), Some(UPDATED_CHANGELOG_NO_SECTIONS));
+    }
+
+    #[test]
+    fn should_create_changelog_with_header_if_missing() {
+        let updated = insert_section(None, &version(), DATE).unwrap();
+        assert_eq!(updated, format!("{}\n## [0.2.1] - 2020-10-05\n", HEADER));
+    }
+
+    #[test]
+    fn should_not_insert_section_twice() {
+        assert_eq!(
+            insert_section(Some(UPDATED_CHANGELOG), &version(), DATE),
+            None
+        );
+        assert!(insert_section(Some(UPDATED_CHANGELOG), &Version::new(0, 2, 2), DATE).is_some());
+    }
+
+    #[test]
+    fn should_suggest_tag_names() {
+        assert_eq!(tag_name("casper-node", &version()), "casper-node-v0.2.1");
+        assert_eq!(
+            tag_name(
+                "@casperlabs/contract",
+                &Version::parse("1.0.0-rc.1").unwrap()
+            ),
+            "casperlabs-contract-v1.0.0-rc.1"
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use rayon::prelude::*;
+
+use crate::package::FileChanges;
+
+/// Errors which can occur while updating files as part of a `Transaction`.
+#[derive(Debug)]
+pub(crate) enum Error {
+    /// Failed to read a file's original contents.
+    Read { path: PathBuf, error: io::Error },
+    /// Failed to write a file's updated contents.
+    Write { path: PathBuf, error: io::Error },
+}
+
+impl Display for Error {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Read { path, error } => {
+                write!(formatter, "failed to read {}: {}", path.display(), error)
+            }
+            Error::Write { path, error } => {
+                write!(formatter, "failed to write {}: {}", path.display(), error)
+            }
+        }
+    }
+}
+
+/// A set of file updates which are rolled back if the transaction is dropped without having been
+/// committed, e.g. due to a later error or a panic.
+pub(crate) struct Transaction {
+    /// The original contents of every file which may be written, keyed by full path.  Files which
+    /// didn't exist are held as `None`, and are removed on rollback.
+    snapshot: HashMap<PathBuf, Option<String>>,
+    /// Whether to restore the original contents when dropped.
+    rollback_on_drop: bool,
+}
+
+impl Transaction {
+    /// Starts a transaction by taking a snapshot of the current contents of all the given files.
+    /// Files which don't exist yet may be included.
+    ///
+    /// If `rollback_enabled` is false, the files are never restored.
+    pub(crate) fn begin<'a, I: IntoIterator<Item = &'a PathBuf>>(
+        paths: I,
+        rollback_enabled: bool,
+    ) -> Result<Self, Error> {
+        let snapshot = paths
+            .into_iter()
+            .map(|path| match fs::read_to_string(path) {
+                Ok(contents) => Ok((path.clone(), Some(contents))),
+                Err(error) if error.kind() == io::ErrorKind::NotFound => Ok((path.clone(), None)),
+                Err(error) => Err(Error::Read {
+                    path: path.clone(),
+                    error,
+                }),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Transaction {
+            snapshot,
+            rollback_on_drop: rollback_enabled,
+        })
+    }
+
+    /// Writes all the given file changes, in parallel.
+    ///
+    /// Every write is attempted even if some fail, so that on failure all files are in a known
+    /// state before being rolled back.  Returns the first error encountered.
+    pub(crate) fn write_all(&self, file_changes: &FileChanges) -> Result<(), Error> {
+        file_changes
+            .par_iter()
+            .map(|(path, contents)| {
+                debug_assert!(
+                    self.snapshot.contains_key(path),
+                    "{} should be in the transaction's snapshot",
+                    path.display()
+                );
+                write(path, contents)
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Commits the transaction, so that the written files are not restored.
+    pub(crate) fn commit(mut self) {
+        self.rollback_on_drop = false;
+    }
+
+    /// Restores all files to their original contents, reporting any which couldn't be restored.
+    fn rollback(&self) {
+        for (path, original_contents) in &self.snapshot {
+            let result = match original_contents {
+                Some(contents) => write(path, contents),
+                None if path.exists() => fs::remove_file(path).map_err(|error| Error::Write {
+                    path: path.clone(),
+                    error,
+                }),
+                None => Ok(()),
+            };
+            if let Err(error) = result {
+                eprintln!("Failed to roll back: {}.", error);
+            }
+        }
+        if !self.snapshot.is_empty() {
+            println!("Rolled back all file changes.");
+        }
+    }
+}
+
+impl Drop for Transaction {
+    fn drop(&mut self) {
+        if self.rollback_on_drop {
+            self.rollback();
+        }
+    }
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), Error> {
+    fs::write(path, contents).map_err(|error| Error::Write {
+        path: path.to_path_buf(),
+        error,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    const ORIGINAL: &str = "original";
+    const UPDATED: &str = "updated";
+
+    /// Creates two files with the original contents, returning the temp dir holding them and the
+    /// changes to update both.
+    fn setup() -> (TempDir, FileChanges) {
+        let dir = tempfile::tempdir().expect("should create temp dir");
+        let mut file_changes = FileChanges::new();
+        for name in &["a.txt", "b.txt"] {
+            let path = dir.path().join(name);
+            fs::write(&path, ORIGINAL).unwrap();
+            let _ = file_changes.insert(path, UPDATED.to_string());
+        }
+        (dir, file_changes)
+    }
+
+    /// Replaces `b.txt` with a directory so that writing to it fails.
+    fn inject_write_failure(dir: &TempDir) -> PathBuf {
+        let path = dir.path().join("b.txt");
+        fs::remove_file(&path).unwrap();
+        fs::create_dir(&path).unwrap();
+        path
+    }
+
+    #[test]
+    fn should_keep_changes_when_committed() {
+        let (_dir, file_changes) = setup();
+
+        let transaction = Transaction::begin(file_changes.keys(), true).unwrap();
+        transaction.write_all(&file_changes).unwrap();
+        transaction.commit();
+
+        for path in file_changes.keys() {
+            assert_eq!(fs::read_to_string(path).unwrap(), UPDATED);
+        }
+    }
+
+    #[test]
+    fn should_restore_original_contents_on_write_failure() {
+        let (dir, file_changes) = setup();
+
+        let transaction = Transaction::begin(file_changes.keys(), true).unwrap();
+        let failing_path = inject_write_failure(&dir);
+        match transaction.write_all(&file_changes) {
+            Err(Error::Write { path, .. }) => assert_eq!(path, failing_path),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        drop(transaction);
+
+        let path = dir.path().join("a.txt");
+        assert_eq!(fs::read_to_string(path).unwrap(), ORIGINAL);
+    }
+
+    #[test]
+    fn should_remove_created_files_on_rollback() {
+        let (dir, mut file_changes) = setup();
+        let new_path = dir.path().join("c.txt");
+        let _ = file_changes.insert(new_path.clone(), UPDATED.to_string());
+
+        let transaction = Transaction::begin(file_changes.keys(), true).unwrap();
+        transaction.write_all(&file_changes).unwrap();
+        assert!(new_path.exists());
+        drop(transaction);
+
+        assert!(!new_path.exists());
+        let path = dir.path().join("a.txt");
+        assert_eq!(fs::read_to_string(path).unwrap(), ORIGINAL);
+    }
+
+    #[test]
+    fn should_not_restore_original_contents_if_rollback_disabled() {
+        let (dir, file_changes) = setup();
+
+        let transaction = Transaction::begin(file_changes.keys(), false).unwrap();
+        let _ = inject_write_failure(&dir);
+        assert!(transaction.write_all(&file_changes).is_err());
+        drop(transaction);
+
+        let path = dir.path().join("a.txt");
+        assert_eq!(fs::read_to_string(path).unwrap(), UPDATED);
+    }
+}
@@ -15,28 +15,31 @@ enum DisplayOrder {
     NodeAddress,
     RpcId,
     StateRootHash,
-    PurseURef,
+    PurseIdentifier,
 }
 
-/// Handles providing the arg for and retrieval of the purse URef.
-mod purse_uref {
+/// Handles providing the arg for and retrieval of the purse identifier.
+mod purse_identifier {
     use super::*;
 
-    const ARG_NAME: &str = "purse-uref";
+    const ARG_NAME: &str = "purse-identifier";
+    const ARG_ALIAS: &str = "purse-uref";
     const ARG_SHORT: &str = "p";
-    const ARG_VALUE_NAME: &str = "FORMATTED STRING";
-    const ARG_HELP: &str =
-        "The URef under which the purse is stored.  This must be a properly formatted URef \
-        \"uref-<HEX STRING>-<THREE DIGIT INTEGER>\"";
+    const ARG_VALUE_NAME: &str = "FORMATTED STRING or HEX STRING";
+    const ARG_HELP: &str = "The URef under which the purse is stored, formatted as \
+        \"uref-<HEX STRING>-<THREE DIGIT INTEGER>\".  Alternatively, the account hash formatted as \
+        \"account-hash-<HEX STRING>\", or the hex-encoded public key of an account, in which case \
+        the balance of the account's main purse is retrieved";
 
     pub(super) fn arg() -> Arg<'static, 'static> {
         Arg::with_name(ARG_NAME)
             .long(ARG_NAME)
+            .alias(ARG_ALIAS)
             .short(ARG_SHORT)
             .required(true)
             .value_name(ARG_VALUE_NAME)
             .help(ARG_HELP)
-            .display_order(DisplayOrder::PurseURef as usize)
+            .display_order(DisplayOrder::PurseIdentifier as usize)
     }
 
     pub(super) fn get(matches: &ArgMatches) -> String {
@@ -52,11 +55,11 @@ mod balance_args {
 
     pub(super) fn get(matches: &ArgMatches) -> <GetBalance as RpcWithParams>::RequestParams {
         let state_hash = common::state_root_hash::get(&matches);
-        let purse_uref = purse_uref::get(&matches);
+        let purse_identifier = purse_identifier::get(&matches);
 
         GetBalanceParams {
             state_root_hash: state_hash,
-            purse_uref,
+            purse_identifier,
         }
     }
 }
@@ -81,7 +84,7 @@ impl<'a, 'b> ClientCommand<'a, 'b> for GetBalance {
             .arg(common::state_root_hash::arg(
                 DisplayOrder::StateRootHash as usize,
             ))
-            .arg(purse_uref::arg())
+            .arg(purse_identifier::arg())
     }
 
     fn run(matches: &ArgMatches<'_>) {
@@ -1,12 +1,101 @@
 //! Configuration options for the execution engine.
 
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
-use crate::shared::utils;
+use crate::shared::{
+    utils,
+    wasm_config::{STACK_HEIGHT_LIMIT, WASM_MEMORY_PAGES_LIMIT},
+};
 
 const DEFAULT_MAX_GLOBAL_STATE_SIZE: usize = 805_306_368_000; // 750 GiB
 const DEFAULT_USE_SYSTEM_CONTRACTS: bool = false;
 
+/// The maximum size of the global state store, i.e. 8 TiB.
+pub const MAX_GLOBAL_STATE_SIZE_LIMIT: usize = 8 * 1024 * 1024 * 1024 * 1024;
+
+/// Error returned when a limit in the config is outside the supported range.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ConfigError {
+    /// `max_global_state_size` is zero.
+    #[error("max_global_state_size must be greater than 0")]
+    ZeroGlobalStateSize,
+    /// `max_global_state_size` is not a multiple of the OS page size.
+    #[error(
+        "max_global_state_size of {size} is not a multiple of the system page size {page_size}"
+    )]
+    GlobalStateSizeNotPageAligned {
+        /// The configured size.
+        size: usize,
+        /// The OS page size.
+        page_size: usize,
+    },
+    /// `max_global_state_size` exceeds 8 TiB.
+    #[error(
+        "max_global_state_size of {0} exceeds the limit of {} bytes",
+        MAX_GLOBAL_STATE_SIZE_LIMIT
+    )]
+    GlobalStateSizeTooLarge(usize),
+    /// `max_wasm_memory_pages` is zero.
+    #[error("max_wasm_memory_pages must be greater than 0")]
+    ZeroWasmMemoryPages,
+    /// `max_wasm_memory_pages` exceeds the memory addressable by a wasm32 module.
+    #[error(
+        "max_wasm_memory_pages of {0} exceeds the wasm32 limit of {} pages",
+        WASM_MEMORY_PAGES_LIMIT
+    )]
+    TooManyWasmMemoryPages(u32),
+    /// `max_stack_height` is zero.
+    #[error("max_stack_height must be greater than 0")]
+    ZeroStackHeight,
+    /// `max_stack_height` exceeds the interpreter's own stack limit.
+    #[error(
+        "max_stack_height of {0} exceeds the interpreter limit of {}",
+        STACK_HEIGHT_LIMIT
+    )]
+    StackHeightTooLarge(u32),
+}
+
+/// Checks that the given maximum global state size is non-zero, a multiple of the OS page size
+/// and at most 8 TiB.
+pub fn validate_max_global_state_size(max_global_state_size: usize) -> Result<(), ConfigError> {
+    if max_global_state_size == 0 {
+        return Err(ConfigError::ZeroGlobalStateSize);
+    }
+    if !utils::is_multiple_of_page_size(max_global_state_size) {
+        return Err(ConfigError::GlobalStateSizeNotPageAligned {
+            size: max_global_state_size,
+            page_size: *utils::OS_PAGE_SIZE,
+        });
+    }
+    if max_global_state_size > MAX_GLOBAL_STATE_SIZE_LIMIT {
+        return Err(ConfigError::GlobalStateSizeTooLarge(max_global_state_size));
+    }
+    Ok(())
+}
+
+/// Checks that the given wasm limits, if set, are non-zero and within what the wasm interpreter
+/// supports.
+pub fn validate_wasm_limits(
+    max_wasm_memory_pages: Option<u32>,
+    max_stack_height: Option<u32>,
+) -> Result<(), ConfigError> {
+    match max_wasm_memory_pages {
+        Some(0) => return Err(ConfigError::ZeroWasmMemoryPages),
+        Some(pages) if pages > WASM_MEMORY_PAGES_LIMIT => {
+            return Err(ConfigError::TooManyWasmMemoryPages(pages))
+        }
+        _ => (),
+    }
+    match max_stack_height {
+        Some(0) => Err(ConfigError::ZeroStackHeight),
+        Some(height) if height > STACK_HEIGHT_LIMIT => {
+            Err(ConfigError::StackHeightTooLarge(height))
+        }
+        _ => Ok(()),
+    }
+}
+
 /// Contract runtime configuration.
 #[derive(Clone, Copy, Debug, Deserialize, Serialize)]
 // Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
@@ -14,6 +103,8 @@ const DEFAULT_USE_SYSTEM_CONTRACTS: bool = false;
 pub struct Config {
     use_system_contracts: Option<bool>,
     max_global_state_size: Option<usize>,
+    max_wasm_memory_pages: Option<u32>,
+    max_stack_height: Option<u32>,
 }
 
 impl Config {
@@ -35,6 +126,30 @@ impl Config {
         utils::check_multiple_of_page_size(value);
         value
     }
+
+    /// The maximum number of 64 KiB pages of linear memory a wasm module may use.
+    ///
+    /// Defaults to `None`, i.e. the chainspec's `initial_memory` applies.
+    pub fn max_wasm_memory_pages(&self) -> Option<u32> {
+        self.max_wasm_memory_pages
+    }
+
+    /// The maximum stack height enforced on a wasm module.
+    ///
+    /// Defaults to `None`, i.e. the chainspec's `max_stack_height` applies.
+    pub fn max_stack_height(&self) -> Option<u32> {
+        self.max_stack_height
+    }
+
+    /// Checks that the configured global state size and wasm limits are within the supported
+    /// range.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        validate_max_global_state_size(
+            self.max_global_state_size
+                .unwrap_or(DEFAULT_MAX_GLOBAL_STATE_SIZE),
+        )?;
+        validate_wasm_limits(self.max_wasm_memory_pages, self.max_stack_height)
+    }
 }
 
 impl Default for Config {
@@ -42,6 +157,94 @@ impl Default for Config {
         Config {
             use_system_contracts: Some(DEFAULT_USE_SYSTEM_CONTRACTS),
             max_global_state_size: Some(DEFAULT_MAX_GLOBAL_STATE_SIZE),
+            max_wasm_memory_pages: None,
+            max_stack_height: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_with_global_state_size(max_global_state_size: usize) -> Config {
+        Config {
+            max_global_state_size: Some(max_global_state_size),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn should_accept_default_config() {
+        assert_eq!(Config::default().validate(), Ok(()));
+        assert_eq!(
+            config_with_global_state_size(MAX_GLOBAL_STATE_SIZE_LIMIT).validate(),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn should_reject_zero_global_state_size() {
+        assert_eq!(
+            config_with_global_state_size(0).validate(),
+            Err(ConfigError::ZeroGlobalStateSize)
+        );
+    }
+
+    #[test]
+    fn should_reject_global_state_size_not_multiple_of_page_size() {
+        let size = DEFAULT_MAX_GLOBAL_STATE_SIZE + 1;
+        assert_eq!(
+            config_with_global_state_size(size).validate(),
+            Err(ConfigError::GlobalStateSizeNotPageAligned {
+                size,
+                page_size: *utils::OS_PAGE_SIZE
+            })
+        );
+    }
+
+    #[test]
+    fn should_reject_global_state_size_above_limit() {
+        let size = MAX_GLOBAL_STATE_SIZE_LIMIT + *utils::OS_PAGE_SIZE;
+        assert_eq!(
+            config_with_global_state_size(size).validate(),
+            Err(ConfigError::GlobalStateSizeTooLarge(size))
+        );
+    }
+
+    #[test]
+    fn should_accept_unset_or_supported_wasm_limits() {
+        assert_eq!(validate_wasm_limits(None, None), Ok(()));
+        assert_eq!(
+            validate_wasm_limits(Some(WASM_MEMORY_PAGES_LIMIT), Some(STACK_HEIGHT_LIMIT)),
+            Ok(())
+        );
+        assert_eq!(validate_wasm_limits(Some(1), Some(1)), Ok(()));
+    }
+
+    #[test]
+    fn should_reject_zero_wasm_limits() {
+        assert_eq!(
+            validate_wasm_limits(Some(0), None),
+            Err(ConfigError::ZeroWasmMemoryPages)
+        );
+        assert_eq!(
+            validate_wasm_limits(None, Some(0)),
+            Err(ConfigError::ZeroStackHeight)
+        );
+    }
+
+    #[test]
+    fn should_reject_wasm_limits_above_interpreter_limits() {
+        assert_eq!(
+            validate_wasm_limits(Some(WASM_MEMORY_PAGES_LIMIT + 1), None),
+            Err(ConfigError::TooManyWasmMemoryPages(
+                WASM_MEMORY_PAGES_LIMIT + 1
+            ))
+        );
+        assert_eq!(
+            validate_wasm_limits(None, Some(STACK_HEIGHT_LIMIT + 1)),
+            Err(ConfigError::StackHeightTooLarge(STACK_HEIGHT_LIMIT + 1))
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use super::execution_result::ExecutionResults;
+
+/// The outcome of executing deploys without committing their effects.
+#[derive(Debug)]
+pub struct DryRunResult {
+    /// The results of executing each deploy, including their effects.
+    pub execution_results: ExecutionResults,
+    /// The total gas cost of the deploys, saturating at `u64::MAX`.
+    pub estimated_gas: u64,
+    /// Whether all deploys would execute successfully if run on the same state.
+    pub would_succeed: bool,
+}
+
+impl DryRunResult {
+    pub(crate) fn new(execution_results: ExecutionResults) -> Self {
+        let estimated_gas = execution_results
+            .iter()
+            .fold(0u64, |total, execution_result| {
+                let cost = execution_result.cost().value();
+                if cost > u64::max_value().into() {
+                    return u64::max_value();
+                }
+                total.saturating_add(cost.as_u64())
+            });
+        let would_succeed = execution_results
+            .iter()
+            .all(|execution_result| execution_result.is_success());
+        DryRunResult {
+            execution_results,
+            estimated_gas,
+            would_succeed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::U512;
+
+    use super::*;
+    use crate::{
+        core::engine_state::{execution_result::ExecutionResult, Error},
+        shared::gas::Gas,
+    };
+
+    fn success(cost: u64) -> ExecutionResult {
+        ExecutionResult::Success {
+            effect: Default::default(),
+            cost: Gas::new(U512::from(cost)),
+            execution_trace: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn should_sum_costs_of_successful_deploys() {
+        let dry_run_result = DryRunResult::new(vec![success(3), success(4)].into());
+        assert_eq!(dry_run_result.estimated_gas, 7);
+        assert!(dry_run_result.would_succeed);
+    }
+
+    #[test]
+    fn should_not_succeed_if_any_deploy_fails() {
+        let failure = ExecutionResult::Failure {
+            error: Error::InsufficientPayment,
+            effect: Default::default(),
+            cost: Gas::new(U512::from(5)),
+        };
+        let dry_run_result = DryRunResult::new(vec![success(3), failure].into());
+        assert_eq!(dry_run_result.estimated_gas, 8);
+        assert!(!dry_run_result.would_succeed);
+    }
+
+    #[test]
+    fn should_saturate_estimated_gas() {
+        let expensive = ExecutionResult::Success {
+            effect: Default::default(),
+            cost: Gas::new(U512::from(u64::max_value()) + 1),
+            execution_trace: Vec::new(),
+        };
+        let dry_run_result = DryRunResult::new(vec![success(3), expensive].into());
+        assert_eq!(dry_run_result.estimated_gas, u64::max_value());
+    }
+}
@@ -1,8 +1,29 @@
+use crate::{
+    core::engine_state::wasm_preprocessing_cache::DEFAULT_WASM_PREPROCESSING_CACHE_SIZE,
+    shared::{wasm_config::WasmConfig, wasm_prep::DEFAULT_MEMORY_LIMIT_PAGES},
+};
+
+/// Default maximum size of a deploy's module bytes.
+pub const DEFAULT_MAX_DEPLOY_SIZE_BYTES: usize = 1024 * 1024; // 1 MiB
+/// Default maximum size of a deploy's serialized runtime args.
+pub const DEFAULT_MAX_DEPLOY_ARGS_SIZE_BYTES: usize = 8 * 1024; // 8 KiB
+/// Default maximum number of path components in a global state query.
+pub const DEFAULT_MAX_QUERY_PATH_LENGTH: usize = 10;
+
 /// The runtime configuration of the execution engine
-#[derive(Debug, Copy, Clone, Default)]
+#[derive(Debug, Copy, Clone)]
 pub struct EngineConfig {
     // feature flags go here
     use_system_contracts: bool,
+    max_deploy_size_bytes: usize,
+    max_deploy_args_size_bytes: usize,
+    memory_limit_pages: u32,
+    enable_execution_tracing: bool,
+    max_query_path_length: usize,
+    max_wasm_memory_pages: Option<u32>,
+    max_stack_height: Option<u32>,
+    enable_system_contract_metadata_cache: bool,
+    wasm_preprocessing_cache_size: usize,
 }
 
 impl EngineConfig {
@@ -19,4 +40,139 @@ impl EngineConfig {
         self.use_system_contracts = use_system_contracts;
         self
     }
+
+    /// The maximum size of a deploy's module bytes which will be preprocessed.
+    pub fn max_deploy_size_bytes(self) -> usize {
+        self.max_deploy_size_bytes
+    }
+
+    pub fn with_max_deploy_size_bytes(mut self, max_deploy_size_bytes: usize) -> EngineConfig {
+        self.max_deploy_size_bytes = max_deploy_size_bytes;
+        self
+    }
+
+    /// The maximum size of a deploy's serialized runtime args.
+    pub fn max_deploy_args_size_bytes(self) -> usize {
+        self.max_deploy_args_size_bytes
+    }
+
+    pub fn with_max_deploy_args_size_bytes(
+        mut self,
+        max_deploy_args_size_bytes: usize,
+    ) -> EngineConfig {
+        self.max_deploy_args_size_bytes = max_deploy_args_size_bytes;
+        self
+    }
+
+    /// The maximum number of 64 KiB pages of linear memory a module may declare initially.
+    pub fn memory_limit_pages(self) -> u32 {
+        self.memory_limit_pages
+    }
+
+    pub fn with_memory_limit_pages(mut self, memory_limit_pages: u32) -> EngineConfig {
+        self.memory_limit_pages = memory_limit_pages;
+        self
+    }
+
+    /// Whether to record an execution trace for each successful execution.  Off by default, as
+    /// tracing is only useful for debugging.
+    pub fn enable_execution_tracing(self) -> bool {
+        self.enable_execution_tracing
+    }
+
+    pub fn with_enable_execution_tracing(mut self, enable_execution_tracing: bool) -> EngineConfig {
+        self.enable_execution_tracing = enable_execution_tracing;
+        self
+    }
+
+    /// The maximum number of path components a global state query may follow from its base key.
+    pub fn max_query_path_length(self) -> usize {
+        self.max_query_path_length
+    }
+
+    pub fn with_max_query_path_length(mut self, max_query_path_length: usize) -> EngineConfig {
+        self.max_query_path_length = max_query_path_length;
+        self
+    }
+
+    /// The maximum number of 64 KiB pages of linear memory a module may use at runtime, overriding
+    /// the chainspec's `initial_memory` if set.
+    pub fn max_wasm_memory_pages(self) -> Option<u32> {
+        self.max_wasm_memory_pages
+    }
+
+    pub fn with_max_wasm_memory_pages(
+        mut self,
+        max_wasm_memory_pages: Option<u32>,
+    ) -> EngineConfig {
+        self.max_wasm_memory_pages = max_wasm_memory_pages;
+        self
+    }
+
+    /// The maximum stack height enforced by the instrumented stack limiter, overriding the
+    /// chainspec's `max_stack_height` if set.
+    pub fn max_stack_height(self) -> Option<u32> {
+        self.max_stack_height
+    }
+
+    pub fn with_max_stack_height(mut self, max_stack_height: Option<u32>) -> EngineConfig {
+        self.max_stack_height = max_stack_height;
+        self
+    }
+
+    /// Whether to cache system contracts' entry points and named keys between executions.  On by
+    /// default.
+    pub fn enable_system_contract_metadata_cache(self) -> bool {
+        self.enable_system_contract_metadata_cache
+    }
+
+    pub fn with_enable_system_contract_metadata_cache(
+        mut self,
+        enable_system_contract_metadata_cache: bool,
+    ) -> EngineConfig {
+        self.enable_system_contract_metadata_cache = enable_system_contract_metadata_cache;
+        self
+    }
+
+    /// The maximum number of preprocessed deploy modules to cache, keyed by their module bytes.
+    /// Caching is disabled if 0.
+    pub fn wasm_preprocessing_cache_size(self) -> usize {
+        self.wasm_preprocessing_cache_size
+    }
+
+    pub fn with_wasm_preprocessing_cache_size(
+        mut self,
+        wasm_preprocessing_cache_size: usize,
+    ) -> EngineConfig {
+        self.wasm_preprocessing_cache_size = wasm_preprocessing_cache_size;
+        self
+    }
+
+    /// Returns `wasm_config` with any wasm limits overridden by this config applied.
+    pub fn apply_wasm_limits(self, mut wasm_config: WasmConfig) -> WasmConfig {
+        if let Some(max_wasm_memory_pages) = self.max_wasm_memory_pages {
+            wasm_config.initial_memory = max_wasm_memory_pages;
+        }
+        if let Some(max_stack_height) = self.max_stack_height {
+            wasm_config.max_stack_height = max_stack_height;
+        }
+        wasm_config
+    }
+}
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        EngineConfig {
+            use_system_contracts: false,
+            max_deploy_size_bytes: DEFAULT_MAX_DEPLOY_SIZE_BYTES,
+            max_deploy_args_size_bytes: DEFAULT_MAX_DEPLOY_ARGS_SIZE_BYTES,
+            memory_limit_pages: DEFAULT_MEMORY_LIMIT_PAGES,
+            enable_execution_tracing: false,
+            max_query_path_length: DEFAULT_MAX_QUERY_PATH_LENGTH,
+            max_wasm_memory_pages: None,
+            max_stack_height: None,
+            enable_system_contract_metadata_cache: true,
+            wasm_preprocessing_cache_size: DEFAULT_WASM_PREPROCESSING_CACHE_SIZE,
+        }
+    }
 }
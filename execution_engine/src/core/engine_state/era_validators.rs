@@ -1,3 +1,5 @@
+use std::ops::RangeInclusive;
+
 use thiserror::Error;
 
 use datasize::DataSize;
@@ -44,3 +46,50 @@ impl GetEraValidatorsRequest {
         self.protocol_version
     }
 }
+
+/// A request for the validator weights of each era in an inclusive range of eras.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetEraValidatorsRangeRequest {
+    state_hash: Blake2bHash,
+    start_era_id: EraId,
+    end_era_id: EraId,
+    protocol_version: ProtocolVersion,
+}
+
+impl GetEraValidatorsRangeRequest {
+    pub fn new(
+        state_hash: Blake2bHash,
+        era_ids: RangeInclusive<EraId>,
+        protocol_version: ProtocolVersion,
+    ) -> Self {
+        let (start_era_id, end_era_id) = era_ids.into_inner();
+        GetEraValidatorsRangeRequest {
+            state_hash,
+            start_era_id,
+            end_era_id,
+            protocol_version,
+        }
+    }
+
+    pub fn state_hash(&self) -> Blake2bHash {
+        self.state_hash
+    }
+
+    pub fn era_ids(&self) -> RangeInclusive<EraId> {
+        self.start_era_id..=self.end_era_id
+    }
+
+    pub fn protocol_version(&self) -> ProtocolVersion {
+        self.protocol_version
+    }
+}
+
+impl From<GetEraValidatorsRequest> for GetEraValidatorsRangeRequest {
+    fn from(request: GetEraValidatorsRequest) -> Self {
+        GetEraValidatorsRangeRequest::new(
+            request.state_hash,
+            request.era_id..=request.era_id,
+            request.protocol_version,
+        )
+    }
+}
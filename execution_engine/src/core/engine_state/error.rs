@@ -1,8 +1,11 @@
+use std::mem;
+
 use datasize::DataSize;
 use thiserror::Error;
 
 use casper_types::{bytesrepr, system_contract_errors::mint, ProtocolVersion};
 
+use super::genesis::{GenesisError, GenesisValidationError};
 use crate::{
     core::execution,
     shared::{newtypes::Blake2bHash, wasm_prep},
@@ -51,6 +54,16 @@ pub enum Error {
     InvalidUpgradeResult,
     #[error("Unsupported deploy item variant: {0}")]
     InvalidDeployItemVariant(String),
+    #[error("Deploy too large: {actual} bytes exceeds maximum of {max} bytes")]
+    DeployTooLarge { actual: usize, max: usize },
+    #[error("Deploy args too large: {actual} bytes exceeds maximum of {max} bytes")]
+    DeployArgsTooLarge { actual: usize, max: usize },
+    #[error("Invalid genesis config: {0}")]
+    GenesisValidation(#[from] GenesisValidationError),
+    #[error("Genesis error: {0}")]
+    Genesis(#[from] GenesisError),
+    #[error("Root not found: {0}")]
+    RootNotFound(Blake2bHash),
 }
 
 impl Error {
@@ -76,6 +89,12 @@ impl From<bytesrepr::Error> for Error {
     }
 }
 
+impl From<RootNotFound> for Error {
+    fn from(error: RootNotFound) -> Self {
+        Error::RootNotFound(error.0)
+    }
+}
+
 impl From<mint::Error> for Error {
     fn from(error: mint::Error) -> Self {
         Error::Mint(format!("{}", error))
@@ -87,10 +106,14 @@ impl DataSize for Error {
 
     const STATIC_HEAP_SIZE: usize = 0;
 
-    // TODO
     #[inline]
     fn estimate_heap_size(&self) -> usize {
-        12 // TODO: replace with some actual estimation depending on the variant
+        match self {
+            Error::Exec(error) => mem::size_of_val(error),
+            Error::Storage(error) => error.estimate_heap_size(),
+            Error::WasmPreprocessing(error) => error.to_string().len(),
+            _ => 0,
+        }
     }
 }
 
@@ -106,3 +129,35 @@ impl RootNotFound {
         self.0.as_ref().to_vec()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use datasize::DataSize;
+
+    use super::Error;
+    use crate::{core::execution, shared::wasm_prep::PreprocessingError, storage};
+
+    #[test]
+    fn should_estimate_heap_size_of_variants() {
+        let exec_error = Error::Exec(execution::Error::Interpreter("trap".to_string()));
+        assert!(exec_error.estimate_heap_size() > 0);
+
+        let preprocessing_error =
+            Error::WasmPreprocessing(PreprocessingError::Deserialize("bad wasm".to_string()));
+        assert!(preprocessing_error.estimate_heap_size() > 0);
+
+        let storage_error = Error::Storage(storage::error::Error::Poison);
+        assert_eq!(storage_error.estimate_heap_size(), 0);
+
+        for error in &[
+            Error::InvalidUpgradeConfig,
+            Error::Authorization,
+            Error::InsufficientPayment,
+            Error::Deploy,
+            Error::Finalization,
+            Error::InvalidUpgradeResult,
+        ] {
+            assert_eq!(error.estimate_heap_size(), 0);
+        }
+    }
+}
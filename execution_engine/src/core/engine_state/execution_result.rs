@@ -2,7 +2,13 @@ use std::collections::VecDeque;
 
 use casper_types::{bytesrepr::FromBytes, CLTyped, CLValue, Key};
 
-use super::{error, execution_effect::ExecutionEffect, op::Op, CONV_RATE};
+use super::{
+    error,
+    execution_effect::ExecutionEffect,
+    execution_trace::{ExecutionStep, ExecutionTrace},
+    op::Op,
+    CONV_RATE,
+};
 use crate::{
     shared::{
         additive_map::AdditiveMap, gas::Gas, motes::Motes, newtypes::CorrelationId,
@@ -52,7 +58,12 @@ pub enum ExecutionResult {
         cost: Gas,
     },
     /// Execution was finished successfully
-    Success { effect: ExecutionEffect, cost: Gas },
+    Success {
+        effect: ExecutionEffect,
+        cost: Gas,
+        /// The steps taken during execution; empty unless execution tracing is enabled.
+        execution_trace: ExecutionTrace,
+    },
 }
 
 /// A type alias that represents multiple execution results.
@@ -121,7 +132,15 @@ impl ExecutionResult {
                 effect,
                 cost,
             },
-            ExecutionResult::Success { effect, .. } => ExecutionResult::Success { effect, cost },
+            ExecutionResult::Success {
+                effect,
+                execution_trace,
+                ..
+            } => ExecutionResult::Success {
+                effect,
+                cost,
+                execution_trace,
+            },
         }
     }
 
@@ -132,7 +151,26 @@ impl ExecutionResult {
                 effect,
                 cost,
             },
-            ExecutionResult::Success { cost, .. } => ExecutionResult::Success { effect, cost },
+            ExecutionResult::Success {
+                cost,
+                execution_trace,
+                ..
+            } => ExecutionResult::Success {
+                effect,
+                cost,
+                execution_trace,
+            },
+        }
+    }
+
+    /// Returns the steps taken during execution, which are only recorded for successful
+    /// executions with execution tracing enabled.
+    pub fn execution_trace(&self) -> &[ExecutionStep] {
+        match self {
+            ExecutionResult::Failure { .. } => &[],
+            ExecutionResult::Success {
+                execution_trace, ..
+            } => execution_trace,
         }
     }
 
@@ -283,10 +321,7 @@ impl ExecutionResultBuilder {
         let mut ops = AdditiveMap::new();
         let mut transforms = AdditiveMap::new();
 
-        let mut ret: ExecutionResult = ExecutionResult::Success {
-            effect: Default::default(),
-            cost,
-        };
+        let mut execution_trace = ExecutionTrace::new();
 
         match self.payment_execution_result {
             Some(result) => {
@@ -294,6 +329,7 @@ impl ExecutionResultBuilder {
                     return Ok(result);
                 } else {
                     Self::add_effects(&mut ops, &mut transforms, result.effect());
+                    execution_trace.extend_from_slice(result.execution_trace());
                 }
             }
             None => return Err(ExecutionResultBuilderError::MissingPaymentExecutionResult),
@@ -301,12 +337,14 @@ impl ExecutionResultBuilder {
 
         // session_code_spec_3: only include session exec effects if there is no session
         // exec error
+        let mut maybe_session_failure = None;
         match self.session_execution_result {
             Some(result) => {
                 if result.is_failure() {
-                    ret = result.with_cost(cost);
+                    maybe_session_failure = Some(result.with_cost(cost));
                 } else {
                     Self::add_effects(&mut ops, &mut transforms, result.effect());
+                    execution_trace.extend_from_slice(result.execution_trace());
                 }
             }
             None => return Err(ExecutionResultBuilderError::MissingSessionExecutionResult),
@@ -321,11 +359,18 @@ impl ExecutionResultBuilder {
                     ));
                 } else {
                     Self::add_effects(&mut ops, &mut transforms, result.effect());
+                    execution_trace.extend_from_slice(result.execution_trace());
                 }
             }
             None => return Err(ExecutionResultBuilderError::MissingFinalizeExecutionResult),
         }
 
+        let ret = maybe_session_failure.unwrap_or(ExecutionResult::Success {
+            effect: Default::default(),
+            cost,
+            execution_trace,
+        });
+
         // Remove redundant writes to allow more opportunity to commute
         let reduced_effect = Self::reduce_identity_writes(ops, transforms, reader, correlation_id);
 
@@ -0,0 +1,37 @@
+use casper_types::{ContractHash, Key, URef, U512};
+
+/// A single step taken while executing a deploy, recorded when execution tracing is enabled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExecutionStep {
+    /// A call to a stored contract, recorded once the call has returned.
+    ContractCall {
+        /// The hash of the called contract.
+        contract_hash: ContractHash,
+        /// The name of the called entry point.
+        entry_point: String,
+        /// The gas used by the call, including any nested calls.
+        gas_used: u64,
+    },
+    /// A read from global state.
+    StorageRead {
+        /// The key which was read.
+        key: Key,
+    },
+    /// A write or addition to global state.
+    StorageWrite {
+        /// The key which was written to.
+        key: Key,
+    },
+    /// A transfer of motes between purses via the mint.
+    Transfer {
+        /// The source purse.
+        from: URef,
+        /// The target purse.
+        to: URef,
+        /// The amount of motes transferred.
+        amount: U512,
+    },
+}
+
+/// The steps taken while executing a deploy, in the order they completed.
+pub type ExecutionTrace = Vec<ExecutionStep>;
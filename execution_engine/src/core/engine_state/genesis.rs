@@ -1,4 +1,4 @@
-use std::{fmt, iter};
+use std::{collections::BTreeSet, fmt, iter};
 
 use datasize::DataSize;
 use num_traits::Zero;
@@ -7,19 +7,35 @@ use rand::{
     Rng,
 };
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
-use casper_types::{account::AccountHash, bytesrepr, Key, ProtocolVersion, PublicKey, U512};
+use casper_types::{
+    account::AccountHash,
+    auction::{
+        AUCTION_DELAY, DEFAULT_BID_EVICTION_DELAY, DEFAULT_LOCKED_FUNDS_PERIOD,
+        DEFAULT_UNBONDING_DELAY,
+    },
+    bytesrepr, Key, ProtocolVersion, PublicKey, U512,
+};
 
 use super::SYSTEM_ACCOUNT_ADDR;
 use crate::{
     core::engine_state::execution_effect::ExecutionEffect,
-    shared::{motes::Motes, newtypes::Blake2bHash, wasm_config::WasmConfig, TypeMismatch},
+    shared::{
+        motes::Motes,
+        newtypes::Blake2bHash,
+        wasm_config::{WasmConfig, STACK_HEIGHT_LIMIT, WASM_MEMORY_PAGES_LIMIT},
+        wasm_prep::{PreprocessingError, Preprocessor, DEFAULT_MEMORY_LIMIT_PAGES},
+        TypeMismatch,
+    },
     storage::global_state::CommitResult,
 };
 
 pub const PLACEHOLDER_KEY: Key = Key::Hash([0u8; 32]);
 pub const POS_PAYMENT_PURSE: &str = "pos_payment_purse";
 pub const POS_REWARDS_PURSE: &str = "pos_rewards_purse";
+/// The maximum number of characters in the name of a genesis config.
+pub const MAX_GENESIS_NAME_LENGTH: usize = 32;
 
 #[derive(Debug)]
 pub enum GenesisResult {
@@ -63,6 +79,130 @@ impl GenesisResult {
             },
         }
     }
+
+    /// Returns `true` if genesis was committed successfully.
+    ///
+    /// ```
+    /// # use casper_execution_engine::{
+    /// #     core::engine_state::genesis::GenesisResult, shared::newtypes::Blake2bHash,
+    /// # };
+    /// let success = GenesisResult::Success {
+    ///     post_state_hash: Blake2bHash::new(b"genesis"),
+    ///     effect: Default::default(),
+    /// };
+    /// assert!(success.is_success());
+    /// assert!(!GenesisResult::RootNotFound.is_success());
+    /// ```
+    pub fn is_success(&self) -> bool {
+        matches!(self, GenesisResult::Success { .. })
+    }
+
+    /// Returns the post-state hash if genesis was committed successfully, otherwise `None`.
+    ///
+    /// ```
+    /// # use casper_execution_engine::{
+    /// #     core::engine_state::genesis::GenesisResult, shared::newtypes::Blake2bHash,
+    /// # };
+    /// let post_state_hash = Blake2bHash::new(b"genesis");
+    /// let success = GenesisResult::Success {
+    ///     post_state_hash,
+    ///     effect: Default::default(),
+    /// };
+    /// assert_eq!(success.post_state_hash(), Some(post_state_hash));
+    /// assert_eq!(GenesisResult::RootNotFound.post_state_hash(), None);
+    /// ```
+    pub fn post_state_hash(&self) -> Option<Blake2bHash> {
+        match self {
+            GenesisResult::Success {
+                post_state_hash, ..
+            } => Some(*post_state_hash),
+            _ => None,
+        }
+    }
+
+    /// Returns the post-state hash if genesis was committed successfully, otherwise returns
+    /// `self` as the error.
+    ///
+    /// ```
+    /// # use casper_execution_engine::{
+    /// #     core::engine_state::genesis::GenesisResult, shared::newtypes::Blake2bHash,
+    /// # };
+    /// let post_state_hash = Blake2bHash::new(b"genesis");
+    /// let success = GenesisResult::Success {
+    ///     post_state_hash,
+    ///     effect: Default::default(),
+    /// };
+    /// assert_eq!(success.into_post_state_hash().unwrap(), post_state_hash);
+    ///
+    /// let failure = GenesisResult::RootNotFound.into_post_state_hash().unwrap_err();
+    /// assert_eq!(failure.to_string(), "Root not found");
+    /// ```
+    pub fn into_post_state_hash(self) -> Result<Blake2bHash, GenesisResult> {
+        match self {
+            GenesisResult::Success {
+                post_state_hash, ..
+            } => Ok(post_state_hash),
+            genesis_result => Err(genesis_result),
+        }
+    }
+}
+
+/// An error describing why a genesis config is malformed.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum GenesisValidationError {
+    /// The genesis name is empty.
+    #[error("Genesis name is empty")]
+    EmptyName,
+    /// The genesis name is longer than `MAX_GENESIS_NAME_LENGTH` characters.
+    #[error("Genesis name too long: {length} characters exceeds maximum of {max}")]
+    NameTooLong { length: usize, max: usize },
+    /// The genesis timestamp is zero.
+    #[error("Genesis timestamp is zero")]
+    ZeroTimestamp,
+    /// None of the genesis accounts is a validator with a non-zero bonded amount.
+    #[error("No genesis validator has a non-zero bonded amount")]
+    NoBondedValidators,
+    /// More than one genesis account has the given account hash.
+    #[error("Duplicate genesis account hash: {0}")]
+    DuplicateAccountHash(AccountHash),
+    /// A wasm config has a zero cost for regular opcodes.
+    #[error("Wasm config has zero regular opcode cost")]
+    ZeroRegularOpcodeCost,
+    /// A wasm config's initial memory is zero or exceeds the memory addressable by a wasm32
+    /// module.
+    #[error(
+        "Wasm config initial memory of {0} pages is not between 1 and {} pages",
+        WASM_MEMORY_PAGES_LIMIT
+    )]
+    InvalidInitialMemory(u32),
+    /// A wasm config's max stack height is zero or exceeds the interpreter's own stack limit.
+    #[error(
+        "Wasm config max stack height of {0} is not between 1 and {}",
+        STACK_HEIGHT_LIMIT
+    )]
+    InvalidMaxStackHeight(u32),
+}
+
+/// The reason a system contract installer can't be used to run genesis.
+#[derive(Error, Debug, Clone)]
+pub enum InstallerError {
+    /// The installer bytes are empty.
+    #[error("installer bytes are empty")]
+    Empty,
+    /// The installer bytes failed wasm preprocessing.
+    #[error(transparent)]
+    Preprocessing(#[from] PreprocessingError),
+}
+
+/// An error raised while running genesis.
+#[derive(Error, Debug, Clone)]
+pub enum GenesisError {
+    /// The installer of the named system contract is empty or not valid wasm.
+    #[error("Invalid {name} installer: {source}")]
+    InvalidInstaller {
+        name: &'static str,
+        source: InstallerError,
+    },
 }
 
 #[derive(DataSize, Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -98,6 +238,14 @@ impl GenesisAccount {
         }
     }
 
+    /// Creates a genesis account owned by a compressed secp256k1 public key, with the account hash
+    /// derived from that key.
+    pub fn secp256k1(public_key: [u8; 33], balance: Motes, bonded_amount: Motes) -> Self {
+        let public_key = PublicKey::Secp256k1(public_key.into());
+        let account_hash = AccountHash::from(public_key);
+        GenesisAccount::new(public_key, account_hash, balance, bonded_amount)
+    }
+
     pub fn public_key(&self) -> Option<PublicKey> {
         self.public_key
     }
@@ -131,7 +279,13 @@ impl Distribution<GenesisAccount> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> GenesisAccount {
         let account_hash = AccountHash::new(rng.gen());
 
-        let public_key = PublicKey::Ed25519(rng.gen());
+        let public_key = if rng.gen() {
+            PublicKey::Ed25519(rng.gen())
+        } else {
+            let mut secp256k1_bytes = [0u8; 33];
+            rng.fill_bytes(&mut secp256k1_bytes);
+            PublicKey::Secp256k1(secp256k1_bytes.into())
+        };
 
         let mut u512_array = [0u8; 64];
         rng.fill_bytes(u512_array.as_mut());
@@ -190,6 +344,36 @@ impl GenesisConfig {
     pub fn take_ee_config(self) -> ExecConfig {
         self.ee_config
     }
+
+    /// Checks that the config is well-formed, so that a misconfigured genesis is rejected before
+    /// running any of the installers.
+    pub fn validate(&self) -> Result<(), GenesisValidationError> {
+        let name_length = self.name.chars().count();
+        if name_length == 0 {
+            return Err(GenesisValidationError::EmptyName);
+        }
+        if name_length > MAX_GENESIS_NAME_LENGTH {
+            return Err(GenesisValidationError::NameTooLong {
+                length: name_length,
+                max: MAX_GENESIS_NAME_LENGTH,
+            });
+        }
+
+        if self.timestamp == 0 {
+            return Err(GenesisValidationError::ZeroTimestamp);
+        }
+
+        if !self
+            .ee_config
+            .accounts()
+            .iter()
+            .any(GenesisAccount::is_genesis_validator)
+        {
+            return Err(GenesisValidationError::NoBondedValidators);
+        }
+
+        self.ee_config.validate()
+    }
 }
 
 impl Distribution<GenesisConfig> for Standard {
@@ -224,6 +408,11 @@ pub struct ExecConfig {
     accounts: Vec<GenesisAccount>,
     wasm_config: WasmConfig,
     validator_slots: u32,
+    auction_delay: u64,
+    unbonding_delay: u64,
+    bid_eviction_delay: u64,
+    locked_funds_period: u64,
+    memory_limit_pages: u32,
 }
 
 impl ExecConfig {
@@ -244,6 +433,11 @@ impl ExecConfig {
             accounts,
             wasm_config,
             validator_slots,
+            auction_delay: AUCTION_DELAY,
+            unbonding_delay: DEFAULT_UNBONDING_DELAY,
+            bid_eviction_delay: DEFAULT_BID_EVICTION_DELAY,
+            locked_funds_period: DEFAULT_LOCKED_FUNDS_PERIOD,
+            memory_limit_pages: DEFAULT_MEMORY_LIMIT_PAGES,
         }
     }
 
@@ -263,6 +457,8 @@ impl ExecConfig {
         self.auction_installer_bytes.as_slice()
     }
 
+    /// Returns the wasm config at genesis.  Upgrades replace it by storing a new wasm config in the
+    /// protocol data of the new protocol version.
     pub fn wasm_config(&self) -> &WasmConfig {
         &self.wasm_config
     }
@@ -284,6 +480,120 @@ impl ExecConfig {
     pub fn validator_slots(&self) -> u32 {
         self.validator_slots
     }
+
+    /// The number of eras before an auction defines the set of validators.  Defaults to
+    /// `AUCTION_DELAY`.
+    pub fn auction_delay(&self) -> u64 {
+        self.auction_delay
+    }
+
+    pub fn set_auction_delay(&mut self, auction_delay: u64) {
+        self.auction_delay = auction_delay
+    }
+
+    /// The number of eras before unbonded funds can be withdrawn.  Defaults to
+    /// `DEFAULT_UNBONDING_DELAY`.
+    pub fn unbonding_delay(&self) -> u64 {
+        self.unbonding_delay
+    }
+
+    pub fn set_unbonding_delay(&mut self, unbonding_delay: u64) {
+        self.unbonding_delay = unbonding_delay
+    }
+
+    /// The number of consecutive auctions a bid can stay below the minimum bid amount before it
+    /// is evicted.  Defaults to `DEFAULT_BID_EVICTION_DELAY`.
+    pub fn bid_eviction_delay(&self) -> u64 {
+        self.bid_eviction_delay
+    }
+
+    pub fn set_bid_eviction_delay(&mut self, bid_eviction_delay: u64) {
+        self.bid_eviction_delay = bid_eviction_delay
+    }
+
+    /// The number of eras after genesis during which the genesis validators' stakes are locked.
+    /// Defaults to `DEFAULT_LOCKED_FUNDS_PERIOD`.
+    pub fn locked_funds_period(&self) -> u64 {
+        self.locked_funds_period
+    }
+
+    pub fn set_locked_funds_period(&mut self, locked_funds_period: u64) {
+        self.locked_funds_period = locked_funds_period
+    }
+
+    /// The maximum number of 64 KiB pages of linear memory a module may declare initially.
+    /// Defaults to 512 (32 MiB).
+    pub fn memory_limit_pages(&self) -> u32 {
+        self.memory_limit_pages
+    }
+
+    pub fn set_memory_limit_pages(&mut self, memory_limit_pages: u32) {
+        self.memory_limit_pages = memory_limit_pages
+    }
+
+    /// Checks the parts of a genesis config which are passed to the execution engine: that account
+    /// hashes are unique and that the wasm config charges for regular opcodes and has memory and
+    /// stack limits the interpreter supports.  The installers are checked separately by
+    /// [`ExecConfig::validate_installers`].
+    ///
+    /// Unlike [`GenesisConfig::validate`], this doesn't require any bonded validators.
+    pub fn validate(&self) -> Result<(), GenesisValidationError> {
+        let mut account_hashes = BTreeSet::new();
+        for account in &self.accounts {
+            if !account_hashes.insert(account.account_hash()) {
+                return Err(GenesisValidationError::DuplicateAccountHash(
+                    account.account_hash(),
+                ));
+            }
+        }
+
+        let wasm_config = &self.wasm_config;
+        if wasm_config.opcode_costs().regular == 0 {
+            return Err(GenesisValidationError::ZeroRegularOpcodeCost);
+        }
+        if wasm_config.initial_memory == 0 || wasm_config.initial_memory > WASM_MEMORY_PAGES_LIMIT {
+            return Err(GenesisValidationError::InvalidInitialMemory(
+                wasm_config.initial_memory,
+            ));
+        }
+        if wasm_config.max_stack_height == 0 || wasm_config.max_stack_height > STACK_HEIGHT_LIMIT {
+            return Err(GenesisValidationError::InvalidMaxStackHeight(
+                wasm_config.max_stack_height,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Checks that each system contract installer is non-empty and passes wasm preprocessing,
+    /// naming the first installer which doesn't.
+    pub fn validate_installers(&self, preprocessor: &Preprocessor) -> Result<(), GenesisError> {
+        for (name, bytes) in self.installers().iter() {
+            let result = if bytes.is_empty() {
+                Err(InstallerError::Empty)
+            } else {
+                preprocessor
+                    .preprocess(bytes)
+                    .map(|_module| ())
+                    .map_err(InstallerError::from)
+            };
+            result.map_err(|source| GenesisError::InvalidInstaller {
+                name: *name,
+                source,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Returns the installer bytes of each system contract, along with the contract's name.
+    fn installers(&self) -> [(&'static str, &[u8]); 4] {
+        [
+            ("mint", &self.mint_installer_bytes),
+            ("proof of stake", &self.proof_of_stake_installer_bytes),
+            ("standard payment", &self.standard_payment_installer_bytes),
+            ("auction", &self.auction_installer_bytes),
+        ]
+    }
 }
 
 impl Distribution<ExecConfig> for Standard {
@@ -316,6 +626,201 @@ impl Distribution<ExecConfig> for Standard {
             accounts,
             wasm_config,
             validator_slots,
+            auction_delay: AUCTION_DELAY,
+            unbonding_delay: DEFAULT_UNBONDING_DELAY,
+            bid_eviction_delay: DEFAULT_BID_EVICTION_DELAY,
+            locked_funds_period: DEFAULT_LOCKED_FUNDS_PERIOD,
+            memory_limit_pages: DEFAULT_MEMORY_LIMIT_PAGES,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use casper_types::{account::AccountHash, ProtocolVersion, PublicKey, U512};
+
+    use super::{
+        ExecConfig, GenesisAccount, GenesisConfig, GenesisValidationError, MAX_GENESIS_NAME_LENGTH,
+    };
+    use crate::shared::{
+        motes::Motes,
+        opcode_costs::OpcodeCosts,
+        wasm_config::{
+            WasmConfig, DEFAULT_MAX_STACK_HEIGHT, STACK_HEIGHT_LIMIT, WASM_MEMORY_PAGES_LIMIT,
+        },
+    };
+
+    fn wasm_config_with_initial_memory(initial_memory: u32) -> WasmConfig {
+        WasmConfig::new(
+            initial_memory,
+            DEFAULT_MAX_STACK_HEIGHT,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        )
+    }
+
+    fn genesis_account(byte: u8, bonded_amount: u64) -> GenesisAccount {
+        GenesisAccount::new(
+            PublicKey::Ed25519([byte; 32]),
+            AccountHash::new([byte; 32]),
+            Motes::new(U512::from(1_000)),
+            Motes::new(U512::from(bonded_amount)),
+        )
+    }
+
+    fn valid_exec_config() -> ExecConfig {
+        ExecConfig::new(
+            vec![1],
+            vec![2],
+            vec![3],
+            vec![4],
+            vec![genesis_account(1, 100), genesis_account(2, 0)],
+            wasm_config_with_initial_memory(1),
+            5,
+        )
+    }
+
+    fn genesis_config(name: &str, timestamp: u64, exec_config: ExecConfig) -> GenesisConfig {
+        GenesisConfig::new(
+            name.to_string(),
+            timestamp,
+            ProtocolVersion::V1_0_0,
+            exec_config,
+        )
+    }
+
+    #[test]
+    fn should_validate_genesis_config() {
+        let genesis_config = genesis_config("casper", 1, valid_exec_config());
+        assert_eq!(genesis_config.validate(), Ok(()));
+    }
+
+    #[test]
+    fn should_reject_invalid_name() {
+        let genesis_config = genesis_config("", 1, valid_exec_config());
+        assert_eq!(
+            genesis_config.validate(),
+            Err(GenesisValidationError::EmptyName)
+        );
+
+        let max_length_name = "a".repeat(MAX_GENESIS_NAME_LENGTH);
+        let genesis_config = genesis_config(&max_length_name, 1, valid_exec_config());
+        assert_eq!(genesis_config.validate(), Ok(()));
+
+        let too_long_name = "a".repeat(MAX_GENESIS_NAME_LENGTH + 1);
+        let genesis_config = genesis_config(&too_long_name, 1, valid_exec_config());
+        assert_eq!(
+            genesis_config.validate(),
+            Err(GenesisValidationError::NameTooLong {
+                length: MAX_GENESIS_NAME_LENGTH + 1,
+                max: MAX_GENESIS_NAME_LENGTH
+            })
+        );
+    }
+
+    #[test]
+    fn should_reject_zero_timestamp() {
+        let genesis_config = genesis_config("casper", 0, valid_exec_config());
+        assert_eq!(
+            genesis_config.validate(),
+            Err(GenesisValidationError::ZeroTimestamp)
+        );
+    }
+
+    #[test]
+    fn should_reject_genesis_without_bonded_validators() {
+        let system_account =
+            GenesisAccount::system(Motes::new(U512::from(1_000)), Motes::new(U512::from(100)));
+        let exec_config = ExecConfig::new(
+            vec![1],
+            vec![2],
+            vec![3],
+            vec![4],
+            vec![genesis_account(1, 0), system_account],
+            wasm_config_with_initial_memory(1),
+            5,
+        );
+        // The engine itself doesn't require bonded validators.
+        assert_eq!(exec_config.validate(), Ok(()));
+
+        let genesis_config = genesis_config("casper", 1, exec_config);
+        assert_eq!(
+            genesis_config.validate(),
+            Err(GenesisValidationError::NoBondedValidators)
+        );
+    }
+
+    #[test]
+    fn should_reject_duplicate_account_hashes() {
+        let mut exec_config = valid_exec_config();
+        exec_config.push_account(genesis_account(2, 50));
+        assert_eq!(
+            exec_config.validate(),
+            Err(GenesisValidationError::DuplicateAccountHash(
+                AccountHash::new([2; 32])
+            ))
+        );
+    }
+
+    #[test]
+    fn should_reject_zero_regular_opcode_cost() {
+        let mut opcode_costs = OpcodeCosts::default();
+        opcode_costs.regular = 0;
+        let wasm_config = WasmConfig::new(
+            1,
+            DEFAULT_MAX_STACK_HEIGHT,
+            opcode_costs,
+            Default::default(),
+            Default::default(),
+        );
+
+        let exec_config = ExecConfig {
+            wasm_config,
+            ..valid_exec_config()
+        };
+        assert_eq!(
+            exec_config.validate(),
+            Err(GenesisValidationError::ZeroRegularOpcodeCost)
+        );
+    }
+
+    #[test]
+    fn should_reject_wasm_limits_outside_interpreter_limits() {
+        let with_limits = |initial_memory, max_stack_height| ExecConfig {
+            wasm_config: WasmConfig::new(
+                initial_memory,
+                max_stack_height,
+                Default::default(),
+                Default::default(),
+                Default::default(),
+            ),
+            ..valid_exec_config()
+        };
+
+        assert_eq!(
+            with_limits(WASM_MEMORY_PAGES_LIMIT, STACK_HEIGHT_LIMIT).validate(),
+            Ok(())
+        );
+        assert_eq!(
+            with_limits(0, DEFAULT_MAX_STACK_HEIGHT).validate(),
+            Err(GenesisValidationError::InvalidInitialMemory(0))
+        );
+        assert_eq!(
+            with_limits(WASM_MEMORY_PAGES_LIMIT + 1, DEFAULT_MAX_STACK_HEIGHT).validate(),
+            Err(GenesisValidationError::InvalidInitialMemory(
+                WASM_MEMORY_PAGES_LIMIT + 1
+            ))
+        );
+        assert_eq!(
+            with_limits(1, 0).validate(),
+            Err(GenesisValidationError::InvalidMaxStackHeight(0))
+        );
+        assert_eq!(
+            with_limits(1, STACK_HEIGHT_LIMIT + 1).validate(),
+            Err(GenesisValidationError::InvalidMaxStackHeight(
+                STACK_HEIGHT_LIMIT + 1
+            ))
+        );
+    }
+}
@@ -1,5 +1,6 @@
 pub mod balance;
 pub mod deploy_item;
+pub mod dry_run;
 pub mod engine_config;
 pub mod era_validators;
 mod error;
@@ -7,18 +8,22 @@ pub mod executable_deploy_item;
 pub mod execute_request;
 pub mod execution_effect;
 pub mod execution_result;
+pub mod execution_trace;
 pub mod genesis;
 pub mod op;
 pub mod query;
 pub mod run_genesis_request;
 pub mod step;
 pub mod system_contract_cache;
+pub mod system_contract_metadata_cache;
 mod transfer;
 pub mod upgrade;
+pub mod wasm_preprocessing_cache;
 
 use std::{
     cell::RefCell,
     collections::{BTreeMap, BTreeSet},
+    convert::TryFrom,
     iter::FromIterator,
     rc::Rc,
 };
@@ -30,32 +35,44 @@ use tracing::{debug, error, warn};
 use casper_types::{
     account::AccountHash,
     auction::{
-        ValidatorWeights, ARG_ERA_ID, ARG_GENESIS_VALIDATORS, ARG_MINT_CONTRACT_PACKAGE_HASH,
-        ARG_REWARD_FACTORS, ARG_VALIDATOR_PUBLIC_KEYS, ARG_VALIDATOR_SLOTS, VALIDATOR_SLOTS_KEY,
+        Bids, EraValidators, LegacyBids, LegacyUnbondingPurses, UnbondingPurses, ValidatorWeights,
+        ARG_AUCTION_DELAY, ARG_BID_EVICTION_DELAY, ARG_BLOCKS_PROPOSED, ARG_ERA_ID,
+        ARG_GENESIS_VALIDATORS, ARG_LOCKED_FUNDS_PERIOD, ARG_MINT_CONTRACT_PACKAGE_HASH,
+        ARG_REWARD_FACTORS, ARG_UNBONDING_DELAY, ARG_VALIDATOR_PUBLIC_KEYS, ARG_VALIDATOR_SLOTS,
+        BIDS_KEY, ERA_VALIDATORS_KEY, LOCKED_FUNDS_PERIOD_KEY, UNBONDING_DELAY_KEY,
+        UNBONDING_PURSES_KEY, VALIDATOR_SLOTS_KEY,
     },
-    bytesrepr::{self, ToBytes},
+    bytesrepr::{self, FromBytes, ToBytes},
     contracts::{NamedKeys, ENTRY_POINT_NAME_INSTALL, UPGRADE_ENTRY_POINT_NAME},
     runtime_args,
     system_contract_errors::mint,
-    AccessRights, BlockTime, CLValue, Contract, ContractHash, ContractPackage, ContractPackageHash,
-    ContractVersionKey, EntryPoint, EntryPointType, Key, Phase, ProtocolVersion, RuntimeArgs, URef,
-    U512,
+    AccessRights, BlockTime, CLTyped, CLValue, Contract, ContractHash, ContractPackage,
+    ContractPackageHash, ContractVersionKey, EntryPoint, EntryPointType, Key, Phase,
+    ProtocolVersion, RuntimeArgs, URef, U512,
 };
 
 pub use self::{
     balance::{BalanceRequest, BalanceResult},
     deploy_item::DeployItem,
+    dry_run::DryRunResult,
     engine_config::EngineConfig,
-    era_validators::{GetEraValidatorsError, GetEraValidatorsRequest},
+    era_validators::{
+        GetEraValidatorsError, GetEraValidatorsRangeRequest, GetEraValidatorsRequest,
+    },
     error::{Error, RootNotFound},
     executable_deploy_item::ExecutableDeployItem,
     execute_request::ExecuteRequest,
     execution_result::{ExecutionResult, ExecutionResults, ForcedTransferResult},
-    genesis::{ExecConfig, GenesisAccount, GenesisResult, POS_PAYMENT_PURSE, POS_REWARDS_PURSE},
+    genesis::{
+        ExecConfig, GenesisAccount, GenesisError, GenesisResult, GenesisValidationError,
+        InstallerError, POS_PAYMENT_PURSE, POS_REWARDS_PURSE,
+    },
     query::{QueryRequest, QueryResult},
     system_contract_cache::SystemContractCache,
+    system_contract_metadata_cache::SystemContractMetadataCache,
     transfer::{TransferRuntimeArgsBuilder, TransferTargetMode},
-    upgrade::{UpgradeConfig, UpgradeResult},
+    upgrade::{AuctionChanges, AuctionUpgradeConfig, UpgradeConfig, UpgradeResult},
+    wasm_preprocessing_cache::WasmPreprocessingCache,
 };
 use crate::{
     core::{
@@ -74,10 +91,10 @@ use crate::{
         stored_value::StoredValue,
         transform::Transform,
         wasm_config::WasmConfig,
-        wasm_prep::{self, Preprocessor},
+        wasm_prep::{self, PreprocessingConfig, Preprocessor},
     },
     storage::{
-        global_state::{CommitResult, StateProvider},
+        global_state::{CommitResult, StateProvider, StateReader},
         protocol_data::ProtocolData,
     },
 };
@@ -96,6 +113,8 @@ const ARG_AMOUNT: &str = "amount";
 pub struct EngineState<S> {
     config: EngineConfig,
     system_contract_cache: SystemContractCache,
+    system_contract_metadata_cache: SystemContractMetadataCache,
+    wasm_preprocessing_cache: WasmPreprocessingCache,
     state: S,
 }
 
@@ -132,9 +151,14 @@ where
 {
     pub fn new(state: S, config: EngineConfig) -> EngineState<S> {
         let system_contract_cache = Default::default();
+        let system_contract_metadata_cache = Default::default();
+        let wasm_preprocessing_cache =
+            WasmPreprocessingCache::new(config.wasm_preprocessing_cache_size());
         EngineState {
             config,
             system_contract_cache,
+            system_contract_metadata_cache,
+            wasm_preprocessing_cache,
             state,
         }
     }
@@ -143,6 +167,17 @@ where
         &self.config
     }
 
+    /// The cache of system contracts' entry points and named keys, exposed for its hit and miss
+    /// counts.
+    pub fn system_contract_metadata_cache(&self) -> &SystemContractMetadataCache {
+        &self.system_contract_metadata_cache
+    }
+
+    /// The cache of preprocessed deploy modules, exposed for its hit and miss counts.
+    pub fn wasm_preprocessing_cache(&self) -> &WasmPreprocessingCache {
+        &self.wasm_preprocessing_cache
+    }
+
     pub fn wasm_config(
         &self,
         protocol_version: ProtocolVersion,
@@ -171,6 +206,17 @@ where
         protocol_version: ProtocolVersion,
         ee_config: &ExecConfig,
     ) -> Result<GenesisResult, Error> {
+        let wasm_config = ee_config.wasm_config();
+        let preprocessor = Preprocessor::new(PreprocessingConfig::new(
+            self.config.apply_wasm_limits(*wasm_config),
+            ee_config.memory_limit_pages(),
+        ));
+
+        // Reject a malformed config before running any of the installers.  The installers are
+        // checked first so that a bad one is reported by name.
+        ee_config.validate_installers(&preprocessor)?;
+        ee_config.validate()?;
+
         // Preliminaries
         let executor = Executor::new(self.config);
         let blocktime = BlockTime::new(GENESIS_INITIAL_BLOCKTIME);
@@ -178,8 +224,6 @@ where
         let phase = Phase::System;
 
         let initial_root_hash = self.state.empty_root();
-        let wasm_config = ee_config.wasm_config();
-        let preprocessor = Preprocessor::new(*wasm_config);
 
         // Spec #3: Create "virtual system account" object.
         let mut virtual_system_account = {
@@ -390,11 +434,19 @@ where
             };
 
             let validator_slots = ee_config.validator_slots();
+            let auction_delay = ee_config.auction_delay();
+            let unbonding_delay = ee_config.unbonding_delay();
+            let bid_eviction_delay = ee_config.bid_eviction_delay();
+            let locked_funds_period = ee_config.locked_funds_period();
             let auction_installer_module = preprocessor.preprocess(auction_installer_bytes)?;
             let args = runtime_args! {
                 ARG_MINT_CONTRACT_PACKAGE_HASH => mint_package_hash,
                 ARG_GENESIS_VALIDATORS => bonded_validators,
                 ARG_VALIDATOR_SLOTS => validator_slots,
+                ARG_AUCTION_DELAY => auction_delay,
+                ARG_UNBONDING_DELAY => unbonding_delay,
+                ARG_BID_EVICTION_DELAY => bid_eviction_delay,
+                ARG_LOCKED_FUNDS_PERIOD => locked_funds_period,
             };
             let authorization_keys = BTreeSet::new();
             let install_deploy_hash = genesis_config_hash.value();
@@ -572,6 +624,10 @@ where
         // per specification:
         // https://casperlabs.atlassian.net/wiki/spaces/EN/pages/139854367/Upgrading+System+Contracts+Specification
 
+        // An upgrade may replace any system contract, so cached metadata can't be trusted
+        // afterwards.
+        self.system_contract_metadata_cache.flush();
+
         // 3.1.1.1.1.1 validate pre state hash exists
         // 3.1.2.1 get a tracking_copy at the provided pre_state_hash
         let pre_state_hash = upgrade_config.pre_state_hash();
@@ -622,6 +678,12 @@ where
             .put_protocol_data(new_protocol_version, &new_protocol_data)
             .map_err(Into::into)?;
 
+        // URefs created by the upgrade installer and for new auction named keys must not collide.
+        let uref_address_generator = {
+            let generator = AddressGenerator::new(pre_state_hash.as_ref(), Phase::System);
+            Rc::new(RefCell::new(generator))
+        };
+
         // 3.1.1.1.1.5 upgrade installer is optional except on major version upgrades
         match upgrade_config.upgrade_installer_bytes() {
             None if upgrade_check_result.is_code_required() => {
@@ -636,7 +698,10 @@ where
 
                 // preprocess installer module
                 let upgrade_installer_module = {
-                    let preprocessor = Preprocessor::new(*new_wasm_config);
+                    let preprocessor = Preprocessor::new(PreprocessingConfig::new(
+                        self.config.apply_wasm_limits(*new_wasm_config),
+                        self.config.memory_limit_pages(),
+                    ));
                     preprocessor.preprocess(bytes)?
                 };
 
@@ -684,10 +749,7 @@ where
                     let generator = AddressGenerator::new(pre_state_hash.as_ref(), phase);
                     Rc::new(RefCell::new(generator))
                 };
-                let uref_address_generator = {
-                    let generator = AddressGenerator::new(pre_state_hash.as_ref(), phase);
-                    Rc::new(RefCell::new(generator))
-                };
+                let uref_address_generator = Rc::clone(&uref_address_generator);
                 let tracking_copy = Rc::clone(&tracking_copy);
                 let system_contract_cache = SystemContractCache::clone(&self.system_contract_cache);
 
@@ -737,6 +799,108 @@ where
             tracking_copy.borrow_mut().write(validator_slots_key, value);
         }
 
+        // Bids and unbonding purses written before their encodings were versioned are rewritten in
+        // the current format.
+        {
+            let auction_contract = tracking_copy
+                .borrow_mut()
+                .get_contract(correlation_id, new_protocol_data.auction())?;
+            migrate_auction_value(
+                &mut tracking_copy.borrow_mut(),
+                correlation_id,
+                auction_contract.named_keys(),
+                BIDS_KEY,
+                |legacy_bids: LegacyBids| -> Bids {
+                    legacy_bids
+                        .into_iter()
+                        .map(|(public_key, legacy_bid)| (public_key, legacy_bid.into()))
+                        .collect()
+                },
+            )?;
+            migrate_auction_value(
+                &mut tracking_copy.borrow_mut(),
+                correlation_id,
+                auction_contract.named_keys(),
+                UNBONDING_PURSES_KEY,
+                |legacy_unbonding_purses: LegacyUnbondingPurses| -> UnbondingPurses {
+                    legacy_unbonding_purses
+                        .into_iter()
+                        .map(|(public_key, legacy_list)| {
+                            let list = legacy_list.into_iter().map(Into::into).collect();
+                            (public_key, list)
+                        })
+                        .collect()
+                },
+            )?;
+        }
+
+        // Auction parameter changes and new auction named keys are optional
+        let auction_upgrade_config = upgrade_config.auction_upgrade_config();
+        let mut auction_changes = AuctionChanges::default();
+        if !auction_upgrade_config.is_empty() {
+            let auction_hash = new_protocol_data.auction();
+            let auction_contract = tracking_copy
+                .borrow_mut()
+                .get_contract(correlation_id, auction_hash)?;
+            let contract_package_hash = auction_contract.contract_package_hash();
+            let contract_wasm_hash = auction_contract.contract_wasm_hash();
+            let entry_points = auction_contract.entry_points().clone();
+            let mut named_keys = auction_contract.take_named_keys();
+            let new_uref = |value: CLValue| {
+                let address = uref_address_generator.borrow_mut().create_address();
+                let uref = URef::new(address, AccessRights::READ_ADD_WRITE);
+                tracking_copy
+                    .borrow_mut()
+                    .write(Key::URef(uref), StoredValue::CLValue(value));
+                Key::URef(uref)
+            };
+
+            let mut era_count_changes = vec![];
+            if let Some(new_unbonding_delay) = auction_upgrade_config.new_unbonding_delay() {
+                era_count_changes.push((UNBONDING_DELAY_KEY, new_unbonding_delay));
+                auction_changes.unbonding_delay = Some(new_unbonding_delay);
+            }
+            if let Some(new_locked_funds_period) = auction_upgrade_config.new_locked_funds_period()
+            {
+                era_count_changes.push((LOCKED_FUNDS_PERIOD_KEY, new_locked_funds_period));
+                auction_changes.locked_funds_period = Some(new_locked_funds_period);
+            }
+            for (name, era_count) in era_count_changes {
+                let value =
+                    CLValue::from_t(era_count).map_err(|_| Error::Bytesrepr(name.to_string()))?;
+                match named_keys.get(name).copied() {
+                    Some(key) => tracking_copy
+                        .borrow_mut()
+                        .write(key, StoredValue::CLValue(value)),
+                    None => {
+                        let _ = named_keys.insert(name.to_string(), new_uref(value));
+                    }
+                }
+            }
+
+            for (name, value) in auction_upgrade_config.new_named_keys() {
+                if named_keys.contains_key(name) {
+                    continue;
+                }
+                let _ = named_keys.insert(name.clone(), new_uref(value.clone()));
+                auction_changes.initialized_named_keys.push(name.clone());
+            }
+
+            // The auction contract is rewritten under the new protocol version, as its named
+            // keys may have changed.
+            let auction_contract = Contract::new(
+                contract_package_hash,
+                contract_wasm_hash,
+                named_keys,
+                entry_points,
+                new_protocol_version,
+            );
+            tracking_copy.borrow_mut().write(
+                Key::Hash(auction_hash),
+                StoredValue::Contract(auction_contract),
+            );
+        }
+
         let effects = tracking_copy.borrow().effect();
 
         // commit
@@ -750,7 +914,11 @@ where
             .map_err(Into::into)?;
 
         // return result and effects
-        Ok(UpgradeResult::from_commit_result(commit_result, effects))
+        Ok(UpgradeResult::from_commit_result(
+            commit_result,
+            effects,
+            auction_changes,
+        ))
     }
 
     pub fn tracking_copy(
@@ -768,6 +936,15 @@ where
         correlation_id: CorrelationId,
         query_request: QueryRequest,
     ) -> Result<QueryResult, Error> {
+        let path_length = query_request.path().len();
+        let max_path_length = self.config.max_query_path_length();
+        if path_length > max_path_length {
+            return Ok(QueryResult::PathTooLong {
+                path_length,
+                max_path_length,
+            });
+        }
+
         let tracking_copy = match self.tracking_copy(query_request.state_hash())? {
             Some(tracking_copy) => Rc::new(RefCell::new(tracking_copy)),
             None => return Ok(QueryResult::RootNotFound),
@@ -792,7 +969,10 @@ where
             .unwrap()
             .unwrap();
         let executor = Executor::new(self.config);
-        let preprocessor = Preprocessor::new(wasm_config);
+        let preprocessor = Preprocessor::new(PreprocessingConfig::new(
+            self.config.apply_wasm_limits(wasm_config),
+            self.config.memory_limit_pages(),
+        ));
 
         let deploys = exec_request.take_deploys();
         let mut results = ExecutionResults::with_capacity(deploys.len());
@@ -832,6 +1012,21 @@ where
         Ok(results)
     }
 
+    /// Executes the deploys of `exec_request` as `run_execute` does, but only to estimate their
+    /// cost and outcome.
+    ///
+    /// Execution reads global state through a tracking copy, an in-memory overlay of the parent
+    /// state which is discarded afterwards, so the global state is left unchanged as long as the
+    /// returned effects aren't committed.
+    pub fn dry_run_deploy(
+        &self,
+        correlation_id: CorrelationId,
+        exec_request: ExecuteRequest,
+    ) -> Result<DryRunResult, Error> {
+        let execution_results = self.run_execute(correlation_id, exec_request)?;
+        Ok(DryRunResult::new(execution_results))
+    }
+
     pub fn get_module(
         &self,
         tracking_copy: Rc<RefCell<TrackingCopy<<S as StateProvider>::Reader>>>,
@@ -841,9 +1036,15 @@ where
         preprocessor: &Preprocessor,
         protocol_version: &ProtocolVersion,
     ) -> Result<GetModuleResult, Error> {
+        self.check_deploy_item_size(deploy_item)?;
+
         let (contract_package, contract, base_key) = match deploy_item {
             ExecutableDeployItem::ModuleBytes { module_bytes, .. } => {
-                let module = preprocessor.preprocess(&module_bytes)?;
+                let module = self.wasm_preprocessing_cache.get_or_preprocess(
+                    *protocol_version,
+                    &module_bytes,
+                    || preprocessor.preprocess(&module_bytes),
+                )?;
                 return Ok(GetModuleResult::Session {
                     module,
                     contract_package: ContractPackage::default(),
@@ -950,6 +1151,31 @@ where
         }
     }
 
+    /// Checks the module bytes and serialized args of `deploy_item` against the configured size
+    /// limits so that oversized deploys are rejected before any wasm preprocessing.
+    fn check_deploy_item_size(&self, deploy_item: &ExecutableDeployItem) -> Result<(), Error> {
+        if let ExecutableDeployItem::ModuleBytes { module_bytes, .. } = deploy_item {
+            let max = self.config.max_deploy_size_bytes();
+            if module_bytes.len() > max {
+                return Err(Error::DeployTooLarge {
+                    actual: module_bytes.len(),
+                    max,
+                });
+            }
+        }
+
+        let args_length = deploy_item.args().len();
+        let max = self.config.max_deploy_args_size_bytes();
+        if args_length > max {
+            return Err(Error::DeployArgsTooLarge {
+                actual: args_length,
+                max,
+            });
+        }
+
+        Ok(())
+    }
+
     fn get_module_from_contract_hash(
         &self,
         tracking_copy: Rc<RefCell<TrackingCopy<<S as StateProvider>::Reader>>>,
@@ -1078,10 +1304,12 @@ where
             Err(e) => return Ok(ExecutionResult::precondition_failure(e)),
         };
 
-        let mint_contract = match tracking_copy
-            .borrow_mut()
-            .get_contract(correlation_id, protocol_data.mint())
-        {
+        let mint_contract = match self.get_system_contract(
+            correlation_id,
+            &tracking_copy,
+            prestate_hash,
+            protocol_data.mint(),
+        ) {
             Ok(contract) => contract,
             Err(error) => {
                 return Ok(ExecutionResult::precondition_failure(error.into()));
@@ -1289,10 +1517,12 @@ where
         // payment_code_spec_6: system contract validity
         let mint_hash = protocol_data.mint();
 
-        let mint_contract = match tracking_copy
-            .borrow_mut()
-            .get_contract(correlation_id, mint_hash)
-        {
+        let mint_contract = match self.get_system_contract(
+            correlation_id,
+            &tracking_copy,
+            prestate_hash,
+            mint_hash,
+        ) {
             Ok(contract) => contract,
             Err(error) => {
                 return Ok(ExecutionResult::precondition_failure(error.into()));
@@ -1323,10 +1553,12 @@ where
 
         // Get proof of stake system contract details
         // payment_code_spec_6: system contract validity
-        let proof_of_stake_contract = match tracking_copy
-            .borrow_mut()
-            .get_contract(correlation_id, proof_of_stake_hash)
-        {
+        let proof_of_stake_contract = match self.get_system_contract(
+            correlation_id,
+            &tracking_copy,
+            prestate_hash,
+            proof_of_stake_hash,
+        ) {
             Ok(contract) => contract,
             Err(error) => {
                 return Ok(ExecutionResult::precondition_failure(error.into()));
@@ -1566,6 +1798,7 @@ where
                     Ok(()) => ExecutionResult::Success {
                         effect: runtime.context().effect(),
                         cost: runtime.context().gas_counter(),
+                        execution_trace: runtime.context().recorded_execution_trace(),
                     },
                     Err(error) => ExecutionResult::Failure {
                         error: error.into(),
@@ -1818,12 +2051,60 @@ where
     where
         Error: From<S::Error>,
     {
-        match self.state.commit(correlation_id, pre_state_hash, effects)? {
+        match self.commit_effects(correlation_id, pre_state_hash, effects)? {
             CommitResult::Success { state_root, .. } => Ok(CommitResult::Success { state_root }),
             commit_result => Ok(commit_result),
         }
     }
 
+    /// Returns the system contract under `contract_hash` as of `state_hash`, consulting the system
+    /// contract metadata cache before `tracking_copy` if the cache is enabled.
+    fn get_system_contract(
+        &self,
+        correlation_id: CorrelationId,
+        tracking_copy: &RefCell<TrackingCopy<S::Reader>>,
+        state_hash: Blake2bHash,
+        contract_hash: ContractHash,
+    ) -> Result<Contract, execution::Error> {
+        if !self.config.enable_system_contract_metadata_cache() {
+            return tracking_copy
+                .borrow_mut()
+                .get_contract(correlation_id, contract_hash);
+        }
+        if let Some(contract) = self
+            .system_contract_metadata_cache
+            .get(state_hash, contract_hash)
+        {
+            return Ok(contract);
+        }
+        let contract = tracking_copy
+            .borrow_mut()
+            .get_contract(correlation_id, contract_hash)?;
+        self.system_contract_metadata_cache
+            .insert(state_hash, contract_hash, contract.clone());
+        Ok(contract)
+    }
+
+    /// Commits `effects` on top of `pre_state_hash`, carrying any cached system contracts which
+    /// `effects` leave untouched forward to the new state root.
+    fn commit_effects(
+        &self,
+        correlation_id: CorrelationId,
+        pre_state_hash: Blake2bHash,
+        effects: AdditiveMap<Key, Transform>,
+    ) -> Result<CommitResult, S::Error> {
+        let written_contracts = system_contract_metadata_cache::written_contracts(&effects);
+        let commit_result = self.state.commit(correlation_id, pre_state_hash, effects)?;
+        if let CommitResult::Success { state_root } = commit_result {
+            self.system_contract_metadata_cache.carry_forward(
+                pre_state_hash,
+                state_root,
+                &written_contracts,
+            );
+        }
+        Ok(commit_result)
+    }
+
     /// Obtains validator weights for given era.
     pub fn get_era_validators(
         &self,
@@ -1844,11 +2125,18 @@ where
 
         let wasm_config = protocol_data.wasm_config();
 
-        let preprocessor = Preprocessor::new(*wasm_config);
+        let preprocessor = Preprocessor::new(PreprocessingConfig::new(
+            self.config.apply_wasm_limits(*wasm_config),
+            self.config.memory_limit_pages(),
+        ));
 
-        let auction_contract: Contract = tracking_copy
-            .borrow_mut()
-            .get_contract(correlation_id, protocol_data.auction())
+        let auction_contract: Contract = self
+            .get_system_contract(
+                correlation_id,
+                &tracking_copy,
+                get_era_validators_request.state_hash(),
+                protocol_data.auction(),
+            )
             .map_err(Error::from)?;
 
         let auction_module = {
@@ -1922,6 +2210,67 @@ where
         Ok(era_validators.flatten())
     }
 
+    /// Returns the validator weights of each era in the requested range which has them.
+    ///
+    /// Rather than executing the auction contract once per era, its record of era validators is
+    /// read from global state once.
+    pub fn get_era_validators_range(
+        &self,
+        correlation_id: CorrelationId,
+        request: GetEraValidatorsRangeRequest,
+    ) -> Result<EraValidators, GetEraValidatorsError> {
+        let protocol_version = request.protocol_version();
+
+        let tracking_copy = match self.tracking_copy(request.state_hash())? {
+            Some(tracking_copy) => RefCell::new(tracking_copy),
+            None => return Err(GetEraValidatorsError::RootNotFound),
+        };
+
+        let protocol_data = match self.get_protocol_data(protocol_version)? {
+            Some(protocol_data) => protocol_data,
+            None => return Err(Error::InvalidProtocolVersion(protocol_version).into()),
+        };
+
+        let auction_contract: Contract = self
+            .get_system_contract(
+                correlation_id,
+                &tracking_copy,
+                request.state_hash(),
+                protocol_data.auction(),
+            )
+            .map_err(Error::from)?;
+
+        let era_validators_key = match auction_contract.named_keys().get(ERA_VALIDATORS_KEY) {
+            Some(key) => *key,
+            None => {
+                let error = execution::Error::NamedKeyNotFound(ERA_VALIDATORS_KEY.to_string());
+                return Err(Error::from(error).into());
+            }
+        };
+
+        let stored_value = match tracking_copy
+            .borrow_mut()
+            .read(correlation_id, &era_validators_key)
+        {
+            Ok(Some(stored_value)) => stored_value,
+            Ok(None) => {
+                let error = execution::Error::KeyNotFound(era_validators_key);
+                return Err(Error::from(error).into());
+            }
+            Err(error) => return Err(Error::Exec(error.into()).into()),
+        };
+
+        let era_validators: EraValidators = CLValue::try_from(stored_value)
+            .map_err(execution::Error::TypeMismatch)
+            .and_then(|cl_value| cl_value.into_t().map_err(execution::Error::from))
+            .map_err(Error::from)?;
+
+        Ok(era_validators
+            .range(request.era_ids())
+            .map(|(era_id, validator_weights)| (*era_id, validator_weights.clone()))
+            .collect())
+    }
+
     pub fn commit_step(
         &self,
         correlation_id: CorrelationId,
@@ -1950,15 +2299,20 @@ where
                 .wasm_config(step_request.protocol_version)
                 .unwrap()
                 .unwrap();
-            Preprocessor::new(wasm_config)
+            Preprocessor::new(PreprocessingConfig::new(
+                self.config.apply_wasm_limits(wasm_config),
+                self.config.memory_limit_pages(),
+            ))
         };
 
         let auction_hash = protocol_data.auction();
 
-        let auction_contract = match tracking_copy
-            .borrow_mut()
-            .get_contract(correlation_id, auction_hash)
-        {
+        let auction_contract = match self.get_system_contract(
+            correlation_id,
+            &tracking_copy,
+            step_request.pre_state_hash,
+            auction_hash,
+        ) {
             Ok(contract) => contract,
             Err(_) => {
                 return Ok(StepResult::PreconditionError);
@@ -2079,7 +2433,10 @@ where
             }
         };
 
-        let reward_args = runtime_args! {ARG_REWARD_FACTORS => reward_factors};
+        let reward_args = runtime_args! {
+            ARG_REWARD_FACTORS => reward_factors,
+            ARG_BLOCKS_PROPOSED => step_request.blocks_proposed(),
+        };
 
         let (_, execution_result): (Option<()>, ExecutionResult) = executor.exec_system_contract(
             DirectSystemContractCall::DistributeRewards,
@@ -2109,8 +2466,7 @@ where
 
         // commit
         let commit_result = self
-            .state
-            .commit(
+            .commit_effects(
                 correlation_id,
                 step_request.pre_state_hash,
                 effects.transforms,
@@ -2132,3 +2488,99 @@ where
         }
     }
 }
+
+/// Rewrites the value stored under the auction's named key `name` from its legacy encoding `L` to
+/// its current encoding `T`.  Values which already decode as `T` are left untouched.
+fn migrate_auction_value<R, T, L>(
+    tracking_copy: &mut TrackingCopy<R>,
+    correlation_id: CorrelationId,
+    named_keys: &NamedKeys,
+    name: &str,
+    convert: impl FnOnce(L) -> T,
+) -> Result<(), Error>
+where
+    R: StateReader<Key, StoredValue>,
+    R::Error: Into<execution::Error>,
+    T: CLTyped + ToBytes + FromBytes,
+    L: CLTyped + FromBytes,
+{
+    let key = match named_keys.get(name) {
+        Some(key) => *key,
+        None => return Ok(()),
+    };
+    let cl_value = match tracking_copy.read(correlation_id, &key) {
+        Ok(Some(StoredValue::CLValue(cl_value))) => cl_value,
+        Ok(_) => return Err(Error::Bytesrepr(name.to_string())),
+        Err(error) => return Err(Error::Exec(error.into())),
+    };
+    if cl_value.clone().into_t::<T>().is_ok() {
+        return Ok(());
+    }
+    let legacy_value: L = cl_value
+        .into_t()
+        .map_err(|_| Error::Bytesrepr(name.to_string()))?;
+    let value =
+        CLValue::from_t(convert(legacy_value)).map_err(|_| Error::Bytesrepr(name.to_string()))?;
+    tracking_copy.write(key, StoredValue::CLValue(value));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+    use crate::storage::global_state::in_memory::InMemoryGlobalState;
+
+    const MAX_QUERY_PATH_LENGTH: usize = 2;
+
+    fn engine_state_with_value() -> (EngineState<InMemoryGlobalState>, Blake2bHash, Key) {
+        let key = Key::Hash([1; 32]);
+        let value = StoredValue::CLValue(CLValue::from_t(7_i32).unwrap());
+        let (global_state, root_hash) =
+            InMemoryGlobalState::from_pairs(CorrelationId::new(), &[(key, value)]).unwrap();
+        let engine_config = EngineConfig::new().with_max_query_path_length(MAX_QUERY_PATH_LENGTH);
+        (
+            EngineState::new(global_state, engine_config),
+            root_hash,
+            key,
+        )
+    }
+
+    fn path(length: usize) -> Vec<String> {
+        (0..length).map(|index| index.to_string()).collect()
+    }
+
+    #[test]
+    fn query_with_path_too_long_should_fail() {
+        let (engine_state, root_hash, key) = engine_state_with_value();
+        let query_request = QueryRequest::new(root_hash, key, path(MAX_QUERY_PATH_LENGTH + 1));
+        assert_matches!(
+            engine_state.run_query(CorrelationId::new(), query_request),
+            Ok(QueryResult::PathTooLong {
+                path_length,
+                max_path_length: MAX_QUERY_PATH_LENGTH,
+            }) if path_length == MAX_QUERY_PATH_LENGTH + 1
+        );
+    }
+
+    #[test]
+    fn query_with_path_at_limit_should_be_run() {
+        let (engine_state, root_hash, key) = engine_state_with_value();
+        let query_request = QueryRequest::new(root_hash, key, path(MAX_QUERY_PATH_LENGTH));
+        assert_matches!(
+            engine_state.run_query(CorrelationId::new(), query_request),
+            Ok(QueryResult::TypeMismatch(_))
+        );
+    }
+
+    #[test]
+    fn query_for_missing_key_should_fail_with_value_not_found() {
+        let (engine_state, root_hash, _) = engine_state_with_value();
+        let query_request = QueryRequest::new(root_hash, Key::Hash([2; 32]), vec![]);
+        assert_matches!(
+            engine_state.run_query(CorrelationId::new(), query_request),
+            Ok(QueryResult::ValueNotFound(_))
+        );
+    }
+}
@@ -8,8 +8,16 @@ use crate::{
 #[derive(Debug)]
 pub enum QueryResult {
     RootNotFound,
+    /// No value exists under the base key, or under a name in the query path.
     ValueNotFound(String),
+    /// A value found while following the query path cannot be traversed any further.
+    TypeMismatch(String),
     CircularReference(String),
+    /// The query path has more components than the engine permits.
+    PathTooLong {
+        path_length: usize,
+        max_path_length: usize,
+    },
     Success(StoredValue),
 }
 
@@ -46,6 +54,7 @@ impl From<TrackingCopyQueryResult> for QueryResult {
     fn from(tracking_copy_query_result: TrackingCopyQueryResult) -> Self {
         match tracking_copy_query_result {
             TrackingCopyQueryResult::ValueNotFound(message) => QueryResult::ValueNotFound(message),
+            TrackingCopyQueryResult::TypeMismatch(message) => QueryResult::TypeMismatch(message),
             TrackingCopyQueryResult::CircularReference(message) => {
                 QueryResult::CircularReference(message)
             }
@@ -3,7 +3,9 @@ use std::{collections::BTreeMap, fmt::Display, vec::Vec};
 use core::fmt;
 use uint::static_assertions::_core::fmt::Formatter;
 
-use casper_types::{bytesrepr, bytesrepr::ToBytes, Key, ProtocolVersion, PublicKey};
+use casper_types::{
+    auction::BlocksProposed, bytesrepr, bytesrepr::ToBytes, Key, ProtocolVersion, PublicKey,
+};
 
 use crate::shared::{newtypes::Blake2bHash, TypeMismatch};
 
@@ -22,13 +24,16 @@ impl SlashItem {
 pub struct RewardItem {
     pub validator_id: PublicKey,
     pub value: u64,
+    /// Number of blocks proposed by the validator in the era.
+    pub blocks_proposed: u64,
 }
 
 impl RewardItem {
-    pub fn new(validator_id: PublicKey, value: u64) -> Self {
+    pub fn new(validator_id: PublicKey, value: u64, blocks_proposed: u64) -> Self {
         Self {
             validator_id,
             value,
+            blocks_proposed,
         }
     }
 }
@@ -77,6 +82,13 @@ impl StepRequest {
         }
         Ok(ret)
     }
+
+    pub fn blocks_proposed(&self) -> BlocksProposed {
+        self.reward_items
+            .iter()
+            .map(|reward_item| (reward_item.validator_id, reward_item.blocks_proposed))
+            .collect()
+    }
 }
 
 #[derive(Debug)]
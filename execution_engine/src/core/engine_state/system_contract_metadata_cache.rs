@@ -0,0 +1,249 @@
+use std::{
+    collections::{BTreeSet, HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+use casper_types::{Contract, ContractHash, Key};
+
+use crate::shared::{additive_map::AdditiveMap, newtypes::Blake2bHash, transform::Transform};
+
+/// Default maximum number of contracts held by a `SystemContractMetadataCache`.
+pub const DEFAULT_SYSTEM_CONTRACT_METADATA_CACHE_CAPACITY: usize = 64;
+
+#[derive(Debug, Default)]
+struct Entries {
+    contracts: HashMap<(Blake2bHash, ContractHash), Contract>,
+    /// The keys of `contracts`, oldest first.
+    insertion_order: VecDeque<(Blake2bHash, ContractHash)>,
+}
+
+/// A bounded cache of system contracts' metadata, i.e. their entry points and named keys, keyed by
+/// state root hash and contract hash.
+///
+/// Since a state root hash identifies the entire global state, an entry never becomes stale.  To
+/// be of use across a block, where each deploy is committed on top of the previous one, entries are
+/// carried forward to the post state of a commit which leaves the cached contract untouched.
+#[derive(Debug)]
+pub struct SystemContractMetadataCache {
+    capacity: usize,
+    entries: Mutex<Entries>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl SystemContractMetadataCache {
+    /// Creates an empty cache holding at most `capacity` contracts.
+    pub fn new(capacity: usize) -> Self {
+        SystemContractMetadataCache {
+            capacity,
+            entries: Mutex::new(Entries::default()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns a clone of the contract under `contract_hash` as of `state_hash`, if cached.
+    pub fn get(&self, state_hash: Blake2bHash, contract_hash: ContractHash) -> Option<Contract> {
+        let entries = self.entries.lock().unwrap();
+        let maybe_contract = entries.contracts.get(&(state_hash, contract_hash)).cloned();
+        let counter = if maybe_contract.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        maybe_contract
+    }
+
+    /// Caches `contract` as the contract under `contract_hash` as of `state_hash`, evicting the
+    /// oldest entry if the cache is full.
+    pub fn insert(&self, state_hash: Blake2bHash, contract_hash: ContractHash, contract: Contract) {
+        let mut entries = self.entries.lock().unwrap();
+        self.insert_locked(&mut entries, (state_hash, contract_hash), contract);
+    }
+
+    /// Carries the contracts cached as of `pre_state_hash` forward to `post_state_hash`, except
+    /// for those in `written_contracts`.
+    pub fn carry_forward(
+        &self,
+        pre_state_hash: Blake2bHash,
+        post_state_hash: Blake2bHash,
+        written_contracts: &BTreeSet<ContractHash>,
+    ) {
+        let mut entries = self.entries.lock().unwrap();
+        let unchanged: Vec<(ContractHash, Contract)> = entries
+            .contracts
+            .iter()
+            .filter(|((state_hash, contract_hash), _)| {
+                *state_hash == pre_state_hash && !written_contracts.contains(contract_hash)
+            })
+            .map(|((_, contract_hash), contract)| (*contract_hash, contract.clone()))
+            .collect();
+        for (contract_hash, contract) in unchanged {
+            self.insert_locked(&mut entries, (post_state_hash, contract_hash), contract);
+        }
+    }
+
+    /// Removes all entries.
+    pub fn flush(&self) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.contracts.clear();
+        entries.insertion_order.clear();
+    }
+
+    /// Returns the number of cached contracts.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().contracts.len()
+    }
+
+    /// Returns `true` if no contracts are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the contract under `contract_hash` as of `state_hash` is cached.
+    pub fn contains(&self, state_hash: Blake2bHash, contract_hash: ContractHash) -> bool {
+        self.entries
+            .lock()
+            .unwrap()
+            .contracts
+            .contains_key(&(state_hash, contract_hash))
+    }
+
+    /// Returns the number of lookups which found the requested contract.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of lookups which didn't find the requested contract.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    fn insert_locked(
+        &self,
+        entries: &mut Entries,
+        key: (Blake2bHash, ContractHash),
+        contract: Contract,
+    ) {
+        if entries.contracts.insert(key, contract).is_some() {
+            return;
+        }
+        entries.insertion_order.push_back(key);
+        while entries.insertion_order.len() > self.capacity {
+            if let Some(oldest) = entries.insertion_order.pop_front() {
+                let _ = entries.contracts.remove(&oldest);
+            }
+        }
+    }
+}
+
+impl Default for SystemContractMetadataCache {
+    fn default() -> Self {
+        SystemContractMetadataCache::new(DEFAULT_SYSTEM_CONTRACT_METADATA_CACHE_CAPACITY)
+    }
+}
+
+/// Returns the hashes of all contracts written or otherwise modified by `effects`.
+pub fn written_contracts(effects: &AdditiveMap<Key, Transform>) -> BTreeSet<ContractHash> {
+    effects
+        .iter()
+        .filter_map(|(key, transform)| match (key, transform) {
+            (_, Transform::Identity) => None,
+            (Key::Hash(contract_hash), _) => Some(*contract_hash),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::{
+        contracts::{ContractPackageHash, ContractWasmHash, NamedKeys},
+        EntryPoints, ProtocolVersion,
+    };
+
+    use super::*;
+    use crate::shared::stored_value::StoredValue;
+
+    const MINT_HASH: ContractHash = [1; 32];
+    const AUCTION_HASH: ContractHash = [2; 32];
+
+    fn state_hash(byte: u8) -> Blake2bHash {
+        Blake2bHash::new(&[byte])
+    }
+
+    fn contract(named_key_count: u8) -> Contract {
+        let mut named_keys = NamedKeys::new();
+        for i in 0..named_key_count {
+            named_keys.insert(i.to_string(), Key::Hash([i; 32]));
+        }
+        Contract::new(
+            ContractPackageHash::default(),
+            ContractWasmHash::default(),
+            named_keys,
+            EntryPoints::default(),
+            ProtocolVersion::V1_0_0,
+        )
+    }
+
+    #[test]
+    fn should_count_hits_and_misses() {
+        let cache = SystemContractMetadataCache::default();
+        assert_eq!(cache.get(state_hash(0), MINT_HASH), None);
+
+        cache.insert(state_hash(0), MINT_HASH, contract(1));
+        assert_eq!(cache.get(state_hash(0), MINT_HASH), Some(contract(1)));
+        assert_eq!(cache.get(state_hash(1), MINT_HASH), None);
+
+        assert_eq!(cache.hits(), 1);
+        assert_eq!(cache.misses(), 2);
+    }
+
+    #[test]
+    fn should_evict_oldest_entries_once_full() {
+        let cache = SystemContractMetadataCache::new(2);
+        cache.insert(state_hash(0), MINT_HASH, contract(0));
+        cache.insert(state_hash(1), MINT_HASH, contract(1));
+        cache.insert(state_hash(2), MINT_HASH, contract(2));
+
+        assert_eq!(cache.len(), 2);
+        assert!(!cache.contains(state_hash(0), MINT_HASH));
+        assert!(cache.contains(state_hash(1), MINT_HASH));
+        assert!(cache.contains(state_hash(2), MINT_HASH));
+    }
+
+    #[test]
+    fn should_only_carry_forward_unwritten_contracts() {
+        let cache = SystemContractMetadataCache::default();
+        cache.insert(state_hash(0), MINT_HASH, contract(0));
+        cache.insert(state_hash(0), AUCTION_HASH, contract(1));
+
+        let mut effects = AdditiveMap::new();
+        effects.insert(Key::Hash(MINT_HASH), Transform::Identity);
+        effects.insert(
+            Key::Hash(AUCTION_HASH),
+            Transform::Write(StoredValue::Contract(contract(2))),
+        );
+        let written = written_contracts(&effects);
+        assert_eq!(written.into_iter().collect::<Vec<_>>(), vec![AUCTION_HASH]);
+
+        cache.carry_forward(state_hash(0), state_hash(1), &written_contracts(&effects));
+        assert_eq!(cache.get(state_hash(1), MINT_HASH), Some(contract(0)));
+        assert!(!cache.contains(state_hash(1), AUCTION_HASH));
+        // Entries for the pre state remain valid.
+        assert!(cache.contains(state_hash(0), AUCTION_HASH));
+    }
+
+    #[test]
+    fn should_flush() {
+        let cache = SystemContractMetadataCache::default();
+        cache.insert(state_hash(0), MINT_HASH, contract(0));
+        cache.flush();
+        assert!(cache.is_empty());
+        assert_eq!(cache.get(state_hash(0), MINT_HASH), None);
+    }
+}
@@ -1,6 +1,6 @@
-use std::fmt;
+use std::{collections::BTreeMap, fmt};
 
-use casper_types::{bytesrepr, Key, ProtocolVersion};
+use casper_types::{bytesrepr, CLValue, Key, ProtocolVersion};
 
 use crate::{
     core::engine_state::execution_effect::ExecutionEffect,
@@ -19,6 +19,7 @@ pub enum UpgradeResult {
     Success {
         post_state_hash: Blake2bHash,
         effect: ExecutionEffect,
+        auction_changes: AuctionChanges,
     },
 }
 
@@ -34,13 +35,22 @@ impl fmt::Display for UpgradeResult {
             UpgradeResult::Success {
                 post_state_hash,
                 effect,
-            } => write!(f, "Success: {} {:?}", post_state_hash, effect),
+                auction_changes,
+            } => write!(
+                f,
+                "Success: {} {:?} {:?}",
+                post_state_hash, effect, auction_changes
+            ),
         }
     }
 }
 
 impl UpgradeResult {
-    pub fn from_commit_result(commit_result: CommitResult, effect: ExecutionEffect) -> Self {
+    pub fn from_commit_result(
+        commit_result: CommitResult,
+        effect: ExecutionEffect,
+        auction_changes: AuctionChanges,
+    ) -> Self {
         match commit_result {
             CommitResult::RootNotFound => UpgradeResult::RootNotFound,
             CommitResult::KeyNotFound(key) => UpgradeResult::KeyNotFound(key),
@@ -49,11 +59,67 @@ impl UpgradeResult {
             CommitResult::Success { state_root, .. } => UpgradeResult::Success {
                 post_state_hash: state_root,
                 effect,
+                auction_changes,
             },
         }
     }
 }
 
+/// The changes an upgrade applied to the auction contract.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuctionChanges {
+    /// The new unbonding delay, if it was changed.
+    pub unbonding_delay: Option<u64>,
+    /// The new locked funds period, if it was changed.
+    pub locked_funds_period: Option<u64>,
+    /// The names of the named keys which were missing and got initialized.
+    pub initialized_named_keys: Vec<String>,
+}
+
+/// Changes to the parameters and named keys of the auction contract to apply during an upgrade.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuctionUpgradeConfig {
+    new_unbonding_delay: Option<u64>,
+    new_locked_funds_period: Option<u64>,
+    new_named_keys: BTreeMap<String, CLValue>,
+}
+
+impl AuctionUpgradeConfig {
+    /// Creates a new config. Each entry of `new_named_keys` is added to the auction contract's
+    /// named keys under a new URef holding the given value, unless a named key of that name
+    /// already exists.
+    pub fn new(
+        new_unbonding_delay: Option<u64>,
+        new_locked_funds_period: Option<u64>,
+        new_named_keys: BTreeMap<String, CLValue>,
+    ) -> Self {
+        AuctionUpgradeConfig {
+            new_unbonding_delay,
+            new_locked_funds_period,
+            new_named_keys,
+        }
+    }
+
+    pub fn new_unbonding_delay(&self) -> Option<u64> {
+        self.new_unbonding_delay
+    }
+
+    pub fn new_locked_funds_period(&self) -> Option<u64> {
+        self.new_locked_funds_period
+    }
+
+    pub fn new_named_keys(&self) -> &BTreeMap<String, CLValue> {
+        &self.new_named_keys
+    }
+
+    /// Returns `true` if no changes are requested.
+    pub fn is_empty(&self) -> bool {
+        self.new_unbonding_delay.is_none()
+            && self.new_locked_funds_period.is_none()
+            && self.new_named_keys.is_empty()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct UpgradeConfig {
     pre_state_hash: Blake2bHash,
@@ -64,6 +130,7 @@ pub struct UpgradeConfig {
     wasm_config: Option<WasmConfig>,
     activation_point: Option<ActivationPoint>,
     new_validator_slots: Option<u32>,
+    auction_upgrade_config: AuctionUpgradeConfig,
 }
 
 impl UpgradeConfig {
@@ -77,6 +144,7 @@ impl UpgradeConfig {
         wasm_config: Option<WasmConfig>,
         activation_point: Option<ActivationPoint>,
         new_validator_slots: Option<u32>,
+        auction_upgrade_config: AuctionUpgradeConfig,
     ) -> Self {
         UpgradeConfig {
             pre_state_hash,
@@ -87,6 +155,7 @@ impl UpgradeConfig {
             wasm_config,
             activation_point,
             new_validator_slots,
+            auction_upgrade_config,
         }
     }
 
@@ -123,4 +192,8 @@ impl UpgradeConfig {
     pub fn new_validator_slots(&self) -> Option<u32> {
         self.new_validator_slots
     }
+
+    pub fn auction_upgrade_config(&self) -> &AuctionUpgradeConfig {
+        &self.auction_upgrade_config
+    }
 }
@@ -0,0 +1,228 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+use parity_wasm::elements::Module;
+
+use casper_types::ProtocolVersion;
+
+use crate::shared::newtypes::Blake2bHash;
+
+/// Default maximum number of modules held by a `WasmPreprocessingCache`.
+pub const DEFAULT_WASM_PREPROCESSING_CACHE_SIZE: usize = 256;
+
+/// The protocol version a module was preprocessed for, and the hash of its original bytes.
+type Key = (ProtocolVersion, Blake2bHash);
+
+#[derive(Debug, Default)]
+struct Entries {
+    modules: HashMap<Key, Module>,
+    /// The keys of `modules`, least recently used first.
+    usage_order: VecDeque<Key>,
+}
+
+/// A bounded cache of preprocessed wasm modules, keyed by the protocol version and the hash of the
+/// original module bytes.
+///
+/// Preprocessing depends on the wasm config of the protocol version, so a module is only reused
+/// for the version it was preprocessed for.  Once full, the least recently used module is evicted.
+#[derive(Debug)]
+pub struct WasmPreprocessingCache {
+    capacity: usize,
+    entries: Mutex<Entries>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl WasmPreprocessingCache {
+    /// Creates an empty cache holding at most `capacity` modules.  A capacity of 0 disables
+    /// caching.
+    pub fn new(capacity: usize) -> Self {
+        WasmPreprocessingCache {
+            capacity,
+            entries: Mutex::new(Entries::default()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns a clone of the preprocessed form of `module_bytes` for `protocol_version` if cached,
+    /// otherwise calls `preprocess` and caches its result if successful.
+    pub fn get_or_preprocess<E, F>(
+        &self,
+        protocol_version: ProtocolVersion,
+        module_bytes: &[u8],
+        preprocess: F,
+    ) -> Result<Module, E>
+    where
+        F: FnOnce() -> Result<Module, E>,
+    {
+        if self.capacity == 0 {
+            return preprocess();
+        }
+
+        let key = (protocol_version, Blake2bHash::new(module_bytes));
+        {
+            let mut entries = self.entries.lock().unwrap();
+            if let Some(module) = entries.modules.get(&key).cloned() {
+                if let Some(index) = entries.usage_order.iter().position(|used| *used == key) {
+                    let _ = entries.usage_order.remove(index);
+                }
+                entries.usage_order.push_back(key);
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(module);
+            }
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        // Preprocess without holding the lock, as it can be expensive.
+        let module = preprocess()?;
+        let mut entries = self.entries.lock().unwrap();
+        if entries.modules.insert(key, module.clone()).is_none() {
+            entries.usage_order.push_back(key);
+            while entries.usage_order.len() > self.capacity {
+                if let Some(least_recently_used) = entries.usage_order.pop_front() {
+                    let _ = entries.modules.remove(&least_recently_used);
+                }
+            }
+        }
+        Ok(module)
+    }
+
+    /// Returns the number of cached modules.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().modules.len()
+    }
+
+    /// Returns `true` if no modules are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of lookups which found the requested module.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of lookups which didn't find the requested module.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for WasmPreprocessingCache {
+    fn default() -> Self {
+        WasmPreprocessingCache::new(DEFAULT_WASM_PREPROCESSING_CACHE_SIZE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::shared::{
+        wasm,
+        wasm_config::WasmConfig,
+        wasm_prep::{PreprocessingError, Preprocessor},
+    };
+
+    const V1: ProtocolVersion = ProtocolVersion::V1_0_0;
+
+    /// Preprocesses `module_bytes` for `ProtocolVersion::V1_0_0` via `cache`, counting the calls to
+    /// the preprocessor.
+    fn preprocess(
+        cache: &WasmPreprocessingCache,
+        module_bytes: &[u8],
+        calls: &Cell<usize>,
+    ) -> Result<Module, PreprocessingError> {
+        cache.get_or_preprocess(ProtocolVersion::V1_0_0, module_bytes, || {
+            calls.set(calls.get() + 1);
+            Preprocessor::new(WasmConfig::default()).preprocess(module_bytes)
+        })
+    }
+
+    #[test]
+    fn should_preprocess_same_bytes_once() {
+        let cache = WasmPreprocessingCache::default();
+        let module_bytes = wasm::do_nothing_bytes();
+        let calls = Cell::new(0);
+
+        let first = preprocess(&cache, &module_bytes, &calls).unwrap();
+        let second = preprocess(&cache, &module_bytes, &calls).unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(calls.get(), 1);
+        assert_eq!(cache.hits(), 1);
+        assert_eq!(cache.misses(), 1);
+    }
+
+    #[test]
+    fn should_not_cache_errors() {
+        let cache = WasmPreprocessingCache::default();
+        let calls = Cell::new(0);
+
+        assert!(preprocess(&cache, b"not wasm", &calls).is_err());
+        assert!(preprocess(&cache, b"not wasm", &calls).is_err());
+        assert_eq!(calls.get(), 2);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn should_evict_least_recently_used_module() {
+        let cache = WasmPreprocessingCache::new(2);
+        let module = || Ok::<_, ()>(Module::default());
+        let calls = Cell::new(0);
+        let counted_module = || {
+            calls.set(calls.get() + 1);
+            module()
+        };
+
+        cache.get_or_preprocess(V1, b"a", module).unwrap();
+        cache.get_or_preprocess(V1, b"b", module).unwrap();
+        // Using "a" makes "b" the least recently used module.
+        cache.get_or_preprocess(V1, b"a", module).unwrap();
+        cache.get_or_preprocess(V1, b"c", module).unwrap();
+        assert_eq!(cache.len(), 2);
+
+        cache.get_or_preprocess(V1, b"a", counted_module).unwrap();
+        assert_eq!(calls.get(), 0);
+        cache.get_or_preprocess(V1, b"b", counted_module).unwrap();
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn should_not_cache_with_zero_capacity() {
+        let cache = WasmPreprocessingCache::new(0);
+        let module_bytes = wasm::do_nothing_bytes();
+        let calls = Cell::new(0);
+
+        preprocess(&cache, &module_bytes, &calls).unwrap();
+        preprocess(&cache, &module_bytes, &calls).unwrap();
+        assert_eq!(calls.get(), 2);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn should_preprocess_again_for_new_protocol_version() {
+        let cache = WasmPreprocessingCache::default();
+        let calls = Cell::new(0);
+        let counted_module = || {
+            calls.set(calls.get() + 1);
+            Ok::<_, ()>(Module::default())
+        };
+        let v2 = ProtocolVersion::from_parts(2, 0, 0);
+
+        cache.get_or_preprocess(V1, b"a", counted_module).unwrap();
+        cache.get_or_preprocess(v2, b"a", counted_module).unwrap();
+        assert_eq!(calls.get(), 2);
+        assert_eq!(cache.len(), 2);
+
+        cache.get_or_preprocess(V1, b"a", counted_module).unwrap();
+        assert_eq!(calls.get(), 2);
+    }
+}
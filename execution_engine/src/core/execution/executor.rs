@@ -18,7 +18,8 @@ use crate::{
     core::{
         engine_state::{
             execution_effect::ExecutionEffect, execution_result::ExecutionResult,
-            system_contract_cache::SystemContractCache, EngineConfig,
+            execution_trace::ExecutionTrace, system_contract_cache::SystemContractCache,
+            EngineConfig,
         },
         execution::{address_generator::AddressGenerator, Error},
         runtime::{
@@ -88,6 +89,22 @@ impl Executor {
         self.config
     }
 
+    /// The maximum number of pages of memory a module executed under `protocol_data` may use.
+    fn max_memory_pages(&self, protocol_data: &ProtocolData) -> u32 {
+        self.config
+            .apply_wasm_limits(*protocol_data.wasm_config())
+            .initial_memory
+    }
+
+    /// Returns a new, empty execution trace if execution tracing is enabled.
+    fn new_execution_trace(&self) -> Option<Rc<RefCell<ExecutionTrace>>> {
+        if self.config.enable_execution_tracing() {
+            Some(Rc::new(RefCell::new(ExecutionTrace::new())))
+        } else {
+            None
+        }
+    }
+
     pub fn exec<R>(
         &self,
         module: Module,
@@ -116,8 +133,12 @@ impl Executor {
         let entry_point_type = entry_point.entry_point_type();
         let entry_point_access = entry_point.access();
 
-        let (instance, memory) =
-            on_fail_charge!(instance_and_memory(module.clone(), protocol_version));
+        let max_memory_pages = self.max_memory_pages(&protocol_data);
+        let (instance, memory) = on_fail_charge!(instance_and_memory(
+            module.clone(),
+            protocol_version,
+            max_memory_pages
+        ));
 
         let access_rights = {
             let keys: Vec<Key> = named_keys.values().cloned().collect();
@@ -153,6 +174,7 @@ impl Executor {
             gas_counter,
             hash_address_generator,
             uref_address_generator,
+            self.new_execution_trace(),
             protocol_version,
             correlation_id,
             phase,
@@ -185,6 +207,7 @@ impl Executor {
                         return ExecutionResult::Success {
                             effect: runtime.context().effect(),
                             cost: runtime.context().gas_counter(),
+                            execution_trace: runtime.context().recorded_execution_trace(),
                         };
                     }
                     Err(error) => {
@@ -207,6 +230,7 @@ impl Executor {
                         return ExecutionResult::Success {
                             effect: runtime.context().effect(),
                             cost: runtime.context().gas_counter(),
+                            execution_trace: runtime.context().recorded_execution_trace(),
                         };
                     }
                     Err(error) => {
@@ -229,6 +253,7 @@ impl Executor {
                         return ExecutionResult::Success {
                             effect: runtime.context().effect(),
                             cost: runtime.context().gas_counter(),
+                            execution_trace: runtime.context().recorded_execution_trace(),
                         }
                     }
                     Err(error) => {
@@ -251,6 +276,7 @@ impl Executor {
         ExecutionResult::Success {
             effect: runtime.context().effect(),
             cost: runtime.context().gas_counter(),
+            execution_trace: runtime.context().recorded_execution_trace(),
         }
     }
 
@@ -430,7 +456,11 @@ impl Executor {
                 effect,
                 cost,
             },
-            None => ExecutionResult::Success { effect, cost },
+            None => ExecutionResult::Success {
+                effect,
+                cost,
+                execution_trace: runtime_context.recorded_execution_trace(),
+            },
         };
 
         match maybe_ret {
@@ -556,6 +586,7 @@ impl Executor {
         };
 
         let gas_counter = Gas::default();
+        let max_memory_pages = self.max_memory_pages(&protocol_data);
 
         let runtime_context = RuntimeContext::new(
             tracking_copy,
@@ -572,13 +603,15 @@ impl Executor {
             gas_counter,
             hash_address_generator,
             uref_address_generator,
+            self.new_execution_trace(),
             protocol_version,
             correlation_id,
             phase,
             protocol_data,
         );
 
-        let (instance, memory) = instance_and_memory(module.clone(), protocol_version)?;
+        let (instance, memory) =
+            instance_and_memory(module.clone(), protocol_version, max_memory_pages)?;
 
         let runtime = Runtime::new(
             self.config,
@@ -669,6 +702,7 @@ impl DirectSystemContractCall {
                 Ok(ret) => ExecutionResult::Success {
                     effect: runtime.context().effect(),
                     cost: runtime.context().gas_counter(),
+                    execution_trace: runtime.context().recorded_execution_trace(),
                 }
                 .take_with_ret(ret),
                 Err(error) => ExecutionResult::Failure {
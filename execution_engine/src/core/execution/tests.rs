@@ -19,6 +19,7 @@ fn on_fail_charge_test_helper<T>(
     ExecutionResult::Success {
         effect: Default::default(),
         cost: success_cost,
+        execution_trace: Default::default(),
     }
 }
 
@@ -58,6 +59,7 @@ fn on_fail_charge_with_action() {
         ExecutionResult::Success {
             effect: Default::default(),
             cost: Gas::default(),
+            execution_trace: Default::default(),
         }
     };
     match f() {
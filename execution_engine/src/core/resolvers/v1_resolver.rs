@@ -14,11 +14,12 @@ pub(crate) struct RuntimeModuleImportResolver {
     max_memory: u32,
 }
 
-impl Default for RuntimeModuleImportResolver {
-    fn default() -> Self {
+impl RuntimeModuleImportResolver {
+    /// Creates a resolver which allows an imported memory of at most `max_memory` pages.
+    pub(crate) fn new(max_memory: u32) -> Self {
         RuntimeModuleImportResolver {
             memory: RefCell::new(None),
-            max_memory: 64,
+            max_memory,
         }
     }
 }
@@ -1,7 +1,10 @@
 use casper_types::{
     account,
     account::AccountHash,
-    auction::{Auction, MintProvider, RuntimeProvider, StorageProvider, SystemProvider},
+    auction::{
+        self, Auction, AuctionEvent, AuctionEvents, MintProvider, RuntimeProvider, StorageProvider,
+        SystemProvider, AUCTION_EVENTS_KEY,
+    },
     bytesrepr::{FromBytes, ToBytes},
     system_contract_errors::auction::Error,
     ApiError, CLTyped, CLValue, Key, TransferredTo, URef, BLAKE2B_DIGEST_LENGTH, U512,
@@ -84,6 +87,18 @@ where
     fn blake2b<T: AsRef<[u8]>>(&self, data: T) -> [u8; BLAKE2B_DIGEST_LENGTH] {
         account::blake2b(data)
     }
+
+    fn emit_event(&mut self, event: AuctionEvent) -> Result<(), Error> {
+        // Auction contracts installed before events were introduced don't record them.
+        let auction_events_uref = match self.get_key(AUCTION_EVENTS_KEY).and_then(Key::into_uref) {
+            Some(uref) => uref,
+            None => return Ok(()),
+        };
+        let mut auction_events: AuctionEvents =
+            StorageProvider::read(self, auction_events_uref)?.unwrap_or_default();
+        auction::push_auction_event(&mut auction_events, event);
+        StorageProvider::write(self, auction_events_uref, auction_events)
+    }
 }
 
 impl<'a, R> MintProvider for Runtime<'a, R>
@@ -19,7 +19,7 @@ use wasmi::{ImportsBuilder, MemoryRef, ModuleInstance, ModuleRef, Trap, TrapKind
 
 use casper_types::{
     account::{AccountHash, ActionType, Weight},
-    auction::{self, Auction},
+    auction::{self, Auction, BlocksProposed, SlashFractions},
     bytesrepr::{self, FromBytes, ToBytes},
     contracts::{
         self, Contract, ContractPackage, ContractVersion, ContractVersions, DisabledVersions,
@@ -37,7 +37,10 @@ use casper_types::{
 
 use crate::{
     core::{
-        engine_state::{system_contract_cache::SystemContractCache, EngineConfig},
+        engine_state::{
+            execution_trace::ExecutionStep, system_contract_cache::SystemContractCache,
+            EngineConfig,
+        },
         execution::Error,
         resolvers::{create_module_resolver, memory_resolver::MemoryResolver},
         runtime_context::{self, RuntimeContext},
@@ -73,12 +76,14 @@ pub fn rename_export_to_call(module: &mut Module, name: String) {
     main_export.push_str("call");
 }
 
+/// Instantiates `parity_module`, whose imported memory may have at most `max_memory_pages` pages.
 pub fn instance_and_memory(
     parity_module: Module,
     protocol_version: ProtocolVersion,
+    max_memory_pages: u32,
 ) -> Result<(ModuleRef, MemoryRef), Error> {
     let module = wasmi::Module::from_parity_wasm_module(parity_module)?;
-    let resolver = create_module_resolver(protocol_version)?;
+    let resolver = create_module_resolver(protocol_version, max_memory_pages)?;
     let mut imports = ImportsBuilder::new();
     imports.push_resolver("env", &resolver);
     let not_started_module = ModuleInstance::new(&module, &imports)?;
@@ -1706,6 +1711,7 @@ where
         let gas_counter = self.context.gas_counter();
         let hash_address_generator = self.context.hash_address_generator();
         let uref_address_generator = self.context.uref_address_generator();
+        let execution_trace = self.context.execution_trace();
         let correlation_id = self.context.correlation_id();
         let phase = self.context.phase();
         let protocol_data = self.context.protocol_data();
@@ -1725,6 +1731,7 @@ where
             gas_counter,
             hash_address_generator,
             uref_address_generator,
+            execution_trace,
             protocol_version,
             correlation_id,
             phase,
@@ -1808,6 +1815,7 @@ where
         let gas_counter = self.context.gas_counter();
         let fn_store_id = self.context.hash_address_generator();
         let address_generator = self.context.uref_address_generator();
+        let execution_trace = self.context.execution_trace();
         let correlation_id = self.context.correlation_id();
         let phase = self.context.phase();
         let protocol_data = self.context.protocol_data();
@@ -1827,6 +1835,7 @@ where
             gas_counter,
             fn_store_id,
             address_generator,
+            execution_trace,
             protocol_version,
             correlation_id,
             phase,
@@ -1906,6 +1915,7 @@ where
         let gas_counter = self.context.gas_counter();
         let fn_store_id = self.context.hash_address_generator();
         let address_generator = self.context.uref_address_generator();
+        let execution_trace = self.context.execution_trace();
         let correlation_id = self.context.correlation_id();
         let phase = self.context.phase();
         let protocol_data = self.context.protocol_data();
@@ -1925,6 +1935,7 @@ where
             gas_counter,
             fn_store_id,
             address_generator,
+            execution_trace,
             protocol_version,
             correlation_id,
             phase,
@@ -1949,8 +1960,17 @@ where
             }
 
             auction::METHOD_READ_SEIGNIORAGE_RECIPIENTS => {
+                let era_id = if runtime_args.get(auction::ARG_ERA_ID).is_some() {
+                    Some(Self::get_named_argument(
+                        &runtime_args,
+                        auction::ARG_ERA_ID,
+                    )?)
+                } else {
+                    None
+                };
+
                 let result = runtime
-                    .read_seigniorage_recipients()
+                    .read_seigniorage_recipients(era_id)
                     .map_err(Self::reverter)?;
 
                 CLValue::from_t(result).map_err(Self::reverter)?
@@ -1985,6 +2005,20 @@ where
                 CLValue::from_t(result).map_err(Self::reverter)?
             }
 
+            auction::METHOD_DEACTIVATE_BID => {
+                let public_key = Self::get_named_argument(&runtime_args, auction::ARG_PUBLIC_KEY)?;
+
+                runtime.deactivate_bid(public_key).map_err(Self::reverter)?;
+                CLValue::from_t(()).map_err(Self::reverter)?
+            }
+
+            auction::METHOD_ACTIVATE_BID => {
+                let public_key = Self::get_named_argument(&runtime_args, auction::ARG_PUBLIC_KEY)?;
+
+                runtime.activate_bid(public_key).map_err(Self::reverter)?;
+                CLValue::from_t(()).map_err(Self::reverter)?
+            }
+
             auction::METHOD_DELEGATE => {
                 let delegator = Self::get_named_argument(&runtime_args, auction::ARG_DELEGATOR)?;
                 let source_purse =
@@ -2003,11 +2037,17 @@ where
                 let delegator = Self::get_named_argument(&runtime_args, auction::ARG_DELEGATOR)?;
                 let validator = Self::get_named_argument(&runtime_args, auction::ARG_VALIDATOR)?;
                 let amount = Self::get_named_argument(&runtime_args, auction::ARG_AMOUNT)?;
-                let unbond_purse =
-                    Self::get_named_argument(&runtime_args, auction::ARG_UNBOND_PURSE)?;
+                let target_purse = if runtime_args.get(auction::ARG_TARGET_PURSE).is_some() {
+                    Some(Self::get_named_argument(
+                        &runtime_args,
+                        auction::ARG_TARGET_PURSE,
+                    )?)
+                } else {
+                    None
+                };
 
                 let result = runtime
-                    .undelegate(delegator, validator, amount, unbond_purse)
+                    .undelegate(delegator, validator, amount, target_purse)
                     .map_err(Self::reverter)?;
 
                 CLValue::from_t(result).map_err(Self::reverter)?
@@ -2018,24 +2058,40 @@ where
                 CLValue::from_t(()).map_err(Self::reverter)?
             }
 
-            // Type: `fn slash(validator_account_hashes: &[AccountHash]) -> Result<(), Error>`
+            // Type: `fn slash(validator_public_keys: Vec<PublicKey>, slash_fractions:
+            // SlashFractions) -> Result<(), Error>`
             auction::METHOD_SLASH => {
                 let validator_public_keys =
                     Self::get_named_argument(&runtime_args, auction::ARG_VALIDATOR_PUBLIC_KEYS)?;
+                let slash_fractions: SlashFractions =
+                    if runtime_args.get(auction::ARG_SLASH_FRACTIONS).is_some() {
+                        Self::get_named_argument(&runtime_args, auction::ARG_SLASH_FRACTIONS)?
+                    } else {
+                        SlashFractions::new()
+                    };
                 runtime
-                    .slash(validator_public_keys)
+                    .slash(validator_public_keys, slash_fractions)
                     .map_err(Self::reverter)?;
                 CLValue::from_t(()).map_err(Self::reverter)?
             }
-            // Type: `fn distribute(reward_factors: BTreeMap<PublicKey, u64>) -> Result<(), Error>`
+            // Type: `fn distribute(reward_factors: BTreeMap<PublicKey, u64>,
+            // blocks_proposed: BlocksProposed) -> Result<(), Error>`
             auction::METHOD_DISTRIBUTE => {
                 let reward_factors: BTreeMap<PublicKey, u64> =
                     Self::get_named_argument(&runtime_args, auction::ARG_REWARD_FACTORS)?;
-                runtime.distribute(reward_factors).map_err(Self::reverter)?;
+                let blocks_proposed: BlocksProposed =
+                    if runtime_args.get(auction::ARG_BLOCKS_PROPOSED).is_some() {
+                        Self::get_named_argument(&runtime_args, auction::ARG_BLOCKS_PROPOSED)?
+                    } else {
+                        BlocksProposed::new()
+                    };
+                runtime
+                    .distribute(reward_factors, blocks_proposed)
+                    .map_err(Self::reverter)?;
                 CLValue::from_t(()).map_err(Self::reverter)?
             }
             // Type: `fn withdraw_delegator_reward(validator_public_key: PublicKey,
-            // delegator_public_key: PublicKey, target_purse: URef) -> Result<(), Error>`
+            // delegator_public_key: PublicKey, target_purse: URef) -> Result<U512, Error>`
             auction::METHOD_WITHDRAW_DELEGATOR_REWARD => {
                 let validator_public_key: PublicKey =
                     Self::get_named_argument(&runtime_args, auction::ARG_VALIDATOR_PUBLIC_KEY)?;
@@ -2043,32 +2099,70 @@ where
                     Self::get_named_argument(&runtime_args, auction::ARG_DELEGATOR_PUBLIC_KEY)?;
                 let target_purse: URef =
                     Self::get_named_argument(&runtime_args, auction::ARG_TARGET_PURSE)?;
-                runtime
+                let result = runtime
                     .withdraw_delegator_reward(
                         validator_public_key,
                         delegator_public_key,
                         target_purse,
                     )
                     .map_err(Self::reverter)?;
-                CLValue::from_t(()).map_err(Self::reverter)?
+                CLValue::from_t(result).map_err(Self::reverter)?
             }
-            // Type: `fn withdraw_delegator_reward(validator_public_key: PublicKey, target_purse:
-            // URef) -> Result<(), Error>`
+            // Type: `fn withdraw_validator_reward(validator_public_key: PublicKey, target_purse:
+            // URef) -> Result<U512, Error>`
             auction::METHOD_WITHDRAW_VALIDATOR_REWARD => {
                 let validator_public_key: PublicKey =
                     Self::get_named_argument(&runtime_args, auction::ARG_VALIDATOR_PUBLIC_KEY)?;
                 let target_purse: URef =
                     Self::get_named_argument(&runtime_args, auction::ARG_TARGET_PURSE)?;
-                runtime
+                let result = runtime
                     .withdraw_validator_reward(validator_public_key, target_purse)
                     .map_err(Self::reverter)?;
-                CLValue::from_t(()).map_err(Self::reverter)?
+                CLValue::from_t(result).map_err(Self::reverter)?
             }
             // Type: `fn read_era_id() -> Result<EraId, Error>`
             auction::METHOD_READ_ERA_ID => {
                 let result = runtime.read_era_id().map_err(Self::reverter)?;
                 CLValue::from_t(result).map_err(Self::reverter)?
             }
+            // Type: `fn read_era_summary() -> Result<EraSummary, Error>`
+            auction::METHOD_READ_ERA_SUMMARY => {
+                let result = runtime.read_era_summary().map_err(Self::reverter)?;
+                CLValue::from_t(result).map_err(Self::reverter)?
+            }
+            // Type: `fn read_reward_info(validator_public_key: PublicKey, delegator_public_key:
+            // Option<PublicKey>) -> Result<RewardInfo, Error>`
+            auction::METHOD_READ_REWARD_INFO => {
+                let validator_public_key: PublicKey =
+                    Self::get_named_argument(&runtime_args, auction::ARG_VALIDATOR_PUBLIC_KEY)?;
+                let delegator_public_key: Option<PublicKey> = if runtime_args
+                    .get(auction::ARG_DELEGATOR_PUBLIC_KEY)
+                    .is_some()
+                {
+                    Some(Self::get_named_argument(
+                        &runtime_args,
+                        auction::ARG_DELEGATOR_PUBLIC_KEY,
+                    )?)
+                } else {
+                    None
+                };
+                let result = runtime
+                    .read_reward_info(validator_public_key, delegator_public_key)
+                    .map_err(Self::reverter)?;
+                CLValue::from_t(result).map_err(Self::reverter)?
+            }
+            // Type: `fn read_delegation(validator: PublicKey, delegator: PublicKey) ->
+            // Result<Option<U512>, Error>`
+            auction::METHOD_READ_DELEGATION => {
+                let validator: PublicKey =
+                    Self::get_named_argument(&runtime_args, auction::ARG_VALIDATOR)?;
+                let delegator: PublicKey =
+                    Self::get_named_argument(&runtime_args, auction::ARG_DELEGATOR)?;
+                let result = runtime
+                    .read_delegation(validator, delegator)
+                    .map_err(Self::reverter)?;
+                CLValue::from_t(result).map_err(Self::reverter)?
+            }
 
             _ => CLValue::from_t(()).map_err(Self::reverter)?,
         };
@@ -2104,14 +2198,17 @@ where
 
         let context_key = self.get_context_key_for_contract_call(contract_hash, &entry_point)?;
 
-        self.execute_contract(
+        let gas_before = self.context.gas_counter();
+        let result = self.execute_contract(
             key,
             context_key,
             contract,
             args,
             entry_point,
             self.context.protocol_version(),
-        )
+        );
+        self.record_contract_call(contract_hash, entry_point_name, gas_before);
+        result
     }
 
     /// Calls `version` of the contract living at `key`, invoking `method` with
@@ -2186,14 +2283,34 @@ where
 
         let context_key = self.get_context_key_for_contract_call(contract_hash, &entry_point)?;
 
-        self.execute_contract(
+        let gas_before = self.context.gas_counter();
+        let result = self.execute_contract(
             context_key,
             context_key,
             contract,
             args,
             entry_point,
             self.context.protocol_version(),
-        )
+        );
+        self.record_contract_call(contract_hash, &entry_point_name, gas_before);
+        result
+    }
+
+    /// Records a call to `contract_hash` in the execution trace, along with the gas used since
+    /// `gas_before`.
+    fn record_contract_call(
+        &self,
+        contract_hash: ContractHash,
+        entry_point: &str,
+        gas_before: Gas,
+    ) {
+        let gas_used = (self.context.gas_counter() - gas_before).value().as_u64();
+        self.context
+            .record_execution_step(ExecutionStep::ContractCall {
+                contract_hash,
+                entry_point: entry_point.to_string(),
+                gas_used,
+            });
     }
 
     fn get_context_key_for_contract_call(
@@ -2320,7 +2437,12 @@ where
 
         let entry_point_name = entry_point.name();
 
-        let (instance, memory) = instance_and_memory(module.clone(), protocol_version)?;
+        let max_memory_pages = self
+            .config
+            .apply_wasm_limits(*self.protocol_data().wasm_config())
+            .initial_memory;
+        let (instance, memory) =
+            instance_and_memory(module.clone(), protocol_version, max_memory_pages)?;
 
         let access_rights = {
             let mut keys: Vec<Key> = named_keys.values().cloned().collect();
@@ -2351,6 +2473,7 @@ where
             self.context.gas_counter(),
             self.context.hash_address_generator(),
             self.context.uref_address_generator(),
+            self.context.execution_trace(),
             protocol_version,
             self.context.correlation_id(),
             self.context.phase(),
@@ -3079,7 +3202,13 @@ where
 
         let result = self.call_contract(mint_contract_hash, "transfer", args_values)?;
         let result: Result<(), system_contract_errors::mint::Error> = result.into_t()?;
-        Ok(result.map_err(system_contract_errors::Error::from)?)
+        result.map_err(system_contract_errors::Error::from)?;
+        self.context.record_execution_step(ExecutionStep::Transfer {
+            from: source,
+            to: target,
+            amount,
+        });
+        Ok(())
     }
 
     /// Creates a new account at a given public key, transferring a given amount
@@ -25,7 +25,10 @@ use casper_types::{
 
 use crate::{
     core::{
-        engine_state::execution_effect::ExecutionEffect,
+        engine_state::{
+            execution_effect::ExecutionEffect,
+            execution_trace::{ExecutionStep, ExecutionTrace},
+        },
         execution::{AddressGenerator, Error},
         tracking_copy::{AddResult, TrackingCopy},
         Address,
@@ -101,6 +104,8 @@ pub struct RuntimeContext<'a, R> {
     gas_counter: Gas,
     hash_address_generator: Rc<RefCell<AddressGenerator>>,
     uref_address_generator: Rc<RefCell<AddressGenerator>>,
+    // Shared with the contexts of sub-calls; `None` if execution tracing is disabled
+    execution_trace: Option<Rc<RefCell<ExecutionTrace>>>,
     protocol_version: ProtocolVersion,
     correlation_id: CorrelationId,
     phase: Phase,
@@ -129,6 +134,7 @@ where
         gas_counter: Gas,
         hash_address_generator: Rc<RefCell<AddressGenerator>>,
         uref_address_generator: Rc<RefCell<AddressGenerator>>,
+        execution_trace: Option<Rc<RefCell<ExecutionTrace>>>,
         protocol_version: ProtocolVersion,
         correlation_id: CorrelationId,
         phase: Phase,
@@ -149,6 +155,7 @@ where
             gas_counter,
             hash_address_generator,
             uref_address_generator,
+            execution_trace,
             protocol_version,
             correlation_id,
             phase,
@@ -269,6 +276,25 @@ where
         Rc::clone(&self.hash_address_generator)
     }
 
+    pub fn execution_trace(&self) -> Option<Rc<RefCell<ExecutionTrace>>> {
+        self.execution_trace.clone()
+    }
+
+    /// Records `step` in the execution trace if execution tracing is enabled.
+    pub fn record_execution_step(&self, step: ExecutionStep) {
+        if let Some(execution_trace) = &self.execution_trace {
+            execution_trace.borrow_mut().push(step);
+        }
+    }
+
+    /// Returns the steps recorded so far, or an empty trace if execution tracing is disabled.
+    pub fn recorded_execution_trace(&self) -> ExecutionTrace {
+        self.execution_trace
+            .as_ref()
+            .map(|execution_trace| execution_trace.borrow().clone())
+            .unwrap_or_default()
+    }
+
     pub fn state(&self) -> Rc<RefCell<TrackingCopy<R>>> {
         Rc::clone(&self.tracking_copy)
     }
@@ -350,6 +376,7 @@ where
         }
         let hash: [u8; KEY_HASH_LENGTH] = key_bytes.try_into().unwrap();
         let key: Key = hash.into();
+        self.record_execution_step(ExecutionStep::StorageRead { key });
         let maybe_stored_value = self
             .tracking_copy
             .borrow_mut()
@@ -372,9 +399,11 @@ where
             });
         }
         let hash: [u8; KEY_HASH_LENGTH] = key_bytes.try_into().unwrap();
+        let key: Key = hash.into();
+        self.record_execution_step(ExecutionStep::StorageWrite { key });
         self.tracking_copy
             .borrow_mut()
-            .write(hash.into(), StoredValue::CLValue(cl_value));
+            .write(key, StoredValue::CLValue(cl_value));
         Ok(())
     }
 
@@ -382,6 +411,7 @@ where
         self.validate_readable(key)?;
         self.validate_key(key)?;
 
+        self.record_execution_step(ExecutionStep::StorageRead { key: *key });
         self.tracking_copy
             .borrow_mut()
             .read(self.correlation_id, key)
@@ -390,6 +420,7 @@ where
 
     /// DO NOT EXPOSE THIS VIA THE FFI
     pub fn read_gs_direct(&mut self, key: &Key) -> Result<Option<StoredValue>, Error> {
+        self.record_execution_step(ExecutionStep::StorageRead { key: *key });
         self.tracking_copy
             .borrow_mut()
             .read(self.correlation_id, key)
@@ -422,6 +453,7 @@ where
         self.validate_writeable(&key)?;
         self.validate_key(&key)?;
         self.validate_value(&value)?;
+        self.record_execution_step(ExecutionStep::StorageWrite { key });
         self.tracking_copy.borrow_mut().write(key, value);
         Ok(())
     }
@@ -429,6 +461,7 @@ where
     pub fn read_account(&mut self, key: &Key) -> Result<Option<StoredValue>, Error> {
         if let Key::Account(_) = key {
             self.validate_key(key)?;
+            self.record_execution_step(ExecutionStep::StorageRead { key: *key });
             self.tracking_copy
                 .borrow_mut()
                 .read(self.correlation_id, key)
@@ -442,6 +475,7 @@ where
         if let Key::Account(_) = key {
             self.validate_key(&key)?;
             let account_value = self.account_to_validated_value(account)?;
+            self.record_execution_step(ExecutionStep::StorageWrite { key });
             self.tracking_copy.borrow_mut().write(key, account_value);
             Ok(())
         } else {
@@ -662,7 +696,10 @@ where
             .add(self.correlation_id, key, value)
         {
             Err(storage_error) => Err(storage_error.into()),
-            Ok(AddResult::Success) => Ok(()),
+            Ok(AddResult::Success) => {
+                self.record_execution_step(ExecutionStep::StorageWrite { key });
+                Ok(())
+            }
             Ok(AddResult::KeyNotFound(key)) => Err(Error::KeyNotFound(key)),
             Ok(AddResult::TypeMismatch(type_mismatch)) => Err(Error::TypeMismatch(type_mismatch)),
             Ok(AddResult::Serialization(error)) => Err(Error::BytesRepr(error)),
@@ -19,7 +19,9 @@ use casper_types::{
 use super::{Address, Error, RuntimeContext};
 use crate::{
     core::{
-        execution::AddressGenerator, runtime::extract_access_rights_from_keys,
+        engine_state::execution_trace::{ExecutionStep, ExecutionTrace},
+        execution::AddressGenerator,
+        runtime::extract_access_rights_from_keys,
         tracking_copy::TrackingCopy,
     },
     shared::{
@@ -135,6 +137,7 @@ fn mock_runtime_context<'a>(
         Gas::default(),
         Rc::new(RefCell::new(hash_address_generator)),
         Rc::new(RefCell::new(uref_address_generator)),
+        None,
         ProtocolVersion::V1_0_0,
         CorrelationId::new(),
         Phase::Session,
@@ -363,6 +366,7 @@ fn contract_key_addable_valid() {
         Gas::default(),
         Rc::new(RefCell::new(hash_address_generator)),
         Rc::new(RefCell::new(uref_address_generator)),
+        None,
         ProtocolVersion::V1_0_0,
         CorrelationId::new(),
         PHASE,
@@ -433,6 +437,7 @@ fn contract_key_addable_invalid() {
         Gas::default(),
         Rc::new(RefCell::new(hash_address_generator)),
         Rc::new(RefCell::new(uref_address_generator)),
+        None,
         ProtocolVersion::V1_0_0,
         CorrelationId::new(),
         PHASE,
@@ -833,3 +838,44 @@ fn validate_valid_purse_of_an_account() {
     let purse = URef::new([53; 32], AccessRights::READ_ADD_WRITE);
     assert!(runtime_context.validate_uref(&purse).is_err());
 }
+
+#[test]
+fn should_record_execution_trace_when_enabled() {
+    let mut rng = AddressGenerator::new(&DEPLOY_HASH, PHASE);
+    let uref = create_uref(&mut rng, AccessRights::READ_WRITE);
+    let access_rights = extract_access_rights_from_keys(vec![uref]);
+    let value = StoredValue::CLValue(CLValue::from_t(43_i32).unwrap());
+
+    let query = |mut runtime_context: RuntimeContext<InMemoryGlobalStateView>| {
+        runtime_context.execution_trace = Some(Rc::new(RefCell::new(ExecutionTrace::new())));
+        runtime_context.write_gs(uref, value)?;
+        runtime_context.read_gs(&uref)?;
+        Ok(runtime_context.recorded_execution_trace())
+    };
+    let execution_trace = test(access_rights, query).expect("should be ok");
+
+    assert_eq!(
+        execution_trace,
+        vec![
+            ExecutionStep::StorageWrite { key: uref },
+            ExecutionStep::StorageRead { key: uref },
+        ]
+    );
+}
+
+#[test]
+fn should_not_record_execution_trace_when_disabled() {
+    let mut rng = AddressGenerator::new(&DEPLOY_HASH, PHASE);
+    let uref = create_uref(&mut rng, AccessRights::READ_WRITE);
+    let access_rights = extract_access_rights_from_keys(vec![uref]);
+    let value = StoredValue::CLValue(CLValue::from_t(43_i32).unwrap());
+
+    let query = |mut runtime_context: RuntimeContext<InMemoryGlobalStateView>| {
+        runtime_context.write_gs(uref, value)?;
+        runtime_context.read_gs(&uref)?;
+        Ok(runtime_context.recorded_execution_trace())
+    };
+    let execution_trace = test(access_rights, query).expect("should be ok");
+
+    assert!(execution_trace.is_empty());
+}
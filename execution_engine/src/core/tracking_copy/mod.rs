@@ -32,6 +32,7 @@ use crate::{
 pub enum TrackingCopyQueryResult {
     Success(StoredValue),
     ValueNotFound(String),
+    TypeMismatch(String),
     CircularReference(String),
 }
 
@@ -74,6 +75,11 @@ impl Query {
         TrackingCopyQueryResult::ValueNotFound(msg)
     }
 
+    fn into_type_mismatch_result(self, msg_prefix: &str) -> TrackingCopyQueryResult {
+        let msg = format!("{} at path: {}", msg_prefix, self.current_path());
+        TrackingCopyQueryResult::TypeMismatch(msg)
+    }
+
     fn into_circular_ref_result(self) -> TrackingCopyQueryResult {
         let msg = format!(
             "{:?} has formed a circular reference at path: {}",
@@ -374,7 +380,9 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
                     if let Ok(key) = cl_value.into_t::<Key>() {
                         query.current_key = key.normalize();
                     } else {
-                        return Ok(query.into_not_found_result("Failed to parse CLValue as Key"));
+                        return Ok(
+                            query.into_type_mismatch_result("Failed to parse CLValue as Key")
+                        );
                     }
                 }
                 StoredValue::CLValue(cl_value) => {
@@ -383,7 +391,7 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
                         such.  Value found",
                         cl_value
                     );
-                    return Ok(query.into_not_found_result(&msg_prefix));
+                    return Ok(query.into_type_mismatch_result(&msg_prefix));
                 }
                 StoredValue::Contract(contract) => {
                     let name = query.next_name();
@@ -395,10 +403,10 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
                     }
                 }
                 StoredValue::ContractPackage(_) => {
-                    return Ok(query.into_not_found_result(&"ContractPackage value found."));
+                    return Ok(query.into_type_mismatch_result(&"ContractPackage value found."));
                 }
                 StoredValue::ContractWasm(_) => {
-                    return Ok(query.into_not_found_result(&"ContractWasm value found."));
+                    return Ok(query.into_type_mismatch_result(&"ContractWasm value found."));
                 }
             }
         }
@@ -546,3 +546,45 @@ fn query_for_circular_references_should_fail() {
         panic!("Query didn't fail with a circular reference error");
     }
 }
+
+#[test]
+fn query_for_missing_name_should_fail_with_value_not_found() {
+    let contract_key = Key::Hash([1; 32]);
+    let contract = StoredValue::Contract(Contract::new(
+        [2; 32],
+        [3; 32],
+        NamedKeys::new(),
+        EntryPoints::default(),
+        ProtocolVersion::V1_0_0,
+    ));
+
+    let correlation_id = CorrelationId::new();
+    let (global_state, root_hash) =
+        InMemoryGlobalState::from_pairs(correlation_id, &[(contract_key, contract)]).unwrap();
+    let view = global_state.checkout(root_hash).unwrap().unwrap();
+    let tracking_copy = TrackingCopy::new(view);
+
+    let path = vec!["missing".to_string()];
+    assert_matches!(
+        tracking_copy.query(correlation_id, contract_key, &path),
+        Ok(TrackingCopyQueryResult::ValueNotFound(msg)) if msg.contains("Name missing not found")
+    );
+}
+
+#[test]
+fn query_through_non_traversable_value_should_fail_with_type_mismatch() {
+    let cl_value_key = Key::Hash([1; 32]);
+    let cl_value = StoredValue::CLValue(CLValue::from_t(7_i32).unwrap());
+
+    let correlation_id = CorrelationId::new();
+    let (global_state, root_hash) =
+        InMemoryGlobalState::from_pairs(correlation_id, &[(cl_value_key, cl_value)]).unwrap();
+    let view = global_state.checkout(root_hash).unwrap().unwrap();
+    let tracking_copy = TrackingCopy::new(view);
+
+    let path = vec!["name".to_string()];
+    assert_matches!(
+        tracking_copy.query(correlation_id, cl_value_key, &path),
+        Ok(TrackingCopyQueryResult::TypeMismatch(msg)) if msg.contains("Query cannot continue")
+    );
+}
@@ -19,9 +19,14 @@ lazy_static! {
     };
 }
 
+/// Returns whether `value` is a multiple of the OS page size.
+pub fn is_multiple_of_page_size(value: usize) -> bool {
+    value % *OS_PAGE_SIZE == 0
+}
+
 /// Warns if `value` is not a multiple of the OS page size.
 pub fn check_multiple_of_page_size(value: usize) {
-    if value % *OS_PAGE_SIZE != 0 {
+    if !is_multiple_of_page_size(value) {
         warn!(
             "maximum size {} is not multiple of system page size {}",
             value, *OS_PAGE_SIZE,
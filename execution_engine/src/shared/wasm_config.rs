@@ -10,6 +10,11 @@ use super::{
 
 pub const DEFAULT_INITIAL_MEMORY: u32 = 64;
 pub const DEFAULT_MAX_STACK_HEIGHT: u32 = 64 * 1024;
+/// The maximum number of 64 KiB pages of linear memory addressable by a wasm32 module, i.e. 4 GiB.
+pub const WASM_MEMORY_PAGES_LIMIT: u32 = 65_536;
+/// The maximum stack height which can be enforced.  The wasmi interpreter's value stack holds
+/// 1 MiB of 8-byte values, so a higher limit would never be reached.
+pub const STACK_HEIGHT_LIMIT: u32 = 128 * 1024;
 
 #[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug, DataSize)]
 pub struct WasmConfig {
@@ -1,16 +1,20 @@
 use std::fmt::{self, Display, Formatter};
 
-use parity_wasm::elements::{self, Module};
+use parity_wasm::elements::{self, External, Module};
 use pwasm_utils::{self, stack_height};
 use thiserror::Error;
 
 use super::wasm_config::WasmConfig;
 
+/// Default maximum number of 64 KiB pages of linear memory a module may declare initially.
+pub const DEFAULT_MEMORY_LIMIT_PAGES: u32 = 512;
+
 #[derive(Debug, Clone, Error)]
 pub enum PreprocessingError {
     Deserialize(String),
     OperationForbiddenByGasRules,
     StackLimiter,
+    MemoryLimitExceeded { pages: u32, limit: u32 },
 }
 
 impl From<elements::Error> for PreprocessingError {
@@ -25,32 +29,156 @@ impl Display for PreprocessingError {
             PreprocessingError::Deserialize(error) => write!(f, "Deserialization error: {}", error),
             PreprocessingError::OperationForbiddenByGasRules => write!(f, "Encountered operation forbidden by gas rules. Consult instruction -> metering config map"),
             PreprocessingError::StackLimiter => write!(f, "Stack limiter error"),
+            PreprocessingError::MemoryLimitExceeded { pages, limit } => write!(
+                f,
+                "Initial memory of {} pages exceeds the limit of {} pages",
+                pages, limit
+            ),
         }
     }
 }
 
-pub struct Preprocessor {
+/// The configuration used when preprocessing a module before execution.
+#[derive(Debug, Copy, Clone)]
+pub struct PreprocessingConfig {
     wasm_config: WasmConfig,
+    memory_limit_pages: u32,
+}
+
+impl PreprocessingConfig {
+    pub fn new(wasm_config: WasmConfig, memory_limit_pages: u32) -> Self {
+        PreprocessingConfig {
+            wasm_config,
+            memory_limit_pages,
+        }
+    }
+
+    pub fn wasm_config(&self) -> &WasmConfig {
+        &self.wasm_config
+    }
+
+    /// The maximum number of pages of linear memory a module may declare initially.
+    pub fn memory_limit_pages(&self) -> u32 {
+        self.memory_limit_pages
+    }
+}
+
+impl From<WasmConfig> for PreprocessingConfig {
+    fn from(wasm_config: WasmConfig) -> Self {
+        PreprocessingConfig::new(wasm_config, DEFAULT_MEMORY_LIMIT_PAGES)
+    }
+}
+
+pub struct Preprocessor {
+    config: PreprocessingConfig,
 }
 
 impl Preprocessor {
-    pub fn new(wasm_config: WasmConfig) -> Self {
-        Self { wasm_config }
+    pub fn new<T: Into<PreprocessingConfig>>(config: T) -> Self {
+        Self {
+            config: config.into(),
+        }
     }
 
     pub fn preprocess(&self, module_bytes: &[u8]) -> Result<Module, PreprocessingError> {
         let module = deserialize(module_bytes)?;
-        let module = pwasm_utils::externalize_mem(module, None, self.wasm_config.initial_memory);
-        let module =
-            pwasm_utils::inject_gas_counter(module, &self.wasm_config.opcode_costs().to_set())
-                .map_err(|_| PreprocessingError::OperationForbiddenByGasRules)?;
-        let module = stack_height::inject_limiter(module, self.wasm_config.max_stack_height)
+        self.check_memory_limit(&module)?;
+        let wasm_config = self.config.wasm_config();
+        let module = pwasm_utils::externalize_mem(module, None, wasm_config.initial_memory);
+        let module = pwasm_utils::inject_gas_counter(module, &wasm_config.opcode_costs().to_set())
+            .map_err(|_| PreprocessingError::OperationForbiddenByGasRules)?;
+        let module = stack_height::inject_limiter(module, wasm_config.max_stack_height)
             .map_err(|_| PreprocessingError::StackLimiter)?;
         Ok(module)
     }
+
+    /// Rejects `module` if any memory it defines or imports starts with more pages than allowed.
+    fn check_memory_limit(&self, module: &Module) -> Result<(), PreprocessingError> {
+        let limit = self.config.memory_limit_pages();
+        let defined = module
+            .memory_section()
+            .map(|section| section.entries())
+            .unwrap_or_default()
+            .iter()
+            .map(|memory_type| memory_type.limits().initial());
+        let imported = module
+            .import_section()
+            .map(|section| section.entries())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|entry| match entry.external() {
+                External::Memory(memory_type) => Some(memory_type.limits().initial()),
+                _ => None,
+            });
+        match defined.chain(imported).max() {
+            Some(pages) if pages > limit => {
+                Err(PreprocessingError::MemoryLimitExceeded { pages, limit })
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
 // Returns a parity Module from bytes without making modifications or limits
 pub fn deserialize(module_bytes: &[u8]) -> Result<Module, PreprocessingError> {
     parity_wasm::deserialize_buffer::<Module>(module_bytes).map_err(Into::into)
 }
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+    use crate::shared::wasm_config::DEFAULT_INITIAL_MEMORY;
+
+    fn preprocess_wat(wat: &str, memory_limit_pages: u32) -> Result<Module, PreprocessingError> {
+        let module_bytes = wabt::wat2wasm(wat).expect("failed to parse wat");
+        let config = PreprocessingConfig::new(WasmConfig::default(), memory_limit_pages);
+        Preprocessor::new(config).preprocess(&module_bytes)
+    }
+
+    #[test]
+    fn should_reject_module_exceeding_memory_limit() {
+        let result = preprocess_wat("(module (memory 1024))", DEFAULT_MEMORY_LIMIT_PAGES);
+        assert_matches!(
+            result,
+            Err(PreprocessingError::MemoryLimitExceeded { pages: 1024, limit })
+                if limit == DEFAULT_MEMORY_LIMIT_PAGES
+        );
+    }
+
+    #[test]
+    fn should_reject_imported_memory_exceeding_limit() {
+        let result = preprocess_wat(r#"(module (import "env" "memory" (memory 16)))"#, 8);
+        assert_matches!(
+            result,
+            Err(PreprocessingError::MemoryLimitExceeded {
+                pages: 16,
+                limit: 8
+            })
+        );
+    }
+
+    #[test]
+    fn should_accept_module_within_memory_limit() {
+        preprocess_wat("(module (memory 1))", DEFAULT_MEMORY_LIMIT_PAGES)
+            .expect("should preprocess");
+    }
+
+    #[test]
+    fn should_check_memory_against_limit_pages_rather_than_initial_memory() {
+        let within_limit = format!("(module (memory {}))", DEFAULT_MEMORY_LIMIT_PAGES);
+        preprocess_wat(&within_limit, DEFAULT_MEMORY_LIMIT_PAGES).expect("should preprocess");
+
+        let above_initial_memory = format!("(module (memory {}))", DEFAULT_INITIAL_MEMORY + 1);
+        preprocess_wat(&above_initial_memory, DEFAULT_MEMORY_LIMIT_PAGES)
+            .expect("should preprocess");
+
+        let above_limit = format!("(module (memory {}))", DEFAULT_MEMORY_LIMIT_PAGES + 1);
+        assert_matches!(
+            preprocess_wat(&above_limit, DEFAULT_MEMORY_LIMIT_PAGES),
+            Err(PreprocessingError::MemoryLimitExceeded { pages, limit })
+                if pages == DEFAULT_MEMORY_LIMIT_PAGES + 1 && limit == DEFAULT_MEMORY_LIMIT_PAGES
+        );
+    }
+}
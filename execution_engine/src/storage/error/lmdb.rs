@@ -1,5 +1,6 @@
 use std::sync;
 
+use datasize::DataSize;
 use lmdb as lmdb_external;
 use thiserror::Error;
 
@@ -21,6 +22,18 @@ pub enum Error {
 
 impl wasmi::HostError for Error {}
 
+impl DataSize for Error {
+    const IS_DYNAMIC: bool = false;
+
+    const STATIC_HEAP_SIZE: usize = 0;
+
+    // None of the variants own heap allocations.
+    #[inline]
+    fn estimate_heap_size(&self) -> usize {
+        0
+    }
+}
+
 impl From<bytesrepr::Error> for Error {
     fn from(error: bytesrepr::Error) -> Self {
         Error::BytesRepr(error)
@@ -1,12 +1,124 @@
 use std::{
     collections::BTreeSet,
     convert::{TryFrom, TryInto},
+    fmt::{self, Display, Formatter},
 };
 
-use casper_execution_engine::core::engine_state::deploy_item::DeployItem;
-use casper_types::account::AccountHash;
+use grpc::{GrpcMessageError, GrpcStatus};
 
-use crate::engine_server::{ipc, mappings::MappingError};
+use casper_execution_engine::core::engine_state::{
+    deploy_item::DeployItem, engine_config::DEFAULT_MAX_DEPLOY_ARGS_SIZE_BYTES,
+};
+use casper_types::account::{AccountHash, ACCOUNT_HASH_LENGTH};
+
+use crate::engine_server::{
+    ipc::{self, DeployPayload_oneof_payload},
+    mappings::MappingError,
+};
+
+/// The reason a protobuf `DeployItem` failed validation.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    /// The payment payload is missing.
+    MissingPayment,
+    /// The payment payload is module bytes with no code.
+    EmptyPayment,
+    /// The session payload is missing, or is module bytes with no code.
+    EmptySession,
+    /// The serialized args of the session or payment payload are too large.
+    ArgsTooLarge { actual: usize, max: usize },
+    /// The account hash is not 32 bytes long.
+    InvalidAccountHashLength { expected: usize, actual: usize },
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ValidationError::MissingPayment => write!(f, "Missing payment"),
+            ValidationError::EmptyPayment => write!(f, "Payment module bytes must be non-empty"),
+            ValidationError::EmptySession => write!(
+                f,
+                "Session must be non-empty module bytes or a stored contract"
+            ),
+            ValidationError::ArgsTooLarge { actual, max } => write!(
+                f,
+                "Args too large: maximum {} bytes, actual {}",
+                max, actual
+            ),
+            ValidationError::InvalidAccountHashLength { expected, actual } => write!(
+                f,
+                "Invalid account hash length: expected {}, actual {}",
+                expected, actual
+            ),
+        }
+    }
+}
+
+impl From<ValidationError> for GrpcMessageError {
+    fn from(error: ValidationError) -> Self {
+        GrpcMessageError {
+            grpc_status: GrpcStatus::InvalidArgument as i32,
+            grpc_message: error.to_string(),
+        }
+    }
+}
+
+/// Checks the fields of a protobuf `DeployItem` which can be validated without executing it.
+///
+/// The serialized args of the session and payment may not exceed the engine's default limit of
+/// `DEFAULT_MAX_DEPLOY_ARGS_SIZE_BYTES`.
+pub fn validate_deploy_item(proto: &ipc::DeployItem) -> Result<(), ValidationError> {
+    let payment = match proto.get_payment().payload.as_ref() {
+        None => return Err(ValidationError::MissingPayment),
+        Some(DeployPayload_oneof_payload::deploy_code(deploy_code))
+            if deploy_code.get_code().is_empty() =>
+        {
+            return Err(ValidationError::EmptyPayment)
+        }
+        Some(payment) => payment,
+    };
+
+    let session = match proto.get_session().payload.as_ref() {
+        None => return Err(ValidationError::EmptySession),
+        Some(DeployPayload_oneof_payload::deploy_code(deploy_code))
+            if deploy_code.get_code().is_empty() =>
+        {
+            return Err(ValidationError::EmptySession)
+        }
+        Some(session) => session,
+    };
+
+    for payload in &[payment, session] {
+        let actual = args_length(payload);
+        if actual > DEFAULT_MAX_DEPLOY_ARGS_SIZE_BYTES {
+            return Err(ValidationError::ArgsTooLarge {
+                actual,
+                max: DEFAULT_MAX_DEPLOY_ARGS_SIZE_BYTES,
+            });
+        }
+    }
+
+    let actual = proto.get_address().len();
+    if actual != ACCOUNT_HASH_LENGTH {
+        return Err(ValidationError::InvalidAccountHashLength {
+            expected: ACCOUNT_HASH_LENGTH,
+            actual,
+        });
+    }
+
+    Ok(())
+}
+
+fn args_length(payload: &DeployPayload_oneof_payload) -> usize {
+    match payload {
+        DeployPayload_oneof_payload::deploy_code(inner) => inner.get_args().len(),
+        DeployPayload_oneof_payload::stored_contract_hash(inner) => inner.get_args().len(),
+        DeployPayload_oneof_payload::stored_contract_name(inner) => inner.get_args().len(),
+        DeployPayload_oneof_payload::stored_package_by_name(inner) => inner.get_args().len(),
+        DeployPayload_oneof_payload::stored_package_by_hash(inner) => inner.get_args().len(),
+        DeployPayload_oneof_payload::transfer(inner) => inner.get_args().len(),
+    }
+}
 
 impl TryFrom<ipc::DeployItem> for DeployItem {
     type Error = MappingError;
@@ -71,3 +183,116 @@ impl From<DeployItem> for ipc::DeployItem {
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine_server::ipc::DeployPayload;
+
+    fn valid_deploy_item() -> ipc::DeployItem {
+        let mut deploy_item = ipc::DeployItem::new();
+        deploy_item.set_address(vec![1; ACCOUNT_HASH_LENGTH]);
+        deploy_item
+            .mut_session()
+            .mut_deploy_code()
+            .set_code(vec![0, 97, 115, 109]);
+        deploy_item
+            .mut_payment()
+            .mut_deploy_code()
+            .set_code(vec![0, 97, 115, 109]);
+        deploy_item
+            .mut_payment()
+            .mut_deploy_code()
+            .set_args(vec![1; 16]);
+        deploy_item
+    }
+
+    #[test]
+    fn should_accept_valid_deploy_item() {
+        assert_eq!(validate_deploy_item(&valid_deploy_item()), Ok(()));
+    }
+
+    #[test]
+    fn should_accept_stored_contract_session() {
+        let mut deploy_item = valid_deploy_item();
+        let mut session = DeployPayload::new();
+        session
+            .mut_stored_contract_name()
+            .set_name("contract".to_string());
+        deploy_item.set_session(session);
+        assert_eq!(validate_deploy_item(&deploy_item), Ok(()));
+    }
+
+    #[test]
+    fn should_reject_missing_payment() {
+        let mut deploy_item = valid_deploy_item();
+        deploy_item.clear_payment();
+        assert_eq!(
+            validate_deploy_item(&deploy_item),
+            Err(ValidationError::MissingPayment)
+        );
+    }
+
+    #[test]
+    fn should_reject_empty_payment() {
+        let mut deploy_item = valid_deploy_item();
+        deploy_item.mut_payment().mut_deploy_code().clear_code();
+        assert_eq!(
+            validate_deploy_item(&deploy_item),
+            Err(ValidationError::EmptyPayment)
+        );
+    }
+
+    #[test]
+    fn should_reject_empty_session() {
+        let mut deploy_item = valid_deploy_item();
+        deploy_item.mut_session().mut_deploy_code().clear_code();
+        assert_eq!(
+            validate_deploy_item(&deploy_item),
+            Err(ValidationError::EmptySession)
+        );
+
+        deploy_item.clear_session();
+        assert_eq!(
+            validate_deploy_item(&deploy_item),
+            Err(ValidationError::EmptySession)
+        );
+    }
+
+    #[test]
+    fn should_reject_oversized_args() {
+        let mut deploy_item = valid_deploy_item();
+        let args = vec![0; DEFAULT_MAX_DEPLOY_ARGS_SIZE_BYTES + 1];
+        deploy_item.mut_session().mut_deploy_code().set_args(args);
+        assert_eq!(
+            validate_deploy_item(&deploy_item),
+            Err(ValidationError::ArgsTooLarge {
+                actual: DEFAULT_MAX_DEPLOY_ARGS_SIZE_BYTES + 1,
+                max: DEFAULT_MAX_DEPLOY_ARGS_SIZE_BYTES
+            })
+        );
+    }
+
+    #[test]
+    fn should_reject_invalid_account_hash_length() {
+        let mut deploy_item = valid_deploy_item();
+        deploy_item.set_address(vec![1; ACCOUNT_HASH_LENGTH - 1]);
+        assert_eq!(
+            validate_deploy_item(&deploy_item),
+            Err(ValidationError::InvalidAccountHashLength {
+                expected: ACCOUNT_HASH_LENGTH,
+                actual: ACCOUNT_HASH_LENGTH - 1
+            })
+        );
+    }
+
+    #[test]
+    fn should_map_validation_error_to_invalid_argument() {
+        let error = GrpcMessageError::from(ValidationError::EmptyPayment);
+        assert_eq!(error.grpc_status, GrpcStatus::InvalidArgument as i32);
+        assert_eq!(
+            error.grpc_message,
+            ValidationError::EmptyPayment.to_string()
+        );
+    }
+}
@@ -14,7 +14,9 @@ use crate::engine_server::ipc::{DeployError_OutOfGasError, DeployResult};
 impl From<ExecutionResult> for DeployResult {
     fn from(execution_result: ExecutionResult) -> DeployResult {
         match execution_result {
-            ExecutionResult::Success { effect, cost } => detail::execution_success(effect, cost),
+            ExecutionResult::Success { effect, cost, .. } => {
+                detail::execution_success(effect, cost)
+            }
             ExecutionResult::Failure {
                 error,
                 effect,
@@ -41,7 +43,12 @@ impl From<(EngineStateError, ExecutionEffect, Gas)> for DeployResult {
             | error @ EngineStateError::InvalidKeyVariant(_)
             | error @ EngineStateError::Authorization
             | error @ EngineStateError::InvalidDeployItemVariant(_)
-            | error @ EngineStateError::InvalidUpgradeResult => {
+            | error @ EngineStateError::InvalidUpgradeResult
+            | error @ EngineStateError::DeployTooLarge { .. }
+            | error @ EngineStateError::DeployArgsTooLarge { .. }
+            | error @ EngineStateError::GenesisValidation(_)
+            | error @ EngineStateError::Genesis(_)
+            | error @ EngineStateError::RootNotFound(_) => {
                 detail::precondition_error(error.to_string())
             }
             EngineStateError::Storage(storage_error) => {
@@ -172,6 +179,7 @@ mod tests {
         let execution_result = ExecutionResult::Success {
             effect: execution_effect,
             cost,
+            execution_trace: Default::default(),
         };
         let mut ipc_deploy_result: DeployResult = execution_result.into();
         assert!(ipc_deploy_result.has_execution_result());
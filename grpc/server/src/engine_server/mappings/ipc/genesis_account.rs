@@ -82,6 +82,19 @@ mod tests {
         >(genesis_account);
     }
 
+    #[test]
+    fn round_trip_secp256k1_account() {
+        let genesis_account = GenesisAccount::secp256k1(
+            [42; 33],
+            Motes::new(U512::max_value()),
+            Motes::new(U512::one()),
+        );
+        test_utils::protobuf_round_trip::<
+            GenesisAccount,
+            ChainSpec_GenesisConfig_ExecConfig_GenesisAccount,
+        >(genesis_account);
+    }
+
     #[test]
     fn round_trip_system_account() {
         let genesis_account = GenesisAccount::system(
@@ -1,11 +1,35 @@
 use std::convert::{TryFrom, TryInto};
 
-use casper_execution_engine::core::engine_state::era_validators::GetEraValidatorsRequest;
-use casper_types::{auction::ValidatorWeights, bytesrepr::ToBytes};
+use casper_execution_engine::core::engine_state::era_validators::GetEraValidatorsRangeRequest;
+use casper_types::{
+    auction::{EraValidators, ValidatorWeights},
+    bytesrepr::{self, ToBytes},
+    PublicKey, U512,
+};
 
-use crate::engine_server::{ipc, mappings::MappingError};
+use crate::engine_server::{
+    ipc::{self, GetEraValidatorsRequest_oneof_eras},
+    mappings::MappingError,
+};
 
-impl TryFrom<ipc::GetEraValidatorsRequest> for GetEraValidatorsRequest {
+impl From<GetEraValidatorsRangeRequest> for ipc::GetEraValidatorsRequest {
+    fn from(get_era_validators_request: GetEraValidatorsRangeRequest) -> Self {
+        let era_ids = get_era_validators_request.era_ids();
+        let mut pb_era_range = ipc::GetEraValidatorsRequest_EraRange::new();
+        pb_era_range.set_start_era_id(*era_ids.start());
+        pb_era_range.set_end_era_id(*era_ids.end());
+
+        let mut pb_get_era_validators_request = ipc::GetEraValidatorsRequest::new();
+        pb_get_era_validators_request
+            .set_parent_state_hash(get_era_validators_request.state_hash().to_vec());
+        pb_get_era_validators_request
+            .set_protocol_version(get_era_validators_request.protocol_version().into());
+        pb_get_era_validators_request.set_era_range(pb_era_range);
+        pb_get_era_validators_request
+    }
+}
+
+impl TryFrom<ipc::GetEraValidatorsRequest> for GetEraValidatorsRangeRequest {
     type Error = MappingError;
 
     fn try_from(
@@ -16,13 +40,28 @@ impl TryFrom<ipc::GetEraValidatorsRequest> for GetEraValidatorsRequest {
             .try_into()
             .map_err(|_| MappingError::InvalidStateHash("parent_state_hash".to_string()))?;
 
-        let era_id = pb_get_era_validators_request.era_id;
+        let era_ids = match pb_get_era_validators_request.eras.take() {
+            Some(GetEraValidatorsRequest_oneof_eras::era_id(era_id)) => era_id..=era_id,
+            Some(GetEraValidatorsRequest_oneof_eras::era_range(pb_era_range)) => {
+                let start_era_id = pb_era_range.start_era_id;
+                let end_era_id = pb_era_range.end_era_id;
+                if start_era_id > end_era_id {
+                    return Err(MappingError::InvalidEraRange {
+                        start_era_id,
+                        end_era_id,
+                    });
+                }
+                start_era_id..=end_era_id
+            }
+            // Clients predating era ranges don't send an `era_id` of 0.
+            None => 0..=0,
+        };
 
         let protocol_version = pb_get_era_validators_request.take_protocol_version().into();
 
-        Ok(GetEraValidatorsRequest::new(
+        Ok(GetEraValidatorsRangeRequest::new(
             pre_state_hash,
-            era_id,
+            era_ids,
             protocol_version,
         ))
     }
@@ -48,3 +87,128 @@ impl TryFrom<ValidatorWeights> for ipc::GetEraValidatorsResponse_ValidatorWeight
         Ok(pb_validator_weights)
     }
 }
+
+impl TryFrom<ipc::GetEraValidatorsResponse_ValidatorWeights> for ValidatorWeights {
+    type Error = MappingError;
+
+    fn try_from(
+        mut pb_validator_weights: ipc::GetEraValidatorsResponse_ValidatorWeights,
+    ) -> Result<Self, Self::Error> {
+        let mut validator_weights = ValidatorWeights::new();
+
+        for mut pb_validator_weight in pb_validator_weights.take_validator_weights().into_vec() {
+            let public_key: PublicKey =
+                bytesrepr::deserialize(pb_validator_weight.take_public_key_bytes())?;
+            let weight = U512::try_from(pb_validator_weight.take_weight())?;
+            let _ = validator_weights.insert(public_key, weight);
+        }
+
+        Ok(validator_weights)
+    }
+}
+
+impl TryFrom<EraValidators> for ipc::GetEraValidatorsResponse_EraValidators {
+    type Error = MappingError;
+
+    fn try_from(era_validators: EraValidators) -> Result<Self, Self::Error> {
+        let mut pb_era_validators = ipc::GetEraValidatorsResponse_EraValidators::new();
+
+        for (era_id, validator_weights) in era_validators {
+            let _ = pb_era_validators
+                .mut_era_validators()
+                .insert(era_id, validator_weights.try_into()?);
+        }
+
+        Ok(pb_era_validators)
+    }
+}
+
+impl TryFrom<ipc::GetEraValidatorsResponse_EraValidators> for EraValidators {
+    type Error = MappingError;
+
+    fn try_from(
+        mut pb_era_validators: ipc::GetEraValidatorsResponse_EraValidators,
+    ) -> Result<Self, Self::Error> {
+        pb_era_validators
+            .take_era_validators()
+            .into_iter()
+            .map(|(era_id, pb_validator_weights)| Ok((era_id, pb_validator_weights.try_into()?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_execution_engine::shared::newtypes::Blake2bHash;
+    use casper_types::ProtocolVersion;
+
+    use super::*;
+    use crate::engine_server::mappings::test_utils;
+
+    fn state_hash() -> Blake2bHash {
+        Blake2bHash::new(&[1, 2, 3])
+    }
+
+    #[test]
+    fn request_round_trip() {
+        let request =
+            GetEraValidatorsRangeRequest::new(state_hash(), 3..=5, ProtocolVersion::V1_0_0);
+        test_utils::protobuf_round_trip::<_, ipc::GetEraValidatorsRequest>(request);
+    }
+
+    #[test]
+    fn should_map_single_era_to_range() {
+        let mut pb_request = ipc::GetEraValidatorsRequest::new();
+        pb_request.set_parent_state_hash(state_hash().to_vec());
+        pb_request.set_protocol_version(ProtocolVersion::V1_0_0.into());
+        pb_request.set_era_id(7);
+
+        let request = GetEraValidatorsRangeRequest::try_from(pb_request.clone()).unwrap();
+        assert_eq!(request.era_ids(), 7..=7);
+
+        // A request without eras asks for era 0.
+        pb_request.eras = None;
+        let request = GetEraValidatorsRangeRequest::try_from(pb_request).unwrap();
+        assert_eq!(request.era_ids(), 0..=0);
+    }
+
+    #[test]
+    fn should_reject_reversed_era_range() {
+        let mut pb_request: ipc::GetEraValidatorsRequest =
+            GetEraValidatorsRangeRequest::new(state_hash(), 3..=5, ProtocolVersion::V1_0_0).into();
+        let pb_era_range = pb_request.mut_era_range();
+        pb_era_range.set_start_era_id(5);
+        pb_era_range.set_end_era_id(3);
+
+        assert_eq!(
+            GetEraValidatorsRangeRequest::try_from(pb_request),
+            Err(MappingError::InvalidEraRange {
+                start_era_id: 5,
+                end_era_id: 3
+            })
+        );
+    }
+
+    #[test]
+    fn era_validators_round_trip() {
+        let mut era_validators = EraValidators::new();
+        for era_id in 0..3 {
+            let mut validator_weights = ValidatorWeights::new();
+            for byte in 0..3u8 {
+                let public_key = PublicKey::Ed25519([byte; 32]);
+                let weight = U512::from(u64::from(byte) * 1_000 + era_id);
+                let _ = validator_weights.insert(public_key, weight);
+            }
+            let _ = era_validators.insert(era_id, validator_weights);
+        }
+        // An era without validators is kept as well.
+        let _ = era_validators.insert(3, ValidatorWeights::new());
+
+        let pb_era_validators =
+            ipc::GetEraValidatorsResponse_EraValidators::try_from(era_validators.clone()).unwrap();
+        assert_eq!(
+            EraValidators::try_from(pb_era_validators),
+            Ok(era_validators)
+        );
+    }
+}
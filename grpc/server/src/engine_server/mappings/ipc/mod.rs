@@ -18,3 +18,5 @@ mod step;
 mod storage_costs;
 mod upgrade_request;
 mod wasm_config;
+
+pub use deploy_item::{validate_deploy_item, ValidationError};
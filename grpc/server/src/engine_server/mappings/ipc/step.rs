@@ -52,8 +52,9 @@ impl TryFrom<ipc::RewardItem> for RewardItem {
         let validator_id: PublicKey =
             bytesrepr::deserialize(bytes).map_err(MappingError::Serialization)?;
         let value: u64 = pb_reward_item.get_value();
+        let blocks_proposed: u64 = pb_reward_item.get_blocks_proposed();
 
-        Ok(RewardItem::new(validator_id, value))
+        Ok(RewardItem::new(validator_id, value, blocks_proposed))
     }
 }
 
@@ -64,6 +65,8 @@ impl TryFrom<RewardItem> for ipc::RewardItem {
         let mut result = ipc::RewardItem::new();
         let bytes = reward_item.validator_id.to_bytes()?;
         result.set_validator_id(bytes);
+        result.set_value(reward_item.value);
+        result.set_blocks_proposed(reward_item.blocks_proposed);
         Ok(result)
     }
 }
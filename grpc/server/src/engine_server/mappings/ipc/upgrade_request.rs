@@ -1,9 +1,70 @@
 use std::convert::{TryFrom, TryInto};
 
-use casper_execution_engine::core::engine_state::upgrade::UpgradeConfig;
-use casper_types::ProtocolVersion;
+use casper_execution_engine::core::engine_state::upgrade::{AuctionUpgradeConfig, UpgradeConfig};
+use casper_types::{CLValue, ProtocolVersion};
 
-use crate::engine_server::{ipc::UpgradeRequest, mappings::MappingError};
+use crate::engine_server::{
+    ipc::{ChainSpec_AuctionUpgrade, ChainSpec_NewEraCount, ChainSpec_NewNamedKey, UpgradeRequest},
+    mappings::MappingError,
+};
+
+impl From<AuctionUpgradeConfig> for ChainSpec_AuctionUpgrade {
+    fn from(auction_upgrade_config: AuctionUpgradeConfig) -> Self {
+        let new_era_count = |era_count| {
+            let mut pb_new_era_count = ChainSpec_NewEraCount::new();
+            pb_new_era_count.set_era_count(era_count);
+            pb_new_era_count
+        };
+
+        let mut pb_auction_upgrade = ChainSpec_AuctionUpgrade::new();
+        if let Some(new_unbonding_delay) = auction_upgrade_config.new_unbonding_delay() {
+            pb_auction_upgrade.set_new_unbonding_delay(new_era_count(new_unbonding_delay));
+        }
+        if let Some(new_locked_funds_period) = auction_upgrade_config.new_locked_funds_period() {
+            pb_auction_upgrade.set_new_locked_funds_period(new_era_count(new_locked_funds_period));
+        }
+        for (name, value) in auction_upgrade_config.new_named_keys() {
+            let mut pb_new_named_key = ChainSpec_NewNamedKey::new();
+            pb_new_named_key.set_name(name.clone());
+            pb_new_named_key.set_value(value.clone().into());
+            pb_auction_upgrade
+                .mut_new_named_keys()
+                .push(pb_new_named_key);
+        }
+        pb_auction_upgrade
+    }
+}
+
+impl TryFrom<ChainSpec_AuctionUpgrade> for AuctionUpgradeConfig {
+    type Error = MappingError;
+
+    fn try_from(mut pb_auction_upgrade: ChainSpec_AuctionUpgrade) -> Result<Self, Self::Error> {
+        let new_unbonding_delay = if !pb_auction_upgrade.has_new_unbonding_delay() {
+            None
+        } else {
+            Some(pb_auction_upgrade.get_new_unbonding_delay().era_count)
+        };
+        let new_locked_funds_period = if !pb_auction_upgrade.has_new_locked_funds_period() {
+            None
+        } else {
+            Some(pb_auction_upgrade.get_new_locked_funds_period().era_count)
+        };
+        let new_named_keys = pb_auction_upgrade
+            .take_new_named_keys()
+            .into_iter()
+            .map(|mut pb_new_named_key| {
+                let value = CLValue::try_from(pb_new_named_key.take_value())?;
+                Ok((pb_new_named_key.take_name(), value))
+            })
+            .collect::<Result<_, MappingError>>()?;
+
+        Ok(AuctionUpgradeConfig::new(
+            new_unbonding_delay,
+            new_locked_funds_period,
+            new_named_keys,
+        ))
+    }
+}
 
 impl TryFrom<UpgradeRequest> for UpgradeConfig {
     type Error = MappingError;
@@ -51,6 +112,12 @@ impl TryFrom<UpgradeRequest> for UpgradeConfig {
             )
         };
 
+        let auction_upgrade_config = if !upgrade_point.has_auction_upgrade() {
+            AuctionUpgradeConfig::default()
+        } else {
+            upgrade_point.take_auction_upgrade().try_into()?
+        };
+
         Ok(UpgradeConfig::new(
             pre_state_hash,
             current_protocol_version,
@@ -60,6 +127,28 @@ impl TryFrom<UpgradeRequest> for UpgradeConfig {
             wasm_config,
             activation_point,
             new_validator_slots,
+            auction_upgrade_config,
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::engine_server::mappings::test_utils;
+
+    #[test]
+    fn auction_upgrade_round_trip() {
+        let mut new_named_keys = BTreeMap::new();
+        let _ = new_named_keys.insert("new_map".to_string(), CLValue::from_t(7u64).unwrap());
+        for auction_upgrade_config in vec![
+            AuctionUpgradeConfig::default(),
+            AuctionUpgradeConfig::new(Some(3), None, BTreeMap::new()),
+            AuctionUpgradeConfig::new(Some(3), Some(5), new_named_keys),
+        ] {
+            test_utils::protobuf_round_trip::<_, ChainSpec_AuctionUpgrade>(auction_upgrade_config);
+        }
+    }
+}
@@ -13,6 +13,7 @@ use std::{
 use casper_execution_engine::core::{engine_state, DEPLOY_HASH_LENGTH};
 use casper_types::{account::ACCOUNT_HASH_LENGTH, bytesrepr, KEY_HASH_LENGTH};
 
+pub use ipc::{validate_deploy_item, ValidationError};
 pub use transforms::TransformMap;
 
 /// Try to convert a `Vec<u8>` to a 32-byte array.
@@ -34,6 +35,7 @@ pub enum MappingError {
     MissingPayload,
     TryFromSlice,
     Serialization(bytesrepr::Error),
+    InvalidEraRange { start_era_id: u64, end_era_id: u64 },
 }
 
 impl MappingError {
@@ -105,6 +107,14 @@ impl Display for MappingError {
                 expected, actual
             ),
             MappingError::Serialization(error) => write!(f, "{}", error),
+            MappingError::InvalidEraRange {
+                start_era_id,
+                end_era_id,
+            } => write!(
+                f,
+                "Invalid era range: start {} is after end {}",
+                start_era_id, end_era_id
+            ),
         }
     }
 }
@@ -15,6 +15,7 @@ include!(concat!(
     "/../../../../generated_protobuf/transforms.rs"
 ));
 pub mod mappings;
+mod timeout;
 
 use std::{
     collections::BTreeMap,
@@ -25,13 +26,13 @@ use std::{
     marker::{Send, Sync},
 };
 
-use grpc::{RequestOptions, ServerBuilder, SingleResponse};
+use grpc::{GrpcMessageError, RequestOptions, ServerBuilder, SingleResponse};
 use log::{info, warn, Level};
 
 use casper_execution_engine::{
     core::{
         engine_state::{
-            era_validators::{GetEraValidatorsError, GetEraValidatorsRequest},
+            era_validators::{GetEraValidatorsError, GetEraValidatorsRangeRequest},
             execute_request::ExecuteRequest,
             genesis::GenesisResult,
             query::{QueryRequest, QueryResult},
@@ -60,6 +61,8 @@ use self::{
 };
 use casper_execution_engine::core::engine_state::step::StepRequest;
 
+pub use self::timeout::{TimeoutConfig, TimeoutService};
+
 // Idea is that Engine will represent the core of the execution engine project.
 // It will act as an entry point for execution of Wasm binaries.
 // Proto definitions should be translated into domain objects when Engine's API
@@ -113,6 +116,25 @@ where
                 result.set_failure(msg);
                 result
             }
+            Ok(QueryResult::TypeMismatch(msg)) => {
+                info!("{}", msg);
+                let mut result = ipc::QueryResponse::new();
+                result.set_failure(msg);
+                result
+            }
+            Ok(QueryResult::PathTooLong {
+                path_length,
+                max_path_length,
+            }) => {
+                let log_message = format!(
+                    "Query path of length {} exceeds the maximum of {}",
+                    path_length, max_path_length
+                );
+                info!("{}", log_message);
+                let mut result = ipc::QueryResponse::new();
+                result.set_failure(log_message);
+                result
+            }
             Ok(QueryResult::RootNotFound) => {
                 let log_message = "Root not found";
                 info!("{}", log_message);
@@ -145,6 +167,15 @@ where
     ) -> SingleResponse<ExecuteResponse> {
         let correlation_id = CorrelationId::new();
 
+        for deploy_item in exec_request.get_deploys() {
+            if let Err(error) = mappings::validate_deploy_item(deploy_item) {
+                warn!("invalid deploy item: {}", error);
+                return SingleResponse::err(grpc::Error::GrpcMessage(GrpcMessageError::from(
+                    error,
+                )));
+            }
+        }
+
         let exec_request: ExecuteRequest = match exec_request.try_into() {
             Ok(ret) => ret,
             Err(err) => {
@@ -341,8 +372,12 @@ where
             Ok(UpgradeResult::Success {
                 post_state_hash,
                 effect,
+                auction_changes,
             }) => {
-                info!("upgrade successful: {}", post_state_hash);
+                info!(
+                    "upgrade successful: {} {:?}",
+                    post_state_hash, auction_changes
+                );
                 let mut ret = UpgradeResponse::new();
                 let upgrade_result = ret.mut_success();
                 upgrade_result.set_post_state_hash(post_state_hash.to_vec());
@@ -377,7 +412,7 @@ where
     ) -> SingleResponse<ipc::GetEraValidatorsResponse> {
         let correlation_id = CorrelationId::new();
 
-        let get_era_validators_request: GetEraValidatorsRequest =
+        let get_era_validators_request: GetEraValidatorsRangeRequest =
             match get_era_validators_request.try_into() {
                 Ok(result) => result,
                 Err(error) => {
@@ -393,18 +428,16 @@ where
 
         let mut response = ipc::GetEraValidatorsResponse::new();
 
-        match self.get_era_validators(correlation_id, get_era_validators_request) {
-            Ok(Some(validator_weights)) => {
-                match ipc::GetEraValidatorsResponse_ValidatorWeights::try_from(validator_weights) {
-                    Ok(pb_validator_weights) => response.set_success(pb_validator_weights),
+        match self.get_era_validators_range(correlation_id, get_era_validators_request) {
+            Ok(era_validators) => {
+                match ipc::GetEraValidatorsResponse_EraValidators::try_from(era_validators) {
+                    Ok(pb_era_validators) => response.set_success(pb_era_validators),
                     Err(mapping_error) => {
                         response.mut_error().set_message(mapping_error.to_string())
                     }
                 }
             }
 
-            Ok(None) => {}
-
             Err(GetEraValidatorsError::RootNotFound) => response
                 .mut_missing_prestate()
                 .set_hash(pre_state_hash.to_vec()),
@@ -475,6 +508,7 @@ where
 pub fn new<E: ExecutionEngineService + Sync + Send + 'static>(
     socket: &str,
     thread_count: usize,
+    timeout_config: TimeoutConfig,
     e: E,
 ) -> ServerBuilder {
     let socket_path = std::path::Path::new(socket);
@@ -488,6 +522,8 @@ pub fn new<E: ExecutionEngineService + Sync + Send + 'static>(
     let mut server = ServerBuilder::new_plain();
     server.http.set_unix_addr(socket.to_owned()).unwrap();
     server.http.set_cpu_pool_threads(thread_count);
-    server.add_service(ExecutionEngineServiceServer::new_service_def(e));
+    server.add_service(ExecutionEngineServiceServer::new_service_def(
+        TimeoutService::new(e, thread_count, timeout_config),
+    ));
     server
 }
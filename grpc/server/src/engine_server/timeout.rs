@@ -0,0 +1,436 @@
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use grpc::{GrpcMessageError, GrpcStatus, RequestOptions, SingleResponse};
+use log::warn;
+
+use super::{ipc, ipc_grpc::ExecutionEngineService};
+
+const REQUEST_TIMED_OUT: &str = "request timed out";
+
+/// Time limits applied to each request handled by the gRPC server.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TimeoutConfig {
+    /// Read-only requests taking longer than this many milliseconds fail with `DEADLINE_EXCEEDED`.
+    /// `0` disables the timeout.
+    pub request_timeout_ms: u64,
+    /// Requests taking at least this many milliseconds are logged as slow.  `0` disables the
+    /// warning.
+    pub slow_request_threshold_ms: u64,
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// A fixed number of worker threads running jobs in the order they were submitted.
+struct WorkerPool {
+    sender: Mutex<Sender<Job>>,
+}
+
+impl WorkerPool {
+    fn new(thread_count: usize) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for _ in 0..thread_count.max(1) {
+            let receiver = Arc::clone(&receiver);
+            thread::spawn(move || Self::run_worker(&receiver));
+        }
+        WorkerPool {
+            sender: Mutex::new(sender),
+        }
+    }
+
+    /// Runs jobs until the pool is dropped.
+    fn run_worker(receiver: &Mutex<Receiver<Job>>) {
+        loop {
+            let job = match receiver.lock() {
+                Ok(receiver) => receiver.recv(),
+                Err(_) => return,
+            };
+            match job {
+                // A panicking job drops its response sender, which the caller reports as a panic.
+                // The worker itself keeps running.
+                Ok(job) => {
+                    let _ = panic::catch_unwind(AssertUnwindSafe(job));
+                }
+                Err(_) => return,
+            }
+        }
+    }
+
+    fn submit(&self, job: Job) {
+        let sender = self
+            .sender
+            .lock()
+            .expect("worker pool sender lock poisoned");
+        sender
+            .send(job)
+            .expect("worker pool threads should outlive the pool");
+    }
+}
+
+/// Wraps an `ExecutionEngineService`, failing read-only requests with `DEADLINE_EXCEEDED` if they
+/// don't complete within the configured timeout.
+///
+/// Timed requests (`query`, `execute` and `get_era_validators`) run on a pool of `thread_count`
+/// worker threads.  The engine provides no way of cancelling a request, so a timed out request
+/// keeps its worker until it completes, and its response is discarded.  This is harmless since
+/// these requests don't modify global state.
+///
+/// Requests which modify global state (`commit`, `run_genesis`, `upgrade` and `step`) are never
+/// timed out, so a client is never told that one failed while it goes on to modify the state.
+pub struct TimeoutService<E> {
+    inner: Arc<E>,
+    config: TimeoutConfig,
+    pool: Option<WorkerPool>,
+}
+
+impl<E> TimeoutService<E>
+where
+    E: ExecutionEngineService + Send + Sync + 'static,
+{
+    pub fn new(inner: E, thread_count: usize, config: TimeoutConfig) -> Self {
+        let pool = if config.request_timeout_ms == 0 {
+            None
+        } else {
+            Some(WorkerPool::new(thread_count))
+        };
+        TimeoutService {
+            inner: Arc::new(inner),
+            config,
+            pool,
+        }
+    }
+
+    /// Handles a read-only request, failing it if it doesn't complete within the timeout.
+    fn call<T, F>(&self, method: &'static str, handler: F) -> SingleResponse<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&E) -> SingleResponse<T> + Send + 'static,
+    {
+        let pool = match self.pool.as_ref() {
+            Some(pool) => pool,
+            None => return self.call_without_timeout(method, handler),
+        };
+
+        let start = Instant::now();
+        let inner = Arc::clone(&self.inner);
+        let (sender, receiver) = mpsc::channel();
+        pool.submit(Box::new(move || {
+            // If the request timed out, the receiver is gone and the response is discarded.
+            let _ = sender.send(handler(&inner));
+        }));
+
+        let response =
+            match receiver.recv_timeout(Duration::from_millis(self.config.request_timeout_ms)) {
+                Ok(response) => response,
+                Err(RecvTimeoutError::Timeout) => {
+                    warn!(
+                        "{} request timed out after {} ms",
+                        method, self.config.request_timeout_ms
+                    );
+                    return SingleResponse::err(grpc::Error::GrpcMessage(GrpcMessageError {
+                        grpc_status: GrpcStatus::DeadlineExceeded as i32,
+                        grpc_message: REQUEST_TIMED_OUT.to_string(),
+                    }));
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    return SingleResponse::err(grpc::Error::Panic(format!(
+                        "{} request handler panicked",
+                        method
+                    )));
+                }
+            };
+
+        self.warn_if_slow(method, start);
+        response
+    }
+
+    /// Handles a request on the calling thread, without a timeout.
+    fn call_without_timeout<T, F>(&self, method: &'static str, handler: F) -> SingleResponse<T>
+    where
+        F: FnOnce(&E) -> SingleResponse<T>,
+    {
+        let start = Instant::now();
+        let response = handler(&self.inner);
+        self.warn_if_slow(method, start);
+        response
+    }
+
+    fn warn_if_slow(&self, method: &'static str, start: Instant) {
+        let elapsed = start.elapsed();
+        if self.config.slow_request_threshold_ms != 0
+            && elapsed >= Duration::from_millis(self.config.slow_request_threshold_ms)
+        {
+            warn!(
+                "slow {} request took {} ms; request timeout is {} ms",
+                method,
+                elapsed.as_millis(),
+                self.config.request_timeout_ms
+            );
+        }
+    }
+}
+
+impl<E> ExecutionEngineService for TimeoutService<E>
+where
+    E: ExecutionEngineService + Send + Sync + 'static,
+{
+    fn query(
+        &self,
+        request_options: RequestOptions,
+        query_request: ipc::QueryRequest,
+    ) -> SingleResponse<ipc::QueryResponse> {
+        self.call("query", move |inner| {
+            inner.query(request_options, query_request)
+        })
+    }
+
+    fn execute(
+        &self,
+        request_options: RequestOptions,
+        exec_request: ipc::ExecuteRequest,
+    ) -> SingleResponse<ipc::ExecuteResponse> {
+        self.call("execute", move |inner| {
+            inner.execute(request_options, exec_request)
+        })
+    }
+
+    fn commit(
+        &self,
+        request_options: RequestOptions,
+        commit_request: ipc::CommitRequest,
+    ) -> SingleResponse<ipc::CommitResponse> {
+        self.call_without_timeout("commit", move |inner| {
+            inner.commit(request_options, commit_request)
+        })
+    }
+
+    fn run_genesis(
+        &self,
+        request_options: RequestOptions,
+        run_genesis_request: ipc::RunGenesisRequest,
+    ) -> SingleResponse<ipc::GenesisResponse> {
+        self.call_without_timeout("run_genesis", move |inner| {
+            inner.run_genesis(request_options, run_genesis_request)
+        })
+    }
+
+    fn upgrade(
+        &self,
+        request_options: RequestOptions,
+        upgrade_request: ipc::UpgradeRequest,
+    ) -> SingleResponse<ipc::UpgradeResponse> {
+        self.call_without_timeout("upgrade", move |inner| {
+            inner.upgrade(request_options, upgrade_request)
+        })
+    }
+
+    fn get_era_validators(
+        &self,
+        request_options: RequestOptions,
+        get_era_validators_request: ipc::GetEraValidatorsRequest,
+    ) -> SingleResponse<ipc::GetEraValidatorsResponse> {
+        self.call("get_era_validators", move |inner| {
+            inner.get_era_validators(request_options, get_era_validators_request)
+        })
+    }
+
+    fn step(
+        &self,
+        request_options: RequestOptions,
+        step_request: ipc::StepRequest,
+    ) -> SingleResponse<ipc::StepResponse> {
+        self.call_without_timeout("step", move |inner| {
+            inner.step(request_options, step_request)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    /// A service which sleeps for `delay` before responding to any request, recording the largest
+    /// number of requests it handled at the same time.
+    struct SleepingService {
+        delay: Duration,
+        running: AtomicUsize,
+        max_running: Arc<AtomicUsize>,
+    }
+
+    impl SleepingService {
+        fn respond<T: Default + Send + 'static>(&self) -> SingleResponse<T> {
+            let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_running.fetch_max(running, Ordering::SeqCst);
+            thread::sleep(self.delay);
+            self.running.fetch_sub(1, Ordering::SeqCst);
+            SingleResponse::completed(T::default())
+        }
+    }
+
+    impl ExecutionEngineService for SleepingService {
+        fn query(
+            &self,
+            _: RequestOptions,
+            _: ipc::QueryRequest,
+        ) -> SingleResponse<ipc::QueryResponse> {
+            self.respond()
+        }
+
+        fn execute(
+            &self,
+            _: RequestOptions,
+            _: ipc::ExecuteRequest,
+        ) -> SingleResponse<ipc::ExecuteResponse> {
+            self.respond()
+        }
+
+        fn commit(
+            &self,
+            _: RequestOptions,
+            _: ipc::CommitRequest,
+        ) -> SingleResponse<ipc::CommitResponse> {
+            self.respond()
+        }
+
+        fn run_genesis(
+            &self,
+            _: RequestOptions,
+            _: ipc::RunGenesisRequest,
+        ) -> SingleResponse<ipc::GenesisResponse> {
+            self.respond()
+        }
+
+        fn upgrade(
+            &self,
+            _: RequestOptions,
+            _: ipc::UpgradeRequest,
+        ) -> SingleResponse<ipc::UpgradeResponse> {
+            self.respond()
+        }
+
+        fn get_era_validators(
+            &self,
+            _: RequestOptions,
+            _: ipc::GetEraValidatorsRequest,
+        ) -> SingleResponse<ipc::GetEraValidatorsResponse> {
+            self.respond()
+        }
+
+        fn step(
+            &self,
+            _: RequestOptions,
+            _: ipc::StepRequest,
+        ) -> SingleResponse<ipc::StepResponse> {
+            self.respond()
+        }
+    }
+
+    fn timeout_service_with_threads(
+        delay_ms: u64,
+        request_timeout_ms: u64,
+        thread_count: usize,
+    ) -> (TimeoutService<SleepingService>, Arc<AtomicUsize>) {
+        let config = TimeoutConfig {
+            request_timeout_ms,
+            slow_request_threshold_ms: request_timeout_ms / 2,
+        };
+        let max_running = Arc::new(AtomicUsize::new(0));
+        let service = TimeoutService::new(
+            SleepingService {
+                delay: Duration::from_millis(delay_ms),
+                running: AtomicUsize::new(0),
+                max_running: Arc::clone(&max_running),
+            },
+            thread_count,
+            config,
+        );
+        (service, max_running)
+    }
+
+    fn timeout_service(delay_ms: u64, request_timeout_ms: u64) -> TimeoutService<SleepingService> {
+        timeout_service_with_threads(delay_ms, request_timeout_ms, 2).0
+    }
+
+    fn assert_deadline_exceeded<T: std::fmt::Debug>(result: Result<T, grpc::Error>) {
+        match result {
+            Err(grpc::Error::GrpcMessage(error)) => {
+                assert_eq!(error.grpc_status, GrpcStatus::DeadlineExceeded as i32);
+                assert_eq!(error.grpc_message, REQUEST_TIMED_OUT);
+            }
+            other => panic!("expected DEADLINE_EXCEEDED, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn should_fail_request_exceeding_timeout_with_deadline_exceeded() {
+        let service = timeout_service(1_000, 50);
+        let result = service
+            .execute(RequestOptions::new(), ipc::ExecuteRequest::new())
+            .wait_drop_metadata();
+        assert_deadline_exceeded(result);
+    }
+
+    #[test]
+    fn should_not_time_out_state_modifying_requests() {
+        let service = timeout_service(100, 10);
+        service
+            .commit(RequestOptions::new(), ipc::CommitRequest::new())
+            .wait_drop_metadata()
+            .expect("commit should not time out");
+        service
+            .run_genesis(RequestOptions::new(), ipc::RunGenesisRequest::new())
+            .wait_drop_metadata()
+            .expect("genesis should not time out");
+        service
+            .upgrade(RequestOptions::new(), ipc::UpgradeRequest::new())
+            .wait_drop_metadata()
+            .expect("upgrade should not time out");
+        service
+            .step(RequestOptions::new(), ipc::StepRequest::new())
+            .wait_drop_metadata()
+            .expect("step should not time out");
+    }
+
+    #[test]
+    fn should_run_timed_out_requests_on_bounded_pool() {
+        let (service, max_running) = timeout_service_with_threads(100, 10, 1);
+        for _ in 0..3 {
+            let result = service
+                .query(RequestOptions::new(), ipc::QueryRequest::new())
+                .wait_drop_metadata();
+            assert_deadline_exceeded(result);
+        }
+        // Let the queued requests run to completion.
+        thread::sleep(Duration::from_millis(400));
+        assert_eq!(max_running.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn should_respond_to_request_within_timeout() {
+        let service = timeout_service(0, 1_000);
+        let response = service
+            .query(RequestOptions::new(), ipc::QueryRequest::new())
+            .wait_drop_metadata()
+            .expect("should respond within timeout");
+        assert_eq!(response, ipc::QueryResponse::new());
+    }
+
+    #[test]
+    fn should_not_time_out_when_disabled() {
+        let service = timeout_service(100, 0);
+        service
+            .step(RequestOptions::new(), ipc::StepRequest::new())
+            .wait_drop_metadata()
+            .expect("should respond when timeout is disabled");
+    }
+}
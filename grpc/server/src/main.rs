@@ -15,7 +15,7 @@ use dirs::home_dir;
 use lmdb::DatabaseFlags;
 use log::{error, info, Level, LevelFilter};
 
-use casper_engine_grpc_server::engine_server;
+use casper_engine_grpc_server::engine_server::{self, TimeoutConfig};
 use casper_execution_engine::{
     core::engine_state::{EngineConfig, EngineState},
     shared::{
@@ -96,6 +96,22 @@ const ARG_THREAD_COUNT_VALUE: &str = "NUM";
 const ARG_THREAD_COUNT_HELP: &str = "Worker thread count";
 const ARG_THREAD_COUNT_EXPECT: &str = "expected valid thread count";
 
+// request timeouts
+const ARG_REQUEST_TIMEOUT_MS: &str = "request-timeout-ms";
+const ARG_REQUEST_TIMEOUT_MS_DEFAULT: &str = "0";
+const ARG_REQUEST_TIMEOUT_MS_VALUE: &str = "MILLISECONDS";
+const ARG_REQUEST_TIMEOUT_MS_HELP: &str =
+    "Fails query, execute and get_era_validators requests taking longer than this with \
+    DEADLINE_EXCEEDED.  Requests which modify global state are never timed out.  0 disables the \
+    timeout";
+const ARG_REQUEST_TIMEOUT_MS_EXPECT: &str = "expected valid request timeout";
+const ARG_SLOW_REQUEST_THRESHOLD_MS: &str = "slow-request-threshold-ms";
+const ARG_SLOW_REQUEST_THRESHOLD_MS_DEFAULT: &str = "0";
+const ARG_SLOW_REQUEST_THRESHOLD_MS_VALUE: &str = "MILLISECONDS";
+const ARG_SLOW_REQUEST_THRESHOLD_MS_HELP: &str =
+    "Logs a warning for requests taking at least this long.  0 disables the warning";
+const ARG_SLOW_REQUEST_THRESHOLD_MS_EXPECT: &str = "expected valid slow request threshold";
+
 // use system contracts
 const ARG_USE_SYSTEM_CONTRACTS: &str = "use-system-contracts";
 const ARG_USE_SYSTEM_CONTRACTS_SHORT: &str = "z";
@@ -128,9 +144,18 @@ fn main() {
 
     let thread_count = get_thread_count(&arg_matches);
 
+    let timeout_config = get_timeout_config(&arg_matches);
+
     let engine_config: EngineConfig = get_engine_config(&arg_matches);
 
-    let _server = get_grpc_server(&socket, data_dir, map_size, thread_count, engine_config);
+    let _server = get_grpc_server(
+        &socket,
+        data_dir,
+        map_size,
+        thread_count,
+        timeout_config,
+        engine_config,
+    );
 
     log_listening_message(&socket);
 
@@ -222,6 +247,22 @@ fn get_args() -> ArgMatches<'static> {
                 .value_name(ARG_THREAD_COUNT_VALUE)
                 .help(ARG_THREAD_COUNT_HELP),
         )
+        .arg(
+            Arg::with_name(ARG_REQUEST_TIMEOUT_MS)
+                .long(ARG_REQUEST_TIMEOUT_MS)
+                .takes_value(true)
+                .default_value(ARG_REQUEST_TIMEOUT_MS_DEFAULT)
+                .value_name(ARG_REQUEST_TIMEOUT_MS_VALUE)
+                .help(ARG_REQUEST_TIMEOUT_MS_HELP),
+        )
+        .arg(
+            Arg::with_name(ARG_SLOW_REQUEST_THRESHOLD_MS)
+                .long(ARG_SLOW_REQUEST_THRESHOLD_MS)
+                .takes_value(true)
+                .default_value(ARG_SLOW_REQUEST_THRESHOLD_MS_DEFAULT)
+                .value_name(ARG_SLOW_REQUEST_THRESHOLD_MS_VALUE)
+                .help(ARG_SLOW_REQUEST_THRESHOLD_MS_HELP),
+        )
         .arg(
             Arg::with_name(ARG_USE_SYSTEM_CONTRACTS)
                 .short(ARG_USE_SYSTEM_CONTRACTS_SHORT)
@@ -288,6 +329,24 @@ fn get_thread_count(arg_matches: &ArgMatches) -> usize {
         .expect(ARG_THREAD_COUNT_EXPECT)
 }
 
+/// Returns a [`TimeoutConfig`].
+fn get_timeout_config(arg_matches: &ArgMatches) -> TimeoutConfig {
+    let request_timeout_ms = arg_matches
+        .value_of(ARG_REQUEST_TIMEOUT_MS)
+        .map(str::parse)
+        .expect(ARG_REQUEST_TIMEOUT_MS_EXPECT)
+        .expect(ARG_REQUEST_TIMEOUT_MS_EXPECT);
+    let slow_request_threshold_ms = arg_matches
+        .value_of(ARG_SLOW_REQUEST_THRESHOLD_MS)
+        .map(str::parse)
+        .expect(ARG_SLOW_REQUEST_THRESHOLD_MS_EXPECT)
+        .expect(ARG_SLOW_REQUEST_THRESHOLD_MS_EXPECT);
+    TimeoutConfig {
+        request_timeout_ms,
+        slow_request_threshold_ms,
+    }
+}
+
 /// Returns an [`EngineConfig`].
 fn get_engine_config(arg_matches: &ArgMatches) -> EngineConfig {
     // feature flags go here
@@ -301,11 +360,12 @@ fn get_grpc_server(
     data_dir: PathBuf,
     map_size: usize,
     thread_count: usize,
+    timeout_config: TimeoutConfig,
     engine_config: EngineConfig,
 ) -> grpc::Server {
     let engine_state = get_engine_state(data_dir, map_size, engine_config);
 
-    engine_server::new(socket.as_str(), thread_count, engine_state)
+    engine_server::new(socket.as_str(), thread_count, timeout_config, engine_state)
         .build()
         .expect(SERVER_START_EXPECT)
 }
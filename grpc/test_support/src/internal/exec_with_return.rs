@@ -105,6 +105,7 @@ where
         gas_counter,
         fn_store_id,
         address_generator,
+        None,
         protocol_version,
         correlation_id,
         phase,
@@ -117,7 +118,7 @@ where
         args: Vec::new(),
     };
 
-    let wasm_config = *DEFAULT_WASM_CONFIG;
+    let wasm_config = config.apply_wasm_limits(*DEFAULT_WASM_CONFIG);
 
     let preprocessor = Preprocessor::new(wasm_config);
     let parity_module = builder
@@ -132,9 +133,12 @@ where
         )
         .expect("should get wasm module");
 
-    let (instance, memory) =
-        runtime::instance_and_memory(parity_module.clone().take_module(), protocol_version)
-            .expect("should be able to make wasm instance from module");
+    let (instance, memory) = runtime::instance_and_memory(
+        parity_module.clone().take_module(),
+        protocol_version,
+        wasm_config.initial_memory,
+    )
+    .expect("should be able to make wasm instance from module");
 
     let mut runtime = Runtime::new(
         config,
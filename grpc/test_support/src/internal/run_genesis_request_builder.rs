@@ -0,0 +1,107 @@
+use casper_execution_engine::{
+    core::engine_state::{
+        genesis::{ExecConfig, GenesisAccount},
+        run_genesis_request::RunGenesisRequest,
+    },
+    shared::wasm_config::WasmConfig,
+};
+use casper_types::auction::{
+    AUCTION_DELAY, DEFAULT_BID_EVICTION_DELAY, DEFAULT_LOCKED_FUNDS_PERIOD, DEFAULT_UNBONDING_DELAY,
+};
+
+use super::{
+    DEFAULT_ACCOUNTS, DEFAULT_EXEC_CONFIG, DEFAULT_GENESIS_CONFIG_HASH, DEFAULT_PROTOCOL_VERSION,
+    DEFAULT_VALIDATOR_SLOTS, DEFAULT_WASM_CONFIG,
+};
+
+/// Builds a `RunGenesisRequest`, defaulting to the same values as `DEFAULT_RUN_GENESIS_REQUEST`.
+pub struct RunGenesisRequestBuilder {
+    accounts: Vec<GenesisAccount>,
+    wasm_config: WasmConfig,
+    validator_slots: u32,
+    auction_delay: u64,
+    unbonding_delay: u64,
+    bid_eviction_delay: u64,
+    locked_funds_period: u64,
+}
+
+impl RunGenesisRequestBuilder {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn with_accounts(mut self, accounts: Vec<GenesisAccount>) -> Self {
+        self.accounts = accounts;
+        self
+    }
+
+    pub fn with_wasm_config(mut self, wasm_config: WasmConfig) -> Self {
+        self.wasm_config = wasm_config;
+        self
+    }
+
+    pub fn with_validator_slots(mut self, validator_slots: u32) -> Self {
+        self.validator_slots = validator_slots;
+        self
+    }
+
+    pub fn with_auction_delay(mut self, auction_delay: u64) -> Self {
+        self.auction_delay = auction_delay;
+        self
+    }
+
+    pub fn with_unbonding_delay(mut self, unbonding_delay: u64) -> Self {
+        self.unbonding_delay = unbonding_delay;
+        self
+    }
+
+    pub fn with_bid_eviction_delay(mut self, bid_eviction_delay: u64) -> Self {
+        self.bid_eviction_delay = bid_eviction_delay;
+        self
+    }
+
+    pub fn with_locked_funds_period(mut self, locked_funds_period: u64) -> Self {
+        self.locked_funds_period = locked_funds_period;
+        self
+    }
+
+    pub fn build(self) -> RunGenesisRequest {
+        let mut exec_config = ExecConfig::new(
+            DEFAULT_EXEC_CONFIG.mint_installer_bytes().to_vec(),
+            DEFAULT_EXEC_CONFIG
+                .proof_of_stake_installer_bytes()
+                .to_vec(),
+            DEFAULT_EXEC_CONFIG
+                .standard_payment_installer_bytes()
+                .to_vec(),
+            DEFAULT_EXEC_CONFIG.auction_installer_bytes().to_vec(),
+            self.accounts,
+            self.wasm_config,
+            self.validator_slots,
+        );
+        exec_config.set_auction_delay(self.auction_delay);
+        exec_config.set_unbonding_delay(self.unbonding_delay);
+        exec_config.set_bid_eviction_delay(self.bid_eviction_delay);
+        exec_config.set_locked_funds_period(self.locked_funds_period);
+
+        RunGenesisRequest::new(
+            *DEFAULT_GENESIS_CONFIG_HASH,
+            *DEFAULT_PROTOCOL_VERSION,
+            exec_config,
+        )
+    }
+}
+
+impl Default for RunGenesisRequestBuilder {
+    fn default() -> Self {
+        RunGenesisRequestBuilder {
+            accounts: DEFAULT_ACCOUNTS.clone(),
+            wasm_config: *DEFAULT_WASM_CONFIG,
+            validator_slots: DEFAULT_VALIDATOR_SLOTS,
+            auction_delay: AUCTION_DELAY,
+            unbonding_delay: DEFAULT_UNBONDING_DELAY,
+            bid_eviction_delay: DEFAULT_BID_EVICTION_DELAY,
+            locked_funds_period: DEFAULT_LOCKED_FUNDS_PERIOD,
+        }
+    }
+}
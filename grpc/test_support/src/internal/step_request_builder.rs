@@ -28,6 +28,7 @@ impl TryFrom<SlashItem> for ipc::SlashItem {
 pub struct RewardItem {
     validator_id: PublicKey,
     value: u64,
+    blocks_proposed: u64,
 }
 
 #[allow(dead_code)]
@@ -36,8 +37,14 @@ impl RewardItem {
         RewardItem {
             validator_id,
             value,
+            blocks_proposed: 0,
         }
     }
+
+    pub fn with_blocks_proposed(mut self, blocks_proposed: u64) -> Self {
+        self.blocks_proposed = blocks_proposed;
+        self
+    }
 }
 
 impl TryFrom<RewardItem> for ipc::RewardItem {
@@ -48,6 +55,7 @@ impl TryFrom<RewardItem> for ipc::RewardItem {
         let mut item = ipc::RewardItem::new();
         item.set_validator_id(validator_id);
         item.set_value(reward_item.value);
+        item.set_blocks_proposed(reward_item.blocks_proposed);
         Ok(item)
     }
 }
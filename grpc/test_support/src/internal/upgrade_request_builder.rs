@@ -1,11 +1,13 @@
 use casper_engine_grpc_server::engine_server::{
     ipc::{
-        ChainSpec_ActivationPoint, ChainSpec_NewValidatorSlots, ChainSpec_UpgradePoint,
-        ChainSpec_WasmConfig, DeployCode, UpgradeRequest,
+        ChainSpec_ActivationPoint, ChainSpec_AuctionUpgrade, ChainSpec_NewValidatorSlots,
+        ChainSpec_UpgradePoint, ChainSpec_WasmConfig, DeployCode, UpgradeRequest,
     },
     state,
 };
-use casper_execution_engine::shared::wasm_config::WasmConfig;
+use casper_execution_engine::{
+    core::engine_state::upgrade::AuctionUpgradeConfig, shared::wasm_config::WasmConfig,
+};
 use casper_types::ProtocolVersion;
 
 pub struct UpgradeRequestBuilder {
@@ -16,6 +18,7 @@ pub struct UpgradeRequestBuilder {
     new_wasm_config: Option<ChainSpec_WasmConfig>,
     activation_point: ChainSpec_ActivationPoint,
     new_validator_slots: Option<u32>,
+    auction_upgrade: Option<ChainSpec_AuctionUpgrade>,
 }
 
 impl UpgradeRequestBuilder {
@@ -43,6 +46,14 @@ impl UpgradeRequestBuilder {
         self
     }
 
+    pub fn with_auction_upgrade_config(
+        mut self,
+        auction_upgrade_config: AuctionUpgradeConfig,
+    ) -> Self {
+        self.auction_upgrade = Some(auction_upgrade_config.into());
+        self
+    }
+
     pub fn with_installer_code(mut self, upgrade_installer: DeployCode) -> Self {
         self.upgrade_installer = upgrade_installer;
         self
@@ -76,6 +87,9 @@ impl UpgradeRequestBuilder {
                 upgrade_point.set_new_validator_slots(chainspec_new_validator_slots);
             }
         }
+        if let Some(auction_upgrade) = self.auction_upgrade {
+            upgrade_point.set_auction_upgrade(auction_upgrade);
+        }
         upgrade_point.set_protocol_version(self.new_protocol_version);
         upgrade_point.set_upgrade_installer(self.upgrade_installer);
 
@@ -96,6 +110,7 @@ impl Default for UpgradeRequestBuilder {
             new_wasm_config: None,
             activation_point: Default::default(),
             new_validator_slots: Default::default(),
+            auction_upgrade: None,
         }
     }
 }
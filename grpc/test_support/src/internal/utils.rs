@@ -7,18 +7,21 @@ use std::{
 use lazy_static::lazy_static;
 
 use casper_execution_engine::{
-    core::engine_state::{
-        execution_result::ExecutionResult,
-        genesis::{ExecConfig, GenesisAccount, GenesisConfig},
-        run_genesis_request::RunGenesisRequest,
-        Error,
+    core::{
+        engine_state::{
+            execution_result::ExecutionResult,
+            genesis::{ExecConfig, GenesisAccount, GenesisConfig},
+            run_genesis_request::RunGenesisRequest,
+            Error,
+        },
+        execution,
     },
     shared::{
         account::Account, additive_map::AdditiveMap, gas::Gas, stored_value::StoredValue,
         transform::Transform,
     },
 };
-use casper_types::Key;
+use casper_types::{system_contract_errors, ApiError, Key};
 
 use crate::internal::{
     AUCTION_INSTALL_CONTRACT, DEFAULT_CHAIN_NAME, DEFAULT_GENESIS_CONFIG_HASH,
@@ -198,6 +201,23 @@ pub fn get_error_message<T: AsRef<ExecutionResult>, I: IntoIterator<Item = T>>(
     errors.join("\n")
 }
 
+/// Returns the [`ApiError`] equivalent of `error` if it was caused by a revert or by an error
+/// returned from a system contract, otherwise `None`.
+pub fn get_api_error(error: &Error) -> Option<ApiError> {
+    match error {
+        Error::Exec(execution::Error::Revert(api_error)) => Some(*api_error),
+        Error::Exec(execution::Error::SystemContract(system_contract_error)) => {
+            let api_error = match *system_contract_error {
+                system_contract_errors::Error::Mint(error) => ApiError::from(error),
+                system_contract_errors::Error::Pos(error) => ApiError::from(error),
+                system_contract_errors::Error::Auction(error) => ApiError::from(error),
+            };
+            Some(api_error)
+        }
+        _ => None,
+    }
+}
+
 #[allow(clippy::implicit_hasher)]
 pub fn get_account(transforms: &AdditiveMap<Key, Transform>, account: &Key) -> Option<Account> {
     transforms.get(account).and_then(|transform| {
@@ -208,3 +228,44 @@ pub fn get_account(transforms: &AdditiveMap<Key, Transform>, account: &Key) -> O
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use casper_types::system_contract_errors::{auction, mint};
+
+    use super::*;
+
+    #[test]
+    fn should_get_api_error_from_revert() {
+        let error = Error::Exec(execution::Error::Revert(ApiError::User(100)));
+        assert_eq!(get_api_error(&error), Some(ApiError::User(100)));
+    }
+
+    #[test]
+    fn should_get_api_error_from_system_contract_error() {
+        let error = Error::Exec(execution::Error::SystemContract(
+            auction::Error::BondTooSmall.into(),
+        ));
+        assert_eq!(
+            get_api_error(&error),
+            Some(ApiError::from(auction::Error::BondTooSmall))
+        );
+
+        let error = Error::Exec(execution::Error::SystemContract(
+            mint::Error::InsufficientFunds.into(),
+        ));
+        assert_eq!(
+            get_api_error(&error),
+            Some(ApiError::from(mint::Error::InsufficientFunds))
+        );
+    }
+
+    #[test]
+    fn should_not_get_api_error_from_other_failures() {
+        assert_eq!(
+            get_api_error(&Error::Exec(execution::Error::GasLimit)),
+            None
+        );
+        assert_eq!(get_api_error(&Error::InsufficientPayment), None);
+    }
+}
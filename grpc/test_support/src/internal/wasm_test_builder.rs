@@ -2,6 +2,7 @@ use std::{
     convert::{TryFrom, TryInto},
     ffi::OsStr,
     fs,
+    ops::RangeInclusive,
     path::PathBuf,
     rc::Rc,
     sync::Arc,
@@ -24,8 +25,10 @@ use casper_engine_grpc_server::engine_server::{
 use casper_execution_engine::{
     core::{
         engine_state::{
-            era_validators::GetEraValidatorsRequest, execute_request::ExecuteRequest,
-            execution_result::ExecutionResult, run_genesis_request::RunGenesisRequest,
+            era_validators::{GetEraValidatorsRangeRequest, GetEraValidatorsRequest},
+            execute_request::ExecuteRequest,
+            execution_result::ExecutionResult,
+            run_genesis_request::RunGenesisRequest,
             EngineConfig, EngineState, SYSTEM_ACCOUNT_ADDR,
         },
         execution,
@@ -49,10 +52,14 @@ use casper_execution_engine::{
 };
 use casper_types::{
     account::AccountHash,
-    auction::{EraId, ValidatorWeights},
+    auction::{
+        Delegators, EraId, EraSummary, EraValidators, ValidatorWeights, DELEGATORS_KEY,
+        ERA_SUMMARY_KEY,
+    },
     bytesrepr::{self},
     mint::TOTAL_SUPPLY_KEY,
-    CLTyped, CLValue, Contract, ContractHash, ContractWasm, Key, URef, U512,
+    system_contract_errors::auction,
+    ApiError, CLTyped, CLValue, Contract, ContractHash, ContractWasm, Key, PublicKey, URef, U512,
 };
 
 use crate::internal::{utils, DEFAULT_PROTOCOL_VERSION};
@@ -104,27 +111,9 @@ impl<S> WasmTestBuilder<S> {
 
 impl Default for InMemoryWasmTestBuilder {
     fn default() -> Self {
-        Self::initialize_logging();
         let engine_config =
             EngineConfig::new().with_use_system_contracts(cfg!(feature = "use-system-contracts"));
-
-        let global_state = InMemoryGlobalState::empty().expect("should create global state");
-        let engine_state = EngineState::new(global_state, engine_config);
-
-        WasmTestBuilder {
-            engine_state: Rc::new(engine_state),
-            exec_responses: Vec::new(),
-            upgrade_responses: Vec::new(),
-            genesis_hash: None,
-            post_state_hash: None,
-            transforms: Vec::new(),
-            genesis_account: None,
-            genesis_transforms: None,
-            mint_contract_hash: None,
-            pos_contract_hash: None,
-            standard_payment_hash: None,
-            auction_contract_hash: None,
-        }
+        Self::new_with_config(engine_config)
     }
 }
 
@@ -175,6 +164,28 @@ impl InMemoryWasmTestBuilder {
             ..Default::default()
         }
     }
+
+    /// Creates a builder with an empty global state, using the given engine config.
+    pub fn new_with_config(engine_config: EngineConfig) -> Self {
+        Self::initialize_logging();
+        let global_state = InMemoryGlobalState::empty().expect("should create global state");
+        let engine_state = EngineState::new(global_state, engine_config);
+
+        WasmTestBuilder {
+            engine_state: Rc::new(engine_state),
+            exec_responses: Vec::new(),
+            upgrade_responses: Vec::new(),
+            genesis_hash: None,
+            post_state_hash: None,
+            transforms: Vec::new(),
+            genesis_account: None,
+            genesis_transforms: None,
+            mint_contract_hash: None,
+            pos_contract_hash: None,
+            standard_payment_hash: None,
+            auction_contract_hash: None,
+        }
+    }
 }
 
 impl LmdbWasmTestBuilder {
@@ -551,6 +562,45 @@ where
         self
     }
 
+    /// Asserts that the first result of the last execution failed with `expected`.
+    ///
+    /// Matches both reverts and errors returned by system contracts, panicking with the expected
+    /// and actual errors if the execution succeeded or failed differently.
+    pub fn expect_failure_with_api_error(&mut self, expected: ApiError) -> &mut Self {
+        {
+            let exec_response = self
+                .exec_responses
+                .last()
+                .expect("Expected to be called after run()");
+            let exec_result = exec_response
+                .get(0)
+                .expect("Unable to get first deploy result");
+            let error = exec_result.as_error().unwrap_or_else(|| {
+                panic!(
+                    "Expected execution to fail with {:?}, but it succeeded",
+                    expected
+                )
+            });
+            match utils::get_api_error(error) {
+                Some(actual) if actual == expected => (),
+                Some(actual) => panic!(
+                    "Expected execution to fail with {:?}, but it failed with {:?}",
+                    expected, actual
+                ),
+                None => panic!(
+                    "Expected execution to fail with {:?}, but it failed with {:?}",
+                    expected, error
+                ),
+            }
+        }
+        self
+    }
+
+    /// Asserts that the first result of the last execution failed with the given auction error.
+    pub fn expect_failure_with_auction_error(&mut self, expected: auction::Error) -> &mut Self {
+        self.expect_failure_with_api_error(ApiError::from(expected))
+    }
+
     pub fn is_error(&self) -> bool {
         let exec_response = self
             .exec_responses
@@ -735,6 +785,43 @@ where
             .expect("should get era validators")
     }
 
+    /// Requests the validator weights of each era in `era_ids` via the gRPC service.
+    pub fn get_era_validators_range(&mut self, era_ids: RangeInclusive<EraId>) -> EraValidators {
+        let state_hash = Blake2bHash::try_from(self.get_post_state_hash().as_slice())
+            .expect("should create state hash");
+        let request =
+            GetEraValidatorsRangeRequest::new(state_hash, era_ids, *DEFAULT_PROTOCOL_VERSION);
+
+        let mut response = ExecutionEngineService::get_era_validators(
+            self.engine_state.as_ref(),
+            RequestOptions::new(),
+            request.into(),
+        )
+        .wait_drop_metadata()
+        .expect("should get era validators response");
+
+        if !response.has_success() {
+            panic!("get era validators failure: {:?}", response);
+        }
+        EraValidators::try_from(response.take_success()).expect("should convert era validators")
+    }
+
+    pub fn get_era_summary(&mut self) -> EraSummary {
+        let auction_contract_hash = self.get_auction_contract_hash();
+        self.get_value(auction_contract_hash, ERA_SUMMARY_KEY)
+    }
+
+    /// Returns the amount `delegator` currently has delegated to `validator`, as recorded in the
+    /// auction's `Delegators` map.
+    pub fn get_delegation(&mut self, validator: PublicKey, delegator: PublicKey) -> Option<U512> {
+        let auction_contract_hash = self.get_auction_contract_hash();
+        let delegators: Delegators = self.get_value(auction_contract_hash, DELEGATORS_KEY);
+        delegators
+            .get(&validator)
+            .and_then(|delegated_amounts| delegated_amounts.get(&delegator))
+            .copied()
+    }
+
     pub fn get_value<T>(&mut self, contract_hash: ContractHash, name: &str) -> T
     where
         T: FromBytes + CLTyped,
@@ -751,6 +838,22 @@ where
         let result: T = cl_value.into_t().expect("should convert");
         result
     }
+
+    /// Reads the value stored under the named key `name` of the given account, e.g. the result
+    /// of a contract call which a session code put there.
+    pub fn get_account_value<T>(&mut self, account_hash: AccountHash, name: &str) -> T
+    where
+        T: FromBytes + CLTyped,
+    {
+        let account = self.get_account(account_hash).expect("should have account");
+        let key = account.named_keys().get(name).expect("should have key");
+        let stored_value = self.query(None, *key, &[]).expect("should query");
+        let cl_value = stored_value
+            .as_cl_value()
+            .cloned()
+            .expect("should be cl value");
+        cl_value.into_t().expect("should convert")
+    }
 }
 
 fn create_query_request(post_state: Vec<u8>, base_key: Key, path: Vec<String>) -> QueryRequest {
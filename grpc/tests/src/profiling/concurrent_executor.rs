@@ -68,6 +68,7 @@ const TRANSFER_MODE_ARG_VALUE_NAME: &str = "&str";
 const TRANSFER_MODE_ARG_HELP: &str = "Transfer mode [WASMLESS|WASM]";
 
 const CONTRACT_NAME: &str = "transfer_to_existing_account.wasm";
+const STANDARD_PAYMENT_CONTRACT: &str = "standard_payment.wasm";
 const THREAD_PREFIX: &str = "client-worker-";
 const ARG_AMOUNT: &str = "amount";
 const ARG_TARGET: &str = "target";
@@ -351,13 +352,16 @@ fn new_execute_request(args: &Args) -> ExecuteRequest {
     let deploy_item = match args.transfer_mode {
         TransferMode::WASM => DeployItemBuilder::new()
             .with_address(account_1_addr)
-            .with_empty_payment_bytes(runtime_args! { ARG_AMOUNT => *DEFAULT_PAYMENT })
+            .with_payment_code(
+                STANDARD_PAYMENT_CONTRACT,
+                runtime_args! { ARG_AMOUNT => *DEFAULT_PAYMENT },
+            )
             .with_session_code(CONTRACT_NAME, transfer_args)
             .with_authorization_keys(&[account_1_addr])
             .build(),
         TransferMode::WASMLESS => DeployItemBuilder::new()
             .with_address(account_1_addr)
-            .with_empty_payment_bytes(runtime_args! {})
+            .with_payment_code(STANDARD_PAYMENT_CONTRACT, runtime_args! {})
             .with_transfer_args(transfer_args)
             .with_authorization_keys(&[account_1_addr])
             .build(),
@@ -2,7 +2,7 @@ use casper_engine_test_support::{
     internal::{ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_RUN_GENESIS_REQUEST},
     DEFAULT_ACCOUNT_ADDR,
 };
-use casper_types::RuntimeArgs;
+use casper_types::{system_contract_errors::auction, ApiError, RuntimeArgs};
 
 const REVERT_WASM: &str = "revert.wasm";
 
@@ -18,3 +18,29 @@ fn should_revert() {
         .commit()
         .is_error();
 }
+
+#[ignore]
+#[test]
+fn should_expect_failure_with_revert_code() {
+    let exec_request =
+        ExecuteRequestBuilder::standard(*DEFAULT_ACCOUNT_ADDR, REVERT_WASM, RuntimeArgs::default())
+            .build();
+    InMemoryWasmTestBuilder::default()
+        .run_genesis(&DEFAULT_RUN_GENESIS_REQUEST)
+        .exec(exec_request)
+        .expect_failure_with_api_error(ApiError::User(100))
+        .commit();
+}
+
+#[ignore]
+#[test]
+#[should_panic(expected = "but it failed with ApiError::User(100)")]
+fn should_panic_when_expecting_other_error() {
+    let exec_request =
+        ExecuteRequestBuilder::standard(*DEFAULT_ACCOUNT_ADDR, REVERT_WASM, RuntimeArgs::default())
+            .build();
+    InMemoryWasmTestBuilder::default()
+        .run_genesis(&DEFAULT_RUN_GENESIS_REQUEST)
+        .exec(exec_request)
+        .expect_failure_with_auction_error(auction::Error::BondTooSmall);
+}
@@ -0,0 +1,54 @@
+use casper_engine_test_support::{
+    internal::{ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_RUN_GENESIS_REQUEST},
+    DEFAULT_ACCOUNT_ADDR,
+};
+use casper_execution_engine::shared::newtypes::CorrelationId;
+use casper_types::{account::AccountHash, runtime_args, RuntimeArgs, U512};
+
+const CONTRACT_TRANSFER_TO_ACCOUNT: &str = "transfer_to_account_u512.wasm";
+const ACCOUNT_1_ADDR: AccountHash = AccountHash::new([1u8; 32]);
+const TRANSFER_AMOUNT: u64 = 1_000_000_000;
+
+#[ignore]
+#[test]
+fn should_dry_run_transfer_without_changing_state() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+
+    let post_state_hash = builder.get_post_state_hash();
+    let default_account = builder
+        .get_account(*DEFAULT_ACCOUNT_ADDR)
+        .expect("should have account");
+    let balance_before = builder.get_purse_balance(default_account.main_purse());
+
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT,
+        runtime_args! {
+            "target" => ACCOUNT_1_ADDR,
+            "amount" => U512::from(TRANSFER_AMOUNT),
+        },
+    )
+    .with_pre_state_hash(&post_state_hash)
+    .build();
+    let dry_run_result = builder
+        .get_engine_state()
+        .dry_run_deploy(CorrelationId::new(), exec_request)
+        .expect("should dry run");
+
+    assert!(dry_run_result.would_succeed);
+    assert_eq!(dry_run_result.execution_results.len(), 1);
+    assert!(dry_run_result.estimated_gas > 0);
+    assert_eq!(
+        U512::from(dry_run_result.estimated_gas),
+        dry_run_result.execution_results[0].cost().value()
+    );
+
+    // The effects of the transfer were not committed.
+    assert_eq!(builder.get_post_state_hash(), post_state_hash);
+    assert_eq!(
+        builder.get_purse_balance(default_account.main_purse()),
+        balance_before
+    );
+    assert!(builder.get_account(ACCOUNT_1_ADDR).is_none());
+}
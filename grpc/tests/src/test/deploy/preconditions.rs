@@ -7,7 +7,10 @@ use casper_engine_test_support::{
     },
     DEFAULT_ACCOUNT_ADDR,
 };
-use casper_execution_engine::core::engine_state::Error;
+use casper_execution_engine::core::engine_state::{
+    engine_config::{DEFAULT_MAX_DEPLOY_ARGS_SIZE_BYTES, DEFAULT_MAX_DEPLOY_SIZE_BYTES},
+    Error,
+};
 use casper_types::{account::AccountHash, runtime_args, RuntimeArgs, U512};
 
 const ACCOUNT_1_ADDR: AccountHash = AccountHash::new([42u8; 32]);
@@ -119,3 +122,78 @@ fn should_raise_precondition_authorization_failure_invalid_authorized_keys() {
     let precondition_failure = utils::get_precondition_failure(response);
     assert_matches!(precondition_failure, Error::Authorization);
 }
+
+#[ignore]
+#[test]
+fn should_raise_precondition_deploy_too_large() {
+    let payment_purse_amount = 10_000_000;
+    let oversized_module_bytes = vec![0u8; DEFAULT_MAX_DEPLOY_SIZE_BYTES + 1];
+
+    let exec_request = {
+        let deploy = DeployItemBuilder::new()
+            .with_address(*DEFAULT_ACCOUNT_ADDR)
+            .with_deploy_hash([1; 32])
+            .with_session_bytes(oversized_module_bytes, RuntimeArgs::default())
+            .with_empty_payment_bytes(
+                runtime_args! { ARG_AMOUNT => U512::from(payment_purse_amount) },
+            )
+            .with_authorization_keys(&[*DEFAULT_ACCOUNT_ADDR])
+            .build();
+
+        ExecuteRequestBuilder::new().push_deploy(deploy).build()
+    };
+
+    let result = InMemoryWasmTestBuilder::default()
+        .run_genesis(&DEFAULT_RUN_GENESIS_REQUEST)
+        .exec(exec_request)
+        .finish();
+
+    let response = result
+        .builder()
+        .get_exec_response(0)
+        .expect("there should be a response");
+
+    let precondition_failure = utils::get_precondition_failure(response);
+    assert_matches!(
+        precondition_failure,
+        Error::DeployTooLarge { actual, max }
+        if *actual == DEFAULT_MAX_DEPLOY_SIZE_BYTES + 1 && *max == DEFAULT_MAX_DEPLOY_SIZE_BYTES
+    );
+}
+
+#[ignore]
+#[test]
+fn should_raise_precondition_deploy_args_too_large() {
+    let payment_purse_amount = 10_000_000;
+    let oversized_arg = vec![0u8; DEFAULT_MAX_DEPLOY_ARGS_SIZE_BYTES];
+
+    let exec_request = {
+        let deploy = DeployItemBuilder::new()
+            .with_address(*DEFAULT_ACCOUNT_ADDR)
+            .with_deploy_hash([1; 32])
+            .with_session_code("do_nothing.wasm", runtime_args! { "data" => oversized_arg })
+            .with_empty_payment_bytes(
+                runtime_args! { ARG_AMOUNT => U512::from(payment_purse_amount) },
+            )
+            .with_authorization_keys(&[*DEFAULT_ACCOUNT_ADDR])
+            .build();
+
+        ExecuteRequestBuilder::new().push_deploy(deploy).build()
+    };
+
+    let result = InMemoryWasmTestBuilder::default()
+        .run_genesis(&DEFAULT_RUN_GENESIS_REQUEST)
+        .exec(exec_request)
+        .finish();
+
+    let response = result
+        .builder()
+        .get_exec_response(0)
+        .expect("there should be a response");
+
+    let precondition_failure = utils::get_precondition_failure(response);
+    assert_matches!(
+        precondition_failure,
+        Error::DeployArgsTooLarge { max, .. } if *max == DEFAULT_MAX_DEPLOY_ARGS_SIZE_BYTES
+    );
+}
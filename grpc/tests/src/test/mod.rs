@@ -8,6 +8,8 @@ mod groups;
 mod manage_groups;
 mod regression;
 mod step;
+mod system_contract_metadata_cache;
 mod system_contracts;
 mod upgrade;
+mod wasm_limits;
 mod wasmless_transfer;
@@ -5,7 +5,7 @@ use casper_engine_test_support::internal::{
 };
 use casper_execution_engine::{core::engine_state::GenesisAccount, shared::motes::Motes};
 use casper_types::{
-    account::AccountHash, system_contract_errors::auction, ApiError, PublicKey, RuntimeArgs, U512,
+    account::AccountHash, system_contract_errors::auction, PublicKey, RuntimeArgs, U512,
 };
 
 const CONTRACT_EE_597_REGRESSION: &str = "ee_597_regression.wasm";
@@ -41,26 +41,9 @@ fn should_fail_when_bonding_amount_is_zero_ee_597_regression() {
     )
     .build();
 
-    let result = InMemoryWasmTestBuilder::default()
+    InMemoryWasmTestBuilder::default()
         .run_genesis(&run_genesis_request)
         .exec(exec_request)
-        .commit()
-        .finish();
-
-    let response = result
-        .builder()
-        .get_exec_response(0)
-        .expect("should have a response")
-        .to_owned();
-
-    let error_message = utils::get_error_message(response);
-
-    // Error::BondTooSmall => 5,
-    assert!(
-        error_message.contains(&format!(
-            "{:?}",
-            ApiError::from(auction::Error::BondTooSmall)
-        )),
-        error_message
-    );
+        .expect_failure_with_auction_error(auction::Error::BondTooSmall)
+        .commit();
 }
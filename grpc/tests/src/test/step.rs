@@ -9,8 +9,9 @@ use casper_execution_engine::{
 use casper_types::{
     account::AccountHash,
     auction::{
-        BidPurses, Bids, SeigniorageRecipientsSnapshot, BIDS_KEY, BID_PURSES_KEY, BLOCK_REWARD,
-        SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY, VALIDATOR_REWARD_PURSE,
+        BidPurses, Bids, EraValidators, SeigniorageRecipientsSnapshot, BIDS_KEY, BID_PURSES_KEY,
+        BLOCK_REWARD, ERA_VALIDATORS_KEY, SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY,
+        VALIDATOR_REWARD_PURSE,
     },
     ContractHash, Key, ProtocolVersion, PublicKey,
 };
@@ -140,4 +141,26 @@ fn should_step() {
             .all(|key| after_auction_seigniorage.contains_key(key)),
         "run auction should have changed seigniorage keys"
     );
+
+    // era summary should describe the latest entries of the snapshots
+    let era_summary = builder.get_era_summary();
+    let era_validators: EraValidators = builder.get_value(auction_hash, ERA_VALIDATORS_KEY);
+    let (last_era_id, last_validator_weights) = era_validators
+        .iter()
+        .next_back()
+        .expect("should have era validators");
+    assert_eq!(era_summary.era_id, *last_era_id);
+    assert_eq!(
+        after_auction_seigniorage.keys().next_back(),
+        Some(&era_summary.era_id)
+    );
+    assert_eq!(&era_summary.validator_weights, last_validator_weights);
+    assert_eq!(
+        era_summary.bids_considered,
+        bids_after_slashing.len() as u64
+    );
+    assert_eq!(
+        era_summary.total_staked,
+        last_validator_weights.values().cloned().sum()
+    );
 }
@@ -0,0 +1,118 @@
+use std::convert::TryFrom;
+
+use casper_engine_test_support::{
+    internal::{
+        DeployItemBuilder, ExecuteRequestBuilder, InMemoryWasmTestBuilder, UpgradeRequestBuilder,
+        ARG_AMOUNT, DEFAULT_PAYMENT, DEFAULT_RUN_GENESIS_REQUEST,
+    },
+    DEFAULT_ACCOUNT_ADDR,
+};
+use casper_execution_engine::{
+    core::engine_state::{upgrade::ActivationPoint, EngineConfig},
+    shared::newtypes::Blake2bHash,
+};
+use casper_types::{account::AccountHash, runtime_args, ProtocolVersion, RuntimeArgs, U512};
+
+const CONTRACT_TRANSFER_TO_ACCOUNT: &str = "transfer_to_account_u512.wasm";
+const ACCOUNT_1_ADDR: AccountHash = AccountHash::new([1u8; 32]);
+const TRANSFER_AMOUNT: u64 = 1_000_000_000;
+const TRANSFER_COUNT: u8 = 3;
+const ACTIVATION_POINT: ActivationPoint = 1;
+
+fn engine_config(enable_system_contract_metadata_cache: bool) -> EngineConfig {
+    EngineConfig::new()
+        .with_use_system_contracts(cfg!(feature = "use-system-contracts"))
+        .with_enable_system_contract_metadata_cache(enable_system_contract_metadata_cache)
+}
+
+/// Runs genesis followed by `TRANSFER_COUNT` transfers, each committed on top of the previous one
+/// as the node does within a block.
+fn run_transfers(engine_config: EngineConfig) -> InMemoryWasmTestBuilder {
+    let mut builder = InMemoryWasmTestBuilder::new_with_config(engine_config);
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+
+    for deploy_hash in 0..TRANSFER_COUNT {
+        let deploy = DeployItemBuilder::new()
+            .with_address(*DEFAULT_ACCOUNT_ADDR)
+            .with_session_code(
+                CONTRACT_TRANSFER_TO_ACCOUNT,
+                runtime_args! {
+                    "target" => ACCOUNT_1_ADDR,
+                    "amount" => U512::from(TRANSFER_AMOUNT),
+                },
+            )
+            .with_empty_payment_bytes(runtime_args! { ARG_AMOUNT => *DEFAULT_PAYMENT, })
+            .with_authorization_keys(&[*DEFAULT_ACCOUNT_ADDR])
+            .with_deploy_hash([deploy_hash; 32])
+            .build();
+        let exec_request = ExecuteRequestBuilder::new().push_deploy(deploy).build();
+        builder.exec(exec_request).expect_success().commit();
+    }
+
+    builder
+}
+
+#[ignore]
+#[test]
+fn should_produce_identical_results_with_and_without_cache() {
+    let cached = run_transfers(engine_config(true));
+    let uncached = run_transfers(engine_config(false));
+
+    assert_eq!(cached.get_post_state_hash(), uncached.get_post_state_hash());
+    for index in 0..usize::from(TRANSFER_COUNT) {
+        assert_eq!(cached.exec_costs(index), uncached.exec_costs(index));
+        assert_eq!(
+            cached.get_transforms()[index],
+            uncached.get_transforms()[index]
+        );
+    }
+
+    // After the first transfer, the system contracts are carried forward across each commit.
+    let cache = cached.get_engine_state().system_contract_metadata_cache();
+    assert!(cache.hits() > 0, "expected cache hits");
+    let cache = uncached.get_engine_state().system_contract_metadata_cache();
+    assert_eq!(cache.hits() + cache.misses(), 0);
+    assert!(cache.is_empty());
+}
+
+#[ignore]
+#[test]
+fn should_flush_cache_on_upgrade() {
+    let mut builder = run_transfers(engine_config(true));
+    assert!(!builder
+        .get_engine_state()
+        .system_contract_metadata_cache()
+        .is_empty());
+
+    let sem_ver = ProtocolVersion::V1_0_0.value();
+    let new_protocol_version =
+        ProtocolVersion::from_parts(sem_ver.major, sem_ver.minor, sem_ver.patch + 1);
+    let mut upgrade_request = UpgradeRequestBuilder::new()
+        .with_current_protocol_version(ProtocolVersion::V1_0_0)
+        .with_new_protocol_version(new_protocol_version)
+        .with_activation_point(ACTIVATION_POINT)
+        .build();
+    builder.upgrade_with_upgrade_request(&mut upgrade_request);
+    assert!(builder
+        .get_engine_state()
+        .system_contract_metadata_cache()
+        .is_empty());
+
+    // The auction contract is looked up afresh and cached as of the upgraded state.
+    let misses = builder
+        .get_engine_state()
+        .system_contract_metadata_cache()
+        .misses();
+    let _ = builder.get_era_validators(0);
+    let cache = builder.get_engine_state().system_contract_metadata_cache();
+    assert_eq!(cache.misses(), misses + 1);
+
+    let post_state_hash = Blake2bHash::try_from(builder.get_post_state_hash().as_slice())
+        .expect("should create state hash");
+    let auction_hash = builder.get_auction_contract_hash();
+    let cached_auction = builder
+        .get_engine_state()
+        .system_contract_metadata_cache()
+        .get(post_state_hash, auction_hash);
+    assert_eq!(cached_auction, builder.get_contract(auction_hash));
+}
@@ -4,24 +4,36 @@ use lazy_static::lazy_static;
 
 use casper_engine_test_support::{
     internal::{
-        utils, ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNTS,
-        DEFAULT_RUN_GENESIS_REQUEST,
+        utils, ExecuteRequestBuilder, InMemoryWasmTestBuilder, RunGenesisRequestBuilder,
+        UpgradeRequestBuilder, DEFAULT_ACCOUNTS, DEFAULT_RUN_GENESIS_REQUEST,
     },
     DEFAULT_ACCOUNT_ADDR, DEFAULT_ACCOUNT_INITIAL_BALANCE,
 };
-use casper_execution_engine::{core::engine_state::genesis::GenesisAccount, shared::motes::Motes};
+use casper_execution_engine::{
+    core::engine_state::{
+        execute_request::ExecuteRequest, genesis::GenesisAccount, upgrade::AuctionUpgradeConfig,
+    },
+    shared::motes::Motes,
+};
 use casper_types::{
     self,
     account::AccountHash,
     auction::{
-        Bids, DelegationRate, Delegators, EraId, EraValidators, SeigniorageRecipients,
-        UnbondingPurses, ValidatorWeights, ARG_AMOUNT, ARG_DELEGATION_RATE, ARG_DELEGATOR,
-        ARG_PUBLIC_KEY, ARG_UNBOND_PURSE, ARG_VALIDATOR, AUCTION_DELAY, BIDS_KEY,
-        DEFAULT_LOCKED_FUNDS_PERIOD, DEFAULT_UNBONDING_DELAY, DELEGATORS_KEY, ERA_ID_KEY,
-        ERA_VALIDATORS_KEY, INITIAL_ERA_ID, METHOD_RUN_AUCTION, SNAPSHOT_SIZE,
-        UNBONDING_PURSES_KEY,
+        AuctionEvent, AuctionEvents, Bids, DelegationRate, DelegatorBondingPurses, Delegators,
+        EraId, EraValidators, SeigniorageRecipients, SeigniorageRecipientsSnapshot, SlashFractions,
+        UnbondingPurses, UnbondingTarget, ValidatorWeights, ARG_AMOUNT, ARG_DELEGATION_RATE,
+        ARG_DELEGATOR, ARG_ERA_ID, ARG_PUBLIC_KEY, ARG_SLASH_FRACTIONS, ARG_TARGET_PURSE,
+        ARG_UNBOND_PURSE, ARG_VALIDATOR, ARG_VALIDATOR_PUBLIC_KEYS, AUCTION_DELAY,
+        AUCTION_EVENTS_KEY, BIDS_KEY, DEFAULT_LOCKED_FUNDS_PERIOD, DEFAULT_UNBONDING_DELAY,
+        DELEGATORS_KEY, DELEGATOR_BONDING_PURSES_KEY, ERA_ID_KEY, ERA_VALIDATORS_KEY,
+        INITIAL_ERA_ID, LOCKED_FUNDS_PERIOD_KEY, METHOD_ACTIVATE_BID, METHOD_DEACTIVATE_BID,
+        METHOD_READ_DELEGATION, METHOD_RUN_AUCTION, METHOD_SLASH,
+        SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY, SLASHED_FUNDS_PURSE, SLASH_FRACTION_DENOMINATOR,
+        SNAPSHOT_SIZE, UNBONDING_PURSES_KEY,
     },
-    runtime_args, PublicKey, RuntimeArgs, URef, U512,
+    runtime_args,
+    system_contract_errors::auction::Error as AuctionError,
+    ApiError, ProtocolVersion, PublicKey, RuntimeArgs, URef, U512,
 };
 
 const ARG_ENTRY_POINT: &str = "entry_point";
@@ -60,6 +72,8 @@ const ACCOUNT_1_BOND: u64 = 100_000;
 const ACCOUNT_1_WITHDRAW_1: u64 = 55_000;
 const ACCOUNT_1_WITHDRAW_2: u64 = 45_000;
 
+const LOCKED_FUNDS_PERIOD: EraId = 2;
+
 const ACCOUNT_2_PK: PublicKey = PublicKey::Ed25519([202; 32]);
 const ACCOUNT_2_BALANCE: u64 = 1_000_000_000;
 const ACCOUNT_2_BOND: u64 = 200_000;
@@ -84,6 +98,7 @@ lazy_static! {
 const UNBONDING_PURSE_NAME_1: &str = "unbonding_purse_1";
 const UNBONDING_PURSE_NAME_2: &str = "unbonding_purse_2";
 const ARG_PURSE_NAME: &str = "purse_name";
+const DELEGATION: &str = "delegation";
 
 #[ignore]
 #[test]
@@ -130,7 +145,8 @@ fn should_run_add_bid() {
         U512::from(ADD_BID_AMOUNT_1)
     );
     assert_eq!(active_bid.delegation_rate, ADD_BID_DELEGATION_RATE_1);
-    assert_eq!(active_bid.funds_locked, None);
+    assert!(!active_bid.founding_validator);
+    assert_eq!(active_bid.release_era, None);
 
     // 2nd bid top-up
     let exec_request_2 = ExecuteRequestBuilder::standard(
@@ -209,7 +225,8 @@ fn should_run_add_bid() {
     // `WITHDRAW_BID_AMOUNT_2` is in unbonding list
 
     assert_eq!(
-        unbonding_purse, unbond_list[0].purse,
+        UnbondingTarget::Purse(unbonding_purse),
+        unbond_list[0].target,
         "unbonding queue should have account's unbonding purse"
     );
     assert_eq!(unbond_list[0].amount, U512::from(WITHDRAW_BID_AMOUNT_2),);
@@ -218,6 +235,25 @@ fn should_run_add_bid() {
         unbond_list[0].era_of_withdrawal,
         INITIAL_ERA_ID + DEFAULT_UNBONDING_DELAY,
     );
+
+    let auction_events: AuctionEvents = builder.get_value(auction_hash, AUCTION_EVENTS_KEY);
+    assert_eq!(
+        auction_events,
+        vec![
+            AuctionEvent::BidAdded {
+                validator: BID_ACCOUNT_1_PK,
+                amount: U512::from(ADD_BID_AMOUNT_1),
+            },
+            AuctionEvent::BidAdded {
+                validator: BID_ACCOUNT_1_PK,
+                amount: U512::from(BID_AMOUNT_2),
+            },
+            AuctionEvent::BidWithdrawn {
+                validator: BID_ACCOUNT_1_PK,
+                amount: U512::from(WITHDRAW_BID_AMOUNT_2),
+            },
+        ]
+    );
 }
 
 #[ignore]
@@ -323,6 +359,17 @@ fn should_run_delegate_and_undelegate() {
         delegators
     );
 
+    let delegator_bonding_purses: DelegatorBondingPurses =
+        builder.get_value(auction_hash, DELEGATOR_BONDING_PURSES_KEY);
+    let delegator_bonding_purse = *delegator_bonding_purses
+        .get(&NON_FOUNDER_VALIDATOR_1_PK)
+        .and_then(|bonding_purses| bonding_purses.get(&BID_ACCOUNT_1_PK))
+        .expect("should record delegator bonding purse");
+    assert_eq!(
+        builder.get_purse_balance(delegator_bonding_purse),
+        U512::from(DELEGATE_AMOUNT_1)
+    );
+
     // 2nd bid top-up
     let exec_request_2 = ExecuteRequestBuilder::standard(
         *BID_ACCOUNT_1_ADDR,
@@ -352,6 +399,21 @@ fn should_run_delegate_and_undelegate() {
         delegators
     );
 
+    // The top-up goes to the same bonding purse
+    let delegator_bonding_purses: DelegatorBondingPurses =
+        builder.get_value(auction_hash, DELEGATOR_BONDING_PURSES_KEY);
+    assert_eq!(delegator_bonding_purses.len(), 1);
+    assert_eq!(
+        delegator_bonding_purses
+            .get(&NON_FOUNDER_VALIDATOR_1_PK)
+            .and_then(|bonding_purses| bonding_purses.get(&BID_ACCOUNT_1_PK)),
+        Some(&delegator_bonding_purse)
+    );
+    assert_eq!(
+        builder.get_purse_balance(delegator_bonding_purse),
+        U512::from(DELEGATE_AMOUNT_1 + DELEGATE_AMOUNT_2)
+    );
+
     let exec_request_3 = ExecuteRequestBuilder::standard(
         *BID_ACCOUNT_1_ADDR,
         CONTRACT_UNDELEGATE,
@@ -359,7 +421,7 @@ fn should_run_delegate_and_undelegate() {
             ARG_AMOUNT => U512::from(UNDELEGATE_AMOUNT_1),
             ARG_VALIDATOR => NON_FOUNDER_VALIDATOR_1_PK,
             ARG_DELEGATOR => BID_ACCOUNT_1_PK,
-            ARG_UNBOND_PURSE => Option::<URef>::None,
+            ARG_TARGET_PURSE => Option::<URef>::None,
         },
     )
     .build();
@@ -478,6 +540,13 @@ fn should_calculate_era_validators() {
     let pre_era_id: EraId = builder.get_value(auction_hash, ERA_ID_KEY);
     assert_eq!(pre_era_id, 0);
 
+    let genesis_era_summary = builder.get_era_summary();
+    assert_eq!(genesis_era_summary.era_id, INITIAL_ERA_ID + AUCTION_DELAY);
+    assert_eq!(
+        genesis_era_summary.validator_weights,
+        first_validator_weights
+    );
+
     // non-founding validator request
     let run_auction_request_1 = ExecuteRequestBuilder::standard(
         SYSTEM_ADDR,
@@ -551,6 +620,24 @@ fn should_calculate_era_validators() {
 
     // Make sure looked up era validators are different than initial era validators
     assert_ne!(era_validators_result, first_validator_weights);
+
+    // Era summary should match the entries written to the snapshots by this auction
+    let era_summary = builder.get_era_summary();
+    assert_eq!(era_summary.era_id, lookup_era_id);
+    assert_eq!(era_summary.validator_weights, *validator_weights);
+    assert_eq!(era_summary.bids_considered, 3);
+    assert_eq!(
+        era_summary.total_staked,
+        validator_weights.values().cloned().sum()
+    );
+    let seigniorage_recipients_snapshot: SeigniorageRecipientsSnapshot =
+        builder.get_value(auction_hash, SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY);
+    let seigniorage_recipients = seigniorage_recipients_snapshot
+        .get(&era_summary.era_id)
+        .expect("should have seigniorage recipients for summarized era");
+    assert!(seigniorage_recipients
+        .keys()
+        .eq(era_summary.validator_weights.keys()));
 }
 
 #[ignore]
@@ -596,14 +683,16 @@ fn should_get_first_seigniorage_recipients() {
     assert_eq!(bids.len(), 2);
 
     let founding_validator_1 = bids.get(&ACCOUNT_1_PK).expect("should have account 1 pk");
+    assert!(founding_validator_1.founding_validator);
     assert_eq!(
-        founding_validator_1.funds_locked,
+        founding_validator_1.release_era,
         Some(DEFAULT_LOCKED_FUNDS_PERIOD)
     );
 
     let founding_validator_2 = bids.get(&ACCOUNT_2_PK).expect("should have account 2 pk");
+    assert!(founding_validator_2.founding_validator);
     assert_eq!(
-        founding_validator_2.funds_locked,
+        founding_validator_2.release_era,
         Some(DEFAULT_LOCKED_FUNDS_PERIOD)
     );
 
@@ -678,6 +767,141 @@ fn should_get_first_seigniorage_recipients() {
     assert_eq!(first_validator_weights, validator_weights);
 }
 
+/// Runs genesis with two founding validators and a single auction, returning the builder.
+fn setup_first_auction() -> InMemoryWasmTestBuilder {
+    let accounts = {
+        let mut tmp: Vec<GenesisAccount> = DEFAULT_ACCOUNTS.clone();
+        let account_1 = GenesisAccount::new(
+            ACCOUNT_1_PK,
+            *ACCOUNT_1_ADDR,
+            Motes::new(ACCOUNT_1_BALANCE.into()),
+            Motes::new(ACCOUNT_1_BOND.into()),
+        );
+        let account_2 = GenesisAccount::new(
+            ACCOUNT_2_PK,
+            *ACCOUNT_2_ADDR,
+            Motes::new(ACCOUNT_2_BALANCE.into()),
+            Motes::new(ACCOUNT_2_BOND.into()),
+        );
+        tmp.push(account_1);
+        tmp.push(account_2);
+        tmp
+    };
+
+    let run_genesis_request = utils::create_run_genesis_request(accounts);
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&run_genesis_request);
+
+    let transfer_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT,
+        runtime_args! {
+            "target" => SYSTEM_ADDR,
+            ARG_AMOUNT => U512::from(TRANSFER_AMOUNT)
+        },
+    )
+    .build();
+
+    builder.exec(transfer_request).commit().expect_success();
+
+    let run_auction_request = ExecuteRequestBuilder::standard(
+        SYSTEM_ADDR,
+        CONTRACT_AUCTION_BIDS,
+        runtime_args! {
+            ARG_ENTRY_POINT => ARG_RUN_AUCTION,
+        },
+    )
+    .build();
+
+    builder.exec(run_auction_request).commit().expect_success();
+
+    builder
+}
+
+#[ignore]
+#[test]
+fn should_get_seigniorage_recipients_for_future_era() {
+    let mut builder = setup_first_auction();
+
+    let auction_hash = builder.get_auction_contract_hash();
+    let current_era_id: EraId = builder.get_value(auction_hash, ERA_ID_KEY);
+    let future_era_id = current_era_id + AUCTION_DELAY;
+
+    let read_request = ExecuteRequestBuilder::standard(
+        SYSTEM_ADDR,
+        CONTRACT_AUCTION_BIDS,
+        runtime_args! {
+            ARG_ENTRY_POINT => ARG_READ_SEIGNIORAGE_RECIPIENTS,
+            ARG_ERA_ID => future_era_id,
+        },
+    )
+    .build();
+
+    builder.exec(read_request).commit().expect_success();
+
+    let account = builder.get_account(SYSTEM_ADDR).unwrap();
+    let key = account
+        .named_keys()
+        .get("seigniorage_recipients_result")
+        .copied()
+        .unwrap();
+    let stored_value = builder.query(None, key, &[]).unwrap();
+    let seigniorage_recipients: SeigniorageRecipients = stored_value
+        .as_cl_value()
+        .cloned()
+        .unwrap()
+        .into_t()
+        .unwrap();
+
+    let mut seigniorage_recipients_snapshot: SeigniorageRecipientsSnapshot =
+        builder.get_value(auction_hash, SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY);
+    let expected_seigniorage_recipients = seigniorage_recipients_snapshot
+        .remove(&future_era_id)
+        .expect("snapshot should cover current era plus auction delay");
+    assert_eq!(seigniorage_recipients, expected_seigniorage_recipients);
+}
+
+#[ignore]
+#[test]
+fn should_fail_to_get_seigniorage_recipients_for_era_missing_from_snapshot() {
+    let mut builder = setup_first_auction();
+
+    let auction_hash = builder.get_auction_contract_hash();
+    let current_era_id: EraId = builder.get_value(auction_hash, ERA_ID_KEY);
+    let seigniorage_recipients_snapshot: SeigniorageRecipientsSnapshot =
+        builder.get_value(auction_hash, SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY);
+    let missing_era_id = current_era_id + SNAPSHOT_SIZE as u64 + 1;
+    assert!(!seigniorage_recipients_snapshot.contains_key(&missing_era_id));
+
+    let read_request = ExecuteRequestBuilder::standard(
+        SYSTEM_ADDR,
+        CONTRACT_AUCTION_BIDS,
+        runtime_args! {
+            ARG_ENTRY_POINT => ARG_READ_SEIGNIORAGE_RECIPIENTS,
+            ARG_ERA_ID => missing_era_id,
+        },
+    )
+    .build();
+
+    builder.exec(read_request).commit();
+
+    let response = builder
+        .get_exec_response(2)
+        .expect("should have a response")
+        .to_owned();
+    let error_message = utils::get_error_message(response);
+
+    assert!(
+        error_message.contains(&format!(
+            "{:?}",
+            ApiError::from(AuctionError::MissingEraInSnapshot)
+        )),
+        error_message
+    );
+}
+
 #[ignore]
 #[test]
 fn should_release_founder_stake() {
@@ -754,7 +978,7 @@ fn should_release_founder_stake() {
     let genesis_bids: Bids = builder.get_value(auction_hash, BIDS_KEY);
     assert_eq!(genesis_bids.len(), 1);
     let entry = genesis_bids.get(&ACCOUNT_1_PK).unwrap();
-    assert_eq!(entry.funds_locked, Some(DEFAULT_LOCKED_FUNDS_PERIOD));
+    assert_eq!(entry.release_era, Some(DEFAULT_LOCKED_FUNDS_PERIOD));
 
     builder.exec(transfer_request_1).commit().expect_success();
 
@@ -773,7 +997,8 @@ fn should_release_founder_stake() {
     let bids: Bids = builder.get_value(auction_hash, BIDS_KEY);
     assert_eq!(bids.len(), 1);
     let (founding_validator, entry) = bids.into_iter().next().unwrap();
-    assert_eq!(entry.funds_locked, None);
+    assert!(entry.founding_validator);
+    assert_eq!(entry.release_era, None);
     assert_eq!(
         builder.get_purse_balance(entry.bonding_purse),
         ACCOUNT_1_BOND.into()
@@ -865,16 +1090,18 @@ fn should_release_founder_stake() {
     assert_eq!(pre_unbond_list[0].amount, ACCOUNT_1_WITHDRAW_1.into());
     assert_eq!(pre_unbond_list[1].origin, ACCOUNT_1_PK);
     assert_eq!(pre_unbond_list[1].amount, ACCOUNT_1_WITHDRAW_2.into());
-
-    // Funds are not transferred yet from the original bonding purse
     assert_eq!(
-        builder.get_purse_balance(pre_unbond_list[0].purse),
-        U512::zero(),
+        pre_unbond_list[0].target,
+        UnbondingTarget::Purse(unbonding_purse_1)
     );
     assert_eq!(
-        builder.get_purse_balance(pre_unbond_list[1].purse),
-        U512::zero(),
+        pre_unbond_list[1].target,
+        UnbondingTarget::Purse(unbonding_purse_2)
     );
+
+    // Funds are not transferred yet from the original bonding purse
+    assert_eq!(builder.get_purse_balance(unbonding_purse_1), U512::zero(),);
+    assert_eq!(builder.get_purse_balance(unbonding_purse_2), U512::zero(),);
     // check that bids are updated for given validator
 
     for _ in 0..DEFAULT_UNBONDING_DELAY {
@@ -899,11 +1126,11 @@ fn should_release_founder_stake() {
     // Funds are transferred from the original bonding purse to the unbonding purses
     //
     assert_eq!(
-        builder.get_purse_balance(pre_unbond_list[0].purse), // still valid
+        builder.get_purse_balance(unbonding_purse_1), // still valid
         ACCOUNT_1_WITHDRAW_1.into(),
     );
     assert_eq!(
-        builder.get_purse_balance(pre_unbond_list[1].purse), // still valid
+        builder.get_purse_balance(unbonding_purse_2), // still valid
         U512::zero(),
     );
 
@@ -921,11 +1148,11 @@ fn should_release_founder_stake() {
     builder.exec(exec_request_4).expect_success().commit();
 
     assert_eq!(
-        builder.get_purse_balance(pre_unbond_list[0].purse), // still valid ref
+        builder.get_purse_balance(unbonding_purse_1), // still valid ref
         ACCOUNT_1_WITHDRAW_1.into(),
     );
     assert_eq!(
-        builder.get_purse_balance(pre_unbond_list[1].purse), // still valid ref
+        builder.get_purse_balance(unbonding_purse_2), // still valid ref
         ACCOUNT_1_WITHDRAW_2.into(),
     );
 
@@ -939,7 +1166,7 @@ fn should_release_founder_stake() {
 
 #[ignore]
 #[test]
-fn should_fail_to_get_era_validators() {
+fn should_lock_founder_stake_until_release_era() {
     let accounts = {
         let mut tmp: Vec<GenesisAccount> = DEFAULT_ACCOUNTS.clone();
         let account_1 = GenesisAccount::new(
@@ -952,59 +1179,109 @@ fn should_fail_to_get_era_validators() {
         tmp
     };
 
-    let run_genesis_request = utils::create_run_genesis_request(accounts);
+    let run_genesis_request = RunGenesisRequestBuilder::new()
+        .with_accounts(accounts)
+        .with_locked_funds_period(LOCKED_FUNDS_PERIOD)
+        .build();
 
     let mut builder = InMemoryWasmTestBuilder::default();
 
     builder.run_genesis(&run_genesis_request);
 
+    let auction_hash = builder.get_auction_contract_hash();
+    let genesis_bids: Bids = builder.get_value(auction_hash, BIDS_KEY);
+    let entry = genesis_bids.get(&ACCOUNT_1_PK).unwrap();
+    assert!(entry.founding_validator);
     assert_eq!(
-        builder.get_era_validators(u64::max_value()),
-        None,
-        "should not have era validators for invalid era"
+        entry.release_era,
+        Some(INITIAL_ERA_ID + LOCKED_FUNDS_PERIOD)
     );
-}
+    let locked_funds_period: EraId = builder.get_value(auction_hash, LOCKED_FUNDS_PERIOD_KEY);
+    assert_eq!(locked_funds_period, LOCKED_FUNDS_PERIOD);
 
-#[ignore]
-#[test]
-fn should_use_era_validators_endpoint_for_first_era() {
-    let extra_accounts = vec![GenesisAccount::new(
-        ACCOUNT_1_PK,
-        *ACCOUNT_1_ADDR,
-        Motes::new(ACCOUNT_1_BALANCE.into()),
-        Motes::new(ACCOUNT_1_BOND.into()),
-    )];
+    let transfer_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT,
+        runtime_args! {
+            "target" => SYSTEM_ADDR,
+            ARG_AMOUNT => U512::from(TRANSFER_AMOUNT)
+        },
+    )
+    .build();
+    builder.exec(transfer_request).commit().expect_success();
 
-    let accounts = {
-        let mut tmp: Vec<GenesisAccount> = DEFAULT_ACCOUNTS.clone();
-        tmp.extend(extra_accounts);
-        tmp
+    let withdraw_bid_request = || {
+        ExecuteRequestBuilder::standard(
+            *ACCOUNT_1_ADDR,
+            CONTRACT_WITHDRAW_BID,
+            runtime_args! {
+                ARG_PUBLIC_KEY => ACCOUNT_1_PK,
+                ARG_AMOUNT => U512::from(ACCOUNT_1_WITHDRAW_1),
+                ARG_UNBOND_PURSE => Option::<URef>::None,
+            },
+        )
+        .build()
     };
 
-    let run_genesis_request = utils::create_run_genesis_request(accounts);
+    // Withdrawing is refused while the funds are locked.
+    builder.exec(withdraw_bid_request()).commit();
+    let error_message = builder
+        .exec_error_message(builder.get_exec_responses_count() - 1)
+        .expect("should have a response");
+    assert!(
+        error_message.contains(&format!(
+            "{:?}",
+            ApiError::from(AuctionError::ValidatorFundsLocked)
+        )),
+        error_message
+    );
 
-    let mut builder = InMemoryWasmTestBuilder::default();
+    // Advance to the release era.
+    for _ in 0..LOCKED_FUNDS_PERIOD {
+        let run_auction_request = ExecuteRequestBuilder::standard(
+            SYSTEM_ADDR,
+            CONTRACT_AUCTION_BIDS,
+            runtime_args! {
+                ARG_ENTRY_POINT => ARG_RUN_AUCTION,
+            },
+        )
+        .build();
+        builder.exec(run_auction_request).commit().expect_success();
+    }
+    let era_id: EraId = builder.get_value(auction_hash, ERA_ID_KEY);
+    assert_eq!(era_id, INITIAL_ERA_ID + LOCKED_FUNDS_PERIOD);
 
-    builder.run_genesis(&run_genesis_request);
+    builder
+        .exec(withdraw_bid_request())
+        .commit()
+        .expect_success();
 
-    let validator_weights = builder
-        .get_era_validators(INITIAL_ERA_ID)
-        .expect("should have validator weights for era 0");
+    let bids: Bids = builder.get_value(auction_hash, BIDS_KEY);
+    assert_eq!(
+        bids[&ACCOUNT_1_PK].staked_amount,
+        U512::from(ACCOUNT_1_BOND - ACCOUNT_1_WITHDRAW_1)
+    );
 
-    assert_eq!(validator_weights.len(), 1);
-    assert_eq!(validator_weights[&ACCOUNT_1_PK], ACCOUNT_1_BOND.into());
+    // The next auction clears the lock, while the bid remains marked as a founding validator's.
+    let run_auction_request = ExecuteRequestBuilder::standard(
+        SYSTEM_ADDR,
+        CONTRACT_AUCTION_BIDS,
+        runtime_args! {
+            ARG_ENTRY_POINT => ARG_RUN_AUCTION,
+        },
+    )
+    .build();
+    builder.exec(run_auction_request).commit().expect_success();
 
-    let era_validators: EraValidators =
-        builder.get_value(builder.get_auction_contract_hash(), ERA_VALIDATORS_KEY);
-    assert_eq!(era_validators[&0], validator_weights);
+    let bids: Bids = builder.get_value(auction_hash, BIDS_KEY);
+    let entry = bids.get(&ACCOUNT_1_PK).unwrap();
+    assert!(entry.founding_validator);
+    assert_eq!(entry.release_era, None);
 }
 
 #[ignore]
 #[test]
-fn should_calculate_era_validators_multiple_new_bids() {
-    assert_ne!(*ACCOUNT_1_ADDR, *ACCOUNT_2_ADDR,);
-    assert_ne!(*ACCOUNT_2_ADDR, *BID_ACCOUNT_1_ADDR,);
-    assert_ne!(*ACCOUNT_2_ADDR, *DEFAULT_ACCOUNT_ADDR,);
+fn should_lock_founder_stake_for_upgraded_locked_funds_period() {
     let accounts = {
         let mut tmp: Vec<GenesisAccount> = DEFAULT_ACCOUNTS.clone();
         let account_1 = GenesisAccount::new(
@@ -1013,59 +1290,307 @@ fn should_calculate_era_validators_multiple_new_bids() {
             Motes::new(ACCOUNT_1_BALANCE.into()),
             Motes::new(ACCOUNT_1_BOND.into()),
         );
-        let account_2 = GenesisAccount::new(
-            ACCOUNT_2_PK,
-            *ACCOUNT_2_ADDR,
-            Motes::new(ACCOUNT_2_BALANCE.into()),
-            Motes::new(ACCOUNT_2_BOND.into()),
-        );
-        let account_3 = GenesisAccount::new(
-            BID_ACCOUNT_1_PK,
-            *BID_ACCOUNT_1_ADDR,
-            Motes::new(BID_ACCOUNT_1_BALANCE.into()),
-            Motes::new(BID_ACCOUNT_1_BOND.into()),
-        );
-        let account_4 = GenesisAccount::new(
-            BID_ACCOUNT_2_PK,
-            *BID_ACCOUNT_2_ADDR,
-            Motes::new(BID_ACCOUNT_2_BALANCE.into()),
-            Motes::new(BID_ACCOUNT_2_BOND.into()),
-        );
         tmp.push(account_1);
-        tmp.push(account_2);
-        tmp.push(account_3);
-        tmp.push(account_4);
         tmp
     };
 
-    let run_genesis_request = utils::create_run_genesis_request(accounts);
+    let run_genesis_request = RunGenesisRequestBuilder::new()
+        .with_accounts(accounts)
+        .with_locked_funds_period(LOCKED_FUNDS_PERIOD)
+        .build();
 
     let mut builder = InMemoryWasmTestBuilder::default();
 
     builder.run_genesis(&run_genesis_request);
 
-    let genesis_validator_weights = builder
-        .get_era_validators(INITIAL_ERA_ID)
-        .expect("should have genesis validators for initial era");
-
-    // new_era is the first era in the future where new era validator weights will be calculated
-    let new_era = INITIAL_ERA_ID + AUCTION_DELAY + 1;
-    assert!(builder.get_era_validators(new_era).is_none());
-    assert_eq!(
-        builder.get_era_validators(new_era - 1).unwrap(),
-        builder.get_era_validators(INITIAL_ERA_ID).unwrap()
-    );
+    // Extend the locked funds period by one era.
+    let sem_ver = ProtocolVersion::V1_0_0.value();
+    let new_protocol_version =
+        ProtocolVersion::from_parts(sem_ver.major, sem_ver.minor, sem_ver.patch + 1);
+    let new_locked_funds_period = LOCKED_FUNDS_PERIOD + 1;
+    let mut upgrade_request = UpgradeRequestBuilder::new()
+        .with_current_protocol_version(ProtocolVersion::V1_0_0)
+        .with_new_protocol_version(new_protocol_version)
+        .with_activation_point(1)
+        .with_auction_upgrade_config(AuctionUpgradeConfig::new(
+            None,
+            Some(new_locked_funds_period),
+            Default::default(),
+        ))
+        .build();
+    builder.upgrade_with_upgrade_request(&mut upgrade_request);
+    assert!(builder
+        .get_upgrade_response(0)
+        .expect("should have response")
+        .has_success());
 
-    assert_eq!(
-        genesis_validator_weights
-            .keys()
-            .copied()
-            .collect::<BTreeSet<_>>(),
-        BTreeSet::from_iter(vec![ACCOUNT_1_PK, ACCOUNT_2_PK])
-    );
+    let transfer_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT,
+        runtime_args! {
+            "target" => SYSTEM_ADDR,
+            ARG_AMOUNT => U512::from(TRANSFER_AMOUNT)
+        },
+    )
+    .with_protocol_version(new_protocol_version)
+    .build();
+    builder.exec(transfer_request).commit().expect_success();
 
-    // Fund additional accounts
-    for target in &[
+    let run_auction = |builder: &mut InMemoryWasmTestBuilder| {
+        let run_auction_request = ExecuteRequestBuilder::standard(
+            SYSTEM_ADDR,
+            CONTRACT_AUCTION_BIDS,
+            runtime_args! {
+                ARG_ENTRY_POINT => ARG_RUN_AUCTION,
+            },
+        )
+        .with_protocol_version(new_protocol_version)
+        .build();
+        builder.exec(run_auction_request).commit().expect_success();
+    };
+    let withdraw_bid_request = || {
+        ExecuteRequestBuilder::standard(
+            *ACCOUNT_1_ADDR,
+            CONTRACT_WITHDRAW_BID,
+            runtime_args! {
+                ARG_PUBLIC_KEY => ACCOUNT_1_PK,
+                ARG_AMOUNT => U512::from(ACCOUNT_1_WITHDRAW_1),
+                ARG_UNBOND_PURSE => Option::<URef>::None,
+            },
+        )
+        .with_protocol_version(new_protocol_version)
+        .build()
+    };
+
+    // The release era set at genesis has passed, but the funds stay locked for the new period.
+    for _ in 0..LOCKED_FUNDS_PERIOD {
+        run_auction(&mut builder);
+    }
+    builder.exec(withdraw_bid_request()).commit();
+    let error_message = builder
+        .exec_error_message(builder.get_exec_responses_count() - 1)
+        .expect("should have a response");
+    assert!(
+        error_message.contains(&format!(
+            "{:?}",
+            ApiError::from(AuctionError::ValidatorFundsLocked)
+        )),
+        error_message
+    );
+
+    run_auction(&mut builder);
+    let auction_hash = builder.get_auction_contract_hash();
+    let era_id: EraId = builder.get_value(auction_hash, ERA_ID_KEY);
+    assert_eq!(era_id, INITIAL_ERA_ID + new_locked_funds_period);
+
+    builder
+        .exec(withdraw_bid_request())
+        .commit()
+        .expect_success();
+}
+
+#[ignore]
+#[test]
+fn should_fail_to_get_era_validators() {
+    let accounts = {
+        let mut tmp: Vec<GenesisAccount> = DEFAULT_ACCOUNTS.clone();
+        let account_1 = GenesisAccount::new(
+            ACCOUNT_1_PK,
+            *ACCOUNT_1_ADDR,
+            Motes::new(ACCOUNT_1_BALANCE.into()),
+            Motes::new(ACCOUNT_1_BOND.into()),
+        );
+        tmp.push(account_1);
+        tmp
+    };
+
+    let run_genesis_request = utils::create_run_genesis_request(accounts);
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&run_genesis_request);
+
+    assert_eq!(
+        builder.get_era_validators(u64::max_value()),
+        None,
+        "should not have era validators for invalid era"
+    );
+}
+
+#[ignore]
+#[test]
+fn should_use_era_validators_endpoint_for_first_era() {
+    let extra_accounts = vec![GenesisAccount::new(
+        ACCOUNT_1_PK,
+        *ACCOUNT_1_ADDR,
+        Motes::new(ACCOUNT_1_BALANCE.into()),
+        Motes::new(ACCOUNT_1_BOND.into()),
+    )];
+
+    let accounts = {
+        let mut tmp: Vec<GenesisAccount> = DEFAULT_ACCOUNTS.clone();
+        tmp.extend(extra_accounts);
+        tmp
+    };
+
+    let run_genesis_request = utils::create_run_genesis_request(accounts);
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&run_genesis_request);
+
+    let validator_weights = builder
+        .get_era_validators(INITIAL_ERA_ID)
+        .expect("should have validator weights for era 0");
+
+    assert_eq!(validator_weights.len(), 1);
+    assert_eq!(validator_weights[&ACCOUNT_1_PK], ACCOUNT_1_BOND.into());
+
+    let era_validators: EraValidators =
+        builder.get_value(builder.get_auction_contract_hash(), ERA_VALIDATORS_KEY);
+    assert_eq!(era_validators[&0], validator_weights);
+}
+
+#[ignore]
+#[test]
+fn should_get_era_validators_for_range() {
+    let accounts = {
+        let mut tmp: Vec<GenesisAccount> = DEFAULT_ACCOUNTS.clone();
+        tmp.push(GenesisAccount::new(
+            ACCOUNT_1_PK,
+            *ACCOUNT_1_ADDR,
+            Motes::new(ACCOUNT_1_BALANCE.into()),
+            Motes::new(ACCOUNT_1_BOND.into()),
+        ));
+        tmp.push(GenesisAccount::new(
+            ACCOUNT_2_PK,
+            *ACCOUNT_2_ADDR,
+            Motes::new(ACCOUNT_2_BALANCE.into()),
+            Motes::new(ACCOUNT_2_BOND.into()),
+        ));
+        tmp
+    };
+
+    let run_genesis_request = utils::create_run_genesis_request(accounts);
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&run_genesis_request);
+
+    let transfer_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT,
+        runtime_args! {
+            "target" => SYSTEM_ADDR,
+            ARG_AMOUNT => U512::from(DEFAULT_ACCOUNT_INITIAL_BALANCE / 10)
+        },
+    )
+    .build();
+    builder.exec(transfer_request).commit().expect_success();
+
+    for _ in 0..2 {
+        let run_auction_request = ExecuteRequestBuilder::standard(
+            SYSTEM_ADDR,
+            CONTRACT_AUCTION_BIDS,
+            runtime_args! {
+                ARG_ENTRY_POINT => ARG_RUN_AUCTION,
+            },
+        )
+        .build();
+        builder.exec(run_auction_request).commit().expect_success();
+    }
+
+    let era_id: EraId = builder.get_value(builder.get_auction_contract_hash(), ERA_ID_KEY);
+    assert_eq!(era_id, INITIAL_ERA_ID + 2);
+
+    let era_validators = builder.get_era_validators_range(era_id..=era_id + 2);
+    assert_eq!(
+        era_validators.keys().copied().collect::<Vec<_>>(),
+        vec![era_id, era_id + 1, era_id + 2]
+    );
+    for (era_id, validator_weights) in era_validators {
+        assert_eq!(validator_weights.len(), 2);
+        assert_eq!(validator_weights[&ACCOUNT_1_PK], ACCOUNT_1_BOND.into());
+        assert_eq!(validator_weights[&ACCOUNT_2_PK], ACCOUNT_2_BOND.into());
+        assert_eq!(builder.get_era_validators(era_id), Some(validator_weights));
+    }
+
+    // Eras which were dropped from or not yet added to the snapshot are omitted.
+    let last_era_id = era_id + AUCTION_DELAY;
+    let era_validators = builder.get_era_validators_range(INITIAL_ERA_ID..=last_era_id + 1);
+    assert_eq!(
+        era_validators.keys().copied().collect::<Vec<_>>(),
+        (era_id..=last_era_id).collect::<Vec<_>>()
+    );
+}
+
+#[ignore]
+#[test]
+fn should_calculate_era_validators_multiple_new_bids() {
+    assert_ne!(*ACCOUNT_1_ADDR, *ACCOUNT_2_ADDR,);
+    assert_ne!(*ACCOUNT_2_ADDR, *BID_ACCOUNT_1_ADDR,);
+    assert_ne!(*ACCOUNT_2_ADDR, *DEFAULT_ACCOUNT_ADDR,);
+    let accounts = {
+        let mut tmp: Vec<GenesisAccount> = DEFAULT_ACCOUNTS.clone();
+        let account_1 = GenesisAccount::new(
+            ACCOUNT_1_PK,
+            *ACCOUNT_1_ADDR,
+            Motes::new(ACCOUNT_1_BALANCE.into()),
+            Motes::new(ACCOUNT_1_BOND.into()),
+        );
+        let account_2 = GenesisAccount::new(
+            ACCOUNT_2_PK,
+            *ACCOUNT_2_ADDR,
+            Motes::new(ACCOUNT_2_BALANCE.into()),
+            Motes::new(ACCOUNT_2_BOND.into()),
+        );
+        let account_3 = GenesisAccount::new(
+            BID_ACCOUNT_1_PK,
+            *BID_ACCOUNT_1_ADDR,
+            Motes::new(BID_ACCOUNT_1_BALANCE.into()),
+            Motes::new(BID_ACCOUNT_1_BOND.into()),
+        );
+        let account_4 = GenesisAccount::new(
+            BID_ACCOUNT_2_PK,
+            *BID_ACCOUNT_2_ADDR,
+            Motes::new(BID_ACCOUNT_2_BALANCE.into()),
+            Motes::new(BID_ACCOUNT_2_BOND.into()),
+        );
+        tmp.push(account_1);
+        tmp.push(account_2);
+        tmp.push(account_3);
+        tmp.push(account_4);
+        tmp
+    };
+
+    let run_genesis_request = utils::create_run_genesis_request(accounts);
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&run_genesis_request);
+
+    let genesis_validator_weights = builder
+        .get_era_validators(INITIAL_ERA_ID)
+        .expect("should have genesis validators for initial era");
+
+    // new_era is the first era in the future where new era validator weights will be calculated
+    let new_era = INITIAL_ERA_ID + AUCTION_DELAY + 1;
+    assert!(builder.get_era_validators(new_era).is_none());
+    assert_eq!(
+        builder.get_era_validators(new_era - 1).unwrap(),
+        builder.get_era_validators(INITIAL_ERA_ID).unwrap()
+    );
+
+    assert_eq!(
+        genesis_validator_weights
+            .keys()
+            .copied()
+            .collect::<BTreeSet<_>>(),
+        BTreeSet::from_iter(vec![ACCOUNT_1_PK, ACCOUNT_2_PK])
+    );
+
+    // Fund additional accounts
+    for target in &[
         SYSTEM_ADDR,
         *NON_FOUNDER_VALIDATOR_1_ADDR,
         *NON_FOUNDER_VALIDATOR_2_ADDR,
@@ -1259,7 +1784,7 @@ fn undelegated_funds_should_be_released() {
             ARG_AMOUNT => U512::from(UNDELEGATE_AMOUNT_1),
             ARG_VALIDATOR => NON_FOUNDER_VALIDATOR_1_PK,
             ARG_DELEGATOR => BID_ACCOUNT_1_PK,
-            ARG_UNBOND_PURSE => Some(delegator_1_undelegate_purse),
+            ARG_TARGET_PURSE => Some(delegator_1_undelegate_purse),
         },
     )
     .build();
@@ -1390,7 +1915,7 @@ fn fully_undelegated_funds_should_be_released() {
             ARG_AMOUNT => U512::from(DELEGATE_AMOUNT_1),
             ARG_VALIDATOR => NON_FOUNDER_VALIDATOR_1_PK,
             ARG_DELEGATOR => BID_ACCOUNT_1_PK,
-            ARG_UNBOND_PURSE => Some(delegator_1_undelegate_purse),
+            ARG_TARGET_PURSE => Some(delegator_1_undelegate_purse),
         },
     )
     .build();
@@ -1414,3 +1939,635 @@ fn fully_undelegated_funds_should_be_released() {
         U512::from(DELEGATE_AMOUNT_1)
     )
 }
+
+#[ignore]
+#[test]
+fn undelegated_funds_should_be_paid_to_main_purse_after_unbonding_delay() {
+    const SYSTEM_TRANSFER_AMOUNT: u64 = 1_000_000_000;
+
+    let system_fund_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT,
+        runtime_args! {
+            "target" => SYSTEM_ADDR,
+            ARG_AMOUNT => U512::from(SYSTEM_TRANSFER_AMOUNT)
+        },
+    )
+    .build();
+
+    let validator_1_fund_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT,
+        runtime_args! {
+            "target" => *NON_FOUNDER_VALIDATOR_1_ADDR,
+            ARG_AMOUNT => U512::from(TRANSFER_AMOUNT)
+        },
+    )
+    .build();
+
+    let delegator_1_fund_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT,
+        runtime_args! {
+            "target" => *BID_ACCOUNT_1_ADDR,
+            ARG_AMOUNT => U512::from(TRANSFER_AMOUNT)
+        },
+    )
+    .build();
+
+    let validator_1_add_bid_request = ExecuteRequestBuilder::standard(
+        *NON_FOUNDER_VALIDATOR_1_ADDR,
+        CONTRACT_ADD_BID,
+        runtime_args! {
+            ARG_PUBLIC_KEY => NON_FOUNDER_VALIDATOR_1_PK,
+            ARG_AMOUNT => U512::from(ADD_BID_AMOUNT_1),
+            ARG_DELEGATION_RATE => ADD_BID_DELEGATION_RATE_1,
+        },
+    )
+    .build();
+
+    let delegator_1_validator_1_delegate_request = ExecuteRequestBuilder::standard(
+        *BID_ACCOUNT_1_ADDR,
+        CONTRACT_DELEGATE,
+        runtime_args! {
+            ARG_AMOUNT => U512::from(DELEGATE_AMOUNT_1),
+            ARG_VALIDATOR => NON_FOUNDER_VALIDATOR_1_PK,
+            ARG_DELEGATOR => BID_ACCOUNT_1_PK,
+        },
+    )
+    .build();
+
+    let post_genesis_requests = vec![
+        system_fund_request,
+        delegator_1_fund_request,
+        validator_1_fund_request,
+        validator_1_add_bid_request,
+        delegator_1_validator_1_delegate_request,
+    ];
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+
+    for request in post_genesis_requests {
+        builder.exec(request).commit().expect_success();
+    }
+
+    let auction_hash = builder.get_auction_contract_hash();
+    let delegator_bonding_purses: DelegatorBondingPurses =
+        builder.get_value(auction_hash, DELEGATOR_BONDING_PURSES_KEY);
+    let delegator_bonding_purse = *delegator_bonding_purses
+        .get(&NON_FOUNDER_VALIDATOR_1_PK)
+        .and_then(|bonding_purses| bonding_purses.get(&BID_ACCOUNT_1_PK))
+        .expect("should record delegator bonding purse");
+
+    let delegator_1_undelegate_request = ExecuteRequestBuilder::standard(
+        *BID_ACCOUNT_1_ADDR,
+        CONTRACT_UNDELEGATE,
+        runtime_args! {
+            ARG_AMOUNT => U512::from(UNDELEGATE_AMOUNT_1),
+            ARG_VALIDATOR => NON_FOUNDER_VALIDATOR_1_PK,
+            ARG_DELEGATOR => BID_ACCOUNT_1_PK,
+            ARG_TARGET_PURSE => Option::<URef>::None,
+        },
+    )
+    .build();
+
+    builder
+        .exec(delegator_1_undelegate_request)
+        .commit()
+        .expect_success();
+
+    let unbonding_purses: UnbondingPurses = builder.get_value(auction_hash, UNBONDING_PURSES_KEY);
+    let unbond_list = unbonding_purses
+        .get(&BID_ACCOUNT_1_PK)
+        .expect("should have unbonding entry for the delegator");
+    assert_eq!(unbond_list.len(), 1);
+    assert_eq!(unbond_list[0].origin, BID_ACCOUNT_1_PK);
+    assert_eq!(
+        unbond_list[0].validator_public_key,
+        NON_FOUNDER_VALIDATOR_1_PK
+    );
+    assert_eq!(unbond_list[0].amount, U512::from(UNDELEGATE_AMOUNT_1));
+
+    let delegator_1_main_purse = builder
+        .get_account(*BID_ACCOUNT_1_ADDR)
+        .expect("should have delegator account")
+        .main_purse();
+    let main_purse_balance_before = builder.get_purse_balance(delegator_1_main_purse);
+
+    for _ in 0..=DEFAULT_UNBONDING_DELAY {
+        assert_eq!(
+            builder.get_purse_balance(delegator_1_main_purse),
+            main_purse_balance_before
+        );
+        assert_eq!(
+            builder.get_purse_balance(delegator_bonding_purse),
+            U512::from(DELEGATE_AMOUNT_1)
+        );
+        super::run_auction(&mut builder);
+    }
+
+    assert_eq!(
+        builder.get_purse_balance(delegator_1_main_purse),
+        main_purse_balance_before + U512::from(UNDELEGATE_AMOUNT_1)
+    );
+    assert_eq!(
+        builder.get_purse_balance(delegator_bonding_purse),
+        U512::from(DELEGATE_AMOUNT_1 - UNDELEGATE_AMOUNT_1)
+    );
+
+    let unbonding_purses: UnbondingPurses = builder.get_value(auction_hash, UNBONDING_PURSES_KEY);
+    assert!(!unbonding_purses.contains_key(&BID_ACCOUNT_1_PK));
+}
+
+/// Sets up a non-founding validator with a delegator who has a pending undelegation, returning the
+/// builder, the delegator's bonding purse and the slashed funds purse.
+fn setup_pending_undelegation() -> (InMemoryWasmTestBuilder, URef, URef) {
+    const SYSTEM_TRANSFER_AMOUNT: u64 = 1_000_000_000;
+
+    let system_fund_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT,
+        runtime_args! {
+            "target" => SYSTEM_ADDR,
+            ARG_AMOUNT => U512::from(SYSTEM_TRANSFER_AMOUNT)
+        },
+    )
+    .build();
+
+    let validator_1_fund_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT,
+        runtime_args! {
+            "target" => *NON_FOUNDER_VALIDATOR_1_ADDR,
+            ARG_AMOUNT => U512::from(TRANSFER_AMOUNT)
+        },
+    )
+    .build();
+
+    let delegator_1_fund_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT,
+        runtime_args! {
+            "target" => *BID_ACCOUNT_1_ADDR,
+            ARG_AMOUNT => U512::from(TRANSFER_AMOUNT)
+        },
+    )
+    .build();
+
+    let validator_1_add_bid_request = ExecuteRequestBuilder::standard(
+        *NON_FOUNDER_VALIDATOR_1_ADDR,
+        CONTRACT_ADD_BID,
+        runtime_args! {
+            ARG_PUBLIC_KEY => NON_FOUNDER_VALIDATOR_1_PK,
+            ARG_AMOUNT => U512::from(ADD_BID_AMOUNT_1),
+            ARG_DELEGATION_RATE => ADD_BID_DELEGATION_RATE_1,
+        },
+    )
+    .build();
+
+    let delegator_1_validator_1_delegate_request = ExecuteRequestBuilder::standard(
+        *BID_ACCOUNT_1_ADDR,
+        CONTRACT_DELEGATE,
+        runtime_args! {
+            ARG_AMOUNT => U512::from(DELEGATE_AMOUNT_1),
+            ARG_VALIDATOR => NON_FOUNDER_VALIDATOR_1_PK,
+            ARG_DELEGATOR => BID_ACCOUNT_1_PK,
+        },
+    )
+    .build();
+
+    let delegator_1_undelegate_request = ExecuteRequestBuilder::standard(
+        *BID_ACCOUNT_1_ADDR,
+        CONTRACT_UNDELEGATE,
+        runtime_args! {
+            ARG_AMOUNT => U512::from(UNDELEGATE_AMOUNT_1),
+            ARG_VALIDATOR => NON_FOUNDER_VALIDATOR_1_PK,
+            ARG_DELEGATOR => BID_ACCOUNT_1_PK,
+            ARG_TARGET_PURSE => Option::<URef>::None,
+        },
+    )
+    .build();
+
+    let post_genesis_requests = vec![
+        system_fund_request,
+        delegator_1_fund_request,
+        validator_1_fund_request,
+        validator_1_add_bid_request,
+        delegator_1_validator_1_delegate_request,
+        delegator_1_undelegate_request,
+    ];
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+
+    for request in post_genesis_requests {
+        builder.exec(request).commit().expect_success();
+    }
+
+    let auction_hash = builder.get_auction_contract_hash();
+    let delegator_bonding_purses: DelegatorBondingPurses =
+        builder.get_value(auction_hash, DELEGATOR_BONDING_PURSES_KEY);
+    let delegator_bonding_purse = *delegator_bonding_purses
+        .get(&NON_FOUNDER_VALIDATOR_1_PK)
+        .and_then(|bonding_purses| bonding_purses.get(&BID_ACCOUNT_1_PK))
+        .expect("should record delegator bonding purse");
+    let slashed_funds_purse = builder
+        .get_contract(auction_hash)
+        .expect("should have auction contract")
+        .named_keys()
+        .get(SLASHED_FUNDS_PURSE)
+        .expect("should have slashed funds purse")
+        .into_uref()
+        .expect("slashed funds purse should be an uref");
+    assert_eq!(builder.get_purse_balance(slashed_funds_purse), U512::zero());
+
+    (builder, delegator_bonding_purse, slashed_funds_purse)
+}
+
+#[ignore]
+#[test]
+fn pending_undelegation_should_be_slashed_with_validator() {
+    let (mut builder, delegator_bonding_purse, slashed_funds_purse) = setup_pending_undelegation();
+    let auction_hash = builder.get_auction_contract_hash();
+
+    // Slash half of the validator's stake while the undelegation is still pending.
+    let mut slash_fractions = SlashFractions::new();
+    slash_fractions.insert(NON_FOUNDER_VALIDATOR_1_PK, SLASH_FRACTION_DENOMINATOR / 2);
+
+    let slash_request = ExecuteRequestBuilder::contract_call_by_hash(
+        SYSTEM_ADDR,
+        auction_hash,
+        METHOD_SLASH,
+        runtime_args! {
+            ARG_VALIDATOR_PUBLIC_KEYS => vec![NON_FOUNDER_VALIDATOR_1_PK],
+            ARG_SLASH_FRACTIONS => slash_fractions,
+        },
+    )
+    .build();
+
+    builder.exec(slash_request).commit().expect_success();
+
+    let slashed_undelegation = U512::from(UNDELEGATE_AMOUNT_1 / 2);
+    let remaining_undelegation = U512::from(UNDELEGATE_AMOUNT_1) - slashed_undelegation;
+
+    let unbonding_purses: UnbondingPurses = builder.get_value(auction_hash, UNBONDING_PURSES_KEY);
+    let unbond_list = unbonding_purses
+        .get(&BID_ACCOUNT_1_PK)
+        .expect("should still have unbonding entry for the delegator");
+    assert_eq!(unbond_list.len(), 1);
+    assert_eq!(unbond_list[0].amount, remaining_undelegation);
+
+    assert_eq!(
+        builder.get_purse_balance(slashed_funds_purse),
+        U512::from(ADD_BID_AMOUNT_1 / 2) + slashed_undelegation
+    );
+    assert_eq!(
+        builder.get_purse_balance(delegator_bonding_purse),
+        U512::from(DELEGATE_AMOUNT_1) - slashed_undelegation
+    );
+
+    // Only the unslashed part of the undelegation is paid out after the unbonding delay.
+    let delegator_1_main_purse = builder
+        .get_account(*BID_ACCOUNT_1_ADDR)
+        .expect("should have delegator account")
+        .main_purse();
+    let main_purse_balance_before = builder.get_purse_balance(delegator_1_main_purse);
+
+    for _ in 0..=DEFAULT_UNBONDING_DELAY {
+        super::run_auction(&mut builder);
+    }
+
+    assert_eq!(
+        builder.get_purse_balance(delegator_1_main_purse),
+        main_purse_balance_before + remaining_undelegation
+    );
+    assert_eq!(
+        builder.get_purse_balance(delegator_bonding_purse),
+        U512::from(DELEGATE_AMOUNT_1 - UNDELEGATE_AMOUNT_1)
+    );
+
+    let unbonding_purses: UnbondingPurses = builder.get_value(auction_hash, UNBONDING_PURSES_KEY);
+    assert!(!unbonding_purses.contains_key(&BID_ACCOUNT_1_PK));
+}
+
+#[ignore]
+#[test]
+fn pending_undelegation_should_be_fully_slashed_by_default() {
+    let (mut builder, delegator_bonding_purse, slashed_funds_purse) = setup_pending_undelegation();
+    let auction_hash = builder.get_auction_contract_hash();
+
+    // Without a slash fraction for the validator, its whole stake is slashed.
+    let slash_request = ExecuteRequestBuilder::contract_call_by_hash(
+        SYSTEM_ADDR,
+        auction_hash,
+        METHOD_SLASH,
+        runtime_args! {
+            ARG_VALIDATOR_PUBLIC_KEYS => vec![NON_FOUNDER_VALIDATOR_1_PK],
+        },
+    )
+    .build();
+
+    builder.exec(slash_request).commit().expect_success();
+
+    // The validator's bid is gone, and so is the delegator's whole pending undelegation.
+    let bids: Bids = builder.get_value(auction_hash, BIDS_KEY);
+    assert!(!bids.contains_key(&NON_FOUNDER_VALIDATOR_1_PK));
+
+    let unbonding_purses: UnbondingPurses = builder.get_value(auction_hash, UNBONDING_PURSES_KEY);
+    assert!(!unbonding_purses.contains_key(&BID_ACCOUNT_1_PK));
+
+    assert_eq!(
+        builder.get_purse_balance(slashed_funds_purse),
+        U512::from(ADD_BID_AMOUNT_1 + UNDELEGATE_AMOUNT_1)
+    );
+
+    // Stake which is still delegated is not slashed.
+    assert_eq!(
+        builder.get_purse_balance(delegator_bonding_purse),
+        U512::from(DELEGATE_AMOUNT_1 - UNDELEGATE_AMOUNT_1)
+    );
+
+    // Nothing is paid out to the delegator after the unbonding delay.
+    let delegator_1_main_purse = builder
+        .get_account(*BID_ACCOUNT_1_ADDR)
+        .expect("should have delegator account")
+        .main_purse();
+    let main_purse_balance_before = builder.get_purse_balance(delegator_1_main_purse);
+
+    for _ in 0..=DEFAULT_UNBONDING_DELAY {
+        super::run_auction(&mut builder);
+    }
+
+    assert_eq!(
+        builder.get_purse_balance(delegator_1_main_purse),
+        main_purse_balance_before
+    );
+}
+
+fn setup_non_founder_validator() -> InMemoryWasmTestBuilder {
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+
+    for target in &[SYSTEM_ADDR, *NON_FOUNDER_VALIDATOR_1_ADDR] {
+        let transfer_request = ExecuteRequestBuilder::standard(
+            *DEFAULT_ACCOUNT_ADDR,
+            CONTRACT_TRANSFER_TO_ACCOUNT,
+            runtime_args! {
+                "target" => *target,
+                ARG_AMOUNT => U512::from(TRANSFER_AMOUNT)
+            },
+        )
+        .build();
+        builder.exec(transfer_request).commit().expect_success();
+    }
+
+    let add_bid_request = ExecuteRequestBuilder::standard(
+        *NON_FOUNDER_VALIDATOR_1_ADDR,
+        CONTRACT_ADD_BID,
+        runtime_args! {
+            ARG_PUBLIC_KEY => NON_FOUNDER_VALIDATOR_1_PK,
+            ARG_AMOUNT => U512::from(ADD_BID_AMOUNT_1),
+            ARG_DELEGATION_RATE => ADD_BID_DELEGATION_RATE_1,
+        },
+    )
+    .build();
+    builder.exec(add_bid_request).commit().expect_success();
+
+    builder
+}
+
+/// Reads the amount `delegator` has delegated to `validator` through the auction's
+/// `read_delegation` entry point.
+fn read_delegation(
+    builder: &mut InMemoryWasmTestBuilder,
+    validator: PublicKey,
+    delegator: PublicKey,
+) -> Option<U512> {
+    let read_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_AUCTION_BIDS,
+        runtime_args! {
+            ARG_ENTRY_POINT => METHOD_READ_DELEGATION,
+            ARG_VALIDATOR => validator,
+            ARG_DELEGATOR => delegator,
+        },
+    )
+    .build();
+    builder.exec(read_request).commit().expect_success();
+    builder.get_account_value(*DEFAULT_ACCOUNT_ADDR, DELEGATION)
+}
+
+fn set_bid_deactivated_request(sender: AccountHash, deactivated: bool) -> ExecuteRequest {
+    let entry_point = if deactivated {
+        METHOD_DEACTIVATE_BID
+    } else {
+        METHOD_ACTIVATE_BID
+    };
+    ExecuteRequestBuilder::standard(
+        sender,
+        CONTRACT_AUCTION_BIDS,
+        runtime_args! {
+            ARG_ENTRY_POINT => entry_point,
+            ARG_PUBLIC_KEY => NON_FOUNDER_VALIDATOR_1_PK,
+        },
+    )
+    .build()
+}
+
+/// Runs the auction and returns the id of the era whose validators it computed.
+fn run_auction_for_next_era(builder: &mut InMemoryWasmTestBuilder) -> EraId {
+    super::run_auction(builder);
+    let auction_hash = builder.get_auction_contract_hash();
+    let era_id: EraId = builder.get_value(auction_hash, ERA_ID_KEY);
+    era_id + AUCTION_DELAY
+}
+
+fn assert_validator_in_era(builder: &mut InMemoryWasmTestBuilder, era_id: EraId, expected: bool) {
+    let era_validators = builder
+        .get_era_validators(era_id)
+        .expect("should have era validators");
+    assert_eq!(
+        era_validators.contains_key(&NON_FOUNDER_VALIDATOR_1_PK),
+        expected
+    );
+
+    let auction_hash = builder.get_auction_contract_hash();
+    let seigniorage_recipients_snapshot: SeigniorageRecipientsSnapshot =
+        builder.get_value(auction_hash, SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY);
+    let seigniorage_recipients = seigniorage_recipients_snapshot
+        .get(&era_id)
+        .expect("should have seigniorage recipients");
+    assert_eq!(
+        seigniorage_recipients.contains_key(&NON_FOUNDER_VALIDATOR_1_PK),
+        expected
+    );
+}
+
+#[ignore]
+#[test]
+fn should_skip_deactivated_bid_in_auction() {
+    let mut builder = setup_non_founder_validator();
+
+    let active_era = run_auction_for_next_era(&mut builder);
+    assert_validator_in_era(&mut builder, active_era, true);
+
+    builder
+        .exec(set_bid_deactivated_request(
+            *NON_FOUNDER_VALIDATOR_1_ADDR,
+            true,
+        ))
+        .commit()
+        .expect_success();
+
+    let deactivated_era = run_auction_for_next_era(&mut builder);
+    assert_validator_in_era(&mut builder, deactivated_era, false);
+    // eras computed before the bid was deactivated are unaffected
+    assert_validator_in_era(&mut builder, active_era, true);
+
+    let auction_hash = builder.get_auction_contract_hash();
+    let bids: Bids = builder.get_value(auction_hash, BIDS_KEY);
+    let bid = bids
+        .get(&NON_FOUNDER_VALIDATOR_1_PK)
+        .expect("deactivated bid should be kept");
+    assert!(bid.deactivated);
+    assert_eq!(bid.staked_amount, U512::from(ADD_BID_AMOUNT_1));
+
+    builder
+        .exec(set_bid_deactivated_request(
+            *NON_FOUNDER_VALIDATOR_1_ADDR,
+            false,
+        ))
+        .commit()
+        .expect_success();
+
+    let reactivated_era = run_auction_for_next_era(&mut builder);
+    assert_validator_in_era(&mut builder, reactivated_era, true);
+    assert_validator_in_era(&mut builder, deactivated_era, false);
+
+    let bids: Bids = builder.get_value(auction_hash, BIDS_KEY);
+    let bid = bids
+        .get(&NON_FOUNDER_VALIDATOR_1_PK)
+        .expect("should have bid");
+    assert!(!bid.deactivated);
+    assert_eq!(bid.staked_amount, U512::from(ADD_BID_AMOUNT_1));
+}
+
+#[ignore]
+#[test]
+fn should_not_deactivate_bid_of_another_validator() {
+    let mut builder = setup_non_founder_validator();
+
+    builder
+        .exec(set_bid_deactivated_request(*DEFAULT_ACCOUNT_ADDR, true))
+        .expect_failure_with_auction_error(AuctionError::InvalidCaller)
+        .commit();
+
+    let auction_hash = builder.get_auction_contract_hash();
+    let bids: Bids = builder.get_value(auction_hash, BIDS_KEY);
+    let bid = bids
+        .get(&NON_FOUNDER_VALIDATOR_1_PK)
+        .expect("should have bid");
+    assert!(!bid.deactivated);
+}
+
+#[ignore]
+#[test]
+fn should_read_delegation() {
+    let mut builder = setup_non_founder_validator();
+
+    let transfer_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT,
+        runtime_args! {
+            "target" => *BID_ACCOUNT_1_ADDR,
+            ARG_AMOUNT => U512::from(TRANSFER_AMOUNT)
+        },
+    )
+    .build();
+    builder.exec(transfer_request).commit().expect_success();
+
+    // absent before delegating
+    assert_eq!(
+        read_delegation(&mut builder, NON_FOUNDER_VALIDATOR_1_PK, BID_ACCOUNT_1_PK),
+        None
+    );
+
+    let delegate_request = ExecuteRequestBuilder::standard(
+        *BID_ACCOUNT_1_ADDR,
+        CONTRACT_DELEGATE,
+        runtime_args! {
+            ARG_AMOUNT => U512::from(DELEGATE_AMOUNT_1),
+            ARG_VALIDATOR => NON_FOUNDER_VALIDATOR_1_PK,
+            ARG_DELEGATOR => BID_ACCOUNT_1_PK,
+        },
+    )
+    .build();
+    builder.exec(delegate_request).commit().expect_success();
+
+    // present once delegated
+    let expected = Some(U512::from(DELEGATE_AMOUNT_1));
+    assert_eq!(
+        read_delegation(&mut builder, NON_FOUNDER_VALIDATOR_1_PK, BID_ACCOUNT_1_PK),
+        expected
+    );
+    assert_eq!(
+        builder.get_delegation(NON_FOUNDER_VALIDATOR_1_PK, BID_ACCOUNT_1_PK),
+        expected
+    );
+
+    // absent for other delegators and validators
+    assert_eq!(
+        read_delegation(&mut builder, NON_FOUNDER_VALIDATOR_1_PK, BID_ACCOUNT_2_PK),
+        None
+    );
+    assert_eq!(
+        read_delegation(&mut builder, NON_FOUNDER_VALIDATOR_2_PK, BID_ACCOUNT_1_PK),
+        None
+    );
+    assert_eq!(
+        builder.get_delegation(NON_FOUNDER_VALIDATOR_2_PK, BID_ACCOUNT_1_PK),
+        None
+    );
+
+    let undelegate = |builder: &mut InMemoryWasmTestBuilder, amount: u64| {
+        let undelegate_request = ExecuteRequestBuilder::standard(
+            *BID_ACCOUNT_1_ADDR,
+            CONTRACT_UNDELEGATE,
+            runtime_args! {
+                ARG_AMOUNT => U512::from(amount),
+                ARG_VALIDATOR => NON_FOUNDER_VALIDATOR_1_PK,
+                ARG_DELEGATOR => BID_ACCOUNT_1_PK,
+                ARG_TARGET_PURSE => Option::<URef>::None,
+            },
+        )
+        .build();
+        builder.exec(undelegate_request).commit().expect_success();
+    };
+
+    // reduced by a partial undelegation
+    undelegate(&mut builder, UNDELEGATE_AMOUNT_1);
+    let expected = Some(U512::from(DELEGATE_AMOUNT_1 - UNDELEGATE_AMOUNT_1));
+    assert_eq!(
+        read_delegation(&mut builder, NON_FOUNDER_VALIDATOR_1_PK, BID_ACCOUNT_1_PK),
+        expected
+    );
+    assert_eq!(
+        builder.get_delegation(NON_FOUNDER_VALIDATOR_1_PK, BID_ACCOUNT_1_PK),
+        expected
+    );
+
+    // absent once fully undelegated, even while the unbonding is still pending
+    undelegate(&mut builder, DELEGATE_AMOUNT_1 - UNDELEGATE_AMOUNT_1);
+    assert_eq!(
+        read_delegation(&mut builder, NON_FOUNDER_VALIDATOR_1_PK, BID_ACCOUNT_1_PK),
+        None
+    );
+    assert_eq!(
+        builder.get_delegation(NON_FOUNDER_VALIDATOR_1_PK, BID_ACCOUNT_1_PK),
+        None
+    );
+}
@@ -7,16 +7,20 @@ use casper_engine_test_support::{
     internal::{ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_RUN_GENESIS_REQUEST},
     DEFAULT_ACCOUNT_ADDR,
 };
+use casper_execution_engine::core::engine_state::execute_request::ExecuteRequest;
 use casper_types::{
     self,
     account::AccountHash,
     auction::{
-        DelegationRate, ARG_AMOUNT, ARG_DELEGATION_RATE, ARG_DELEGATOR, ARG_DELEGATOR_PUBLIC_KEY,
-        ARG_PUBLIC_KEY, ARG_REWARD_FACTORS, ARG_VALIDATOR, ARG_VALIDATOR_PUBLIC_KEY, BLOCK_REWARD,
-        DELEGATION_RATE_DENOMINATOR, METHOD_DISTRIBUTE, METHOD_WITHDRAW_DELEGATOR_REWARD,
-        METHOD_WITHDRAW_VALIDATOR_REWARD,
+        DelegationRate, RewardInfo, ARG_AMOUNT, ARG_DELEGATION_RATE, ARG_DELEGATOR,
+        ARG_DELEGATOR_PUBLIC_KEY, ARG_PUBLIC_KEY, ARG_REWARD_FACTORS, ARG_VALIDATOR,
+        ARG_VALIDATOR_PUBLIC_KEY, BLOCK_REWARD, DELEGATION_RATE_DENOMINATOR,
+        DELEGATOR_REWARD_PURSE, METHOD_DISTRIBUTE, METHOD_READ_REWARD_INFO,
+        METHOD_WITHDRAW_DELEGATOR_REWARD, METHOD_WITHDRAW_VALIDATOR_REWARD, VALIDATOR_REWARD_PURSE,
     },
-    mint, runtime_args, PublicKey, RuntimeArgs, U512,
+    mint, runtime_args,
+    system_contract_errors::auction::Error as AuctionError,
+    ApiError, PublicKey, RuntimeArgs, URef, U512,
 };
 
 const ARG_ENTRY_POINT: &str = "entry_point";
@@ -44,14 +48,12 @@ lazy_static! {
     static ref DELEGATOR_3_ADDR: AccountHash = DELEGATOR_3.into();
 }
 
-fn withdraw_validator_reward(
-    builder: &mut InMemoryWasmTestBuilder,
-    sender: AccountHash,
-    validator: PublicKey,
-) -> U512 {
-    const REWARD_PURSE: &str = "reward_purse"; // used in auction-bids contract
+const REWARD_PURSE: &str = "reward_purse"; // used in auction-bids contract
+const WITHDRAWN_REWARD: &str = "withdrawn_reward"; // used in auction-bids contract
+const REWARD_INFO: &str = "reward_info"; // used in auction-bids contract
 
-    let withdraw_request = ExecuteRequestBuilder::standard(
+fn withdraw_validator_reward_request(sender: AccountHash, validator: PublicKey) -> ExecuteRequest {
+    ExecuteRequestBuilder::standard(
         sender,
         CONTRACT_AUCTION_BIDS,
         runtime_args! {
@@ -59,20 +61,68 @@ fn withdraw_validator_reward(
             ARG_VALIDATOR_PUBLIC_KEY => validator,
         },
     )
-    .build();
+    .build()
+}
+
+fn withdraw_delegator_reward_request(
+    sender: AccountHash,
+    validator: PublicKey,
+    delegator: PublicKey,
+) -> ExecuteRequest {
+    ExecuteRequestBuilder::standard(
+        sender,
+        CONTRACT_AUCTION_BIDS,
+        runtime_args! {
+            ARG_ENTRY_POINT => METHOD_WITHDRAW_DELEGATOR_REWARD,
+            ARG_VALIDATOR_PUBLIC_KEY => validator,
+            ARG_DELEGATOR_PUBLIC_KEY => delegator,
+        },
+    )
+    .build()
+}
 
+/// Executes `withdraw_request` and returns the amount the auction contract reports as withdrawn,
+/// having checked that exactly this amount was paid into the reward purse.
+fn withdraw_reward(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    withdraw_request: ExecuteRequest,
+) -> U512 {
     builder.exec(withdraw_request).commit().expect_success();
 
-    let validator_reward_purse = builder
+    let named_keys = builder
         .get_account(sender)
         .expect("should have account")
         .named_keys()
+        .clone();
+
+    let reward_purse = named_keys
         .get(REWARD_PURSE)
         .expect("should have key")
         .into_uref()
         .expect("should be uref");
 
-    builder.get_purse_balance(validator_reward_purse)
+    let withdrawn_reward_key = named_keys.get(WITHDRAWN_REWARD).expect("should have key");
+    let withdrawn_reward: U512 = builder
+        .query(None, *withdrawn_reward_key, &[])
+        .expect("should query")
+        .as_cl_value()
+        .cloned()
+        .expect("should be cl value")
+        .into_t()
+        .expect("should convert");
+
+    assert_eq!(builder.get_purse_balance(reward_purse), withdrawn_reward);
+    withdrawn_reward
+}
+
+fn withdraw_validator_reward(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    validator: PublicKey,
+) -> U512 {
+    let withdraw_request = withdraw_validator_reward_request(sender, validator);
+    withdraw_reward(builder, sender, withdraw_request)
 }
 
 fn withdraw_delegator_reward(
@@ -81,31 +131,61 @@ fn withdraw_delegator_reward(
     validator: PublicKey,
     delegator: PublicKey,
 ) -> U512 {
-    const REWARD_PURSE: &str = "reward_purse"; // used in auction-bids contract
-
-    let withdraw_request = ExecuteRequestBuilder::standard(
-        sender,
-        CONTRACT_AUCTION_BIDS,
-        runtime_args! {
-            ARG_ENTRY_POINT => METHOD_WITHDRAW_DELEGATOR_REWARD,
-            ARG_VALIDATOR_PUBLIC_KEY => validator,
-            ARG_DELEGATOR_PUBLIC_KEY => delegator,
-        },
-    )
-    .build();
+    let withdraw_request = withdraw_delegator_reward_request(sender, validator, delegator);
+    withdraw_reward(builder, sender, withdraw_request)
+}
 
-    builder.exec(withdraw_request).commit().expect_success();
+/// Reads the rewards of `validator`, or of `delegator` of `validator` if given, through the
+/// auction's `read_reward_info` entry point.
+fn read_reward_info(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    validator: PublicKey,
+    delegator: Option<PublicKey>,
+) -> RewardInfo {
+    let mut args = runtime_args! {
+        ARG_ENTRY_POINT => METHOD_READ_REWARD_INFO,
+        ARG_VALIDATOR_PUBLIC_KEY => validator,
+    };
+    if let Some(delegator) = delegator {
+        args.insert(ARG_DELEGATOR_PUBLIC_KEY, delegator);
+    }
+    let read_request = ExecuteRequestBuilder::standard(sender, CONTRACT_AUCTION_BIDS, args).build();
+    builder.exec(read_request).commit().expect_success();
+    builder.get_account_value(sender, REWARD_INFO)
+}
 
-    let validator_reward_purse = builder
-        .get_account(sender)
-        .expect("should have account")
+/// Returns the balance of the auction's reward purse stored under `name`.
+fn reward_purse_balance(builder: &mut InMemoryWasmTestBuilder, name: &str) -> U512 {
+    let auction_hash = builder.get_auction_contract_hash();
+    let purse: URef = builder
+        .get_contract(auction_hash)
+        .expect("should have auction contract")
         .named_keys()
-        .get(REWARD_PURSE)
-        .expect("should have key")
+        .get(name)
+        .expect("should have reward purse")
         .into_uref()
         .expect("should be uref");
+    builder.get_purse_balance(purse)
+}
+
+/// Executes `withdraw_request`, asserting that it fails as there are no rewards to withdraw.
+fn assert_no_rewards_to_withdraw(
+    builder: &mut InMemoryWasmTestBuilder,
+    withdraw_request: ExecuteRequest,
+) {
+    builder.exec(withdraw_request).commit();
 
-    builder.get_purse_balance(validator_reward_purse)
+    let error_message = builder
+        .exec_error_message(builder.get_exec_responses_count() - 1)
+        .expect("should have a response");
+    assert!(
+        error_message.contains(&format!(
+            "{:?}",
+            ApiError::from(AuctionError::NoRewardsToWithdraw)
+        )),
+        error_message
+    );
 }
 
 #[ignore]
@@ -258,17 +338,18 @@ fn should_distribute_delegation_rate_zero() {
     assert_eq!(total_payout, expected_total_reward_integer);
 
     // Subsequently, there should be no more rewards
-    let validator_1_balance =
-        withdraw_validator_reward(&mut builder, *VALIDATOR_1_ADDR, VALIDATOR_1);
-    assert!(validator_1_balance.is_zero());
-
-    let delegator_1_balance =
-        withdraw_delegator_reward(&mut builder, *DELEGATOR_1_ADDR, VALIDATOR_1, DELEGATOR_1);
-    assert!(delegator_1_balance.is_zero());
-
-    let delegator_2_balance =
-        withdraw_delegator_reward(&mut builder, *DELEGATOR_2_ADDR, VALIDATOR_1, DELEGATOR_2);
-    assert!(delegator_2_balance.is_zero());
+    assert_no_rewards_to_withdraw(
+        &mut builder,
+        withdraw_validator_reward_request(*VALIDATOR_1_ADDR, VALIDATOR_1),
+    );
+    assert_no_rewards_to_withdraw(
+        &mut builder,
+        withdraw_delegator_reward_request(*DELEGATOR_1_ADDR, VALIDATOR_1, DELEGATOR_1),
+    );
+    assert_no_rewards_to_withdraw(
+        &mut builder,
+        withdraw_delegator_reward_request(*DELEGATOR_2_ADDR, VALIDATOR_1, DELEGATOR_2),
+    );
 }
 
 #[ignore]
@@ -558,18 +639,17 @@ fn should_distribute_delegation_rate_full() {
         (expected_total_reward * Ratio::from(U512::one())).to_integer();
     assert_eq!(validator_1_balance, expected_validator_1_balance);
 
-    let delegator_1_balance =
-        withdraw_delegator_reward(&mut builder, *DELEGATOR_1_ADDR, VALIDATOR_1, DELEGATOR_1);
-    let expected_delegator_1_balance = U512::zero();
-    assert_eq!(delegator_1_balance, expected_delegator_1_balance);
-
-    let delegator_2_balance =
-        withdraw_delegator_reward(&mut builder, *DELEGATOR_2_ADDR, VALIDATOR_1, DELEGATOR_2);
-    let expected_delegator_2_balance = U512::zero();
-    assert_eq!(delegator_2_balance, expected_delegator_2_balance);
+    // With a delegation rate of 100%, the delegators have no rewards to withdraw.
+    assert_no_rewards_to_withdraw(
+        &mut builder,
+        withdraw_delegator_reward_request(*DELEGATOR_1_ADDR, VALIDATOR_1, DELEGATOR_1),
+    );
+    assert_no_rewards_to_withdraw(
+        &mut builder,
+        withdraw_delegator_reward_request(*DELEGATOR_2_ADDR, VALIDATOR_1, DELEGATOR_2),
+    );
 
-    let total_payout = validator_1_balance + delegator_1_balance + delegator_2_balance;
-    assert_eq!(total_payout, expected_total_reward_integer);
+    assert_eq!(validator_1_balance, expected_total_reward_integer);
 }
 
 #[ignore]
@@ -2179,3 +2259,154 @@ fn should_increase_total_supply_after_distribute() {
         "total supply should increase after distribute"
     );
 }
+
+#[ignore]
+#[test]
+fn should_report_accrued_and_withdrawn_rewards() {
+    const VALIDATOR_1_STAKE: u64 = 1_000_000;
+    const DELEGATOR_1_STAKE: u64 = 1_000_000;
+
+    const VALIDATOR_1_DELEGATION_RATE: DelegationRate = DELEGATION_RATE_DENOMINATOR / 2;
+
+    let fund_requests = [SYSTEM_ADDR, *VALIDATOR_1_ADDR, *DELEGATOR_1_ADDR]
+        .iter()
+        .map(|target| {
+            ExecuteRequestBuilder::standard(
+                *DEFAULT_ACCOUNT_ADDR,
+                CONTRACT_TRANSFER_TO_ACCOUNT,
+                runtime_args! {
+                    "target" => *target,
+                    ARG_AMOUNT => U512::from(TRANSFER_AMOUNT)
+                },
+            )
+            .build()
+        })
+        .collect::<Vec<_>>();
+
+    let validator_1_add_bid_request = ExecuteRequestBuilder::standard(
+        *VALIDATOR_1_ADDR,
+        CONTRACT_ADD_BID,
+        runtime_args! {
+            ARG_AMOUNT => U512::from(VALIDATOR_1_STAKE),
+            ARG_DELEGATION_RATE => VALIDATOR_1_DELEGATION_RATE,
+            ARG_PUBLIC_KEY => VALIDATOR_1,
+        },
+    )
+    .build();
+
+    let delegator_1_delegate_request = ExecuteRequestBuilder::standard(
+        *DELEGATOR_1_ADDR,
+        CONTRACT_DELEGATE,
+        runtime_args! {
+            ARG_AMOUNT => U512::from(DELEGATOR_1_STAKE),
+            ARG_VALIDATOR => VALIDATOR_1,
+            ARG_DELEGATOR => DELEGATOR_1,
+        },
+    )
+    .build();
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+
+    for request in fund_requests {
+        builder.exec(request).commit().expect_success();
+    }
+    builder
+        .exec(validator_1_add_bid_request)
+        .commit()
+        .expect_success();
+    builder
+        .exec(delegator_1_delegate_request)
+        .commit()
+        .expect_success();
+
+    for _ in 0..5 {
+        super::run_auction(&mut builder);
+    }
+
+    let distribute = |builder: &mut InMemoryWasmTestBuilder| {
+        let mut reward_factors = BTreeMap::new();
+        reward_factors.insert(VALIDATOR_1, BLOCK_REWARD);
+        let distribute_request = ExecuteRequestBuilder::standard(
+            SYSTEM_ADDR,
+            CONTRACT_AUCTION_BIDS,
+            runtime_args! {
+                ARG_ENTRY_POINT => METHOD_DISTRIBUTE,
+                ARG_REWARD_FACTORS => reward_factors
+            },
+        )
+        .build();
+        builder.exec(distribute_request).commit().expect_success();
+    };
+
+    let pre_distribute_supply = builder.total_supply(None);
+
+    // Nothing has been distributed yet.
+    let validator_1_info = read_reward_info(&mut builder, *VALIDATOR_1_ADDR, VALIDATOR_1, None);
+    assert_eq!(validator_1_info, RewardInfo::default());
+
+    // After the first distribution, all rewards are pending.
+    distribute(&mut builder);
+
+    let validator_1_info = read_reward_info(&mut builder, *VALIDATOR_1_ADDR, VALIDATOR_1, None);
+    assert!(!validator_1_info.pending.is_zero());
+    assert!(validator_1_info.withdrawn.is_zero());
+    assert_eq!(validator_1_info.accrued, validator_1_info.pending);
+    assert_eq!(
+        reward_purse_balance(&mut builder, VALIDATOR_REWARD_PURSE),
+        validator_1_info.pending
+    );
+
+    // The validator withdraws what it has earned so far.
+    let first_withdrawal = withdraw_validator_reward(&mut builder, *VALIDATOR_1_ADDR, VALIDATOR_1);
+    assert_eq!(first_withdrawal, validator_1_info.pending);
+
+    // A second distribution adds to the pending rewards, while the withdrawn amount is kept.
+    distribute(&mut builder);
+
+    let validator_1_info = read_reward_info(&mut builder, *VALIDATOR_1_ADDR, VALIDATOR_1, None);
+    assert_eq!(validator_1_info.withdrawn, first_withdrawal);
+    assert!(!validator_1_info.pending.is_zero());
+    assert_eq!(
+        validator_1_info.accrued,
+        validator_1_info.withdrawn + validator_1_info.pending
+    );
+    assert_eq!(
+        reward_purse_balance(&mut builder, VALIDATOR_REWARD_PURSE),
+        validator_1_info.pending
+    );
+
+    // The delegator has not withdrawn anything, so both distributions are pending.
+    let delegator_1_info = read_reward_info(
+        &mut builder,
+        *DELEGATOR_1_ADDR,
+        VALIDATOR_1,
+        Some(DELEGATOR_1),
+    );
+    assert!(delegator_1_info.withdrawn.is_zero());
+    assert_eq!(delegator_1_info.accrued, delegator_1_info.pending);
+    assert_eq!(
+        reward_purse_balance(&mut builder, DELEGATOR_REWARD_PURSE),
+        delegator_1_info.pending
+    );
+
+    // Everything minted by both distributions is accounted for.
+    let minted = builder.total_supply(None) - pre_distribute_supply;
+    assert_eq!(validator_1_info.accrued + delegator_1_info.accrued, minted);
+
+    // Once the delegator withdraws, its rewards are reported as withdrawn.
+    let delegator_1_withdrawal =
+        withdraw_delegator_reward(&mut builder, *DELEGATOR_1_ADDR, VALIDATOR_1, DELEGATOR_1);
+    let delegator_1_info = read_reward_info(
+        &mut builder,
+        *DELEGATOR_1_ADDR,
+        VALIDATOR_1,
+        Some(DELEGATOR_1),
+    );
+    assert_eq!(
+        delegator_1_info,
+        RewardInfo::new(delegator_1_withdrawal, delegator_1_withdrawal, U512::zero())
+    );
+    assert!(reward_purse_balance(&mut builder, DELEGATOR_REWARD_PURSE).is_zero());
+}
@@ -1,7 +1,7 @@
 use casper_engine_test_support::{
     internal::{
-        utils, ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNTS,
-        DEFAULT_ACCOUNT_PUBLIC_KEY, DEFAULT_PAYMENT, DEFAULT_RUN_GENESIS_REQUEST,
+        utils, ExecuteRequestBuilder, InMemoryWasmTestBuilder, RunGenesisRequestBuilder,
+        DEFAULT_ACCOUNTS, DEFAULT_ACCOUNT_PUBLIC_KEY, DEFAULT_PAYMENT, DEFAULT_RUN_GENESIS_REQUEST,
     },
     DEFAULT_ACCOUNT_ADDR,
 };
@@ -9,13 +9,15 @@ use casper_execution_engine::{core::engine_state::genesis::GenesisAccount, share
 use casper_types::{
     account::AccountHash,
     auction::{
-        BidPurses, DelegationRate, UnbondingPurses, ARG_UNBOND_PURSE, ARG_VALIDATOR_PUBLIC_KEYS,
-        BID_PURSES_KEY, DEFAULT_UNBONDING_DELAY, INITIAL_ERA_ID, METHOD_RUN_AUCTION, METHOD_SLASH,
-        UNBONDING_PURSES_KEY,
+        BidPurses, Bids, DelegationRate, Delegators, SlashFractions, UnbondingPurses,
+        ARG_DELEGATOR, ARG_SLASH_FRACTIONS, ARG_UNBOND_PURSE, ARG_VALIDATOR,
+        ARG_VALIDATOR_PUBLIC_KEYS, BIDS_KEY, BID_PURSES_KEY, DEFAULT_UNBONDING_DELAY,
+        DELEGATORS_KEY, INITIAL_ERA_ID, METHOD_RUN_AUCTION, METHOD_SLASH, MINIMUM_BID_AMOUNT,
+        SLASHED_FUNDS_PURSE, SLASH_FRACTION_DENOMINATOR, UNBONDING_PURSES_KEY,
     },
     runtime_args,
     system_contract_errors::auction,
-    ApiError, PublicKey, RuntimeArgs, URef, U512,
+    PublicKey, RuntimeArgs, URef, U512,
 };
 
 const CONTRACT_TRANSFER_TO_ACCOUNT: &str = "transfer_to_account_u512.wasm";
@@ -24,10 +26,20 @@ const CONTRACT_WITHDRAW_BID: &str = "withdraw_bid.wasm";
 const CONTRACT_AUCTION_BIDDING: &str = "auction_bidding.wasm";
 const CONTRACT_AUCTION_BIDS: &str = "auction_bids.wasm";
 const CONTRACT_CREATE_PURSE_01: &str = "create_purse_01.wasm";
+const CONTRACT_DELEGATE: &str = "delegate.wasm";
 
 const GENESIS_VALIDATOR_STAKE: u64 = 50_000;
 const GENESIS_ACCOUNT_STAKE: u64 = 100_000;
 const TRANSFER_AMOUNT: u64 = 500_000_000;
+/// Unbonding delay used in place of `DEFAULT_UNBONDING_DELAY` to keep tests short.
+const UNBONDING_DELAY: u64 = 1;
+/// Bid eviction delay used in place of `DEFAULT_BID_EVICTION_DELAY` to keep tests short.
+const BID_EVICTION_DELAY: u64 = 2;
+/// Amount left in a bid after a partial withdrawal, below `MINIMUM_BID_AMOUNT`.
+const INACTIVE_BID_AMOUNT: u64 = MINIMUM_BID_AMOUNT / 2;
+const DELEGATE_AMOUNT: u64 = 10_000;
+
+const DELEGATOR_PK: PublicKey = PublicKey::Ed25519([207; 32]);
 
 const TEST_BOND_FROM_MAIN_PURSE: &str = "bond-from-main-purse";
 const TEST_SEED_NEW_ACCOUNT: &str = "seed_new_account";
@@ -140,7 +152,12 @@ fn should_run_successful_bond_and_unbond_and_slashing() {
     assert_eq!(unbond_list.len(), 1);
     assert_eq!(unbond_list[0].origin, default_public_key_arg,);
     assert_eq!(
-        builder.get_purse_balance(unbond_list[0].purse),
+        builder.get_purse_balance(
+            unbond_list[0]
+                .target
+                .purse()
+                .expect("should pay out to a purse")
+        ),
         U512::zero(),
     );
 
@@ -170,7 +187,12 @@ fn should_run_successful_bond_and_unbond_and_slashing() {
     assert_eq!(unbond_list.len(), 1);
     assert_eq!(unbond_list[0].origin, default_public_key_arg,);
     assert_eq!(
-        builder.get_purse_balance(unbond_list[0].purse),
+        builder.get_purse_balance(
+            unbond_list[0]
+                .target
+                .purse()
+                .expect("should pay out to a purse")
+        ),
         U512::zero(),
     );
     assert_eq!(unbond_list[0].amount, unbond_amount,);
@@ -204,6 +226,204 @@ fn should_run_successful_bond_and_unbond_and_slashing() {
     assert!(bid_purses.is_empty());
 }
 
+#[ignore]
+#[test]
+fn should_slash_bid_and_pending_unbonds_proportionally() {
+    let default_public_key_arg = *DEFAULT_ACCOUNT_PUBLIC_KEY;
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT,
+        runtime_args! {
+            "target" => SYSTEM_ADDR,
+            "amount" => U512::from(TRANSFER_AMOUNT)
+        },
+    )
+    .build();
+
+    builder.exec(exec_request).expect_success().commit();
+
+    let auction = builder.get_auction_contract_hash();
+    let slashed_funds_purse = builder
+        .get_contract(auction)
+        .expect("should have auction contract")
+        .named_keys()
+        .get(SLASHED_FUNDS_PURSE)
+        .expect("should have slashed funds purse")
+        .into_uref()
+        .expect("slashed funds purse should be an uref");
+    assert_eq!(builder.get_purse_balance(slashed_funds_purse), U512::zero());
+
+    let exec_request_1 = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_ADD_BID,
+        runtime_args! {
+            ARG_AMOUNT => U512::from(GENESIS_ACCOUNT_STAKE),
+            ARG_PUBLIC_KEY => default_public_key_arg,
+            ARG_DELEGATION_RATE => DelegationRate::from(42u8),
+        },
+    )
+    .build();
+
+    builder.exec(exec_request_1).expect_success().commit();
+
+    let exec_request_2 = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_CREATE_PURSE_01,
+        runtime_args! {
+            ARG_PURSE_NAME => UNBONDING_PURSE_NAME,
+        },
+    )
+    .build();
+
+    builder.exec(exec_request_2).expect_success().commit();
+    let unbonding_purse = builder
+        .get_account(*DEFAULT_ACCOUNT_ADDR)
+        .expect("should have default account")
+        .named_keys()
+        .get(UNBONDING_PURSE_NAME)
+        .expect("should have unbonding purse")
+        .into_uref()
+        .expect("unbonding purse should be an uref");
+
+    // Leaves 60_000 motes staked and 40_000 pending unbond, all held in the bid purse.
+    let unbond_amount = U512::from(GENESIS_ACCOUNT_STAKE * 2 / 5);
+
+    let exec_request_3 = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_WITHDRAW_BID,
+        runtime_args! {
+            ARG_AMOUNT => unbond_amount,
+            ARG_PUBLIC_KEY => default_public_key_arg,
+            ARG_UNBOND_PURSE => Some(unbonding_purse),
+        },
+    )
+    .build();
+
+    builder.exec(exec_request_3).expect_success().commit();
+
+    //
+    // Slash half of the validator's funds
+    //
+
+    let mut slash_fractions = SlashFractions::new();
+    slash_fractions.insert(default_public_key_arg, SLASH_FRACTION_DENOMINATOR / 2);
+
+    let exec_request_4 = ExecuteRequestBuilder::contract_call_by_hash(
+        SYSTEM_ADDR,
+        auction,
+        METHOD_SLASH,
+        runtime_args! {
+            ARG_VALIDATOR_PUBLIC_KEYS => vec![default_public_key_arg],
+            ARG_SLASH_FRACTIONS => slash_fractions,
+        },
+    )
+    .build();
+
+    builder.exec(exec_request_4).expect_success().commit();
+
+    let half_stake = U512::from(GENESIS_ACCOUNT_STAKE / 2);
+    assert_eq!(builder.get_purse_balance(slashed_funds_purse), half_stake);
+
+    let bid_purses: BidPurses = builder.get_value(auction, BID_PURSES_KEY);
+    let bid_purse = bid_purses
+        .get(&*DEFAULT_ACCOUNT_PUBLIC_KEY)
+        .expect("should still have bid purse");
+    assert_eq!(builder.get_purse_balance(*bid_purse), half_stake);
+
+    let bids: Bids = builder.get_value(auction, BIDS_KEY);
+    let bid = bids
+        .get(&*DEFAULT_ACCOUNT_PUBLIC_KEY)
+        .expect("should still have bid");
+    assert_eq!(
+        bid.staked_amount,
+        (U512::from(GENESIS_ACCOUNT_STAKE) - unbond_amount) / 2
+    );
+
+    let unbond_purses: UnbondingPurses = builder.get_value(auction, UNBONDING_PURSES_KEY);
+    let unbond_list = unbond_purses
+        .get(&*DEFAULT_ACCOUNT_PUBLIC_KEY)
+        .expect("should have unbond");
+    assert_eq!(unbond_list.len(), 1);
+    assert_eq!(unbond_list[0].amount, unbond_amount / 2);
+
+    //
+    // Slash the remainder, defaulting to a full slash
+    //
+
+    let exec_request_5 = ExecuteRequestBuilder::contract_call_by_hash(
+        SYSTEM_ADDR,
+        auction,
+        METHOD_SLASH,
+        runtime_args! {
+            ARG_VALIDATOR_PUBLIC_KEYS => vec![default_public_key_arg],
+        },
+    )
+    .build();
+
+    builder.exec(exec_request_5).expect_success().commit();
+
+    assert_eq!(
+        builder.get_purse_balance(slashed_funds_purse),
+        U512::from(GENESIS_ACCOUNT_STAKE)
+    );
+
+    let bids: Bids = builder.get_value(auction, BIDS_KEY);
+    assert!(!bids.contains_key(&*DEFAULT_ACCOUNT_PUBLIC_KEY));
+
+    let bid_purses: BidPurses = builder.get_value(auction, BID_PURSES_KEY);
+    assert!(!bid_purses.contains_key(&*DEFAULT_ACCOUNT_PUBLIC_KEY));
+
+    let unbond_purses: UnbondingPurses = builder.get_value(auction, UNBONDING_PURSES_KEY);
+    let unbond_list = unbond_purses
+        .get(&*DEFAULT_ACCOUNT_PUBLIC_KEY)
+        .expect("should have unbond");
+    assert!(unbond_list.is_empty());
+}
+
+#[ignore]
+#[test]
+fn should_fail_slashing_with_invalid_slash_fraction() {
+    let default_public_key_arg = *DEFAULT_ACCOUNT_PUBLIC_KEY;
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT,
+        runtime_args! {
+            "target" => SYSTEM_ADDR,
+            "amount" => U512::from(TRANSFER_AMOUNT)
+        },
+    )
+    .build();
+
+    builder.exec(exec_request).expect_success().commit();
+
+    let auction = builder.get_auction_contract_hash();
+
+    let mut slash_fractions = SlashFractions::new();
+    slash_fractions.insert(default_public_key_arg, SLASH_FRACTION_DENOMINATOR + 1);
+
+    let exec_request_1 = ExecuteRequestBuilder::contract_call_by_hash(
+        SYSTEM_ADDR,
+        auction,
+        METHOD_SLASH,
+        runtime_args! {
+            ARG_VALIDATOR_PUBLIC_KEYS => vec![default_public_key_arg],
+            ARG_SLASH_FRACTIONS => slash_fractions,
+        },
+    )
+    .build();
+
+    builder
+        .exec(exec_request_1)
+        .expect_failure_with_auction_error(auction::Error::InvalidSlashFraction)
+        .commit();
+}
+
 #[ignore]
 #[test]
 fn should_fail_bonding_with_insufficient_funds() {
@@ -238,20 +458,10 @@ fn should_fail_bonding_with_insufficient_funds() {
         .exec(exec_request_1)
         .commit();
 
-    builder.exec(exec_request_2).commit();
-
-    let response = builder
-        .get_exec_response(1)
-        .expect("should have a response")
-        .to_owned();
-
-    let error_message = utils::get_error_message(response);
-
-    assert!(
-        error_message.contains(&format!("{:?}", ApiError::from(auction::Error::Transfer))),
-        "error: {:?}",
-        error_message
-    );
+    builder
+        .exec(exec_request_2)
+        .expect_failure_with_auction_error(auction::Error::Transfer)
+        .commit();
 }
 
 #[ignore]
@@ -310,24 +520,10 @@ fn should_fail_unbonding_validator_with_locked_funds() {
     )
     .build();
 
-    builder.exec(exec_request_2).commit();
-
-    let response = builder
-        .get_exec_response(1)
-        .expect("should have a response")
-        .to_owned();
-
-    let error_message = utils::get_error_message(response);
-
-    // pos::Error::NotBonded => 0
-    assert!(
-        error_message.contains(&format!(
-            "{:?}",
-            ApiError::from(auction::Error::ValidatorFundsLocked)
-        )),
-        "error {:?}",
-        error_message
-    );
+    builder
+        .exec(exec_request_2)
+        .expect_failure_with_auction_error(auction::Error::ValidatorFundsLocked)
+        .commit();
 }
 
 #[ignore]
@@ -348,23 +544,10 @@ fn should_fail_unbonding_validator_without_bonding_first() {
 
     builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
 
-    builder.exec(exec_request).commit();
-
-    let response = builder
-        .get_exec_response(0)
-        .expect("should have a response")
-        .to_owned();
-
-    let error_message = utils::get_error_message(response);
-
-    assert!(
-        error_message.contains(&format!(
-            "{:?}",
-            ApiError::from(auction::Error::ValidatorNotFound)
-        )),
-        "error {:?}",
-        error_message
-    );
+    builder
+        .exec(exec_request)
+        .expect_failure_with_auction_error(auction::Error::ValidatorNotFound)
+        .commit();
 }
 
 #[ignore]
@@ -372,8 +555,12 @@ fn should_fail_unbonding_validator_without_bonding_first() {
 fn should_run_successful_bond_and_unbond_with_release() {
     let default_public_key_arg = *DEFAULT_ACCOUNT_PUBLIC_KEY;
 
+    let run_genesis_request = RunGenesisRequestBuilder::new()
+        .with_unbonding_delay(UNBONDING_DELAY)
+        .build();
+
     let mut builder = InMemoryWasmTestBuilder::default();
-    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+    builder.run_genesis(&run_genesis_request);
 
     let create_purse_request_1 = ExecuteRequestBuilder::standard(
         *DEFAULT_ACCOUNT_ADDR,
@@ -485,13 +672,18 @@ fn should_run_successful_bond_and_unbond_with_release() {
     assert_eq!(unbond_list.len(), 1);
     assert_eq!(unbond_list[0].origin, default_public_key_arg,);
     assert_eq!(
-        builder.get_purse_balance(unbond_list[0].purse),
+        builder.get_purse_balance(
+            unbond_list[0]
+                .target
+                .purse()
+                .expect("should pay out to a purse")
+        ),
         U512::zero(),
     );
 
     assert_eq!(
         unbond_list[0].era_of_withdrawal as usize,
-        INITIAL_ERA_ID as usize + 1 + DEFAULT_UNBONDING_DELAY as usize
+        INITIAL_ERA_ID as usize + 1 + UNBONDING_DELAY as usize
     );
 
     let unbond_era_1 = unbond_list[0].era_of_withdrawal;
@@ -515,14 +707,17 @@ fn should_run_successful_bond_and_unbond_with_release() {
     assert_eq!(unbond_list.len(), 1);
     assert_eq!(unbond_list[0].origin, default_public_key_arg,);
 
-    assert_eq!(unbonding_purse, unbond_list[0].purse);
+    assert_eq!(
+        UnbondingTarget::Purse(unbonding_purse),
+        unbond_list[0].target
+    );
     assert_ne!(
-        unbond_list[0].purse,
-        *bid_purse // unbond purse is different than bid purse
+        unbond_list[0].target,
+        UnbondingTarget::Purse(*bid_purse) // unbond purse is different than bid purse
     );
     assert_eq!(
-        unbond_list[0].purse,
-        unbonding_purse, // unbond purse is not changed
+        unbond_list[0].target,
+        UnbondingTarget::Purse(unbonding_purse), // unbond purse is not changed
     );
     assert_eq!(
         builder.get_purse_balance(unbonding_purse),
@@ -537,7 +732,7 @@ fn should_run_successful_bond_and_unbond_with_release() {
     // Advance state to hit the unbonding period
     //
 
-    for _ in 0..DEFAULT_UNBONDING_DELAY {
+    for _ in 0..UNBONDING_DELAY {
         let run_auction_request_1 = ExecuteRequestBuilder::standard(
             SYSTEM_ADDR,
             CONTRACT_AUCTION_BIDS,
@@ -585,3 +780,261 @@ fn should_run_successful_bond_and_unbond_with_release() {
         U512::from(GENESIS_ACCOUNT_STAKE) - unbond_amount, // remaining funds
     );
 }
+
+fn run_auction(builder: &mut InMemoryWasmTestBuilder) {
+    let auction = builder.get_auction_contract_hash();
+    let run_request = ExecuteRequestBuilder::contract_call_by_hash(
+        SYSTEM_ADDR,
+        auction,
+        METHOD_RUN_AUCTION,
+        runtime_args! {},
+    )
+    .build();
+    builder.exec(run_request).expect_success().commit();
+}
+
+/// Bonds `GENESIS_ACCOUNT_STAKE` from the default account and withdraws all but
+/// `INACTIVE_BID_AMOUNT` of it.
+fn setup_inactive_bid() -> InMemoryWasmTestBuilder {
+    let run_genesis_request = RunGenesisRequestBuilder::new()
+        .with_unbonding_delay(UNBONDING_DELAY)
+        .with_bid_eviction_delay(BID_EVICTION_DELAY)
+        .build();
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&run_genesis_request);
+
+    let transfer_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT,
+        runtime_args! {
+            "target" => SYSTEM_ADDR,
+            "amount" => U512::from(TRANSFER_AMOUNT)
+        },
+    )
+    .build();
+    builder.exec(transfer_request).expect_success().commit();
+
+    let create_purse_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_CREATE_PURSE_01,
+        runtime_args! {
+            ARG_PURSE_NAME => UNBONDING_PURSE_NAME,
+        },
+    )
+    .build();
+    builder.exec(create_purse_request).expect_success().commit();
+    let unbonding_purse = builder
+        .get_account(*DEFAULT_ACCOUNT_ADDR)
+        .expect("should have default account")
+        .named_keys()
+        .get(UNBONDING_PURSE_NAME)
+        .expect("should have unbonding purse")
+        .into_uref()
+        .expect("unbonding purse should be an uref");
+
+    let add_bid_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_ADD_BID,
+        runtime_args! {
+            ARG_AMOUNT => U512::from(GENESIS_ACCOUNT_STAKE),
+            ARG_PUBLIC_KEY => *DEFAULT_ACCOUNT_PUBLIC_KEY,
+            ARG_DELEGATION_RATE => DelegationRate::from(42u8),
+        },
+    )
+    .build();
+    builder.exec(add_bid_request).expect_success().commit();
+
+    let withdraw_bid_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_WITHDRAW_BID,
+        runtime_args! {
+            ARG_AMOUNT => U512::from(GENESIS_ACCOUNT_STAKE - INACTIVE_BID_AMOUNT),
+            ARG_PUBLIC_KEY => *DEFAULT_ACCOUNT_PUBLIC_KEY,
+            ARG_UNBOND_PURSE => Some(unbonding_purse),
+        },
+    )
+    .build();
+    builder.exec(withdraw_bid_request).expect_success().commit();
+
+    builder
+}
+
+#[ignore]
+#[test]
+fn should_evict_inactive_bid_and_pay_out_after_unbonding_delay() {
+    let mut builder = setup_inactive_bid();
+    let auction = builder.get_auction_contract_hash();
+
+    let bid_purses: BidPurses = builder.get_value(auction, BID_PURSES_KEY);
+    let bid_purse = *bid_purses
+        .get(&*DEFAULT_ACCOUNT_PUBLIC_KEY)
+        .expect("should have bid purse");
+
+    for expected_inactive_eras in 1..=BID_EVICTION_DELAY {
+        run_auction(&mut builder);
+
+        let bids: Bids = builder.get_value(auction, BIDS_KEY);
+        let bid = bids
+            .get(&*DEFAULT_ACCOUNT_PUBLIC_KEY)
+            .expect("should still have bid");
+        assert_eq!(bid.staked_amount, U512::from(INACTIVE_BID_AMOUNT));
+        assert_eq!(bid.inactive_eras, expected_inactive_eras);
+    }
+
+    // Pending withdrawal has been paid out by now, only the inactive stake is left
+    assert_eq!(
+        builder.get_purse_balance(bid_purse),
+        U512::from(INACTIVE_BID_AMOUNT)
+    );
+
+    //
+    // Eviction
+    //
+    run_auction(&mut builder);
+
+    let bids: Bids = builder.get_value(auction, BIDS_KEY);
+    assert!(
+        !bids.contains_key(&*DEFAULT_ACCOUNT_PUBLIC_KEY),
+        "inactive bid should be evicted"
+    );
+
+    let unbond_purses: UnbondingPurses = builder.get_value(auction, UNBONDING_PURSES_KEY);
+    let unbond_list = unbond_purses
+        .get(&*DEFAULT_ACCOUNT_PUBLIC_KEY)
+        .expect("should have unbond");
+    assert_eq!(unbond_list.len(), 1);
+    assert_eq!(unbond_list[0].target, UnbondingTarget::OriginAccount);
+    assert_eq!(unbond_list[0].origin, *DEFAULT_ACCOUNT_PUBLIC_KEY);
+    assert_eq!(unbond_list[0].amount, U512::from(INACTIVE_BID_AMOUNT));
+
+    //
+    // Payout
+    //
+    let main_purse = builder
+        .get_account(*DEFAULT_ACCOUNT_ADDR)
+        .expect("should have default account")
+        .main_purse();
+    let main_purse_balance_before = builder.get_purse_balance(main_purse);
+
+    for _ in 0..UNBONDING_DELAY {
+        run_auction(&mut builder);
+    }
+
+    assert_eq!(
+        builder.get_purse_balance(main_purse),
+        main_purse_balance_before + U512::from(INACTIVE_BID_AMOUNT)
+    );
+    assert_eq!(builder.get_purse_balance(bid_purse), U512::zero());
+
+    let unbond_purses: UnbondingPurses = builder.get_value(auction, UNBONDING_PURSES_KEY);
+    assert!(
+        !unbond_purses.contains_key(&*DEFAULT_ACCOUNT_PUBLIC_KEY),
+        "Unbond entry should be removed"
+    );
+}
+
+#[ignore]
+#[test]
+fn should_unbond_delegators_of_evicted_bid() {
+    let mut builder = setup_inactive_bid();
+    let auction = builder.get_auction_contract_hash();
+    let delegator_addr = AccountHash::from(DELEGATOR_PK);
+
+    let transfer_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_TO_ACCOUNT,
+        runtime_args! {
+            "target" => delegator_addr,
+            "amount" => U512::from(TRANSFER_AMOUNT)
+        },
+    )
+    .build();
+    builder.exec(transfer_request).expect_success().commit();
+
+    let delegate_request = ExecuteRequestBuilder::standard(
+        delegator_addr,
+        CONTRACT_DELEGATE,
+        runtime_args! {
+            ARG_AMOUNT => U512::from(DELEGATE_AMOUNT),
+            ARG_VALIDATOR => *DEFAULT_ACCOUNT_PUBLIC_KEY,
+            ARG_DELEGATOR => DELEGATOR_PK,
+        },
+    )
+    .build();
+    builder.exec(delegate_request).expect_success().commit();
+
+    for _ in 0..=BID_EVICTION_DELAY {
+        run_auction(&mut builder);
+    }
+
+    let bids: Bids = builder.get_value(auction, BIDS_KEY);
+    assert!(
+        !bids.contains_key(&*DEFAULT_ACCOUNT_PUBLIC_KEY),
+        "inactive bid should be evicted"
+    );
+
+    let delegators: Delegators = builder.get_value(auction, DELEGATORS_KEY);
+    assert!(
+        !delegators.contains_key(&*DEFAULT_ACCOUNT_PUBLIC_KEY),
+        "delegations to an evicted bid should be removed"
+    );
+
+    let unbond_purses: UnbondingPurses = builder.get_value(auction, UNBONDING_PURSES_KEY);
+    let unbond_list = unbond_purses
+        .get(&DELEGATOR_PK)
+        .expect("delegator should have unbond");
+    assert_eq!(unbond_list.len(), 1);
+    assert_eq!(unbond_list[0].origin, DELEGATOR_PK);
+    assert_eq!(
+        unbond_list[0].validator_public_key,
+        *DEFAULT_ACCOUNT_PUBLIC_KEY
+    );
+    assert_eq!(unbond_list[0].amount, U512::from(DELEGATE_AMOUNT));
+}
+
+#[ignore]
+#[test]
+fn should_reset_inactivity_counter_on_top_up() {
+    let mut builder = setup_inactive_bid();
+    let auction = builder.get_auction_contract_hash();
+
+    for _ in 0..BID_EVICTION_DELAY {
+        run_auction(&mut builder);
+    }
+
+    let top_up_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_ADD_BID,
+        runtime_args! {
+            ARG_AMOUNT => U512::from(MINIMUM_BID_AMOUNT),
+            ARG_PUBLIC_KEY => *DEFAULT_ACCOUNT_PUBLIC_KEY,
+            ARG_DELEGATION_RATE => DelegationRate::from(42u8),
+        },
+    )
+    .build();
+    builder.exec(top_up_request).expect_success().commit();
+
+    let bids: Bids = builder.get_value(auction, BIDS_KEY);
+    let bid = bids
+        .get(&*DEFAULT_ACCOUNT_PUBLIC_KEY)
+        .expect("should have bid");
+    assert_eq!(bid.inactive_eras, 0);
+
+    for _ in 0..=BID_EVICTION_DELAY {
+        run_auction(&mut builder);
+    }
+
+    let bids: Bids = builder.get_value(auction, BIDS_KEY);
+    let bid = bids
+        .get(&*DEFAULT_ACCOUNT_PUBLIC_KEY)
+        .expect("topped up bid should not be evicted");
+    assert_eq!(
+        bid.staked_amount,
+        U512::from(INACTIVE_BID_AMOUNT + MINIMUM_BID_AMOUNT)
+    );
+    assert_eq!(bid.inactive_eras, 0);
+
+    let unbond_purses: UnbondingPurses = builder.get_value(auction, UNBONDING_PURSES_KEY);
+    assert!(!unbond_purses.contains_key(&*DEFAULT_ACCOUNT_PUBLIC_KEY));
+}
@@ -11,10 +11,16 @@ use casper_execution_engine::core::engine_state::EngineConfig;
 use casper_types::{
     account::AccountHash,
     auction::{
-        ARG_GENESIS_VALIDATORS, ARG_MINT_CONTRACT_PACKAGE_HASH, ARG_VALIDATOR_SLOTS, BIDS_KEY,
-        BID_PURSES_KEY, DELEGATORS_KEY, DELEGATOR_REWARD_MAP, DELEGATOR_REWARD_PURSE, ERA_ID_KEY,
-        ERA_VALIDATORS_KEY, SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY, UNBONDING_PURSES_KEY,
-        VALIDATOR_REWARD_MAP, VALIDATOR_REWARD_PURSE,
+        ARG_AUCTION_DELAY, ARG_BID_EVICTION_DELAY, ARG_GENESIS_VALIDATORS, ARG_LOCKED_FUNDS_PERIOD,
+        ARG_MINT_CONTRACT_PACKAGE_HASH, ARG_UNBONDING_DELAY, ARG_VALIDATOR_SLOTS, AUCTION_DELAY,
+        AUCTION_DELAY_KEY, AUCTION_EVENTS_KEY, BIDS_KEY, BID_EVICTION_DELAY_KEY, BID_PURSES_KEY,
+        DEFAULT_BID_EVICTION_DELAY, DEFAULT_LOCKED_FUNDS_PERIOD, DEFAULT_UNBONDING_DELAY,
+        DELEGATORS_KEY, DELEGATOR_ACCRUED_REWARD_MAP, DELEGATOR_BONDING_PURSES_KEY,
+        DELEGATOR_REWARD_MAP, DELEGATOR_REWARD_PURSE, DELEGATOR_WITHDRAWN_REWARD_MAP, ERA_ID_KEY,
+        ERA_SUMMARY_KEY, ERA_VALIDATORS_KEY, LOCKED_FUNDS_PERIOD_KEY,
+        SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY, SLASHED_FUNDS_PURSE, UNBONDING_DELAY_KEY,
+        UNBONDING_PURSES_KEY, VALIDATOR_ACCRUED_REWARD_MAP, VALIDATOR_REWARD_MAP,
+        VALIDATOR_REWARD_PURSE, VALIDATOR_WITHDRAWN_REWARD_MAP,
     },
     runtime_args, ContractHash, RuntimeArgs, U512,
 };
@@ -24,8 +30,10 @@ const TRANSFER_AMOUNT: u64 = 250_000_000 + 1000;
 const SYSTEM_ADDR: AccountHash = AccountHash::new([0u8; 32]);
 const DEPLOY_HASH_2: [u8; 32] = [2u8; 32];
 
-// one named_key for each validator and three for the purses and one for validator slots
-const EXPECTED_KNOWN_KEYS_LEN: usize = 12;
+// one named_key for each validator and three for the purses, one for validator slots, three for
+// the auction, unbonding and bid eviction delays, one for the locked funds period and two for the
+// withdrawn rewards
+const EXPECTED_KNOWN_KEYS_LEN: usize = 24;
 
 #[ignore]
 #[test]
@@ -78,7 +86,11 @@ fn should_run_auction_install_contract() {
         runtime_args! {
             ARG_MINT_CONTRACT_PACKAGE_HASH => mint.contract_package_hash(),
             ARG_GENESIS_VALIDATORS => genesis_validators,
-            ARG_VALIDATOR_SLOTS => DEFAULT_VALIDATOR_SLOTS
+            ARG_VALIDATOR_SLOTS => DEFAULT_VALIDATOR_SLOTS,
+            ARG_AUCTION_DELAY => AUCTION_DELAY,
+            ARG_UNBONDING_DELAY => DEFAULT_UNBONDING_DELAY,
+            ARG_BID_EVICTION_DELAY => DEFAULT_BID_EVICTION_DELAY,
+            ARG_LOCKED_FUNDS_PERIOD => DEFAULT_LOCKED_FUNDS_PERIOD
         },
         vec![],
     );
@@ -98,13 +110,25 @@ fn should_run_auction_install_contract() {
 
     assert!(named_keys.contains_key(BIDS_KEY));
     assert!(named_keys.contains_key(DELEGATORS_KEY));
+    assert!(named_keys.contains_key(DELEGATOR_BONDING_PURSES_KEY));
     assert!(named_keys.contains_key(ERA_VALIDATORS_KEY));
     assert!(named_keys.contains_key(ERA_ID_KEY));
+    assert!(named_keys.contains_key(ERA_SUMMARY_KEY));
+    assert!(named_keys.contains_key(AUCTION_EVENTS_KEY));
     assert!(named_keys.contains_key(SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY));
     assert!(named_keys.contains_key(BID_PURSES_KEY));
     assert!(named_keys.contains_key(UNBONDING_PURSES_KEY));
     assert!(named_keys.contains_key(DELEGATOR_REWARD_PURSE));
     assert!(named_keys.contains_key(VALIDATOR_REWARD_PURSE));
+    assert!(named_keys.contains_key(SLASHED_FUNDS_PURSE));
     assert!(named_keys.contains_key(DELEGATOR_REWARD_MAP));
     assert!(named_keys.contains_key(VALIDATOR_REWARD_MAP));
+    assert!(named_keys.contains_key(DELEGATOR_ACCRUED_REWARD_MAP));
+    assert!(named_keys.contains_key(VALIDATOR_ACCRUED_REWARD_MAP));
+    assert!(named_keys.contains_key(DELEGATOR_WITHDRAWN_REWARD_MAP));
+    assert!(named_keys.contains_key(VALIDATOR_WITHDRAWN_REWARD_MAP));
+    assert!(named_keys.contains_key(AUCTION_DELAY_KEY));
+    assert!(named_keys.contains_key(UNBONDING_DELAY_KEY));
+    assert!(named_keys.contains_key(BID_EVICTION_DELAY_KEY));
+    assert!(named_keys.contains_key(LOCKED_FUNDS_PERIOD_KEY));
 }
@@ -1,22 +1,28 @@
+use assert_matches::assert_matches;
 use lazy_static::lazy_static;
 
 use casper_engine_test_support::{
     internal::{
-        utils, InMemoryWasmTestBuilder, AUCTION_INSTALL_CONTRACT, DEFAULT_VALIDATOR_SLOTS,
-        DEFAULT_WASM_CONFIG, MINT_INSTALL_CONTRACT, POS_INSTALL_CONTRACT,
+        utils, InMemoryWasmTestBuilder, AUCTION_INSTALL_CONTRACT, DEFAULT_ACCOUNTS,
+        DEFAULT_VALIDATOR_SLOTS, DEFAULT_WASM_CONFIG, MINT_INSTALL_CONTRACT, POS_INSTALL_CONTRACT,
         STANDARD_PAYMENT_INSTALL_CONTRACT,
     },
     AccountHash,
 };
 use casper_execution_engine::{
     core::engine_state::{
-        genesis::{ExecConfig, GenesisAccount},
+        genesis::{ExecConfig, GenesisAccount, GenesisError, InstallerError},
         run_genesis_request::RunGenesisRequest,
-        SYSTEM_ACCOUNT_ADDR,
+        Error, SYSTEM_ACCOUNT_ADDR,
     },
-    shared::{motes::Motes, stored_value::StoredValue},
+    shared::{
+        motes::Motes, newtypes::CorrelationId, stored_value::StoredValue,
+        wasm_prep::PreprocessingError,
+    },
+};
+use casper_types::{
+    auction::INITIAL_ERA_ID, mint::TOTAL_SUPPLY_KEY, ProtocolVersion, PublicKey, U512,
 };
-use casper_types::{mint::TOTAL_SUPPLY_KEY, ProtocolVersion, PublicKey, U512};
 
 #[cfg(feature = "use-system-contracts")]
 const BAD_INSTALL: &str = "standard_payment.wasm";
@@ -30,6 +36,9 @@ const ACCOUNT_1_PUBLIC_KEY: PublicKey = PublicKey::Ed25519([42; 32]);
 const ACCOUNT_1_ADDR: AccountHash = AccountHash::new([43; 32]);
 const ACCOUNT_2_PUBLIC_KEY: PublicKey = PublicKey::Ed25519([44; 32]);
 const ACCOUNT_2_ADDR: AccountHash = AccountHash::new([45; 32]);
+const SECP256K1_ACCOUNT_PUBLIC_KEY_BYTES: [u8; 33] = [46; 33];
+const SECP256K1_ACCOUNT_BALANCE: u64 = 3_000_000_000;
+const SECP256K1_ACCOUNT_BONDED_AMOUNT: u64 = 3_000_000;
 
 lazy_static! {
     static ref GENESIS_CUSTOM_ACCOUNTS: Vec<GenesisAccount> = {
@@ -122,6 +131,49 @@ fn should_run_genesis() {
     }
 }
 
+#[ignore]
+#[test]
+fn should_run_genesis_with_secp256k1_validator() {
+    let secp256k1_account = GenesisAccount::secp256k1(
+        SECP256K1_ACCOUNT_PUBLIC_KEY_BYTES,
+        Motes::new(SECP256K1_ACCOUNT_BALANCE.into()),
+        Motes::new(SECP256K1_ACCOUNT_BONDED_AMOUNT.into()),
+    );
+    let public_key = secp256k1_account
+        .public_key()
+        .expect("should have public key");
+    let account_hash = secp256k1_account.account_hash();
+    assert_eq!(account_hash, AccountHash::from(public_key));
+
+    let accounts = {
+        let mut tmp: Vec<GenesisAccount> = DEFAULT_ACCOUNTS.clone();
+        tmp.push(secp256k1_account);
+        tmp
+    };
+    let run_genesis_request = utils::create_run_genesis_request(accounts);
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&run_genesis_request);
+
+    let account = builder
+        .get_account(account_hash)
+        .expect("secp256k1 account should exist");
+    let account_balance_actual = builder.get_purse_balance(account.main_purse());
+    assert_eq!(
+        account_balance_actual,
+        U512::from(SECP256K1_ACCOUNT_BALANCE)
+    );
+
+    let validator_weights = builder
+        .get_era_validators(INITIAL_ERA_ID)
+        .expect("should have genesis era validators");
+    assert_eq!(
+        validator_weights.get(&public_key),
+        Some(&U512::from(SECP256K1_ACCOUNT_BONDED_AMOUNT))
+    );
+}
+
 #[ignore]
 #[test]
 fn should_track_total_token_supply_in_mint() {
@@ -236,3 +288,67 @@ fn should_fail_if_bad_pos_install_contract_is_provided() {
 
     builder.run_genesis(&run_genesis_request);
 }
+
+#[ignore]
+#[test]
+fn should_name_empty_auction_installer() {
+    let exec_config = ExecConfig::new(
+        utils::read_wasm_file_bytes(MINT_INSTALL_CONTRACT),
+        utils::read_wasm_file_bytes(POS_INSTALL_CONTRACT),
+        utils::read_wasm_file_bytes(STANDARD_PAYMENT_INSTALL_CONTRACT),
+        Vec::new(),
+        GENESIS_CUSTOM_ACCOUNTS.clone(),
+        *DEFAULT_WASM_CONFIG,
+        DEFAULT_VALIDATOR_SLOTS,
+    );
+
+    let builder = InMemoryWasmTestBuilder::default();
+    let result = builder.get_engine_state().commit_genesis(
+        CorrelationId::new(),
+        GENESIS_CONFIG_HASH.into(),
+        ProtocolVersion::V1_0_0,
+        &exec_config,
+    );
+
+    let error = result.expect_err("genesis should fail");
+    assert!(error.to_string().contains("auction"));
+    assert_matches!(
+        error,
+        Error::Genesis(GenesisError::InvalidInstaller {
+            name: "auction",
+            source: InstallerError::Empty,
+        })
+    );
+}
+
+#[ignore]
+#[test]
+fn should_name_truncated_mint_installer() {
+    let mut mint_installer_bytes = utils::read_wasm_file_bytes(MINT_INSTALL_CONTRACT);
+    mint_installer_bytes.truncate(mint_installer_bytes.len() / 2);
+    let exec_config = ExecConfig::new(
+        mint_installer_bytes,
+        utils::read_wasm_file_bytes(POS_INSTALL_CONTRACT),
+        utils::read_wasm_file_bytes(STANDARD_PAYMENT_INSTALL_CONTRACT),
+        utils::read_wasm_file_bytes(AUCTION_INSTALL_CONTRACT),
+        GENESIS_CUSTOM_ACCOUNTS.clone(),
+        *DEFAULT_WASM_CONFIG,
+        DEFAULT_VALIDATOR_SLOTS,
+    );
+
+    let builder = InMemoryWasmTestBuilder::default();
+    let result = builder.get_engine_state().commit_genesis(
+        CorrelationId::new(),
+        GENESIS_CONFIG_HASH.into(),
+        ProtocolVersion::V1_0_0,
+        &exec_config,
+    );
+
+    assert_matches!(
+        result,
+        Err(Error::Genesis(GenesisError::InvalidInstaller {
+            name: "mint",
+            source: InstallerError::Preprocessing(PreprocessingError::Deserialize(_)),
+        }))
+    );
+}
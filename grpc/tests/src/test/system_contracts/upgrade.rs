@@ -1,14 +1,20 @@
+use std::collections::BTreeMap;
+
 use casper_engine_grpc_server::engine_server::ipc::DeployCode;
-use casper_engine_test_support::internal::{
-    utils, InMemoryWasmTestBuilder, UpgradeRequestBuilder, DEFAULT_RUN_GENESIS_REQUEST,
-    DEFAULT_WASM_CONFIG,
+use casper_engine_test_support::{
+    internal::{
+        utils, ExecuteRequestBuilder, InMemoryWasmTestBuilder, UpgradeRequestBuilder,
+        DEFAULT_ACCOUNT_PUBLIC_KEY, DEFAULT_RUN_GENESIS_REQUEST, DEFAULT_WASM_CONFIG,
+    },
+    DEFAULT_ACCOUNT_ADDR,
 };
 #[cfg(feature = "use-system-contracts")]
-use casper_engine_test_support::{internal::ExecuteRequestBuilder, DEFAULT_ACCOUNT_ADDR};
-#[cfg(feature = "use-system-contracts")]
 use casper_execution_engine::shared::{stored_value::StoredValue, transform::Transform};
 use casper_execution_engine::{
-    core::engine_state::{upgrade::ActivationPoint, Error},
+    core::engine_state::{
+        upgrade::{ActivationPoint, AuctionUpgradeConfig},
+        Error,
+    },
     shared::{
         host_function_costs::HostFunctionCosts,
         opcode_costs::{
@@ -23,9 +29,15 @@ use casper_execution_engine::{
         wasm_config::{WasmConfig, DEFAULT_INITIAL_MEMORY, DEFAULT_MAX_STACK_HEIGHT},
     },
 };
-use casper_types::{auction::VALIDATOR_SLOTS_KEY, ProtocolVersion};
 #[cfg(feature = "use-system-contracts")]
-use casper_types::{runtime_args, CLValue, Key, RuntimeArgs, U512};
+use casper_types::Key;
+use casper_types::{
+    auction::{
+        DelegationRate, UnbondingPurses, ARG_UNBOND_PURSE, DELEGATOR_REWARD_MAP, INITIAL_ERA_ID,
+        LOCKED_FUNDS_PERIOD_KEY, UNBONDING_DELAY_KEY, UNBONDING_PURSES_KEY, VALIDATOR_SLOTS_KEY,
+    },
+    runtime_args, CLValue, ProtocolVersion, RuntimeArgs, U512,
+};
 
 const PROTOCOL_VERSION: ProtocolVersion = ProtocolVersion::V1_0_0;
 const DEFAULT_ACTIVATION_POINT: ActivationPoint = 1;
@@ -36,6 +48,15 @@ const MODIFIED_MINT_CALLER_CONTRACT_NAME: &str = "modified_mint_caller.wasm";
 const PAYMENT_AMOUNT: u64 = 200_000_000;
 #[cfg(feature = "use-system-contracts")]
 const ARG_TARGET: &str = "target";
+const CONTRACT_ADD_BID: &str = "add_bid.wasm";
+const CONTRACT_WITHDRAW_BID: &str = "withdraw_bid.wasm";
+const CONTRACT_CREATE_PURSE_01: &str = "create_purse_01.wasm";
+const ARG_AMOUNT: &str = "amount";
+const ARG_PUBLIC_KEY: &str = "public_key";
+const ARG_DELEGATION_RATE: &str = "delegation_rate";
+const ARG_PURSE_NAME: &str = "purse_name";
+const UNBONDING_PURSE_NAME: &str = "unbonding_purse";
+const BID_AMOUNT: u64 = 100_000;
 
 fn get_upgraded_wasm_config() -> WasmConfig {
     let opcode_cost = OpcodeCosts {
@@ -737,3 +758,130 @@ fn should_upgrade_only_validator_slots() {
         "should have upgraded validator slots to expected value"
     )
 }
+
+/// Reads the `u64` stored under the auction contract's named key `name`.
+fn get_auction_u64(builder: &mut InMemoryWasmTestBuilder, name: &str) -> u64 {
+    let auction = builder.get_auction_contract_hash();
+    builder.get_value(auction, name)
+}
+
+#[ignore]
+#[test]
+fn should_upgrade_auction_parameters_and_named_keys() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+
+    let sem_ver = PROTOCOL_VERSION.value();
+    let new_protocol_version =
+        ProtocolVersion::from_parts(sem_ver.major, sem_ver.minor, sem_ver.patch + 1);
+
+    let auction = builder.get_auction_contract_hash();
+    let delegator_reward_map_key = builder
+        .get_contract(auction)
+        .expect("auction should exist")
+        .named_keys()[DELEGATOR_REWARD_MAP];
+    let new_unbonding_delay = get_auction_u64(&mut builder, UNBONDING_DELAY_KEY) + 3;
+    let new_locked_funds_period = 5u64;
+    let new_named_key = "new_named_key";
+
+    let mut new_named_keys = BTreeMap::new();
+    let _ = new_named_keys.insert(new_named_key.to_string(), CLValue::from_t(42u64).unwrap());
+    // Already present, so this must not replace the existing map.
+    let _ = new_named_keys.insert(
+        DELEGATOR_REWARD_MAP.to_string(),
+        CLValue::from_t(7u64).unwrap(),
+    );
+
+    let mut upgrade_request = {
+        UpgradeRequestBuilder::new()
+            .with_current_protocol_version(PROTOCOL_VERSION)
+            .with_new_protocol_version(new_protocol_version)
+            .with_activation_point(DEFAULT_ACTIVATION_POINT)
+            .with_auction_upgrade_config(AuctionUpgradeConfig::new(
+                Some(new_unbonding_delay),
+                Some(new_locked_funds_period),
+                new_named_keys,
+            ))
+            .build()
+    };
+
+    builder.upgrade_with_upgrade_request(&mut upgrade_request);
+
+    let upgrade_response = builder
+        .get_upgrade_response(0)
+        .expect("should have response");
+
+    assert!(upgrade_response.has_success(), "expected success");
+
+    let auction_contract = builder.get_contract(auction).expect("auction should exist");
+    assert_eq!(auction_contract.protocol_version(), new_protocol_version);
+    assert_eq!(
+        auction_contract.named_keys()[DELEGATOR_REWARD_MAP],
+        delegator_reward_map_key
+    );
+    assert_eq!(
+        get_auction_u64(&mut builder, UNBONDING_DELAY_KEY),
+        new_unbonding_delay
+    );
+    assert_eq!(
+        get_auction_u64(&mut builder, LOCKED_FUNDS_PERIOD_KEY),
+        new_locked_funds_period
+    );
+    assert_eq!(get_auction_u64(&mut builder, new_named_key), 42);
+
+    // Unbonds after the upgrade use the new unbonding delay.
+    let add_bid_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_ADD_BID,
+        runtime_args! {
+            ARG_AMOUNT => U512::from(BID_AMOUNT),
+            ARG_PUBLIC_KEY => *DEFAULT_ACCOUNT_PUBLIC_KEY,
+            ARG_DELEGATION_RATE => DelegationRate::from(42u8),
+        },
+    )
+    .with_protocol_version(new_protocol_version)
+    .build();
+    builder.exec(add_bid_request).expect_success().commit();
+
+    let create_purse_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_CREATE_PURSE_01,
+        runtime_args! {
+            ARG_PURSE_NAME => UNBONDING_PURSE_NAME,
+        },
+    )
+    .with_protocol_version(new_protocol_version)
+    .build();
+    builder.exec(create_purse_request).expect_success().commit();
+    let unbonding_purse = builder
+        .get_account(*DEFAULT_ACCOUNT_ADDR)
+        .expect("should have default account")
+        .named_keys()
+        .get(UNBONDING_PURSE_NAME)
+        .expect("should have unbonding purse")
+        .into_uref()
+        .expect("unbonding purse should be an uref");
+
+    let withdraw_bid_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_WITHDRAW_BID,
+        runtime_args! {
+            ARG_AMOUNT => U512::from(BID_AMOUNT - 1),
+            ARG_PUBLIC_KEY => *DEFAULT_ACCOUNT_PUBLIC_KEY,
+            ARG_UNBOND_PURSE => Some(unbonding_purse),
+        },
+    )
+    .with_protocol_version(new_protocol_version)
+    .build();
+    builder.exec(withdraw_bid_request).expect_success().commit();
+
+    let unbond_purses: UnbondingPurses = builder.get_value(auction, UNBONDING_PURSES_KEY);
+    let unbond_list = unbond_purses
+        .get(&*DEFAULT_ACCOUNT_PUBLIC_KEY)
+        .expect("should have unbond");
+    assert_eq!(unbond_list.len(), 1);
+    assert_eq!(
+        unbond_list[0].era_of_withdrawal,
+        INITIAL_ERA_ID + new_unbonding_delay
+    );
+}
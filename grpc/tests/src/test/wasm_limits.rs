@@ -0,0 +1,88 @@
+use casper_engine_test_support::{
+    internal::{
+        DeployItemBuilder, ExecuteRequestBuilder, InMemoryWasmTestBuilder, ARG_AMOUNT,
+        DEFAULT_PAYMENT, DEFAULT_RUN_GENESIS_REQUEST,
+    },
+    DEFAULT_ACCOUNT_ADDR,
+};
+use casper_execution_engine::core::engine_state::EngineConfig;
+use casper_types::{runtime_args, RuntimeArgs};
+
+const MAX_WASM_MEMORY_PAGES: u32 = 16;
+
+/// Returns a session contract which grows its memory to `total_pages` pages, trapping if the
+/// memory can't grow that far.
+fn memory_hungry_wasm(total_pages: u32) -> Vec<u8> {
+    let wat = format!(
+        r#"
+(module
+    (memory (;0;) 1)
+    (export "memory" (memory 0))
+    (type (;0;) (func))
+    (func (;0;) (type 0)
+      (if (i32.eq (memory.grow (i32.const {})) (i32.const -1))
+        (then unreachable)))
+    (export "call" (func 0)))
+"#,
+        total_pages - 1
+    );
+    wabt::wat2wasm(wat).expect("should parse")
+}
+
+fn exec_memory_hungry_session(
+    engine_config: EngineConfig,
+    total_pages: u32,
+) -> InMemoryWasmTestBuilder {
+    let deploy = DeployItemBuilder::new()
+        .with_address(*DEFAULT_ACCOUNT_ADDR)
+        .with_session_bytes(memory_hungry_wasm(total_pages), RuntimeArgs::new())
+        .with_empty_payment_bytes(runtime_args! { ARG_AMOUNT => *DEFAULT_PAYMENT, })
+        .with_authorization_keys(&[*DEFAULT_ACCOUNT_ADDR])
+        .with_deploy_hash([42; 32])
+        .build();
+
+    let exec_request = ExecuteRequestBuilder::new().push_deploy(deploy).build();
+
+    let mut builder = InMemoryWasmTestBuilder::new_with_config(engine_config);
+    builder
+        .run_genesis(&DEFAULT_RUN_GENESIS_REQUEST)
+        .exec(exec_request);
+    builder
+}
+
+fn limited_engine_config() -> EngineConfig {
+    EngineConfig::new()
+        .with_use_system_contracts(cfg!(feature = "use-system-contracts"))
+        .with_max_wasm_memory_pages(Some(MAX_WASM_MEMORY_PAGES))
+}
+
+#[ignore]
+#[test]
+fn should_grow_memory_up_to_configured_limit() {
+    exec_memory_hungry_session(limited_engine_config(), MAX_WASM_MEMORY_PAGES)
+        .expect_success()
+        .commit();
+}
+
+#[ignore]
+#[test]
+fn should_trap_when_growing_memory_beyond_configured_limit() {
+    let builder = exec_memory_hungry_session(limited_engine_config(), MAX_WASM_MEMORY_PAGES + 1);
+    assert!(builder.is_error());
+    let message = builder.exec_error_message(0).expect("should fail");
+    assert!(
+        message.contains("Unreachable"),
+        "Error message {:?} does not contain expected pattern",
+        message
+    );
+}
+
+#[ignore]
+#[test]
+fn should_not_limit_memory_below_chainspec_by_default() {
+    let engine_config =
+        EngineConfig::new().with_use_system_contracts(cfg!(feature = "use-system-contracts"));
+    exec_memory_hungry_session(engine_config, MAX_WASM_MEMORY_PAGES + 1)
+        .expect_success()
+        .commit();
+}
@@ -4,7 +4,7 @@
 
 pub mod arglang;
 
-use std::{env, fs, path::PathBuf, str::FromStr};
+use std::{env, fs, path::PathBuf, str::FromStr, time::Duration};
 
 use anyhow::{self, bail, Context};
 use rand::SeedableRng;
@@ -22,6 +22,10 @@ use casper_node::{
 };
 use prometheus::Registry;
 
+/// How long a shutting-down validator waits for in-flight work, such as the execution of the
+/// current block, before finalizing its components regardless.
+const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
 // Note: The docstring on `Cli` is the help shown when calling the binary with `--help`.
 #[derive(Debug, StructOpt)]
 #[structopt(version = casper_node::VERSION_STRING.as_str())]
@@ -179,7 +183,14 @@ impl Cli {
 
                 let mut validator_runner =
                     Runner::<validator::Reactor>::with_metrics(config, &mut rng, &registry).await?;
+                validator_runner.announce_shutdown_on_signal();
                 validator_runner.run(&mut rng).await;
+
+                info!("shutting down");
+                validator_runner
+                    .shutdown(&mut rng, SHUTDOWN_DRAIN_TIMEOUT)
+                    .await;
+                info!("shut down cleanly");
             }
         }
 
@@ -13,6 +13,7 @@
 //! For the list of supported RPCs and SSEs, see
 //! https://github.com/CasperLabs/ceps/blob/master/text/0009-client-api.md#rpcs
 
+mod balance;
 mod config;
 mod event;
 mod http_server;
@@ -20,39 +21,43 @@ mod rest_server;
 pub mod rpcs;
 mod sse_server;
 
-use std::fmt::Debug;
+use std::{convert::TryFrom, fmt::Debug, time::Duration};
 
 use datasize::DataSize;
 use futures::join;
 use lazy_static::lazy_static;
 use semver::Version;
 use tokio::sync::mpsc::{self, UnboundedSender};
+use tracing::warn;
 
 use casper_execution_engine::{
     core::engine_state::{
-        self, BalanceRequest, BalanceResult, GetEraValidatorsError, GetEraValidatorsRequest,
-        QueryRequest, QueryResult,
+        self, DeployItem, DryRunResult, ExecuteRequest, GetEraValidatorsError,
+        GetEraValidatorsRequest, QueryRequest, QueryResult,
     },
     storage::protocol_data::ProtocolData,
 };
-use casper_types::{auction::ValidatorWeights, Key, ProtocolVersion, URef};
+use casper_types::{auction::ValidatorWeights, Key, ProtocolVersion, U512};
 
 use super::Component;
 use crate::{
-    components::storage::Storage,
-    crypto::hash::Digest,
+    components::{consensus::EraId, storage::Storage},
+    crypto::{asymmetric_key::PublicKey, hash::Digest},
     effect::{
         announcements::ApiServerAnnouncement,
         requests::{
-            ApiRequest, ChainspecLoaderRequest, ContractRuntimeRequest, LinearChainRequest,
-            MetricsRequest, NetworkInfoRequest, StorageRequest,
+            ApiRequest, ChainspecLoaderRequest, ConsensusRequest, ContractRuntimeRequest,
+            DiagnosticsRequest, LinearChainRequest, MetricsRequest, NetworkInfoRequest,
+            StorageRequest,
         },
-        EffectBuilder, EffectExt, Effects, Responder,
+        EffectBuilder, EffectExt, Effects, RequestError, Responder,
     },
+    reactor::QueueKind,
     small_network::NodeId,
-    types::{CryptoRngCore, StatusFeed},
+    types::{BlockHeader, CryptoRngCore, Deploy, StatusFeed, Timestamp},
 };
 
+pub use balance::{GetBalanceError, PurseIdentifier};
 pub use config::Config;
 pub(crate) use event::Event;
 pub use sse_server::SseData;
@@ -70,6 +75,7 @@ trait ReactorEventT:
     + From<StorageRequest<Storage>>
     + From<LinearChainRequest<NodeId>>
     + From<ContractRuntimeRequest>
+    + From<ConsensusRequest>
     + Send
 {
 }
@@ -80,6 +86,7 @@ impl<REv> ReactorEventT for REv where
         + From<StorageRequest<Storage>>
         + From<LinearChainRequest<NodeId>>
         + From<ContractRuntimeRequest>
+        + From<ConsensusRequest>
         + Send
         + 'static
 {
@@ -91,6 +98,8 @@ pub(crate) struct ApiServer {
     // TODO - this should not be skipped.  Awaiting support for `UnboundedSender` in datasize crate.
     #[data_size(skip)]
     sse_data_sender: UnboundedSender<SseData>,
+    /// Time to wait for other components to answer a request made on behalf of a client.
+    request_timeout: Duration,
 }
 
 impl ApiServer {
@@ -101,12 +110,17 @@ impl ApiServer {
             + From<StorageRequest<Storage>>
             + From<LinearChainRequest<NodeId>>
             + From<ContractRuntimeRequest>
+            + From<ConsensusRequest>
             + Send,
     {
+        let request_timeout = config.request_timeout();
         let (sse_data_sender, sse_data_receiver) = mpsc::unbounded_channel();
         tokio::spawn(http_server::run(config, effect_builder, sse_data_receiver));
 
-        ApiServer { sse_data_sender }
+        ApiServer {
+            sse_data_sender,
+            request_timeout,
+        }
     }
 }
 
@@ -131,11 +145,18 @@ impl ApiServer {
         state_root_hash: Digest,
         base_key: Key,
         path: Vec<String>,
-        responder: Responder<Result<QueryResult, engine_state::Error>>,
+        responder: Responder<Result<Result<QueryResult, engine_state::Error>, RequestError>>,
     ) -> Effects<Event> {
-        let query = QueryRequest::new(state_root_hash.into(), base_key, path);
+        let query_request = QueryRequest::new(state_root_hash.into(), base_key, path);
         effect_builder
-            .query_global_state(query)
+            .make_request_with_timeout(
+                |responder| ContractRuntimeRequest::Query {
+                    query_request,
+                    responder,
+                },
+                QueueKind::Regular,
+                self.request_timeout,
+            )
             .event(move |result| Event::QueryGlobalStateResult {
                 result,
                 main_responder: responder,
@@ -164,18 +185,150 @@ impl ApiServer {
         &mut self,
         effect_builder: EffectBuilder<REv>,
         state_root_hash: Digest,
-        purse_uref: URef,
-        responder: Responder<Result<BalanceResult, engine_state::Error>>,
+        purse_identifier: PurseIdentifier,
+        responder: Responder<Result<U512, GetBalanceError>>,
     ) -> Effects<Event> {
-        let query = BalanceRequest::new(state_root_hash.into(), purse_uref);
+        let timeout = self.request_timeout;
+        async move {
+            let result =
+                balance::get_balance(effect_builder, state_root_hash, purse_identifier, timeout)
+                    .await;
+            responder.respond(result).await
+        }
+        .ignore()
+    }
+
+    fn handle_get_status<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        responder: Responder<Result<StatusFeed<NodeId>, RequestError>>,
+    ) -> Effects<Event>
+    where
+        REv: From<StorageRequest<Storage>>
+            + From<NetworkInfoRequest<NodeId>>
+            + From<ChainspecLoaderRequest>
+            + Send,
+    {
+        let timeout = self.request_timeout;
+        async move {
+            let (last_added_block, peers, chainspec_info, upgrade_schedule) = join!(
+                effect_builder.make_request_with_timeout(
+                    |responder| StorageRequest::<Storage>::GetHighestBlock { responder },
+                    QueueKind::Regular,
+                    timeout,
+                ),
+                effect_builder.make_request_with_timeout(
+                    |responder| NetworkInfoRequest::<NodeId>::GetPeers { responder },
+                    QueueKind::Api,
+                    timeout,
+                ),
+                effect_builder.make_request_with_timeout(
+                    ChainspecLoaderRequest::GetChainspecInfo,
+                    QueueKind::Regular,
+                    timeout,
+                ),
+                effect_builder.make_request_with_timeout(
+                    ChainspecLoaderRequest::GetUpgradeSchedule,
+                    QueueKind::Regular,
+                    timeout,
+                )
+            );
+            let status_feed = match (last_added_block, peers, chainspec_info, upgrade_schedule) {
+                (Ok(last_added_block), Ok(peers), Ok(chainspec_info), Ok(upgrade_schedule)) => Ok(
+                    StatusFeed::new(last_added_block, peers, chainspec_info, upgrade_schedule),
+                ),
+                (Err(error), _, _, _)
+                | (_, Err(error), _, _)
+                | (_, _, Err(error), _)
+                | (_, _, _, Err(error)) => Err(error),
+            };
+            responder.respond(status_feed).await;
+        }
+        .ignore()
+    }
+
+    fn handle_dry_run_deploy<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        state_root_hash: Digest,
+        deploy: Deploy,
+        responder: Responder<Result<DryRunResult, engine_state::Error>>,
+    ) -> Effects<Event> {
+        // The deploy is executed as if it were included in a block at the deploy's timestamp.
+        let block_time = deploy.header().timestamp().millis();
+        let execute_request = ExecuteRequest::new(
+            state_root_hash.into(),
+            block_time,
+            vec![Ok(DeployItem::from(deploy))],
+            ProtocolVersion::V1_0_0,
+        );
+        async move {
+            let result = effect_builder.dry_run_deploy(execute_request).await;
+            responder.respond(result).await
+        }
+        .ignore()
+    }
+
+    /// Requests the validator weights of the era following the given switch block, in order to
+    /// announce the start of that era on the event stream.
+    fn handle_switch_block_added<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        block_header: &BlockHeader,
+    ) -> Effects<Event> {
+        let era_id = block_header.era_id().successor();
+        let era_start_timestamp = block_header.timestamp();
+        let request = GetEraValidatorsRequest::new(
+            (*block_header.state_root_hash()).into(),
+            era_id.0,
+            ProtocolVersion::V1_0_0,
+        );
         effect_builder
-            .get_balance(query)
-            .event(move |result| Event::GetBalanceResult {
+            .get_validators(request)
+            .event(move |result| Event::NextEraValidatorsResult {
+                era_id,
+                era_start_timestamp,
                 result,
-                main_responder: responder,
             })
     }
 
+    /// Announces the start of the given era on the event stream, if its validators are known.
+    fn handle_next_era_validators(
+        &mut self,
+        era_id: EraId,
+        era_start_timestamp: Timestamp,
+        result: Result<Option<ValidatorWeights>, GetEraValidatorsError>,
+    ) -> Effects<Event> {
+        let validator_weights = match result {
+            Ok(Some(validator_weights)) => validator_weights,
+            Ok(None) => {
+                warn!(%era_id, "no validators known for started era");
+                return Effects::new();
+            }
+            Err(error) => {
+                warn!(%era_id, %error, "failed to get validators of started era");
+                return Effects::new();
+            }
+        };
+        let validator_weights = validator_weights
+            .into_iter()
+            .filter_map(
+                |(public_key, weight)| match PublicKey::try_from(public_key) {
+                    Ok(public_key) => Some((public_key, weight)),
+                    Err(error) => {
+                        warn!(%era_id, %error, "invalid validator public key");
+                        None
+                    }
+                },
+            )
+            .collect();
+        self.broadcast(SseData::EraStarted {
+            era_id,
+            validator_weights,
+            era_start_timestamp,
+        })
+    }
+
     /// Broadcasts the SSE data to all clients connected to the event stream.
     fn broadcast(&mut self, sse_data: SseData) -> Effects<Event> {
         let _ = self.sse_data_sender.send(sse_data);
@@ -189,8 +342,10 @@ where
         + From<NetworkInfoRequest<NodeId>>
         + From<LinearChainRequest<NodeId>>
         + From<ContractRuntimeRequest>
+        + From<ConsensusRequest>
         + From<ChainspecLoaderRequest>
         + From<MetricsRequest>
+        + From<DiagnosticsRequest>
         + From<StorageRequest<Storage>>
         + From<Event>
         + From<ApiRequest<NodeId>>
@@ -205,11 +360,17 @@ where
         event: Self::Event,
     ) -> Effects<Self::Event> {
         match event {
-            Event::ApiRequest(ApiRequest::SubmitDeploy { deploy, responder }) => {
-                let mut effects = effect_builder.announce_deploy_received(deploy).ignore();
-                effects.extend(responder.respond(()).ignore());
-                effects
-            }
+            Event::ApiRequest(ApiRequest::SubmitDeploy { deploy, responder }) => effect_builder
+                .announce_deploy_received(deploy, Some(responder))
+                .ignore(),
+            Event::ApiRequest(ApiRequest::VerifyDeploy { deploy, responder }) => effect_builder
+                .announce_dry_run_deploy_received(deploy, responder)
+                .ignore(),
+            Event::ApiRequest(ApiRequest::DryRunDeploy {
+                state_root_hash,
+                deploy,
+                responder,
+            }) => self.handle_dry_run_deploy(effect_builder, state_root_hash, *deploy, responder),
             Event::ApiRequest(ApiRequest::GetBlock {
                 maybe_hash: Some(hash),
                 responder,
@@ -230,6 +391,11 @@ where
                     result: Box::new(result),
                     main_responder: responder,
                 }),
+            Event::ApiRequest(ApiRequest::GetBlockAtHeight { height, responder }) => async move {
+                let maybe_block = effect_builder.get_block_at_height(height).await;
+                responder.respond(maybe_block).await;
+            }
+            .ignore(),
             Event::ApiRequest(ApiRequest::QueryProtocolData {
                 protocol_version,
                 responder,
@@ -254,9 +420,14 @@ where
             ),
             Event::ApiRequest(ApiRequest::GetBalance {
                 state_root_hash,
-                purse_uref,
+                purse_identifier,
                 responder,
-            }) => self.handle_get_balance(effect_builder, state_root_hash, purse_uref, responder),
+            }) => self.handle_get_balance(
+                effect_builder,
+                state_root_hash,
+                purse_identifier,
+                responder,
+            ),
             Event::ApiRequest(ApiRequest::GetDeploy { hash, responder }) => effect_builder
                 .get_deploy_and_metadata_from_storage(hash)
                 .event(move |result| Event::GetDeployResult {
@@ -270,22 +441,47 @@ where
                     peers,
                     main_responder: responder,
                 }),
-            Event::ApiRequest(ApiRequest::GetStatus { responder }) => async move {
-                let (last_added_block, peers, chainspec_info) = join!(
-                    effect_builder.get_highest_block(),
-                    effect_builder.network_peers(),
-                    effect_builder.get_chainspec_info()
-                );
-                let status_feed = StatusFeed::new(last_added_block, peers, chainspec_info);
-                responder.respond(status_feed).await;
+            Event::ApiRequest(ApiRequest::GetBans { responder }) => async move {
+                let bans = effect_builder.network_bans().await;
+                responder.respond(bans).await;
             }
             .ignore(),
+            Event::ApiRequest(ApiRequest::BanPeer {
+                target,
+                expires,
+                responder,
+            }) => async move {
+                let result = effect_builder.network_ban_peer(target, expires).await;
+                responder.respond(result).await;
+            }
+            .ignore(),
+            Event::ApiRequest(ApiRequest::UnbanPeer { target, responder }) => async move {
+                let result = effect_builder.network_unban_peer(target).await;
+                responder.respond(result).await;
+            }
+            .ignore(),
+            Event::ApiRequest(ApiRequest::GetConsensusStatus { responder }) => async move {
+                let consensus_status = effect_builder.get_consensus_status().await;
+                responder.respond(consensus_status).await;
+            }
+            .ignore(),
+            Event::ApiRequest(ApiRequest::GetStatus { responder }) => {
+                self.handle_get_status(effect_builder, responder)
+            }
             Event::ApiRequest(ApiRequest::GetMetrics { responder }) => effect_builder
                 .get_metrics()
                 .event(move |text| Event::GetMetricsResult {
                     text,
                     main_responder: responder,
                 }),
+            Event::ApiRequest(ApiRequest::DumpQueues {
+                max_events,
+                responder,
+            }) => async move {
+                let queue_dump = effect_builder.get_queue_dump(max_events).await;
+                responder.respond(queue_dump).await;
+            }
+            .ignore(),
             Event::GetBlockResult {
                 maybe_hash: _,
                 result,
@@ -303,10 +499,6 @@ where
                 result,
                 main_responder,
             } => main_responder.respond(result).ignore(),
-            Event::GetBalanceResult {
-                result,
-                main_responder,
-            } => main_responder.respond(result).ignore(),
             Event::GetDeployResult {
                 hash: _,
                 result,
@@ -326,10 +518,23 @@ where
             Event::BlockAdded {
                 block_hash,
                 block_header,
-            } => self.broadcast(SseData::BlockAdded {
-                block_hash,
-                block_header: *block_header,
-            }),
+            } => {
+                let mut effects = if block_header.switch_block() {
+                    self.handle_switch_block_added(effect_builder, &block_header)
+                } else {
+                    Effects::new()
+                };
+                effects.extend(self.broadcast(SseData::BlockAdded {
+                    block_hash,
+                    block_header: *block_header,
+                }));
+                effects
+            }
+            Event::NextEraValidatorsResult {
+                era_id,
+                era_start_timestamp,
+                result,
+            } => self.handle_next_era_validators(era_id, era_start_timestamp, result),
             Event::DeployProcessed {
                 deploy_hash,
                 block_hash,
@@ -339,6 +544,17 @@ where
                 block_hash,
                 execution_result,
             }),
+            Event::FinalitySignature {
+                block_hash,
+                era_id,
+                public_key,
+                signature,
+            } => self.broadcast(SseData::FinalitySignature {
+                block_hash,
+                era_id,
+                public_key: *public_key,
+                signature: *signature,
+            }),
         }
     }
 }
@@ -0,0 +1,296 @@
+//! Resolving the purse identified by a balance request and getting its balance.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    time::Duration,
+};
+
+use thiserror::Error;
+
+use casper_execution_engine::{
+    core::{
+        engine_state::{self, BalanceRequest, BalanceResult, QueryRequest, QueryResult},
+        execution,
+    },
+    shared::stored_value::StoredValue,
+};
+use casper_types::{account::AccountHash, Key, URef, U512};
+
+use crate::{
+    crypto::{asymmetric_key::PublicKey, hash::Digest},
+    effect::{requests::ContractRuntimeRequest, EffectBuilder, RequestError},
+    reactor::QueueKind,
+};
+
+/// Identifies the purse whose balance is requested.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PurseIdentifier {
+    /// The URef of the purse.
+    PurseUref(URef),
+    /// The hash of an account, whose main purse is used.
+    AccountHash(AccountHash),
+    /// The public key of an account, whose main purse is used.
+    PublicKey(PublicKey),
+}
+
+impl PurseIdentifier {
+    /// Parses a formatted URef, a formatted account hash or a hex-encoded public key.
+    pub fn from_formatted_str(input: &str) -> Result<Self, String> {
+        if let Ok(uref) = URef::from_formatted_str(input) {
+            return Ok(PurseIdentifier::PurseUref(uref));
+        }
+        if let Ok(account_hash) = AccountHash::from_formatted_str(input) {
+            return Ok(PurseIdentifier::AccountHash(account_hash));
+        }
+        PublicKey::from_hex(input)
+            .map(PurseIdentifier::PublicKey)
+            .map_err(|_| {
+                format!(
+                    "failed to parse purse identifier '{}': expected a formatted URef, a formatted \
+                    account hash or a hex-encoded public key",
+                    input
+                )
+            })
+    }
+}
+
+impl Display for PurseIdentifier {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            PurseIdentifier::PurseUref(uref) => {
+                write!(formatter, "purse {}", uref.to_formatted_string())
+            }
+            PurseIdentifier::AccountHash(account_hash) => write!(
+                formatter,
+                "main purse of {}",
+                account_hash.to_formatted_string()
+            ),
+            PurseIdentifier::PublicKey(public_key) => {
+                write!(formatter, "main purse of {}", public_key)
+            }
+        }
+    }
+}
+
+/// Error returned when getting the balance of a purse.
+#[derive(Debug, Error)]
+pub enum GetBalanceError {
+    /// The state root hash is not known.
+    #[error("state root not found")]
+    RootNotFound,
+    /// No account exists under the given account hash.
+    #[error("account not found: {}", .0.to_formatted_string())]
+    AccountNotFound(AccountHash),
+    /// The account could not be read.
+    #[error("failed to query account: {0}")]
+    AccountQueryFailed(String),
+    /// The purse doesn't exist.
+    #[error("purse not found: {}", .0.to_formatted_string())]
+    PurseNotFound(URef),
+    /// The purse exists, but its balance doesn't.
+    #[error("balance not found for purse {}", .0.to_formatted_string())]
+    BalanceNotFound(URef),
+    /// The contract runtime failed to execute a request.
+    #[error("failed to execute: {0}")]
+    Engine(engine_state::Error),
+    /// The contract runtime didn't answer the request.
+    #[error(transparent)]
+    Request(#[from] RequestError),
+}
+
+/// Gets the balance of the purse identified by `purse_identifier`, first looking up the account's
+/// main purse if it is identified by account.
+///
+/// Each request to the contract runtime fails with `GetBalanceError::Request` if it is not answered
+/// within `timeout`.
+pub(super) async fn get_balance<REv>(
+    effect_builder: EffectBuilder<REv>,
+    state_root_hash: Digest,
+    purse_identifier: PurseIdentifier,
+    timeout: Duration,
+) -> Result<U512, GetBalanceError>
+where
+    REv: From<ContractRuntimeRequest>,
+{
+    let purse_uref = match purse_identifier {
+        PurseIdentifier::PurseUref(purse_uref) => purse_uref,
+        PurseIdentifier::AccountHash(account_hash) => {
+            get_main_purse(effect_builder, state_root_hash, account_hash, timeout).await?
+        }
+        PurseIdentifier::PublicKey(public_key) => {
+            get_main_purse(
+                effect_builder,
+                state_root_hash,
+                public_key.to_account_hash(),
+                timeout,
+            )
+            .await?
+        }
+    };
+
+    let balance_request = BalanceRequest::new(state_root_hash.into(), purse_uref);
+    let balance_result = effect_builder
+        .make_request_with_timeout(
+            |responder| ContractRuntimeRequest::GetBalance {
+                balance_request,
+                responder,
+            },
+            QueueKind::Regular,
+            timeout,
+        )
+        .await?;
+    balance(purse_uref, balance_result)
+}
+
+/// Gets the main purse of the account under `account_hash`.
+async fn get_main_purse<REv>(
+    effect_builder: EffectBuilder<REv>,
+    state_root_hash: Digest,
+    account_hash: AccountHash,
+    timeout: Duration,
+) -> Result<URef, GetBalanceError>
+where
+    REv: From<ContractRuntimeRequest>,
+{
+    let query_request =
+        QueryRequest::new(state_root_hash.into(), Key::Account(account_hash), vec![]);
+    let query_result = effect_builder
+        .make_request_with_timeout(
+            |responder| ContractRuntimeRequest::Query {
+                query_request,
+                responder,
+            },
+            QueueKind::Regular,
+            timeout,
+        )
+        .await?;
+    main_purse(account_hash, query_result)
+}
+
+/// Returns the main purse of the account from the result of querying `account_hash`.
+fn main_purse(
+    account_hash: AccountHash,
+    query_result: Result<QueryResult, engine_state::Error>,
+) -> Result<URef, GetBalanceError> {
+    match query_result.map_err(GetBalanceError::Engine)? {
+        QueryResult::Success(StoredValue::Account(account)) => Ok(account.main_purse()),
+        QueryResult::ValueNotFound(_) => Err(GetBalanceError::AccountNotFound(account_hash)),
+        QueryResult::RootNotFound => Err(GetBalanceError::RootNotFound),
+        query_result => Err(GetBalanceError::AccountQueryFailed(format!(
+            "{:?}",
+            query_result
+        ))),
+    }
+}
+
+/// Returns the balance from the result of a balance request for `purse_uref`.
+fn balance(
+    purse_uref: URef,
+    balance_result: Result<BalanceResult, engine_state::Error>,
+) -> Result<U512, GetBalanceError> {
+    match balance_result {
+        Ok(BalanceResult::Success(balance)) => Ok(balance),
+        Ok(BalanceResult::RootNotFound) => Err(GetBalanceError::RootNotFound),
+        // The purse's balance key is looked up under its address first, then the balance itself.
+        Err(engine_state::Error::Exec(execution::Error::URefNotFound(_))) => {
+            Err(GetBalanceError::PurseNotFound(purse_uref))
+        }
+        Err(engine_state::Error::Exec(execution::Error::KeyNotFound(_))) => {
+            Err(GetBalanceError::BalanceNotFound(purse_uref))
+        }
+        Err(error) => Err(GetBalanceError::Engine(error)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_execution_engine::shared::account::Account;
+    use casper_types::{contracts::NamedKeys, AccessRights};
+
+    use super::*;
+    use crate::testing::TestRng;
+
+    fn purse_uref() -> URef {
+        URef::new([7; 32], AccessRights::READ_ADD_WRITE)
+    }
+
+    #[test]
+    fn should_parse_purse_uref() {
+        let uref = purse_uref();
+        assert_eq!(
+            PurseIdentifier::from_formatted_str(&uref.to_formatted_string()),
+            Ok(PurseIdentifier::PurseUref(uref))
+        );
+    }
+
+    #[test]
+    fn should_parse_account_hash() {
+        let account_hash = AccountHash::new([3; 32]);
+        assert_eq!(
+            PurseIdentifier::from_formatted_str(&account_hash.to_formatted_string()),
+            Ok(PurseIdentifier::AccountHash(account_hash))
+        );
+    }
+
+    #[test]
+    fn should_parse_public_key() {
+        let mut rng = TestRng::new();
+        let public_key = PublicKey::random(&mut rng);
+        assert_eq!(
+            PurseIdentifier::from_formatted_str(&public_key.to_hex()),
+            Ok(PurseIdentifier::PublicKey(public_key))
+        );
+    }
+
+    #[test]
+    fn should_fail_to_parse_invalid_identifier() {
+        assert!(PurseIdentifier::from_formatted_str("purse").is_err());
+        assert!(PurseIdentifier::from_formatted_str("").is_err());
+    }
+
+    #[test]
+    fn should_get_main_purse_of_account() {
+        let account_hash = AccountHash::new([3; 32]);
+        let account = Account::create(account_hash, NamedKeys::new(), purse_uref());
+        let query_result = Ok(QueryResult::Success(StoredValue::Account(account)));
+        assert_eq!(
+            main_purse(account_hash, query_result).unwrap(),
+            purse_uref()
+        );
+    }
+
+    #[test]
+    fn should_distinguish_missing_account() {
+        let account_hash = AccountHash::new([3; 32]);
+        let query_result = Ok(QueryResult::ValueNotFound(String::new()));
+        assert!(matches!(
+            main_purse(account_hash, query_result),
+            Err(GetBalanceError::AccountNotFound(hash)) if hash == account_hash
+        ));
+    }
+
+    #[test]
+    fn should_distinguish_missing_purse_and_balance() {
+        let uref = purse_uref();
+        let missing_purse = Err(engine_state::Error::Exec(execution::Error::URefNotFound(
+            String::new(),
+        )));
+        assert!(matches!(
+            balance(uref, missing_purse),
+            Err(GetBalanceError::PurseNotFound(_))
+        ));
+
+        let missing_balance = Err(engine_state::Error::Exec(execution::Error::KeyNotFound(
+            Key::Hash([1; 32]),
+        )));
+        assert!(matches!(
+            balance(uref, missing_balance),
+            Err(GetBalanceError::BalanceNotFound(_))
+        ));
+
+        assert_eq!(
+            balance(uref, Ok(BalanceResult::Success(U512::from(10)))).unwrap(),
+            U512::from(10)
+        );
+    }
+}
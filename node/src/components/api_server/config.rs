@@ -1,6 +1,11 @@
+use std::time::Duration;
+
 use datasize::DataSize;
+use secrecy::Secret;
 use serde::{Deserialize, Serialize};
 
+use casper_execution_engine::core::engine_state::engine_config::DEFAULT_MAX_DEPLOY_SIZE_BYTES;
+
 /// Default binding address for the HTTP server.
 ///
 /// Uses a fixed port per node, but binds on any interface.
@@ -9,6 +14,27 @@ const DEFAULT_ADDRESS: &str = "0.0.0.0:7777";
 /// Default number of SSEs to buffer.
 const DEFAULT_EVENT_STREAM_BUFFER_LENGTH: u32 = 100;
 
+/// Default number of seconds to wait for other components to answer a request made on behalf of a
+/// client.
+const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 30;
+
+/// Allowance in bytes for the parts of a JSON-RPC request other than a deploy's hex-encoded module
+/// bytes: 64 KiB.
+const REQUEST_ENVELOPE_SIZE_BYTES: u64 = 64 * 1024;
+
+/// Default maximum size in bytes of a JSON-RPC request body.
+///
+/// `account_put_deploy` carries the deploy's module bytes hex-encoded, so a request holding a
+/// deploy of the maximum size the contract runtime accepts is about twice that size.
+const DEFAULT_MAX_REQUEST_BODY_SIZE_BYTES: u64 =
+    2 * DEFAULT_MAX_DEPLOY_SIZE_BYTES as u64 + REQUEST_ENVELOPE_SIZE_BYTES;
+
+/// Default maximum size in bytes of a JSON-RPC response body: 10 MiB.
+const DEFAULT_MAX_RESPONSE_BODY_SIZE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Default HTTP methods allowed in cross-origin requests.
+const DEFAULT_ALLOWED_METHODS: [&str; 3] = ["GET", "POST", "OPTIONS"];
+
 /// API server configuration.
 #[derive(DataSize, Debug, Deserialize, Serialize)]
 // Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
@@ -19,6 +45,46 @@ pub struct Config {
 
     /// Number of SSEs to buffer.
     pub event_stream_buffer_length: u32,
+
+    /// Whether to serve the `debug/queues` REST endpoint, which dumps the reactor's event queues.
+    pub enable_queue_dump: bool,
+
+    /// Whether to serve the `admin_set_log_filter` RPC, which replaces the node's log filter.
+    pub enable_log_filter_rpc: bool,
+
+    /// Whether to serve the `admin_ban_peer` and `admin_unban_peer` RPCs, which change the
+    /// networking component's ban list.
+    pub enable_peer_ban_rpcs: bool,
+
+    /// Number of seconds to wait for other components to answer a request made on behalf of a
+    /// client before responding with an error.
+    pub request_timeout_secs: u64,
+
+    /// Maximum size in bytes of a JSON-RPC request body.  Larger requests are rejected without
+    /// being read.  Should be at least twice the contract runtime's maximum deploy size, as
+    /// deploys are submitted with hex-encoded module bytes.
+    ///
+    /// Requests must declare their size in a `Content-Length` header; chunked requests without
+    /// one are rejected with HTTP status 411.
+    pub max_request_body_size_bytes: u64,
+
+    /// Maximum size in bytes of a JSON-RPC response body.  Larger responses are replaced with an
+    /// error.
+    pub max_response_body_size_bytes: u64,
+
+    /// Origins allowed to make cross-origin requests, e.g. `https://example.com`.  If empty, no
+    /// CORS headers are sent.  If it contains `*`, requests from any origin are allowed.
+    pub allowed_origins: Vec<String>,
+
+    /// HTTP methods allowed in cross-origin requests.  Ignored if `allowed_origins` is empty.
+    pub allowed_methods: Vec<String>,
+
+    /// Token required to submit or dry-run deploys and to call the admin RPCs.  If set, such
+    /// requests must carry an `Authorization: Bearer <token>` header.  Queries and the event
+    /// stream remain open.
+    #[data_size(skip)]
+    #[serde(skip_serializing)]
+    pub api_token: Option<Secret<String>>,
 }
 
 impl Config {
@@ -27,8 +93,26 @@ impl Config {
         Config {
             address: DEFAULT_ADDRESS.to_string(),
             event_stream_buffer_length: DEFAULT_EVENT_STREAM_BUFFER_LENGTH,
+            enable_queue_dump: false,
+            enable_log_filter_rpc: false,
+            enable_peer_ban_rpcs: false,
+            request_timeout_secs: DEFAULT_REQUEST_TIMEOUT_SECS,
+            max_request_body_size_bytes: DEFAULT_MAX_REQUEST_BODY_SIZE_BYTES,
+            max_response_body_size_bytes: DEFAULT_MAX_RESPONSE_BODY_SIZE_BYTES,
+            allowed_origins: vec![],
+            allowed_methods: DEFAULT_ALLOWED_METHODS
+                .iter()
+                .map(|method| method.to_string())
+                .collect(),
+            api_token: None,
         }
     }
+
+    /// Returns the time to wait for other components to answer a request made on behalf of a
+    /// client.
+    pub(super) fn request_timeout(&self) -> Duration {
+        Duration::from_secs(self.request_timeout_secs)
+    }
 }
 
 impl Default for Config {
@@ -7,17 +7,18 @@ use std::{
 use derive_more::From;
 
 use casper_execution_engine::{
-    core::engine_state::{self, BalanceResult, GetEraValidatorsError, QueryResult},
+    core::engine_state::{self, GetEraValidatorsError, QueryResult},
     storage::protocol_data::ProtocolData,
 };
 use casper_types::auction::ValidatorWeights;
 
 use crate::{
-    components::{small_network::NodeId, storage::DeployMetadata},
-    effect::{requests::ApiRequest, Responder},
+    components::{consensus::EraId, small_network::NodeId, storage::DeployMetadata},
+    crypto::asymmetric_key::{PublicKey, Signature},
+    effect::{requests::ApiRequest, RequestError, Responder},
     types::{
         json_compatibility::ExecutionResult, Block, BlockHash, BlockHeader, Deploy, DeployHash,
-        FinalizedBlock,
+        FinalizedBlock, Timestamp,
     },
 };
 
@@ -35,8 +36,8 @@ pub enum Event {
         main_responder: Responder<Result<Option<Box<ProtocolData>>, engine_state::Error>>,
     },
     QueryGlobalStateResult {
-        result: Result<QueryResult, engine_state::Error>,
-        main_responder: Responder<Result<QueryResult, engine_state::Error>>,
+        result: Result<Result<QueryResult, engine_state::Error>, RequestError>,
+        main_responder: Responder<Result<Result<QueryResult, engine_state::Error>, RequestError>>,
     },
     QueryEraValidatorsResult {
         result: Result<Option<ValidatorWeights>, GetEraValidatorsError>,
@@ -55,20 +56,29 @@ pub enum Event {
         text: Option<String>,
         main_responder: Responder<Option<String>>,
     },
-    GetBalanceResult {
-        result: Result<BalanceResult, engine_state::Error>,
-        main_responder: Responder<Result<BalanceResult, engine_state::Error>>,
-    },
     BlockFinalized(Box<FinalizedBlock>),
     BlockAdded {
         block_hash: BlockHash,
         block_header: Box<BlockHeader>,
     },
+    /// The result of querying the validator weights of an era which started with the addition of
+    /// the previous era's switch block.
+    NextEraValidatorsResult {
+        era_id: EraId,
+        era_start_timestamp: Timestamp,
+        result: Result<Option<ValidatorWeights>, GetEraValidatorsError>,
+    },
     DeployProcessed {
         deploy_hash: DeployHash,
         block_hash: BlockHash,
         execution_result: ExecutionResult,
     },
+    FinalitySignature {
+        block_hash: BlockHash,
+        era_id: EraId,
+        public_key: Box<PublicKey>,
+        signature: Box<Signature>,
+    },
 }
 
 impl Display for Event {
@@ -94,9 +104,6 @@ impl Display for Event {
             Event::QueryEraValidatorsResult { result, .. } => {
                 write!(formatter, "query era validators result: {:?}", result)
             }
-            Event::GetBalanceResult { result, .. } => {
-                write!(formatter, "balance result: {:?}", result)
-            }
             Event::GetDeployResult { hash, result, .. } => {
                 write!(formatter, "get deploy result for {}: {:?}", hash, result)
             }
@@ -111,9 +118,23 @@ impl Display for Event {
                 finalized_block.proto_block().hash()
             ),
             Event::BlockAdded { block_hash, .. } => write!(formatter, "block added {}", block_hash),
+            Event::NextEraValidatorsResult { era_id, result, .. } => write!(
+                formatter,
+                "validators of started era {}: {:?}",
+                era_id, result
+            ),
             Event::DeployProcessed { deploy_hash, .. } => {
                 write!(formatter, "deploy processed {}", deploy_hash)
             }
+            Event::FinalitySignature {
+                block_hash,
+                public_key,
+                ..
+            } => write!(
+                formatter,
+                "finality signature for block {} by {}",
+                block_hash, public_key
+            ),
         }
     }
 }
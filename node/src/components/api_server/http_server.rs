@@ -1,69 +1,221 @@
-use std::convert::Infallible;
+use std::{convert::Infallible, sync::Arc};
 
 use futures::{
     future::{self, select},
     FutureExt,
 };
+use http::{header, HeaderValue, Method, Uri};
 use hyper::Server;
 use tokio::{
     select,
     sync::{mpsc, oneshot},
 };
 use tracing::{debug, info, trace, warn};
-use warp::Filter;
+use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
 use wheelbuf::WheelBuf;
 
 use super::{
     rest_server,
-    rpcs::{self, RpcWithOptionalParamsExt, RpcWithParamsExt, RpcWithoutParamsExt},
+    rpcs::{self, RpcWithOptionalParamsExt, RpcWithParams, RpcWithParamsExt, RpcWithoutParamsExt},
     sse_server::{self, BroadcastChannelMessage, ServerSentEvent, SSE_INITIAL_EVENT},
     Config, ReactorEventT, SseData,
 };
 use crate::{effect::EffectBuilder, utils};
 
+/// Wraps `routes` in CORS handling as configured by `allowed_origins` and `allowed_methods`.
+///
+/// If no origins are configured, `routes` are returned unchanged.  If specific origins are listed,
+/// `origin` is appended to the responses' `Vary` header, since their CORS headers depend on the
+/// request's origin.
+fn with_cors<F, R>(routes: F, config: &Config) -> BoxedFilter<(Box<dyn Reply>,)>
+where
+    F: Filter<Extract = (R,), Error = Rejection> + Clone + Send + Sync + 'static,
+    R: Reply + 'static,
+{
+    let routes = routes.map(|reply: R| Box::new(reply) as Box<dyn Reply>);
+    if config.allowed_origins.is_empty() {
+        return routes.boxed();
+    }
+
+    let allowed_methods = config
+        .allowed_methods
+        .iter()
+        .filter_map(|method| match Method::from_bytes(method.as_bytes()) {
+            Ok(method) => Some(method),
+            Err(_) => {
+                warn!(%method, "ignoring invalid CORS allowed method");
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    let cors = warp::cors()
+        .allow_methods(allowed_methods)
+        .allow_header("content-type");
+
+    if config.allowed_origins.iter().any(|origin| origin == "*") {
+        return routes
+            .with(cors.allow_any_origin())
+            .map(|reply| Box::new(reply) as Box<dyn Reply>)
+            .boxed();
+    }
+
+    let allowed_origins = config
+        .allowed_origins
+        .iter()
+        .filter_map(|origin| {
+            let normalized = normalize_origin(origin);
+            if normalized.is_none() {
+                warn!(%origin, "ignoring invalid CORS allowed origin");
+            }
+            normalized
+        })
+        .collect::<Vec<_>>();
+    routes
+        .with(cors.allow_origins(allowed_origins.iter().map(String::as_str)))
+        .map(|reply| {
+            let mut response = Reply::into_response(reply);
+            response
+                .headers_mut()
+                .append(header::VARY, HeaderValue::from_static("origin"));
+            Box::new(response) as Box<dyn Reply>
+        })
+        .boxed()
+}
+
+/// Returns `origin` in the `scheme://authority` form of an `Origin` header, or `None` if it isn't
+/// a valid origin.
+///
+/// An origin consists of a scheme and an authority only, though a trailing `/` is tolerated.
+fn normalize_origin(origin: &str) -> Option<String> {
+    let uri = origin.parse::<Uri>().ok()?;
+    let scheme = uri.scheme_str()?;
+    let authority = uri.authority()?;
+    if !(uri.path().is_empty() || uri.path() == "/") || uri.query().is_some() {
+        return None;
+    }
+    Some(format!("{}://{}", scheme, authority))
+}
+
 /// Run the HTTP server.
 ///
 /// `data_receiver` will provide the server with local events which should then be sent to all
 /// subscribed clients.
 pub(super) async fn run<REv: ReactorEventT>(
-    config: Config,
+    mut config: Config,
     effect_builder: EffectBuilder<REv>,
     mut data_receiver: mpsc::UnboundedReceiver<SseData>,
 ) {
     // REST filters.
     let rest_status = rest_server::create_status_filter(effect_builder);
     let rest_metrics = rest_server::create_metrics_filter(effect_builder);
+    let rest_queue_dump =
+        rest_server::create_queue_dump_filter(effect_builder, config.enable_queue_dump);
 
     // RPC filters.
-    let rpc_put_deploy = rpcs::account::PutDeploy::create_filter(effect_builder);
+    // Deploy submission, dry runs and the admin RPCs may require an API token; all other RPCs are
+    // open.
+    let api_token = config.api_token.take().map(Arc::new);
+    let rpc_put_deploy = rpcs::require_api_token(
+        rpcs::account::PutDeploy::create_filter(effect_builder),
+        rpcs::account::PutDeploy::METHOD,
+        api_token.clone(),
+    );
+    let rpc_dry_run_deploy = rpcs::require_api_token(
+        rpcs::account::DryRunDeploy::create_filter(effect_builder),
+        rpcs::account::DryRunDeploy::METHOD,
+        api_token.clone(),
+    );
     let rpc_get_block = rpcs::chain::GetBlock::create_filter(effect_builder);
+    let rpc_get_block_by_height = rpcs::chain::GetBlockByHeight::create_filter(effect_builder);
     let rpc_get_state_root_hash = rpcs::chain::GetStateRootHash::create_filter(effect_builder);
     let rpc_get_item = rpcs::state::GetItem::create_filter(effect_builder);
     let rpc_get_balance = rpcs::state::GetBalance::create_filter(effect_builder);
     let rpc_get_deploy = rpcs::info::GetDeploy::create_filter(effect_builder);
     let rpc_get_peers = rpcs::info::GetPeers::create_filter(effect_builder);
+    let rpc_get_bans = rpcs::info::GetBans::create_filter(effect_builder);
     let rpc_get_status = rpcs::info::GetStatus::create_filter(effect_builder);
+    let rpc_get_consensus_status = rpcs::info::GetConsensusStatus::create_filter(effect_builder);
     let rpc_get_auction_info = rpcs::state::GetAuctionInfo::create_filter(effect_builder);
+    let rpc_get_delegation = rpcs::state::GetDelegation::create_filter(effect_builder);
+    let rpc_ban_peer = rpcs::require_api_token(
+        rpcs::admin::BanPeer::create_filter(effect_builder),
+        rpcs::admin::BanPeer::METHOD,
+        api_token.clone(),
+    );
+    let rpc_unban_peer = rpcs::require_api_token(
+        rpcs::admin::UnbanPeer::create_filter(effect_builder),
+        rpcs::admin::UnbanPeer::METHOD,
+        api_token.clone(),
+    );
+    let rpc_set_log_filter = rpcs::require_api_token(
+        rpcs::admin::SetLogFilter::create_filter(effect_builder),
+        rpcs::admin::SetLogFilter::METHOD,
+        api_token,
+    );
 
     // Event stream channels and filter.
     let (broadcaster, mut new_subscriber_info_receiver, sse_filter) =
         sse_server::create_channels_and_filter();
 
-    let service = warp_json_rpc::service(
-        rest_status
-            .or(rest_metrics)
-            .or(rpc_put_deploy)
-            .or(rpc_get_block)
-            .or(rpc_get_state_root_hash)
-            .or(rpc_get_item)
-            .or(rpc_get_balance)
-            .or(rpc_get_deploy)
-            .or(rpc_get_peers)
-            .or(rpc_get_status)
-            .or(rpc_get_auction_info)
-            .or(sse_filter),
+    // All RPCs are subject to the request and response body size limits.
+    let rpc_filters = rpc_put_deploy
+        .or(rpc_dry_run_deploy)
+        .unify()
+        .or(rpc_get_block)
+        .unify()
+        .or(rpc_get_block_by_height)
+        .unify()
+        .or(rpc_get_state_root_hash)
+        .unify()
+        .or(rpc_get_item)
+        .unify()
+        .or(rpc_get_balance)
+        .unify()
+        .or(rpc_get_deploy)
+        .unify()
+        .or(rpc_get_peers)
+        .unify()
+        .or(rpc_get_bans)
+        .unify()
+        .or(rpc_get_status)
+        .unify()
+        .or(rpc_get_consensus_status)
+        .unify()
+        .or(rpc_get_auction_info)
+        .unify()
+        .or(rpc_get_delegation)
+        .unify()
+        .boxed();
+    // Banning and unbanning peers is only possible if explicitly enabled in the config.
+    let rpc_filters = if config.enable_peer_ban_rpcs {
+        rpc_filters
+            .or(rpc_ban_peer)
+            .unify()
+            .or(rpc_unban_peer)
+            .unify()
+            .boxed()
+    } else {
+        rpc_filters
+    };
+    // Changing the log filter is only possible if explicitly enabled in the config.
+    let rpc_filters = if config.enable_log_filter_rpc {
+        rpc_filters.or(rpc_set_log_filter).unify().boxed()
+    } else {
+        rpc_filters
+    };
+    let rpc_filters = rpcs::limit_body_sizes(
+        rpc_filters,
+        config.max_request_body_size_bytes,
+        config.max_response_body_size_bytes,
     );
 
+    let routes = rest_status
+        .or(rest_metrics)
+        .or(rest_queue_dump)
+        .or(rpc_filters)
+        .or(sse_filter);
+    let service = warp_json_rpc::service(with_cors(routes, &config));
+
     let mut server_address = match utils::resolve_address(&config.address) {
         Ok(address) => address,
         Err(error) => {
@@ -170,3 +322,115 @@ pub(super) async fn run<REv: ReactorEventT>(
 
     trace!("HTTP server stopped");
 }
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use super::*;
+
+    const ORIGIN: &str = "https://example.com";
+
+    fn cors_filter(allowed_origins: &[&str]) -> BoxedFilter<(Box<dyn Reply>,)> {
+        let mut config = Config::new();
+        config.allowed_origins = allowed_origins
+            .iter()
+            .map(|origin| origin.to_string())
+            .collect();
+        with_cors(warp::path("rpc").map(warp::reply), &config)
+    }
+
+    fn preflight_request() -> warp::test::RequestBuilder {
+        warp::test::request()
+            .method("OPTIONS")
+            .path("/rpc")
+            .header("origin", ORIGIN)
+            .header("access-control-request-method", "POST")
+    }
+
+    #[tokio::test]
+    async fn should_answer_preflight_for_listed_origin() {
+        let response = preflight_request().reply(&cors_filter(&[ORIGIN])).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["access-control-allow-origin"], ORIGIN);
+        assert_eq!(response.headers()["vary"], "origin");
+    }
+
+    #[tokio::test]
+    async fn should_answer_preflight_for_listed_origin_with_trailing_slash() {
+        let allowed_origin = format!("{}/", ORIGIN);
+        let response = preflight_request()
+            .reply(&cors_filter(&[&allowed_origin]))
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["access-control-allow-origin"], ORIGIN);
+    }
+
+    #[test]
+    fn should_normalize_origins() {
+        assert_eq!(normalize_origin(ORIGIN).as_deref(), Some(ORIGIN));
+        assert_eq!(
+            normalize_origin("https://example.com/").as_deref(),
+            Some(ORIGIN)
+        );
+        assert_eq!(
+            normalize_origin("http://localhost:8080").as_deref(),
+            Some("http://localhost:8080")
+        );
+        assert_eq!(normalize_origin("https://example.com/app"), None);
+        assert_eq!(normalize_origin("https://example.com/?query"), None);
+        assert_eq!(normalize_origin("example.com"), None);
+    }
+
+    #[tokio::test]
+    async fn should_append_to_existing_vary_header() {
+        let mut config = Config::new();
+        config.allowed_origins = vec![ORIGIN.to_string()];
+        let routes = warp::path("rpc")
+            .map(|| warp::reply::with_header(warp::reply(), "vary", "accept-encoding"));
+        let response = warp::test::request()
+            .path("/rpc")
+            .header("origin", ORIGIN)
+            .reply(&with_cors(routes, &config))
+            .await;
+        let vary = response
+            .headers()
+            .get_all("vary")
+            .iter()
+            .collect::<Vec<_>>();
+        assert_eq!(vary, vec!["accept-encoding", "origin"]);
+    }
+
+    #[tokio::test]
+    async fn should_answer_preflight_for_any_origin() {
+        let response = preflight_request().reply(&cors_filter(&["*"])).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response
+            .headers()
+            .contains_key("access-control-allow-origin"));
+        assert!(!response.headers().contains_key("vary"));
+    }
+
+    #[tokio::test]
+    async fn should_reject_preflight_for_unlisted_origin() {
+        let response = preflight_request()
+            .reply(&cors_filter(&["https://other.example.com"]))
+            .await;
+        assert!(!response
+            .headers()
+            .contains_key("access-control-allow-origin"));
+    }
+
+    #[tokio::test]
+    async fn should_not_add_cors_headers_without_allowed_origins() {
+        let response = warp::test::request()
+            .path("/rpc")
+            .header("origin", ORIGIN)
+            .reply(&cors_filter(&[]))
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!response
+            .headers()
+            .contains_key("access-control-allow-origin"));
+    }
+}
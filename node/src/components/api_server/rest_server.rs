@@ -22,6 +22,15 @@ pub const STATUS_API_PATH: &str = "status";
 /// The metrics URL path.
 pub const METRICS_API_PATH: &str = "metrics";
 
+/// The URL path prefix of debugging endpoints.
+pub const DEBUG_API_PATH: &str = "debug";
+
+/// The event queue dump URL path, under `DEBUG_API_PATH`.
+pub const QUEUE_DUMP_API_PATH: &str = "queues";
+
+/// The maximum number of events rendered per queue in an event queue dump.
+const QUEUE_DUMP_MAX_EVENTS: usize = 100;
+
 pub(super) fn create_status_filter<REv: ReactorEventT>(
     effect_builder: EffectBuilder<REv>,
 ) -> BoxedFilter<(Response<Body>,)> {
@@ -33,9 +42,18 @@ pub(super) fn create_status_filter<REv: ReactorEventT>(
                     |responder| ApiRequest::GetStatus { responder },
                     QueueKind::Api,
                 )
-                .map(|status_feed| {
-                    let body = GetStatusResult::from(status_feed);
-                    Ok::<_, Rejection>(reply::json(&body).into_response())
+                .map(|result| match result {
+                    Ok(status_feed) => {
+                        let body = GetStatusResult::from(status_feed);
+                        Ok::<_, Rejection>(reply::json(&body).into_response())
+                    }
+                    Err(error) => {
+                        warn!(%error, "status not available");
+                        Ok(
+                            reply::with_status(error.to_string(), StatusCode::SERVICE_UNAVAILABLE)
+                                .into_response(),
+                        )
+                    }
                 })
         })
         .boxed()
@@ -68,3 +86,30 @@ pub(super) fn create_metrics_filter<REv: ReactorEventT>(
         })
         .boxed()
 }
+
+/// Creates the filter serving a dump of the reactor's event queues.  If `enabled` is false, all
+/// requests are rejected as not found.
+pub(super) fn create_queue_dump_filter<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    enabled: bool,
+) -> BoxedFilter<(Response<Body>,)> {
+    warp::get()
+        .and(warp::path(DEBUG_API_PATH))
+        .and(warp::path(QUEUE_DUMP_API_PATH))
+        .and_then(move || async move {
+            if !enabled {
+                return Err(warp::reject::not_found());
+            }
+            let queue_dump = effect_builder
+                .make_request(
+                    |responder| ApiRequest::DumpQueues {
+                        max_events: QUEUE_DUMP_MAX_EVENTS,
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+            Ok::<_, Rejection>(reply::json(&queue_dump).into_response())
+        })
+        .boxed()
+}
@@ -3,25 +3,32 @@
 //! See https://github.com/CasperLabs/ceps/blob/master/text/0009-client-api.md#rpcs for info.
 
 pub mod account;
+pub mod admin;
 pub mod chain;
 pub mod info;
 pub mod state;
 
-use std::str;
+use std::{str, sync::Arc};
 
-use futures::{future::BoxFuture, TryFutureExt};
-use http::Response;
-use hyper::Body;
+use futures::{
+    future::{self, BoxFuture},
+    TryFutureExt,
+};
+use http::{header, Response, StatusCode};
+use hyper::{body::HttpBody, Body};
+use secrecy::{ExposeSecret, Secret};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use warp::{
     filters::BoxedFilter,
-    reject::{self, Reject},
+    reject::{self, PayloadTooLarge, Reject, Rejection},
+    reply::{self, Reply},
     Filter,
 };
 use warp_json_rpc::{filters, Builder};
 
 use super::{ApiRequest, ReactorEventT};
-use crate::effect::EffectBuilder;
+use crate::{effect::EffectBuilder, utils};
 
 /// The URL path.
 pub const RPC_API_PATH: &str = "rpc";
@@ -36,9 +43,22 @@ enum ErrorCode {
     ParseQueryKey = 32002,
     QueryFailed = 32003,
     QueryFailedToExecute = 32004,
-    ParseGetBalanceURef = 32005,
+    ParsePurseIdentifier = 32005,
     GetBalanceFailed = 32006,
     GetBalanceFailedToExecute = 32007,
+    PersistBanListFailed = 32008,
+    QueryValueNotFound = 32009,
+    QueryTypeMismatch = 32010,
+    QueryPathTooLong = 32011,
+    InvalidDeploy = 32012,
+    GetBalanceAccountNotFound = 32013,
+    GetBalancePurseNotFound = 32014,
+    GetBalanceValueNotFound = 32015,
+    DryRunFailed = 32016,
+    RequestTimedOut = 32017,
+    RequestBodyTooLarge = 32019,
+    ResponseBodyTooLarge = 32020,
+    SetLogFilterFailed = 32021,
 }
 
 #[derive(Debug)]
@@ -52,6 +72,132 @@ impl From<anyhow::Error> for Error {
     }
 }
 
+/// Rejection of a request lacking the required API token.
+#[derive(Debug)]
+struct Unauthorized;
+
+impl Reject for Unauthorized {}
+
+/// Wraps the filter of the RPC `method` so that, if `api_token` is set, requests for it are only
+/// handled if they carry an `Authorization: Bearer <api_token>` header.
+///
+/// Unauthorized requests get an HTTP 401 response.  Requests for other methods are not affected.
+pub(super) fn require_api_token(
+    rpc_filter: BoxedFilter<(Response<Body>,)>,
+    method: &'static str,
+    api_token: Option<Arc<Secret<String>>>,
+) -> BoxedFilter<(Response<Body>,)> {
+    let api_token = match api_token {
+        Some(api_token) => api_token,
+        None => return rpc_filter,
+    };
+
+    // `rpc_filter` matches the path and JSON-RPC envelope itself; only the method is checked here
+    // so that requests for other methods fall through to their own filters.
+    filters::method(method)
+        .and(warp::header::optional::<String>("authorization"))
+        .and_then(move |authorization: Option<String>| {
+            let result = if is_authorized(authorization.as_deref(), &api_token) {
+                Ok(())
+            } else {
+                Err(reject::custom(Unauthorized))
+            };
+            future::ready(result)
+        })
+        .untuple_one()
+        .and(rpc_filter)
+        .recover(|rejection: Rejection| {
+            let result = if rejection.find::<Unauthorized>().is_some() {
+                let body = json!({ "error": "unauthorized" });
+                Ok(reply::with_header(
+                    reply::with_status(reply::json(&body), StatusCode::UNAUTHORIZED),
+                    header::WWW_AUTHENTICATE,
+                    "Bearer",
+                )
+                .into_response())
+            } else {
+                Err(rejection)
+            };
+            future::ready(result)
+        })
+        .unify()
+        .boxed()
+}
+
+/// Returns whether the value of an `Authorization` header holds `api_token` as a bearer token.
+fn is_authorized(authorization: Option<&str>, api_token: &Secret<String>) -> bool {
+    match authorization.and_then(|value| value.strip_prefix("Bearer ")) {
+        Some(token) => {
+            utils::constant_time_compare(token.as_bytes(), api_token.expose_secret().as_bytes())
+        }
+        None => false,
+    }
+}
+
+/// Wraps the RPC filters so that request bodies larger than `max_request_body_size_bytes` are
+/// rejected before being read, and response bodies larger than `max_response_body_size_bytes` are
+/// replaced with an error.
+///
+/// Oversized requests get an HTTP 413 response.  As their ID is unknown, the JSON-RPC error in
+/// either case has a null ID.  Requests without a `Content-Length` header, i.e. chunked ones, can't
+/// be checked up front and are rejected by warp with HTTP 411.
+pub(super) fn limit_body_sizes(
+    rpc_filters: BoxedFilter<(Response<Body>,)>,
+    max_request_body_size_bytes: u64,
+    max_response_body_size_bytes: u64,
+) -> BoxedFilter<(Response<Body>,)> {
+    warp::body::content_length_limit(max_request_body_size_bytes)
+        .and(rpc_filters)
+        .and_then(move |response: Response<Body>| {
+            future::ok::<_, Rejection>(limit_response_size(response, max_response_body_size_bytes))
+        })
+        .recover(move |rejection: Rejection| {
+            let result = if rejection.find::<PayloadTooLarge>().is_some() {
+                Ok(error_response(
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    ErrorCode::RequestBodyTooLarge,
+                    format!(
+                        "request body exceeds the limit of {} bytes",
+                        max_request_body_size_bytes
+                    ),
+                ))
+            } else {
+                Err(rejection)
+            };
+            future::ready(result)
+        })
+        .unify()
+        .boxed()
+}
+
+/// Replaces `response` with an error if its body is known to exceed `max_body_size_bytes`.
+fn limit_response_size(response: Response<Body>, max_body_size_bytes: u64) -> Response<Body> {
+    match response.body().size_hint().exact() {
+        Some(size) if size > max_body_size_bytes => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorCode::ResponseBodyTooLarge,
+            format!(
+                "response body of {} bytes exceeds the limit of {} bytes",
+                size, max_body_size_bytes
+            ),
+        ),
+        _ => response,
+    }
+}
+
+/// Creates a JSON-RPC error response with a null ID.
+fn error_response(status: StatusCode, code: ErrorCode, message: String) -> Response<Body> {
+    let body = json!({
+        "jsonrpc": "2.0",
+        "id": null,
+        "error": {
+            "code": code as i64,
+            "message": message,
+        },
+    });
+    reply::with_status(reply::json(&body), status).into_response()
+}
+
 /// A JSON-RPC requiring the "params" field to be present.
 pub trait RpcWithParams {
     /// The JSON-RPC "method" name.
@@ -167,3 +313,157 @@ pub(super) trait RpcWithOptionalParamsExt: RpcWithOptionalParams {
         maybe_params: Option<Self::OptionalRequestParams>,
     ) -> BoxFuture<'static, Result<Response<Body>, Error>>;
 }
+
+#[cfg(test)]
+mod tests {
+    use http::Request;
+    use hyper::service::Service;
+    use serde_json::Value;
+
+    use super::*;
+
+    const MAX_REQUEST_BODY_SIZE: u64 = 16;
+    const MAX_RESPONSE_BODY_SIZE: u64 = 32;
+
+    /// Creates a filter standing in for the RPCs, which responds with `response_body`.
+    fn limited_filter(response_body: &'static str) -> BoxedFilter<(Response<Body>,)> {
+        let rpc_filter = warp::path(RPC_API_PATH)
+            .map(move || Response::new(Body::from(response_body)))
+            .boxed();
+        limit_body_sizes(rpc_filter, MAX_REQUEST_BODY_SIZE, MAX_RESPONSE_BODY_SIZE)
+    }
+
+    async fn error_code(response: Response<Body>) -> i64 {
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .expect("should read body");
+        let json: Value = serde_json::from_slice(&body).expect("should parse body");
+        json["error"]["code"]
+            .as_i64()
+            .expect("should have error code")
+    }
+
+    #[tokio::test]
+    async fn should_pass_request_within_limits() {
+        let response = warp::test::request()
+            .method("POST")
+            .path("/rpc")
+            .body(vec![b'a'; MAX_REQUEST_BODY_SIZE as usize])
+            .filter(&limited_filter("ok"))
+            .await
+            .expect("should not reject");
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn should_reject_oversized_request() {
+        let response = warp::test::request()
+            .method("POST")
+            .path("/rpc")
+            .body(vec![b'a'; MAX_REQUEST_BODY_SIZE as usize + 1])
+            .filter(&limited_filter("ok"))
+            .await
+            .expect("should not reject");
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(
+            error_code(response).await,
+            ErrorCode::RequestBodyTooLarge as i64
+        );
+    }
+
+    #[tokio::test]
+    async fn should_replace_oversized_response() {
+        let response = warp::test::request()
+            .method("POST")
+            .path("/rpc")
+            .body("{}")
+            .filter(&limited_filter(
+                "a response which is longer than thirty-two bytes",
+            ))
+            .await
+            .expect("should not reject");
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(
+            error_code(response).await,
+            ErrorCode::ResponseBodyTooLarge as i64
+        );
+    }
+
+    const API_TOKEN: &str = "test-api-token";
+    const GUARDED_METHOD: &str = "account_put_deploy";
+    const OPEN_METHOD: &str = "info_get_status";
+
+    /// Creates a stand-in for the filter of the RPC `method`, which responds with "ok".
+    fn stand_in_filter(method: &'static str) -> BoxedFilter<(Response<Body>,)> {
+        warp::path(RPC_API_PATH)
+            .and(filters::json_rpc())
+            .and(filters::method(method))
+            .map(|_: Builder| Response::new(Body::from("ok")))
+            .boxed()
+    }
+
+    /// Sends a JSON-RPC request for `method` to stand-in RPC filters, of which the one for
+    /// `GUARDED_METHOD` is guarded by `API_TOKEN`.
+    async fn send_guarded_request(method: &str, authorization: Option<&str>) -> Response<Body> {
+        let api_token = Some(Arc::new(Secret::new(API_TOKEN.to_string())));
+        let guarded_filter =
+            require_api_token(stand_in_filter(GUARDED_METHOD), GUARDED_METHOD, api_token);
+        let rpc_filters = guarded_filter
+            .or(stand_in_filter(OPEN_METHOD))
+            .unify()
+            .boxed();
+        let mut service = warp_json_rpc::service(rpc_filters);
+
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": {} });
+        let mut request = Request::post("/rpc").header("content-type", "application/json");
+        if let Some(authorization) = authorization {
+            request = request.header("authorization", authorization);
+        }
+        let request = request
+            .body(Body::from(body.to_string()))
+            .expect("should build request");
+        service.call(request).await.expect("should respond")
+    }
+
+    async fn assert_unauthorized(response: Response<Body>) {
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .expect("should read body");
+        let json: Value = serde_json::from_slice(&body).expect("should parse body");
+        assert_eq!(json["error"], "unauthorized");
+    }
+
+    #[tokio::test]
+    async fn should_accept_deploy_with_api_token() {
+        let authorization = format!("Bearer {}", API_TOKEN);
+        let response = send_guarded_request(GUARDED_METHOD, Some(&authorization)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .expect("should read body");
+        assert_eq!(&body[..], b"ok");
+    }
+
+    #[tokio::test]
+    async fn should_reject_deploy_without_api_token() {
+        let response = send_guarded_request(GUARDED_METHOD, None).await;
+        assert_unauthorized(response).await;
+    }
+
+    #[tokio::test]
+    async fn should_reject_deploy_with_wrong_api_token() {
+        let response = send_guarded_request(GUARDED_METHOD, Some("Bearer wrong-token")).await;
+        assert_unauthorized(response).await;
+
+        let authorization = format!("Basic {}", API_TOKEN);
+        let response = send_guarded_request(GUARDED_METHOD, Some(&authorization)).await;
+        assert_unauthorized(response).await;
+    }
+
+    #[tokio::test]
+    async fn should_not_require_api_token_for_other_rpcs() {
+        let response = send_guarded_request(OPEN_METHOD, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
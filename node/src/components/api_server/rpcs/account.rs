@@ -7,14 +7,16 @@ use http::Response;
 use hyper::Body;
 use semver::Version;
 use serde::{Deserialize, Serialize};
+use tracing::info;
 use warp_json_rpc::Builder;
 
-use super::{ApiRequest, Error, ReactorEventT, RpcWithParams, RpcWithParamsExt};
+use super::{ApiRequest, Error, ErrorCode, ReactorEventT, RpcWithParams, RpcWithParamsExt};
 use crate::{
     components::api_server::CLIENT_API_VERSION,
+    crypto::hash::Digest,
     effect::EffectBuilder,
     reactor::QueueKind,
-    types::{Deploy, DeployHash},
+    types::{json_compatibility::ExecutionResult, Deploy, DeployHash},
 };
 
 /// Params for "account_put_deploy" RPC request.
@@ -51,8 +53,8 @@ impl RpcWithParamsExt for PutDeploy {
         async move {
             let deploy_hash = *params.deploy.id();
 
-            // Submit the new deploy to be announced.
-            effect_builder
+            // Submit the new deploy to be validated and announced.
+            let submit_result = effect_builder
                 .make_request(
                     |responder| ApiRequest::SubmitDeploy {
                         deploy: Box::new(params.deploy),
@@ -62,10 +64,148 @@ impl RpcWithParamsExt for PutDeploy {
                 )
                 .await;
 
+            if let Err(error) = submit_result {
+                info!(%deploy_hash, %error, "rejected deploy");
+                return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                    ErrorCode::InvalidDeploy as i64,
+                    error.to_string(),
+                ))?);
+            }
+
+            // Return the result.
+            let result = Self::ResponseResult {
+                api_version: CLIENT_API_VERSION.clone(),
+                deploy_hash,
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
+/// Params for "account_dry_run_deploy" RPC request.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DryRunDeployParams {
+    /// The `Deploy`.
+    pub deploy: Deploy,
+}
+
+/// Result for "account_dry_run_deploy" RPC response.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DryRunDeployResult {
+    /// The RPC API version.
+    pub api_version: Version,
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+    /// The state root hash of the latest block, on top of which the deploy was executed.
+    pub state_root_hash: Digest,
+    /// The result of executing the deploy.  Its effects have not been committed.
+    pub execution_result: ExecutionResult,
+    /// The gas the deploy would cost.
+    pub estimated_gas: u64,
+    /// Whether the deploy would execute successfully.
+    pub would_succeed: bool,
+}
+
+/// "account_dry_run_deploy" RPC
+pub struct DryRunDeploy {}
+
+impl RpcWithParams for DryRunDeploy {
+    const METHOD: &'static str = "account_dry_run_deploy";
+    type RequestParams = DryRunDeployParams;
+    type ResponseResult = DryRunDeployResult;
+}
+
+impl RpcWithParamsExt for DryRunDeploy {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let deploy_hash = *params.deploy.id();
+
+            // Validate the deploy as if it had been submitted, without storing it.
+            let verify_result = effect_builder
+                .make_request(
+                    |responder| ApiRequest::VerifyDeploy {
+                        deploy: Box::new(params.deploy.clone()),
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+
+            if let Err(error) = verify_result {
+                info!(%deploy_hash, %error, "rejected deploy to dry run");
+                return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                    ErrorCode::InvalidDeploy as i64,
+                    error.to_string(),
+                ))?);
+            }
+
+            // Get the latest block, whose global state the deploy is executed on.
+            let maybe_block = effect_builder
+                .make_request(
+                    |responder| ApiRequest::GetBlock {
+                        maybe_hash: None,
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+            let state_root_hash = match maybe_block {
+                Some(block) => *block.state_root_hash(),
+                None => {
+                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                        ErrorCode::NoSuchBlock as i64,
+                        "no block has been added to the linear chain yet",
+                    ))?);
+                }
+            };
+
+            // Execute the deploy without committing its effects.
+            let dry_run_result = effect_builder
+                .make_request(
+                    |responder| ApiRequest::DryRunDeploy {
+                        state_root_hash,
+                        deploy: Box::new(params.deploy),
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+
+            let mut dry_run_result = match dry_run_result {
+                Ok(dry_run_result) => dry_run_result,
+                Err(error) => {
+                    info!(%deploy_hash, %error, "failed to dry run deploy");
+                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                        ErrorCode::DryRunFailed as i64,
+                        error.to_string(),
+                    ))?);
+                }
+            };
+
+            // A single deploy was executed, so there is exactly one execution result.
+            let execution_result = match dry_run_result.execution_results.pop_front() {
+                Some(execution_result) => ExecutionResult::from(&execution_result),
+                None => {
+                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                        ErrorCode::DryRunFailed as i64,
+                        "deploy produced no execution result",
+                    ))?);
+                }
+            };
+
             // Return the result.
             let result = Self::ResponseResult {
                 api_version: CLIENT_API_VERSION.clone(),
                 deploy_hash,
+                state_root_hash,
+                execution_result,
+                estimated_gas: dry_run_result.estimated_gas,
+                would_succeed: dry_run_result.would_succeed,
             };
             Ok(response_builder.success(result)?)
         }
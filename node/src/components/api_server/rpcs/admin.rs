@@ -0,0 +1,204 @@
+//! RPCs for administering the node.
+
+use futures::{future::BoxFuture, FutureExt};
+use http::Response;
+use hyper::Body;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use warp_json_rpc::Builder;
+
+use super::{ApiRequest, Error, ErrorCode, ReactorEventT, RpcWithParams, RpcWithParamsExt};
+use crate::{
+    components::{api_server::CLIENT_API_VERSION, small_network::BanTarget},
+    effect::EffectBuilder,
+    logging,
+    reactor::QueueKind,
+    types::{TimeDiff, Timestamp},
+};
+
+/// Params for "admin_ban_peer" RPC request.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BanPeerParams {
+    /// The peer to ban.
+    pub target: BanTarget,
+    /// How long the ban lasts, or `None` for a permanent ban.
+    pub duration: Option<TimeDiff>,
+}
+
+/// Result for "admin_ban_peer" RPC response.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BanPeerResult {
+    /// The RPC API version.
+    pub api_version: Version,
+    /// The moment the ban expires, or `None` if the ban is permanent.
+    pub expires: Option<Timestamp>,
+}
+
+/// "admin_ban_peer" RPC.
+pub struct BanPeer {}
+
+impl RpcWithParams for BanPeer {
+    const METHOD: &'static str = "admin_ban_peer";
+    type RequestParams = BanPeerParams;
+    type ResponseResult = BanPeerResult;
+}
+
+impl RpcWithParamsExt for BanPeer {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let expires = params.duration.map(|duration| Timestamp::now() + duration);
+
+            // Ban the peer.  The ban is in effect even if it failed to be persisted.
+            let ban_result = effect_builder
+                .make_request(
+                    |responder| ApiRequest::BanPeer {
+                        target: params.target,
+                        expires,
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+
+            if let Err(error) = ban_result {
+                let error_msg = format!("failed to persist ban of {}: {}", params.target, error);
+                info!("{}", error_msg);
+                return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                    ErrorCode::PersistBanListFailed as i64,
+                    error_msg,
+                ))?);
+            }
+
+            // Return the result.
+            let result = Self::ResponseResult {
+                api_version: CLIENT_API_VERSION.clone(),
+                expires,
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
+/// Params for "admin_unban_peer" RPC request.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UnbanPeerParams {
+    /// The peer to unban.
+    pub target: BanTarget,
+}
+
+/// Result for "admin_unban_peer" RPC response.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UnbanPeerResult {
+    /// The RPC API version.
+    pub api_version: Version,
+    /// Whether the peer was banned.
+    pub was_banned: bool,
+}
+
+/// "admin_unban_peer" RPC.
+pub struct UnbanPeer {}
+
+impl RpcWithParams for UnbanPeer {
+    const METHOD: &'static str = "admin_unban_peer";
+    type RequestParams = UnbanPeerParams;
+    type ResponseResult = UnbanPeerResult;
+}
+
+impl RpcWithParamsExt for UnbanPeer {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            // Lift the ban of the peer.
+            let unban_result = effect_builder
+                .make_request(
+                    |responder| ApiRequest::UnbanPeer {
+                        target: params.target,
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+
+            let was_banned = match unban_result {
+                Ok(was_banned) => was_banned,
+                Err(error) => {
+                    let error_msg =
+                        format!("failed to persist unban of {}: {}", params.target, error);
+                    info!("{}", error_msg);
+                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                        ErrorCode::PersistBanListFailed as i64,
+                        error_msg,
+                    ))?);
+                }
+            };
+
+            // Return the result.
+            let result = Self::ResponseResult {
+                api_version: CLIENT_API_VERSION.clone(),
+                was_banned,
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
+/// Params for "admin_set_log_filter" RPC request.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SetLogFilterParams {
+    /// The new log filter, in the same format as the `RUST_LOG` environment variable.
+    pub filter: String,
+}
+
+/// Result for "admin_set_log_filter" RPC response.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SetLogFilterResult {
+    /// The RPC API version.
+    pub api_version: Version,
+}
+
+/// "admin_set_log_filter" RPC.
+pub struct SetLogFilter {}
+
+impl RpcWithParams for SetLogFilter {
+    const METHOD: &'static str = "admin_set_log_filter";
+    type RequestParams = SetLogFilterParams;
+    type ResponseResult = SetLogFilterResult;
+}
+
+impl RpcWithParamsExt for SetLogFilter {
+    fn handle_request<REv: ReactorEventT>(
+        _effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            // The logger is global, so the filter is replaced directly rather than via a component.
+            if let Err(error) = logging::set_log_filter(&params.filter) {
+                let error_msg = format!("failed to set log filter: {}", error);
+                info!("{}", error_msg);
+                return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                    ErrorCode::SetLogFilterFailed as i64,
+                    error_msg,
+                ))?);
+            }
+            info!(filter = %params.filter, "log filter replaced");
+
+            // Return the result.
+            let result = Self::ResponseResult {
+                api_version: CLIENT_API_VERSION.clone(),
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
@@ -12,6 +12,7 @@ use warp_json_rpc::Builder;
 
 use super::{
     ApiRequest, Error, ErrorCode, ReactorEventT, RpcWithOptionalParams, RpcWithOptionalParamsExt,
+    RpcWithParams, RpcWithParamsExt,
 };
 use crate::{
     components::api_server::CLIENT_API_VERSION,
@@ -71,6 +72,62 @@ impl RpcWithOptionalParamsExt for GetBlock {
     }
 }
 
+/// Params for "chain_get_block_by_height" RPC request.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetBlockByHeightParams {
+    /// The block height.
+    pub height: u64,
+}
+
+/// "chain_get_block_by_height" RPC.
+pub struct GetBlockByHeight {}
+
+impl RpcWithParams for GetBlockByHeight {
+    const METHOD: &'static str = "chain_get_block_by_height";
+    type RequestParams = GetBlockByHeightParams;
+    type ResponseResult = GetBlockResult;
+}
+
+impl RpcWithParamsExt for GetBlockByHeight {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            // Get the block from storage via the height index.
+            let maybe_block = effect_builder
+                .make_request(
+                    |responder| ApiRequest::GetBlockAtHeight {
+                        height: params.height,
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+
+            if maybe_block.is_none() {
+                info!(
+                    "failed to get block at height {} from storage",
+                    params.height
+                );
+                return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                    ErrorCode::NoSuchBlock as i64,
+                    "block not known",
+                ))?);
+            }
+
+            // Return the result.
+            let result = Self::ResponseResult {
+                api_version: CLIENT_API_VERSION.clone(),
+                block: maybe_block,
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
 /// Params for "chain_get_state_root_hash" RPC request.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct GetStateRootHashParams {
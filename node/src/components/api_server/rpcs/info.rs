@@ -19,7 +19,12 @@ use super::{
     RpcWithoutParamsExt,
 };
 use crate::{
-    components::{api_server::CLIENT_API_VERSION, consensus::EraId, small_network::NodeId},
+    components::{
+        api_server::CLIENT_API_VERSION,
+        chainspec_loader::UpgradeSummary,
+        consensus::{EraId, ProtocolStats},
+        small_network::{BanEntry, NodeId},
+    },
     effect::EffectBuilder,
     reactor::QueueKind,
     types::{
@@ -119,7 +124,7 @@ impl RpcWithParamsExt for GetDeploy {
 pub struct GetPeersResult {
     /// The RPC API version.
     pub api_version: Version,
-    /// The node ID and network address of each connected peer.
+    /// The hex-encoded node ID and network address of each connected peer.
     pub peers: BTreeMap<String, SocketAddr>,
 }
 
@@ -155,6 +160,46 @@ impl RpcWithoutParamsExt for GetPeers {
     }
 }
 
+/// Result for "info_get_bans" RPC response.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetBansResult {
+    /// The RPC API version.
+    pub api_version: Version,
+    /// The peers banned by this node whose ban has not yet expired.
+    pub bans: Vec<BanEntry>,
+}
+
+/// "info_get_bans" RPC.
+pub struct GetBans {}
+
+impl RpcWithoutParams for GetBans {
+    const METHOD: &'static str = "info_get_bans";
+    type ResponseResult = GetBansResult;
+}
+
+impl RpcWithoutParamsExt for GetBans {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let bans = effect_builder
+                .make_request(
+                    |responder| ApiRequest::GetBans { responder },
+                    QueueKind::Api,
+                )
+                .await;
+
+            let result = Self::ResponseResult {
+                api_version: CLIENT_API_VERSION.clone(),
+                bans,
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
 /// Minimal info of a `Block`.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct MinimalBlockInfo {
@@ -184,10 +229,12 @@ pub struct GetStatusResult {
     pub chainspec_name: String,
     /// The genesis root hash.
     pub genesis_root_hash: String,
-    /// The node ID and network address of each connected peer.
+    /// The hex-encoded node ID and network address of each connected peer.
     pub peers: BTreeMap<String, SocketAddr>,
     /// The minimal info of the last block from the linear chain.
     pub last_added_block_info: Option<MinimalBlockInfo>,
+    /// The upgrades scheduled in the chainspec, in order of activation.
+    pub upgrade_schedule: Vec<UpgradeSummary>,
     /// The compiled node version.
     pub build_version: String,
 }
@@ -206,6 +253,7 @@ impl From<StatusFeed<NodeId>> for GetStatusResult {
             genesis_root_hash,
             peers: peers_hashmap_to_btreemap(status_feed.peers),
             last_added_block_info: status_feed.last_added_block.map(Into::into),
+            upgrade_schedule: status_feed.upgrade_schedule,
             build_version: crate::VERSION_STRING.clone(),
         }
     }
@@ -226,12 +274,23 @@ impl RpcWithoutParamsExt for GetStatus {
     ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
         async move {
             // Get the status.
-            let status_feed = effect_builder
+            let status_feed = match effect_builder
                 .make_request(
                     |responder| ApiRequest::GetStatus { responder },
                     QueueKind::Api,
                 )
-                .await;
+                .await
+            {
+                Ok(status_feed) => status_feed,
+                Err(error) => {
+                    let error_msg = format!("get-status failed: {}", error);
+                    info!("{}", error_msg);
+                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                        ErrorCode::RequestTimedOut as i64,
+                        error_msg,
+                    ))?);
+                }
+            };
 
             // Convert to `ResponseResult` and send.
             let result = Self::ResponseResult::from(status_feed);
@@ -241,9 +300,55 @@ impl RpcWithoutParamsExt for GetStatus {
     }
 }
 
+/// Result for "info_get_consensus_status" RPC response.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetConsensusStatusResult {
+    /// The RPC API version.
+    pub api_version: Version,
+    /// The current era.
+    pub era_id: EraId,
+    /// Whether this node is an active validator in the current era.
+    pub is_active_validator: bool,
+    /// Statistics about the consensus protocol state of the current era.
+    pub stats: ProtocolStats,
+}
+
+/// "info_get_consensus_status" RPC.
+pub struct GetConsensusStatus {}
+
+impl RpcWithoutParams for GetConsensusStatus {
+    const METHOD: &'static str = "info_get_consensus_status";
+    type ResponseResult = GetConsensusStatusResult;
+}
+
+impl RpcWithoutParamsExt for GetConsensusStatus {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let consensus_status = effect_builder
+                .make_request(
+                    |responder| ApiRequest::GetConsensusStatus { responder },
+                    QueueKind::Api,
+                )
+                .await;
+
+            let result = Self::ResponseResult {
+                api_version: CLIENT_API_VERSION.clone(),
+                era_id: consensus_status.era_id,
+                is_active_validator: consensus_status.is_active_validator,
+                stats: consensus_status.stats,
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
 fn peers_hashmap_to_btreemap(peers: HashMap<NodeId, SocketAddr>) -> BTreeMap<String, SocketAddr> {
     peers
         .into_iter()
-        .map(|(node_id, address)| (format!("{}", node_id), address))
+        .map(|(node_id, address)| (node_id.to_hex(), address))
         .collect()
 }
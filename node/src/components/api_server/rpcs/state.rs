@@ -11,16 +11,14 @@ use tracing::{debug, info};
 use warp_json_rpc::Builder;
 
 use casper_execution_engine::{
-    core::engine_state::{BalanceResult, QueryResult},
-    shared::stored_value,
-    storage::protocol_data::ProtocolData,
+    core::engine_state::QueryResult, shared::stored_value, storage::protocol_data::ProtocolData,
 };
-use casper_types::{Key, ProtocolVersion, URef, U512};
+use casper_types::{auction::Delegators, Key, ProtocolVersion, U512};
 
 use super::{ApiRequest, Error, ErrorCode, ReactorEventT, RpcWithParams, RpcWithParamsExt};
 use crate::{
-    components::api_server::CLIENT_API_VERSION,
-    crypto::hash::Digest,
+    components::api_server::{GetBalanceError, PurseIdentifier, CLIENT_API_VERSION},
+    crypto::{asymmetric_key::PublicKey, hash::Digest},
     effect::EffectBuilder,
     reactor::QueueKind,
     types::{
@@ -94,20 +92,26 @@ impl RpcWithParamsExt for GetItem {
 
             // Extract the EE `StoredValue` from the result.
             let ee_stored_value = match query_result {
-                Ok(QueryResult::Success(stored_value)) => stored_value,
-                Ok(query_result) => {
-                    let error_msg = format!("state query failed: {:?}", query_result);
+                Ok(Ok(QueryResult::Success(stored_value))) => stored_value,
+                Ok(Ok(query_result)) => {
+                    let (error_code, error_msg) = query_failure(&query_result);
+                    info!("{}", error_msg);
+                    return Ok(response_builder
+                        .error(warp_json_rpc::Error::custom(error_code as i64, error_msg))?);
+                }
+                Ok(Err(error)) => {
+                    let error_msg = format!("state query failed to execute: {}", error);
                     info!("{}", error_msg);
                     return Ok(response_builder.error(warp_json_rpc::Error::custom(
-                        ErrorCode::QueryFailed as i64,
+                        ErrorCode::QueryFailedToExecute as i64,
                         error_msg,
                     ))?);
                 }
                 Err(error) => {
-                    let error_msg = format!("state query failed to execute: {}", error);
+                    let error_msg = format!("state query failed: {}", error);
                     info!("{}", error_msg);
                     return Ok(response_builder.error(warp_json_rpc::Error::custom(
-                        ErrorCode::QueryFailedToExecute as i64,
+                        ErrorCode::RequestTimedOut as i64,
                         error_msg,
                     ))?);
                 }
@@ -132,13 +136,29 @@ impl RpcWithParamsExt for GetItem {
     }
 }
 
+/// Returns the JSON-RPC error code and message for a global state query which didn't succeed.
+fn query_failure(query_result: &QueryResult) -> (ErrorCode, String) {
+    let error_code = match query_result {
+        QueryResult::ValueNotFound(_) => ErrorCode::QueryValueNotFound,
+        QueryResult::TypeMismatch(_) => ErrorCode::QueryTypeMismatch,
+        QueryResult::PathTooLong { .. } => ErrorCode::QueryPathTooLong,
+        QueryResult::RootNotFound | QueryResult::CircularReference(_) | QueryResult::Success(_) => {
+            ErrorCode::QueryFailed
+        }
+    };
+    let error_msg = format!("state query failed: {:?}", query_result);
+    (error_code, error_msg)
+}
+
 /// Params for "state_get_balance" RPC request.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct GetBalanceParams {
     /// The hash of state root.
     pub state_root_hash: Digest,
-    /// Formatted URef.
-    pub purse_uref: String,
+    /// The purse, as a formatted URef, or the account whose main purse to use, as a formatted
+    /// account hash or a hex-encoded public key.
+    #[serde(alias = "purse_uref")]
+    pub purse_identifier: String,
 }
 
 /// Result for "state_get_balance" RPC response.
@@ -166,26 +186,25 @@ impl RpcWithParamsExt for GetBalance {
         params: Self::RequestParams,
     ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
         async move {
-            // Try to parse the purse's URef from the params.
-            let purse_uref = match URef::from_formatted_str(&params.purse_uref)
-                .map_err(|error| format!("failed to parse purse_uref: {:?}", error))
-            {
-                Ok(uref) => uref,
-                Err(error_msg) => {
-                    info!("{}", error_msg);
-                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
-                        ErrorCode::ParseGetBalanceURef as i64,
-                        error_msg,
-                    ))?);
-                }
-            };
+            // Try to parse the purse identifier from the params.
+            let purse_identifier =
+                match PurseIdentifier::from_formatted_str(&params.purse_identifier) {
+                    Ok(purse_identifier) => purse_identifier,
+                    Err(error_msg) => {
+                        info!("{}", error_msg);
+                        return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                            ErrorCode::ParsePurseIdentifier as i64,
+                            error_msg,
+                        ))?);
+                    }
+                };
 
-            // Get the balance.
+            // Get the balance, looking up the account's main purse first if required.
             let balance_result = effect_builder
                 .make_request(
                     |responder| ApiRequest::GetBalance {
                         state_root_hash: params.state_root_hash,
-                        purse_uref,
+                        purse_identifier,
                         responder,
                     },
                     QueueKind::Api,
@@ -193,20 +212,12 @@ impl RpcWithParamsExt for GetBalance {
                 .await;
 
             let balance_value = match balance_result {
-                Ok(BalanceResult::Success(value)) => value,
-                Ok(balance_result) => {
-                    let error_msg = format!("get-balance failed: {:?}", balance_result);
-                    info!("{}", error_msg);
-                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
-                        ErrorCode::GetBalanceFailed as i64,
-                        error_msg,
-                    ))?);
-                }
+                Ok(value) => value,
                 Err(error) => {
-                    let error_msg = format!("get-balance failed to execute: {}", error);
+                    let error_msg = format!("get-balance failed: {}", error);
                     info!("{}", error_msg);
                     return Ok(response_builder.error(warp_json_rpc::Error::custom(
-                        ErrorCode::GetBalanceFailedToExecute as i64,
+                        balance_failure(&error) as i64,
                         error_msg,
                     ))?);
                 }
@@ -223,6 +234,20 @@ impl RpcWithParamsExt for GetBalance {
     }
 }
 
+/// Returns the JSON-RPC error code for a balance request which didn't succeed.
+fn balance_failure(error: &GetBalanceError) -> ErrorCode {
+    match error {
+        GetBalanceError::AccountNotFound(_) => ErrorCode::GetBalanceAccountNotFound,
+        GetBalanceError::PurseNotFound(_) => ErrorCode::GetBalancePurseNotFound,
+        GetBalanceError::BalanceNotFound(_) => ErrorCode::GetBalanceValueNotFound,
+        GetBalanceError::RootNotFound | GetBalanceError::AccountQueryFailed(_) => {
+            ErrorCode::GetBalanceFailed
+        }
+        GetBalanceError::Engine(_) => ErrorCode::GetBalanceFailedToExecute,
+        GetBalanceError::Request(_) => ErrorCode::RequestTimedOut,
+    }
+}
+
 // auction info
 
 /// Params for "state_get_auction_info" RPC request.
@@ -320,7 +345,7 @@ impl RpcWithParamsExt for GetAuctionInfo {
                 .await;
 
             let bids = {
-                if let Ok(QueryResult::Success(stored_value::StoredValue::CLValue(cl_value))) =
+                if let Ok(Ok(QueryResult::Success(stored_value::StoredValue::CLValue(cl_value)))) =
                     query_result
                 {
                     cl_value.into_t().ok()
@@ -350,3 +375,182 @@ impl RpcWithParamsExt for GetAuctionInfo {
         .boxed()
     }
 }
+
+/// Params for "state_get_delegation" RPC request.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetDelegationParams {
+    /// The hash of state root.
+    pub state_root_hash: Digest,
+    /// The validator delegated to.
+    pub validator_public_key: PublicKey,
+    /// The delegator.
+    pub delegator_public_key: PublicKey,
+}
+
+/// Result for "state_get_delegation" RPC response.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetDelegationResult {
+    /// The RPC API version.
+    pub api_version: Version,
+    /// The amount currently delegated, or `None` if there is no such delegation.
+    pub delegated_amount: Option<U512>,
+}
+
+/// "state_get_delegation" RPC.
+pub struct GetDelegation {}
+
+impl RpcWithParams for GetDelegation {
+    const METHOD: &'static str = "state_get_delegation";
+    type RequestParams = GetDelegationParams;
+    type ResponseResult = GetDelegationResult;
+}
+
+impl RpcWithParamsExt for GetDelegation {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let protocol_version_result = effect_builder
+                .make_request(
+                    |responder| ApiRequest::QueryProtocolData {
+                        protocol_version: ProtocolVersion::V1_0_0,
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+
+            let protocol_data = {
+                if let Ok(Some(protocol_data)) = protocol_version_result {
+                    protocol_data
+                } else {
+                    Box::new(ProtocolData::default())
+                }
+            };
+
+            // Query the delegators named key of the auction contract.
+            let query_result = effect_builder
+                .make_request(
+                    |responder| ApiRequest::QueryGlobalState {
+                        state_root_hash: params.state_root_hash,
+                        base_key: protocol_data.auction().into(),
+                        path: vec![casper_types::auction::DELEGATORS_KEY.to_string()],
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+
+            let cl_value = match query_result {
+                Ok(Ok(QueryResult::Success(stored_value::StoredValue::CLValue(cl_value)))) => {
+                    cl_value
+                }
+                Ok(Ok(QueryResult::Success(_))) => {
+                    info!("delegators are not stored as a CLValue");
+                    return Ok(response_builder.error(warp_json_rpc::Error::INTERNAL_ERROR)?);
+                }
+                Ok(Ok(query_result)) => {
+                    let (error_code, error_msg) = query_failure(&query_result);
+                    info!("{}", error_msg);
+                    return Ok(response_builder
+                        .error(warp_json_rpc::Error::custom(error_code as i64, error_msg))?);
+                }
+                Ok(Err(error)) => {
+                    let error_msg = format!("state query failed to execute: {}", error);
+                    info!("{}", error_msg);
+                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                        ErrorCode::QueryFailedToExecute as i64,
+                        error_msg,
+                    ))?);
+                }
+                Err(error) => {
+                    let error_msg = format!("state query failed: {}", error);
+                    info!("{}", error_msg);
+                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                        ErrorCode::RequestTimedOut as i64,
+                        error_msg,
+                    ))?);
+                }
+            };
+
+            let validator_public_key: casper_types::PublicKey = params.validator_public_key.into();
+            let delegator_public_key: casper_types::PublicKey = params.delegator_public_key.into();
+            let delegated_amount = match cl_value.into_t::<Delegators>() {
+                Ok(delegators) => delegators
+                    .get(&validator_public_key)
+                    .and_then(|delegators| delegators.get(&delegator_public_key))
+                    .copied(),
+                Err(error) => {
+                    info!("failed to decode delegators: {:?}", error);
+                    return Ok(response_builder.error(warp_json_rpc::Error::INTERNAL_ERROR)?);
+                }
+            };
+
+            let result = Self::ResponseResult {
+                api_version: CLIENT_API_VERSION.clone(),
+                delegated_amount,
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::{account::AccountHash, AccessRights, URef};
+
+    use super::*;
+
+    fn query_failure_code(query_result: QueryResult) -> i64 {
+        query_failure(&query_result).0 as i64
+    }
+
+    #[test]
+    fn should_map_query_failures_to_distinct_error_codes() {
+        assert_eq!(
+            query_failure_code(QueryResult::ValueNotFound(String::new())),
+            32009
+        );
+        assert_eq!(
+            query_failure_code(QueryResult::TypeMismatch(String::new())),
+            32010
+        );
+        assert_eq!(
+            query_failure_code(QueryResult::PathTooLong {
+                path_length: 11,
+                max_path_length: 10,
+            }),
+            32011
+        );
+        assert_eq!(query_failure_code(QueryResult::RootNotFound), 32003);
+        assert_eq!(
+            query_failure_code(QueryResult::CircularReference(String::new())),
+            32003
+        );
+    }
+
+    fn balance_failure_code(error: GetBalanceError) -> i64 {
+        balance_failure(&error) as i64
+    }
+
+    #[test]
+    fn should_map_balance_failures_to_distinct_error_codes() {
+        let purse_uref = URef::new([1; 32], AccessRights::READ_ADD_WRITE);
+        assert_eq!(
+            balance_failure_code(GetBalanceError::AccountNotFound(AccountHash::new([2; 32]))),
+            32013
+        );
+        assert_eq!(
+            balance_failure_code(GetBalanceError::PurseNotFound(purse_uref)),
+            32014
+        );
+        assert_eq!(
+            balance_failure_code(GetBalanceError::BalanceNotFound(purse_uref)),
+            32015
+        );
+        assert_eq!(balance_failure_code(GetBalanceError::RootNotFound), 32006);
+    }
+}
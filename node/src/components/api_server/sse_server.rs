@@ -3,6 +3,8 @@
 use datasize::DataSize;
 use futures::{Stream, StreamExt};
 use lazy_static::lazy_static;
+#[cfg(test)]
+use rand::Rng;
 use semver::Version;
 use serde::{Deserialize, Serialize};
 use tokio::sync::{broadcast, mpsc};
@@ -13,9 +15,25 @@ use warp::{
     Filter, Reply,
 };
 
+use casper_types::U512;
+
 use super::CLIENT_API_VERSION;
-use crate::types::{
-    json_compatibility::ExecutionResult, BlockHash, BlockHeader, DeployHash, FinalizedBlock,
+use crate::{
+    components::consensus::EraId,
+    crypto::asymmetric_key::{PublicKey, Signature},
+    types::{
+        json_compatibility::ExecutionResult, BlockHash, BlockHeader, DeployHash, FinalizedBlock,
+        Timestamp,
+    },
+};
+#[cfg(test)]
+use crate::{
+    crypto::{
+        asymmetric_key::{self, SecretKey},
+        hash::Digest,
+    },
+    testing::TestRng,
+    types::Block,
 };
 
 /// The URL path.
@@ -56,6 +74,68 @@ pub enum SseData {
         block_hash: BlockHash,
         execution_result: ExecutionResult,
     },
+    /// A verified finality signature of the given block has been stored locally.  Each signer's
+    /// signature of a given block is only sent once.
+    FinalitySignature {
+        block_hash: BlockHash,
+        era_id: EraId,
+        public_key: PublicKey,
+        signature: Signature,
+    },
+    /// The given era has started, following the addition of the previous era's switch block to
+    /// the linear chain.
+    #[data_size(skip)]
+    EraStarted {
+        era_id: EraId,
+        validator_weights: Vec<(PublicKey, U512)>,
+        era_start_timestamp: Timestamp,
+    },
+}
+
+#[cfg(test)]
+impl SseData {
+    /// Generates a random instance using a `TestRng`.
+    pub(super) fn random(rng: &mut TestRng) -> Self {
+        match rng.gen_range(0, 6) {
+            0 => SseData::ApiVersion(Version::new(rng.gen(), rng.gen(), rng.gen())),
+            1 => SseData::BlockFinalized(FinalizedBlock::random(rng)),
+            2 => {
+                let block = Block::random(rng);
+                SseData::BlockAdded {
+                    block_hash: *block.hash(),
+                    block_header: block.header().clone(),
+                }
+            }
+            3 => SseData::DeployProcessed {
+                deploy_hash: DeployHash::new(Digest::random(rng)),
+                block_hash: BlockHash::new(Digest::random(rng)),
+                execution_result: ExecutionResult::random(rng),
+            },
+            4 => {
+                let block_hash = BlockHash::new(Digest::random(rng));
+                let secret_key = SecretKey::random(rng);
+                let public_key = PublicKey::from(&secret_key);
+                let signature = asymmetric_key::sign(block_hash, &secret_key, &public_key, rng);
+                SseData::FinalitySignature {
+                    block_hash,
+                    era_id: EraId(rng.gen()),
+                    public_key,
+                    signature,
+                }
+            }
+            _ => {
+                let validator_count = rng.gen_range(1, 6);
+                let validator_weights = (0..validator_count)
+                    .map(|_| (PublicKey::random(rng), U512::from(rng.gen::<u64>())))
+                    .collect();
+                SseData::EraStarted {
+                    era_id: EraId(rng.gen()),
+                    validator_weights,
+                    era_start_timestamp: Timestamp::random(rng),
+                }
+            }
+        }
+    }
 }
 
 /// The components of a single SSE.
@@ -166,7 +246,9 @@ fn stream_to_client(
                     (None, &SseData::ApiVersion { .. }) => Ok(sse::json(event.data).boxed()),
                     (Some(id), &SseData::BlockFinalized { .. })
                     | (Some(id), &SseData::BlockAdded { .. })
-                    | (Some(id), &SseData::DeployProcessed { .. }) => {
+                    | (Some(id), &SseData::DeployProcessed { .. })
+                    | (Some(id), &SseData::FinalitySignature { .. })
+                    | (Some(id), &SseData::EraStarted { .. }) => {
                         Ok((sse::id(id), sse::json(event.data)).boxed())
                     }
                     _ => unreachable!("only ApiVersion may have no event ID"),
@@ -175,3 +257,47 @@ fn stream_to_client(
             }
         })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_roundtrip_via_json() {
+        let mut rng = TestRng::new();
+        for _ in 0..50 {
+            let sse_data = SseData::random(&mut rng);
+            let json = serde_json::to_string(&sse_data).expect("should serialize");
+            let decoded: SseData = serde_json::from_str(&json).expect("should deserialize");
+            assert_eq!(decoded, sse_data);
+        }
+    }
+
+    #[test]
+    fn should_serialize_era_started() {
+        let mut rng = TestRng::new();
+        let public_key = PublicKey::random(&mut rng);
+        let era_start_timestamp = Timestamp::random(&mut rng);
+        let sse_data = SseData::EraStarted {
+            era_id: EraId(3),
+            validator_weights: vec![(public_key, U512::from(1_000))],
+            era_start_timestamp,
+        };
+
+        let json = serde_json::to_value(&sse_data).expect("should serialize");
+        let era_started = &json["EraStarted"];
+        assert_eq!(era_started["era_id"], 3);
+        assert_eq!(
+            era_started["validator_weights"][0][0],
+            serde_json::to_value(&public_key).unwrap()
+        );
+        assert_eq!(
+            era_started["validator_weights"][0][1],
+            serde_json::to_value(&U512::from(1_000)).unwrap()
+        );
+        assert_eq!(
+            era_started["era_start_timestamp"],
+            serde_json::to_value(&era_start_timestamp).unwrap()
+        );
+    }
+}
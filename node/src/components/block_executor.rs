@@ -1,15 +1,20 @@
 //! Block executor component.
 mod event;
+mod metrics;
 
 use std::{
     collections::{HashMap, VecDeque},
     fmt::Debug,
+    sync::Arc,
+    time::Instant,
 };
 
 use datasize::DataSize;
 use itertools::Itertools;
+use prometheus::Registry;
 use smallvec::SmallVec;
-use tracing::{debug, error, trace};
+use tracing::{debug, error, info_span, trace, Span};
+use tracing_futures::Instrument;
 
 use casper_execution_engine::{
     core::engine_state::{
@@ -39,6 +44,7 @@ use crate::{
     },
 };
 pub(crate) use event::Event;
+use metrics::BlockExecutorMetrics;
 
 /// A helper trait whose bounds represent the requirements for a reactor event that `BlockExecutor`
 /// can work with.
@@ -71,8 +77,18 @@ struct ExecutedBlockSummary {
 
 type BlockHeight = u64;
 
+/// A finalized block waiting for its parent to be executed.
+#[derive(DataSize, Debug)]
+struct QueuedBlock {
+    finalized_block: FinalizedBlock,
+    deploys: VecDeque<Deploy>,
+    /// The span of the block's execution.
+    #[data_size(skip)]
+    span: Span,
+}
+
 /// The Block executor component.
-#[derive(DataSize, Debug, Default)]
+#[derive(DataSize, Debug)]
 pub(crate) struct BlockExecutor {
     genesis_state_root_hash: Digest,
     /// A mapping from proto block to executed block's ID and post-state hash, to allow
@@ -83,16 +99,34 @@ pub(crate) struct BlockExecutor {
     /// created from that proto block.
     parent_map: HashMap<BlockHeight, ExecutedBlockSummary>,
     /// Finalized blocks waiting for their pre-state hash to start executing.
-    exec_queue: HashMap<BlockHeight, (FinalizedBlock, VecDeque<Deploy>)>,
+    exec_queue: HashMap<BlockHeight, QueuedBlock>,
+    /// Token cloned into the `State` of every block whose execution has started.
+    ///
+    /// The state is dropped on every exit path of an execute-commit cycle, including errors,
+    /// so the strong count minus one is the number of blocks still executing.
+    #[data_size(skip)]
+    executing_blocks: Arc<()>,
+    #[data_size(skip)]
+    metrics: BlockExecutorMetrics,
 }
 
 impl BlockExecutor {
-    pub(crate) fn new(genesis_state_root_hash: Digest) -> Self {
-        BlockExecutor {
+    pub(crate) fn new(
+        genesis_state_root_hash: Digest,
+        registry: &Registry,
+    ) -> Result<Self, prometheus::Error> {
+        Ok(BlockExecutor {
             genesis_state_root_hash,
             parent_map: HashMap::new(),
             exec_queue: HashMap::new(),
-        }
+            executing_blocks: Arc::new(()),
+            metrics: BlockExecutorMetrics::new(registry)?,
+        })
+    }
+
+    /// Returns `true` if no finalized block is currently being executed.
+    pub(crate) fn is_idle(&self) -> bool {
+        Arc::strong_count(&self.executing_blocks) == 1
     }
 
     /// Adds the "parent map" to the instance of `BlockExecutor`.
@@ -119,18 +153,39 @@ impl BlockExecutor {
     }
 
     /// Gets the deploy(s) of the given finalized block from storage.
+    ///
+    /// This starts the execution of the block, so opens the `execute_block` span which all further
+    /// logging about the block's execution is part of, including that of the effects it causes.
     fn get_deploys<REv: ReactorEventT>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
         finalized_block: FinalizedBlock,
     ) -> Effects<Event> {
-        let deploy_hashes = SmallVec::from_slice(finalized_block.proto_block().deploys());
         let era_id = finalized_block.era_id();
         let height = finalized_block.height();
+        let span = info_span!("execute_block", era_id = %era_id.0, height = %height);
+        let _entered = span.enter();
+        debug!(?finalized_block, "execute block");
+
+        if finalized_block.proto_block().deploys().is_empty() {
+            let block_span = span.clone();
+            return effect_builder
+                .immediately()
+                .instrument(span.clone())
+                .event(move |_| Event::GetDeploysResult {
+                    finalized_block,
+                    deploys: VecDeque::new(),
+                    span: block_span,
+                });
+        }
+
+        let deploy_hashes = SmallVec::from_slice(finalized_block.proto_block().deploys());
+        let block_span = span.clone();
 
         // Get all deploys in order they appear in the finalized block.
         effect_builder
             .get_deploys_from_storage(deploy_hashes)
+            .instrument(span.clone())
             .event(move |result| Event::GetDeploysResult {
                 finalized_block,
                 deploys: result
@@ -138,6 +193,7 @@ impl BlockExecutor {
                     // Assumes all deploys are present
                     .map(|maybe_deploy| maybe_deploy.unwrap_or_else(|| panic!("deploy for block in era={} and height={} is expected to exist in the storage", era_id, height)))
                     .collect(),
+                span: block_span,
             })
     }
 
@@ -150,17 +206,32 @@ impl BlockExecutor {
         // The state hash of the last execute-commit cycle is used as the block's post state
         // hash.
         let next_height = state.finalized_block.height() + 1;
+        self.metrics
+            .block_execution_duration
+            .observe(state.block_started.elapsed().as_secs_f64());
         let block = self.create_block(state.finalized_block, state.state_root_hash);
+        debug!(block_hash = %block.hash(), "block executed");
 
         let mut effects = effect_builder
             .announce_linear_chain_block(block, state.execution_results)
+            .instrument(state.span)
             .ignore();
         // If the child is already finalized, start execution.
-        if let Some((finalized_block, deploys)) = self.exec_queue.remove(&next_height) {
+        if let Some(queued_block) = self.exec_queue.remove(&next_height) {
+            self.metrics
+                .exec_queue_depth
+                .set(self.exec_queue.len() as i64);
+            let QueuedBlock {
+                finalized_block,
+                deploys,
+                span,
+            } = queued_block;
+            let _entered = span.enter();
             effects.extend(self.handle_get_deploys_result(
                 effect_builder,
                 finalized_block,
                 deploys,
+                span.clone(),
             ));
         }
         effects
@@ -176,33 +247,19 @@ impl BlockExecutor {
         let next_deploy = match state.remaining_deploys.pop_front() {
             Some(deploy) => deploy,
             None => {
-                let era_end = match state.finalized_block.era_end().as_ref() {
-                    Some(era_end) => era_end,
+                let request = match self.step_request(&state) {
+                    Some(request) => request,
                     None => return self.finalize_block_execution(effect_builder, state),
                 };
-                let reward_items = era_end
-                    .rewards
-                    .iter()
-                    .map(|(&vid, &value)| RewardItem::new(vid.into(), value))
-                    .collect();
-                let slash_items = era_end
-                    .equivocators
-                    .iter()
-                    .map(|&vid| SlashItem::new(vid.into()))
-                    .collect();
-                let request = StepRequest {
-                    pre_state_hash: state.state_root_hash.into(),
-                    protocol_version: ProtocolVersion::V1_0_0,
-                    reward_items,
-                    slash_items,
-                    run_auction: true,
-                };
+                state.request_started = Instant::now();
                 return effect_builder
                     .run_step(request)
+                    .instrument(state.span.clone())
                     .event(|result| Event::RunStepResult { state, result });
             }
         };
         let deploy_hash = *next_deploy.id();
+        trace!(%deploy_hash, "executing deploy");
         let deploy_item = DeployItem::from(next_deploy);
 
         let execute_request = ExecuteRequest::new(
@@ -212,8 +269,10 @@ impl BlockExecutor {
             ProtocolVersion::V1_0_0,
         );
 
+        state.request_started = Instant::now();
         effect_builder
             .request_execute(execute_request)
+            .instrument(state.span.clone())
             .event(move |result| Event::DeployExecutionResult {
                 state,
                 deploy_hash,
@@ -221,18 +280,53 @@ impl BlockExecutor {
             })
     }
 
+    /// If the executed block is a switch block, returns the step request ending its era.
+    fn step_request(&self, state: &State) -> Option<StepRequest> {
+        let era_end = state.finalized_block.era_end().as_ref()?;
+        let reward_items = era_end
+            .rewards
+            .iter()
+            .map(|(vid, &value)| {
+                let blocks_proposed = era_end
+                    .blocks_proposed
+                    .get(vid)
+                    .copied()
+                    .unwrap_or_default();
+                RewardItem::new((*vid).into(), value, blocks_proposed)
+            })
+            .collect();
+        let slash_items = era_end
+            .equivocators
+            .iter()
+            .map(|&vid| SlashItem::new(vid.into()))
+            .collect();
+        Some(StepRequest {
+            pre_state_hash: state.state_root_hash.into(),
+            protocol_version: ProtocolVersion::V1_0_0,
+            reward_items,
+            slash_items,
+            run_auction: true,
+        })
+    }
+
     fn handle_get_deploys_result<REv: ReactorEventT>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
         finalized_block: FinalizedBlock,
         deploys: VecDeque<Deploy>,
+        span: Span,
     ) -> Effects<Event> {
         if let Some(state_root_hash) = self.pre_state_hash(&finalized_block) {
+            let now = Instant::now();
             let state = Box::new(State {
                 finalized_block,
                 remaining_deploys: deploys,
                 execution_results: HashMap::new(),
                 state_root_hash,
+                block_started: now,
+                request_started: now,
+                span,
+                execution_token: Arc::clone(&self.executing_blocks),
             });
             self.execute_next_deploy_or_create_block(effect_builder, state)
         } else {
@@ -241,6 +335,7 @@ impl BlockExecutor {
             let height = finalized_block.height();
             effect_builder
                 .get_block_at_height_local(height - 1)
+                .instrument(span.clone())
                 .event(|parent| Event::GetParentResult {
                     finalized_block,
                     deploys,
@@ -251,6 +346,7 @@ impl BlockExecutor {
                             *b.state_root_hash(),
                         )
                     }),
+                    span,
                 })
         }
     }
@@ -261,13 +357,24 @@ impl BlockExecutor {
         finalized_block: FinalizedBlock,
         deploys: VecDeque<Deploy>,
         parent: Option<ExecutedBlockSummary>,
+        span: Span,
     ) -> Effects<Event> {
         match parent {
             None => {
                 let height = finalized_block.height();
-                debug!("no pre-state hash for height {}", height);
+                debug!(%height, "no pre-state hash, delaying execution");
                 // The parent block has not been executed yet; delay handling.
-                self.exec_queue.insert(height, (finalized_block, deploys));
+                self.exec_queue.insert(
+                    height,
+                    QueuedBlock {
+                        finalized_block,
+                        deploys,
+                        span,
+                    },
+                );
+                self.metrics
+                    .exec_queue_depth
+                    .set(self.exec_queue.len() as i64);
                 Effects::new()
             }
             Some(parent_summary) => {
@@ -276,7 +383,7 @@ impl BlockExecutor {
                 // It will be removed in `create_block` method.
                 self.parent_map
                     .insert(finalized_block.height().saturating_sub(1), parent_summary);
-                self.handle_get_deploys_result(effect_builder, finalized_block, deploys)
+                self.handle_get_deploys_result(effect_builder, finalized_block, deploys, span)
             }
         }
     }
@@ -299,8 +406,8 @@ impl BlockExecutor {
             .insert(deploy_hash, execution_result);
 
         let execution_effect = match ee_execution_result {
-            EngineExecutionResult::Success { effect, cost } => {
-                debug!(?effect, %cost, "execution succeeded");
+            EngineExecutionResult::Success { effect, cost, .. } => {
+                debug!(%deploy_hash, ?effect, %cost, "execution succeeded");
                 effect
             }
             EngineExecutionResult::Failure {
@@ -308,12 +415,13 @@ impl BlockExecutor {
                 effect,
                 cost,
             } => {
-                error!(?error, ?effect, %cost, "execution failure");
+                error!(%deploy_hash, ?error, ?effect, %cost, "execution failure");
                 effect
             }
         };
         effect_builder
             .request_commit(state.state_root_hash, execution_effect.transforms)
+            .instrument(state.span.clone())
             .event(|commit_result| Event::CommitExecutionEffects {
                 state,
                 commit_result,
@@ -371,33 +479,37 @@ impl<REv: ReactorEventT> Component<REv> for BlockExecutor {
         _rng: &mut dyn CryptoRngCore,
         event: Self::Event,
     ) -> Effects<Self::Event> {
+        // Handle the event within the span of the execution of the block it relates to.
+        let span = match &event {
+            Event::Request(_) => Span::none(),
+            Event::GetDeploysResult { span, .. } | Event::GetParentResult { span, .. } => {
+                span.clone()
+            }
+            Event::DeployExecutionResult { state, .. }
+            | Event::CommitExecutionEffects { state, .. }
+            | Event::RunStepResult { state, .. } => state.span.clone(),
+        };
+        let _entered = span.enter();
+
         match event {
             Event::Request(BlockExecutorRequest::ExecuteBlock(finalized_block)) => {
-                debug!(?finalized_block, "execute block");
-                if finalized_block.proto_block().deploys().is_empty() {
-                    effect_builder
-                        .immediately()
-                        .event(move |_| Event::GetDeploysResult {
-                            finalized_block,
-                            deploys: VecDeque::new(),
-                        })
-                } else {
-                    self.get_deploys(effect_builder, finalized_block)
-                }
+                self.get_deploys(effect_builder, finalized_block)
             }
 
             Event::GetDeploysResult {
                 finalized_block,
                 deploys,
+                span,
             } => {
                 trace!(total = %deploys.len(), ?deploys, "fetched deploys");
-                self.handle_get_deploys_result(effect_builder, finalized_block, deploys)
+                self.handle_get_deploys_result(effect_builder, finalized_block, deploys, span)
             }
 
             Event::GetParentResult {
                 finalized_block,
                 deploys,
                 parent,
+                span,
             } => {
                 trace!(parent_found = %parent.is_some(), finalized_height = %finalized_block.height(), "fetched parent");
                 let parent_summary =
@@ -413,6 +525,7 @@ impl<REv: ReactorEventT> Component<REv> for BlockExecutor {
                     finalized_block,
                     deploys,
                     parent_summary,
+                    span,
                 )
             }
 
@@ -421,7 +534,10 @@ impl<REv: ReactorEventT> Component<REv> for BlockExecutor {
                 deploy_hash,
                 result,
             } => {
-                trace!(?state, %deploy_hash, ?result, "deploy execution result");
+                trace!(%deploy_hash, ?state, ?result, "deploy execution result");
+                self.metrics
+                    .deploy_execution_duration
+                    .observe(state.request_started.elapsed().as_secs_f64());
                 // As for now a given state is expected to exist.
                 let execution_results = result.unwrap();
                 self.commit_execution_effects(effect_builder, state, deploy_hash, execution_results)
@@ -452,6 +568,9 @@ impl<REv: ReactorEventT> Component<REv> for BlockExecutor {
 
             Event::RunStepResult { mut state, result } => {
                 trace!(?result, "run step result");
+                self.metrics
+                    .step_duration
+                    .observe(state.request_started.elapsed().as_secs_f64());
                 match result {
                     Ok(StepResult::Success { post_state_hash }) => {
                         state.state_root_hash = post_state_hash.into();
@@ -466,3 +585,256 @@ impl<REv: ReactorEventT> Component<REv> for BlockExecutor {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use derive_more::From;
+    use futures::FutureExt;
+    use tracing::Level;
+
+    use casper_execution_engine::shared::gas::Gas;
+
+    use super::*;
+    use crate::{
+        components::consensus::EraId,
+        crypto::asymmetric_key::PublicKey,
+        reactor::{EventQueueHandle, QueueKind, Scheduler},
+        testing::{CaptureWriter, TestRng},
+        types::{ProtoBlock, Timestamp},
+        utils,
+    };
+
+    type EraEnd = crate::components::consensus::EraEnd<PublicKey>;
+
+    fn state_for_block(
+        era_id: u64,
+        height: u64,
+        proposer: PublicKey,
+        era_end: Option<EraEnd>,
+    ) -> State {
+        let finalized_block = FinalizedBlock::new(
+            ProtoBlock::new(vec![], false),
+            Timestamp::now(),
+            era_end,
+            EraId(era_id),
+            height,
+            proposer,
+        );
+        let now = Instant::now();
+        State {
+            finalized_block,
+            remaining_deploys: VecDeque::new(),
+            execution_results: HashMap::new(),
+            state_root_hash: Digest::default(),
+            block_started: now,
+            request_started: now,
+            span: Span::none(),
+            execution_token: Arc::new(()),
+        }
+    }
+
+    /// Returns an era end rewarding each validator, with the given number of proposed blocks.
+    fn era_end(blocks_proposed: &[(PublicKey, u64)]) -> EraEnd {
+        EraEnd {
+            equivocators: vec![],
+            rewards: blocks_proposed.iter().map(|&(vid, _)| (vid, 1)).collect(),
+            blocks_proposed: blocks_proposed
+                .iter()
+                .filter(|(_, count)| *count > 0)
+                .copied()
+                .collect(),
+        }
+    }
+
+    /// Returns the number of blocks proposed by each validator, according to the reward items.
+    fn blocks_proposed(step_request: &StepRequest) -> BTreeMap<casper_types::PublicKey, u64> {
+        step_request
+            .reward_items
+            .iter()
+            .map(|reward_item| (reward_item.validator_id, reward_item.blocks_proposed))
+            .collect()
+    }
+
+    #[test]
+    fn should_take_blocks_proposed_from_era_end() {
+        let mut rng = TestRng::new();
+        let alice = PublicKey::random(&mut rng);
+        let bob = PublicKey::random(&mut rng);
+        let carol = PublicKey::random(&mut rng);
+
+        let block_executor = BlockExecutor::new(Digest::default(), &Registry::new())
+            .expect("should create block executor");
+
+        // Blocks that don't end an era don't create a step request.
+        let state = state_for_block(0, 0, alice, None);
+        assert!(block_executor.step_request(&state).is_none());
+
+        // Validators missing from the era end's counts didn't propose any block.
+        let era_end = era_end(&[(alice, 2), (bob, 2), (carol, 0)]);
+        let switch_block = state_for_block(0, 4, bob, Some(era_end));
+        let step_request = block_executor
+            .step_request(&switch_block)
+            .expect("should create step request for switch block");
+        let expected: BTreeMap<_, _> = vec![(alice.into(), 2), (bob.into(), 2), (carol.into(), 0)]
+            .into_iter()
+            .collect();
+        assert_eq!(blocks_proposed(&step_request), expected);
+    }
+
+    #[test]
+    fn should_be_idle_once_executing_state_is_dropped() {
+        let mut rng = TestRng::new();
+        let block_executor = BlockExecutor::new(Digest::default(), &Registry::new())
+            .expect("should create block executor");
+        assert!(block_executor.is_idle());
+
+        // The state is dropped without completing, as on an execution error.
+        let mut state = state_for_block(0, 0, PublicKey::random(&mut rng), None);
+        state.execution_token = Arc::clone(&block_executor.executing_blocks);
+        assert!(!block_executor.is_idle());
+        drop(state);
+        assert!(block_executor.is_idle());
+    }
+
+    /// A reactor event type the block executor can be driven with in tests.
+    #[derive(Debug, From)]
+    enum ReactorEvent {
+        BlockExecutor(Event),
+        Storage(StorageRequest<Storage>),
+        LinearChain(LinearChainRequest<NodeId>),
+        ContractRuntime(ContractRuntimeRequest),
+        Announcement(BlockExecutorAnnouncement),
+    }
+
+    #[test]
+    fn should_observe_execution_metrics() {
+        let mut rng = TestRng::new();
+        let registry = Registry::new();
+        let mut block_executor =
+            BlockExecutor::new(Digest::default(), &registry).expect("should create block executor");
+        let scheduler = utils::leak(Scheduler::<ReactorEvent>::new(QueueKind::weights()));
+        let effect_builder = EffectBuilder::new(EventQueueHandle::new(scheduler));
+
+        // Execute a switch block with a single deploy, feeding the component the results the
+        // contract runtime would return.
+        let proposer = PublicKey::random(&mut rng);
+        let state = Box::new(state_for_block(
+            0,
+            0,
+            proposer,
+            Some(era_end(&[(proposer, 1)])),
+        ));
+        let deploy_hash = *Deploy::random(&mut rng).id();
+        let execution_result = EngineExecutionResult::Success {
+            effect: Default::default(),
+            cost: Gas::default(),
+            execution_trace: Vec::new(),
+        };
+        let events = vec![
+            Event::DeployExecutionResult {
+                state,
+                deploy_hash,
+                result: Ok(vec![execution_result].into()),
+            },
+            // The states below stand in for the one moved into the returned effects.
+            Event::CommitExecutionEffects {
+                state: Box::new(state_for_block(
+                    0,
+                    0,
+                    proposer,
+                    Some(era_end(&[(proposer, 1)])),
+                )),
+                commit_result: Ok(CommitResult::Success {
+                    state_root: Digest::default().into(),
+                }),
+            },
+            Event::RunStepResult {
+                state: Box::new(state_for_block(
+                    0,
+                    0,
+                    proposer,
+                    Some(era_end(&[(proposer, 1)])),
+                )),
+                result: Ok(StepResult::Success {
+                    post_state_hash: Digest::default().into(),
+                }),
+            },
+        ];
+        for event in events {
+            let _effects = block_executor.handle_event(effect_builder, &mut rng, event);
+        }
+
+        let metrics = &block_executor.metrics;
+        assert_eq!(metrics.deploy_execution_duration.get_sample_count(), 1);
+        assert_eq!(metrics.step_duration.get_sample_count(), 1);
+        assert_eq!(metrics.block_execution_duration.get_sample_count(), 1);
+        assert_eq!(metrics.exec_queue_depth.get(), 0);
+
+        let metric_names: Vec<_> = registry
+            .gather()
+            .iter()
+            .map(|family| family.get_name().to_string())
+            .collect();
+        for name in &[
+            "block_executor_deploy_execution_duration_seconds",
+            "block_executor_block_execution_duration_seconds",
+            "block_executor_step_duration_seconds",
+            "block_executor_exec_queue_depth",
+        ] {
+            assert!(metric_names.iter().any(|metric_name| metric_name == name));
+        }
+    }
+
+    #[test]
+    fn should_log_within_block_execution_span() {
+        let mut rng = TestRng::new();
+        let mut block_executor = BlockExecutor::new(Digest::default(), &Registry::new())
+            .expect("should create block executor");
+        let scheduler = utils::leak(Scheduler::<ReactorEvent>::new(QueueKind::weights()));
+        let effect_builder = EffectBuilder::new(EventQueueHandle::new(scheduler));
+
+        let writer = CaptureWriter::default();
+        let make_writer = writer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || make_writer.clone())
+            .with_max_level(Level::TRACE)
+            .with_ansi(false)
+            .finish();
+
+        let finalized_block = FinalizedBlock::new(
+            ProtoBlock::new(vec![], false),
+            Timestamp::now(),
+            None,
+            EraId(0),
+            0,
+            PublicKey::random(&mut rng),
+        );
+        tracing::subscriber::with_default(subscriber, || {
+            let event = Event::Request(BlockExecutorRequest::ExecuteBlock(finalized_block));
+            let effects = block_executor.handle_event(effect_builder, &mut rng, event);
+            // Feed the (empty) list of deploys back, upon which the block gets executed.
+            let events: Vec<Event> = effects
+                .into_iter()
+                .flat_map(|effect| effect.now_or_never().expect("should be ready"))
+                .collect();
+            for event in events {
+                let _effects = block_executor.handle_event(effect_builder, &mut rng, event);
+            }
+        });
+
+        let output = writer.contents();
+        for message in &["execute block", "fetched deploys", "block executed"] {
+            let line = output
+                .lines()
+                .find(|line| line.contains(message))
+                .unwrap_or_else(|| panic!("should log {:?}:\n{}", message, output));
+            assert!(
+                line.contains("execute_block{era_id=0 height=0}"),
+                "{}",
+                line
+            );
+        }
+    }
+}
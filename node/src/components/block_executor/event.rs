@@ -14,7 +14,10 @@ use derive_more::From;
 use std::{
     collections::{HashMap, VecDeque},
     fmt::Display,
+    sync::Arc,
+    time::Instant,
 };
+use tracing::Span;
 
 /// Block executor component event.
 #[derive(Debug, From)]
@@ -28,6 +31,8 @@ pub enum Event {
         finalized_block: FinalizedBlock,
         /// Contents of deploys. All deploys are expected to be present in the storage component.
         deploys: VecDeque<Deploy>,
+        /// The span of the block's execution.
+        span: Span,
     },
     GetParentResult {
         /// The block that needs the deploys for execution.
@@ -37,6 +42,8 @@ pub enum Event {
         /// Parent of the newly finalized block.
         /// If it's the first block after Genesis then `parent` is `None`.
         parent: Option<(BlockHash, Digest, Digest)>,
+        /// The span of the block's execution.
+        span: Span,
     },
     /// The result of executing a single deploy.
     DeployExecutionResult {
@@ -70,6 +77,7 @@ impl Display for Event {
             Event::GetDeploysResult {
                 finalized_block,
                 deploys,
+                ..
             } => write!(
                 f,
                 "fetch deploys for finalized block with height {} has {} deploys",
@@ -155,4 +163,13 @@ pub struct State {
     /// Current state root hash of global storage.  Is initialized with the parent block's
     /// state hash, and is updated after each commit.
     pub state_root_hash: Digest,
+    /// When execution of the block started.
+    pub block_started: Instant,
+    /// When the currently outstanding execute or step request was made.
+    pub request_started: Instant,
+    /// The span of the block's execution, which all logging about it is part of.
+    pub span: Span,
+    /// Keeps the block counted as executing in `BlockExecutor::is_idle` until this state is
+    /// dropped.
+    pub execution_token: Arc<()>,
 }
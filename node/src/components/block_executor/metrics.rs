@@ -0,0 +1,82 @@
+use prometheus::{Histogram, HistogramOpts, IntGauge, Registry};
+
+/// Value of upper bound of the first histogram bucket, in seconds.
+const EXPONENTIAL_BUCKET_START: f64 = 0.01;
+/// Multiplier of previous upper bound for next bound.
+const EXPONENTIAL_BUCKET_FACTOR: f64 = 2.0;
+/// Bucket count, with last going to +Inf.
+const EXPONENTIAL_BUCKET_COUNT: usize = 10;
+
+/// Metrics of the block executor component.
+#[derive(Debug)]
+pub struct BlockExecutorMetrics {
+    /// Time taken to execute a single deploy, in seconds.
+    pub(super) deploy_execution_duration: Histogram,
+    /// Time taken to execute and commit all deploys of a block, including the step, in seconds.
+    pub(super) block_execution_duration: Histogram,
+    /// Time taken to run the step at the end of an era, in seconds.
+    pub(super) step_duration: Histogram,
+    /// Number of finalized blocks waiting for their parent to be executed.
+    pub(super) exec_queue_depth: IntGauge,
+    /// Reference to the registry for unregistering.
+    registry: Registry,
+}
+
+/// Creates a histogram with the common buckets of the block executor metrics.
+fn new_histogram(name: &str, help: &str) -> Result<Histogram, prometheus::Error> {
+    let buckets = prometheus::exponential_buckets(
+        EXPONENTIAL_BUCKET_START,
+        EXPONENTIAL_BUCKET_FACTOR,
+        EXPONENTIAL_BUCKET_COUNT,
+    )?;
+    Histogram::with_opts(HistogramOpts::new(name, help).buckets(buckets))
+}
+
+impl BlockExecutorMetrics {
+    pub fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
+        let deploy_execution_duration = new_histogram(
+            "block_executor_deploy_execution_duration_seconds",
+            "time in seconds to execute a single deploy",
+        )?;
+        let block_execution_duration = new_histogram(
+            "block_executor_block_execution_duration_seconds",
+            "time in seconds to execute and commit all deploys of a block, including the step",
+        )?;
+        let step_duration = new_histogram(
+            "block_executor_step_duration_seconds",
+            "time in seconds to run the step at the end of an era",
+        )?;
+        let exec_queue_depth = IntGauge::new(
+            "block_executor_exec_queue_depth",
+            "number of finalized blocks waiting for their parent block to be executed",
+        )?;
+        registry.register(Box::new(deploy_execution_duration.clone()))?;
+        registry.register(Box::new(block_execution_duration.clone()))?;
+        registry.register(Box::new(step_duration.clone()))?;
+        registry.register(Box::new(exec_queue_depth.clone()))?;
+        Ok(BlockExecutorMetrics {
+            deploy_execution_duration,
+            block_execution_duration,
+            step_duration,
+            exec_queue_depth,
+            registry: registry.clone(),
+        })
+    }
+}
+
+impl Drop for BlockExecutorMetrics {
+    fn drop(&mut self) {
+        self.registry
+            .unregister(Box::new(self.deploy_execution_duration.clone()))
+            .expect("did not expect deregistering deploy execution duration to fail");
+        self.registry
+            .unregister(Box::new(self.block_execution_duration.clone()))
+            .expect("did not expect deregistering block execution duration to fail");
+        self.registry
+            .unregister(Box::new(self.step_duration.clone()))
+            .expect("did not expect deregistering step duration to fail");
+        self.registry
+            .unregister(Box::new(self.exec_queue_depth.clone()))
+            .expect("did not expect deregistering exec queue depth to fail");
+    }
+}
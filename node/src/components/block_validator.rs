@@ -1,7 +1,8 @@
 //! Block validator
 //!
 //! The block validator checks whether all the deploys included in the proto block exist, either
-//! locally or on the network.
+//! locally or on the network. Proposed blocks are also checked against their era's context: the
+//! timestamp must not be earlier than the era start.
 //!
 //! When multiple requests are made to validate the same proto block, they will eagerly return true
 //! if valid, but only fail if all sources have been exhausted. This is only relevant when calling
@@ -17,6 +18,8 @@ use std::{
 use datasize::DataSize;
 use derive_more::{Display, From};
 use smallvec::{smallvec, SmallVec};
+use thiserror::Error;
+use tracing::debug;
 
 use crate::{
     components::Component,
@@ -24,10 +27,54 @@ use crate::{
         requests::{BlockValidationRequest, FetcherRequest},
         EffectBuilder, EffectExt, EffectOptionExt, Effects, Responder,
     },
-    types::{BlockLike, CryptoRngCore, Deploy, DeployHash},
+    types::{BlockLike, CryptoRngCore, Deploy, DeployHash, Timestamp},
 };
 use keyed_counter::KeyedCounter;
 
+/// The reason a block failed validation.
+#[derive(Clone, DataSize, Debug, Error, PartialEq, Eq)]
+pub enum InvalidBlock {
+    /// Some of the block's deploys could be found neither locally nor on the network.
+    #[error("block contains deploys which could not be fetched")]
+    MissingDeploys,
+
+    /// The block's timestamp is earlier than the start of its era.
+    #[error("block timestamp {timestamp} is earlier than the era start {era_start_time}")]
+    TimestampBeforeEraStart {
+        timestamp: Timestamp,
+        era_start_time: Timestamp,
+    },
+}
+
+/// The era context a proposed block is validated in.
+///
+/// Highway has already checked the vote proposing the block: its creator is a validator of the
+/// era and not banned, its timestamp is not earlier than its justifications' (including the
+/// parent's), and votes from the future are held back until they are due. The only check left
+/// is that the block does not predate its era.
+#[derive(Clone, Debug)]
+pub(crate) struct ProposalContext {
+    /// The block's timestamp.
+    pub(crate) timestamp: Timestamp,
+    /// The start time of the block's era.
+    pub(crate) era_start_time: Timestamp,
+}
+
+impl ProposalContext {
+    /// Checks that the block's timestamp is not earlier than the start of its era.
+    ///
+    /// This doesn't depend on the local clock, so all nodes come to the same result.
+    fn check(&self) -> Result<(), InvalidBlock> {
+        if self.timestamp < self.era_start_time {
+            return Err(InvalidBlock::TimestampBeforeEraStart {
+                timestamp: self.timestamp,
+                era_start_time: self.era_start_time,
+            });
+        }
+        Ok(())
+    }
+}
+
 /// Block validator component event.
 #[derive(Debug, From, Display)]
 pub enum Event<T, I> {
@@ -52,7 +99,7 @@ pub(crate) struct BlockValidationState<T> {
     /// The deploys that have not yet been "crossed off" the list of potential misses.
     missing_deploys: HashSet<DeployHash>,
     /// A list of responders that are awaiting an answer.
-    responders: SmallVec<[Responder<(bool, T)>; 2]>,
+    responders: SmallVec<[Responder<(Result<(), InvalidBlock>, T)>; 2]>,
 }
 
 /// Block validator.
@@ -99,12 +146,19 @@ where
             Event::Request(BlockValidationRequest {
                 block,
                 sender,
+                context,
                 responder,
             }) => {
+                if let Some(context) = context {
+                    if let Err(error) = context.check() {
+                        debug!(%error, "rejecting proposed block");
+                        return responder.respond((Err(error), block)).ignore();
+                    }
+                }
                 if block.deploys().is_empty() {
                     // If there are no deploys, return early.
                     let mut effects = Effects::new();
-                    effects.extend(responder.respond((true, block)).ignore());
+                    effects.extend(responder.respond((Ok(()), block)).ignore());
                     return effects;
                 }
                 // No matter the current state, we will request the deploys inside this protoblock
@@ -167,7 +221,7 @@ where
                     if state.missing_deploys.is_empty() {
                         // This one is done and valid.
                         state.responders.drain(..).for_each(|responder| {
-                            effects.extend(responder.respond((true, key.clone())).ignore());
+                            effects.extend(responder.respond((Ok(()), key.clone())).ignore());
                         });
                         false
                     } else {
@@ -194,7 +248,11 @@ where
                         // This validation state contains a failed deploy hash, it can never
                         // succeed.
                         state.responders.drain(..).for_each(|responder| {
-                            effects.extend(responder.respond((false, key.clone())).ignore());
+                            effects.extend(
+                                responder
+                                    .respond((Err(InvalidBlock::MissingDeploys), key.clone()))
+                                    .ignore(),
+                            );
                         });
                         false
                     } else {
@@ -207,3 +265,34 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ERA_START: u64 = 1_000;
+
+    fn context(timestamp: u64) -> ProposalContext {
+        ProposalContext {
+            timestamp: timestamp.into(),
+            era_start_time: ERA_START.into(),
+        }
+    }
+
+    #[test]
+    fn should_accept_proposal_from_era_start() {
+        assert_eq!(context(ERA_START).check(), Ok(()));
+        assert_eq!(context(ERA_START + 1).check(), Ok(()));
+    }
+
+    #[test]
+    fn should_reject_timestamp_before_era_start() {
+        assert_eq!(
+            context(ERA_START - 1).check(),
+            Err(InvalidBlock::TimestampBeforeEraStart {
+                timestamp: (ERA_START - 1).into(),
+                era_start_time: ERA_START.into(),
+            })
+        );
+    }
+}
@@ -94,6 +94,18 @@ impl From<ChainspecLoader> for ChainspecInfo {
     }
 }
 
+/// Summary of a protocol upgrade scheduled in the chainspec.
+#[derive(Clone, DataSize, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpgradeSummary {
+    /// The rank at which the upgrade activates.
+    pub activation_rank: u64,
+    /// The protocol version after the upgrade.
+    #[data_size(skip)]
+    pub protocol_version: Version,
+    /// Whether the upgrade runs an installer.
+    pub has_installer: bool,
+}
+
 #[derive(Clone, DataSize, Debug, Serialize, Deserialize)]
 pub(crate) struct ChainspecLoader {
     chainspec: Chainspec,
@@ -111,6 +123,7 @@ impl ChainspecLoader {
     where
         REv: From<Event> + From<StorageRequest<Storage>> + Send,
     {
+        chainspec.validate_upgrades()?;
         let version = chainspec.genesis.protocol_version.clone();
         let effects = effect_builder
             .put_chainspec(chainspec.clone())
@@ -140,6 +153,19 @@ impl ChainspecLoader {
     pub(crate) fn chainspec(&self) -> &Chainspec {
         &self.chainspec
     }
+
+    /// Returns a summary of the upgrades scheduled in the chainspec, in order of activation.
+    pub(crate) fn upgrade_schedule(&self) -> Vec<UpgradeSummary> {
+        self.chainspec
+            .upgrades
+            .iter()
+            .map(|upgrade_point| UpgradeSummary {
+                activation_rank: upgrade_point.activation_point.rank,
+                protocol_version: upgrade_point.protocol_version.clone(),
+                has_installer: upgrade_point.upgrade_installer_bytes.is_some(),
+            })
+            .collect()
+    }
 }
 
 impl<REv> Component<REv> for ChainspecLoader
@@ -158,6 +184,9 @@ where
             Event::Request(ChainspecLoaderRequest::GetChainspecInfo(req)) => {
                 req.respond(self.clone().into()).ignore()
             }
+            Event::Request(ChainspecLoaderRequest::GetUpgradeSchedule(req)) => {
+                req.respond(self.upgrade_schedule()).ignore()
+            }
             Event::PutToStorage { version } => {
                 debug!("stored chainspec {}", version);
                 effect_builder
@@ -166,24 +195,18 @@ where
             }
             Event::CommitGenesisResult(result) => {
                 match result {
-                    Ok(genesis_result) => match genesis_result {
-                        GenesisResult::RootNotFound
-                        | GenesisResult::KeyNotFound(_)
-                        | GenesisResult::TypeMismatch(_)
-                        | GenesisResult::Serialization(_) => {
-                            error!("failed to commit genesis: {}", genesis_result);
-                            self.completed_successfully = Some(false);
-                        }
-                        GenesisResult::Success {
-                            post_state_hash,
-                            effect,
-                        } => {
+                    Ok(genesis_result) => match genesis_result.post_state_hash() {
+                        Some(post_state_hash) => {
                             info!("chainspec name {}", self.chainspec.genesis.name);
                             info!("genesis state root hash {}", post_state_hash);
-                            trace!(%post_state_hash, ?effect);
+                            trace!(%post_state_hash, ?genesis_result);
                             self.completed_successfully = Some(true);
                             self.genesis_state_root_hash = Some(post_state_hash.into());
                         }
+                        None => {
+                            error!("failed to commit genesis: {}", genesis_result);
+                            self.completed_successfully = Some(false);
+                        }
                     },
                     Err(error) => {
                         error!("failed to commit genesis: {}", error);
@@ -195,3 +218,43 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        reactor::{EventQueueHandle, QueueKind, Scheduler},
+        utils::{self, Loadable},
+    };
+
+    #[derive(Debug, From)]
+    enum ReactorEvent {
+        ChainspecLoader(Event),
+        Storage(StorageRequest<Storage>),
+    }
+
+    fn new_loader(chainspec: Chainspec) -> Result<ChainspecLoader, Error> {
+        let scheduler = utils::leak(Scheduler::<ReactorEvent>::new(QueueKind::weights()));
+        let effect_builder = EffectBuilder::new(EventQueueHandle::new(scheduler));
+        ChainspecLoader::new(chainspec, effect_builder).map(|(loader, _effects)| loader)
+    }
+
+    #[test]
+    fn should_summarize_upgrade_schedule() {
+        let chainspec = Chainspec::from_resources("test/valid/chainspec.toml");
+        let loader = new_loader(chainspec).expect("should create chainspec loader");
+        let expected = vec![
+            UpgradeSummary {
+                activation_rank: 23,
+                protocol_version: Version::new(0, 2, 0),
+                has_installer: true,
+            },
+            UpgradeSummary {
+                activation_rank: 39,
+                protocol_version: Version::new(0, 3, 0),
+                has_installer: false,
+            },
+        ];
+        assert_eq!(loader.upgrade_schedule(), expected);
+    }
+}
@@ -180,6 +180,8 @@ pub struct GenesisConfig {
     pub(crate) name: String,
     pub(crate) timestamp: Timestamp,
     pub(crate) validator_slots: u32,
+    /// Number of eras after genesis during which the genesis validators' stakes are locked.
+    pub(crate) locked_funds_period: u64,
     // We don't have an implementation for the semver version type, we skip it for now
     #[data_size(skip)]
     pub(crate) protocol_version: Version,
@@ -260,6 +262,7 @@ impl GenesisConfig {
         let name = rng.gen::<char>().to_string();
         let timestamp = Timestamp::random(rng);
         let validator_slots = rng.gen::<u32>();
+        let locked_funds_period = rng.gen::<u64>();
         let protocol_version = Version::new(
             rng.gen_range(0, 10),
             rng.gen::<u8>() as u64,
@@ -278,6 +281,7 @@ impl GenesisConfig {
             name,
             timestamp,
             validator_slots,
+            locked_funds_period,
             protocol_version,
             mint_installer_bytes,
             pos_installer_bytes,
@@ -371,6 +375,39 @@ impl Chainspec {
     pub fn validate_config(&self) {
         self.genesis.validate_config();
     }
+
+    /// Checks that the upgrades are listed in order of activation, that each one increases the
+    /// protocol version, and that installer args are only given along with an installer.
+    pub(crate) fn validate_upgrades(&self) -> Result<(), Error> {
+        let mut previous_rank = None;
+        let mut previous_version = &self.genesis.protocol_version;
+        for upgrade in &self.upgrades {
+            let rank = upgrade.activation_point.rank;
+            match previous_rank {
+                Some(previous) if previous == rank => {
+                    return Err(Error::DuplicateActivationPoint(rank))
+                }
+                Some(previous) if previous > rank => {
+                    return Err(Error::DecreasingActivationPoint { previous, rank })
+                }
+                _ => (),
+            }
+            if upgrade.protocol_version <= *previous_version {
+                return Err(Error::NonIncreasingProtocolVersion {
+                    rank,
+                    previous: previous_version.clone(),
+                    version: upgrade.protocol_version.clone(),
+                });
+            }
+            if upgrade.upgrade_installer_args.is_some() && upgrade.upgrade_installer_bytes.is_none()
+            {
+                return Err(Error::UpgradeArgsWithoutInstaller(rank));
+            }
+            previous_rank = Some(rank);
+            previous_version = &upgrade.protocol_version;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -385,7 +422,7 @@ impl Chainspec {
 
 impl Into<ExecConfig> for Chainspec {
     fn into(self) -> ExecConfig {
-        ExecConfig::new(
+        let mut exec_config = ExecConfig::new(
             self.genesis.mint_installer_bytes,
             self.genesis.pos_installer_bytes,
             self.genesis.standard_payment_installer_bytes,
@@ -393,7 +430,9 @@ impl Into<ExecConfig> for Chainspec {
             self.genesis.accounts,
             self.genesis.wasm_config,
             self.genesis.validator_slots,
-        )
+        );
+        exec_config.set_locked_funds_period(self.genesis.locked_funds_period);
+        exec_config
     }
 }
 
@@ -631,6 +670,7 @@ mod tests {
         assert_eq!(spec.genesis.name, "test-chain");
         assert_eq!(spec.genesis.timestamp.millis(), 1600454700000);
         assert_eq!(spec.genesis.protocol_version, Version::from((0, 1, 0)));
+        assert_eq!(spec.genesis.locked_funds_period, 7);
         assert_eq!(spec.genesis.mint_installer_bytes, b"Mint installer bytes");
         assert_eq!(
             spec.genesis.pos_installer_bytes,
@@ -756,4 +796,57 @@ mod tests {
         let chainspec = Chainspec::random(&mut rng);
         testing::bincode_roundtrip(&chainspec);
     }
+
+    #[test]
+    fn bundled_spec_has_valid_upgrades() {
+        let spec = Chainspec::from_resources("test/valid/chainspec.toml");
+        assert!(spec.validate_upgrades().is_ok());
+    }
+
+    #[test]
+    fn should_reject_overlapping_activation_points() {
+        let mut spec = Chainspec::from_resources("test/valid/chainspec.toml");
+        spec.upgrades[1].activation_point = spec.upgrades[0].activation_point;
+        assert!(matches!(
+            spec.validate_upgrades(),
+            Err(Error::DuplicateActivationPoint(23))
+        ));
+
+        spec.upgrades[1].activation_point = ActivationPoint { rank: 22 };
+        assert!(matches!(
+            spec.validate_upgrades(),
+            Err(Error::DecreasingActivationPoint {
+                previous: 23,
+                rank: 22
+            })
+        ));
+    }
+
+    #[test]
+    fn should_reject_decreasing_protocol_versions() {
+        let mut spec = Chainspec::from_resources("test/valid/chainspec.toml");
+        spec.upgrades[1].protocol_version = Version::from((0, 1, 5));
+        assert!(matches!(
+            spec.validate_upgrades(),
+            Err(Error::NonIncreasingProtocolVersion { rank: 39, .. })
+        ));
+
+        // The first upgrade must also increase the genesis protocol version.
+        let mut spec = Chainspec::from_resources("test/valid/chainspec.toml");
+        spec.upgrades[0].protocol_version = spec.genesis.protocol_version.clone();
+        assert!(matches!(
+            spec.validate_upgrades(),
+            Err(Error::NonIncreasingProtocolVersion { rank: 23, .. })
+        ));
+    }
+
+    #[test]
+    fn should_reject_installer_args_without_installer() {
+        let mut spec = Chainspec::from_resources("test/valid/chainspec.toml");
+        spec.upgrades[1].upgrade_installer_args = Some(vec![1]);
+        assert!(matches!(
+            spec.validate_upgrades(),
+            Err(Error::UpgradeArgsWithoutInstaller(39))
+        ));
+    }
 }
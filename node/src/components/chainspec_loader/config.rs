@@ -8,6 +8,7 @@ use serde::{Deserialize, Serialize};
 use casper_execution_engine::{
     core::engine_state::genesis::GenesisAccount, shared::wasm_config::WasmConfig,
 };
+use casper_types::auction::DEFAULT_LOCKED_FUNDS_PERIOD;
 
 use super::{chainspec, DeployConfig, Error, HighwayConfig};
 use crate::{
@@ -29,6 +30,7 @@ struct Genesis {
     name: String,
     timestamp: Timestamp,
     validator_slots: u32,
+    locked_funds_period: u64,
     protocol_version: Version,
     mint_installer_path: External<Vec<u8>>,
     pos_installer_path: External<Vec<u8>>,
@@ -43,6 +45,7 @@ impl Default for Genesis {
             name: String::from(DEFAULT_CHAIN_NAME),
             timestamp: Timestamp::zero(),
             validator_slots: DEFAULT_VALIDATOR_SLOTS,
+            locked_funds_period: DEFAULT_LOCKED_FUNDS_PERIOD,
             protocol_version: Version::from((1, 0, 0)),
             mint_installer_path: External::path(DEFAULT_MINT_INSTALLER_PATH),
             pos_installer_path: External::path(DEFAULT_POS_INSTALLER_PATH),
@@ -121,6 +124,7 @@ impl From<&chainspec::Chainspec> for ChainspecConfig {
             name: chainspec.genesis.name.clone(),
             timestamp: chainspec.genesis.timestamp,
             validator_slots: chainspec.genesis.validator_slots,
+            locked_funds_period: chainspec.genesis.locked_funds_period,
             protocol_version: chainspec.genesis.protocol_version.clone(),
             mint_installer_path: External::path(DEFAULT_MINT_INSTALLER_PATH),
             pos_installer_path: External::path(DEFAULT_POS_INSTALLER_PATH),
@@ -199,6 +203,7 @@ pub(super) fn parse_toml<P: AsRef<Path>>(chainspec_path: P) -> Result<chainspec:
         name: chainspec.genesis.name,
         timestamp: chainspec.genesis.timestamp,
         validator_slots: chainspec.genesis.validator_slots,
+        locked_funds_period: chainspec.genesis.locked_funds_period,
         protocol_version: chainspec.genesis.protocol_version,
         mint_installer_bytes,
         pos_installer_bytes,
@@ -1,3 +1,4 @@
+use semver::Version;
 use thiserror::Error;
 use uint::FromDecStrErr;
 
@@ -62,6 +63,40 @@ pub enum Error {
     /// Error loading the genesis accounts.
     #[error("could not load genesis accounts: {0}")]
     LoadGenesisAccounts(LoadError<GenesisLoadError>),
+
+    /// Two upgrades have the same activation point.
+    #[error("more than one upgrade activates at rank {0}")]
+    DuplicateActivationPoint(u64),
+
+    /// An upgrade activates before the upgrade listed ahead of it.
+    #[error(
+        "upgrade activating at rank {rank} is listed after one activating at rank {previous} - \
+        upgrades must be listed in order of activation"
+    )]
+    DecreasingActivationPoint {
+        /// The activation rank of the preceding upgrade.
+        previous: u64,
+        /// The activation rank of the offending upgrade.
+        rank: u64,
+    },
+
+    /// An upgrade does not increase the protocol version.
+    #[error(
+        "upgrade activating at rank {rank} has protocol version {version}, which is not greater \
+        than the preceding version {previous}"
+    )]
+    NonIncreasingProtocolVersion {
+        /// The activation rank of the offending upgrade.
+        rank: u64,
+        /// The protocol version of genesis or of the preceding upgrade.
+        previous: Version,
+        /// The protocol version of the offending upgrade.
+        version: Version,
+    },
+
+    /// An upgrade has installer args, but no installer.
+    #[error("upgrade activating at rank {0} has installer args, but no upgrade installer")]
+    UpgradeArgsWithoutInstaller(u64),
 }
 
 /// Error loading genesis accounts file.
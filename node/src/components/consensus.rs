@@ -6,6 +6,7 @@ mod consensus_protocol;
 mod era_supervisor;
 mod highway_core;
 mod metrics;
+mod proposal_rules;
 mod protocols;
 #[cfg(test)]
 mod tests;
@@ -26,19 +27,21 @@ use crate::{
             self, BlockExecutorRequest, BlockValidationRequest, ContractRuntimeRequest,
             DeployBufferRequest, NetworkRequest, StorageRequest,
         },
-        EffectBuilder, Effects,
+        EffectBuilder, Effects, RequestError,
     },
     protocol::Message,
     types::{BlockHash, BlockHeader, CryptoRngCore, ProtoBlock, Timestamp},
 };
 
 pub use config::Config;
+pub use consensus_protocol::ProtocolStats;
 pub(crate) use consensus_protocol::{BlockContext, EraEnd};
 use derive_more::From;
 pub(crate) use era_supervisor::{EraId, EraSupervisor};
 use hex_fmt::HexFmt;
+use proposal_rules::InvalidProposal;
 use serde::{Deserialize, Serialize};
-use tracing::error;
+use tracing::{error, info, warn};
 use traits::NodeIdT;
 
 #[derive(Debug, DataSize, Clone, Serialize, Deserialize)]
@@ -50,6 +53,17 @@ pub enum ConsensusMessage {
     EvidenceRequest { era_id: EraId, pub_key: PublicKey },
 }
 
+/// The status of the consensus protocol instance of the current era.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ConsensusStatus {
+    /// The current era.
+    pub era_id: EraId,
+    /// Whether this node is an active validator, creating new units, in the current era.
+    pub is_active_validator: bool,
+    /// Statistics about the protocol state of the current era.
+    pub stats: ProtocolStats,
+}
+
 /// Consensus component event.
 #[derive(DataSize, Debug, From)]
 pub enum Event<I> {
@@ -75,6 +89,7 @@ pub enum Event<I> {
         era_id: EraId,
         sender: I,
         proto_block: ProtoBlock,
+        reason: InvalidProposal,
     },
     /// Event raised when a new era should be created: once we get the set of validators, the
     /// booking block hash and the seed from the key block
@@ -85,7 +100,12 @@ pub enum Event<I> {
         booking_block_hash: Result<BlockHash, u64>,
         /// Ok(seed) if the key block was found, Err(height) if not
         key_block_seed: Result<Digest, u64>,
-        get_validators_result: Result<Option<ValidatorWeights>, GetEraValidatorsError>,
+        /// The validators of the new era, or an error if the contract runtime didn't return them
+        /// in time
+        get_validators_result:
+            Result<Result<Option<ValidatorWeights>, GetEraValidatorsError>, RequestError>,
+        /// The number of earlier requests for the validators that timed out
+        attempt: u32,
     },
 }
 
@@ -139,10 +159,12 @@ impl<I: Debug> Display for Event<I> {
                 era_id,
                 sender,
                 proto_block,
+                reason,
             } => write!(
                 f,
-                "A proto-block received from {:?} turned out to be invalid for era {:?}: {:?}",
-                sender, era_id, proto_block
+                "A proto-block received from {:?} turned out to be invalid for era {:?}: {:?} \
+                ({})",
+                sender, era_id, proto_block, reason
             ),
             Event::CreateNewEra {
                 booking_block_hash,
@@ -213,6 +235,9 @@ where
                 block_header,
                 responder,
             )) => handling_es.handle_linear_chain_block(*block_header, responder),
+            Event::ConsensusRequest(requests::ConsensusRequest::Status(responder)) => {
+                handling_es.handle_get_status(responder)
+            }
             Event::AcceptProtoBlock {
                 era_id,
                 proto_block,
@@ -221,13 +246,35 @@ where
                 era_id,
                 sender,
                 proto_block,
-            } => handling_es.handle_invalid_proto_block(era_id, sender, proto_block),
+                reason,
+            } => handling_es.handle_invalid_proto_block(era_id, sender, proto_block, reason),
             Event::CreateNewEra {
                 block_header,
                 booking_block_hash,
                 key_block_seed,
                 get_validators_result,
+                attempt,
             } => {
+                let get_validators_result = match get_validators_result {
+                    Ok(result) => result,
+                    Err(error @ RequestError::Timeout(_)) => {
+                        warn!(
+                            %error,
+                            attempt,
+                            "getting the validators for era {} timed out, retrying",
+                            block_header.era_id().successor()
+                        );
+                        return handling_es.request_new_era(*block_header, attempt + 1);
+                    }
+                    Err(error @ RequestError::ResponderDropped) => {
+                        info!(
+                            %error,
+                            "not creating era {}, the validators request can't be answered",
+                            block_header.era_id().successor()
+                        );
+                        return Effects::new();
+                    }
+                };
                 let booking_block_hash = booking_block_hash.unwrap_or_else(|height| {
                     error!(
                         "could not find the booking block at height {} for era {}",
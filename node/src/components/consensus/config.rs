@@ -1,7 +1,16 @@
+use std::time::Duration;
+
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    components::chainspec_loader::HighwayConfig, crypto::asymmetric_key::SecretKey,
+    types::TimeDiff, utils::External,
+};
 
-use crate::{crypto::asymmetric_key::SecretKey, utils::External};
+/// Default time to wait for the contract runtime to return the validators of a new era.
+const DEFAULT_GET_VALIDATORS_TIMEOUT: Duration = Duration::from_secs(60);
 
 /// Consensus configuration.
 #[derive(DataSize, Debug, Deserialize, Serialize, Default, Clone)]
@@ -10,4 +19,227 @@ use crate::{crypto::asymmetric_key::SecretKey, utils::External};
 pub struct Config {
     /// Path to secret key file.
     pub secret_key_path: External<SecretKey>,
+    /// The maximum round exponent this node uses for its own votes.  Must be at least the
+    /// chainspec's `minimum_round_exponent`, and the corresponding round length must not exceed
+    /// the chainspec's `era_duration`.  Defaults to the chainspec's `minimum_round_exponent`.
+    pub max_round_exponent: Option<u8>,
+    /// How long to wait for a block to propose before abandoning the proposal.  Must be at least
+    /// the chainspec's minimum round length and at most its `era_duration`.  Defaults to the
+    /// chainspec's `era_duration`.
+    pub proposal_timeout: Option<TimeDiff>,
+    /// How long to wait for the contract runtime to return the validators of a new era before
+    /// retrying the request.  Defaults to one minute.
+    pub get_validators_timeout: Option<TimeDiff>,
+}
+
+impl Config {
+    /// Checks that the node-local overrides lie within the bounds defined by the chainspec.
+    pub(crate) fn validate(&self, highway_config: &HighwayConfig) -> Result<(), ConfigError> {
+        let minimum_round_exponent = highway_config.minimum_round_exponent;
+        let era_duration = highway_config.era_duration;
+
+        if let Some(max_round_exponent) = self.max_round_exponent {
+            if max_round_exponent < minimum_round_exponent {
+                return Err(ConfigError::MaxRoundExponentTooLow {
+                    max_round_exponent,
+                    minimum_round_exponent,
+                });
+            }
+            let round_length = 1u64.checked_shl(u32::from(max_round_exponent));
+            if round_length.map_or(true, |round_length| round_length > era_duration.millis()) {
+                return Err(ConfigError::MaxRoundExponentTooHigh {
+                    max_round_exponent,
+                    era_duration,
+                });
+            }
+        }
+
+        if let Some(proposal_timeout) = self.proposal_timeout {
+            let minimum_round_length = TimeDiff::from(1u64 << minimum_round_exponent);
+            if proposal_timeout < minimum_round_length {
+                return Err(ConfigError::ProposalTimeoutTooShort {
+                    proposal_timeout,
+                    minimum_round_length,
+                });
+            }
+            if proposal_timeout > era_duration {
+                return Err(ConfigError::ProposalTimeoutTooLong {
+                    proposal_timeout,
+                    era_duration,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the configured maximum round exponent, or the chainspec's minimum if not set.
+    pub(crate) fn max_round_exponent(&self, highway_config: &HighwayConfig) -> u8 {
+        self.max_round_exponent
+            .unwrap_or(highway_config.minimum_round_exponent)
+    }
+
+    /// Returns the configured proposal timeout, or the chainspec's era duration if not set.
+    pub(crate) fn proposal_timeout(&self, highway_config: &HighwayConfig) -> TimeDiff {
+        self.proposal_timeout.unwrap_or(highway_config.era_duration)
+    }
+
+    /// Returns the configured timeout for getting the validators of a new era, or the default if
+    /// not set.
+    pub(crate) fn get_validators_timeout(&self) -> Duration {
+        self.get_validators_timeout
+            .map_or(DEFAULT_GET_VALIDATORS_TIMEOUT, Duration::from)
+    }
+}
+
+/// Error returned when the node-local consensus config conflicts with the chainspec.
+#[derive(Debug, Error)]
+pub(crate) enum ConfigError {
+    /// The maximum round exponent is below the chainspec's minimum.
+    #[error(
+        "consensus max_round_exponent {max_round_exponent} is less than the chainspec's \
+        minimum_round_exponent {minimum_round_exponent}"
+    )]
+    MaxRoundExponentTooLow {
+        max_round_exponent: u8,
+        minimum_round_exponent: u8,
+    },
+
+    /// The round length for the maximum round exponent is longer than an era.
+    #[error(
+        "consensus max_round_exponent {max_round_exponent} gives a round length longer than the \
+        chainspec's era_duration {era_duration}"
+    )]
+    MaxRoundExponentTooHigh {
+        max_round_exponent: u8,
+        era_duration: TimeDiff,
+    },
+
+    /// The proposal timeout is shorter than the chainspec's minimum round length.
+    #[error(
+        "consensus proposal_timeout {proposal_timeout} is shorter than the chainspec's minimum \
+        round length {minimum_round_length}"
+    )]
+    ProposalTimeoutTooShort {
+        proposal_timeout: TimeDiff,
+        minimum_round_length: TimeDiff,
+    },
+
+    /// The proposal timeout is longer than the chainspec's era duration.
+    #[error(
+        "consensus proposal_timeout {proposal_timeout} is longer than the chainspec's \
+        era_duration {era_duration}"
+    )]
+    ProposalTimeoutTooLong {
+        proposal_timeout: TimeDiff,
+        era_duration: TimeDiff,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    fn highway_config() -> HighwayConfig {
+        HighwayConfig {
+            era_duration: TimeDiff::from_str("1hour").unwrap(),
+            minimum_round_exponent: 14,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_accept_overrides_within_bounds() {
+        let highway_config = highway_config();
+        let config = Config {
+            max_round_exponent: Some(18),
+            proposal_timeout: Some(TimeDiff::from_str("1min").unwrap()),
+            ..Default::default()
+        };
+        config
+            .validate(&highway_config)
+            .expect("should accept overrides within bounds");
+        assert_eq!(config.max_round_exponent(&highway_config), 18);
+        assert_eq!(
+            config.proposal_timeout(&highway_config),
+            TimeDiff::from_str("1min").unwrap()
+        );
+    }
+
+    #[test]
+    fn should_default_to_chainspec_values() {
+        let highway_config = highway_config();
+        let config = Config::default();
+        config
+            .validate(&highway_config)
+            .expect("should accept missing overrides");
+        assert_eq!(config.max_round_exponent(&highway_config), 14);
+        assert_eq!(
+            config.proposal_timeout(&highway_config),
+            highway_config.era_duration
+        );
+        assert_eq!(
+            config.get_validators_timeout(),
+            DEFAULT_GET_VALIDATORS_TIMEOUT
+        );
+    }
+
+    #[test]
+    fn should_reject_max_round_exponent_out_of_bounds() {
+        let highway_config = highway_config();
+
+        let config = Config {
+            max_round_exponent: Some(13),
+            ..Default::default()
+        };
+        let error = config.validate(&highway_config).unwrap_err();
+        assert_matches!(
+            error,
+            ConfigError::MaxRoundExponentTooLow {
+                max_round_exponent: 13,
+                minimum_round_exponent: 14
+            }
+        );
+        let message = error.to_string();
+        assert!(message.contains("13") && message.contains("14"));
+
+        // 2^22 ms is longer than an hour.
+        for max_round_exponent in &[22, 64, u8::MAX] {
+            let config = Config {
+                max_round_exponent: Some(*max_round_exponent),
+                ..Default::default()
+            };
+            assert_matches!(
+                config.validate(&highway_config),
+                Err(ConfigError::MaxRoundExponentTooHigh { .. })
+            );
+        }
+    }
+
+    #[test]
+    fn should_reject_proposal_timeout_out_of_bounds() {
+        let highway_config = highway_config();
+
+        let config = Config {
+            proposal_timeout: Some(TimeDiff::from_str("1s").unwrap()),
+            ..Default::default()
+        };
+        assert_matches!(
+            config.validate(&highway_config),
+            Err(ConfigError::ProposalTimeoutTooShort { .. })
+        );
+
+        let config = Config {
+            proposal_timeout: Some(TimeDiff::from_str("2hours").unwrap()),
+            ..Default::default()
+        };
+        assert_matches!(
+            config.validate(&highway_config),
+            Err(ConfigError::ProposalTimeoutTooLong { .. })
+        );
+    }
 }
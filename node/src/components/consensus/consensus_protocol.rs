@@ -42,6 +42,8 @@ pub struct EraEnd<VID> {
     /// This is a measure of the value of each validator's contribution to consensus, in
     /// fractions of the configured maximum block reward.
     pub(crate) rewards: BTreeMap<VID, u64>,
+    /// The number of blocks in this era proposed by each validator, including the terminal block.
+    pub(crate) blocks_proposed: BTreeMap<VID, u64>,
 }
 
 /// A finalized block. All nodes are guaranteed to see the same sequence of blocks, and to agree
@@ -57,10 +59,26 @@ pub(crate) struct FinalizedBlock<C: ConsensusValueT, VID> {
     pub(crate) height: u64,
     /// If this is a terminal block, i.e. the last one to be finalized, this includes rewards.
     pub(crate) rewards: Option<BTreeMap<VID, u64>>,
+    /// If this is a terminal block, this includes the number of blocks in the era proposed by each
+    /// validator.
+    pub(crate) blocks_proposed: Option<BTreeMap<VID, u64>>,
     /// Proposer of this value
     pub(crate) proposer: VID,
 }
 
+/// Statistics about the state of a consensus protocol instance, for monitoring.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ProtocolStats {
+    /// The number of units, i.e. votes, in the protocol state.
+    pub unit_count: u64,
+    /// The round exponent of our next round, if we are an active validator.
+    pub round_exponent: Option<u8>,
+    /// The number of validators known to be faulty.
+    pub fault_count: u64,
+    /// The timestamp of the latest unit we created, if any.
+    pub our_last_unit_timestamp: Option<Timestamp>,
+}
+
 #[derive(Debug)]
 pub(crate) enum ConsensusProtocolResult<I, C: ConsensusValueT, VID> {
     CreatedGossipMessage(Vec<u8>),
@@ -80,7 +98,17 @@ pub(crate) enum ConsensusProtocolResult<I, C: ConsensusValueT, VID> {
     /// The domain logic should verify any intrinsic validity conditions of consensus values, e.g.
     /// that it has the expected structure, or that deploys that are mentioned by hash actually
     /// exist, and then call `ConsensusProtocol::resolve_validity`.
-    ValidateConsensusValue(I, C),
+    ValidateConsensusValue {
+        /// The node the value was received from.
+        sender: I,
+        /// The proposed consensus value.
+        value: C,
+        /// The timestamp of the vote proposing the value, by which the value's deploys must not
+        /// have expired.
+        timestamp: Timestamp,
+        /// The values of the value's ancestors in this era, starting with its parent.
+        ancestor_values: Vec<C>,
+    },
     /// New direct evidence was added against the given validator.
     NewEvidence(VID),
     /// Send evidence about the validator from an earlier era to the peer.
@@ -130,6 +158,9 @@ pub(crate) trait ConsensusProtocol<I, C: ConsensusValueT, VID> {
     /// Turns this instance into a passive observer, that does not create any new vertices.
     fn deactivate_validator(&mut self);
 
+    /// Returns whether this instance is an active validator, creating new vertices.
+    fn is_active(&self) -> bool;
+
     /// Returns whether the validator `vid` is known to be faulty.
     fn has_evidence(&self, vid: &VID) -> bool;
 
@@ -141,4 +172,9 @@ pub(crate) trait ConsensusProtocol<I, C: ConsensusValueT, VID> {
 
     /// Returns the list of all validators that were observed as faulty in this consensus instance.
     fn validators_with_evidence(&self) -> Vec<&VID>;
+
+    /// Returns statistics about the protocol state, for monitoring.
+    fn stats(&self) -> ProtocolStats {
+        ProtocolStats::default()
+    }
 }
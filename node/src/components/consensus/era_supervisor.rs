@@ -10,6 +10,7 @@ use std::{
     convert::TryInto,
     fmt::{self, Debug, Formatter},
     rc::Rc,
+    time::Duration,
 };
 
 use anyhow::Error;
@@ -36,6 +37,7 @@ use casper_types::{
 
 use crate::{
     components::{
+        block_validator::ProposalContext,
         chainspec_loader::{Chainspec, HighwayConfig},
         consensus::{
             candidate_block::CandidateBlock,
@@ -45,17 +47,22 @@ use crate::{
             },
             highway_core::{highway::Params, validators::Validators},
             metrics::ConsensusMetrics,
+            proposal_rules::{self, InvalidProposal},
             protocols::highway::{HighwayContext, HighwayProtocol, HighwaySecret},
             traits::NodeIdT,
-            Config, ConsensusMessage, Event, ReactorEventT,
+            Config, ConsensusMessage, ConsensusStatus, Event, ReactorEventT,
         },
+        storage::Storage,
     },
     crypto::{
-        asymmetric_key::{self, PublicKey, SecretKey, Signature},
+        asymmetric_key::{PublicKey, SecretKey, Signature},
         hash,
     },
-    effect::{EffectBuilder, EffectExt, Effects, Responder},
-    types::{BlockHash, BlockHeader, CryptoRngCore, FinalizedBlock, ProtoBlock, Timestamp},
+    effect::{requests::StorageRequest, EffectBuilder, EffectExt, Effects, Responder},
+    types::{
+        BlockHash, BlockHeader, CryptoRngCore, DeployHash, FinalizedBlock, ProtoBlock, TimeDiff,
+        Timestamp,
+    },
     utils::WithDir,
 };
 
@@ -66,6 +73,28 @@ use crate::{
 /// receive blocks that refer to `BONDED_ERAS` before that.
 const BONDED_ERAS: u64 = DEFAULT_UNBONDING_DELAY - AUCTION_DELAY;
 
+/// The delay before the first retry of a timed-out request for a new era's validators.
+const NEW_ERA_INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// The maximum delay between retries of a timed-out request for a new era's validators.
+const NEW_ERA_MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Returns the delay before making the request for a new era's validators for the given attempt.
+///
+/// The first attempt is made right away, and the delay doubles with every retry up to
+/// `NEW_ERA_MAX_RETRY_DELAY`.
+fn new_era_retry_delay(attempt: u32) -> Duration {
+    match attempt.checked_sub(1) {
+        None => Duration::from_secs(0),
+        Some(retry) => 1u32
+            .checked_shl(retry)
+            .and_then(|factor| NEW_ERA_INITIAL_RETRY_DELAY.checked_mul(factor))
+            .map_or(NEW_ERA_MAX_RETRY_DELAY, |delay| {
+                delay.min(NEW_ERA_MAX_RETRY_DELAY)
+            }),
+    }
+}
+
 #[derive(
     DataSize, Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
 )]
@@ -105,6 +134,35 @@ impl Display for EraId {
     }
 }
 
+/// Returns the validators of an era with the given stakes, scaled down to `u64` weights, with the
+/// `slashed` ones banned.
+///
+/// Panics if the total stake is zero.
+fn era_validators(
+    validator_stakes: Vec<(PublicKey, Motes)>,
+    slashed: &HashSet<PublicKey>,
+) -> Validators<PublicKey> {
+    let sum_stakes: Motes = validator_stakes.iter().map(|(_, stake)| *stake).sum();
+    assert!(
+        !sum_stakes.value().is_zero(),
+        "cannot start era with total weight 0"
+    );
+    // For Highway, we need u64 weights. Scale down by  sum / u64::MAX,  rounded up.
+    // If we round up the divisor, the resulting sum is guaranteed to be  <= u64::MAX.
+    let scaling_factor = (sum_stakes.value() + U512::from(u64::MAX) - 1) / U512::from(u64::MAX);
+    let scale_stake = |(key, stake): (PublicKey, Motes)| {
+        (key, AsPrimitive::<u64>::as_(stake.value() / scaling_factor))
+    };
+    let mut validators: Validators<PublicKey> =
+        validator_stakes.into_iter().map(scale_stake).collect();
+
+    for pub_key in slashed {
+        validators.ban(pub_key);
+    }
+
+    validators
+}
+
 /// A candidate block waiting for validation and dependencies.
 #[derive(DataSize)]
 pub struct PendingCandidate {
@@ -135,6 +193,8 @@ pub struct Era<I> {
     consensus: Box<dyn ConsensusProtocol<I, CandidateBlock, PublicKey>>,
     /// The height of this era's first block.
     start_height: u64,
+    /// The start time of this era.
+    start_time: Timestamp,
     /// Pending candidate blocks, waiting for validation. The boolean is `true` if the proto block
     /// has been validated; the vector contains the list of accused validators missing evidence.
     candidates: Vec<PendingCandidate>,
@@ -144,21 +204,27 @@ pub struct Era<I> {
     /// Validators that have been slashed in any of the recent BONDED_ERAS switch blocks. This
     /// includes `newly_slashed`.
     slashed: HashSet<PublicKey>,
+    /// Deploys included in the blocks finalized so far in this era. They must not be proposed
+    /// again.
+    finalized_deploys: HashSet<DeployHash>,
 }
 
 impl<I> Era<I> {
     fn new<C: 'static + ConsensusProtocol<I, CandidateBlock, PublicKey>>(
         consensus: C,
         start_height: u64,
+        start_time: Timestamp,
         newly_slashed: Vec<PublicKey>,
         slashed: HashSet<PublicKey>,
     ) -> Self {
         Era {
             consensus: Box::new(consensus),
             start_height,
+            start_time,
             candidates: Vec::new(),
             newly_slashed,
             slashed,
+            finalized_deploys: HashSet::new(),
         }
     }
 
@@ -226,9 +292,11 @@ where
         let Era {
             consensus,
             start_height,
+            start_time,
             candidates,
             newly_slashed,
             slashed,
+            finalized_deploys,
         } = self;
 
         // `DataSize` cannot be made object safe due its use of associated constants. We implement
@@ -250,9 +318,11 @@ where
 
         consensus_heap_size
             + start_height.estimate_heap_size()
+            + start_time.estimate_heap_size()
             + candidates.estimate_heap_size()
             + newly_slashed.estimate_heap_size()
             + slashed.estimate_heap_size()
+            + finalized_deploys.estimate_heap_size()
     }
 }
 
@@ -265,6 +335,12 @@ pub struct EraSupervisor<I> {
     pub(super) public_signing_key: PublicKey,
     current_era: EraId,
     chainspec: Chainspec,
+    /// The node-local maximum round exponent, within the chainspec's bounds.
+    max_round_exponent: u8,
+    /// The node-local proposal timeout, within the chainspec's bounds.
+    proposal_timeout: TimeDiff,
+    /// How long to wait for the contract runtime to return the validators of a new era.
+    get_validators_timeout: Duration,
     node_start_time: Timestamp,
     #[data_size(skip)]
     metrics: ConsensusMetrics,
@@ -294,6 +370,11 @@ where
         mut rng: &mut dyn CryptoRngCore,
     ) -> Result<(Self, Effects<Event<I>>), Error> {
         let (root, config) = config.into_parts();
+        let highway_config = &chainspec.genesis.highway_config;
+        config.validate(highway_config)?;
+        let max_round_exponent = config.max_round_exponent(highway_config);
+        let proposal_timeout = config.proposal_timeout(highway_config);
+        let get_validators_timeout = config.get_validators_timeout();
         let secret_signing_key = Rc::new(config.secret_key_path.load(root)?);
         let public_signing_key = PublicKey::from(secret_signing_key.as_ref());
         let metrics = ConsensusMetrics::new(registry)
@@ -305,6 +386,9 @@ where
             public_signing_key,
             current_era: EraId(0),
             chainspec: chainspec.clone(),
+            max_round_exponent,
+            proposal_timeout,
+            get_validators_timeout,
             node_start_time: Timestamp::now(),
             metrics,
         };
@@ -422,11 +506,6 @@ where
         }
         self.current_era = era_id;
 
-        let sum_stakes: Motes = validator_stakes.iter().map(|(_, stake)| *stake).sum();
-        assert!(
-            !sum_stakes.value().is_zero(),
-            "cannot start era with total weight 0"
-        );
         info!(
             ?validator_stakes,
             %start_time,
@@ -435,25 +514,26 @@ where
             era = era_id.0,
             "starting era",
         );
-        // For Highway, we need u64 weights. Scale down by  sum / u64::MAX,  rounded up.
-        // If we round up the divisor, the resulting sum is guaranteed to be  <= u64::MAX.
-        let scaling_factor = (sum_stakes.value() + U512::from(u64::MAX) - 1) / U512::from(u64::MAX);
-        let scale_stake = |(key, stake): (PublicKey, Motes)| {
-            (key, AsPrimitive::<u64>::as_(stake.value() / scaling_factor))
-        };
-        let mut validators: Validators<PublicKey> =
-            validator_stakes.into_iter().map(scale_stake).collect();
 
+        // The auction may still include validators slashed in recent switch blocks, if its
+        // snapshot for this era was taken before they were slashed.
         let slashed = era_id
             .iter_other_bonded()
             .flat_map(|e_id| &self.active_eras[&e_id].newly_slashed)
             .chain(&newly_slashed)
             .cloned()
             .collect();
+        let validators = era_validators(validator_stakes, &slashed);
 
-        for pub_key in &slashed {
-            validators.ban(pub_key);
-        }
+        // The share of the largest third of validators, since more than a third is needed to
+        // break finality.
+        let top_third = (validators.iter().count() + 2) / 3;
+        info!(
+            era = era_id.0,
+            stake_gini = validators.stake_distribution_gini(),
+            top_third_stake_fraction = validators.top_k_stake_fraction(top_third),
+            "validator stake distribution",
+        );
 
         let total_weight = u128::from(validators.total_weight());
         let ftt_percent = u128::from(self.highway_config().finality_threshold_percent);
@@ -465,6 +545,8 @@ where
             BLOCK_REWARD,
             BLOCK_REWARD / 5, // TODO: Make reduced block reward configurable?
             self.highway_config().minimum_round_exponent,
+            self.max_round_exponent,
+            self.proposal_timeout,
             self.highway_config().minimum_era_height,
             start_time + self.highway_config().era_duration,
         );
@@ -472,7 +554,7 @@ where
         // Activate the era if this node was already running when the era began, it is still
         // ongoing based on its minimum duration, and we are one of the validators.
         let our_id = self.public_signing_key;
-        let era_rounds_len = params.min_round_len() * params.end_height();
+        let era_rounds_len = TimeDiff::from_rounds(params.end_height(), params.min_round_exp());
         let min_end_time = start_time + self.highway_config().era_duration.max(era_rounds_len);
         let should_activate = self.node_start_time < start_time
             && min_end_time >= timestamp
@@ -507,18 +589,37 @@ where
             Vec::new()
         };
 
-        let era = Era::new(highway, start_height, newly_slashed, slashed);
+        let era = Era::new(highway, start_height, start_time, newly_slashed, slashed);
         let _ = self.active_eras.insert(era_id, era);
+        self.update_era_metrics(era_id);
 
         // Remove the era that has become obsolete now. We keep 2 * BONDED_ERAS past eras because
         // the oldest bonded era could still receive blocks that refer to BONDED_ERAS before that.
         if let Some(obsolete_era_id) = era_id.checked_sub(2 * BONDED_ERAS + 1) {
             self.active_eras.remove(&obsolete_era_id);
+            self.metrics.remove_era(obsolete_era_id);
         }
 
         results
     }
 
+    /// Sets the per-era metrics to the statistics of the given era's protocol instance.
+    fn update_era_metrics(&self, era_id: EraId) {
+        if let Some(era) = self.active_eras.get(&era_id) {
+            self.metrics.observe_era(era_id, &era.consensus.stats());
+        }
+    }
+
+    /// Returns the status of the current era's protocol instance.
+    fn status(&self) -> ConsensusStatus {
+        let consensus = &self.active_eras[&self.current_era].consensus;
+        ConsensusStatus {
+            era_id: self.current_era,
+            is_active_validator: consensus.is_active(),
+            stats: consensus.stats(),
+        }
+    }
+
     /// Returns the current era.
     fn current_era_mut(&mut self) -> &mut Era<I> {
         self.active_eras
@@ -568,6 +669,7 @@ where
             }
             Some(era) => {
                 let results = f(&mut *era.consensus, self.rng);
+                self.era_supervisor.update_era_metrics(era_id);
                 self.handle_consensus_results(era_id, results)
             }
         }
@@ -641,16 +743,16 @@ where
     pub(super) fn handle_linear_chain_block(
         &mut self,
         block_header: BlockHeader,
-        responder: Responder<Signature>,
+        responder: Responder<(PublicKey, Signature)>,
     ) -> Effects<Event<I>> {
         // TODO - we should only sign if we're a validator for the given era ID.
-        let signature = asymmetric_key::sign(
-            block_header.hash().inner(),
+        let signature = block_header.hash().sign(
             &self.era_supervisor.secret_signing_key,
             &self.era_supervisor.public_signing_key,
             self.rng,
         );
-        let mut effects = responder.respond(signature).ignore();
+        let public_key = self.era_supervisor.public_signing_key;
+        let mut effects = responder.respond((public_key, signature)).ignore();
         if block_header.era_id() < self.era_supervisor.current_era {
             trace!(era_id = %block_header.era_id(), "executed block in old era");
             return effects;
@@ -658,32 +760,7 @@ where
         if block_header.switch_block() {
             // if the block is a switch block, we have to get the validators for the new era and
             // create it, before we can say we handled the block
-            let new_era_id = block_header.era_id().successor();
-            let request = GetEraValidatorsRequest::new(
-                (*block_header.state_root_hash()).into(),
-                new_era_id.0,
-                ProtocolVersion::V1_0_0,
-            );
-            let key_block_height = self
-                .era_supervisor
-                .key_block_height(new_era_id, block_header.height() + 1);
-            let booking_block_height = self.era_supervisor.booking_block_height(new_era_id);
-            let effect = self
-                .effect_builder
-                .create_new_era(request, booking_block_height, key_block_height)
-                .event(
-                    move |(validators, booking_block, key_block)| Event::CreateNewEra {
-                        block_header: Box::new(block_header),
-                        booking_block_hash: booking_block
-                            .map_or_else(|| Err(booking_block_height), |block| Ok(*block.hash())),
-                        key_block_seed: key_block.map_or_else(
-                            || Err(key_block_height),
-                            |block| Ok(block.header().accumulated_seed()),
-                        ),
-                        get_validators_result: validators,
-                    },
-                );
-            effects.extend(effect);
+            effects.extend(self.request_new_era(block_header, 0));
         } else {
             // if it's not a switch block, we can already declare it handled
             effects.extend(
@@ -695,6 +772,65 @@ where
         effects
     }
 
+    /// Requests the validators, the booking block and the key block for the era following the
+    /// given switch block, which are needed to create the new era.
+    ///
+    /// `attempt` counts the earlier requests for these validators that timed out.  Retries are
+    /// delayed by `new_era_retry_delay`, so that a struggling contract runtime isn't flooded with
+    /// duplicate requests.
+    pub(super) fn request_new_era(
+        &mut self,
+        block_header: BlockHeader,
+        attempt: u32,
+    ) -> Effects<Event<I>> {
+        let new_era_id = block_header.era_id().successor();
+        let request = GetEraValidatorsRequest::new(
+            (*block_header.state_root_hash()).into(),
+            new_era_id.0,
+            ProtocolVersion::V1_0_0,
+        );
+        let key_block_height = self
+            .era_supervisor
+            .key_block_height(new_era_id, block_header.height() + 1);
+        let booking_block_height = self.era_supervisor.booking_block_height(new_era_id);
+        let effect_builder = self.effect_builder;
+        let get_validators_timeout = self.era_supervisor.get_validators_timeout;
+        let retry_delay = new_era_retry_delay(attempt);
+        async move {
+            if attempt > 0 {
+                effect_builder.set_timeout(retry_delay).await;
+            }
+            effect_builder
+                .create_new_era(
+                    request,
+                    get_validators_timeout,
+                    booking_block_height,
+                    key_block_height,
+                )
+                .await
+        }
+        .event(
+            move |(validators, booking_block, key_block)| Event::CreateNewEra {
+                block_header: Box::new(block_header),
+                booking_block_hash: booking_block
+                    .map_or_else(|| Err(booking_block_height), |block| Ok(*block.hash())),
+                key_block_seed: key_block.map_or_else(
+                    || Err(key_block_height),
+                    |block| Ok(block.header().accumulated_seed()),
+                ),
+                get_validators_result: validators,
+                attempt,
+            },
+        )
+    }
+
+    pub(super) fn handle_get_status(
+        &mut self,
+        responder: Responder<ConsensusStatus>,
+    ) -> Effects<Event<I>> {
+        responder.respond(self.era_supervisor.status()).ignore()
+    }
+
     pub(super) fn handle_create_new_era(
         &mut self,
         block_header: BlockHeader,
@@ -716,6 +852,8 @@ where
             .current_era_mut()
             .consensus
             .deactivate_validator();
+        let current_era = self.era_supervisor.current_era;
+        self.era_supervisor.update_era_metrics(current_era);
         let newly_slashed = block_header
             .era_end()
             .expect("switch block must have era_end")
@@ -775,9 +913,11 @@ where
     pub(super) fn handle_invalid_proto_block(
         &mut self,
         era_id: EraId,
-        _sender: I,
+        sender: I,
         proto_block: ProtoBlock,
+        reason: InvalidProposal,
     ) -> Effects<Event<I>> {
+        warn!(%reason, %sender, era = era_id.0, "rejecting invalid proposal");
         let mut effects = Effects::new();
         let candidate_blocks = if let Some(era) = self.era_supervisor.active_eras.get_mut(&era_id) {
             era.reject_proto_block(&proto_block)
@@ -846,24 +986,32 @@ where
                     .set_timeout(timediff.into())
                     .event(move |_| Event::Timer { era_id, timestamp })
             }
-            ConsensusProtocolResult::CreateNewBlock { block_context } => self
-                .effect_builder
-                .request_proto_block(block_context, self.rng.gen())
-                .event(move |(proto_block, block_context)| Event::NewProtoBlock {
-                    era_id,
-                    proto_block,
-                    block_context,
-                }),
+            ConsensusProtocolResult::CreateNewBlock { block_context } => {
+                let past_deploys = self.era(era_id).finalized_deploys.clone();
+                self.effect_builder
+                    .request_proto_block(block_context, past_deploys, self.rng.gen())
+                    .event(move |(proto_block, block_context)| Event::NewProtoBlock {
+                        era_id,
+                        proto_block,
+                        block_context,
+                    })
+            }
             ConsensusProtocolResult::FinalizedBlock(CpFinalizedBlock {
                 value,
                 timestamp,
                 height,
                 rewards,
+                blocks_proposed,
                 proposer,
             }) => {
+                if let Some(era) = self.era_supervisor.active_eras.get_mut(&era_id) {
+                    era.finalized_deploys
+                        .extend(value.proto_block().deploys().iter().cloned());
+                }
                 let era_end = rewards.map(|rewards| EraEnd {
                     equivocators: value.accusations().clone(),
                     rewards,
+                    blocks_proposed: blocks_proposed.unwrap_or_default(),
                 });
                 let finalized_block = FinalizedBlock::new(
                     value.proto_block().clone(),
@@ -888,7 +1036,12 @@ where
                 effects.extend(self.effect_builder.execute_block(finalized_block).ignore());
                 effects
             }
-            ConsensusProtocolResult::ValidateConsensusValue(sender, candidate_block) => {
+            ConsensusProtocolResult::ValidateConsensusValue {
+                sender,
+                value: candidate_block,
+                timestamp,
+                ancestor_values,
+            } => {
                 let proto_block = candidate_block.proto_block().clone();
                 let missing_evidence: Vec<PublicKey> = candidate_block
                     .accusations()
@@ -905,29 +1058,98 @@ where
                             .ignore(),
                     );
                 }
-                if let Some(era) = self.era_supervisor.active_eras.get_mut(&era_id) {
+                let max_deploy_count = self
+                    .era_supervisor
+                    .chainspec
+                    .genesis
+                    .deploy_config
+                    .block_max_deploy_count;
+                let ancestor_deploys: HashSet<DeployHash> = ancestor_values
+                    .iter()
+                    .flat_map(|value| value.proto_block().deploys().iter().cloned())
+                    .collect();
+                // The proposal must not repeat any deploy from the blocks it builds on: its
+                // ancestors in this era, and the blocks of earlier eras, which are all finalized.
+                let past_deploys: HashSet<DeployHash> = era_id
+                    .iter_other_bonded()
+                    .filter_map(|e_id| self.era_supervisor.active_eras.get(&e_id))
+                    .flat_map(|era| era.finalized_deploys.iter().cloned())
+                    .chain(ancestor_deploys.iter().cloned())
+                    .collect();
+                if let Err(reason) =
+                    proposal_rules::check_proposal(&proto_block, &past_deploys, max_deploy_count)
+                {
+                    warn!(%reason, %sender, era = era_id.0, "rejecting invalid proposal");
+                    effects.extend(self.delegate_to_era(era_id, |consensus, rng| {
+                        consensus.resolve_validity(&candidate_block, false, rng)
+                    }));
+                    return effects;
+                }
+                let context = if let Some(era) = self.era_supervisor.active_eras.get_mut(&era_id) {
                     era.add_candidate(candidate_block, missing_evidence);
+                    ProposalContext {
+                        timestamp,
+                        era_start_time: era.start_time,
+                    }
                 } else {
                     return effects;
-                }
-                effects.extend(
-                    self.effect_builder
-                        .validate_block(sender.clone(), proto_block)
-                        .event(move |(is_valid, proto_block)| {
-                            if is_valid {
-                                Event::AcceptProtoBlock {
-                                    era_id,
-                                    proto_block,
-                                }
-                            } else {
-                                Event::InvalidProtoBlock {
-                                    era_id,
-                                    sender,
-                                    proto_block,
-                                }
-                            }
-                        }),
-                );
+                };
+                let effect_builder = self.effect_builder;
+                let validation = async move {
+                    let (result, proto_block) = effect_builder
+                        .validate_proposed_block(sender.clone(), proto_block, context)
+                        .await;
+                    if let Err(invalid_block) = result {
+                        return (
+                            Err(InvalidProposal::from(invalid_block)),
+                            sender,
+                            proto_block,
+                        );
+                    }
+                    // The block validator has stored all the deploys, so we can check their
+                    // headers for expiry and unresolved dependencies.
+                    let deploy_headers = effect_builder
+                        .get_deploy_headers_from_storage::<Storage>(
+                            proto_block.deploys().iter().cloned().collect(),
+                        )
+                        .await;
+                    // A dependency is resolved if it is included in one of the blocks the proposal
+                    // builds on: an ancestor in this era, or a block of an earlier era.
+                    let mut resolved_deploys = ancestor_deploys;
+                    let dependencies = deploy_headers
+                        .iter()
+                        .flatten()
+                        .flat_map(|header| header.dependencies().iter().cloned())
+                        .collect::<Vec<_>>();
+                    for dependency in dependencies {
+                        if !resolved_deploys.contains(&dependency)
+                            && executed_before_era(effect_builder, dependency, era_id).await
+                        {
+                            resolved_deploys.insert(dependency);
+                        }
+                    }
+                    let deploys = proto_block
+                        .deploys()
+                        .iter()
+                        .zip(deploy_headers.iter().map(Option::as_ref));
+                    let result =
+                        proposal_rules::check_deploys(deploys, &resolved_deploys, timestamp);
+                    (result, sender, proto_block)
+                };
+                effects.extend(validation.event(
+                    move |(result, sender, proto_block)| match result {
+                        Ok(()) => Event::AcceptProtoBlock {
+                            era_id,
+                            proto_block,
+                        },
+                        Err(reason) => Event::InvalidProtoBlock {
+                            era_id,
+                            sender,
+                            proto_block,
+                            reason,
+                        },
+                    },
+                ));
                 effects
             }
             ConsensusProtocolResult::NewEvidence(pub_key) => {
@@ -958,3 +1180,79 @@ where
         }
     }
 }
+
+/// Returns whether the deploy was executed in a block of an era before `era_id`.
+///
+/// All blocks of earlier eras have been executed before an era starts, so the answer doesn't
+/// depend on how far this node has got with the current era.
+async fn executed_before_era<REv>(
+    effect_builder: EffectBuilder<REv>,
+    deploy_hash: DeployHash,
+    era_id: EraId,
+) -> bool
+where
+    REv: From<StorageRequest<Storage>>,
+{
+    let metadata = match effect_builder
+        .get_deploy_and_metadata_from_storage::<Storage>(deploy_hash)
+        .await
+    {
+        Some((_, metadata)) => metadata,
+        None => return false,
+    };
+    for block_hash in metadata.execution_results.keys() {
+        if let Some(block) = effect_builder
+            .get_block_from_storage::<Storage>(*block_hash)
+            .await
+        {
+            if block.header().era_id() < era_id {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter;
+
+    use super::*;
+    use crate::testing::TestRng;
+
+    #[test]
+    fn should_back_off_exponentially_between_new_era_retries() {
+        assert_eq!(new_era_retry_delay(0), Duration::from_secs(0));
+        assert_eq!(new_era_retry_delay(1), NEW_ERA_INITIAL_RETRY_DELAY);
+        assert_eq!(new_era_retry_delay(2), NEW_ERA_INITIAL_RETRY_DELAY * 2);
+        assert_eq!(new_era_retry_delay(3), NEW_ERA_INITIAL_RETRY_DELAY * 4);
+        assert_eq!(new_era_retry_delay(20), NEW_ERA_MAX_RETRY_DELAY);
+        assert_eq!(
+            new_era_retry_delay(u32::max_value()),
+            NEW_ERA_MAX_RETRY_DELAY
+        );
+    }
+
+    #[test]
+    fn should_ban_equivocators_still_in_weights() {
+        let mut rng = TestRng::new();
+        let honest = PublicKey::random(&mut rng);
+        let equivocator = PublicKey::random(&mut rng);
+
+        // The equivocator was reported in era N's switch block, but the auction snapshot for era
+        // N + 1 was taken before it was slashed.
+        let validator_stakes = vec![
+            (honest, Motes::new(U512::from(100))),
+            (equivocator, Motes::new(U512::from(200))),
+        ];
+        let slashed: HashSet<_> = iter::once(equivocator).collect();
+        let validators = era_validators(validator_stakes, &slashed);
+
+        let banned: Vec<PublicKey> = validators
+            .iter_banned_idx()
+            .map(|idx| *validators.id(idx).unwrap())
+            .collect();
+        assert_eq!(banned, vec![equivocator]);
+        assert!(validators.get_index(&honest).is_some());
+    }
+}
@@ -80,7 +80,10 @@ impl<C: Context> ActiveValidator<C> {
         let mut av = ActiveValidator {
             vidx,
             secret,
-            next_round_exp: state.params().init_round_exp(),
+            next_round_exp: state
+                .params()
+                .init_round_exp()
+                .min(state.params().max_round_exp()),
             next_timer: Timestamp::zero(),
             next_proposal: None,
         };
@@ -145,9 +148,20 @@ impl<C: Context> ActiveValidator<C> {
         vec![]
     }
 
+    /// Returns the round exponent of our next round.
+    pub(crate) fn next_round_exp(&self) -> u8 {
+        self.next_round_exp
+    }
+
+    /// Returns the timestamp of the most recent vote by this validator.
+    pub(crate) fn latest_vote_timestamp(&self, state: &State<C>) -> Option<Timestamp> {
+        self.latest_vote(state).map(|vote| vote.timestamp)
+    }
+
     /// Returns an effect to request a consensus value for a block to propose.
     ///
-    /// If we are already waiting for a consensus value, `None` is returned instead.
+    /// If we are already waiting for a consensus value, `None` is returned instead, unless the
+    /// proposal timeout has elapsed, in which case the pending proposal is replaced by the new one.
     /// If the new value would come after a terminal block, the proposal is made immediately, and
     /// without a value.
     pub(crate) fn request_new_block(
@@ -158,11 +172,17 @@ impl<C: Context> ActiveValidator<C> {
         rng: &mut dyn CryptoRngCore,
     ) -> Option<Effect<C>> {
         if let Some((prop_time, _)) = self.next_proposal {
+            if timestamp.saturating_sub(prop_time) < state.params().proposal_timeout() {
+                warn!(
+                    ?timestamp,
+                    "skipping proposal, still waiting for value for {}", prop_time
+                );
+                return None;
+            }
             warn!(
                 ?timestamp,
-                "skipping proposal, still waiting for value for {}", prop_time
+                "abandoning proposal for {}, timed out waiting for value", prop_time
             );
-            return None;
         }
         let panorama = state.panorama().cutoff(state, timestamp);
         let opt_parent_hash = state.fork_choice(&panorama);
@@ -195,18 +215,25 @@ impl<C: Context> ActiveValidator<C> {
             warn!("Creator knows it's faulty. Won't create a message.");
             return vec![];
         }
-        let panorama = if let Some((prop_time, panorama)) = self.next_proposal.take() {
-            if prop_time != timestamp {
+        // Only take the pending proposal if the value is for it, so that a late value for an
+        // abandoned proposal doesn't discard the current one.
+        match self.next_proposal {
+            Some((prop_time, _)) if prop_time != timestamp => {
                 warn!(
                     ?timestamp,
                     "unexpected proposal; expected timestamp {}", prop_time
                 );
                 return vec![];
             }
-            panorama
-        } else {
-            warn!("unexpected proposal value");
-            return vec![];
+            Some(_) => (),
+            None => {
+                warn!("unexpected proposal value");
+                return vec![];
+            }
+        }
+        let panorama = match self.next_proposal.take() {
+            Some((_, panorama)) => panorama,
+            None => unreachable!("checked above that there is a pending proposal"),
         };
         let proposal_vote =
             self.new_vote(panorama, timestamp, Some(value), state, instance_id, rng);
@@ -382,7 +409,8 @@ mod tests {
     use super::{
         super::{
             finality_detector::FinalityDetector,
-            state::{tests::*, Weight},
+            highway_testing::TEST_BLOCK_REWARD,
+            state::{tests::*, Params, Weight},
         },
         Vertex, *,
     };
@@ -478,4 +506,73 @@ mod tests {
         assert_eq!(Some(&prop_hash), fd.next_finalized(&state));
         Ok(())
     }
+    #[test]
+    fn should_keep_pending_proposal_on_mismatched_value() {
+        let state = State::new_test(&[Weight(3), Weight(4)], 0);
+        let mut rng = TestRng::new();
+        let instance_id = 1u64;
+        let (mut alice_av, _) = ActiveValidator::new(ALICE, TestSecret(0), 410.into(), &state);
+
+        let bctx = match alice_av.request_new_block(&state, instance_id, 416.into(), &mut rng) {
+            Some(Eff::RequestNewBlock(bctx)) => bctx,
+            effect => panic!("unexpected effect {:?}", effect),
+        };
+
+        // A value for a timestamp we never requested is ignored...
+        let other_bctx = BlockContext::new(417.into(), 0);
+        assert!(alice_av
+            .propose(0xBAD, other_bctx, &state, instance_id, &mut rng)
+            .is_empty());
+
+        // ...and the pending proposal is still made once its value arrives.
+        let effects = alice_av.propose(0xC0FFEE, bctx, &state, instance_id, &mut rng);
+        let proposal_wvote = unwrap_single(effects).unwrap_vote();
+        assert_eq!(Timestamp::from(416), proposal_wvote.wire_vote.timestamp);
+        assert_eq!(Some(0xC0FFEE), proposal_wvote.wire_vote.value);
+    }
+
+    #[test]
+    fn should_replace_timed_out_proposal() {
+        let params = Params::new(
+            0,
+            TEST_BLOCK_REWARD,
+            TEST_BLOCK_REWARD / 5,
+            4,
+            19,
+            TimeDiff::from(20),
+            u64::MAX,
+            Timestamp::from(u64::MAX),
+        );
+        let state = State::new(&[Weight(3), Weight(4)], params, vec![]);
+        let mut rng = TestRng::new();
+        let instance_id = 1u64;
+        let (mut alice_av, _) = ActiveValidator::new(ALICE, TestSecret(0), 410.into(), &state);
+
+        let old_bctx = match alice_av.request_new_block(&state, instance_id, 416.into(), &mut rng) {
+            Some(Eff::RequestNewBlock(bctx)) => bctx,
+            effect => panic!("unexpected effect {:?}", effect),
+        };
+
+        // Before the timeout, we keep waiting for the pending value.
+        assert_eq!(
+            None,
+            alice_av.request_new_block(&state, instance_id, 432.into(), &mut rng)
+        );
+
+        // Once it has elapsed, the pending proposal is replaced by a new one.
+        let new_bctx = match alice_av.request_new_block(&state, instance_id, 448.into(), &mut rng) {
+            Some(Eff::RequestNewBlock(bctx)) => bctx,
+            effect => panic!("unexpected effect {:?}", effect),
+        };
+        assert_eq!(Timestamp::from(448), new_bctx.timestamp());
+
+        // A late value for the abandoned proposal doesn't discard the new one.
+        assert!(alice_av
+            .propose(0xBAD, old_bctx, &state, instance_id, &mut rng)
+            .is_empty());
+        let effects = alice_av.propose(0xC0FFEE, new_bctx, &state, instance_id, &mut rng);
+        let proposal_wvote = unwrap_single(effects).unwrap_vote();
+        assert_eq!(Timestamp::from(448), proposal_wvote.wire_vote.timestamp);
+        assert_eq!(Some(0xC0FFEE), proposal_wvote.wire_vote.value);
+    }
 }
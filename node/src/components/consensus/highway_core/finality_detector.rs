@@ -1,7 +1,7 @@
 mod horizon;
 mod rewards;
 
-use std::iter;
+use std::{collections::BTreeMap, iter};
 
 use tracing::trace;
 
@@ -64,12 +64,21 @@ impl<C: Context> FinalityDetector<C> {
             let to_id = |vidx: ValidatorIndex| highway.validators().id(vidx).unwrap().clone();
             let block = state.block(bhash);
             let vote = state.vote(bhash);
-            let rewards = if state.is_terminal_block(bhash) {
+            let (rewards, blocks_proposed) = if state.is_terminal_block(bhash) {
                 let rewards = rewards::compute_rewards(state, bhash);
                 let rewards_iter = rewards.enumerate();
-                Some(rewards_iter.map(|(vidx, r)| (to_id(vidx), *r)).collect())
+                let blocks_proposed = blocks_proposed(state, bhash);
+                (
+                    Some(rewards_iter.map(|(vidx, r)| (to_id(vidx), *r)).collect()),
+                    Some(
+                        blocks_proposed
+                            .into_iter()
+                            .map(|(vidx, count)| (to_id(vidx), count))
+                            .collect(),
+                    ),
+                )
             } else {
-                None
+                (None, None)
             };
 
             Some(FinalizedBlock {
@@ -77,6 +86,7 @@ impl<C: Context> FinalityDetector<C> {
                 timestamp: vote.timestamp,
                 height: block.height,
                 rewards,
+                blocks_proposed,
                 proposer: to_id(vote.creator),
             })
         }))
@@ -150,6 +160,16 @@ impl<C: Context> FinalityDetector<C> {
     }
 }
 
+/// Returns the number of blocks proposed by each validator in the chain ending with `bhash`,
+/// including `bhash` itself. Validators that didn't propose any block are omitted.
+fn blocks_proposed<C: Context>(state: &State<C>, bhash: &C::Hash) -> BTreeMap<ValidatorIndex, u64> {
+    let mut counts = BTreeMap::new();
+    for hash in iter::once(bhash).chain(state.ancestor_hashes(bhash)) {
+        *counts.entry(state.vote(hash).creator).or_default() += 1;
+    }
+    counts
+}
+
 #[allow(unused_qualifications)] // This is to suppress warnings originating in the test macros.
 #[cfg(test)]
 mod tests {
@@ -242,4 +262,27 @@ mod tests {
         assert_eq!(Some(&a0), fde4.next_finalized(&bstate));
         Ok(())
     }
+
+    #[test]
+    fn blocks_proposed() -> Result<(), AddVoteError<TestContext>> {
+        let mut state = State::new_test(&[Weight(5), Weight(4), Weight(1)], 0);
+        let mut rng = TestRng::new();
+
+        // Create the chain b0 — a0 — a1, with Carol's block c0 on a fork:
+        //
+        // b0 — a0 — a1
+        //    \
+        //      c0
+        let b0 = add_vote!(state, rng, BOB, 0xB0; N, N, N)?;
+        let _c0 = add_vote!(state, rng, CAROL, 0xC0; N, b0, N)?;
+        let a0 = add_vote!(state, rng, ALICE, 0xA0; N, b0, N)?;
+        let a1 = add_vote!(state, rng, ALICE, 0xA1; a0, b0, N)?;
+
+        // Blocks that are not ancestors of `a1` are not counted.
+        let expected: BTreeMap<_, _> = vec![(ALICE, 2), (BOB, 1)].into_iter().collect();
+        assert_eq!(expected, super::blocks_proposed(&state, &a1));
+        let expected: BTreeMap<_, _> = vec![(BOB, 1)].into_iter().collect();
+        assert_eq!(expected, super::blocks_proposed(&state, &b0));
+        Ok(())
+    }
 }
@@ -149,6 +149,7 @@ mod tests {
             validators::ValidatorMap,
         },
         testing::TestRng,
+        types::TimeDiff,
     };
 
     #[test]
@@ -198,6 +199,8 @@ mod tests {
             TEST_BLOCK_REWARD,
             TEST_BLOCK_REWARD / 5,
             3,
+            19,
+            TimeDiff::from(u64::MAX),
             u64::MAX,
             Timestamp::from(u64::MAX),
         );
@@ -3,12 +3,14 @@ mod vertex;
 pub(crate) use crate::components::consensus::highway_core::state::Params;
 pub(crate) use vertex::{Dependency, SignedWireVote, Vertex, WireVote};
 
+use std::iter;
+
 use thiserror::Error;
 use tracing::{debug, error, info};
 
 use crate::{
     components::consensus::{
-        consensus_protocol::BlockContext,
+        consensus_protocol::{BlockContext, ProtocolStats},
         highway_core::{
             active_validator::{ActiveValidator, Effect},
             evidence::EvidenceError,
@@ -166,6 +168,23 @@ impl<C: Context> Highway<C> {
         self.active_validator = None;
     }
 
+    /// Returns whether this instance is an active validator.
+    pub(crate) fn is_active(&self) -> bool {
+        self.active_validator.is_some()
+    }
+
+    /// Returns statistics about the protocol state.
+    pub(crate) fn stats(&self) -> ProtocolStats {
+        let active_validator = self.active_validator.as_ref();
+        ProtocolStats {
+            unit_count: self.state.vote_count() as u64,
+            round_exponent: active_validator.map(ActiveValidator::next_round_exp),
+            fault_count: self.state.faulty_validators().count() as u64,
+            our_last_unit_timestamp: active_validator
+                .and_then(|av| av.latest_vote_timestamp(&self.state)),
+        }
+    }
+
     /// Does initial validation. Returns an error if the vertex is invalid.
     pub(crate) fn pre_validate_vertex(
         &self,
@@ -345,6 +364,24 @@ impl<C: Context> Highway<C> {
         &self.validators
     }
 
+    /// Returns the values of the blocks the block proposed in `vv` builds on, i.e. of its parent
+    /// and all the parent's ancestors, starting with the parent. This is empty if the vertex is
+    /// evidence or the block is the first in this instance.
+    pub(crate) fn ancestor_values(&self, vv: &ValidVertex<C>) -> Vec<C::ConsensusValue> {
+        let wire_vote = match vv.inner() {
+            Vertex::Vote(swvote) => &swvote.wire_vote,
+            Vertex::Evidence(_) => return vec![],
+        };
+        let parent = match self.state.fork_choice(&wire_vote.panorama) {
+            Some(parent) => parent,
+            None => return vec![],
+        };
+        iter::once(parent)
+            .chain(self.state.ancestor_hashes(parent))
+            .map(|bhash| self.state.block(bhash).value.clone())
+            .collect()
+    }
+
     /// Returns an iterator over all validators against which we have direct evidence.
     pub(crate) fn validators_with_evidence(&self) -> impl Iterator<Item = &C::ValidatorId> {
         self.validators
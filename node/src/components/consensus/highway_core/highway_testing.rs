@@ -41,6 +41,8 @@ use crate::{
 type ConsensusValue = Vec<u32>;
 
 const TEST_MIN_ROUND_EXP: u8 = 12;
+const TEST_MAX_ROUND_EXP: u8 = 19;
+const TEST_PROPOSAL_TIMEOUT: u64 = 1 << TEST_MIN_ROUND_EXP;
 const TEST_END_HEIGHT: u64 = 100000;
 pub(crate) const TEST_BLOCK_REWARD: u64 = 1_000_000_000_000;
 pub(crate) const TEST_REDUCED_BLOCK_REWARD: u64 = 200_000_000_000;
@@ -478,6 +480,7 @@ where
             timestamp: _,
             height,
             rewards,
+            blocks_proposed: _,
             proposer: _,
         } in finalized_values
         {
@@ -874,6 +877,8 @@ impl<DS: DeliveryStrategy> HighwayTestHarnessBuilder<DS> {
                     TEST_BLOCK_REWARD,
                     TEST_REDUCED_BLOCK_REWARD,
                     TEST_MIN_ROUND_EXP,
+                    TEST_MAX_ROUND_EXP,
+                    TEST_PROPOSAL_TIMEOUT.into(),
                     TEST_END_HEIGHT,
                     Timestamp::zero(), // Length depends only on block number.
                 );
@@ -247,6 +247,11 @@ impl<C: Context> State<C> {
         self.votes.contains_key(hash)
     }
 
+    /// Returns the number of votes in the protocol state.
+    pub(crate) fn vote_count(&self) -> usize {
+        self.votes.len()
+    }
+
     /// Returns the vote with the given hash. Panics if not found.
     pub(crate) fn vote(&self, hash: &C::Hash) -> &Vote<C> {
         self.opt_vote(hash).expect("vote hash must exist")
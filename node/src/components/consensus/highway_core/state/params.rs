@@ -7,7 +7,9 @@ pub(crate) struct Params {
     block_reward: u64,
     reduced_block_reward: u64,
     min_round_exp: u8,
+    max_round_exp: u8,
     init_round_exp: u8,
+    proposal_timeout: TimeDiff,
     end_height: u64,
     end_timestamp: Timestamp,
 }
@@ -26,6 +28,10 @@ impl Params {
     ///   summit does not exceed half the total weight.
     /// * `min_round_exp`: The minimum round exponent. `1 << min_round_exp` milliseconds is the
     ///   minimum round length.
+    /// * `max_round_exp`: The maximum round exponent this node will use for its own votes. This is
+    ///   node-local and does not affect the validation of other validators' votes.
+    /// * `proposal_timeout`: How long this node waits for a consensus value to propose before it
+    ///   gives up on a pending proposal.
     /// * `end_height`, `end_timestamp`: The last block will be the first one that has at least the
     ///   specified height _and_ is no earlier than the specified timestamp. No children of this
     ///   block can be proposed.
@@ -34,6 +40,8 @@ impl Params {
         block_reward: u64,
         reduced_block_reward: u64,
         min_round_exp: u8,
+        max_round_exp: u8,
+        proposal_timeout: TimeDiff,
         end_height: u64,
         end_timestamp: Timestamp,
     ) -> Params {
@@ -41,12 +49,18 @@ impl Params {
             reduced_block_reward <= block_reward,
             "reduced block reward must not be greater than the reward for a finalized block"
         );
+        assert!(
+            min_round_exp <= max_round_exp,
+            "minimum round exponent must not be greater than the maximum round exponent"
+        );
         Params {
             seed,
             block_reward,
             reduced_block_reward,
             min_round_exp,
+            max_round_exp,
             init_round_exp: min_round_exp, // TODO: The median seen by previous era's switch block?
+            proposal_timeout,
             end_height,
             end_timestamp,
         }
@@ -74,14 +88,19 @@ impl Params {
         self.min_round_exp
     }
 
+    /// Returns the maximum round exponent this node will use for its own votes.
+    pub(crate) fn max_round_exp(&self) -> u8 {
+        self.max_round_exp
+    }
+
     /// Returns the initial round exponent.
     pub(crate) fn init_round_exp(&self) -> u8 {
         self.init_round_exp
     }
 
-    /// Returns the minimum round length, i.e. `1 << self.min_round_exp()` milliseconds.
-    pub(crate) fn min_round_len(&self) -> TimeDiff {
-        super::round_len(self.min_round_exp)
+    /// Returns how long to wait for a consensus value before giving up on a pending proposal.
+    pub(crate) fn proposal_timeout(&self) -> TimeDiff {
+        self.proposal_timeout
     }
 
     /// Returns the minimum height of the last block.
@@ -105,6 +105,8 @@ impl State<TestContext> {
             TEST_BLOCK_REWARD,
             TEST_BLOCK_REWARD / 5,
             4,
+            19,
+            TimeDiff::from(u64::MAX),
             u64::MAX,
             Timestamp::from(u64::MAX),
         );
@@ -210,6 +212,8 @@ fn ban_and_mark_faulty() -> Result<(), AddVoteError<TestContext>> {
         TEST_BLOCK_REWARD,
         TEST_BLOCK_REWARD / 5,
         4,
+        19,
+        TimeDiff::from(u64::MAX),
         u64::MAX,
         Timestamp::from(u64::MAX),
     );
@@ -86,6 +86,48 @@ impl<VID: Eq + Hash> Validators<VID> {
         }
     }
 
+    /// Returns the Gini coefficient of the validators' weights: `0.0` if all weights are equal,
+    /// approaching `1.0` the more the total weight is concentrated in a single validator.
+    ///
+    /// Returns `0.0` if there are no validators or their total weight is zero.
+    pub(crate) fn stake_distribution_gini(&self) -> f64 {
+        let mut weights: Vec<u128> = self
+            .validators
+            .iter()
+            .map(|v| v.weight().0.into())
+            .collect();
+        weights.sort_unstable();
+        let n = weights.len() as u128;
+        let total: u128 = weights.iter().sum();
+        if total == 0 {
+            return 0.0;
+        }
+        // With the weights `w_i` sorted ascending and `i` counting from 1, the coefficient is
+        // `sum((2i - n - 1) * w_i) / (n * sum(w))`.  The numerator is non-negative, since the
+        // larger factors belong to the larger weights.
+        let weighted_sum: u128 = weights
+            .iter()
+            .zip(1..)
+            .map(|(w, i): (&u128, u128)| 2 * i * w)
+            .sum();
+        let numerator = weighted_sum - (n + 1) * total;
+        numerator as f64 / (n * total) as f64
+    }
+
+    /// Returns the fraction of the total weight held by the `k` heaviest validators.
+    ///
+    /// Returns `0.0` if the total weight is zero.
+    pub(crate) fn top_k_stake_fraction(&self, k: usize) -> f64 {
+        let mut weights: Vec<u64> = self.validators.iter().map(|v| v.weight().0).collect();
+        weights.sort_unstable_by(|w0, w1| w1.cmp(w0));
+        let total: u128 = weights.iter().map(|&w| u128::from(w)).sum();
+        if total == 0 {
+            return 0.0;
+        }
+        let top_k: u128 = weights.iter().take(k).map(|&w| u128::from(w)).sum();
+        top_k as f64 / total as f64
+    }
+
     /// Returns an iterator of all indices of banned validators.
     pub(crate) fn iter_banned_idx(&self) -> impl Iterator<Item = ValidatorIndex> + '_ {
         self.validators
@@ -236,4 +278,43 @@ mod tests {
         assert_eq!(ValidatorIndex(1), validators.index_by_id["Bob"]);
         assert_eq!(ValidatorIndex(2), validators.index_by_id["Carol"]);
     }
+
+    fn assert_close(expected: f64, actual: f64) {
+        assert!(
+            (expected - actual).abs() < 1e-9,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn stake_distribution_gini() {
+        let gini = |weights: Vec<u64>| {
+            Validators::from_iter(weights.into_iter().enumerate()).stake_distribution_gini()
+        };
+        assert_close(0.0, gini(vec![]));
+        assert_close(0.0, gini(vec![0, 0]));
+        assert_close(0.0, gini(vec![7]));
+        assert_close(0.0, gini(vec![5, 5, 5, 5]));
+        // A single validator holding all of the weight among four.
+        assert_close(0.75, gini(vec![0, 0, 10, 0]));
+        // sum((2i - n - 1) * w_i) = -2 * 1 + 0 * 2 + 2 * 3 = 4, divided by n * sum(w) = 18.
+        assert_close(4.0 / 18.0, gini(vec![3, 1, 2]));
+        // Must not overflow with maximal weights.
+        assert_close(0.0, gini(vec![u64::MAX; 3]));
+    }
+
+    #[test]
+    fn top_k_stake_fraction() {
+        let validators = Validators::from_iter(vec![("Alice", 1u64), ("Bob", 6), ("Carol", 3)]);
+        assert_close(0.0, validators.top_k_stake_fraction(0));
+        assert_close(0.6, validators.top_k_stake_fraction(1));
+        assert_close(0.9, validators.top_k_stake_fraction(2));
+        assert_close(1.0, validators.top_k_stake_fraction(3));
+        assert_close(1.0, validators.top_k_stake_fraction(10));
+
+        let empty = Validators::<u32>::from_iter(Vec::<(u32, u64)>::new());
+        assert_close(0.0, empty.top_k_stake_fraction(1));
+    }
 }
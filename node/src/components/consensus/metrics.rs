@@ -1,4 +1,9 @@
-use prometheus::{Gauge, IntCounter, Registry};
+use prometheus::{Gauge, IntCounter, IntGaugeVec, Opts, Registry};
+
+use super::{consensus_protocol::ProtocolStats, EraId};
+
+/// The label identifying the era of per-era metrics.
+const ERA_LABEL: &str = "era";
 
 /// Network metrics to track Consensus
 #[derive(Debug)]
@@ -9,6 +14,14 @@ pub struct ConsensusMetrics {
     pub finalized_block_count: IntCounter,
     /// Timestamp of the most recently accepted proto block.
     pub time_of_last_proposed_block: Gauge,
+    /// Number of units in the protocol state of each active era.
+    pub era_unit_count: IntGaugeVec,
+    /// Our round exponent in each active era in which we are a validator.
+    pub era_round_exponent: IntGaugeVec,
+    /// Number of validators known to be faulty in each active era.
+    pub era_fault_count: IntGaugeVec,
+    /// Timestamp of our latest unit in each active era in which we created one.
+    pub era_our_last_unit_timestamp: IntGaugeVec,
     /// registry component.
     registry: Registry,
 }
@@ -25,15 +38,93 @@ impl ConsensusMetrics {
             "time_of_last_proto_block",
             "timestamp of the most recently accepted proto block",
         )?;
+        let era_unit_count = IntGaugeVec::new(
+            Opts::new(
+                "consensus_era_unit_count",
+                "the number of units in the protocol state of an era",
+            ),
+            &[ERA_LABEL],
+        )?;
+        let era_round_exponent = IntGaugeVec::new(
+            Opts::new(
+                "consensus_era_round_exponent",
+                "the round exponent of our next round in an era",
+            ),
+            &[ERA_LABEL],
+        )?;
+        let era_fault_count = IntGaugeVec::new(
+            Opts::new(
+                "consensus_era_fault_count",
+                "the number of validators known to be faulty in an era",
+            ),
+            &[ERA_LABEL],
+        )?;
+        let era_our_last_unit_timestamp = IntGaugeVec::new(
+            Opts::new(
+                "consensus_era_our_last_unit_timestamp",
+                "the timestamp, in milliseconds, of the latest unit we created in an era",
+            ),
+            &[ERA_LABEL],
+        )?;
         registry.register(Box::new(finalization_time.clone()))?;
         registry.register(Box::new(finalized_block_count.clone()))?;
+        registry.register(Box::new(era_unit_count.clone()))?;
+        registry.register(Box::new(era_round_exponent.clone()))?;
+        registry.register(Box::new(era_fault_count.clone()))?;
+        registry.register(Box::new(era_our_last_unit_timestamp.clone()))?;
         Ok(ConsensusMetrics {
             finalization_time,
             finalized_block_count,
             time_of_last_proposed_block,
+            era_unit_count,
+            era_round_exponent,
+            era_fault_count,
+            era_our_last_unit_timestamp,
             registry: registry.clone(),
         })
     }
+
+    /// Sets the per-era gauges of the given era to the protocol statistics.
+    pub(super) fn observe_era(&self, era_id: EraId, stats: &ProtocolStats) {
+        let era = era_id.0.to_string();
+        self.era_unit_count
+            .with_label_values(&[&era])
+            .set(stats.unit_count as i64);
+        self.era_fault_count
+            .with_label_values(&[&era])
+            .set(stats.fault_count as i64);
+        match stats.round_exponent {
+            Some(round_exponent) => self
+                .era_round_exponent
+                .with_label_values(&[&era])
+                .set(i64::from(round_exponent)),
+            None => {
+                let _ = self.era_round_exponent.remove_label_values(&[&era]);
+            }
+        }
+        match stats.our_last_unit_timestamp {
+            Some(timestamp) => self
+                .era_our_last_unit_timestamp
+                .with_label_values(&[&era])
+                .set(timestamp.millis() as i64),
+            None => {
+                let _ = self
+                    .era_our_last_unit_timestamp
+                    .remove_label_values(&[&era]);
+            }
+        }
+    }
+
+    /// Removes the per-era gauges of the given era.
+    pub(super) fn remove_era(&self, era_id: EraId) {
+        let era = era_id.0.to_string();
+        let _ = self.era_unit_count.remove_label_values(&[&era]);
+        let _ = self.era_round_exponent.remove_label_values(&[&era]);
+        let _ = self.era_fault_count.remove_label_values(&[&era]);
+        let _ = self
+            .era_our_last_unit_timestamp
+            .remove_label_values(&[&era]);
+    }
 }
 
 impl Drop for ConsensusMetrics {
@@ -44,5 +135,17 @@ impl Drop for ConsensusMetrics {
         self.registry
             .unregister(Box::new(self.finalized_block_count.clone()))
             .expect("did not expect deregisterting amount to fail");
+        self.registry
+            .unregister(Box::new(self.era_unit_count.clone()))
+            .expect("did not expect deregistering unit count to fail");
+        self.registry
+            .unregister(Box::new(self.era_round_exponent.clone()))
+            .expect("did not expect deregistering round exponent to fail");
+        self.registry
+            .unregister(Box::new(self.era_fault_count.clone()))
+            .expect("did not expect deregistering fault count to fail");
+        self.registry
+            .unregister(Box::new(self.era_our_last_unit_timestamp.clone()))
+            .expect("did not expect deregistering last unit timestamp to fail");
     }
 }
@@ -0,0 +1,233 @@
+//! Rules every proposed proto block must satisfy, both when proposing one and when validating a
+//! proposal received from another validator.
+
+use std::collections::HashSet;
+
+use datasize::DataSize;
+use thiserror::Error;
+
+use crate::{
+    components::block_validator::InvalidBlock,
+    types::{DeployHash, DeployHeader, ProtoBlock, Timestamp},
+};
+
+/// The reason a proposed proto block was rejected.
+#[derive(DataSize, Debug, Error, PartialEq, Eq)]
+pub enum InvalidProposal {
+    /// The proto block contains more deploys than the chainspec allows.
+    #[error("proto block contains {count} deploys, more than the maximum of {max_deploy_count}")]
+    TooManyDeploys { count: usize, max_deploy_count: u32 },
+
+    /// The proto block contains a deploy which is already included in one of the blocks it builds
+    /// on.
+    #[error("proto block contains {0}, which is already included in an earlier block")]
+    AlreadyIncluded(DeployHash),
+
+    /// The proto block contains a deploy whose header isn't available.
+    #[error("proto block contains {0}, which is missing from storage")]
+    MissingDeploy(DeployHash),
+
+    /// The proto block contains a deploy which has expired by the block's timestamp.
+    #[error("proto block contains {0}, which has expired")]
+    ExpiredDeploy(DeployHash),
+
+    /// The proto block contains a deploy with a dependency which isn't included in any of the
+    /// blocks it builds on.
+    #[error("proto block contains {deploy_hash}, whose dependency {dependency} isn't included")]
+    UnresolvedDependency {
+        deploy_hash: DeployHash,
+        dependency: DeployHash,
+    },
+
+    /// The block validator rejected the proto block.
+    #[error(transparent)]
+    InvalidBlock(#[from] InvalidBlock),
+}
+
+/// Checks that `proto_block` contains at most `max_deploy_count` deploys and none of the
+/// `past_deploys`, i.e. the deploys in the blocks it builds on.
+pub(crate) fn check_proposal(
+    proto_block: &ProtoBlock,
+    past_deploys: &HashSet<DeployHash>,
+    max_deploy_count: u32,
+) -> Result<(), InvalidProposal> {
+    let count = proto_block.deploys().len();
+    if count > max_deploy_count as usize {
+        return Err(InvalidProposal::TooManyDeploys {
+            count,
+            max_deploy_count,
+        });
+    }
+    match proto_block
+        .deploys()
+        .iter()
+        .find(|deploy_hash| past_deploys.contains(deploy_hash))
+    {
+        Some(deploy_hash) => Err(InvalidProposal::AlreadyIncluded(*deploy_hash)),
+        None => Ok(()),
+    }
+}
+
+/// Checks that every deploy in a proto block with the given `timestamp` is eligible for inclusion:
+/// it must not have expired by `timestamp`, and all its dependencies must be among the
+/// `resolved_deploys`, i.e. included in the blocks the proto block builds on.
+///
+/// `deploys` holds each deploy's hash along with its header, if available.
+pub(crate) fn check_deploys<'a, I>(
+    deploys: I,
+    resolved_deploys: &HashSet<DeployHash>,
+    timestamp: Timestamp,
+) -> Result<(), InvalidProposal>
+where
+    I: IntoIterator<Item = (&'a DeployHash, Option<&'a DeployHeader>)>,
+{
+    for (deploy_hash, maybe_header) in deploys {
+        let header = maybe_header.ok_or(InvalidProposal::MissingDeploy(*deploy_hash))?;
+        if header.expired(timestamp) {
+            return Err(InvalidProposal::ExpiredDeploy(*deploy_hash));
+        }
+        if let Some(dependency) = header
+            .dependencies()
+            .iter()
+            .find(|dependency| !resolved_deploys.contains(dependency))
+        {
+            return Err(InvalidProposal::UnresolvedDependency {
+                deploy_hash: *deploy_hash,
+                dependency: *dependency,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_execution_engine::core::engine_state::executable_deploy_item::ExecutableDeployItem;
+    use rand::Rng;
+
+    use super::*;
+    use crate::{
+        crypto::{asymmetric_key::SecretKey, hash::Digest},
+        testing::TestRng,
+        types::{Deploy, TimeDiff},
+    };
+
+    fn random_deploy_hashes(rng: &mut TestRng, count: usize) -> Vec<DeployHash> {
+        (0..count)
+            .map(|_| DeployHash::new(Digest::random(rng)))
+            .collect()
+    }
+
+    fn generate_deploy(
+        rng: &mut TestRng,
+        timestamp: Timestamp,
+        ttl: TimeDiff,
+        dependencies: Vec<DeployHash>,
+    ) -> (DeployHash, DeployHeader) {
+        let secret_key = SecretKey::random(rng);
+        let module_bytes = || ExecutableDeployItem::ModuleBytes {
+            module_bytes: vec![],
+            args: vec![],
+        };
+        let deploy = Deploy::new(
+            timestamp,
+            ttl,
+            10,
+            dependencies,
+            "chain".to_string(),
+            module_bytes(),
+            module_bytes(),
+            &secret_key,
+            rng,
+        );
+        (*deploy.id(), deploy.take_header())
+    }
+
+    #[test]
+    fn should_accept_proposal_within_rules() {
+        let mut rng = TestRng::new();
+        let deploys = random_deploy_hashes(&mut rng, 3);
+        let past_deploys = random_deploy_hashes(&mut rng, 3).into_iter().collect();
+        let proto_block = ProtoBlock::new(deploys, rng.gen());
+        assert_eq!(check_proposal(&proto_block, &past_deploys, 3), Ok(()));
+    }
+
+    #[test]
+    fn should_reject_oversized_proposal() {
+        let mut rng = TestRng::new();
+        let deploys = random_deploy_hashes(&mut rng, 4);
+        let proto_block = ProtoBlock::new(deploys, rng.gen());
+        assert_eq!(
+            check_proposal(&proto_block, &HashSet::new(), 3),
+            Err(InvalidProposal::TooManyDeploys {
+                count: 4,
+                max_deploy_count: 3
+            })
+        );
+    }
+
+    #[test]
+    fn should_reject_proposal_with_past_deploy() {
+        let mut rng = TestRng::new();
+        let deploys = random_deploy_hashes(&mut rng, 3);
+        let past_deploys = deploys[1..2].iter().cloned().collect();
+        let proto_block = ProtoBlock::new(deploys.clone(), rng.gen());
+        assert_eq!(
+            check_proposal(&proto_block, &past_deploys, 3),
+            Err(InvalidProposal::AlreadyIncluded(deploys[1]))
+        );
+    }
+
+    #[test]
+    fn should_reject_expired_deploy() {
+        let mut rng = TestRng::new();
+        let creation_time = Timestamp::from(100);
+        let ttl = TimeDiff::from(100);
+        let (hash, header) = generate_deploy(&mut rng, creation_time, ttl, vec![]);
+        let deploys = vec![(&hash, Some(&header))];
+
+        assert_eq!(
+            check_deploys(deploys.clone(), &HashSet::new(), Timestamp::from(200)),
+            Ok(())
+        );
+        assert_eq!(
+            check_deploys(deploys, &HashSet::new(), Timestamp::from(201)),
+            Err(InvalidProposal::ExpiredDeploy(hash))
+        );
+    }
+
+    #[test]
+    fn should_reject_deploy_with_unresolved_dependency() {
+        let mut rng = TestRng::new();
+        let creation_time = Timestamp::from(100);
+        let ttl = TimeDiff::from(100);
+        let block_time = Timestamp::from(120);
+        let (hash1, _) = generate_deploy(&mut rng, creation_time, ttl, vec![]);
+        let (hash2, header2) = generate_deploy(&mut rng, creation_time, ttl, vec![hash1]);
+        let deploys = vec![(&hash2, Some(&header2))];
+
+        assert_eq!(
+            check_deploys(deploys.clone(), &HashSet::new(), block_time),
+            Err(InvalidProposal::UnresolvedDependency {
+                deploy_hash: hash2,
+                dependency: hash1
+            })
+        );
+
+        let resolved_deploys = vec![hash1].into_iter().collect();
+        assert_eq!(
+            check_deploys(deploys, &resolved_deploys, block_time),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn should_reject_missing_deploy() {
+        let mut rng = TestRng::new();
+        let hash = random_deploy_hashes(&mut rng, 1)[0];
+        assert_eq!(
+            check_deploys(vec![(&hash, None)], &HashSet::new(), Timestamp::from(100)),
+            Err(InvalidProposal::MissingDeploy(hash))
+        );
+    }
+}
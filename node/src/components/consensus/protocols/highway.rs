@@ -13,7 +13,9 @@ use tracing::{info, trace};
 use crate::{
     components::consensus::{
         candidate_block::CandidateBlock,
-        consensus_protocol::{BlockContext, ConsensusProtocol, ConsensusProtocolResult},
+        consensus_protocol::{
+            BlockContext, ConsensusProtocol, ConsensusProtocolResult, ProtocolStats,
+        },
         highway_core::{
             active_validator::Effect as AvEffect,
             finality_detector::FinalityDetector,
@@ -199,15 +201,21 @@ impl<I: NodeIdT, C: Context> HighwayProtocol<I, C> {
                 } else {
                     match self.highway.validate_vertex(pvv) {
                         Ok(vv) => {
-                            if let Some(value) = vv.inner().value().cloned() {
+                            if let (Some(value), Some(timestamp)) =
+                                (vv.inner().value().cloned(), vv.inner().timestamp())
+                            {
                                 // It's a block: Request validation before adding it to the state.
+                                let ancestor_values = self.highway.ancestor_values(&vv);
                                 self.pending_values
                                     .entry(value.clone())
                                     .or_default()
                                     .push(vv);
-                                results.push(ConsensusProtocolResult::ValidateConsensusValue(
-                                    sender, value,
-                                ));
+                                results.push(ConsensusProtocolResult::ValidateConsensusValue {
+                                    sender,
+                                    value,
+                                    timestamp,
+                                    ancestor_values,
+                                });
                             } else {
                                 // It's not a block: Add it to the state.
                                 let now = Timestamp::now();
@@ -395,6 +403,10 @@ where
         self.highway.deactivate_validator()
     }
 
+    fn is_active(&self) -> bool {
+        self.highway.is_active()
+    }
+
     fn has_evidence(&self, vid: &C::ValidatorId) -> bool {
         self.highway.has_evidence(vid)
     }
@@ -429,6 +441,10 @@ where
         self.highway.validators_with_evidence().collect()
     }
 
+    fn stats(&self) -> ProtocolStats {
+        self.highway.stats()
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -480,3 +496,129 @@ impl Context for HighwayContext {
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use prometheus::Registry;
+
+    use super::*;
+    use crate::{
+        components::consensus::{metrics::ConsensusMetrics, EraId},
+        testing::TestRng,
+        types::ProtoBlock,
+    };
+
+    #[test]
+    fn should_verify_signatures_of_mixed_validator_set() {
+        let mut rng = TestRng::new();
+        let secret_keys = vec![
+            SecretKey::random_ed25519(&mut rng),
+            SecretKey::random_secp256k1(&mut rng),
+        ];
+        // Validator IDs are obtained from the contract runtime as `casper_types` public keys.
+        let validator_ids: Vec<PublicKey> = secret_keys
+            .iter()
+            .map(|secret_key| {
+                let public_key = casper_types::PublicKey::from(PublicKey::from(secret_key));
+                PublicKey::try_from(public_key).unwrap()
+            })
+            .collect();
+
+        let hash = hash::hash(b"vote");
+        let signatures: Vec<Signature> = secret_keys
+            .into_iter()
+            .zip(&validator_ids)
+            .map(|(secret_key, public_key)| {
+                HighwaySecret::new(Rc::new(secret_key), *public_key).sign(&hash, &mut rng)
+            })
+            .collect();
+
+        for (signer_index, signature) in signatures.iter().enumerate() {
+            for (validator_index, validator_id) in validator_ids.iter().enumerate() {
+                assert_eq!(
+                    HighwayContext::verify_signature(&hash, validator_id, signature),
+                    signer_index == validator_index
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn should_report_stats_as_era_metrics() {
+        let mut rng = TestRng::new();
+        let secret_key = SecretKey::random_ed25519(&mut rng);
+        let public_key = PublicKey::from(&secret_key);
+        let validators: Validators<PublicKey> = vec![(public_key, 10u64)].into_iter().collect();
+        let params = Params::new(
+            0,
+            1_000,
+            200,
+            4,
+            4,
+            "1s".parse().unwrap(),
+            10,
+            Timestamp::from(1_000),
+        );
+        let mut highway = HighwayProtocol::<u64, HighwayContext>::new(
+            hash::hash(b"instance"),
+            validators,
+            params,
+            Weight(0),
+        );
+        let metrics = ConsensusMetrics::new(&Registry::new()).unwrap();
+        let era_id = EraId(1);
+        let unit_count = || metrics.era_unit_count.with_label_values(&["1"]).get();
+        let last_unit_timestamp = || {
+            metrics
+                .era_our_last_unit_timestamp
+                .with_label_values(&["1"])
+                .get()
+        };
+
+        assert!(!highway.is_active());
+        let secret = HighwaySecret::new(Rc::new(secret_key), public_key);
+        let _ = highway.activate_validator(public_key, secret, 410.into());
+        assert!(highway.is_active());
+        let stats = highway.stats();
+        assert_eq!(stats.unit_count, 0);
+        assert_eq!(stats.round_exponent, Some(4));
+        assert_eq!(stats.our_last_unit_timestamp, None);
+        metrics.observe_era(era_id, &stats);
+        assert_eq!(unit_count(), 0);
+        assert_eq!(
+            metrics.era_round_exponent.with_label_values(&["1"]).get(),
+            4
+        );
+
+        // As the only validator, we lead the round starting at 416 and propose a block.
+        let block_context = highway
+            .handle_timer(416.into(), &mut rng)
+            .into_iter()
+            .find_map(|result| match result {
+                ConsensusProtocolResult::CreateNewBlock { block_context } => Some(block_context),
+                _ => None,
+            })
+            .expect("should request a new block");
+        let value = CandidateBlock::new(ProtoBlock::new(vec![], false), vec![]);
+        let _ = highway.propose(value, block_context, &mut rng);
+        metrics.observe_era(era_id, &highway.stats());
+        assert_eq!(unit_count(), 1);
+        assert_eq!(last_unit_timestamp(), 416);
+
+        // Two thirds into the round, we cast our witness vote.
+        let _ = highway.handle_timer(426.into(), &mut rng);
+        metrics.observe_era(era_id, &highway.stats());
+        assert_eq!(unit_count(), 2);
+        assert_eq!(last_unit_timestamp(), 426);
+        assert_eq!(metrics.era_fault_count.with_label_values(&["1"]).get(), 0);
+
+        metrics.remove_era(era_id);
+        assert!(metrics.era_unit_count.remove_label_values(&["1"]).is_err());
+        assert!(metrics
+            .era_our_last_unit_timestamp
+            .remove_label_values(&["1"])
+            .is_err());
+    }
+}
@@ -12,13 +12,17 @@ use std::{
 use datasize::DataSize;
 use derive_more::From;
 use lmdb::DatabaseFlags;
-use prometheus::{self, Histogram, HistogramOpts, Registry};
+use prometheus::{self, Histogram, HistogramOpts, IntGauge, Registry};
 use thiserror::Error;
 use tokio::task;
 use tracing::trace;
 
 use casper_execution_engine::{
-    core::engine_state::{genesis::GenesisResult, EngineConfig, EngineState, Error},
+    config::ConfigError as EngineConfigError,
+    core::engine_state::{
+        genesis::GenesisResult, EngineState, Error, SystemContractMetadataCache,
+        WasmPreprocessingCache,
+    },
     shared::newtypes::CorrelationId,
     storage::{
         error::lmdb::Error as StorageLmdbError, global_state::lmdb::LmdbGlobalState,
@@ -70,11 +74,16 @@ impl Display for Event {
 #[derive(Debug)]
 pub struct ContractRuntimeMetrics {
     run_execute: Histogram,
+    dry_run_deploy: Histogram,
     apply_effect: Histogram,
     commit_upgrade: Histogram,
     run_query: Histogram,
     get_balance: Histogram,
     get_validator_weights: Histogram,
+    system_contract_cache_hits: IntGauge,
+    system_contract_cache_misses: IntGauge,
+    wasm_preprocessing_cache_hits: IntGauge,
+    wasm_preprocessing_cache_misses: IntGauge,
 }
 
 /// Value of upper bound of histogram.
@@ -86,6 +95,8 @@ const EXPONENTIAL_BUCKET_COUNT: usize = 6;
 
 const RUN_EXECUTE_NAME: &str = "contract_runtime_run_execute";
 const RUN_EXECUTE_HELP: &str = "tracking run of engine_state.run_execute.";
+const DRY_RUN_DEPLOY_NAME: &str = "contract_runtime_dry_run_deploy";
+const DRY_RUN_DEPLOY_HELP: &str = "tracking run of engine_state.dry_run_deploy.";
 const APPLY_EFFECT_NAME: &str = "contract_runtime_apply_commit";
 const APPLY_EFFECT_HELP: &str = "tracking run of engine_state.apply_effect.";
 const RUN_QUERY_NAME: &str = "contract_runtime_run_query";
@@ -96,6 +107,19 @@ const GET_BALANCE_NAME: &str = "contract_runtime_get_balance";
 const GET_BALANCE_HELP: &str = "tracking run of engine_state.get_balance.";
 const GET_VALIDATOR_WEIGHTS_NAME: &str = "contract_runtime_get_validator_weights";
 const GET_VALIDATOR_WEIGHTS_HELP: &str = "tracking run of engine_state.get_validator_weights.";
+const SYSTEM_CONTRACT_CACHE_HITS_NAME: &str = "contract_runtime_system_contract_cache_hits";
+const SYSTEM_CONTRACT_CACHE_HITS_HELP: &str =
+    "number of system contract lookups served by the metadata cache.";
+const SYSTEM_CONTRACT_CACHE_MISSES_NAME: &str = "contract_runtime_system_contract_cache_misses";
+const SYSTEM_CONTRACT_CACHE_MISSES_HELP: &str =
+    "number of system contract lookups not served by the metadata cache.";
+const WASM_PREPROCESSING_CACHE_HITS_NAME: &str = "contract_runtime_wasm_preprocessing_cache_hits";
+const WASM_PREPROCESSING_CACHE_HITS_HELP: &str =
+    "number of deploy modules served by the wasm preprocessing cache.";
+const WASM_PREPROCESSING_CACHE_MISSES_NAME: &str =
+    "contract_runtime_wasm_preprocessing_cache_misses";
+const WASM_PREPROCESSING_CACHE_MISSES_HELP: &str =
+    "number of deploy modules preprocessed as they were not in the wasm preprocessing cache.";
 
 /// Create prometheus Histogram and register.
 fn register_histogram_metric(
@@ -114,11 +138,27 @@ fn register_histogram_metric(
     Ok(histogram)
 }
 
+/// Create prometheus IntGauge and register.
+fn register_int_gauge_metric(
+    registry: &Registry,
+    metric_name: &str,
+    metric_help: &str,
+) -> Result<IntGauge, prometheus::Error> {
+    let int_gauge = IntGauge::new(metric_name, metric_help)?;
+    registry.register(Box::new(int_gauge.clone()))?;
+    Ok(int_gauge)
+}
+
 impl ContractRuntimeMetrics {
     /// Constructor of metrics which creates and registers metrics objects for use.
     fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
         Ok(ContractRuntimeMetrics {
             run_execute: register_histogram_metric(registry, RUN_EXECUTE_NAME, RUN_EXECUTE_HELP)?,
+            dry_run_deploy: register_histogram_metric(
+                registry,
+                DRY_RUN_DEPLOY_NAME,
+                DRY_RUN_DEPLOY_HELP,
+            )?,
             apply_effect: register_histogram_metric(
                 registry,
                 APPLY_EFFECT_NAME,
@@ -136,8 +176,41 @@ impl ContractRuntimeMetrics {
                 GET_VALIDATOR_WEIGHTS_NAME,
                 GET_VALIDATOR_WEIGHTS_HELP,
             )?,
+            system_contract_cache_hits: register_int_gauge_metric(
+                registry,
+                SYSTEM_CONTRACT_CACHE_HITS_NAME,
+                SYSTEM_CONTRACT_CACHE_HITS_HELP,
+            )?,
+            system_contract_cache_misses: register_int_gauge_metric(
+                registry,
+                SYSTEM_CONTRACT_CACHE_MISSES_NAME,
+                SYSTEM_CONTRACT_CACHE_MISSES_HELP,
+            )?,
+            wasm_preprocessing_cache_hits: register_int_gauge_metric(
+                registry,
+                WASM_PREPROCESSING_CACHE_HITS_NAME,
+                WASM_PREPROCESSING_CACHE_HITS_HELP,
+            )?,
+            wasm_preprocessing_cache_misses: register_int_gauge_metric(
+                registry,
+                WASM_PREPROCESSING_CACHE_MISSES_NAME,
+                WASM_PREPROCESSING_CACHE_MISSES_HELP,
+            )?,
         })
     }
+
+    /// Updates the system contract cache metrics from the cache's running totals.
+    fn observe_system_contract_cache(&self, cache: &SystemContractMetadataCache) {
+        self.system_contract_cache_hits.set(cache.hits() as i64);
+        self.system_contract_cache_misses.set(cache.misses() as i64);
+    }
+
+    /// Updates the wasm preprocessing cache metrics from the cache's running totals.
+    fn observe_wasm_preprocessing_cache(&self, cache: &WasmPreprocessingCache) {
+        self.wasm_preprocessing_cache_hits.set(cache.hits() as i64);
+        self.wasm_preprocessing_cache_misses
+            .set(cache.misses() as i64);
+    }
 }
 
 impl<REv> Component<REv> for ContractRuntime
@@ -185,6 +258,12 @@ where
                         let execution_result =
                             engine_state.run_execute(correlation_id, execute_request);
                         metrics.run_execute.observe(start.elapsed().as_secs_f64());
+                        metrics.observe_system_contract_cache(
+                            engine_state.system_contract_metadata_cache(),
+                        );
+                        metrics.observe_wasm_preprocessing_cache(
+                            engine_state.wasm_preprocessing_cache(),
+                        );
                         execution_result
                     })
                     .await
@@ -194,6 +273,31 @@ where
                 }
                 .ignore()
             }
+            Event::Request(ContractRuntimeRequest::DryRunDeploy {
+                execute_request,
+                responder,
+            }) => {
+                trace!(?execute_request, "dry run");
+                let engine_state = Arc::clone(&self.engine_state);
+                let metrics = Arc::clone(&self.metrics);
+                async move {
+                    let correlation_id = CorrelationId::new();
+                    let result = task::spawn_blocking(move || {
+                        let start = Instant::now();
+                        let dry_run_result =
+                            engine_state.dry_run_deploy(correlation_id, execute_request);
+                        metrics
+                            .dry_run_deploy
+                            .observe(start.elapsed().as_secs_f64());
+                        dry_run_result
+                    })
+                    .await
+                    .expect("should run");
+                    trace!(?result, "dry run result");
+                    responder.respond(result).await
+                }
+                .ignore()
+            }
             Event::Request(ContractRuntimeRequest::Commit {
                 state_root_hash,
                 effects,
@@ -306,6 +410,9 @@ where
                         let start = Instant::now();
                         let result = engine_state.get_era_validators(correlation_id, get_request);
                         metrics.get_balance.observe(start.elapsed().as_secs_f64());
+                        metrics.observe_system_contract_cache(
+                            engine_state.system_contract_metadata_cache(),
+                        );
                         result
                     })
                     .await
@@ -328,6 +435,9 @@ where
                         let start = Instant::now();
                         let result = engine_state.commit_step(correlation_id, step_request);
                         metrics.get_balance.observe(start.elapsed().as_secs_f64());
+                        metrics.observe_system_contract_cache(
+                            engine_state.system_contract_metadata_cache(),
+                        );
                         result
                     })
                     .await
@@ -350,6 +460,9 @@ pub enum ConfigError {
     /// Error initializing metrics.
     #[error("failed to initialize metrics for contract runtime: {0}")]
     Prometheus(#[from] prometheus::Error),
+    /// Invalid limits.
+    #[error("invalid limits for contract runtime: {0}")]
+    Limits(#[from] EngineConfigError),
 }
 
 impl ContractRuntime {
@@ -358,6 +471,8 @@ impl ContractRuntime {
         contract_runtime_config: Config,
         registry: &Registry,
     ) -> Result<Self, ConfigError> {
+        contract_runtime_config.validate()?;
+
         let path = storage_config.with_dir(storage_config.value().path());
         let environment = Arc::new(LmdbEnvironment::new(
             path.as_path(),
@@ -377,10 +492,10 @@ impl ContractRuntime {
         )?);
 
         let global_state = LmdbGlobalState::empty(environment, trie_store, protocol_data_store)?;
-        let engine_config = EngineConfig::new()
-            .with_use_system_contracts(contract_runtime_config.use_system_contracts());
-
-        let engine_state = Arc::new(EngineState::new(global_state, engine_config));
+        let engine_state = Arc::new(EngineState::new(
+            global_state,
+            contract_runtime_config.engine_config(),
+        ));
 
         let metrics = Arc::new(ContractRuntimeMetrics::new(registry)?);
         Ok(ContractRuntime {
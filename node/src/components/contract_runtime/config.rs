@@ -1,7 +1,16 @@
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
-use casper_execution_engine::shared::utils;
+use casper_execution_engine::{
+    config::{
+        validate_max_global_state_size, validate_wasm_limits, ConfigError as EngineConfigError,
+    },
+    core::engine_state::{
+        engine_config::DEFAULT_MAX_QUERY_PATH_LENGTH,
+        wasm_preprocessing_cache::DEFAULT_WASM_PREPROCESSING_CACHE_SIZE, EngineConfig,
+    },
+    shared::utils,
+};
 
 const DEFAULT_MAX_GLOBAL_STATE_SIZE: usize = 805_306_368_000; // 750 GiB
 const DEFAULT_USE_SYSTEM_CONTRACTS: bool = false;
@@ -17,8 +26,24 @@ pub struct Config {
     ///
     /// Defaults to 805,306,368,000 == 750 GiB.
     ///
-    /// The size should be a multiple of the OS page size.
+    /// The size must be a non-zero multiple of the OS page size, and at most 8 TiB.
     max_global_state_size: Option<usize>,
+    /// The maximum number of path components a global state query may follow from its base key.
+    ///
+    /// Defaults to 10.
+    max_query_path_length: Option<usize>,
+    /// The maximum number of 64 KiB pages of linear memory a wasm module may use.
+    ///
+    /// Defaults to the chainspec's `initial_memory`.  Must be between 1 and 65,536.
+    max_wasm_memory_pages: Option<u32>,
+    /// The maximum stack height enforced on a wasm module.
+    ///
+    /// Defaults to the chainspec's `max_stack_height`.  Must be between 1 and 131,072.
+    max_stack_height: Option<u32>,
+    /// The maximum number of preprocessed deploy modules to cache, keyed by their module bytes.
+    ///
+    /// Defaults to 256.  A size of 0 disables the cache.
+    wasm_preprocessing_cache_size: Option<usize>,
 }
 
 impl Config {
@@ -34,6 +59,44 @@ impl Config {
         utils::check_multiple_of_page_size(value);
         value
     }
+
+    pub(crate) fn max_query_path_length(&self) -> usize {
+        self.max_query_path_length
+            .unwrap_or(DEFAULT_MAX_QUERY_PATH_LENGTH)
+    }
+
+    pub(crate) fn max_wasm_memory_pages(&self) -> Option<u32> {
+        self.max_wasm_memory_pages
+    }
+
+    pub(crate) fn max_stack_height(&self) -> Option<u32> {
+        self.max_stack_height
+    }
+
+    pub(crate) fn wasm_preprocessing_cache_size(&self) -> usize {
+        self.wasm_preprocessing_cache_size
+            .unwrap_or(DEFAULT_WASM_PREPROCESSING_CACHE_SIZE)
+    }
+
+    /// Returns the execution engine configuration corresponding to this config.
+    pub(crate) fn engine_config(&self) -> EngineConfig {
+        EngineConfig::new()
+            .with_use_system_contracts(self.use_system_contracts())
+            .with_max_query_path_length(self.max_query_path_length())
+            .with_max_wasm_memory_pages(self.max_wasm_memory_pages())
+            .with_max_stack_height(self.max_stack_height())
+            .with_wasm_preprocessing_cache_size(self.wasm_preprocessing_cache_size())
+    }
+
+    /// Checks that the configured global state size and wasm limits are within the supported
+    /// range.
+    pub(crate) fn validate(&self) -> Result<(), EngineConfigError> {
+        validate_max_global_state_size(
+            self.max_global_state_size
+                .unwrap_or(DEFAULT_MAX_GLOBAL_STATE_SIZE),
+        )?;
+        validate_wasm_limits(self.max_wasm_memory_pages, self.max_stack_height)
+    }
 }
 
 impl Default for Config {
@@ -41,6 +104,10 @@ impl Default for Config {
         Config {
             use_system_contracts: Some(DEFAULT_USE_SYSTEM_CONTRACTS),
             max_global_state_size: Some(DEFAULT_MAX_GLOBAL_STATE_SIZE),
+            max_query_path_length: Some(DEFAULT_MAX_QUERY_PATH_LENGTH),
+            max_wasm_memory_pages: None,
+            max_stack_height: None,
+            wasm_preprocessing_cache_size: Some(DEFAULT_WASM_PREPROCESSING_CACHE_SIZE),
         }
     }
 }
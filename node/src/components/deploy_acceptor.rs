@@ -4,16 +4,26 @@ mod event;
 use std::{collections::HashMap, fmt::Debug};
 
 use semver::Version;
+use thiserror::Error;
+use tokio::task;
 use tracing::{debug, error, warn};
 
+use casper_execution_engine::{
+    core::engine_state::{executable_deploy_item::ExecutableDeployItem, EngineConfig},
+    shared::{
+        wasm_config::WasmConfig,
+        wasm_prep::{self, PreprocessingConfig, PreprocessingError, Preprocessor},
+    },
+};
+
 use crate::{
-    components::{chainspec_loader::Chainspec, storage::Storage, Component},
+    components::{chainspec_loader::Chainspec, contract_runtime, storage::Storage, Component},
     effect::{
         announcements::DeployAcceptorAnnouncement, requests::StorageRequest, EffectBuilder,
-        EffectExt, Effects,
+        EffectExt, Effects, Responder,
     },
     small_network::NodeId,
-    types::{CryptoRngCore, Deploy},
+    types::{CryptoRngCore, Deploy, TimeDiff},
     utils::Source,
 };
 
@@ -35,10 +45,55 @@ impl<REv> ReactorEventT for REv where
 {
 }
 
+/// The reason a `Deploy` was rejected by the `DeployAcceptor`.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The chainspec needed to validate the deploy couldn't be retrieved.
+    #[error("failed to get chainspec at {chainspec_version}")]
+    MissingChainspec { chainspec_version: Version },
+
+    /// The deploy is for a different chain.
+    #[error("invalid chain name: expected {expected}, got {got}")]
+    InvalidChainName { expected: String, got: String },
+
+    /// The deploy has more dependencies than permitted.
+    #[error("{got} dependencies exceed the limit of {max_dependencies}")]
+    ExcessiveDependencies { max_dependencies: u8, got: usize },
+
+    /// The deploy's time-to-live is longer than permitted.
+    #[error("ttl of {got} exceeds the limit of {max_ttl}")]
+    ExcessiveTimeToLive { max_ttl: TimeDiff, got: TimeDiff },
+
+    /// The deploy's hash or approvals are invalid.
+    #[error("invalid deploy hash or approvals")]
+    InvalidDeploy,
+
+    /// The payment or session module bytes are larger than permitted.
+    #[error("{code} module bytes of {size} bytes exceed the limit of {max_size} bytes")]
+    ModuleBytesTooLarge {
+        code: &'static str,
+        size: usize,
+        max_size: usize,
+    },
+
+    /// The payment or session module bytes define no memory of their own.
+    #[error("{code} module bytes define no memory")]
+    ModuleWithoutMemory { code: &'static str },
+
+    /// The payment or session module bytes are not a valid wasm module within the configured
+    /// limits.
+    #[error("invalid {code} module bytes: {error}")]
+    InvalidModuleBytes {
+        code: &'static str,
+        error: PreprocessingError,
+    },
+}
+
 #[derive(Debug, Clone)]
 pub struct DeployAcceptorConfig {
     chain_name: String,
     deploy_config: DeployConfig,
+    wasm_config: WasmConfig,
 }
 
 impl From<Chainspec> for DeployAcceptorConfig {
@@ -46,6 +101,7 @@ impl From<Chainspec> for DeployAcceptorConfig {
         DeployAcceptorConfig {
             chain_name: c.genesis.name,
             deploy_config: c.genesis.deploy_config,
+            wasm_config: c.genesis.wasm_config,
         }
     }
 }
@@ -54,25 +110,34 @@ impl From<Chainspec> for DeployAcceptorConfig {
 /// received by this node, regardless of whether they were provided by a peer or a client.
 ///
 /// It validates a new `Deploy` as far as possible, stores it if valid, then announces the newly-
-/// accepted `Deploy`.
+/// accepted `Deploy`.  Payment and session module bytes are preprocessed as the execution engine
+/// would, so that invalid wasm is rejected before it is gossiped or included in a block.
 #[derive(Debug)]
 pub(crate) struct DeployAcceptor {
     cached_deploy_configs: HashMap<Version, DeployAcceptorConfig>,
+    /// The execution engine's configuration, providing the limits applied to module bytes.
+    engine_config: EngineConfig,
 }
 
 impl DeployAcceptor {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(contract_runtime_config: &contract_runtime::Config) -> Self {
         DeployAcceptor {
             cached_deploy_configs: HashMap::new(),
+            engine_config: contract_runtime_config.engine_config(),
         }
     }
 
     /// Handles receiving a new `Deploy` from a peer or client.
+    ///
+    /// If `verify_only` is set, the outcome of validating the deploy is the only effect: it is
+    /// neither stored nor announced.
     fn accept<REv: ReactorEventT>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
         deploy: Box<Deploy>,
         source: Source<NodeId>,
+        verify_only: bool,
+        responder: Option<Responder<Result<(), Error>>>,
     ) -> Effects<Event> {
         // TODO - where to get version from?
         let chainspec_version = Version::new(1, 0, 0);
@@ -86,6 +151,8 @@ impl DeployAcceptor {
                         source,
                         chainspec_version,
                         maybe_deploy_config: Box::new(Some(genesis_config)),
+                        verify_only,
+                        responder,
                     })
             }
             None => effect_builder
@@ -95,30 +162,81 @@ impl DeployAcceptor {
                     source,
                     chainspec_version,
                     maybe_deploy_config: Box::new(maybe_chainspec.map(|c| c.into())),
+                    verify_only,
+                    responder,
                 }),
         }
     }
 
-    fn validate<REv: ReactorEventT>(
+    /// Validates the deploy on a blocking thread, as preprocessing its module bytes is too
+    /// expensive to run on the reactor.
+    fn validate(
+        &self,
+        deploy: Box<Deploy>,
+        source: Source<NodeId>,
+        deploy_config: DeployAcceptorConfig,
+        verify_only: bool,
+        responder: Option<Responder<Result<(), Error>>>,
+    ) -> Effects<Event> {
+        let engine_config = self.engine_config;
+        async move {
+            task::spawn_blocking(move || {
+                let mut deploy = deploy;
+                let result = validate(&mut deploy, &deploy_config, engine_config);
+                (deploy, result)
+            })
+            .await
+            .expect("should run")
+        }
+        .event(move |(deploy, result)| Event::ValidationResult {
+            deploy,
+            source,
+            verify_only,
+            result,
+            responder,
+        })
+    }
+
+    fn handle_validation_result<REv: ReactorEventT>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
         deploy: Box<Deploy>,
         source: Source<NodeId>,
-        deploy_config: DeployAcceptorConfig,
+        verify_only: bool,
+        result: Result<(), Error>,
+        responder: Option<Responder<Result<(), Error>>>,
     ) -> Effects<Event> {
-        let mut cloned_deploy = deploy.clone();
-        if is_valid(&mut cloned_deploy, deploy_config) {
-            effect_builder
-                .put_deploy_to_storage(cloned_deploy)
+        if verify_only {
+            if let Err(error) = &result {
+                debug!(deploy_hash = %deploy.id(), %error, "invalid deploy to dry run");
+            }
+            return respond(responder, result);
+        }
+        match result {
+            Ok(()) => effect_builder
+                .put_deploy_to_storage(deploy.clone())
                 .event(move |is_new| Event::PutToStorageResult {
                     deploy,
                     source,
                     is_new,
-                })
-        } else {
-            effect_builder
-                .announce_invalid_deploy(deploy, source)
-                .ignore()
+                    responder,
+                }),
+            Err(error) => {
+                warn!(
+                    deploy_hash = %deploy.id(),
+                    deploy_header = %deploy.header(),
+                    %source,
+                    %error,
+                    "invalid deploy"
+                );
+                let mut effects = respond(responder, Err(error));
+                effects.extend(
+                    effect_builder
+                        .announce_invalid_deploy(deploy, source)
+                        .ignore(),
+                );
+                effects
+            }
         }
     }
 
@@ -127,9 +245,13 @@ impl DeployAcceptor {
         deploy: Box<Deploy>,
         source: Source<NodeId>,
         chainspec_version: Version,
+        responder: Option<Responder<Result<(), Error>>>,
     ) -> Effects<Event> {
         error!(%deploy, %source, %chainspec_version, "failed to get chainspec");
-        Effects::new()
+        respond(
+            responder,
+            Err(Error::MissingChainspec { chainspec_version }),
+        )
     }
 
     fn handle_put_to_storage<REv: ReactorEventT>(
@@ -138,13 +260,17 @@ impl DeployAcceptor {
         deploy: Box<Deploy>,
         source: Source<NodeId>,
         is_new: bool,
+        responder: Option<Responder<Result<(), Error>>>,
     ) -> Effects<Event> {
+        let mut effects = respond(responder, Ok(()));
         if is_new {
-            return effect_builder
-                .announce_new_deploy_accepted(deploy, source)
-                .ignore();
+            effects.extend(
+                effect_builder
+                    .announce_new_deploy_accepted(deploy, source)
+                    .ignore(),
+            );
         }
-        Effects::new()
+        effects
     }
 }
 
@@ -159,62 +285,197 @@ impl<REv: ReactorEventT> Component<REv> for DeployAcceptor {
     ) -> Effects<Self::Event> {
         debug!(?event, "handling event");
         match event {
-            Event::Accept { deploy, source } => self.accept(effect_builder, deploy, source),
+            Event::Accept {
+                deploy,
+                source,
+                responder,
+            } => self.accept(effect_builder, deploy, source, false, responder),
+            Event::Verify { deploy, responder } => self.accept(
+                effect_builder,
+                deploy,
+                Source::Client,
+                true,
+                Some(responder),
+            ),
             Event::GetChainspecResult {
                 deploy,
                 source,
                 chainspec_version,
                 maybe_deploy_config,
+                verify_only,
+                responder,
             } => match *maybe_deploy_config {
                 Some(deploy_config) => {
                     // Update chainspec cache.
                     self.cached_deploy_configs
                         .insert(chainspec_version, deploy_config.clone());
-                    self.validate(effect_builder, deploy, source, deploy_config)
+                    self.validate(deploy, source, deploy_config, verify_only, responder)
                 }
-                None => self.failed_to_get_chainspec(deploy, source, chainspec_version),
+                None => self.failed_to_get_chainspec(deploy, source, chainspec_version, responder),
             },
+            Event::ValidationResult {
+                deploy,
+                source,
+                verify_only,
+                result,
+                responder,
+            } => self.handle_validation_result(
+                effect_builder,
+                deploy,
+                source,
+                verify_only,
+                result,
+                responder,
+            ),
             Event::PutToStorageResult {
                 deploy,
                 source,
                 is_new,
-            } => self.handle_put_to_storage(effect_builder, deploy, source, is_new),
+                responder,
+            } => self.handle_put_to_storage(effect_builder, deploy, source, is_new, responder),
         }
     }
 }
 
-fn is_valid(deploy: &mut Deploy, config: DeployAcceptorConfig) -> bool {
+/// Responds to the client which submitted a deploy, if any.
+fn respond(
+    responder: Option<Responder<Result<(), Error>>>,
+    result: Result<(), Error>,
+) -> Effects<Event> {
+    match responder {
+        Some(responder) => responder.respond(result).ignore(),
+        None => Effects::new(),
+    }
+}
+
+fn validate(
+    deploy: &mut Deploy,
+    config: &DeployAcceptorConfig,
+    engine_config: EngineConfig,
+) -> Result<(), Error> {
     if deploy.header().chain_name() != config.chain_name {
-        warn!(
-            deploy_hash = %deploy.id(),
-            deploy_header = %deploy.header(),
-            chain_name = %config.chain_name,
-            "invalid chain identifier"
-        );
-        return false;
-    }
-
-    if deploy.header().dependencies().len() > config.deploy_config.max_dependencies as usize {
-        warn!(
-            deploy_hash = %deploy.id(),
-            deploy_header = %deploy.header(),
-            max_dependencies = %config.deploy_config.max_dependencies,
-            "deploy dependency ceiling exceeded"
-        );
-        return false;
-    }
-
-    if deploy.header().ttl() > config.deploy_config.max_ttl {
-        warn!(
-            deploy_hash = %deploy.id(),
-            deploy_header = %deploy.header(),
-            max_ttl = %config.deploy_config.max_ttl,
-            "deploy ttl excessive"
-        );
-        return false;
+        return Err(Error::InvalidChainName {
+            expected: config.chain_name.clone(),
+            got: deploy.header().chain_name().to_string(),
+        });
+    }
+
+    let max_dependencies = config.deploy_config.max_dependencies;
+    if deploy.header().dependencies().len() > max_dependencies as usize {
+        return Err(Error::ExcessiveDependencies {
+            max_dependencies,
+            got: deploy.header().dependencies().len(),
+        });
     }
 
+    let max_ttl = config.deploy_config.max_ttl;
+    if deploy.header().ttl() > max_ttl {
+        return Err(Error::ExcessiveTimeToLive {
+            max_ttl,
+            got: deploy.header().ttl(),
+        });
+    }
+
+    let preprocessor = Preprocessor::new(PreprocessingConfig::new(
+        engine_config.apply_wasm_limits(config.wasm_config),
+        engine_config.memory_limit_pages(),
+    ));
+    let max_size = engine_config.max_deploy_size_bytes();
+    validate_module_bytes("payment", deploy.payment(), &preprocessor, max_size)?;
+    validate_module_bytes("session", deploy.session(), &preprocessor, max_size)?;
+
     // TODO - check if there is more that can be validated here.
 
-    deploy.is_valid()
+    if !deploy.is_valid() {
+        return Err(Error::InvalidDeploy);
+    }
+    Ok(())
+}
+
+/// Checks that the module bytes of `item`, if any, are no larger than `max_size` and would pass
+/// the execution engine's preprocessing.  Stored contracts and transfers aren't checked.
+///
+/// Empty payment module bytes are permitted, as they select the standard payment contract.
+fn validate_module_bytes(
+    code: &'static str,
+    item: &ExecutableDeployItem,
+    preprocessor: &Preprocessor,
+    max_size: usize,
+) -> Result<(), Error> {
+    let module_bytes = match item {
+        ExecutableDeployItem::ModuleBytes { module_bytes, .. } => module_bytes,
+        _ => return Ok(()),
+    };
+    if code == "payment" && module_bytes.is_empty() {
+        return Ok(());
+    }
+    if module_bytes.len() > max_size {
+        return Err(Error::ModuleBytesTooLarge {
+            code,
+            size: module_bytes.len(),
+            max_size,
+        });
+    }
+
+    // Preprocessing assumes the module defines its own memory, so check this first.
+    let module = wasm_prep::deserialize(module_bytes)
+        .map_err(|error| Error::InvalidModuleBytes { code, error })?;
+    if module.memory_section().is_none() {
+        return Err(Error::ModuleWithoutMemory { code });
+    }
+
+    preprocessor
+        .preprocess(module_bytes)
+        .map(|_| ())
+        .map_err(|error| Error::InvalidModuleBytes { code, error })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{crypto::asymmetric_key::SecretKey, testing::TestRng, types::Timestamp};
+
+    fn config(chain_name: &str) -> DeployAcceptorConfig {
+        DeployAcceptorConfig {
+            chain_name: chain_name.to_string(),
+            deploy_config: DeployConfig::default(),
+            wasm_config: WasmConfig::default(),
+        }
+    }
+
+    fn deploy(chain_name: &str, rng: &mut TestRng) -> Deploy {
+        let stored_contract = || ExecutableDeployItem::StoredContractByName {
+            name: "contract".to_string(),
+            entry_point: "call".to_string(),
+            args: vec![],
+        };
+        Deploy::new(
+            Timestamp::now(),
+            TimeDiff::from(60_000),
+            1,
+            vec![],
+            chain_name.to_string(),
+            stored_contract(),
+            stored_contract(),
+            &SecretKey::random(rng),
+            rng,
+        )
+    }
+
+    #[test]
+    fn should_reject_deploy_for_other_chain() {
+        let mut rng = TestRng::new();
+        let mainnet_config = config("casper-mainnet");
+        let engine_config = EngineConfig::default();
+
+        let mut testnet_deploy = deploy("casper-testnet", &mut rng);
+        assert!(matches!(
+            validate(&mut testnet_deploy, &mainnet_config, engine_config),
+            Err(Error::InvalidChainName { expected, got })
+                if expected == "casper-mainnet" && got == "casper-testnet"
+        ));
+
+        let mut mainnet_deploy = deploy("casper-mainnet", &mut rng);
+        assert!(validate(&mut mainnet_deploy, &mainnet_config, engine_config).is_ok());
+    }
 }
@@ -2,8 +2,8 @@ use std::fmt::{self, Display, Formatter};
 
 use semver::Version;
 
-use super::{DeployAcceptorConfig, Source};
-use crate::{small_network::NodeId, types::Deploy};
+use super::{DeployAcceptorConfig, Error, Source};
+use crate::{effect::Responder, small_network::NodeId, types::Deploy};
 
 /// `DeployAcceptor` events.
 #[derive(Debug)]
@@ -12,6 +12,14 @@ pub enum Event {
     Accept {
         deploy: Box<Deploy>,
         source: Source<NodeId>,
+        /// Responder to call with the outcome, if the deploy was submitted by a client.
+        responder: Option<Responder<Result<(), Error>>>,
+    },
+    /// The initiating event to validate a `Deploy` submitted by a client without storing or
+    /// announcing it, e.g. before dry running it.
+    Verify {
+        deploy: Box<Deploy>,
+        responder: Responder<Result<(), Error>>,
     },
     /// The result of getting the chainspec from the storage component.
     GetChainspecResult {
@@ -19,21 +27,35 @@ pub enum Event {
         source: Source<NodeId>,
         chainspec_version: Version,
         maybe_deploy_config: Box<Option<DeployAcceptorConfig>>,
+        /// Whether the deploy is only to be validated, not stored.
+        verify_only: bool,
+        responder: Option<Responder<Result<(), Error>>>,
+    },
+    /// The result of validating a `Deploy` on a blocking thread.
+    ValidationResult {
+        deploy: Box<Deploy>,
+        source: Source<NodeId>,
+        /// Whether the deploy is only to be validated, not stored.
+        verify_only: bool,
+        result: Result<(), Error>,
+        responder: Option<Responder<Result<(), Error>>>,
     },
     /// The result of the `DeployAcceptor` putting a `Deploy` to the storage component.
     PutToStorageResult {
         deploy: Box<Deploy>,
         source: Source<NodeId>,
         is_new: bool,
+        responder: Option<Responder<Result<(), Error>>>,
     },
 }
 
 impl Display for Event {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
         match self {
-            Event::Accept { deploy, source } => {
+            Event::Accept { deploy, source, .. } => {
                 write!(formatter, "accept {} from {}", deploy.id(), source)
             }
+            Event::Verify { deploy, .. } => write!(formatter, "verify {}", deploy.id()),
             Event::GetChainspecResult {
                 chainspec_version,
                 maybe_deploy_config,
@@ -49,6 +71,10 @@ impl Display for Event {
                     )
                 }
             }
+            Event::ValidationResult { deploy, result, .. } => match result {
+                Ok(()) => write!(formatter, "validated {}", deploy.id()),
+                Err(error) => write!(formatter, "invalid {}: {}", deploy.id(), error),
+            },
             Event::PutToStorageResult { deploy, is_new, .. } => {
                 if *is_new {
                     write!(formatter, "put new {} to storage", deploy.id())
@@ -11,7 +11,7 @@ use std::{
 
 use datasize::DataSize;
 use derive_more::From;
-use prometheus::{self, IntGauge, Registry};
+use prometheus::{self, IntCounter, IntGauge, Registry};
 use semver::Version;
 use tracing::{error, info, trace};
 
@@ -50,6 +50,7 @@ pub enum Event {
         chainspec_version: Version,
         current_instant: Timestamp,
         past_blocks: HashSet<ProtoBlockHash>,
+        past_deploys: HashSet<DeployHash>,
         responder: Responder<HashSet<DeployHash>>,
     },
 }
@@ -165,6 +166,7 @@ impl DeployBuffer {
         effect_builder: EffectBuilder<REv>,
         current_instant: Timestamp,
         past_blocks: HashSet<ProtoBlockHash>,
+        past_deploys: HashSet<DeployHash>,
         responder: Responder<HashSet<DeployHash>>,
     ) -> Effects<Event>
     where
@@ -182,6 +184,7 @@ impl DeployBuffer {
                         chainspec_version,
                         current_instant,
                         past_blocks,
+                        past_deploys,
                         responder,
                     })
             }
@@ -190,6 +193,7 @@ impl DeployBuffer {
                 chainspec_version,
                 current_instant,
                 past_blocks,
+                past_deploys,
                 responder,
             ),
         }
@@ -202,6 +206,7 @@ impl DeployBuffer {
         chainspec_version: Version,
         current_instant: Timestamp,
         past_blocks: HashSet<ProtoBlockHash>,
+        past_deploys: HashSet<DeployHash>,
         responder: Responder<HashSet<DeployHash>>,
     ) -> Effects<Event>
     where
@@ -214,6 +219,7 @@ impl DeployBuffer {
                 chainspec_version,
                 current_instant,
                 past_blocks,
+                past_deploys,
                 responder,
             })
     }
@@ -221,26 +227,39 @@ impl DeployBuffer {
     /// Returns a list of candidates for inclusion into a block.
     /// rename to proposed deploys
     /// maybe use cuckoofilter
+    ///
+    /// Deploys in `past_deploys` have been finalized in blocks this buffer may not know about,
+    /// e.g. blocks proposed by other validators, and are excluded as well.
+    ///
+    /// A deploy's dependencies are only considered resolved once they have been finalized, so a
+    /// deploy stays in the buffer until then, even if its dependencies are in proposed blocks.
     fn remaining_deploys(
         &mut self,
         deploy_config: DeployConfig,
         current_instant: Timestamp,
         past_blocks: HashSet<ProtoBlockHash>,
+        past_deploys: &HashSet<DeployHash>,
     ) -> HashSet<DeployHash> {
-        let past_deploys = past_blocks
+        let finalized_deploys = self
+            .finalized
+            .values()
+            .flat_map(|deploys| deploys.keys())
+            .chain(past_deploys)
+            .collect::<HashSet<_>>();
+        let proposed_deploys = past_blocks
             .iter()
             .filter_map(|block_hash| self.proposed.get(block_hash))
-            .chain(self.finalized.values())
             .flat_map(|deploys| deploys.keys())
             .collect::<HashSet<_>>();
 
-        // deploys_to_return = all deploys in pending that aren't in finalized blocks or
-        // proposed blocks from the set `past_blocks`
+        // deploys_to_return = all deploys in pending that aren't in finalized blocks, proposed
+        // blocks from the set `past_blocks` or `past_deploys`
         self.pending
             .iter()
             .filter(|&(hash, deploy)| {
-                self.is_deploy_valid(deploy, current_instant, &deploy_config, &past_deploys)
-                    && !past_deploys.contains(hash)
+                self.is_deploy_valid(deploy, current_instant, &deploy_config, &finalized_deploys)
+                    && !finalized_deploys.contains(hash)
+                    && !proposed_deploys.contains(hash)
             })
             .map(|(hash, _deploy)| *hash)
             .take(deploy_config.block_max_deploy_count as usize)
@@ -254,13 +273,13 @@ impl DeployBuffer {
         deploy: &DeployHeader,
         current_instant: Timestamp,
         deploy_config: &DeployConfig,
-        past_deploys: &HashSet<&DeployHash>,
+        finalized_deploys: &HashSet<&DeployHash>,
     ) -> bool {
         let all_deps_resolved = || {
             deploy
                 .dependencies()
                 .iter()
-                .all(|dep| past_deploys.contains(dep))
+                .all(|dep| finalized_deploys.contains(dep))
         };
         let ttl_valid = deploy.ttl() <= deploy_config.max_ttl;
         let timestamp_valid = deploy.timestamp() <= current_instant;
@@ -336,6 +355,7 @@ impl DeployBuffer {
             pruned
         }
         let collected = prune_deploys(&mut self.pending, current_instant);
+        self.metrics.expired_deploys.inc_by(collected as u64);
         let proposed = prune_blocks(&mut self.proposed, current_instant);
         let finalized = prune_blocks(&mut self.finalized, current_instant);
         collected + proposed + finalized
@@ -366,9 +386,16 @@ where
             Event::Request(DeployBufferRequest::ListForInclusion {
                 current_instant,
                 past_blocks,
+                past_deploys,
                 responder,
             }) => {
-                return self.get_chainspec(effect_builder, current_instant, past_blocks, responder);
+                return self.get_chainspec(
+                    effect_builder,
+                    current_instant,
+                    past_blocks,
+                    past_deploys,
+                    responder,
+                );
             }
             Event::Buffer { hash, header } => self.add_deploy(Timestamp::now(), hash, *header),
             Event::ProposedProtoBlock(block) => {
@@ -382,12 +409,18 @@ where
                 chainspec_version,
                 current_instant,
                 past_blocks,
+                past_deploys,
                 responder,
             } => {
                 let deploy_config = maybe_deploy_config.expect("should return chainspec");
                 // Update chainspec cache.
                 self.chainspecs.insert(chainspec_version, deploy_config);
-                let deploys = self.remaining_deploys(deploy_config, current_instant, past_blocks);
+                let deploys = self.remaining_deploys(
+                    deploy_config,
+                    current_instant,
+                    past_blocks,
+                    &past_deploys,
+                );
                 return responder.respond(deploys).ignore();
             }
         }
@@ -399,6 +432,8 @@ where
 pub struct DeployBufferMetrics {
     /// Amount of pending deploys
     pending_deploys: IntGauge,
+    /// Number of pending deploys pruned after expiring before being proposed
+    expired_deploys: IntCounter,
     /// registry Component.
     registry: Registry,
 }
@@ -406,9 +441,15 @@ pub struct DeployBufferMetrics {
 impl DeployBufferMetrics {
     pub fn new(registry: Registry) -> Result<Self, prometheus::Error> {
         let pending_deploys = IntGauge::new("pending_deploy", "amount of pending deploys")?;
+        let expired_deploys = IntCounter::new(
+            "expired_deploy",
+            "number of pending deploys pruned after expiring before being proposed",
+        )?;
         registry.register(Box::new(pending_deploys.clone()))?;
+        registry.register(Box::new(expired_deploys.clone()))?;
         Ok(DeployBufferMetrics {
             pending_deploys,
+            expired_deploys,
             registry,
         })
     }
@@ -419,6 +460,9 @@ impl Drop for DeployBufferMetrics {
         self.registry
             .unregister(Box::new(self.pending_deploys.clone()))
             .expect("did not expect deregistering pending_deploys to fail");
+        self.registry
+            .unregister(Box::new(self.expired_deploys.clone()))
+            .expect("did not expect deregistering expired_deploys to fail");
     }
 }
 
@@ -482,9 +526,7 @@ mod tests {
 
     impl From<StorageRequest<Storage>> for Event {
         fn from(_: StorageRequest<Storage>) -> Self {
-            // we never send a storage request in our unit tests, but if this does become
-            // meaningful....
-            todo!()
+            unreachable!("the deploy buffer's unit tests never send a storage request")
         }
     }
 
@@ -505,7 +547,12 @@ mod tests {
         let (hash4, deploy4) = generate_deploy(&mut rng, creation_time, ttl, vec![]);
 
         assert!(buffer
-            .remaining_deploys(DeployConfig::default(), block_time2, no_blocks.clone())
+            .remaining_deploys(
+                DeployConfig::default(),
+                block_time2,
+                no_blocks.clone(),
+                &HashSet::new(),
+            )
             .is_empty());
 
         // add two deploys
@@ -515,18 +562,32 @@ mod tests {
         // if we try to create a block with a timestamp that is too early, we shouldn't get any
         // deploys
         assert!(buffer
-            .remaining_deploys(DeployConfig::default(), block_time1, no_blocks.clone())
+            .remaining_deploys(
+                DeployConfig::default(),
+                block_time1,
+                no_blocks.clone(),
+                &HashSet::new(),
+            )
             .is_empty());
 
         // if we try to create a block with a timestamp that is too late, we shouldn't get any
         // deploys, either
         assert!(buffer
-            .remaining_deploys(DeployConfig::default(), block_time3, no_blocks.clone())
+            .remaining_deploys(
+                DeployConfig::default(),
+                block_time3,
+                no_blocks.clone(),
+                &HashSet::new(),
+            )
             .is_empty());
 
         // take the deploys out
-        let deploys =
-            buffer.remaining_deploys(DeployConfig::default(), block_time2, no_blocks.clone());
+        let deploys = buffer.remaining_deploys(
+            DeployConfig::default(),
+            block_time2,
+            no_blocks.clone(),
+            &HashSet::new(),
+        );
 
         assert_eq!(deploys.len(), 2);
         assert!(deploys.contains(&hash1));
@@ -534,7 +595,12 @@ mod tests {
 
         // the deploys should not have been removed yet
         assert!(!buffer
-            .remaining_deploys(DeployConfig::default(), block_time2, no_blocks.clone())
+            .remaining_deploys(
+                DeployConfig::default(),
+                block_time2,
+                no_blocks.clone(),
+                &HashSet::new(),
+            )
             .is_empty());
 
         // the two deploys will be included in block 1
@@ -543,14 +609,24 @@ mod tests {
 
         // the deploys should have been removed now
         assert!(buffer
-            .remaining_deploys(DeployConfig::default(), block_time2, no_blocks.clone())
+            .remaining_deploys(
+                DeployConfig::default(),
+                block_time2,
+                no_blocks.clone(),
+                &HashSet::new(),
+            )
             .is_empty());
 
         let mut blocks = HashSet::new();
         blocks.insert(block_hash1);
 
         assert!(buffer
-            .remaining_deploys(DeployConfig::default(), block_time2, blocks.clone())
+            .remaining_deploys(
+                DeployConfig::default(),
+                block_time2,
+                blocks.clone(),
+                &HashSet::new(),
+            )
             .is_empty());
 
         // try adding the same deploy again
@@ -558,12 +634,22 @@ mod tests {
 
         // it shouldn't be returned if we include block 1 in the past blocks
         assert!(buffer
-            .remaining_deploys(DeployConfig::default(), block_time2, blocks)
+            .remaining_deploys(
+                DeployConfig::default(),
+                block_time2,
+                blocks,
+                &HashSet::new(),
+            )
             .is_empty());
         // ...but it should be returned if we don't include it
         assert!(
             buffer
-                .remaining_deploys(DeployConfig::default(), block_time2, no_blocks.clone())
+                .remaining_deploys(
+                    DeployConfig::default(),
+                    block_time2,
+                    no_blocks.clone(),
+                    &HashSet::new(),
+                )
                 .len()
                 == 1
         );
@@ -578,7 +664,12 @@ mod tests {
         buffer.add_deploy(block_time2, hash3, deploy3);
         buffer.add_deploy(block_time2, hash4, deploy4);
 
-        let deploys = buffer.remaining_deploys(DeployConfig::default(), block_time2, no_blocks);
+        let deploys = buffer.remaining_deploys(
+            DeployConfig::default(),
+            block_time2,
+            no_blocks,
+            &HashSet::new(),
+        );
 
         // since block 1 is now finalized, deploy2 shouldn't be among the ones returned
         assert_eq!(deploys.len(), 2);
@@ -662,13 +753,23 @@ mod tests {
 
         // deploy2 has an unsatisfied dependency
         assert!(buffer
-            .remaining_deploys(DeployConfig::default(), block_time, blocks.clone())
+            .remaining_deploys(
+                DeployConfig::default(),
+                block_time,
+                blocks.clone(),
+                &HashSet::new(),
+            )
             .is_empty());
 
         // add deploy1
         buffer.add_deploy(creation_time, hash1, deploy1);
 
-        let deploys = buffer.remaining_deploys(DeployConfig::default(), block_time, blocks.clone());
+        let deploys = buffer.remaining_deploys(
+            DeployConfig::default(),
+            block_time,
+            blocks.clone(),
+            &HashSet::new(),
+        );
         // only deploy1 should be returned, as it has no dependencies
         assert_eq!(deploys.len(), 1);
         assert!(deploys.contains(&hash1));
@@ -678,9 +779,100 @@ mod tests {
         buffer.added_block(block_hash1, deploys);
         blocks.insert(block_hash1);
 
-        let deploys2 = buffer.remaining_deploys(DeployConfig::default(), block_time, blocks);
-        // `blocks` contains a block that contains deploy1 now, so we should get deploy2
+        // `blocks` contains a block that contains deploy1 now, but it hasn't been finalized yet, so
+        // deploy2 should stay in the buffer
+        assert!(buffer
+            .remaining_deploys(
+                DeployConfig::default(),
+                block_time,
+                blocks.clone(),
+                &HashSet::new(),
+            )
+            .is_empty());
+        assert!(buffer.pending.contains_key(&hash2));
+
+        // once block 1 is finalized, deploy2 can be included in block 2
+        buffer.finalized_block(block_hash1);
+        let deploys2 =
+            buffer.remaining_deploys(DeployConfig::default(), block_time, blocks, &HashSet::new());
         assert_eq!(deploys2.len(), 1);
         assert!(deploys2.contains(&hash2));
     }
+
+    #[test]
+    fn should_exclude_expired_deploys_and_count_them_when_pruned() {
+        let creation_time = Timestamp::from(100);
+        let block_time = Timestamp::from(150);
+        let expired_time = Timestamp::from(201);
+
+        let mut rng = TestRng::new();
+        let (hash1, deploy1) = generate_deploy(&mut rng, creation_time, TimeDiff::from(20), vec![]);
+        let (hash2, deploy2) =
+            generate_deploy(&mut rng, creation_time, TimeDiff::from(100), vec![]);
+
+        let (mut buffer, _effects) = create_test_buffer();
+        buffer.add_deploy(creation_time, hash1, deploy1);
+        buffer.add_deploy(creation_time, hash2, deploy2);
+
+        // deploy1 has expired by the block timestamp, so only deploy2 is eligible
+        let deploys = buffer.remaining_deploys(
+            DeployConfig::default(),
+            block_time,
+            HashSet::new(),
+            &HashSet::new(),
+        );
+        assert_eq!(deploys.len(), 1);
+        assert!(deploys.contains(&hash2));
+
+        // deploy1 stays buffered until pruned
+        assert_eq!(buffer.pending.len(), 2);
+        assert_eq!(buffer.prune(block_time), 1);
+        assert_eq!(buffer.metrics.expired_deploys.get(), 1);
+        assert!(!buffer.pending.contains_key(&hash1));
+
+        assert_eq!(buffer.prune(expired_time), 1);
+        assert_eq!(buffer.metrics.expired_deploys.get(), 2);
+        assert!(buffer.pending.is_empty());
+    }
+
+    #[test]
+    fn should_exclude_past_deploys_and_cap_count() {
+        let creation_time = Timestamp::from(100);
+        let ttl = TimeDiff::from(100);
+        let block_time = Timestamp::from(120);
+
+        let mut rng = TestRng::new();
+        let (hash1, deploy1) = generate_deploy(&mut rng, creation_time, ttl, vec![]);
+        let (hash2, deploy2) = generate_deploy(&mut rng, creation_time, ttl, vec![]);
+        let (hash3, deploy3) = generate_deploy(&mut rng, creation_time, ttl, vec![]);
+
+        let (mut buffer, _effects) = create_test_buffer();
+        buffer.add_deploy(creation_time, hash1, deploy1);
+        buffer.add_deploy(creation_time, hash2, deploy2);
+        buffer.add_deploy(creation_time, hash3, deploy3);
+
+        // deploy1 was finalized in a block proposed by another validator, which this buffer never
+        // saw proposed
+        let mut past_deploys = HashSet::new();
+        past_deploys.insert(hash1);
+
+        let deploys = buffer.remaining_deploys(
+            DeployConfig::default(),
+            block_time,
+            HashSet::new(),
+            &past_deploys,
+        );
+        assert_eq!(deploys.len(), 2);
+        assert!(!deploys.contains(&hash1));
+
+        // the number of deploys is capped by the chainspec
+        let deploy_config = DeployConfig {
+            block_max_deploy_count: 1,
+            ..DeployConfig::default()
+        };
+        let deploys =
+            buffer.remaining_deploys(deploy_config, block_time, HashSet::new(), &past_deploys);
+        assert_eq!(deploys.len(), 1);
+        assert!(!deploys.contains(&hash1));
+    }
 }
@@ -1,4 +1,5 @@
 mod event;
+mod in_flight;
 mod tests;
 
 use std::{collections::HashMap, fmt::Debug, time::Duration};
@@ -15,12 +16,16 @@ use crate::{
     },
     protocol::Message,
     small_network::NodeId,
-    types::{Block, BlockByHeight, BlockHash, CryptoRngCore, Deploy, DeployHash, Item},
+    types::{
+        Block, BlockByHeight, BlockHash, BlockRange, BlockRangeId, CryptoRngCore, Deploy,
+        DeployHash, Item,
+    },
     utils::Source,
     GossipConfig,
 };
 
 pub use event::{Event, FetchResult};
+use in_flight::InFlightRequests;
 
 /// A helper trait constraining `Fetcher` compatible reactor events.
 pub trait ReactorEventT<T>:
@@ -55,6 +60,9 @@ pub trait ItemFetcher<T: Item + 'static> {
 
     fn peer_timeout(&self) -> Duration;
 
+    /// Requests for items which are in flight.
+    fn in_flight(&mut self) -> &mut InFlightRequests<T::Id>;
+
     /// We've been asked to fetch the item by another component of this node.  We'll try to get it
     /// from our own storage component first, and if that fails, we'll send a request to `peer` for
     /// the item.
@@ -104,6 +112,27 @@ pub trait ItemFetcher<T: Item + 'static> {
         id: T::Id,
         peer: NodeId,
     ) -> Effects<Event<T>> {
+        self.get_from_peer(effect_builder, id, peer)
+    }
+
+    /// Sends a request for the item to `peer`, unless a request for it is already in flight, and
+    /// sets a timeout to check we got the item.
+    ///
+    /// If a request is already in flight, e.g. as the item was fetched from a different peer too,
+    /// we await its outcome rather than request the item twice.
+    fn get_from_peer<REv: ReactorEventT<T>>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        id: T::Id,
+        peer: NodeId,
+    ) -> Effects<Event<T>> {
+        if !self.in_flight().start(id, peer) {
+            debug!(%id, %peer, "request for item already in flight");
+            return effect_builder
+                .set_timeout(self.peer_timeout())
+                .event(move |_| Event::TimeoutPeer { id, peer });
+        }
+
         match Message::new_get_request::<T>(&id) {
             Ok(message) => {
                 let mut effects = effect_builder.send_message(peer, message).ignore();
@@ -118,11 +147,38 @@ pub trait ItemFetcher<T: Item + 'static> {
             }
             Err(error) => {
                 error!("failed to construct get request: {}", error);
+                self.in_flight().fail(&id, peer);
                 self.signal(id, None, peer)
             }
         }
     }
 
+    /// Handles the timeout for getting the item from `peer`.
+    ///
+    /// If our request to `peer` failed, the responders waiting on it are signalled.  If instead we
+    /// never sent one since another request was in flight, we now ask `peer`, or, if the other
+    /// request is still in flight, keep waiting until the next timeout.
+    fn timed_out<REv: ReactorEventT<T>>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        id: T::Id,
+        peer: NodeId,
+    ) -> Effects<Event<T>> {
+        if self.in_flight().fail(&id, peer) {
+            return self.signal(id, None, peer);
+        }
+
+        let is_awaited = self
+            .responders()
+            .get(&id)
+            .map_or(false, |responders| responders.contains_key(&peer));
+        if is_awaited {
+            self.get_from_peer(effect_builder, id, peer)
+        } else {
+            Effects::new()
+        }
+    }
+
     /// Handles signalling responders with the item or `None`.
     fn signal(
         &mut self,
@@ -134,6 +190,7 @@ pub trait ItemFetcher<T: Item + 'static> {
         let mut all_responders = self.responders().remove(&id).unwrap_or_default();
         match result {
             Some(ret) => {
+                self.in_flight().finish(&id);
                 // signal all responders waiting for this item
                 for (_, responders) in all_responders {
                     for responder in responders {
@@ -165,6 +222,8 @@ where
 {
     get_from_peer_timeout: Duration,
     responders: HashMap<T::Id, HashMap<NodeId, Vec<FetchResponder<T>>>>,
+    #[data_size(skip)]
+    in_flight: InFlightRequests<T::Id>,
 }
 
 impl<T: Item> Fetcher<T> {
@@ -172,6 +231,7 @@ impl<T: Item> Fetcher<T> {
         Fetcher {
             get_from_peer_timeout: Duration::from_secs(config.get_remainder_timeout_secs()),
             responders: HashMap::new(),
+            in_flight: InFlightRequests::new(config),
         }
     }
 }
@@ -187,6 +247,10 @@ impl ItemFetcher<Deploy> for Fetcher<Deploy> {
         self.get_from_peer_timeout
     }
 
+    fn in_flight(&mut self) -> &mut InFlightRequests<DeployHash> {
+        &mut self.in_flight
+    }
+
     /// Gets a `Deploy` from the storage component.
     fn get_from_storage<REv: ReactorEventT<Deploy>>(
         &mut self,
@@ -215,6 +279,10 @@ impl ItemFetcher<Block> for Fetcher<Block> {
         self.get_from_peer_timeout
     }
 
+    fn in_flight(&mut self) -> &mut InFlightRequests<BlockHash> {
+        &mut self.in_flight
+    }
+
     fn get_from_storage<REv: ReactorEventT<Block>>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -242,6 +310,10 @@ impl ItemFetcher<BlockByHeight> for Fetcher<BlockByHeight> {
         self.get_from_peer_timeout
     }
 
+    fn in_flight(&mut self) -> &mut InFlightRequests<u64> {
+        &mut self.in_flight
+    }
+
     fn get_from_storage<REv: ReactorEventT<BlockByHeight>>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -258,6 +330,44 @@ impl ItemFetcher<BlockByHeight> for Fetcher<BlockByHeight> {
     }
 }
 
+impl ItemFetcher<BlockRange> for Fetcher<BlockRange> {
+    fn responders(
+        &mut self,
+    ) -> &mut HashMap<BlockRangeId, HashMap<NodeId, Vec<FetchResponder<BlockRange>>>> {
+        &mut self.responders
+    }
+
+    fn peer_timeout(&self) -> Duration {
+        self.get_from_peer_timeout
+    }
+
+    fn in_flight(&mut self) -> &mut InFlightRequests<BlockRangeId> {
+        &mut self.in_flight
+    }
+
+    /// Gets the run of blocks from the storage component.  Any blocks we already hold are returned
+    /// as they are, even if the run is shorter than requested.
+    fn get_from_storage<REv: ReactorEventT<BlockRange>>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        id: BlockRangeId,
+        peer: NodeId,
+    ) -> Effects<Event<BlockRange>> {
+        let capped_id = id.capped();
+        effect_builder
+            .get_block_range(capped_id.start_height(), capped_id.count())
+            .event(move |blocks| Event::GetFromStorageResult {
+                id,
+                peer,
+                maybe_item: Box::new(if blocks.is_empty() {
+                    None
+                } else {
+                    Some(BlockRange::new(id, blocks))
+                }),
+            })
+    }
+}
+
 impl<T, REv> Component<REv> for Fetcher<T>
 where
     Fetcher<T>: ItemFetcher<T>,
@@ -298,8 +408,11 @@ where
                     }
                 }
             }
-            Event::AbsentRemotely { id, peer } => self.signal(id, None, peer),
-            Event::TimeoutPeer { id, peer } => self.signal(id, None, peer),
+            Event::AbsentRemotely { id, peer } => {
+                self.in_flight().fail(&id, peer);
+                self.signal(id, None, peer)
+            }
+            Event::TimeoutPeer { id, peer } => self.timed_out(effect_builder, id, peer),
         }
     }
 }
@@ -0,0 +1,306 @@
+//! Record of requests for items which have been sent to peers and not yet answered.
+//!
+//! The fetcher may be asked for the same item several times at once, e.g. by the gossiper getting
+//! the remainder of a deploy while a block referencing the deploy is being validated.  It keeps an
+//! `InFlightRequests` so that only one request for the item is sent at a time, while the other
+//! requesters await the outcome: the arrival of the item, or the failure of the request in which
+//! case they may ask another peer.
+
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    time::{Duration, Instant},
+};
+
+use crate::{components::small_network::NodeId, GossipConfig};
+
+/// Maximum number of requests tracked at once.  If exceeded, further requests are sent without
+/// being tracked, i.e. without deduplication.
+const MAX_IN_FLIGHT_REQUESTS: usize = 10_000;
+
+/// A request for an item which has been sent to a peer.
+#[derive(Debug)]
+struct InFlightRequest {
+    /// The peer the request was sent to.
+    peer: NodeId,
+    /// The moment after which the request is considered failed, even if no failure was reported.
+    deadline: Instant,
+}
+
+/// Requests for items of a single type which are in flight.
+#[derive(Debug)]
+pub struct InFlightRequests<I> {
+    requests: HashMap<I, InFlightRequest>,
+    capacity: usize,
+    timeout: Duration,
+}
+
+impl<I: Copy + Eq + Hash> InFlightRequests<I> {
+    /// Creates a new record, with requests considered failed once the config's timeout for getting
+    /// an item from a peer has elapsed.
+    pub(crate) fn new(config: GossipConfig) -> Self {
+        Self::with_capacity(
+            MAX_IN_FLIGHT_REQUESTS,
+            Duration::from_secs(config.get_remainder_timeout_secs()),
+        )
+    }
+
+    fn with_capacity(capacity: usize, timeout: Duration) -> Self {
+        InFlightRequests {
+            requests: HashMap::new(),
+            capacity,
+            timeout,
+        }
+    }
+
+    /// Records that a request for `id` is about to be sent to `peer`.
+    ///
+    /// Returns `false` if a request for `id` is already in flight, in which case the caller should
+    /// not send another one, but await the outcome of the existing one.
+    pub(crate) fn start(&mut self, id: I, peer: NodeId) -> bool {
+        let now = Instant::now();
+        if let Some(request) = self.requests.get(&id) {
+            if request.deadline > now {
+                return false;
+            }
+        }
+
+        if self.requests.len() >= self.capacity && !self.requests.contains_key(&id) {
+            self.requests.retain(|_, request| request.deadline > now);
+            if self.requests.len() >= self.capacity {
+                // Still full: let the request go out untracked.
+                return true;
+            }
+        }
+
+        let deadline = now + self.timeout;
+        let _ = self.requests.insert(id, InFlightRequest { peer, deadline });
+        true
+    }
+
+    /// Records that the item `id` has arrived, so that it is no longer in flight.
+    pub(crate) fn finish(&mut self, id: &I) {
+        let _ = self.requests.remove(id);
+    }
+
+    /// Records that the request for `id` sent to `peer` failed, so that the next requester may ask
+    /// a different peer.
+    ///
+    /// Returns `false` if the request in flight for `id`, if any, was not sent to `peer`.
+    pub(crate) fn fail(&mut self, id: &I, peer: NodeId) -> bool {
+        match self.requests.get(id) {
+            Some(request) if request.peer == peer => {
+                let _ = self.requests.remove(id);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use derive_more::From;
+    use rand::Rng;
+    use tokio::time;
+
+    use super::*;
+    use crate::{
+        components::{
+            fetcher::{self, Fetcher},
+            storage::Storage,
+            Component,
+        },
+        effect::{
+            requests::{FetcherRequest, LinearChainRequest, NetworkRequest, StorageRequest},
+            EffectBuilder, EffectExt, Effects,
+        },
+        protocol::Message,
+        reactor::{self, EventQueueHandle, QueueKind, Scheduler},
+        testing::TestRng,
+        types::{Deploy, DeployHash},
+        utils,
+    };
+
+    /// How long the fetcher is driven for after the last event was handled.  Much shorter than the
+    /// timeout for getting an item from a peer, so that such timeouts never elapse.
+    const IDLE_TIMEOUT: Duration = Duration::from_millis(100);
+
+    #[test]
+    fn should_only_allow_one_request_per_item() {
+        let mut rng = TestRng::new();
+        let mut in_flight = InFlightRequests::<u64>::new(GossipConfig::default());
+        let (peer_1, peer_2) = (rng.gen::<NodeId>(), rng.gen::<NodeId>());
+
+        assert!(in_flight.start(1, peer_1));
+        assert!(!in_flight.start(1, peer_2));
+        assert!(in_flight.start(2, peer_2));
+
+        // A failure reported for a different peer doesn't free the item.
+        assert!(!in_flight.fail(&1, peer_2));
+        assert!(!in_flight.start(1, peer_2));
+
+        // Once the request failed, the next peer can be asked.
+        assert!(in_flight.fail(&1, peer_1));
+        assert!(in_flight.start(1, peer_2));
+
+        // Once the item arrived, it can be requested again.
+        in_flight.finish(&2);
+        assert!(in_flight.start(2, peer_1));
+    }
+
+    #[test]
+    fn should_treat_expired_requests_as_failed() {
+        let mut rng = TestRng::new();
+        let mut in_flight = InFlightRequests::<u64>::with_capacity(10, Duration::from_secs(0));
+        let (peer_1, peer_2) = (rng.gen::<NodeId>(), rng.gen::<NodeId>());
+
+        assert!(in_flight.start(1, peer_1));
+        assert!(in_flight.start(1, peer_2));
+    }
+
+    #[test]
+    fn should_be_bounded() {
+        let mut rng = TestRng::new();
+        let mut in_flight = InFlightRequests::<u64>::with_capacity(2, Duration::from_secs(60));
+        let peer = rng.gen::<NodeId>();
+
+        assert!(in_flight.start(1, peer));
+        assert!(in_flight.start(2, peer));
+        // Exceeding the capacity lets requests through untracked.
+        assert!(in_flight.start(3, peer));
+        assert!(in_flight.start(3, peer));
+        assert_eq!(in_flight.requests.len(), 2);
+    }
+
+    /// A reactor event type the deploy fetcher can be driven with.
+    #[derive(Debug, From)]
+    enum ReactorEvent {
+        Fetcher(fetcher::Event<Deploy>),
+        FetcherRequest(FetcherRequest<NodeId, Deploy>),
+        NetworkRequest(NetworkRequest<NodeId, Message>),
+        StorageRequest(StorageRequest<Storage>),
+        LinearChainRequest(LinearChainRequest<NodeId>),
+    }
+
+    /// Spawns the effects, queueing the events they produce.
+    fn process_effects(
+        scheduler: &'static Scheduler<ReactorEvent>,
+        effects: Effects<ReactorEvent>,
+    ) {
+        for effect in effects {
+            tokio::spawn(async move {
+                for event in effect.await {
+                    scheduler.push(event, QueueKind::Regular).await
+                }
+            });
+        }
+    }
+
+    /// Drives the fetcher until no more events are queued, with storage holding no deploys.
+    ///
+    /// Returns the number of `GetRequest`s sent to peers.
+    async fn run_fetcher(
+        fetcher: &mut Fetcher<Deploy>,
+        scheduler: &'static Scheduler<ReactorEvent>,
+        rng: &mut TestRng,
+    ) -> usize {
+        let effect_builder = EffectBuilder::new(EventQueueHandle::new(scheduler));
+        let mut count = 0;
+        while let Ok((event, _)) = time::timeout(IDLE_TIMEOUT, scheduler.pop()).await {
+            let effects = match event {
+                ReactorEvent::Fetcher(event) => reactor::wrap_effects(
+                    ReactorEvent::Fetcher,
+                    fetcher.handle_event(effect_builder, rng, event),
+                ),
+                ReactorEvent::FetcherRequest(request) => reactor::wrap_effects(
+                    ReactorEvent::Fetcher,
+                    fetcher.handle_event(effect_builder, rng, request.into()),
+                ),
+                ReactorEvent::StorageRequest(StorageRequest::GetDeploys {
+                    deploy_hashes,
+                    responder,
+                }) => responder
+                    .respond(deploy_hashes.iter().map(|_| None).collect())
+                    .ignore(),
+                ReactorEvent::NetworkRequest(NetworkRequest::SendMessage {
+                    payload: Message::GetRequest { .. },
+                    ..
+                }) => {
+                    count += 1;
+                    Effects::new()
+                }
+                event => panic!("should not get {:?}", event),
+            };
+            process_effects(scheduler, effects);
+        }
+        count
+    }
+
+    /// Has a component ask the fetcher for the deploy from `peer`.
+    fn fetch_deploy(
+        scheduler: &'static Scheduler<ReactorEvent>,
+        deploy_hash: DeployHash,
+        peer: NodeId,
+    ) {
+        let effect_builder = EffectBuilder::new(EventQueueHandle::new(scheduler));
+        let _ = tokio::spawn(effect_builder.fetch_deploy(deploy_hash, peer));
+    }
+
+    #[tokio::test]
+    async fn should_request_deploy_once_when_fetched_from_two_peers() {
+        let mut rng = TestRng::new();
+        let scheduler = utils::leak(Scheduler::<ReactorEvent>::new(QueueKind::weights()));
+        let mut fetcher = Fetcher::<Deploy>::new(GossipConfig::default());
+
+        // E.g. the gossiper getting the remainder of the deploy from one of its holders, while a
+        // block referencing the deploy is validated against its proposer.
+        let deploy_hash = *Deploy::random(&mut rng).id();
+        let (holder, proposer) = (rng.gen::<NodeId>(), rng.gen::<NodeId>());
+        fetch_deploy(scheduler, deploy_hash, holder);
+        fetch_deploy(scheduler, deploy_hash, proposer);
+
+        assert_eq!(run_fetcher(&mut fetcher, scheduler, &mut rng).await, 1);
+    }
+
+    #[tokio::test]
+    async fn should_ask_next_peer_once_request_failed() {
+        let mut rng = TestRng::new();
+        let scheduler = utils::leak(Scheduler::<ReactorEvent>::new(QueueKind::weights()));
+        let mut fetcher = Fetcher::<Deploy>::new(GossipConfig::default());
+
+        let deploy_hash = *Deploy::random(&mut rng).id();
+        let (peer_1, peer_2) = (rng.gen::<NodeId>(), rng.gen::<NodeId>());
+        fetch_deploy(scheduler, deploy_hash, peer_1);
+        fetch_deploy(scheduler, deploy_hash, peer_2);
+        assert_eq!(run_fetcher(&mut fetcher, scheduler, &mut rng).await, 1);
+
+        // While the request to `peer_1` is in flight, the timeout for `peer_2` doesn't lead to a
+        // second request.
+        let timeout_peer_2 = fetcher::Event::TimeoutPeer {
+            id: deploy_hash,
+            peer: peer_2,
+        };
+        scheduler
+            .push(timeout_peer_2.into(), QueueKind::Regular)
+            .await;
+        assert_eq!(run_fetcher(&mut fetcher, scheduler, &mut rng).await, 0);
+
+        // Once the request to `peer_1` failed, `peer_2` is asked when its timeout elapses.
+        let absent_from_peer_1 = fetcher::Event::AbsentRemotely {
+            id: deploy_hash,
+            peer: peer_1,
+        };
+        scheduler
+            .push(absent_from_peer_1.into(), QueueKind::Regular)
+            .await;
+        let timeout_peer_2 = fetcher::Event::TimeoutPeer {
+            id: deploy_hash,
+            peer: peer_2,
+        };
+        scheduler
+            .push(timeout_peer_2.into(), QueueKind::Regular)
+            .await;
+        assert_eq!(run_fetcher(&mut fetcher, scheduler, &mut rng).await, 1);
+    }
+}
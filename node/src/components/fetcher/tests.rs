@@ -15,6 +15,7 @@ use super::*;
 use crate::{
     components::{
         chainspec_loader::Chainspec,
+        contract_runtime,
         deploy_acceptor::{self, DeployAcceptor},
         in_memory_network::{InMemoryNetwork, NetworkController, NodeId},
         storage::{self, Storage, StorageType},
@@ -113,16 +114,20 @@ impl reactor::Reactor for Reactor {
 
     fn new(
         config: Self::Config,
-        _registry: &Registry,
+        registry: &Registry,
         event_queue: EventQueueHandle<Self::Event>,
         rng: &mut dyn CryptoRngCore,
     ) -> Result<(Self, Effects<Self::Event>), Self::Error> {
         let network = NetworkController::create_node(event_queue, rng);
 
         let (storage_config, _storage_tempdir) = storage::Config::default_for_tests();
-        let storage = Storage::new(WithDir::new(_storage_tempdir.path(), storage_config)).unwrap();
+        let storage = Storage::new(
+            WithDir::new(_storage_tempdir.path(), storage_config),
+            registry,
+        )
+        .unwrap();
 
-        let deploy_acceptor = DeployAcceptor::new();
+        let deploy_acceptor = DeployAcceptor::new(&contract_runtime::Config::default());
         let deploy_fetcher = Fetcher::<Deploy>::new(config);
 
         let reactor = Reactor {
@@ -212,6 +217,7 @@ impl reactor::Reactor for Reactor {
                         Event::DeployAcceptor(deploy_acceptor::Event::Accept {
                             deploy,
                             source: Source::Peer(sender),
+                            responder: None,
                         })
                     }
                     msg => panic!("should not get {}", msg),
@@ -221,13 +227,24 @@ impl reactor::Reactor for Reactor {
             Event::NetworkAnnouncement(ann) => {
                 unreachable!("should not receive announcements of type {:?}", ann);
             }
-            Event::ApiServerAnnouncement(ApiServerAnnouncement::DeployReceived { deploy }) => {
+            Event::ApiServerAnnouncement(ApiServerAnnouncement::DeployReceived {
+                deploy,
+                responder,
+            }) => {
                 let event = deploy_acceptor::Event::Accept {
                     deploy,
                     source: Source::<NodeId>::Client,
+                    responder,
                 };
                 self.dispatch_event(effect_builder, rng, Event::DeployAcceptor(event))
             }
+            Event::ApiServerAnnouncement(ApiServerAnnouncement::DryRunDeployReceived {
+                deploy,
+                responder,
+            }) => {
+                let event = deploy_acceptor::Event::Verify { deploy, responder };
+                self.dispatch_event(effect_builder, rng, Event::DeployAcceptor(event))
+            }
             Event::DeployAcceptorAnnouncement(DeployAcceptorAnnouncement::AcceptedNewDeploy {
                 deploy,
                 source,
@@ -258,7 +275,7 @@ impl NetworkedReactor for Reactor {
 fn announce_deploy_received(deploy: Deploy) -> impl FnOnce(EffectBuilder<Event>) -> Effects<Event> {
     |effect_builder: EffectBuilder<Event>| {
         effect_builder
-            .announce_deploy_received(Box::new(deploy))
+            .announce_deploy_received(Box::new(deploy), None)
             .ignore()
     }
 }
@@ -10,25 +10,30 @@ use datasize::DataSize;
 use futures::FutureExt;
 use prometheus::Registry;
 use smallvec::smallvec;
+#[cfg(not(test))]
+use std::time::Instant;
 use std::{
     collections::HashSet,
     fmt::{self, Debug, Formatter},
     time::Duration,
 };
+
+#[cfg(test)]
+use fake_instant::FakeClock as Instant;
 use tracing::{debug, error};
 
 use crate::{
     components::{small_network::NodeId, storage::Storage, Component},
     effect::{
         announcements::GossiperAnnouncement,
-        requests::{NetworkRequest, StorageRequest},
+        requests::{FetcherRequest, NetworkInfoRequest, NetworkRequest, StorageRequest},
         EffectBuilder, EffectExt, Effects,
     },
     protocol::Message as NodeMessage,
     types::{CryptoRngCore, Deploy, DeployHash, Item},
     utils::Source,
 };
-pub use config::Config;
+pub use config::{Config, PerItemConfig};
 pub use error::Error;
 pub use event::Event;
 use gossip_table::{GossipAction, GossipTable};
@@ -41,6 +46,7 @@ pub trait ReactorEventT<T>:
     From<Event<T>>
     + From<NetworkRequest<NodeId, Message<T>>>
     + From<NetworkRequest<NodeId, NodeMessage>>
+    + From<NetworkInfoRequest<NodeId>>
     + From<StorageRequest<Storage>>
     + From<GossiperAnnouncement<T>>
     + Send
@@ -58,6 +64,7 @@ where
     REv: From<Event<T>>
         + From<NetworkRequest<NodeId, Message<T>>>
         + From<NetworkRequest<NodeId, NodeMessage>>
+        + From<NetworkInfoRequest<NodeId>>
         + From<StorageRequest<Storage>>
         + From<GossiperAnnouncement<T>>
         + Send
@@ -91,6 +98,28 @@ pub(crate) fn get_deploy_from_storage<T: Item + 'static, REv: ReactorEventT<T>>(
         })
 }
 
+/// This function can be passed in to `Gossiper::new_for_partial_items()` as the `get_remainder`
+/// arg when constructing a `Gossiper<Deploy>`.
+///
+/// The deploy is fetched via the deploy fetcher, so that it isn't requested twice if the fetcher is
+/// already getting it for a different component.  Once the fetch has completed, the gossiper checks
+/// whether the deploy arrived.
+pub(crate) fn fetch_deploy_from_holder<REv>(
+    effect_builder: EffectBuilder<REv>,
+    deploy_hash: DeployHash,
+    holder: NodeId,
+) -> Effects<Event<Deploy>>
+where
+    REv: ReactorEventT<Deploy> + From<FetcherRequest<NodeId, Deploy>>,
+{
+    effect_builder
+        .fetch_deploy(deploy_hash, holder)
+        .event(move |_| Event::CheckGetFromPeerTimeout {
+            item_id: deploy_hash,
+            peer: holder,
+        })
+}
+
 /// The component which gossips to peers and handles incoming gossip messages from peers.
 #[allow(clippy::type_complexity)]
 #[derive(DataSize)]
@@ -102,9 +131,15 @@ where
     table: GossipTable<T::Id>,
     gossip_timeout: Duration,
     get_from_peer_timeout: Duration,
+    item_ttl: Duration,
+    /// Whether an `Event::ExpireItems` is currently scheduled.
+    expiry_scheduled: bool,
     #[data_size(skip)] // Not well supported by datasize.
     get_from_holder:
         Box<dyn Fn(EffectBuilder<REv>, T::Id, NodeId) -> Effects<Event<T>> + Send + 'static>,
+    #[data_size(skip)] // Not well supported by datasize.
+    get_remainder:
+        Box<dyn Fn(EffectBuilder<REv>, T::Id, NodeId) -> Effects<Event<T>> + Send + 'static>,
     #[data_size(skip)]
     metrics: GossiperMetrics,
 }
@@ -120,6 +155,13 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
     /// For an example of how `get_from_holder` should be implemented, see
     /// `gossiper::get_deploy_from_store()` which is used by `Gossiper<Deploy>`.
     ///
+    /// `get_remainder` is called by the gossiper when a holder failed to provide the full item, in
+    /// order to get it from a different holder.  It must eventually produce an
+    /// `Event::CheckGetFromPeerTimeout` for that holder.
+    ///
+    /// For an example of how `get_remainder` should be implemented, see
+    /// `gossiper::fetch_deploy_from_holder()` which is used by `Gossiper<Deploy>`.
+    ///
     /// Must be supplied with a name, which should be a snake-case identifier to disambiguate the
     /// specific gossiper from other potentially present gossipers.
     pub(crate) fn new_for_partial_items(
@@ -128,6 +170,7 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
         get_from_holder: impl Fn(EffectBuilder<REv>, T::Id, NodeId) -> Effects<Event<T>>
             + Send
             + 'static,
+        get_remainder: impl Fn(EffectBuilder<REv>, T::Id, NodeId) -> Effects<Event<T>> + Send + 'static,
         registry: &Registry,
     ) -> Result<Self, prometheus::Error> {
         assert!(
@@ -138,7 +181,10 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
             table: GossipTable::new(config),
             gossip_timeout: Duration::from_secs(config.gossip_request_timeout_secs()),
             get_from_peer_timeout: Duration::from_secs(config.get_remainder_timeout_secs()),
+            item_ttl: Duration::from_secs(config.item_ttl_secs()),
+            expiry_scheduled: false,
             get_from_holder: Box::new(get_from_holder),
+            get_remainder: Box::new(get_remainder),
             metrics: GossiperMetrics::new(name, registry)?,
         })
     }
@@ -146,6 +192,9 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
     /// Constructs a new gossiper component for use where `T::ID_IS_COMPLETE_ITEM == true`, i.e.
     /// where the gossip messages themselves contain the actual data being gossiped.
     ///
+    /// Items received via gossip are announced, but not gossiped onwards until the reactor has
+    /// verified them and passed them back in via `Event::ItemReceived`.
+    ///
     /// Must be supplied with a name, which should be a snake-case identifier to disambiguate the
     /// specific gossiper from other potentially present gossipers.
     pub(crate) fn new_for_complete_items(
@@ -161,9 +210,14 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
             table: GossipTable::new(config),
             gossip_timeout: Duration::from_secs(config.gossip_request_timeout_secs()),
             get_from_peer_timeout: Duration::from_secs(config.get_remainder_timeout_secs()),
+            item_ttl: Duration::from_secs(config.item_ttl_secs()),
+            expiry_scheduled: false,
             get_from_holder: Box::new(|_, item, _| {
                 panic!("gossiper should never try to get {}", item)
             }),
+            get_remainder: Box::new(|_, item, _| {
+                panic!("gossiper should never try to get the remainder of {}", item)
+            }),
             metrics: GossiperMetrics::new(name, registry)?,
         })
     }
@@ -191,6 +245,8 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
     }
 
     /// Gossips the given item ID to `count` random peers excluding the indicated ones.
+    ///
+    /// If gossiping terminates based on peer saturation, also refreshes the estimated peer count.
     fn gossip(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -199,9 +255,17 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
         exclude_peers: HashSet<NodeId>,
     ) -> Effects<Event<T>> {
         let message = Message::Gossip(item_id);
-        effect_builder
+        let mut effects = effect_builder
             .gossip_message(message, count, exclude_peers)
-            .event(move |peers| Event::GossipedTo { item_id, peers })
+            .event(move |peers| Event::GossipedTo { item_id, peers });
+        if self.table.uses_peer_saturation() {
+            effects.extend(
+                effect_builder
+                    .network_peer_count()
+                    .event(|peer_count| Event::PeerCountReceived { peer_count }),
+            );
+        }
+        effects
     }
 
     /// Handles the response from the network component detailing which peers it gossiped to.
@@ -257,8 +321,9 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
         }
     }
 
-    /// Checks that the given peer has responded to a previous gossip response or `GetRequest` we
-    /// sent it indicating we wanted to get the full item from it.
+    /// Checks that the given peer has provided the full item, either in response to a previous
+    /// gossip response we sent it indicating we wanted the full item from it, or when asked for the
+    /// item via `get_remainder`.
     fn check_get_from_peer_timeout(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -274,28 +339,9 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
             ),
 
             GossipAction::GetRemainder { holder } => {
-                // The previous peer failed to provide the item, so we still need to get it.  Send
-                // a `GetRequest` to a different holder and set a timeout to check we got the
-                // response.
-                let request = match NodeMessage::new_get_request::<T>(&item_id) {
-                    Ok(request) => request,
-                    Err(error) => {
-                        error!("failed to create get-request: {}", error);
-                        // Treat this as if the holder didn't respond - i.e. try to get from a
-                        // different holder.
-                        return self.check_get_from_peer_timeout(effect_builder, item_id, holder);
-                    }
-                };
-                let mut effects = effect_builder.send_message(holder, request).ignore();
-                effects.extend(
-                    effect_builder
-                        .set_timeout(self.get_from_peer_timeout)
-                        .event(move |_| Event::CheckGetFromPeerTimeout {
-                            item_id,
-                            peer: holder,
-                        }),
-                );
-                effects
+                // The previous peer failed to provide the item, so we still need to get it from a
+                // different holder.  Once that has been tried, this check is made again.
+                (self.get_remainder)(effect_builder, item_id, holder)
             }
 
             GossipAction::Noop | GossipAction::AwaitingRemainder => Effects::new(),
@@ -310,6 +356,19 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
         sender: NodeId,
     ) -> Effects<Event<T>> {
         let action = if T::ID_IS_COMPLETE_ITEM {
+            if !self.table.has_entry(&item_id) {
+                // This is a new complete item to us.  Announce it, but leave it to the reactor to
+                // start gossiping it once verified.
+                let mut effects = effect_builder
+                    .announce_complete_item_received_via_gossip(item_id)
+                    .ignore();
+                let reply = Message::GossipResponse {
+                    item_id,
+                    is_already_held: false,
+                };
+                effects.extend(effect_builder.send_message(sender, reply).ignore());
+                return effects;
+            }
             self.table
                 .new_complete_data(&item_id, Some(sender))
                 .map_or_else(|| GossipAction::Noop, GossipAction::ShouldGossip)
@@ -327,15 +386,6 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
                     should_gossip.exclude_peers,
                 );
 
-                // If this is a new complete item to us, announce it.
-                if T::ID_IS_COMPLETE_ITEM && !should_gossip.is_already_held {
-                    effects.extend(
-                        effect_builder
-                            .announce_complete_item_received_via_gossip(item_id)
-                            .ignore(),
-                    );
-                }
-
                 // Send a response to the sender indicating whether we already hold the item.
                 let reply = Message::GossipResponse {
                     item_id,
@@ -360,6 +410,7 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
                             peer: sender,
                         }),
                 );
+                effects.extend(self.schedule_expiry(effect_builder));
                 effects
             }
             GossipAction::Noop | GossipAction::AwaitingRemainder => {
@@ -437,6 +488,32 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
         Effects::new()
     }
 
+    /// Schedules an `Event::ExpireItems` after the item TTL has elapsed, unless one is already
+    /// scheduled.
+    fn schedule_expiry(&mut self, effect_builder: EffectBuilder<REv>) -> Effects<Event<T>> {
+        if self.expiry_scheduled {
+            return Effects::new();
+        }
+        self.expiry_scheduled = true;
+        effect_builder
+            .set_timeout(self.item_ttl)
+            .event(|_| Event::ExpireItems)
+    }
+
+    /// Removes entries for partial items whose remainder hasn't been retrieved within the item
+    /// TTL, and reschedules the check while any partial items remain.
+    fn expire_items(&mut self, effect_builder: EffectBuilder<REv>) -> Effects<Event<T>> {
+        self.expiry_scheduled = false;
+        for item_id in self.table.expire_old_items(Instant::now()) {
+            debug!(%item_id, "expired partial item which was never retrieved");
+        }
+        if self.table.has_partial_items() {
+            self.schedule_expiry(effect_builder)
+        } else {
+            Effects::new()
+        }
+    }
+
     /// Updates the gossiper metrics from the state of the gossip table.
     fn update_gossip_table_metrics(&self) {
         self.metrics
@@ -492,6 +569,11 @@ where
                 Ok(item) => self.got_from_holder(effect_builder, item, requester),
                 Err(error) => self.failed_to_get_from_holder(item_id, error),
             },
+            Event::PeerCountReceived { peer_count } => {
+                self.table.set_estimated_peer_count(peer_count);
+                Effects::new()
+            }
+            Event::ExpireItems => self.expire_items(effect_builder),
         };
         self.update_gossip_table_metrics();
         effects
@@ -505,6 +587,8 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Debug for Gossiper<T, REv> {
             .field("table", &self.table)
             .field("gossip_timeout", &self.gossip_timeout)
             .field("get_from_peer_timeout", &self.get_from_peer_timeout)
+            .field("item_ttl", &self.item_ttl)
+            .field("expiry_scheduled", &self.expiry_scheduled)
             .finish()
     }
 }
@@ -11,12 +11,15 @@ use super::Error;
 const DEFAULT_INFECTION_TARGET: u8 = 3;
 const DEFAULT_SATURATION_LIMIT_PERCENT: u8 = 80;
 pub(super) const MAX_SATURATION_LIMIT_PERCENT: u8 = 99;
+pub(super) const MAX_PEER_SATURATION_PERCENT: u8 = 100;
 pub(super) const DEFAULT_FINISHED_ENTRY_DURATION_SECS: u64 = 3_600;
 const DEFAULT_GOSSIP_REQUEST_TIMEOUT_SECS: u64 = 10;
 const DEFAULT_GET_REMAINDER_TIMEOUT_SECS: u64 = 60;
+pub(super) const DEFAULT_ITEM_TTL_SECS: u64 = 60;
 
 /// Configuration options for gossiping.
 #[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize)]
+#[serde(default)]
 pub struct Config {
     /// Target number of peers to infect with a given piece of data.
     infection_target: u8,
@@ -28,6 +31,16 @@ pub struct Config {
     /// holders excluding us since 80% saturation would imply 3 new infections in 15 peers.
     #[serde(deserialize_with = "deserialize_saturation_limit_percent")]
     saturation_limit_percent: u8,
+    /// The peer saturation as a percentage, with a maximum value of 100.  Used as a termination
+    /// condition if set.
+    ///
+    /// Example: assume the `peer_saturation_percent` is 50 and we are connected to 20 peers.  We
+    /// will stop gossiping once 10 distinct peers have responded that they already hold the data.
+    #[serde(
+        deserialize_with = "deserialize_peer_saturation_percent",
+        skip_serializing_if = "Option::is_none"
+    )]
+    peer_saturation_percent: Option<u8>,
     /// The maximum duration in seconds for which to keep finished entries.
     ///
     /// The longer they are retained, the lower the likelihood of re-gossiping a piece of data.
@@ -40,6 +53,9 @@ pub struct Config {
     /// The timeout duration in seconds for retrieving the remaining part(s) of newly-discovered
     /// data from a peer which gossiped information about that data to this node.
     get_remainder_timeout_secs: u64,
+    /// The maximum duration in seconds for which to keep an entry for data of which we only hold
+    /// part.  Entries whose remainder hasn't been retrieved within this duration are dropped.
+    item_ttl_secs: u64,
 }
 
 impl Config {
@@ -57,9 +73,11 @@ impl Config {
         Ok(Config {
             infection_target,
             saturation_limit_percent,
+            peer_saturation_percent: None,
             finished_entry_duration_secs,
             gossip_request_timeout_secs,
             get_remainder_timeout_secs,
+            item_ttl_secs: DEFAULT_ITEM_TTL_SECS,
         })
     }
 
@@ -67,10 +85,27 @@ impl Config {
         self.infection_target
     }
 
+    /// Returns a copy of this config with the given peer saturation percentage.
+    #[cfg(test)]
+    pub(crate) fn with_peer_saturation_percent(
+        mut self,
+        peer_saturation_percent: u8,
+    ) -> Result<Self, Error> {
+        if peer_saturation_percent > MAX_PEER_SATURATION_PERCENT {
+            return Err(Error::InvalidPeerSaturation);
+        }
+        self.peer_saturation_percent = Some(peer_saturation_percent);
+        Ok(self)
+    }
+
     pub(crate) fn saturation_limit_percent(&self) -> u8 {
         self.saturation_limit_percent
     }
 
+    pub(crate) fn peer_saturation_percent(&self) -> Option<u8> {
+        self.peer_saturation_percent
+    }
+
     pub(crate) fn finished_entry_duration_secs(&self) -> u64 {
         self.finished_entry_duration_secs
     }
@@ -82,6 +117,10 @@ impl Config {
     pub(crate) fn get_remainder_timeout_secs(&self) -> u64 {
         self.get_remainder_timeout_secs
     }
+
+    pub(crate) fn item_ttl_secs(&self) -> u64 {
+        self.item_ttl_secs
+    }
 }
 
 impl Default for Config {
@@ -89,13 +128,47 @@ impl Default for Config {
         Config {
             infection_target: DEFAULT_INFECTION_TARGET,
             saturation_limit_percent: DEFAULT_SATURATION_LIMIT_PERCENT,
+            peer_saturation_percent: None,
             finished_entry_duration_secs: DEFAULT_FINISHED_ENTRY_DURATION_SECS,
             gossip_request_timeout_secs: DEFAULT_GOSSIP_REQUEST_TIMEOUT_SECS,
             get_remainder_timeout_secs: DEFAULT_GET_REMAINDER_TIMEOUT_SECS,
+            item_ttl_secs: DEFAULT_ITEM_TTL_SECS,
         }
     }
 }
 
+/// Gossip configuration, with optional sections overriding the common options for each type of
+/// gossiped item.
+#[derive(Copy, Clone, DataSize, Debug, Default, Deserialize, Serialize)]
+pub struct PerItemConfig {
+    /// Options used by all gossipers and fetchers, unless overridden below.
+    #[serde(flatten)]
+    common: Config,
+    /// Options for gossiping deploys.  Defaults to the common options if not specified.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    deploys: Option<Config>,
+    /// Options for gossiping peer addresses.  Defaults to the common options if not specified.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    addresses: Option<Config>,
+}
+
+impl PerItemConfig {
+    /// Returns the common options.
+    pub(crate) fn common(&self) -> Config {
+        self.common
+    }
+
+    /// Returns the options for gossiping deploys.
+    pub(crate) fn deploys(&self) -> Config {
+        self.deploys.unwrap_or(self.common)
+    }
+
+    /// Returns the options for gossiping peer addresses.
+    pub(crate) fn addresses(&self) -> Config {
+        self.addresses.unwrap_or(self.common)
+    }
+}
+
 /// Deserializes a `usize` but fails if it's not in the range 0..100.
 fn deserialize_saturation_limit_percent<'de, D>(deserializer: D) -> Result<u8, D::Error>
 where
@@ -116,6 +189,28 @@ where
     Ok(saturation_limit_percent)
 }
 
+/// Deserializes an optional `u8` but fails if it's not in the range 0..=100.
+fn deserialize_peer_saturation_percent<'de, D>(deserializer: D) -> Result<Option<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let maybe_peer_saturation_percent = Option::<u8>::deserialize(deserializer)?;
+    if let Some(peer_saturation_percent) = maybe_peer_saturation_percent {
+        if peer_saturation_percent > MAX_PEER_SATURATION_PERCENT {
+            error!(
+                "peer_saturation_percent of {} is above {}",
+                peer_saturation_percent, MAX_PEER_SATURATION_PERCENT
+            );
+            return Err(SerdeError::invalid_value(
+                Unexpected::Unsigned(peer_saturation_percent as u64),
+                &"a value between 0 and 100 inclusive",
+            ));
+        }
+    }
+
+    Ok(maybe_peer_saturation_percent)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -126,9 +221,11 @@ mod tests {
         let invalid_config = Config {
             infection_target: 3,
             saturation_limit_percent: MAX_SATURATION_LIMIT_PERCENT + 1,
+            peer_saturation_percent: None,
             finished_entry_duration_secs: DEFAULT_FINISHED_ENTRY_DURATION_SECS,
             gossip_request_timeout_secs: DEFAULT_GOSSIP_REQUEST_TIMEOUT_SECS,
             get_remainder_timeout_secs: DEFAULT_GET_REMAINDER_TIMEOUT_SECS,
+            item_ttl_secs: DEFAULT_ITEM_TTL_SECS,
         };
 
         // Parsing should fail.
@@ -145,4 +242,39 @@ mod tests {
         )
         .is_err())
     }
+
+    #[test]
+    fn invalid_peer_saturation_should_fail() {
+        let invalid_config = Config {
+            peer_saturation_percent: Some(MAX_PEER_SATURATION_PERCENT + 1),
+            ..Default::default()
+        };
+
+        // Parsing should fail.
+        let config_as_json = serde_json::to_string(&invalid_config).unwrap();
+        assert!(serde_json::from_str::<Config>(&config_as_json).is_err());
+
+        // Construction should fail.
+        assert!(Config::default()
+            .with_peer_saturation_percent(MAX_PEER_SATURATION_PERCENT + 1)
+            .is_err())
+    }
+
+    #[test]
+    fn per_item_config_should_default_to_common_options() {
+        let toml = r#"
+            infection_target = 4
+
+            [addresses]
+            infection_target = 2
+            peer_saturation_percent = 50
+        "#;
+        let config: PerItemConfig = toml::from_str(toml).unwrap();
+
+        assert_eq!(config.common().infection_target(), 4);
+        assert_eq!(config.deploys().infection_target(), 4);
+        assert_eq!(config.deploys().peer_saturation_percent(), None);
+        assert_eq!(config.addresses().infection_target(), 2);
+        assert_eq!(config.addresses().peer_saturation_percent(), Some(50));
+    }
 }
@@ -1,6 +1,6 @@
 use thiserror::Error;
 
-use super::config::MAX_SATURATION_LIMIT_PERCENT;
+use super::config::{MAX_PEER_SATURATION_PERCENT, MAX_SATURATION_LIMIT_PERCENT};
 
 /// Error returned by a `GossipTable`.
 #[derive(Debug, Error)]
@@ -12,6 +12,13 @@ pub enum Error {
     )]
     InvalidSaturationLimit,
 
+    /// Invalid configuration value for `peer_saturation_percent`.
+    #[error(
+        "invalid peer_saturation_percent - should be between 0 and {} inclusive",
+        MAX_PEER_SATURATION_PERCENT
+    )]
+    InvalidPeerSaturation,
+
     /// Attempted to reset data which had not been paused.
     #[error("gossiping is not paused for this data")]
     NotPaused,
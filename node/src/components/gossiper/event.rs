@@ -37,6 +37,11 @@ pub enum Event<T: Item> {
         requester: NodeId,
         result: Box<Result<T, String>>,
     },
+    /// The network component reported the number of peers we're connected to.
+    PeerCountReceived { peer_count: usize },
+    /// The timer for expiring entries for partial items whose remainder was never retrieved has
+    /// elapsed.
+    ExpireItems,
 }
 
 impl<T: Item> Display for Event<T> {
@@ -73,6 +78,10 @@ impl<T: Item> Display for Event<T> {
                     write!(formatter, "failed to get {} from holder component", item_id)
                 }
             }
+            Event::PeerCountReceived { peer_count } => {
+                write!(formatter, "connected to {} peers", peer_count)
+            }
+            Event::ExpireItems => write!(formatter, "expire old partial items"),
         }
     }
 }
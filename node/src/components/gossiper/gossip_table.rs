@@ -4,6 +4,7 @@ use std::{
     collections::{hash_map::Entry, HashMap, HashSet},
     fmt::Display,
     hash::Hash,
+    mem,
     time::Duration,
 };
 
@@ -17,6 +18,10 @@ use super::Config;
 use super::Error;
 use crate::small_network::NodeId;
 
+/// Estimated number of bytes used by each entry of a `GossipTable` in addition to the data ID,
+/// covering the hash table slot and the gossip state.  Intentionally conservative.
+const ESTIMATED_ENTRY_OVERHEAD: usize = 128;
+
 #[derive(Debug, PartialEq, Eq)]
 pub(crate) enum GossipAction {
     /// This is new data, previously unknown by us, and for which we don't yet hold everything
@@ -54,14 +59,24 @@ pub(crate) struct State {
     /// The subset of `holders` we have infected.  Not just a count so we don't attribute the same
     /// peer multiple times.
     infected_by_us: HashSet<NodeId>,
+    /// The subset of `holders` which responded to our gossip indicating they already held the
+    /// data.  Not just a count so we don't attribute the same peer multiple times.
+    already_held_by: HashSet<NodeId>,
     /// The count of in-flight gossip messages sent by us for this data.
     in_flight_count: usize,
 }
 
 impl State {
     /// Returns whether we should finish gossiping this data.
-    fn is_finished(&self, infection_target: usize, holders_limit: usize) -> bool {
-        self.infected_by_us.len() >= infection_target || self.holders.len() >= holders_limit
+    fn is_finished(
+        &self,
+        infection_target: usize,
+        holders_limit: usize,
+        already_held_limit: Option<usize>,
+    ) -> bool {
+        self.infected_by_us.len() >= infection_target
+            || self.holders.len() >= holders_limit
+            || already_held_limit.map_or(false, |limit| self.already_held_by.len() >= limit)
     }
 
     /// Returns a `GossipAction` derived from the given state.
@@ -69,9 +84,10 @@ impl State {
         &mut self,
         infection_target: usize,
         holders_limit: usize,
+        already_held_limit: Option<usize>,
         is_new: bool,
     ) -> GossipAction {
-        if self.is_finished(infection_target, holders_limit) {
+        if self.is_finished(infection_target, holders_limit, already_held_limit) {
             return GossipAction::Noop;
         }
 
@@ -134,29 +150,35 @@ impl<T> Timeouts<T> {
     }
 }
 
-#[derive(DataSize, Debug)]
+#[derive(Debug)]
 pub(crate) struct GossipTable<T> {
     /// Data IDs for which gossiping is still ongoing.
     current: HashMap<T, State>,
     /// Data IDs for which gossiping is complete.
     finished: HashSet<T>,
     /// Timeouts for removal of items from the `finished` cache.
-    #[data_size(skip)]
     finished_timeouts: Timeouts<T>,
     /// Data IDs for which gossiping has been paused (likely due to detecting that the data was not
     /// correct as per our current knowledge).  Such data could later be decided as still requiring
     /// to be gossiped, so we retain the `State` part here in order to resume gossiping.
     paused: HashMap<T, State>,
     /// Timeouts for removal of items from the `paused` cache.
-    #[data_size(skip)]
     paused_timeouts: Timeouts<T>,
     /// See `Config::infection_target`.
     infection_target: usize,
     /// Derived from `Config::saturation_limit_percent` - we gossip data while the number of
     /// holders doesn't exceed `holders_limit`.
     holders_limit: usize,
+    /// See `Config::peer_saturation_percent`.
+    peer_saturation_percent: Option<u8>,
+    /// The number of peers we're connected to, as last reported by the network component.
+    estimated_peer_count: usize,
     /// See `Config::finished_entry_duration`.
     finished_entry_duration: Duration,
+    /// The times at which entries for data of which we only hold part were created.
+    inserted_at: HashMap<T, Instant>,
+    /// See `Config::item_ttl_secs`.
+    item_ttl: Duration,
 }
 
 impl<T> GossipTable<T> {
@@ -174,6 +196,29 @@ impl<T> GossipTable<T> {
     pub fn items_paused(&self) -> usize {
         self.paused.len()
     }
+
+    /// Returns a conservative estimate of the heap memory in bytes used by the table's entries.
+    ///
+    /// This is a heuristic based on the number of entries, since the ID type isn't required to
+    /// implement `DataSize`.
+    pub fn estimated_memory_usage(&self) -> usize {
+        let entry_count = self.items_current() + self.items_finished() + self.items_paused();
+        entry_count * (mem::size_of::<T>() + ESTIMATED_ENTRY_OVERHEAD)
+    }
+}
+
+impl<T> DataSize for GossipTable<T>
+where
+    T: 'static,
+{
+    const IS_DYNAMIC: bool = true;
+
+    const STATIC_HEAP_SIZE: usize = 0;
+
+    #[inline]
+    fn estimate_heap_size(&self) -> usize {
+        self.estimated_memory_usage()
+    }
 }
 
 impl<T: Copy + Eq + Hash + Display> GossipTable<T> {
@@ -189,8 +234,45 @@ impl<T: Copy + Eq + Hash + Display> GossipTable<T> {
             paused_timeouts: Timeouts::new(),
             infection_target: usize::from(config.infection_target()),
             holders_limit,
+            peer_saturation_percent: config.peer_saturation_percent(),
+            estimated_peer_count: 0,
             finished_entry_duration: Duration::from_secs(config.finished_entry_duration_secs()),
+            inserted_at: HashMap::new(),
+            item_ttl: Duration::from_secs(config.item_ttl_secs()),
+        }
+    }
+
+    /// Returns whether gossiping can be terminated based on the estimated number of peers, i.e.
+    /// whether the estimate should be kept up to date via `set_estimated_peer_count`.
+    pub(crate) fn uses_peer_saturation(&self) -> bool {
+        self.peer_saturation_percent.is_some()
+    }
+
+    /// Updates the estimated number of peers we're connected to.
+    pub(crate) fn set_estimated_peer_count(&mut self, estimated_peer_count: usize) {
+        self.estimated_peer_count = estimated_peer_count;
+    }
+
+    /// Returns the number of distinct peers which must respond that they already hold given data
+    /// for us to stop gossiping it, or `None` if this termination condition doesn't apply.
+    ///
+    /// Derived from `Config::peer_saturation_percent` and the estimated peer count.  If the peer
+    /// count is unknown, the condition doesn't apply.
+    fn already_held_limit(&self) -> Option<usize> {
+        let peer_saturation_percent = usize::from(self.peer_saturation_percent?);
+        if self.estimated_peer_count == 0 {
+            return None;
         }
+        let limit = (self.estimated_peer_count * peer_saturation_percent + 99) / 100;
+        Some(limit.max(1))
+    }
+
+    /// Returns whether the data with the given ID is currently being gossiped, has been paused or
+    /// has recently finished being gossiped.
+    pub(crate) fn has_entry(&self, data_id: &T) -> bool {
+        self.current.contains_key(data_id)
+            || self.paused.contains_key(data_id)
+            || self.finished.contains(data_id)
     }
 
     /// We received knowledge about potentially new data with given ID from the given peer.  This
@@ -203,6 +285,7 @@ impl<T: Copy + Eq + Hash + Display> GossipTable<T> {
     /// Returns whether we should gossip it, and a list of peers to exclude.
     pub(crate) fn new_partial_data(&mut self, data_id: &T, holder: NodeId) -> GossipAction {
         self.purge_finished();
+        let already_held_limit = self.already_held_limit();
 
         if self.finished.contains(data_id) {
             return GossipAction::Noop;
@@ -218,13 +301,24 @@ impl<T: Copy + Eq + Hash + Display> GossipTable<T> {
                 let is_new = false;
                 let state = entry.get_mut();
                 let _ = state.holders.insert(holder);
-                state.action(self.infection_target, self.holders_limit, is_new)
+                state.action(
+                    self.infection_target,
+                    self.holders_limit,
+                    already_held_limit,
+                    is_new,
+                )
             }
             Entry::Vacant(entry) => {
                 let is_new = true;
+                let _ = self.inserted_at.insert(*data_id, Instant::now());
                 let state = entry.insert(State::default());
                 let _ = state.holders.insert(holder);
-                state.action(self.infection_target, self.holders_limit, is_new)
+                state.action(
+                    self.infection_target,
+                    self.holders_limit,
+                    already_held_limit,
+                    is_new,
+                )
             }
         }
     }
@@ -243,11 +337,15 @@ impl<T: Copy + Eq + Hash + Display> GossipTable<T> {
         maybe_holder: Option<NodeId>,
     ) -> Option<ShouldGossip> {
         self.purge_finished();
+        let already_held_limit = self.already_held_limit();
 
         if self.finished.contains(data_id) {
             return None;
         }
 
+        // We now hold the complete data, so the entry is no longer subject to expiry.
+        let _ = self.inserted_at.remove(data_id);
+
         let update = |state: &mut State| {
             state.holders.extend(maybe_holder);
             state.held_by_us = true;
@@ -263,13 +361,23 @@ impl<T: Copy + Eq + Hash + Display> GossipTable<T> {
                 let state = entry.get_mut();
                 update(state);
                 let is_new = false;
-                state.action(self.infection_target, self.holders_limit, is_new)
+                state.action(
+                    self.infection_target,
+                    self.holders_limit,
+                    already_held_limit,
+                    is_new,
+                )
             }
             Entry::Vacant(entry) => {
                 let state = entry.insert(State::default());
                 update(state);
                 let is_new = true;
-                state.action(self.infection_target, self.holders_limit, is_new)
+                state.action(
+                    self.infection_target,
+                    self.holders_limit,
+                    already_held_limit,
+                    is_new,
+                )
             }
         };
 
@@ -307,6 +415,7 @@ impl<T: Copy + Eq + Hash + Display> GossipTable<T> {
     fn infected(&mut self, data_id: &T, peer: NodeId, by_us: bool) -> GossipAction {
         let infection_target = self.infection_target;
         let holders_limit = self.holders_limit;
+        let already_held_limit = self.already_held_limit();
         let update = |state: &mut State| {
             if !state.held_by_us {
                 warn!(
@@ -318,9 +427,11 @@ impl<T: Copy + Eq + Hash + Display> GossipTable<T> {
             let _ = state.holders.insert(peer);
             if by_us {
                 let _ = state.infected_by_us.insert(peer);
+            } else {
+                let _ = state.already_held_by.insert(peer);
             }
             state.in_flight_count = state.in_flight_count.saturating_sub(1);
-            Some(state.is_finished(infection_target, holders_limit))
+            Some(state.is_finished(infection_target, holders_limit, already_held_limit))
         };
 
         let is_finished = if let Some(state) = self.current.get_mut(data_id) {
@@ -330,7 +441,12 @@ impl<T: Copy + Eq + Hash + Display> GossipTable<T> {
             };
             if !is_finished {
                 let is_new = false;
-                return state.action(self.infection_target, self.holders_limit, is_new);
+                return state.action(
+                    self.infection_target,
+                    self.holders_limit,
+                    already_held_limit,
+                    is_new,
+                );
             }
             true
         } else {
@@ -369,6 +485,7 @@ impl<T: Copy + Eq + Hash + Display> GossipTable<T> {
     /// If the peer is already counted as a holder, it has previously responded and this method
     /// returns Noop.  Otherwise it has timed out and we return the appropriate action to take.
     pub(crate) fn check_timeout(&mut self, data_id: &T, peer: NodeId) -> GossipAction {
+        let already_held_limit = self.already_held_limit();
         if let Some(state) = self.current.get_mut(data_id) {
             debug_assert!(
                 state.held_by_us,
@@ -380,7 +497,12 @@ impl<T: Copy + Eq + Hash + Display> GossipTable<T> {
                 let _ = state.holders.insert(peer);
                 state.in_flight_count = state.in_flight_count.saturating_sub(1);
                 let is_new = false;
-                return state.action(self.infection_target, self.holders_limit, is_new);
+                return state.action(
+                    self.infection_target,
+                    self.holders_limit,
+                    already_held_limit,
+                    is_new,
+                );
             }
         }
 
@@ -397,16 +519,23 @@ impl<T: Copy + Eq + Hash + Display> GossipTable<T> {
         data_id: &T,
         peer: NodeId,
     ) -> GossipAction {
+        let already_held_limit = self.already_held_limit();
         if let Some(mut state) = self.current.remove(data_id) {
             if !state.held_by_us {
                 let _ = state.holders.remove(&peer);
                 if state.holders.is_empty() {
                     // We don't hold the full data, and we don't know any holders - pause the entry
+                    let _ = self.inserted_at.remove(data_id);
                     return GossipAction::Noop;
                 }
             }
             let is_new = !state.held_by_us;
-            let action = state.action(self.infection_target, self.holders_limit, is_new);
+            let action = state.action(
+                self.infection_target,
+                self.holders_limit,
+                already_held_limit,
+                is_new,
+            );
             let _ = self.current.insert(*data_id, state);
             return action;
         }
@@ -423,6 +552,7 @@ impl<T: Copy + Eq + Hash + Display> GossipTable<T> {
     /// We have deemed the data not suitable for gossiping further.  If left in paused state, the
     /// entry will eventually be purged, as for finished entries.
     pub(crate) fn pause(&mut self, data_id: &T) {
+        let _ = self.inserted_at.remove(data_id);
         if let Some(mut state) = self.current.remove(data_id) {
             state.in_flight_count = 0;
             let timeout = Instant::now() + self.finished_entry_duration;
@@ -437,13 +567,46 @@ impl<T: Copy + Eq + Hash + Display> GossipTable<T> {
     // TODO - remove lint relaxation once the method is used.
     #[cfg(test)]
     pub(crate) fn resume(&mut self, data_id: &T) -> Result<GossipAction, Error> {
+        let already_held_limit = self.already_held_limit();
         let mut state = self.paused.remove(data_id).ok_or(Error::NotPaused)?;
         let is_new = !state.held_by_us;
-        let action = state.action(self.infection_target, self.holders_limit, is_new);
+        let action = state.action(
+            self.infection_target,
+            self.holders_limit,
+            already_held_limit,
+            is_new,
+        );
         let _ = self.current.insert(*data_id, state);
         Ok(action)
     }
 
+    /// Returns whether there are entries for data of which we only hold part, i.e. entries which
+    /// may need to be expired via `expire_old_items`.
+    pub(crate) fn has_partial_items(&self) -> bool {
+        !self.inserted_at.is_empty()
+    }
+
+    /// Removes entries for data of which we only hold part and which were created more than
+    /// `Config::item_ttl_secs` before `now`, as if we'd never heard of them.
+    ///
+    /// Returns the IDs of the removed entries.
+    pub(crate) fn expire_old_items(&mut self, now: Instant) -> Vec<T> {
+        let item_ttl = self.item_ttl;
+        let expired: Vec<T> = self
+            .inserted_at
+            .iter()
+            .filter(|(_, inserted_at)| **inserted_at + item_ttl <= now)
+            .map(|(data_id, _)| *data_id)
+            .collect();
+
+        for data_id in &expired {
+            let _ = self.inserted_at.remove(data_id);
+            let _ = self.current.remove(data_id);
+        }
+
+        expired
+    }
+
     /// Retains only those finished entries which still haven't timed out.
     fn purge_finished(&mut self) {
         let now = Instant::now();
@@ -462,10 +625,14 @@ impl<T: Copy + Eq + Hash + Display> GossipTable<T> {
 mod tests {
     use std::{collections::BTreeSet, iter};
 
+    use assert_matches::assert_matches;
     use rand::Rng;
     use test::Bencher;
 
-    use super::{super::config::DEFAULT_FINISHED_ENTRY_DURATION_SECS, *};
+    use super::{
+        super::config::{DEFAULT_FINISHED_ENTRY_DURATION_SECS, DEFAULT_ITEM_TTL_SECS},
+        *,
+    };
     use crate::{crypto::hash::Digest, testing::TestRng, types::DeployHash, utils::DisplayIter};
 
     const EXPECTED_DEFAULT_INFECTION_TARGET: usize = 3;
@@ -758,6 +925,84 @@ mod tests {
         assert_eq!(expected, action);
     }
 
+    #[test]
+    fn should_terminate_via_peer_saturation() {
+        const PEER_SATURATION_PERCENT: u8 = 50;
+        const PEER_COUNT: usize = 10;
+        const ALREADY_HELD_LIMIT: usize = 5;
+
+        let mut rng = TestRng::new();
+        let node_ids = random_node_ids(&mut rng);
+        let data_id: u64 = rng.gen();
+
+        let config = Config::default()
+            .with_peer_saturation_percent(PEER_SATURATION_PERCENT)
+            .unwrap();
+        let mut gossip_table = GossipTable::new(config);
+        assert!(gossip_table.uses_peer_saturation());
+        gossip_table.set_estimated_peer_count(PEER_COUNT);
+        assert_eq!(Some(ALREADY_HELD_LIMIT), gossip_table.already_held_limit());
+
+        // Add new complete data with 4 non-infections and check this doesn't cause us to stop
+        // gossiping.
+        let _ = gossip_table.new_complete_data(&data_id, None);
+        let limit = ALREADY_HELD_LIMIT - 1;
+        for (index, node_id) in node_ids.iter().enumerate().take(limit) {
+            let action = gossip_table.already_infected(&data_id, *node_id);
+            let expected = GossipAction::ShouldGossip(ShouldGossip {
+                count: 1,
+                exclude_peers: node_ids[..(index + 1)].iter().copied().collect(),
+                is_already_held: true,
+            });
+            assert_eq!(expected, action);
+        }
+
+        // Check recording a non-infection from an already-recorded responder doesn't cause us to
+        // stop gossiping.
+        let action = gossip_table.already_infected(&data_id, node_ids[0]);
+        assert_matches!(action, GossipAction::ShouldGossip(_));
+
+        // Check 5th distinct non-infection does cause us to stop gossiping.
+        let action = gossip_table.already_infected(&data_id, node_ids[limit]);
+        assert_eq!(GossipAction::Noop, action);
+        assert!(gossip_table.finished.contains(&data_id));
+    }
+
+    #[test]
+    fn should_not_terminate_via_peer_saturation_with_unknown_peer_count() {
+        let mut rng = TestRng::new();
+        let node_ids = random_node_ids(&mut rng);
+        let data_id: u64 = rng.gen();
+
+        let config = Config::default().with_peer_saturation_percent(1).unwrap();
+        let mut gossip_table = GossipTable::new(config);
+        assert_eq!(None, gossip_table.already_held_limit());
+
+        // Add new complete data with a single non-infection and check this doesn't cause us to stop
+        // gossiping.
+        let _ = gossip_table.new_complete_data(&data_id, None);
+        let action = gossip_table.already_infected(&data_id, node_ids[0]);
+        assert_matches!(action, GossipAction::ShouldGossip(_));
+    }
+
+    #[test]
+    fn should_derive_already_held_limit_from_peer_count() {
+        let config = Config::default().with_peer_saturation_percent(80).unwrap();
+        let mut gossip_table = GossipTable::<u64>::new(config);
+
+        // The limit is rounded up, and at least one.
+        for (peer_count, expected_limit) in &[(1, 1), (4, 4), (10, 8), (11, 9), (100, 80)] {
+            gossip_table.set_estimated_peer_count(*peer_count);
+            assert_eq!(Some(*expected_limit), gossip_table.already_held_limit());
+        }
+
+        // The condition doesn't apply if not configured.
+        let mut gossip_table = GossipTable::<u64>::new(Config::default());
+        assert!(!gossip_table.uses_peer_saturation());
+        gossip_table.set_estimated_peer_count(10);
+        assert_eq!(None, gossip_table.already_held_limit());
+    }
+
     #[test]
     fn check_timeout_should_detect_holder() {
         let mut rng = TestRng::new();
@@ -899,6 +1144,28 @@ mod tests {
         assert!(gossip_table.paused.contains_key(&data_id));
     }
 
+    #[test]
+    fn should_report_entries() {
+        let mut rng = TestRng::new();
+        let node_ids = random_node_ids(&mut rng);
+        let data_id: u64 = rng.gen();
+
+        let mut gossip_table = GossipTable::new(Config::default());
+        assert!(!gossip_table.has_entry(&data_id));
+
+        // Check current, paused and finished data are all reported.
+        let _ = gossip_table.new_complete_data(&data_id, None);
+        assert!(gossip_table.has_entry(&data_id));
+        gossip_table.pause(&data_id);
+        assert!(gossip_table.has_entry(&data_id));
+        let _ = gossip_table.resume(&data_id).unwrap();
+        for node_id in &node_ids[0..EXPECTED_DEFAULT_INFECTION_TARGET] {
+            let _ = gossip_table.we_infected(&data_id, *node_id);
+        }
+        assert!(gossip_table.finished.contains(&data_id));
+        assert!(gossip_table.has_entry(&data_id));
+    }
+
     #[test]
     fn should_purge() {
         let mut rng = TestRng::new();
@@ -930,6 +1197,63 @@ mod tests {
         assert!(!gossip_table.paused.contains_key(&data_id));
     }
 
+    #[test]
+    fn should_expire_old_partial_items() {
+        let mut rng = TestRng::new();
+        let node_ids = random_node_ids(&mut rng);
+        let partial_id: u64 = rng.gen();
+        let completed_id: u64 = rng.gen();
+
+        let mut gossip_table = GossipTable::new(Config::default());
+
+        // Add two partial entries, and get the complete data for the second one.
+        let _ = gossip_table.new_partial_data(&partial_id, node_ids[0]);
+        let _ = gossip_table.new_partial_data(&completed_id, node_ids[0]);
+        let _ = gossip_table.new_complete_data(&completed_id, Some(node_ids[0]));
+        assert!(gossip_table.has_partial_items());
+
+        // Nothing should expire before the TTL has elapsed.
+        Instant::advance_time(DEFAULT_ITEM_TTL_SECS * 1_000 - 1);
+        assert!(gossip_table.expire_old_items(Instant::now()).is_empty());
+        assert!(gossip_table.has_entry(&partial_id));
+
+        // Only the partial entry should expire once the TTL has elapsed.
+        Instant::advance_time(1);
+        assert_eq!(
+            gossip_table.expire_old_items(Instant::now()),
+            vec![partial_id]
+        );
+        assert!(!gossip_table.has_entry(&partial_id));
+        assert!(gossip_table.has_entry(&completed_id));
+        assert!(!gossip_table.has_partial_items());
+
+        // Check the expired data is treated as new if gossiped to us again.
+        let action = gossip_table.new_partial_data(&partial_id, node_ids[1]);
+        let expected = GossipAction::GetRemainder {
+            holder: node_ids[1],
+        };
+        assert_eq!(expected, action);
+    }
+
+    #[test]
+    fn should_estimate_memory_usage() {
+        const ENTRY_COUNT: usize = 100;
+        let mut rng = TestRng::new();
+
+        let mut gossip_table = GossipTable::new(Config::default());
+        assert_eq!(gossip_table.estimated_memory_usage(), 0);
+
+        for _ in 0..ENTRY_COUNT {
+            let data_id = DeployHash::new(Digest::random(&mut rng));
+            let _ = gossip_table.new_complete_data(&data_id, None);
+        }
+        assert_eq!(gossip_table.items_current(), ENTRY_COUNT);
+
+        let estimate = gossip_table.estimated_memory_usage();
+        assert!(estimate > ENTRY_COUNT * mem::size_of::<DeployHash>());
+        assert_eq!(gossip_table.estimate_heap_size(), estimate);
+    }
+
     #[bench]
     fn benchmark_purging(bencher: &mut Bencher) {
         const ENTRY_COUNT: usize = 10_000;
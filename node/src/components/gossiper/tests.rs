@@ -13,17 +13,25 @@ use thiserror::Error;
 use tokio::time;
 use tracing::debug;
 
+use casper_execution_engine::core::engine_state::executable_deploy_item::ExecutableDeployItem;
+
 use super::*;
 use crate::{
     components::{
         chainspec_loader::Chainspec,
+        contract_runtime,
         deploy_acceptor::{self, DeployAcceptor},
+        fetcher::{self, Fetcher},
         in_memory_network::{InMemoryNetwork, NetworkController, NodeId},
         storage::{self, Storage, StorageType},
     },
-    effect::announcements::{
-        ApiServerAnnouncement, DeployAcceptorAnnouncement, GossiperAnnouncement,
-        NetworkAnnouncement,
+    crypto::asymmetric_key::SecretKey,
+    effect::{
+        announcements::{
+            ApiServerAnnouncement, DeployAcceptorAnnouncement, GossiperAnnouncement,
+            NetworkAnnouncement,
+        },
+        requests::LinearChainRequest,
     },
     protocol::Message as NodeMessage,
     reactor::{self, EventQueueHandle, Runner},
@@ -31,7 +39,7 @@ use crate::{
         network::{Network, NetworkedReactor},
         ConditionCheckReactor, TestRng,
     },
-    types::{Deploy, Tag},
+    types::{Deploy, Tag, TimeDiff, Timestamp},
     utils::{Loadable, WithDir},
 };
 use rand::Rng;
@@ -45,10 +53,18 @@ enum Event {
     #[from]
     DeployAcceptor(deploy_acceptor::Event),
     #[from]
+    DeployFetcher(fetcher::Event<Deploy>),
+    #[from]
+    DeployFetcherRequest(FetcherRequest<NodeId, Deploy>),
+    #[from]
     DeployGossiper(super::Event<Deploy>),
     #[from]
     NetworkRequest(NetworkRequest<NodeId, NodeMessage>),
     #[from]
+    NetworkInfoRequest(NetworkInfoRequest<NodeId>),
+    #[from]
+    LinearChainRequest(LinearChainRequest<NodeId>),
+    #[from]
     NetworkAnnouncement(NetworkAnnouncement<NodeId, NodeMessage>),
     #[from]
     ApiServerAnnouncement(ApiServerAnnouncement),
@@ -75,8 +91,12 @@ impl Display for Event {
         match self {
             Event::Storage(event) => write!(formatter, "storage: {}", event),
             Event::DeployAcceptor(event) => write!(formatter, "deploy acceptor: {}", event),
+            Event::DeployFetcher(event) => write!(formatter, "deploy fetcher: {}", event),
+            Event::DeployFetcherRequest(req) => write!(formatter, "fetcher request: {}", req),
             Event::DeployGossiper(event) => write!(formatter, "deploy gossiper: {}", event),
             Event::NetworkRequest(req) => write!(formatter, "network request: {}", req),
+            Event::NetworkInfoRequest(req) => write!(formatter, "network info request: {}", req),
+            Event::LinearChainRequest(req) => write!(formatter, "linear chain request: {}", req),
             Event::NetworkAnnouncement(ann) => write!(formatter, "network announcement: {}", ann),
             Event::ApiServerAnnouncement(ann) => {
                 write!(formatter, "api server announcement: {}", ann)
@@ -102,6 +122,7 @@ struct Reactor {
     network: InMemoryNetwork<NodeMessage>,
     storage: Storage,
     deploy_acceptor: DeployAcceptor,
+    deploy_fetcher: Fetcher<Deploy>,
     deploy_gossiper: Gossiper<Deploy, Event>,
     _storage_tempdir: TempDir,
 }
@@ -126,13 +147,19 @@ impl reactor::Reactor for Reactor {
         let network = NetworkController::create_node(event_queue, rng);
 
         let (storage_config, storage_tempdir) = storage::Config::default_for_tests();
-        let storage = Storage::new(WithDir::new(storage_tempdir.path(), storage_config)).unwrap();
+        let storage = Storage::new(
+            WithDir::new(storage_tempdir.path(), storage_config),
+            registry,
+        )
+        .unwrap();
 
-        let deploy_acceptor = DeployAcceptor::new();
+        let deploy_acceptor = DeployAcceptor::new(&contract_runtime::Config::default());
+        let deploy_fetcher = Fetcher::new(config);
         let deploy_gossiper = Gossiper::new_for_partial_items(
             "deploy_gossiper",
             config,
             get_deploy_from_storage,
+            fetch_deploy_from_holder,
             registry,
         )?;
 
@@ -140,6 +167,7 @@ impl reactor::Reactor for Reactor {
             network,
             storage,
             deploy_acceptor,
+            deploy_fetcher,
             deploy_gossiper,
             _storage_tempdir: storage_tempdir,
         };
@@ -171,6 +199,15 @@ impl reactor::Reactor for Reactor {
                 self.deploy_acceptor
                     .handle_event(effect_builder, rng, event),
             ),
+            Event::DeployFetcher(event) => reactor::wrap_effects(
+                Event::DeployFetcher,
+                self.deploy_fetcher.handle_event(effect_builder, rng, event),
+            ),
+            Event::DeployFetcherRequest(request) => reactor::wrap_effects(
+                Event::DeployFetcher,
+                self.deploy_fetcher
+                    .handle_event(effect_builder, rng, request.into()),
+            ),
             Event::DeployGossiper(event) => reactor::wrap_effects(
                 Event::DeployGossiper,
                 self.deploy_gossiper
@@ -180,6 +217,11 @@ impl reactor::Reactor for Reactor {
                 Event::NetworkRequest,
                 self.network.handle_event(effect_builder, rng, request),
             ),
+            Event::NetworkInfoRequest(NetworkInfoRequest::GetPeerCount { responder }) => {
+                responder.respond(self.network.peer_count()).ignore()
+            }
+            Event::NetworkInfoRequest(request) => panic!("should not receive {}", request),
+            Event::LinearChainRequest(request) => panic!("should not receive {}", request),
             Event::NetworkAnnouncement(NetworkAnnouncement::MessageReceived {
                 sender,
                 payload,
@@ -218,6 +260,7 @@ impl reactor::Reactor for Reactor {
                         Event::DeployAcceptor(deploy_acceptor::Event::Accept {
                             deploy,
                             source: Source::Peer(sender),
+                            responder: None,
                         })
                     }
                     NodeMessage::DeployGossiper(message) => {
@@ -230,17 +273,31 @@ impl reactor::Reactor for Reactor {
             Event::NetworkAnnouncement(NetworkAnnouncement::GossipOurAddress(_)) => {
                 unreachable!("should not receive announcements of type GossipOurAddress");
             }
+            Event::NetworkAnnouncement(NetworkAnnouncement::PeerAddressVerified(_)) => {
+                unreachable!("should not receive announcements of type PeerAddressVerified");
+            }
             Event::NetworkAnnouncement(NetworkAnnouncement::NewPeer(_)) => {
                 // We do not care about new peers in the gossiper test.
                 Effects::new()
             }
-            Event::ApiServerAnnouncement(ApiServerAnnouncement::DeployReceived { deploy }) => {
+            Event::ApiServerAnnouncement(ApiServerAnnouncement::DeployReceived {
+                deploy,
+                responder,
+            }) => {
                 let event = deploy_acceptor::Event::Accept {
                     deploy,
                     source: Source::<NodeId>::Client,
+                    responder,
                 };
                 self.dispatch_event(effect_builder, rng, Event::DeployAcceptor(event))
             }
+            Event::ApiServerAnnouncement(ApiServerAnnouncement::DryRunDeployReceived {
+                deploy,
+                responder,
+            }) => {
+                let event = deploy_acceptor::Event::Verify { deploy, responder };
+                self.dispatch_event(effect_builder, rng, Event::DeployAcceptor(event))
+            }
             Event::DeployAcceptorAnnouncement(DeployAcceptorAnnouncement::AcceptedNewDeploy {
                 deploy,
                 source,
@@ -249,7 +306,19 @@ impl reactor::Reactor for Reactor {
                     item_id: *deploy.id(),
                     source,
                 };
-                self.dispatch_event(effect_builder, rng, Event::DeployGossiper(event))
+                let mut effects =
+                    self.dispatch_event(effect_builder, rng, Event::DeployGossiper(event));
+
+                let event = fetcher::Event::GotRemotely {
+                    item: deploy,
+                    source,
+                };
+                effects.extend(self.dispatch_event(
+                    effect_builder,
+                    rng,
+                    Event::DeployFetcher(event),
+                ));
+                effects
             }
             Event::DeployAcceptorAnnouncement(DeployAcceptorAnnouncement::InvalidDeploy {
                 deploy: _,
@@ -273,7 +342,11 @@ impl NetworkedReactor for Reactor {
 fn announce_deploy_received(
     deploy: Box<Deploy>,
 ) -> impl FnOnce(EffectBuilder<Event>) -> Effects<Event> {
-    |effect_builder: EffectBuilder<Event>| effect_builder.announce_deploy_received(deploy).ignore()
+    |effect_builder: EffectBuilder<Event>| {
+        effect_builder
+            .announce_deploy_received(deploy, None)
+            .ignore()
+    }
 }
 
 async fn run_gossip(rng: &mut TestRng, network_size: usize, deploy_count: usize) {
@@ -498,3 +571,65 @@ async fn should_timeout_gossip_response() {
 
     NetworkController::<NodeMessage>::remove_active();
 }
+
+#[tokio::test]
+async fn should_neither_store_nor_gossip_deploy_with_invalid_wasm() {
+    const NETWORK_SIZE: usize = 2;
+    const QUIET_FOR: Duration = Duration::from_millis(50);
+    const TIMEOUT: Duration = Duration::from_secs(2);
+
+    NetworkController::<NodeMessage>::create_active();
+    let mut network = Network::<Reactor>::new();
+    let mut rng = TestRng::new();
+
+    // Add `NETWORK_SIZE` nodes.
+    let node_ids = network.add_nodes(&mut rng, NETWORK_SIZE).await;
+
+    // Create a deploy whose session module bytes are truncated.
+    let mut module_bytes = wabt::wat2wasm("(module (memory 1))").unwrap();
+    module_bytes.truncate(module_bytes.len() - 1);
+    let session = ExecutableDeployItem::ModuleBytes {
+        module_bytes,
+        args: vec![],
+    };
+    let standard_payment = ExecutableDeployItem::ModuleBytes {
+        module_bytes: vec![],
+        args: vec![],
+    };
+    let deploy = Box::new(Deploy::new(
+        Timestamp::now(),
+        TimeDiff::from(60_000),
+        1,
+        vec![],
+        String::from("casper-example"),
+        standard_payment,
+        session,
+        &SecretKey::random(&mut rng),
+        &mut rng,
+    ));
+    let deploy_id = *deploy.id();
+
+    // Give the deploy to node 0 and run it until the deploy has been rejected.
+    network
+        .process_injected_effect_on(&node_ids[0], announce_deploy_received(deploy))
+        .await;
+    let rejected_deploy = |event: &Event| -> bool {
+        matches!(
+            event,
+            Event::DeployAcceptorAnnouncement(DeployAcceptorAnnouncement::InvalidDeploy { .. })
+        )
+    };
+    network
+        .crank_until(&node_ids[0], &mut rng, rejected_deploy, TIMEOUT)
+        .await;
+    network.settle(&mut rng, QUIET_FOR, TIMEOUT).await;
+
+    // Check no node has stored or started gossiping the deploy.
+    for runner in network.nodes().values() {
+        let reactor = runner.reactor().inner();
+        assert!(reactor.storage.deploy_store().ids().unwrap().is_empty());
+        assert!(!reactor.deploy_gossiper.table.has_entry(&deploy_id));
+    }
+
+    NetworkController::<NodeMessage>::remove_active();
+}
@@ -461,6 +461,15 @@ where
     pub fn node_id(&self) -> NodeId {
         self.node_id
     }
+
+    /// Returns the number of other nodes on the network.
+    pub fn peer_count(&self) -> usize {
+        self.nodes
+            .read()
+            .expect("network lock poisoned")
+            .len()
+            .saturating_sub(1)
+    }
 }
 
 impl<P> InMemoryNetwork<P>
@@ -532,6 +541,24 @@ where
 
                 responder.respond(()).ignore()
             }
+            NetworkRequest::BroadcastExcept {
+                payload,
+                exclude,
+                responder,
+            } => {
+                if let Ok(guard) = self.nodes.read() {
+                    for dest in guard
+                        .keys()
+                        .filter(|&node_id| !exclude.contains(node_id) && node_id != &self.node_id)
+                    {
+                        self.send(&guard, *dest, payload.clone());
+                    }
+                } else {
+                    error!("network lock has been poisoned")
+                };
+
+                responder.respond(()).ignore()
+            }
             NetworkRequest::Gossip {
                 payload,
                 count,
@@ -1,17 +1,19 @@
+mod tests;
+
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, HashSet},
     fmt::{self, Display, Formatter},
     marker::PhantomData,
 };
 
 use datasize::DataSize;
 use derive_more::From;
-use futures::FutureExt;
 use tracing::{debug, error, info, warn};
 
 use super::{storage::Storage, Component};
 use crate::{
-    crypto::asymmetric_key::Signature,
+    components::consensus::EraId,
+    crypto::asymmetric_key::{PublicKey, Signature},
     effect::{
         announcements::LinearChainAnnouncement,
         requests::{ConsensusRequest, LinearChainRequest, NetworkRequest, StorageRequest},
@@ -19,8 +21,8 @@ use crate::{
     },
     protocol::Message,
     types::{
-        json_compatibility::ExecutionResult, Block, BlockByHeight, BlockHash, CryptoRngCore,
-        DeployHash,
+        json_compatibility::ExecutionResult, Block, BlockByHeight, BlockHash, BlockHeader,
+        BlockRange, BlockRangeId, CryptoRngCore, DeployHash,
     },
 };
 
@@ -42,8 +44,11 @@ pub enum Event<I> {
     GetBlockByHeightResult(u64, Option<Box<Block>>, I),
     /// A continuation for `BlockAtHeightLocal` scenario.
     GetBlockByHeightResultLocal(u64, Option<Box<Block>>, Responder<Option<Block>>),
-    /// New finality signature.
-    NewFinalitySignature(BlockHash, Signature),
+    /// A continuation for `BlockRange` scenario.
+    GetBlockRangeResult(BlockRangeId, Vec<Block>, I),
+    /// New finality signature of a block in the given era, along with the public key of the
+    /// signer.
+    NewFinalitySignature(BlockHash, EraId, Box<PublicKey>, Box<Signature>),
     /// The result of putting a block to storage.
     PutBlockResult {
         /// The block.
@@ -51,6 +56,13 @@ pub enum Event<I> {
         /// The deploys' execution results.
         execution_results: HashMap<DeployHash, ExecutionResult>,
     },
+    /// The result of putting a block's execution results to storage.
+    PutExecutionResultsResult {
+        /// The header of the stored block.
+        block_header: Box<BlockHeader>,
+        /// The deploys' execution results.
+        execution_results: HashMap<DeployHash, ExecutionResult>,
+    },
 }
 
 impl<I: Display> Display for Event<I> {
@@ -67,12 +79,17 @@ impl<I: Display> Display for Event<I> {
                 peer,
                 maybe_block.is_some()
             ),
-            Event::NewFinalitySignature(block_hash, _) => write!(
+            Event::NewFinalitySignature(block_hash, _, public_key, _) => write!(
                 f,
-                "linear-chain new finality signature for block: {}",
-                block_hash
+                "linear-chain new finality signature for block: {} by {}",
+                block_hash, public_key
             ),
             Event::PutBlockResult { .. } => write!(f, "linear-chain put-block result"),
+            Event::PutExecutionResultsResult { block_header, .. } => write!(
+                f,
+                "linear-chain put-execution-results result for {}",
+                block_header.hash()
+            ),
             Event::GetBlockByHeightResult(height, result, peer) => write!(
                 f,
                 "linear chain get-block-height for height {} from {} found: {}",
@@ -80,6 +97,13 @@ impl<I: Display> Display for Event<I> {
                 peer,
                 result.is_some()
             ),
+            Event::GetBlockRangeResult(block_range_id, blocks, peer) => write!(
+                f,
+                "linear chain get-block-range for {} from {} found: {}",
+                block_range_id,
+                peer,
+                blocks.len()
+            ),
             Event::GetBlockByHeightResultLocal(height, block, _) => write!(
                 f,
                 "linear chain get-block-height-local for height={} found={}",
@@ -95,6 +119,9 @@ pub(crate) struct LinearChain<I> {
     /// A temporary workaround.
     // TODO: Refactor to proper LRU cache.
     linear_chain: Vec<Block>,
+    /// The signers of every block for which a finality signature has been accepted, by era, used
+    /// to ensure each signature is only appended and announced once.
+    finality_signatures: BTreeMap<EraId, HashSet<(BlockHash, PublicKey)>>,
     _marker: PhantomData<I>,
 }
 
@@ -102,6 +129,7 @@ impl<I> LinearChain<I> {
     pub fn new() -> Self {
         LinearChain {
             linear_chain: Vec::new(),
+            finality_signatures: BTreeMap::new(),
             _marker: PhantomData,
         }
     }
@@ -132,22 +160,35 @@ where
         match event {
             Event::Request(LinearChainRequest::BlockRequest(block_hash, sender)) => effect_builder
                 .get_block_from_storage(block_hash)
-                .event(move |maybe_block| Event::GetBlockResult(block_hash, maybe_block.map(Box::new), sender)),
+                .event(move |maybe_block| {
+                    Event::GetBlockResult(block_hash, maybe_block.map(Box::new), sender)
+                }),
             Event::Request(LinearChainRequest::BlockAtHeightLocal(height, responder)) => {
                 effect_builder
                     .get_block_at_height(height)
-                    .event(move |block| Event::GetBlockByHeightResultLocal(height, block.map(Box::new), responder))
+                    .event(move |block| {
+                        Event::GetBlockByHeightResultLocal(height, block.map(Box::new), responder)
+                    })
             }
             Event::Request(LinearChainRequest::BlockAtHeight(height, sender)) => {
                 // Treat `linear_chain` as a cache of least-recently asked for blocks.
                 // match self.linear_chain.get(height as usize).cloned() {
                 //     Some(block) => effect_builder
                 //         .immediately()
-                //         .event(move |_| Event::GetBlockByHeightResult(height, Some(block), sender)),
-                //     None =>
+                //         .event(move |_| Event::GetBlockByHeightResult(height, Some(block),
+                // sender)),     None =>
                 effect_builder
                     .get_block_at_height(height)
-                    .event(move |maybe_block| Event::GetBlockByHeightResult(height, maybe_block.map(Box::new), sender))
+                    .event(move |maybe_block| {
+                        Event::GetBlockByHeightResult(height, maybe_block.map(Box::new), sender)
+                    })
+            }
+            Event::Request(LinearChainRequest::BlockRange(block_range_id, sender)) => {
+                // Serve no more than `MAX_BLOCK_RANGE_LENGTH` blocks, regardless of the request.
+                let capped_id = block_range_id.capped();
+                effect_builder
+                    .get_block_range(capped_id.start_height(), capped_id.count())
+                    .event(move |blocks| Event::GetBlockRangeResult(block_range_id, blocks, sender))
             }
             Event::GetBlockByHeightResultLocal(_height, block, responder) => {
                 responder.respond(block.map(|boxed| *boxed)).ignore()
@@ -157,7 +198,7 @@ where
                     None => {
                         debug!("failed to get {} for {}", block_height, sender);
                         BlockByHeight::Absent(block_height)
-                    },
+                    }
                     Some(block) => BlockByHeight::new(*block),
                 };
                 match Message::new_get_response(&block_at_height) {
@@ -168,27 +209,45 @@ where
                     }
                 }
             }
-            Event::GetBlockResult(block_hash, maybe_block, sender) => {
-                match maybe_block {
-                    None => {
-                        debug!("failed to get {} for {}", block_hash, sender);
+            Event::GetBlockRangeResult(block_range_id, blocks, sender) => {
+                if blocks.is_empty() {
+                    debug!("failed to get {} for {}", block_range_id, sender);
+                }
+                let block_range = BlockRange::new(block_range_id, blocks);
+                match Message::new_get_response(&block_range) {
+                    Ok(message) => effect_builder.send_message(sender, message).ignore(),
+                    Err(error) => {
+                        error!("failed to create get-response {}", error);
                         Effects::new()
-                    },
-                    Some(block) => match Message::new_get_response(&*block) {
-                        Ok(message) => effect_builder.send_message(sender, message).ignore(),
-                        Err(error) => {
-                            error!("failed to create get-response {}", error);
-                            Effects::new()
-                        }
                     }
                 }
             }
-            Event::LinearChainBlock{ block, execution_results } => {
-                effect_builder
-                .put_block_to_storage(block.clone())
-                .event(move |_| Event::PutBlockResult{ block, execution_results })
+            Event::GetBlockResult(block_hash, maybe_block, sender) => match maybe_block {
+                None => {
+                    debug!("failed to get {} for {}", block_hash, sender);
+                    Effects::new()
+                }
+                Some(block) => match Message::new_get_response(&*block) {
+                    Ok(message) => effect_builder.send_message(sender, message).ignore(),
+                    Err(error) => {
+                        error!("failed to create get-response {}", error);
+                        Effects::new()
+                    }
+                },
             },
-            Event::PutBlockResult { block, execution_results } => {
+            Event::LinearChainBlock {
+                block,
+                execution_results,
+            } => effect_builder
+                .put_block_to_storage(block.clone())
+                .event(move |_| Event::PutBlockResult {
+                    block,
+                    execution_results,
+                }),
+            Event::PutBlockResult {
+                block,
+                execution_results,
+            } => {
                 // TODO: Remove once we can return all linear chain blocks from persistent storage.
                 self.linear_chain.push(*block.clone());
 
@@ -197,28 +256,85 @@ where
                 let era_id = block_header.era_id();
                 let height = block_header.height();
                 info!(?block_hash, ?era_id, ?height, "Linear chain block stored.");
-                let mut effects = effect_builder.put_execution_results_to_storage(block_hash, execution_results).ignore();
+                // Signatures are only expected for blocks of this era and the previous one.
+                self.finality_signatures = self
+                    .finality_signatures
+                    .split_off(&EraId(era_id.0.saturating_sub(1)));
+                // Only announce the block and its deploys once the execution results are stored
+                // too, so that everything announced can be read back from storage.
+                effect_builder
+                    .put_execution_results_to_storage(block_hash, execution_results.clone())
+                    .event(move |_| Event::PutExecutionResultsResult {
+                        block_header: Box::new(block_header),
+                        execution_results,
+                    })
+            }
+            Event::PutExecutionResultsResult {
+                block_header,
+                execution_results,
+            } => {
+                let block_hash = block_header.hash();
+                let era_id = block_header.era_id();
+                let mut effects = Effects::new();
+                for (deploy_hash, execution_result) in execution_results {
+                    effects.extend(
+                        effect_builder
+                            .announce_deploy_processed(deploy_hash, block_hash, execution_result)
+                            .ignore(),
+                    );
+                }
+                // Only request our signature once the block has been announced, so that its
+                // finality signatures are announced after it.
                 effects.extend(
-                    effect_builder.handle_linear_chain_block(block_header.clone())
-                    .event(move |signature| Event::NewFinalitySignature(block_hash, signature)));
-                effects.extend(effect_builder.announce_block_added(block_hash, block_header).ignore());
+                    async move {
+                        effect_builder
+                            .announce_block_added(block_hash, (*block_header).clone())
+                            .await;
+                        effect_builder
+                            .handle_linear_chain_block(*block_header)
+                            .await
+                    }
+                    .event(move |(public_key, signature)| {
+                        Event::NewFinalitySignature(
+                            block_hash,
+                            era_id,
+                            Box::new(public_key),
+                            Box::new(signature),
+                        )
+                    }),
+                );
                 effects
-            },
-            Event::NewFinalitySignature(block_hash, signature) => {
-                effect_builder
-                    .get_block_from_storage(block_hash)
-                    .then(move |maybe_block| match maybe_block {
-                        Some(mut block) => {
-                            block.append_proof(signature);
-                            effect_builder.put_block_to_storage(Box::new(block))
-                        }
+            }
+            Event::NewFinalitySignature(block_hash, era_id, public_key, signature) => {
+                if !block_hash.verify(&signature, &public_key) {
+                    warn!(%block_hash, %public_key, "received an invalid finality signature");
+                    return Effects::new();
+                }
+                if !self
+                    .finality_signatures
+                    .entry(era_id)
+                    .or_default()
+                    .insert((block_hash, *public_key))
+                {
+                    debug!(%block_hash, %public_key, "ignoring duplicate finality signature");
+                    return Effects::new();
+                }
+                async move {
+                    let mut block = match effect_builder.get_block_from_storage(block_hash).await {
+                        Some(block) => block,
                         None => {
                             warn!("Received a signature for {} but block was not found in the Linear chain storage", block_hash);
                             panic!("Unhandled")
                         }
-                    })
-                    .ignore()
-            },
+                    };
+                    block.append_proof(*signature);
+                    effect_builder.put_block_to_storage(Box::new(block)).await;
+                    effect_builder
+                        .announce_finality_signature(block_hash, era_id, public_key, signature)
+                        .await
+                }
+                .ignore()
+            }
         }
     }
 }
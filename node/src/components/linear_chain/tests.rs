@@ -0,0 +1,381 @@
+#![cfg(test)]
+use std::time::Duration;
+
+use derive_more::From;
+use prometheus::Registry;
+use rand::Rng;
+use tempfile::TempDir;
+use thiserror::Error;
+
+use super::*;
+use crate::{
+    components::{in_memory_network::NodeId, storage},
+    crypto::asymmetric_key::{PublicKey, SecretKey},
+    effect::EffectBuilder,
+    reactor::{self, EventQueueHandle, Runner},
+    testing::TestRng,
+    types::Deploy,
+    utils::WithDir,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Top-level event for the reactor.
+#[derive(Debug, From)]
+#[must_use]
+enum Event {
+    #[from]
+    Storage(storage::Event<Storage>),
+    #[from]
+    LinearChain(super::Event<NodeId>),
+    #[from]
+    ConsensusRequest(ConsensusRequest),
+    #[from]
+    NetworkRequest(NetworkRequest<NodeId, Message>),
+    #[from]
+    LinearChainAnnouncement(LinearChainAnnouncement),
+    /// The result of reading back a processed deploy's block and execution result from storage.
+    ReadBack {
+        deploy_hash: DeployHash,
+        block_hash: BlockHash,
+        block_stored: bool,
+        execution_result_stored: bool,
+    },
+}
+
+impl From<StorageRequest<Storage>> for Event {
+    fn from(request: StorageRequest<Storage>) -> Self {
+        Event::Storage(storage::Event::Request(request))
+    }
+}
+
+impl Display for Event {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Storage(event) => write!(formatter, "storage: {}", event),
+            Event::LinearChain(event) => write!(formatter, "linear chain: {}", event),
+            Event::ConsensusRequest(req) => write!(formatter, "consensus request: {}", req),
+            Event::NetworkRequest(req) => write!(formatter, "network request: {}", req),
+            Event::LinearChainAnnouncement(ann) => {
+                write!(formatter, "linear chain announcement: {}", ann)
+            }
+            Event::ReadBack { deploy_hash, .. } => write!(formatter, "read back {}", deploy_hash),
+        }
+    }
+}
+
+/// Error type returned by the test reactor.
+#[derive(Debug, Error)]
+enum Error {
+    #[error("prometheus (metrics) error: {0}")]
+    Metrics(#[from] prometheus::Error),
+}
+
+struct Reactor {
+    storage: Storage,
+    linear_chain: LinearChain<NodeId>,
+    secret_key: SecretKey,
+    /// The deploys announced as processed, along with the hash of their block.
+    processed_deploys: Vec<(DeployHash, BlockHash)>,
+    /// Whether the block has been announced as added.
+    block_added: bool,
+    /// The block hash and signer of each finality signature announced, in order.
+    finality_signatures: Vec<(BlockHash, PublicKey)>,
+    _storage_tempdir: TempDir,
+}
+
+impl reactor::Reactor for Reactor {
+    type Event = Event;
+    type Config = ();
+    type Error = Error;
+
+    fn new(
+        _config: Self::Config,
+        registry: &Registry,
+        _event_queue: EventQueueHandle<Self::Event>,
+        rng: &mut dyn CryptoRngCore,
+    ) -> Result<(Self, Effects<Self::Event>), Self::Error> {
+        let (storage_config, _storage_tempdir) = storage::Config::default_for_tests();
+        let storage = Storage::new(
+            WithDir::new(_storage_tempdir.path(), storage_config),
+            registry,
+        )
+        .unwrap();
+
+        let reactor = Reactor {
+            storage,
+            linear_chain: LinearChain::new(),
+            secret_key: SecretKey::new_ed25519(rng.gen()),
+            processed_deploys: Vec::new(),
+            block_added: false,
+            finality_signatures: Vec::new(),
+            _storage_tempdir,
+        };
+
+        Ok((reactor, Effects::new()))
+    }
+
+    fn dispatch_event(
+        &mut self,
+        effect_builder: EffectBuilder<Self::Event>,
+        rng: &mut dyn CryptoRngCore,
+        event: Event,
+    ) -> Effects<Self::Event> {
+        match event {
+            Event::Storage(event) => reactor::wrap_effects(
+                Event::Storage,
+                self.storage.handle_event(effect_builder, rng, event),
+            ),
+            Event::LinearChain(event) => reactor::wrap_effects(
+                Event::LinearChain,
+                self.linear_chain.handle_event(effect_builder, rng, event),
+            ),
+            Event::ConsensusRequest(ConsensusRequest::HandleLinearBlock(
+                block_header,
+                responder,
+            )) => {
+                let public_key = PublicKey::from(&self.secret_key);
+                let signature = block_header.hash().sign(&self.secret_key, &public_key, rng);
+                responder.respond((public_key, signature)).ignore()
+            }
+            Event::NetworkRequest(req) => panic!("should not get network request {}", req),
+            Event::LinearChainAnnouncement(LinearChainAnnouncement::DeployProcessed {
+                deploy_hash,
+                block_hash,
+                ..
+            }) => {
+                // Read back what a client would query on receiving the announcement.
+                async move {
+                    let block = effect_builder
+                        .get_block_from_storage::<Storage>(block_hash)
+                        .await;
+                    let deploy_and_metadata = effect_builder
+                        .get_deploy_and_metadata_from_storage::<Storage>(deploy_hash)
+                        .await;
+                    let execution_result_stored = deploy_and_metadata
+                        .map_or(false, |(_, metadata)| {
+                            metadata.execution_results.contains_key(&block_hash)
+                        });
+                    (block.is_some(), execution_result_stored)
+                }
+                .event(move |(block_stored, execution_result_stored)| {
+                    Event::ReadBack {
+                        deploy_hash,
+                        block_hash,
+                        block_stored,
+                        execution_result_stored,
+                    }
+                })
+            }
+            Event::LinearChainAnnouncement(LinearChainAnnouncement::BlockAdded { .. }) => {
+                self.block_added = true;
+                Effects::new()
+            }
+            Event::LinearChainAnnouncement(LinearChainAnnouncement::FinalitySignature {
+                block_hash,
+                public_key,
+                ..
+            }) => {
+                assert!(
+                    self.block_added,
+                    "finality signature should be announced after the block"
+                );
+                self.finality_signatures.push((block_hash, *public_key));
+                Effects::new()
+            }
+            Event::ReadBack {
+                deploy_hash,
+                block_hash,
+                block_stored,
+                execution_result_stored,
+            } => {
+                assert!(block_stored, "block {} should be stored", block_hash);
+                assert!(
+                    execution_result_stored,
+                    "execution result of {} in {} should be stored",
+                    deploy_hash, block_hash
+                );
+                self.processed_deploys.push((deploy_hash, block_hash));
+                Effects::new()
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn should_announce_processed_deploys_only_once_stored() {
+    let mut rng = TestRng::new();
+    let mut runner = Runner::<Reactor>::new((), &mut rng).await.unwrap();
+
+    let deploys: Vec<Deploy> = (0..3).map(|_| Deploy::random(&mut rng)).collect();
+    let block = Block::random(&mut rng);
+    let block_hash = *block.hash();
+    let execution_results: HashMap<DeployHash, ExecutionResult> = deploys
+        .iter()
+        .map(|deploy| (*deploy.id(), ExecutionResult::random(&mut rng)))
+        .collect();
+
+    // The deploys are stored before their block is executed.
+    let stored_deploys = deploys.clone();
+    runner
+        .process_injected_effects(move |effect_builder| {
+            async move {
+                for deploy in stored_deploys {
+                    effect_builder
+                        .put_deploy_to_storage::<Storage>(Box::new(deploy))
+                        .await;
+                }
+            }
+            .event(move |_| {
+                Event::LinearChain(super::Event::LinearChainBlock {
+                    block: Box::new(block),
+                    execution_results,
+                })
+            })
+        })
+        .await;
+
+    // Each processed deploy is read back, asserting the block and execution result are stored.
+    tokio::time::timeout(TIMEOUT, async {
+        while !runner.reactor().block_added
+            || runner.reactor().processed_deploys.len() < deploys.len()
+        {
+            runner.crank(&mut rng).await;
+        }
+    })
+    .await
+    .expect("should announce the block and deploys before timing out");
+
+    let mut processed_deploys = runner.reactor().processed_deploys.clone();
+    processed_deploys.sort();
+    let mut expected: Vec<(DeployHash, BlockHash)> = deploys
+        .iter()
+        .map(|deploy| (*deploy.id(), block_hash))
+        .collect();
+    expected.sort();
+    assert_eq!(processed_deploys, expected);
+}
+
+/// Injects `event` into the runner's queue.
+async fn inject(runner: &mut Runner<Reactor>, event: Event) {
+    runner
+        .process_injected_effects(move |effect_builder| {
+            effect_builder.immediately().event(move |_| event)
+        })
+        .await;
+}
+
+/// Adds a random block with no deploys to the linear chain, cranking until the block's finality
+/// signature has been announced.  Returns the block's hash and era.
+async fn add_block(runner: &mut Runner<Reactor>, rng: &mut TestRng) -> (BlockHash, EraId) {
+    let block = Block::random(rng);
+    let block_hash = *block.hash();
+    let era_id = block.header().era_id();
+    inject(
+        runner,
+        Event::LinearChain(super::Event::LinearChainBlock {
+            block: Box::new(block),
+            execution_results: HashMap::new(),
+        }),
+    )
+    .await;
+
+    tokio::time::timeout(TIMEOUT, async {
+        while runner.reactor().finality_signatures.is_empty() {
+            runner.crank(rng).await;
+        }
+    })
+    .await
+    .expect("should announce the finality signature before timing out");
+    (block_hash, era_id)
+}
+
+/// Returns a `NewFinalitySignature` event for `block_hash` in `era_id` signed by `secret_key`, but
+/// claiming to be signed by `public_key`.
+fn finality_signature(
+    block_hash: BlockHash,
+    era_id: EraId,
+    secret_key: &SecretKey,
+    public_key: PublicKey,
+    rng: &mut TestRng,
+) -> Event {
+    let signature = block_hash.sign(secret_key, &PublicKey::from(secret_key), rng);
+    Event::LinearChain(super::Event::NewFinalitySignature(
+        block_hash,
+        era_id,
+        Box::new(public_key),
+        Box::new(signature),
+    ))
+}
+
+#[tokio::test]
+async fn should_announce_finality_signature_after_block_added() {
+    let mut rng = TestRng::new();
+    let mut runner = Runner::<Reactor>::new((), &mut rng).await.unwrap();
+
+    let (block_hash, _) = add_block(&mut runner, &mut rng).await;
+
+    let public_key = PublicKey::from(&runner.reactor().secret_key);
+    assert!(runner.reactor().block_added);
+    assert_eq!(
+        runner.reactor().finality_signatures,
+        vec![(block_hash, public_key)]
+    );
+}
+
+#[tokio::test]
+async fn should_not_announce_duplicate_or_invalid_finality_signatures() {
+    let mut rng = TestRng::new();
+    let mut runner = Runner::<Reactor>::new((), &mut rng).await.unwrap();
+
+    let (block_hash, era_id) = add_block(&mut runner, &mut rng).await;
+    let own_secret_key = runner.reactor().secret_key.clone();
+    let own_public_key = PublicKey::from(&own_secret_key);
+
+    // A second signature by the same signer is ignored.
+    let duplicate = finality_signature(
+        block_hash,
+        era_id,
+        &own_secret_key,
+        own_public_key,
+        &mut rng,
+    );
+    inject(&mut runner, duplicate).await;
+    runner.crank(&mut rng).await;
+
+    // A signature which doesn't match the claimed signer is ignored.
+    let other_secret_key = SecretKey::new_ed25519(rng.gen());
+    let unrelated_public_key = PublicKey::from(&SecretKey::new_ed25519(rng.gen()));
+    let invalid = finality_signature(
+        block_hash,
+        era_id,
+        &other_secret_key,
+        unrelated_public_key,
+        &mut rng,
+    );
+    inject(&mut runner, invalid).await;
+    runner.crank(&mut rng).await;
+
+    // A valid signature by a new signer is announced.
+    let other_public_key = PublicKey::from(&other_secret_key);
+    let valid = finality_signature(
+        block_hash,
+        era_id,
+        &other_secret_key,
+        other_public_key,
+        &mut rng,
+    );
+    inject(&mut runner, valid).await;
+    tokio::time::timeout(TIMEOUT, async {
+        while runner.reactor().finality_signatures.len() < 2 {
+            runner.crank(&mut rng).await;
+        }
+    })
+    .await
+    .expect("should announce the new finality signature before timing out");
+
+    assert_eq!(
+        runner.reactor().finality_signatures,
+        vec![(block_hash, own_public_key), (block_hash, other_public_key)]
+    );
+}
@@ -4,16 +4,21 @@
 //!
 //! Steps are:
 //! 1. Fetch blocks up to initial, trusted hash (blocks are downloaded starting from trusted hash up
-//! until Genesis).
+//! until Genesis). The trusted block must match the configured trusted height, if any.
 //! 2. Fetch deploys of the lowest height block.
 //! 3. Execute that block.
 //! 4. Repeat steps 2-3 until trusted hash is reached.
 //! 5. Transition to `SyncingDescendants` state.
-//! 6. Fetch child block of highest block.
+//! 6. Fetch child block of highest block (or a range of consecutive descendants, if peers serve
+//! them).
 //! 7. Fetch deploys of that block.
 //! 8. Execute that block.
 //! 9. Repeat steps 6-8 as long as there's a child in the linear chain.
 //!
+//! Every block downloaded from a peer is checked to hash to its claimed hash, and to be the parent
+//! (or child) of the block fetched before it, so that only a chain consistent with the trusted hash
+//! is accepted. If no peer can provide such a chain up until Genesis, the node gives up.
+//!
 //! The order of "download block – download deploys – execute" block steps differ,
 //! in order to increase the chances of catching up with the linear chain quicker.
 //! When synchronizing linear chain up to the trusted hash we cannot execute later blocks without
@@ -24,19 +29,23 @@
 //! we might miss more eras.
 
 mod event;
+mod tests;
 
 use datasize::DataSize;
 
 use super::{fetcher::FetchResult, storage::Storage, Component};
 use crate::{
     effect::{self, EffectBuilder, EffectExt, EffectOptionExt, Effects},
-    types::{Block, BlockByHeight, BlockHash, BlockHeader, CryptoRngCore, FinalizedBlock},
+    types::{
+        Block, BlockByHeight, BlockHash, BlockHeader, BlockRange, CryptoRngCore, FinalizedBlock,
+        MAX_BLOCK_RANGE_LENGTH,
+    },
 };
 use effect::requests::{
     BlockExecutorRequest, BlockValidationRequest, FetcherRequest, StorageRequest,
 };
-use event::BlockByHeightResult;
 pub use event::Event;
+use event::{BlockByHeightResult, BlockRangeResult};
 use rand::{seq::SliceRandom, Rng};
 use std::{fmt::Display, mem};
 use tracing::{error, info, trace, warn};
@@ -45,6 +54,7 @@ pub trait ReactorEventT<I>:
     From<StorageRequest<Storage>>
     + From<FetcherRequest<I, Block>>
     + From<FetcherRequest<I, BlockByHeight>>
+    + From<FetcherRequest<I, BlockRange>>
     + From<BlockValidationRequest<BlockHeader, I>>
     + From<BlockExecutorRequest>
     + Send
@@ -55,6 +65,7 @@ impl<I, REv> ReactorEventT<I> for REv where
     REv: From<StorageRequest<Storage>>
         + From<FetcherRequest<I, Block>>
         + From<FetcherRequest<I, BlockByHeight>>
+        + From<FetcherRequest<I, BlockRange>>
         + From<BlockValidationRequest<BlockHeader, I>>
         + From<BlockExecutorRequest>
         + Send
@@ -69,6 +80,8 @@ enum State {
     SyncingTrustedHash {
         /// Linear chain block to start sync from.
         trusted_hash: BlockHash,
+        /// Expected height of the trusted block, if configured.
+        trusted_height: Option<u64>,
         /// During synchronization we might see new eras being created.
         /// Track the highest height and wait until it's handled by consensus.
         highest_block_seen: u64,
@@ -85,7 +98,11 @@ enum State {
         trusted_hash: BlockHash,
         /// Linear chain block being downloaded.
         linear_chain_block: Box<Option<BlockHeader>>,
-        /// Block we received from a node and are currently executing.
+        /// Blocks received as part of a block range which are yet to be executed.
+        /// Stored in descending order of height, so that we can `pop()` the next one.
+        pending_blocks: Vec<BlockHeader>,
+        /// Block we received from a node and are currently executing, or the latest block
+        /// executed. The next descendant downloaded must be its child.
         current_block: Box<Option<BlockHeader>>,
         /// During synchronization we might see new eras being created.
         /// Track the highest height and wait until it's handled by consensus.
@@ -115,20 +132,23 @@ impl Display for State {
 }
 
 impl State {
-    fn sync_trusted_hash(trusted_hash: BlockHash) -> Self {
+    fn sync_trusted_hash(trusted_hash: BlockHash, trusted_height: Option<u64>) -> Self {
         State::SyncingTrustedHash {
             trusted_hash,
+            trusted_height,
             highest_block_seen: 0,
             linear_chain: Vec::new(),
             current_block: Box::new(None),
         }
     }
 
-    fn sync_descendants(trusted_hash: BlockHash) -> Self {
+    /// Starts synchronizing the descendants of `latest_block`, the highest block executed so far.
+    fn sync_descendants(trusted_hash: BlockHash, latest_block: BlockHeader) -> Self {
         State::SyncingDescendants {
             trusted_hash,
             linear_chain_block: Box::new(None),
-            current_block: Box::new(None),
+            pending_blocks: Vec::new(),
+            current_block: Box::new(Some(latest_block)),
             highest_block_seen: 0,
         }
     }
@@ -159,15 +179,21 @@ pub(crate) struct LinearChainSync<I> {
     // NOTE: Maybe use a bitmask to decide which peers were tried?.
     peers_to_try: Vec<I>,
     state: State,
+    // Whether to request descendants of the trusted hash in ranges rather than one at a time.
+    // Falls back to `false` once peers stop serving full ranges, i.e. when we're near the tip.
+    block_ranges: bool,
 }
 
 impl<I: Clone + PartialEq + 'static> LinearChainSync<I> {
-    pub fn new(init_hash: Option<BlockHash>) -> Self {
-        let state = init_hash.map_or(State::None, State::sync_trusted_hash);
+    pub fn new(init_hash: Option<BlockHash>, init_height: Option<u64>) -> Self {
+        let state = init_hash.map_or(State::None, |trusted_hash| {
+            State::sync_trusted_hash(trusted_hash, init_height)
+        });
         LinearChainSync {
             peers: Vec::new(),
             peers_to_try: Vec::new(),
             state,
+            block_ranges: true,
         }
     }
 
@@ -247,6 +273,46 @@ impl<I: Clone + PartialEq + 'static> LinearChainSync<I> {
         }
     }
 
+    /// Checks that `block` has the configured trusted height if it is the trusted block.
+    ///
+    /// Panics otherwise, as then the trust anchor is inconsistent and the linear chain can't be
+    /// synchronized.
+    fn check_trusted_height(&self, block: &Block) {
+        if let State::SyncingTrustedHash {
+            trusted_hash,
+            trusted_height: Some(trusted_height),
+            ..
+        } = self.state
+        {
+            if *block.hash() == trusted_hash && block.height() != trusted_height {
+                error!(
+                    %trusted_hash,
+                    %trusted_height,
+                    block_height = %block.height(),
+                    "Trusted block doesn't have the configured trusted height."
+                );
+                panic!(
+                    "Trust anchor mismatch: trusted block {} has height {}, expected {}.",
+                    trusted_hash,
+                    block.height(),
+                    trusted_height
+                )
+            }
+        }
+    }
+
+    /// Returns `true` if `block` is the child of the latest block of the linear chain synchronized
+    /// so far.
+    fn extends_synced_chain(&self, block: &Block) -> bool {
+        match &self.state {
+            State::SyncingDescendants { current_block, .. } => current_block
+                .as_ref()
+                .as_ref()
+                .map_or(false, |parent| is_valid_child(parent, block)),
+            _ => false,
+        }
+    }
+
     fn mark_done(&mut self) {
         self.state = State::Done;
     }
@@ -286,10 +352,9 @@ impl<I: Clone + PartialEq + 'static> LinearChainSync<I> {
                 }
                 if block_height == highest_block_seen {
                     info!(%block_height, "Finished synchronizing linear chain up until trusted hash.");
-                    let peer = self.random_peer_unsafe();
                     // Kick off syncing trusted hash descendants.
-                    self.state = State::sync_descendants(trusted_hash);
-                    fetch_block_at_height(effect_builder, peer, block_height + 1)
+                    self.state = State::sync_descendants(trusted_hash, block_header.clone());
+                    self.fetch_next_block(effect_builder, rng, &block_header)
                 } else {
                     self.state = curr_state;
                     self.fetch_next_block_deploys(effect_builder)
@@ -373,6 +438,16 @@ impl<I: Clone + PartialEq + 'static> LinearChainSync<I> {
         I: Send + Copy + 'static,
         REv: ReactorEventT<I>,
     {
+        // If we already have the next block from a previously fetched range, use it.
+        if let State::SyncingDescendants {
+            ref mut pending_blocks,
+            ..
+        } = self.state
+        {
+            if let Some(next_block) = pending_blocks.pop() {
+                return self.block_downloaded(rng, effect_builder, &next_block);
+            }
+        }
         self.reset_peers(rng);
         let peer = self.random_peer_unsafe();
         match self.state {
@@ -380,6 +455,9 @@ impl<I: Clone + PartialEq + 'static> LinearChainSync<I> {
                 let parent_hash = *block_header.parent_hash();
                 fetch_block_by_hash(effect_builder, peer, parent_hash)
             }
+            State::SyncingDescendants { .. } if self.block_ranges => {
+                fetch_block_range(effect_builder, peer, block_header.clone())
+            }
             State::SyncingDescendants { .. } => {
                 let next_height = block_header.height() + 1;
                 fetch_block_at_height(effect_builder, peer, next_height)
@@ -389,6 +467,36 @@ impl<I: Clone + PartialEq + 'static> LinearChainSync<I> {
             }
         }
     }
+
+    /// Handles a non-empty range of consecutive blocks which has already been checked for validity.
+    ///
+    /// The first block is treated as downloaded, while the rest are kept until the preceding ones
+    /// have been executed.
+    fn block_range_downloaded<REv>(
+        &mut self,
+        rng: &mut dyn CryptoRngCore,
+        effect_builder: EffectBuilder<REv>,
+        blocks: Vec<Block>,
+    ) -> Effects<Event<I>>
+    where
+        I: Send + Copy + 'static,
+        REv: ReactorEventT<I>,
+    {
+        let mut headers: Vec<BlockHeader> = blocks
+            .into_iter()
+            .rev()
+            .map(|block| block.take_header())
+            .collect();
+        let first = headers.pop().expect("block range should not be empty");
+        match self.state {
+            State::SyncingDescendants {
+                ref mut pending_blocks,
+                ..
+            } => *pending_blocks = headers,
+            _ => panic!("Downloaded block range when in {} state.", self.state),
+        }
+        self.block_downloaded(rng, effect_builder, &first)
+    }
 }
 
 impl<I, REv> Component<REv> for LinearChainSync<I>
@@ -456,15 +564,82 @@ where
                             Event::GetBlockHeightResult(block_height, BlockByHeightResult::Absent),
                         );
                     }
+                    if !self.extends_synced_chain(&block) {
+                        warn!(
+                            %block_height,
+                            %peer,
+                            "Block is not a child of the synchronized linear chain."
+                        );
+                        // NOTE: Signal misbehaving validator to networking layer.
+                        self.ban_peer(peer);
+                        return self.handle_event(
+                            effect_builder,
+                            rng,
+                            Event::GetBlockHeightResult(block_height, BlockByHeightResult::Absent),
+                        );
+                    }
                     trace!(%block_height, "Downloaded linear chain block.");
                     self.block_downloaded(rng, effect_builder, block.header())
                 }
             },
+            Event::GetBlockRangeResult(parent, fetch_result) => {
+                let next_height = parent.height() + 1;
+                match fetch_result {
+                    BlockRangeResult::Absent => {
+                        // Peers don't serve ranges starting at `next_height`. Fall back to
+                        // fetching descendants one at a time, which also tells us when we've
+                        // reached the tip.
+                        trace!(%next_height, "Block range not found, fetching single block.");
+                        self.block_ranges = false;
+                        self.reset_peers(rng);
+                        let peer = self.random_peer_unsafe();
+                        fetch_block_at_height(effect_builder, peer, next_height)
+                    }
+                    BlockRangeResult::FromStorage(blocks) => {
+                        // We shouldn't get invalid data from the storage.
+                        // If we do, it's a bug.
+                        assert!(
+                            is_valid_block_range(&parent, &blocks),
+                            "Invalid block range in the local storage."
+                        );
+                        trace!(%next_height, "Linear block range found in the local storage.");
+                        self.block_range_downloaded(rng, effect_builder, blocks)
+                    }
+                    BlockRangeResult::FromPeer(blocks, peer) => {
+                        if !is_valid_block_range(&parent, &blocks) {
+                            warn!(
+                                "Invalid block range starting at height {} from {}.",
+                                next_height, peer
+                            );
+                            // NOTE: Signal misbehaving validator to networking layer.
+                            self.ban_peer(peer);
+                            self.block_ranges = false;
+                            return self.handle_event(
+                                effect_builder,
+                                rng,
+                                Event::GetBlockHeightResult(
+                                    next_height,
+                                    BlockByHeightResult::Absent,
+                                ),
+                            );
+                        }
+                        if (blocks.len() as u64) < MAX_BLOCK_RANGE_LENGTH {
+                            // The peer has no more blocks past this range, so we're close to the
+                            // tip. Continue with single blocks from here on.
+                            self.block_ranges = false;
+                        }
+                        trace!(%next_height, count = blocks.len(), "Downloaded linear chain block range.");
+                        self.block_range_downloaded(rng, effect_builder, blocks)
+                    }
+                }
+            }
             Event::GetBlockHashResult(block_hash, fetch_result) => match fetch_result {
                 None => match self.random_peer() {
                     None => {
                         error!(%block_hash, "Could not download linear block from any of the peers.");
-                        panic!("Failed to download linear chain.")
+                        panic!(
+                            "Failed to download a linear chain consistent with the trusted hash."
+                        )
                     }
                     Some(peer) => fetch_block_by_hash(effect_builder, peer, block_hash),
                 },
@@ -472,6 +647,7 @@ where
                     // We shouldn't get invalid data from the storage.
                     // If we do, it's a bug.
                     assert_eq!(*block.hash(), block_hash, "Block hash mismatch.");
+                    self.check_trusted_height(&block);
                     trace!(%block_hash, "Linear block found in the local storage.");
                     // If we found block in our local storage when syncing trusted hash
                     // it means we have all of its parents as well (if not then that's a bug that
@@ -480,11 +656,13 @@ where
                     self.fetch_next_block_deploys(effect_builder)
                 }
                 Some(FetchResult::FromPeer(block, peer)) => {
-                    if *block.hash() != block_hash {
+                    if *block.hash() != block_hash || block.header().hash() != block_hash {
                         warn!(
-                            "Block hash mismatch. Expected {} got {} from {}.",
+                            "Block hash mismatch. Expected {} got {} (header hashing to {}) from \
+                            {}.",
                             block_hash,
                             block.hash(),
+                            block.header().hash(),
                             peer
                         );
                         // NOTE: Signal misbehaving validator to networking layer.
@@ -497,6 +675,7 @@ where
                             Event::GetBlockHashResult(block_hash, None),
                         );
                     }
+                    self.check_trusted_height(&block);
                     trace!(%block_hash, "Downloaded linear chain block.");
                     self.block_downloaded(rng, effect_builder, block.header())
                 }
@@ -558,8 +737,8 @@ where
 {
     effect_builder
         .validate_block(peer, block_header)
-        .event(move |(found, block_header)| {
-            if found {
+        .event(move |(result, block_header)| {
+            if result.is_ok() {
                 Event::DeploysFound(Box::new(block_header))
             } else {
                 Event::DeploysNotFound(Box::new(block_header))
@@ -621,3 +800,58 @@ where
             move || Event::GetBlockHeightResult(block_height, BlockByHeightResult::Absent),
         )
 }
+
+fn fetch_block_range<I: Send + Copy + 'static, REv>(
+    effect_builder: EffectBuilder<REv>,
+    peer: I,
+    parent: BlockHeader,
+) -> Effects<Event<I>>
+where
+    REv: ReactorEventT<I>,
+{
+    let parent_clone = parent.clone();
+    effect_builder
+        .fetch_block_range(parent.height() + 1, MAX_BLOCK_RANGE_LENGTH, peer)
+        .option(
+            move |fetch_result| {
+                let parent = Box::new(parent);
+                match fetch_result {
+                    FetchResult::FromPeer(range, _) => Event::GetBlockRangeResult(
+                        parent,
+                        BlockRangeResult::FromPeer(range.take_blocks(), peer),
+                    ),
+                    FetchResult::FromStorage(range) => Event::GetBlockRangeResult(
+                        parent,
+                        BlockRangeResult::FromStorage(range.take_blocks()),
+                    ),
+                }
+            },
+            move || Event::GetBlockRangeResult(Box::new(parent_clone), BlockRangeResult::Absent),
+        )
+}
+
+/// Returns `true` if `blocks` is a non-empty run of consecutive descendants of `parent`.
+///
+/// Each block is validated individually: its hash must match its header, and it must be the child
+/// of its predecessor.
+fn is_valid_block_range(parent: &BlockHeader, blocks: &[Block]) -> bool {
+    if blocks.is_empty() {
+        return false;
+    }
+    let mut previous = parent;
+    for block in blocks {
+        if !is_valid_child(previous, block) {
+            return false;
+        }
+        previous = block.header();
+    }
+    true
+}
+
+/// Returns `true` if `block`'s hash matches its header, and it is the child of `parent`.
+fn is_valid_child(parent: &BlockHeader, block: &Block) -> bool {
+    let header = block.header();
+    header.hash() == *block.hash()
+        && header.height() == parent.height() + 1
+        && *header.parent_hash() == parent.hash()
+}
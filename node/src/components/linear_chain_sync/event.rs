@@ -9,6 +9,8 @@ pub enum Event<I> {
     Start(I),
     GetBlockHashResult(BlockHash, Option<FetchResult<Block>>),
     GetBlockHeightResult(u64, BlockByHeightResult<I>),
+    /// Result of fetching a range of blocks following the given parent block.
+    GetBlockRangeResult(Box<BlockHeader>, BlockRangeResult<I>),
     /// Deploys from the block have been found.
     DeploysFound(Box<BlockHeader>),
     /// Deploys from the block have not been found.
@@ -25,6 +27,13 @@ pub enum BlockByHeightResult<I> {
     FromPeer(Box<Block>, I),
 }
 
+#[derive(Debug)]
+pub enum BlockRangeResult<I> {
+    Absent,
+    FromStorage(Vec<Block>),
+    FromPeer(Vec<Block>, I),
+}
+
 impl<I> Display for Event<I>
 where
     I: Debug + Display,
@@ -53,6 +62,12 @@ where
             Event::GetBlockHeightResult(height, res) => {
                 write!(f, "Get block result for height {}: {:?}", height, res)
             }
+            Event::GetBlockRangeResult(parent, res) => write!(
+                f,
+                "Get block range result following height {}: {:?}",
+                parent.height(),
+                res
+            ),
         }
     }
 }
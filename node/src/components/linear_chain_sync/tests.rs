@@ -0,0 +1,592 @@
+#![cfg(test)]
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+    time::Duration,
+};
+
+use derive_more::From;
+use prometheus::Registry;
+use tempfile::TempDir;
+use thiserror::Error;
+
+use super::*;
+use crate::{
+    components::{
+        consensus::EraId,
+        fetcher::{self, Fetcher},
+        in_memory_network::{InMemoryNetwork, NetworkController, NodeId},
+        linear_chain::{self, LinearChain},
+        storage::{self, Storage, StorageType},
+    },
+    crypto::{
+        asymmetric_key::{PublicKey, SecretKey},
+        hash::Digest,
+    },
+    effect::{
+        announcements::{LinearChainAnnouncement, NetworkAnnouncement},
+        requests::{ConsensusRequest, LinearChainRequest, NetworkRequest},
+    },
+    protocol::Message,
+    reactor::{self, EventQueueHandle, Runner},
+    testing::{
+        network::{Network, NetworkedReactor},
+        ConditionCheckReactor, TestRng,
+    },
+    types::{ProtoBlock, Tag, Timestamp},
+    utils::{Source, WithDir},
+    GossipConfig,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Top-level event for the reactor.
+#[derive(Debug, From)]
+#[must_use]
+enum Event {
+    #[from]
+    Storage(storage::Event<Storage>),
+    #[from]
+    LinearChain(linear_chain::Event<NodeId>),
+    #[from]
+    LinearChainSync(super::Event<NodeId>),
+    #[from]
+    BlockFetcher(fetcher::Event<Block>),
+    #[from]
+    BlockByHeightFetcher(fetcher::Event<BlockByHeight>),
+    #[from]
+    BlockRangeFetcher(fetcher::Event<BlockRange>),
+    #[from]
+    BlockFetcherRequest(FetcherRequest<NodeId, Block>),
+    #[from]
+    BlockByHeightFetcherRequest(FetcherRequest<NodeId, BlockByHeight>),
+    #[from]
+    BlockRangeFetcherRequest(FetcherRequest<NodeId, BlockRange>),
+    #[from]
+    BlockValidatorRequest(BlockValidationRequest<BlockHeader, NodeId>),
+    #[from]
+    BlockExecutorRequest(BlockExecutorRequest),
+    #[from]
+    NetworkRequest(NetworkRequest<NodeId, Message>),
+    #[from]
+    NetworkAnnouncement(NetworkAnnouncement<NodeId, Message>),
+    #[from]
+    ConsensusRequest(ConsensusRequest),
+    #[from]
+    LinearChainAnnouncement(LinearChainAnnouncement),
+}
+
+impl From<StorageRequest<Storage>> for Event {
+    fn from(request: StorageRequest<Storage>) -> Self {
+        Event::Storage(storage::Event::Request(request))
+    }
+}
+
+impl From<LinearChainRequest<NodeId>> for Event {
+    fn from(request: LinearChainRequest<NodeId>) -> Self {
+        Event::LinearChain(linear_chain::Event::Request(request))
+    }
+}
+
+impl Display for Event {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Storage(event) => write!(formatter, "storage: {}", event),
+            Event::LinearChain(event) => write!(formatter, "linear chain: {}", event),
+            Event::LinearChainSync(event) => write!(formatter, "linear chain sync: {}", event),
+            Event::BlockFetcher(event) => write!(formatter, "block fetcher: {}", event),
+            Event::BlockByHeightFetcher(event) => {
+                write!(formatter, "block by height fetcher: {}", event)
+            }
+            Event::BlockRangeFetcher(event) => write!(formatter, "block range fetcher: {}", event),
+            Event::BlockFetcherRequest(req) => write!(formatter, "block fetcher request: {}", req),
+            Event::BlockByHeightFetcherRequest(req) => {
+                write!(formatter, "block by height fetcher request: {}", req)
+            }
+            Event::BlockRangeFetcherRequest(req) => {
+                write!(formatter, "block range fetcher request: {}", req)
+            }
+            Event::BlockValidatorRequest(req) => {
+                write!(formatter, "block validator request: {}", req)
+            }
+            Event::BlockExecutorRequest(req) => {
+                write!(formatter, "block executor request: {}", req)
+            }
+            Event::NetworkRequest(req) => write!(formatter, "network request: {}", req),
+            Event::NetworkAnnouncement(ann) => write!(formatter, "network announcement: {}", ann),
+            Event::ConsensusRequest(req) => write!(formatter, "consensus request: {}", req),
+            Event::LinearChainAnnouncement(ann) => {
+                write!(formatter, "linear chain announcement: {}", ann)
+            }
+        }
+    }
+}
+
+/// Error type returned by the test reactor.
+#[derive(Debug, Error)]
+enum Error {
+    #[error("prometheus (metrics) error: {0}")]
+    Metrics(#[from] prometheus::Error),
+}
+
+/// Configuration of the test reactor.
+#[derive(Debug, Default)]
+struct Config {
+    /// Trusted hash to start synchronizing from, if any.
+    trusted_hash: Option<BlockHash>,
+    /// Expected height of the trusted block, if any.
+    trusted_height: Option<u64>,
+    /// Headers of the whole linear chain, indexed by height, used in place of executing blocks.
+    chain: Vec<BlockHeader>,
+    /// Whether to request descendants of the trusted hash in ranges.
+    block_ranges: bool,
+}
+
+struct Reactor {
+    network: InMemoryNetwork<Message>,
+    storage: Storage,
+    linear_chain: LinearChain<NodeId>,
+    linear_chain_sync: LinearChainSync<NodeId>,
+    block_fetcher: Fetcher<Block>,
+    block_by_height_fetcher: Fetcher<BlockByHeight>,
+    block_range_fetcher: Fetcher<BlockRange>,
+    chain: Vec<BlockHeader>,
+    /// Heights of the blocks executed, in order of execution.
+    executed_heights: Vec<u64>,
+    /// Number of `GetRequest` messages sent to peers.
+    get_requests_sent: usize,
+    _storage_tempdir: TempDir,
+}
+
+impl Drop for Reactor {
+    fn drop(&mut self) {
+        NetworkController::<Message>::remove_node(&self.network.node_id())
+    }
+}
+
+impl reactor::Reactor for Reactor {
+    type Event = Event;
+    type Config = Config;
+    type Error = Error;
+
+    fn new(
+        config: Self::Config,
+        registry: &Registry,
+        event_queue: EventQueueHandle<Self::Event>,
+        rng: &mut dyn CryptoRngCore,
+    ) -> Result<(Self, Effects<Self::Event>), Self::Error> {
+        let network = NetworkController::create_node(event_queue, rng);
+
+        let (storage_config, _storage_tempdir) = storage::Config::default_for_tests();
+        let storage = Storage::new(
+            WithDir::new(_storage_tempdir.path(), storage_config),
+            registry,
+        )
+        .unwrap();
+
+        let mut linear_chain_sync =
+            LinearChainSync::new(config.trusted_hash, config.trusted_height);
+        linear_chain_sync.block_ranges = config.block_ranges;
+
+        let gossip_config = GossipConfig::default();
+        let reactor = Reactor {
+            network,
+            storage,
+            linear_chain: LinearChain::new(),
+            linear_chain_sync,
+            block_fetcher: Fetcher::new(gossip_config),
+            block_by_height_fetcher: Fetcher::new(gossip_config),
+            block_range_fetcher: Fetcher::new(gossip_config),
+            chain: config.chain,
+            executed_heights: Vec::new(),
+            get_requests_sent: 0,
+            _storage_tempdir,
+        };
+
+        Ok((reactor, Effects::new()))
+    }
+
+    fn dispatch_event(
+        &mut self,
+        effect_builder: EffectBuilder<Self::Event>,
+        rng: &mut dyn CryptoRngCore,
+        event: Event,
+    ) -> Effects<Self::Event> {
+        match event {
+            Event::Storage(event) => reactor::wrap_effects(
+                Event::Storage,
+                self.storage.handle_event(effect_builder, rng, event),
+            ),
+            Event::LinearChain(event) => reactor::wrap_effects(
+                Event::LinearChain,
+                self.linear_chain.handle_event(effect_builder, rng, event),
+            ),
+            Event::LinearChainSync(event) => reactor::wrap_effects(
+                Event::LinearChainSync,
+                self.linear_chain_sync
+                    .handle_event(effect_builder, rng, event),
+            ),
+            Event::BlockFetcher(event) => reactor::wrap_effects(
+                Event::BlockFetcher,
+                self.block_fetcher.handle_event(effect_builder, rng, event),
+            ),
+            Event::BlockByHeightFetcher(event) => reactor::wrap_effects(
+                Event::BlockByHeightFetcher,
+                self.block_by_height_fetcher
+                    .handle_event(effect_builder, rng, event),
+            ),
+            Event::BlockRangeFetcher(event) => reactor::wrap_effects(
+                Event::BlockRangeFetcher,
+                self.block_range_fetcher
+                    .handle_event(effect_builder, rng, event),
+            ),
+            Event::BlockFetcherRequest(request) => {
+                self.dispatch_event(effect_builder, rng, Event::BlockFetcher(request.into()))
+            }
+            Event::BlockByHeightFetcherRequest(request) => self.dispatch_event(
+                effect_builder,
+                rng,
+                Event::BlockByHeightFetcher(request.into()),
+            ),
+            Event::BlockRangeFetcherRequest(request) => self.dispatch_event(
+                effect_builder,
+                rng,
+                Event::BlockRangeFetcher(request.into()),
+            ),
+            Event::BlockValidatorRequest(BlockValidationRequest {
+                block, responder, ..
+            }) => responder.respond((Ok(()), block)).ignore(),
+            Event::BlockExecutorRequest(BlockExecutorRequest::ExecuteBlock(finalized_block)) => {
+                // Rather than executing the block, look up the expected result in the chain.
+                let height = finalized_block.height();
+                self.executed_heights.push(height);
+                let header = Box::new(self.chain[height as usize].clone());
+                effect_builder
+                    .immediately()
+                    .event(move |_| Event::LinearChainSync(super::Event::BlockHandled(header)))
+            }
+            Event::NetworkRequest(request) => {
+                if let NetworkRequest::SendMessage {
+                    payload: Message::GetRequest { .. },
+                    ..
+                } = request
+                {
+                    self.get_requests_sent += 1;
+                }
+                reactor::wrap_effects(
+                    Event::NetworkRequest,
+                    self.network.handle_event(effect_builder, rng, request),
+                )
+            }
+            Event::NetworkAnnouncement(NetworkAnnouncement::MessageReceived {
+                sender,
+                payload,
+            }) => {
+                let reactor_event = match payload {
+                    Message::GetRequest {
+                        tag: Tag::Block,
+                        serialized_id,
+                    } => {
+                        let block_hash = bincode::deserialize(&serialized_id).unwrap();
+                        Event::LinearChain(linear_chain::Event::Request(
+                            LinearChainRequest::BlockRequest(block_hash, sender),
+                        ))
+                    }
+                    Message::GetRequest {
+                        tag: Tag::BlockByHeight,
+                        serialized_id,
+                    } => {
+                        let height = bincode::deserialize(&serialized_id).unwrap();
+                        Event::LinearChain(linear_chain::Event::Request(
+                            LinearChainRequest::BlockAtHeight(height, sender),
+                        ))
+                    }
+                    Message::GetRequest {
+                        tag: Tag::BlockRange,
+                        serialized_id,
+                    } => {
+                        let block_range_id = bincode::deserialize(&serialized_id).unwrap();
+                        Event::LinearChain(linear_chain::Event::Request(
+                            LinearChainRequest::BlockRange(block_range_id, sender),
+                        ))
+                    }
+                    Message::GetResponse {
+                        tag: Tag::Block,
+                        serialized_item,
+                    } => {
+                        let block = bincode::deserialize(&serialized_item).unwrap();
+                        Event::BlockFetcher(fetcher::Event::GotRemotely {
+                            item: Box::new(block),
+                            source: Source::Peer(sender),
+                        })
+                    }
+                    Message::GetResponse {
+                        tag: Tag::BlockByHeight,
+                        serialized_item,
+                    } => {
+                        let event = match bincode::deserialize(&serialized_item).unwrap() {
+                            BlockByHeight::Absent(block_height) => fetcher::Event::AbsentRemotely {
+                                id: block_height,
+                                peer: sender,
+                            },
+                            block_by_height => fetcher::Event::GotRemotely {
+                                item: Box::new(block_by_height),
+                                source: Source::Peer(sender),
+                            },
+                        };
+                        Event::BlockByHeightFetcher(event)
+                    }
+                    Message::GetResponse {
+                        tag: Tag::BlockRange,
+                        serialized_item,
+                    } => {
+                        let block_range: BlockRange =
+                            bincode::deserialize(&serialized_item).unwrap();
+                        let event = if block_range.is_empty() {
+                            fetcher::Event::AbsentRemotely {
+                                id: block_range.id(),
+                                peer: sender,
+                            }
+                        } else {
+                            fetcher::Event::GotRemotely {
+                                item: Box::new(block_range),
+                                source: Source::Peer(sender),
+                            }
+                        };
+                        Event::BlockRangeFetcher(event)
+                    }
+                    msg => panic!("should not get {}", msg),
+                };
+                self.dispatch_event(effect_builder, rng, reactor_event)
+            }
+            Event::NetworkAnnouncement(ann) => {
+                unreachable!("should not receive announcements of type {:?}", ann);
+            }
+            Event::ConsensusRequest(req) => panic!("should not get consensus request {}", req),
+            Event::LinearChainAnnouncement(ann) => {
+                panic!("should not get linear chain announcement {}", ann)
+            }
+        }
+    }
+}
+
+impl NetworkedReactor for Reactor {
+    type NodeId = NodeId;
+
+    fn node_id(&self) -> NodeId {
+        self.network.node_id()
+    }
+}
+
+/// Creates a valid linear chain of `length` blocks in era 0, starting with the genesis child.
+fn create_chain(rng: &mut TestRng, length: u64) -> Vec<Block> {
+    let proposer = PublicKey::from(&SecretKey::new_ed25519(rng.gen()));
+    let mut parent_hash = BlockHash::new(Digest::random(rng));
+    let mut parent_seed = Digest::random(rng);
+    let mut chain = Vec::new();
+    for height in 0..length {
+        let finalized_block = FinalizedBlock::new(
+            ProtoBlock::new(vec![], rng.gen()),
+            Timestamp::now(),
+            None,
+            EraId(0),
+            height,
+            proposer,
+        );
+        let block = Block::new(
+            parent_hash,
+            parent_seed,
+            Digest::random(rng),
+            finalized_block,
+        );
+        parent_hash = *block.hash();
+        parent_seed = block.header().accumulated_seed();
+        chain.push(block);
+    }
+    chain
+}
+
+/// Creates a network of a node holding all of `chain`, and a joining node created from
+/// `joiner_config` which is connected to it.
+///
+/// Returns the network and the ID of the joining node.
+async fn start_joining(
+    rng: &mut TestRng,
+    chain: &[Block],
+    joiner_config: Config,
+) -> (Network<Reactor>, NodeId) {
+    NetworkController::<Message>::create_active();
+    let mut network = Network::<Reactor>::new();
+
+    let (server, _) = network
+        .add_node_with_config(
+            Config {
+                chain: joiner_config.chain.clone(),
+                ..Default::default()
+            },
+            rng,
+        )
+        .await
+        .unwrap();
+    for block in chain {
+        let block = Box::new(block.clone());
+        network
+            .process_injected_effect_on(&server, move |effect_builder| {
+                effect_builder
+                    .put_block_to_storage::<Storage>(block)
+                    .ignore()
+            })
+            .await;
+    }
+    network
+        .settle(rng, Duration::from_millis(100), TIMEOUT)
+        .await;
+
+    let (joiner, _) = network
+        .add_node_with_config(joiner_config, rng)
+        .await
+        .unwrap();
+    network
+        .process_injected_effect_on(&joiner, move |effect_builder| {
+            effect_builder
+                .immediately()
+                .event(move |_| Event::LinearChainSync(super::Event::NewPeerConnected(server)))
+        })
+        .await;
+
+    (network, joiner)
+}
+
+/// Synchronizes a joining node trusting `chain[trusted_index]` with a node holding all of `chain`,
+/// and returns the number of `GetRequest`s the joining node sent.
+async fn sync_chain_from(
+    rng: &mut TestRng,
+    chain: &[Block],
+    trusted_index: usize,
+    trusted_height: Option<u64>,
+    block_ranges: bool,
+) -> usize {
+    let joiner_config = Config {
+        trusted_hash: Some(*chain[trusted_index].hash()),
+        trusted_height,
+        chain: chain.iter().map(|block| block.header().clone()).collect(),
+        block_ranges,
+    };
+    let (mut network, joiner) = start_joining(rng, chain, joiner_config).await;
+
+    let is_synced = |nodes: &HashMap<NodeId, Runner<ConditionCheckReactor<Reactor>>>| {
+        nodes[&joiner]
+            .reactor()
+            .inner()
+            .linear_chain_sync
+            .is_synced()
+    };
+    network.settle_on(rng, is_synced, TIMEOUT).await;
+
+    let reactor = network.nodes()[&joiner].reactor().inner();
+    let expected_heights: Vec<u64> = (0..chain.len() as u64).collect();
+    assert_eq!(reactor.executed_heights, expected_heights);
+    let get_requests_sent = reactor.get_requests_sent;
+
+    NetworkController::<Message>::remove_active();
+    get_requests_sent
+}
+
+/// Synchronizes a joining node with a node holding all of `chain`, starting from the genesis
+/// child, and returns the number of `GetRequest`s the joining node sent.
+async fn sync_chain(rng: &mut TestRng, chain: &[Block], block_ranges: bool) -> usize {
+    sync_chain_from(rng, chain, 0, None, block_ranges).await
+}
+
+#[tokio::test]
+async fn should_sync_descendants_in_fewer_rounds_with_block_ranges() {
+    const CHAIN_LENGTH: u64 = 70;
+
+    let mut rng = TestRng::new();
+    let chain = create_chain(&mut rng, CHAIN_LENGTH);
+
+    // Without ranges, after fetching the trusted block we request each descendant individually,
+    // plus one more request for the first missing height.
+    let single_block_requests = sync_chain(&mut rng, &chain, false).await;
+    assert_eq!(single_block_requests as u64, 1 + CHAIN_LENGTH);
+
+    // With ranges, we request full ranges of `MAX_BLOCK_RANGE_LENGTH` blocks until the serving
+    // node returns a short one, then fall back to a single request for the first missing height.
+    let block_range_requests = sync_chain(&mut rng, &chain, true).await;
+    let range_count = (CHAIN_LENGTH - 1 + MAX_BLOCK_RANGE_LENGTH - 1) / MAX_BLOCK_RANGE_LENGTH;
+    assert_eq!(block_range_requests as u64, 1 + range_count + 1);
+    assert!(block_range_requests < single_block_requests);
+}
+
+#[test]
+fn should_reject_invalid_block_range() {
+    let mut rng = TestRng::new();
+    let chain = create_chain(&mut rng, 4);
+    let parent = chain[0].header();
+
+    assert!(is_valid_block_range(parent, &chain[1..]));
+    // Empty ranges are invalid.
+    assert!(!is_valid_block_range(parent, &[]));
+    // Ranges must start at the child of the parent.
+    assert!(!is_valid_block_range(parent, &chain[2..]));
+    // Ranges must be contiguous.
+    let gapped = vec![chain[1].clone(), chain[3].clone()];
+    assert!(!is_valid_block_range(parent, &gapped));
+}
+
+#[tokio::test]
+async fn should_sync_with_matching_trust_anchor() {
+    let mut rng = TestRng::new();
+    let chain = create_chain(&mut rng, 10);
+
+    // The ancestors of the trusted block are downloaded and executed before its descendants.
+    let trusted_index = 4;
+    sync_chain_from(
+        &mut rng,
+        &chain,
+        trusted_index,
+        Some(trusted_index as u64),
+        false,
+    )
+    .await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "Trust anchor mismatch")]
+async fn should_fail_fast_on_mismatching_trust_anchor() {
+    let mut rng = TestRng::new();
+    let chain = create_chain(&mut rng, 10);
+
+    // The trusted hash is served by the peer, but doesn't have the trusted height.
+    let joiner_config = Config {
+        trusted_hash: Some(*chain[4].hash()),
+        trusted_height: Some(5),
+        chain: chain.iter().map(|block| block.header().clone()).collect(),
+        block_ranges: false,
+    };
+    let (mut network, joiner) = start_joining(&mut rng, &chain, joiner_config).await;
+
+    // Syncing never completes; the joiner gives up as soon as it receives the trusted block.
+    let is_synced = |nodes: &HashMap<NodeId, Runner<ConditionCheckReactor<Reactor>>>| {
+        nodes[&joiner]
+            .reactor()
+            .inner()
+            .linear_chain_sync
+            .is_synced()
+    };
+    network.settle_on(&mut rng, is_synced, TIMEOUT).await;
+}
+
+#[test]
+fn should_only_accept_valid_children() {
+    let mut rng = TestRng::new();
+    let chain = create_chain(&mut rng, 3);
+    let other_chain = create_chain(&mut rng, 3);
+    let parent = chain[0].header();
+
+    assert!(is_valid_child(parent, &chain[1]));
+    // Children must be at the next height.
+    assert!(!is_valid_child(parent, &chain[2]));
+    // Children must refer to the parent's hash.
+    assert!(!is_valid_child(parent, &other_chain[1]));
+}
@@ -28,40 +28,59 @@
 //! Nodes gossip their public listening addresses periodically, and on learning of a new address,
 //! a node will try to establish an outgoing connection.
 //!
+//! Gossiped addresses are not trusted until a TLS handshake proves a node is listening there: only
+//! then is an address re-gossiped.  Dials of gossiped addresses are rate-limited per IP, addresses
+//! in private or otherwise reserved ranges are only dialed if explicitly allowed, and addresses
+//! which could not be dialed are ignored for a cooldown period.
+//!
 //! On losing an incoming or outgoing connection for a given peer, the other connection is closed.
 //! No explicit reconnect is attempted. Instead, if the peer is still online, the normal gossiping
 //! process will cause both peers to connect again.
+//!
+//! # Ban list
+//!
+//! Peers can be banned by node ID or IP address, optionally until a given moment.  The ban list is
+//! persisted to a file in the node's root directory.  Incoming connections from banned peers are
+//! dropped as soon as the TLS handshake has identified them, and gossiped addresses of banned peers
+//! are never dialed.
 
+mod ban_list;
+mod codec;
 mod config;
+mod dial_guard;
 mod error;
 mod event;
 mod gossiped_address;
 mod message;
+mod metrics;
 #[cfg(test)]
 mod tests;
 
 use std::{
     collections::{HashMap, HashSet},
     fmt::{self, Debug, Display, Formatter},
-    io,
     net::{SocketAddr, TcpListener},
+    path::PathBuf,
+    result,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
     },
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use anyhow::Context;
 use datasize::DataSize;
 use futures::{
     future::{select, BoxFuture, Either},
-    stream::{SplitSink, SplitStream},
-    FutureExt, SinkExt, StreamExt,
+    stream::SplitSink,
+    FutureExt, SinkExt, Stream, StreamExt,
 };
 use openssl::pkey;
 use pkey::{PKey, Private};
+use prometheus::Registry;
 use rand::seq::IteratorRandom;
+use semver::Version;
 use serde::{de::DeserializeOwned, Serialize};
 use tokio::{
     net::TcpStream,
@@ -73,10 +92,16 @@ use tokio::{
 };
 use tokio_openssl::SslStream;
 use tokio_serde::{formats::SymmetricalMessagePack, SymmetricallyFramed};
-use tokio_util::codec::{Framed, LengthDelimitedCodec};
+use tokio_util::codec::Framed;
 use tracing::{debug, error, info, trace, warn};
 
-use self::error::Result;
+use self::{
+    ban_list::BanList,
+    codec::{CodecError, Direction, MessageCodec},
+    dial_guard::DialGuard,
+    error::Result,
+    metrics::NetworkMetrics,
+};
 pub(crate) use self::{event::Event, gossiped_address::GossipedAddress, message::Message};
 use crate::{
     components::Component,
@@ -88,10 +113,11 @@ use crate::{
     fatal,
     reactor::{EventQueueHandle, Finalize, QueueKind},
     tls::{self, KeyFingerprint, TlsCert},
-    types::CryptoRngCore,
-    utils,
+    types::{CryptoRngCore, Timestamp},
+    utils::{self, WithDir},
 };
 
+pub use ban_list::{BanEntry, BanTarget};
 pub use config::Config;
 pub use error::Error;
 
@@ -102,6 +128,9 @@ pub(crate) type NodeId = KeyFingerprint;
 
 const MAX_ASYMMETRIC_CONNECTION_SEEN: u16 = 3;
 
+/// The name of the file in the node's root directory to which the ban list is persisted.
+const BAN_LIST_FILENAME: &str = "ban_list.json";
+
 #[derive(DataSize, Debug)]
 pub(crate) struct OutgoingConnection<P> {
     #[data_size(skip)] // Unfortunately, there is no way to inspect an `UnboundedSender`.
@@ -145,11 +174,21 @@ where
 
     /// List of addresses which this node will avoid connecting to.
     blocklist: HashSet<SocketAddr>,
+    /// Peers banned by the node operator.
+    ban_list: BanList,
 
     /// Pending outgoing connections: ones for which we are currently trying to make a connection.
     pending: HashSet<SocketAddr>,
+    /// Gossiped addresses currently being dialed, which are announced as verified once connected.
+    unverified: HashMap<SocketAddr, GossipedAddress>,
+    /// Decides which gossiped addresses may be dialed.
+    dial_guard: DialGuard,
     /// The interval between each fresh round of gossiping the node's public listening address.
     gossip_interval: Duration,
+    /// The maximum size in bytes of a serialized message received from a peer.
+    max_incoming_message_size: u32,
+    /// The maximum size in bytes of a serialized message sent to a peer.
+    max_outgoing_message_size: u32,
     /// An index for an iteration of gossiping our own public listening address.  This is
     /// incremented by 1 on each iteration, and wraps on overflow.
     next_gossip_address_index: u32,
@@ -166,6 +205,11 @@ where
     is_stopped: Arc<AtomicBool>,
     /// Join handle for the server thread.
     server_join_handle: Option<JoinHandle<()>>,
+    /// Whether to send `Message::Departure` to our peers when shutting down.
+    send_departure_messages: bool,
+    /// Networking metrics.
+    #[data_size(skip)]
+    metrics: NetworkMetrics,
 }
 
 impl<REv, P> SmallNetwork<REv, P>
@@ -177,12 +221,21 @@ where
     ///
     /// If `notify` is set to `false`, no systemd notifications will be sent, regardless of
     /// configuration.
+    ///
+    /// `protocol_version` is the protocol version the network is running, which determines the
+    /// messages our peers are able to decode.
     #[allow(clippy::type_complexity)]
     pub(crate) fn new(
         event_queue: EventQueueHandle<REv>,
-        cfg: Config,
+        cfg: WithDir<Config>,
+        registry: &Registry,
         notify: bool,
+        protocol_version: &Version,
     ) -> Result<(SmallNetwork<REv, P>, Effects<Event<P>>)> {
+        let ban_list = BanList::load(cfg.with_dir(PathBuf::from(BAN_LIST_FILENAME)))?;
+        let metrics = NetworkMetrics::new(registry)?;
+        let (_root, cfg) = cfg.into_parts();
+
         // First, we generate the TLS keys.
         let (cert, secret_key) = tls::generate_node_cert().map_err(Error::CertificateGeneration)?;
         let certificate = Arc::new(tls::validate_cert(cert).map_err(Error::OwnCertificateInvalid)?);
@@ -239,13 +292,20 @@ where
             incoming: HashMap::new(),
             outgoing: HashMap::new(),
             pending: HashSet::new(),
+            unverified: HashMap::new(),
+            dial_guard: DialGuard::new(&cfg),
             blocklist: HashSet::new(),
+            ban_list,
             gossip_interval: cfg.gossip_interval,
+            max_incoming_message_size: cfg.max_incoming_message_size,
+            max_outgoing_message_size: cfg.max_outgoing_message_size,
             next_gossip_address_index: 0,
             shutdown_sender: Some(server_shutdown_sender),
             shutdown_receiver,
             server_join_handle: Some(server_join_handle),
             is_stopped: Arc::new(AtomicBool::new(false)),
+            send_departure_messages: message::supports_departure(protocol_version),
+            metrics,
         };
 
         // Bootstrap process.
@@ -254,7 +314,11 @@ where
         for address in &cfg.known_addresses {
             match utils::resolve_address(address) {
                 Ok(known_address) => {
-                    model.pending.insert(known_address);
+                    if !model.pending.insert(known_address) {
+                        // Several known addresses resolved to the same socket address.
+                        debug!(%known_address, "already connecting to known address");
+                        continue;
+                    }
 
                     // We successfully resolved an address, add an effect to connect to it.
                     effects.extend(
@@ -305,6 +369,17 @@ where
         }
     }
 
+    /// Queues a message to be sent to all nodes except the excluded ones.
+    fn send_to_all_except(&self, msg: Message<P>, exclude: &HashSet<NodeId>) {
+        for peer_id in self
+            .outgoing
+            .keys()
+            .filter(|&peer_id| !exclude.contains(peer_id))
+        {
+            self.send_message(*peer_id, msg.clone());
+        }
+    }
+
     /// Queues a message to `count` random nodes on the network.
     fn gossip_message(
         &self,
@@ -371,6 +446,20 @@ where
                     return Effects::new();
                 }
 
+                // If the peer is banned, drop the connection.
+                if self
+                    .ban_list
+                    .is_peer_banned(Some(peer_id), peer_address, Timestamp::now())
+                {
+                    info!(
+                        %peer_id,
+                        %peer_address,
+                        "{}: dropping incoming connection from banned peer",
+                        self.our_id
+                    );
+                    return Effects::new();
+                }
+
                 // If the peer has already disconnected, allow the connection to drop.
                 if let Err(error) = transport.get_ref().peer_addr() {
                     debug!(
@@ -385,7 +474,12 @@ where
 
                 debug!(%peer_id, %peer_address, "{}: established incoming connection", self.our_id);
                 // The sink is never used, as we only read data from incoming connections.
-                let (_sink, stream) = framed::<P>(transport).split();
+                let (_sink, stream) = framed::<P>(
+                    transport,
+                    Direction::Incoming,
+                    self.max_incoming_message_size,
+                )
+                .split();
 
                 let _ = self.incoming.insert(
                     peer_id,
@@ -440,6 +534,7 @@ where
             "should always add outgoing connect attempts to pendings: {:?}",
             self
         );
+        let gossiped_address = self.unverified.remove(&peer_address);
 
         // If we have connected to ourself, allow the connection to drop.
         if peer_id == self.our_id {
@@ -452,7 +547,26 @@ where
             return Effects::new();
         }
 
-        let (sink, _stream) = framed::<P>(transport).split();
+        // If the peer is banned, allow the connection to drop.
+        if self
+            .ban_list
+            .is_peer_banned(Some(peer_id), peer_address, Timestamp::now())
+        {
+            info!(
+                %peer_id,
+                %peer_address,
+                "{}: dropping outgoing connection to banned peer",
+                self.our_id
+            );
+            return Effects::new();
+        }
+
+        let (sink, _stream) = framed::<P>(
+            transport,
+            Direction::Outgoing,
+            self.max_outgoing_message_size,
+        )
+        .split();
         debug!(%peer_id, %peer_address, "{}: established outgoing connection", self.our_id);
 
         let (sender, receiver) = mpsc::unbounded_channel();
@@ -470,6 +584,15 @@ where
 
         let mut effects = self.check_connection_complete(effect_builder, peer_id);
 
+        // A node is now known to be listening at the gossiped address, so it can be gossiped on.
+        if let Some(gossiped_address) = gossiped_address {
+            effects.extend(
+                effect_builder
+                    .announce_peer_address_verified(gossiped_address)
+                    .ignore(),
+            );
+        }
+
         effects.extend(
             message_sender(receiver, sink).event(move |result| Event::OutgoingFailed {
                 peer_id: Some(peer_id),
@@ -488,9 +611,16 @@ where
         error: Option<Error>,
     ) -> Effects<Event<P>> {
         let _ = self.pending.remove(&peer_address);
+        if self.unverified.remove(&peer_address).is_some() {
+            // Dialing a gossiped address failed, so avoid dialing it again for a while.
+            self.dial_guard.record_failure(peer_address, Instant::now());
+        }
 
         if let Some(peer_id) = peer_id {
-            if let Some(err) = error {
+            if !self.outgoing.contains_key(&peer_id) {
+                // The peer was already removed, e.g. because it announced its departure.
+                debug!(%peer_id, %peer_address, "{}: outgoing connection closed", self.our_id);
+            } else if let Some(err) = error {
                 warn!(%peer_id, %peer_address, %err, "{}: outgoing connection failed", self.our_id);
             } else {
                 warn!(%peer_id, %peer_address, "{}: outgoing connection closed", self.our_id);
@@ -516,6 +646,32 @@ where
         let _ = self.outgoing.remove(&peer_id);
     }
 
+    /// Bans `target`, disconnecting any connected peers it matches.
+    ///
+    /// Peers connected from a banned IP are additionally banned by node ID, since messages may
+    /// still arrive on their open incoming connections.
+    fn ban_peer(&mut self, target: BanTarget, expires: Option<Timestamp>) -> Result<()> {
+        let banned_peers: Vec<NodeId> = self
+            .peers()
+            .into_iter()
+            .filter(|(peer_id, peer_address)| match target {
+                BanTarget::Node(node_id) => *peer_id == node_id,
+                BanTarget::Ip(ip) => peer_address.ip() == ip,
+            })
+            .map(|(peer_id, _)| peer_id)
+            .collect();
+
+        let mut result = self.ban_list.ban(target, expires);
+        for peer_id in banned_peers {
+            info!(%peer_id, %target, "{}: disconnecting banned peer", self.our_id);
+            if let BanTarget::Ip(_) = target {
+                result = result.and(self.ban_list.ban(BanTarget::Node(peer_id), expires));
+            }
+            self.remove(&peer_id);
+        }
+        result
+    }
+
     /// Gossips our public listening address, and schedules the next such gossip round.
     fn gossip_our_address(&mut self, effect_builder: EffectBuilder<REv>) -> Effects<Event<P>> {
         self.next_gossip_address_index = self.next_gossip_address_index.wrapping_add(1);
@@ -582,40 +738,98 @@ where
     where
         REv: From<NetworkAnnouncement<NodeId, P>>,
     {
-        effect_builder
-            .announce_message_received(peer_id, msg.0)
-            .ignore()
+        if self
+            .ban_list
+            .is_banned(&BanTarget::Node(peer_id), Timestamp::now())
+        {
+            debug!(%peer_id, "{}: dropping message from banned peer", self.our_id);
+            return Effects::new();
+        }
+        match msg {
+            Message::Payload(payload) => effect_builder
+                .announce_message_received(peer_id, payload)
+                .ignore(),
+            Message::Departure => {
+                info!(%peer_id, "{}: peer is shutting down", self.our_id);
+                self.remove(&peer_id);
+                Effects::new()
+            }
+        }
     }
 
-    fn connect_to_peer_if_required(&mut self, peer_address: SocketAddr) -> Effects<Event<P>> {
-        if self.pending.contains(&peer_address)
-            || self.blocklist.contains(&peer_address)
+    /// Handles an address gossiped by a peer.
+    ///
+    /// If we are already connected to the address, it is announced as verified.  Otherwise it is
+    /// dialed, if permitted, and only announced as verified once the connection is established.
+    fn handle_gossiped_address(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        gossiped_address: GossipedAddress,
+    ) -> Effects<Event<P>> {
+        let peer_address = SocketAddr::from(gossiped_address);
+        if peer_address == self.public_address {
+            return Effects::new();
+        }
+
+        if self
+            .outgoing
+            .values()
+            .any(|connection| connection.peer_address == peer_address)
+        {
+            return effect_builder
+                .announce_peer_address_verified(gossiped_address)
+                .ignore();
+        }
+
+        if self.pending.contains(&peer_address) {
+            // We're already trying to connect - verify the latest gossiped address once connected.
+            if let Some(unverified) = self.unverified.get_mut(&peer_address) {
+                *unverified = gossiped_address;
+            }
+            return Effects::new();
+        }
+
+        if self.blocklist.contains(&peer_address)
             || self
-                .outgoing
-                .iter()
-                .any(|(_peer_id, connection)| connection.peer_address == peer_address)
+                .ban_list
+                .is_peer_banned(None, peer_address, Timestamp::now())
         {
-            // We're already trying to connect, are connected, or the connection is on the blocklist
-            // - do nothing.
-            Effects::new()
-        } else {
-            // We need to connect.
-            assert!(self.pending.insert(peer_address));
-            connect_outgoing(
-                peer_address,
-                Arc::clone(&self.certificate),
-                Arc::clone(&self.secret_key),
-                Arc::clone(&self.is_stopped),
-            )
-            .result(
-                move |(peer_id, transport)| Event::OutgoingEstablished { peer_id, transport },
-                move |error| Event::OutgoingFailed {
-                    peer_id: None,
-                    peer_address,
-                    error: Some(error),
-                },
-            )
+            // The connection is on the blocklist or banned - do nothing.
+            return Effects::new();
+        }
+
+        if let Err(refusal) = self.dial_guard.permit_dial(peer_address, Instant::now()) {
+            debug!(%peer_address, %refusal, "{}: not dialing gossiped address", self.our_id);
+            return Effects::new();
         }
+
+        let _ = self.unverified.insert(peer_address, gossiped_address);
+        self.connect_to_peer(peer_address)
+    }
+
+    /// Initiates an outgoing connection to `peer_address`.
+    ///
+    /// Does nothing if a connection attempt to `peer_address` is already pending, so that at most
+    /// one dial per address is in flight at any time.
+    fn connect_to_peer(&mut self, peer_address: SocketAddr) -> Effects<Event<P>> {
+        if !self.pending.insert(peer_address) {
+            debug!(%peer_address, "{}: already connecting", self.our_id);
+            return Effects::new();
+        }
+        connect_outgoing(
+            peer_address,
+            Arc::clone(&self.certificate),
+            Arc::clone(&self.secret_key),
+            Arc::clone(&self.is_stopped),
+        )
+        .result(
+            move |(peer_id, transport)| Event::OutgoingEstablished { peer_id, transport },
+            move |error| Event::OutgoingFailed {
+                peer_id: None,
+                peer_address,
+                error: Some(error),
+            },
+        )
     }
 
     /// Checks whether a connection has been established fully, i.e. with an incoming and outgoing
@@ -647,6 +861,20 @@ where
         ret
     }
 
+    /// Updates the connection metrics from the current connections.
+    fn update_connection_metrics(&self) {
+        self.metrics.connected_peers.set(self.peers().len() as i64);
+        self.metrics
+            .pending_connections
+            .set(self.pending.len() as i64);
+        self.metrics
+            .outgoing_connections
+            .set(self.outgoing.len() as i64);
+        self.metrics
+            .incoming_connections
+            .set(self.incoming.len() as i64);
+    }
+
     /// Returns whether or not this node has been isolated.
     ///
     /// An isolated node has no chance of recovering a connection to the network and is not
@@ -670,6 +898,18 @@ where
 {
     fn finalize(mut self) -> BoxFuture<'static, ()> {
         async move {
+            // Let our peers know we are leaving, so they don't treat the closed connections as
+            // failures.  This is best effort, messages still queued when the runtime stops are
+            // lost.  Peers running an older protocol version can't decode the departure message,
+            // so until the network has upgraded, they just see the connections close.
+            for (peer_id, connection) in self.outgoing.drain() {
+                if self.send_departure_messages
+                    && connection.sender.send(Message::Departure).is_err()
+                {
+                    debug!(%peer_id, "{}: could not send departure message", self.our_id);
+                }
+            }
+
             // Close the shutdown socket, causing the server to exit.
             drop(self.shutdown_sender.take());
 
@@ -705,7 +945,7 @@ where
         rng: &mut dyn CryptoRngCore,
         event: Self::Event,
     ) -> Effects<Self::Event> {
-        match event {
+        let effects = match event {
             Event::BootstrappingFailed {
                 peer_address,
                 error,
@@ -781,14 +1021,26 @@ where
                     },
             } => {
                 // We're given a message to send out.
-                self.send_message(dest, Message(payload));
+                self.send_message(dest, Message::Payload(payload));
                 responder.respond(()).ignore()
             }
             Event::NetworkRequest {
                 req: NetworkRequest::Broadcast { payload, responder },
             } => {
                 // We're given a message to broadcast.
-                self.broadcast_message(Message(payload));
+                self.broadcast_message(Message::Payload(payload));
+                responder.respond(()).ignore()
+            }
+            Event::NetworkRequest {
+                req:
+                    NetworkRequest::BroadcastExcept {
+                        payload,
+                        exclude,
+                        responder,
+                    },
+            } => {
+                // We're given a message to broadcast to all but the excluded peers.
+                self.send_to_all_except(Message::Payload(payload), &exclude);
                 responder.respond(()).ignore()
             }
             Event::NetworkRequest {
@@ -801,21 +1053,54 @@ where
                     },
             } => {
                 // We're given a message to gossip.
-                let sent_to = self.gossip_message(rng, Message(payload), count, exclude);
+                let sent_to = self.gossip_message(rng, Message::Payload(payload), count, exclude);
                 responder.respond(sent_to).ignore()
             }
             Event::NetworkInfoRequest {
                 req: NetworkInfoRequest::GetPeers { responder },
             } => responder.respond(self.peers()).ignore(),
+            Event::NetworkInfoRequest {
+                req: NetworkInfoRequest::GetPeerCount { responder },
+            } => responder.respond(self.peers().len()).ignore(),
+            Event::NetworkInfoRequest {
+                req: NetworkInfoRequest::GetBans { responder },
+            } => responder
+                .respond(self.ban_list.entries(Timestamp::now()))
+                .ignore(),
+            Event::NetworkInfoRequest {
+                req:
+                    NetworkInfoRequest::BanPeer {
+                        target,
+                        expires,
+                        responder,
+                    },
+            } => {
+                let result = self.ban_peer(target, expires);
+                if let Err(error) = &result {
+                    warn!(%target, %error, "{}: failed to persist ban list", self.our_id);
+                }
+                responder.respond(result).ignore()
+            }
+            Event::NetworkInfoRequest {
+                req: NetworkInfoRequest::UnbanPeer { target, responder },
+            } => {
+                let result = self.ban_list.unban(&target);
+                if let Err(error) = &result {
+                    warn!(%target, %error, "{}: failed to persist ban list", self.our_id);
+                }
+                responder.respond(result).ignore()
+            }
             Event::GossipOurAddress => {
                 let effects = self.gossip_our_address(effect_builder);
                 self.enforce_symmetric_connections();
                 effects
             }
             Event::PeerAddressReceived(gossiped_address) => {
-                self.connect_to_peer_if_required(gossiped_address.into())
+                self.handle_gossiped_address(effect_builder, gossiped_address)
             }
-        }
+        };
+        self.update_connection_metrics();
+        effects
     }
 }
 
@@ -905,17 +1190,20 @@ async fn setup_tls(
 
 /// Network message reader.
 ///
-/// Schedules all received messages until the stream is closed or an error occurs.
-async fn message_reader<REv, P>(
+/// Schedules all received messages until the stream is closed or an error occurs.  An error, e.g.
+/// due to an oversized message or one of a different schema version, is returned so that the
+/// connection is closed.
+async fn message_reader<REv, P, S>(
     event_queue: EventQueueHandle<REv>,
-    mut stream: SplitStream<FramedTransport<P>>,
+    mut stream: S,
     mut shutdown_receiver: watch::Receiver<()>,
     our_id: NodeId,
     peer_id: NodeId,
-) -> io::Result<()>
+) -> result::Result<(), CodecError>
 where
     P: DeserializeOwned + Send + Display,
     REv: From<Event<P>>,
+    S: Stream<Item = result::Result<Message<P>, CodecError>> + Unpin,
 {
     let read_messages = async move {
         while let Some(msg_result) = stream.next().await {
@@ -944,15 +1232,16 @@ where
     // Now we can wait for either the `shutdown` channel's remote end to do be dropped or the
     // while loop to terminate.
     match select(Box::pin(shutdown_messages), Box::pin(read_messages)).await {
-        Either::Left(_) => info!(
-            %peer_id,
-            "{}: shutting down incoming connection message reader",
-            our_id
-        ),
-        Either::Right(_) => (),
+        Either::Left(_) => {
+            info!(
+                %peer_id,
+                "{}: shutting down incoming connection message reader",
+                our_id
+            );
+            Ok(())
+        }
+        Either::Right((result, _)) => result,
     }
-
-    Ok(())
 }
 
 /// Network message sender.
@@ -978,16 +1267,17 @@ type Transport = SslStream<TcpStream>;
 
 /// A framed transport for `Message`s.
 type FramedTransport<P> = SymmetricallyFramed<
-    Framed<Transport, LengthDelimitedCodec>,
+    Framed<Transport, MessageCodec>,
     Message<P>,
     SymmetricalMessagePack<Message<P>>,
 >;
 
-/// Constructs a new framed transport on a stream.
-fn framed<P>(stream: Transport) -> FramedTransport<P> {
-    let length_delimited = Framed::new(stream, LengthDelimitedCodec::new());
+/// Constructs a new framed transport on a stream, only carrying messages in the given direction
+/// of at most `max_message_size` bytes when serialized.
+fn framed<P>(stream: Transport, direction: Direction, max_message_size: u32) -> FramedTransport<P> {
+    let message_frames = Framed::new(stream, MessageCodec::new(direction, max_message_size));
     SymmetricallyFramed::new(
-        length_delimited,
+        message_frames,
         SymmetricalMessagePack::<Message<P>>::default(),
     )
 }
@@ -1046,6 +1336,7 @@ where
             .field("incoming", &self.incoming)
             .field("outgoing", &self.outgoing)
             .field("pending", &self.pending)
+            .field("unverified", &self.unverified)
             .finish()
     }
 }
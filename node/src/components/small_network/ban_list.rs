@@ -0,0 +1,192 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+    fs, io,
+    net::{IpAddr, SocketAddr},
+    path::PathBuf,
+};
+
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+
+use super::{Error, NodeId};
+use crate::types::Timestamp;
+
+/// A peer which can be banned.
+#[derive(
+    Copy, Clone, DataSize, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize, Debug,
+)]
+pub enum BanTarget {
+    /// The node with the given ID.
+    ///
+    /// Node IDs are derived from a certificate which is regenerated each time a node restarts, so
+    /// such a ban only applies until the banned node restarts.
+    Node(NodeId),
+    /// Every node connecting from, or listening on, the given IP address.
+    Ip(IpAddr),
+}
+
+impl Display for BanTarget {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            BanTarget::Node(node_id) => write!(formatter, "node {}", node_id),
+            BanTarget::Ip(ip) => write!(formatter, "ip {}", ip),
+        }
+    }
+}
+
+/// A single entry of the ban list.
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub struct BanEntry {
+    /// The banned peer.
+    pub target: BanTarget,
+    /// The moment the ban expires, or `None` if the ban is permanent.
+    pub expires: Option<Timestamp>,
+}
+
+/// The set of banned peers, persisted to a JSON file on every change.
+#[derive(DataSize, Debug)]
+pub(super) struct BanList {
+    /// The file the ban list is persisted to.
+    path: PathBuf,
+    /// The banned peers, mapped to the moment their ban expires, if any.
+    bans: BTreeMap<BanTarget, Option<Timestamp>>,
+}
+
+impl BanList {
+    /// Loads the ban list from `path`, dropping any expired entries.
+    ///
+    /// If the file does not exist, an empty ban list is returned.
+    pub(super) fn load(path: PathBuf) -> Result<Self, Error> {
+        let entries: Vec<BanEntry> = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|error| Error::BanListParse(error, path.clone()))?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(error) => return Err(Error::BanListIo(error, path)),
+        };
+
+        let mut ban_list = BanList {
+            path,
+            bans: entries
+                .into_iter()
+                .map(|entry| (entry.target, entry.expires))
+                .collect(),
+        };
+        ban_list.remove_expired(Timestamp::now());
+        Ok(ban_list)
+    }
+
+    /// Returns all bans which have not yet expired.
+    pub(super) fn entries(&self, now: Timestamp) -> Vec<BanEntry> {
+        self.bans
+            .iter()
+            .filter(|(_, expires)| is_active(**expires, now))
+            .map(|(target, expires)| BanEntry {
+                target: *target,
+                expires: *expires,
+            })
+            .collect()
+    }
+
+    /// Returns whether `target` is currently banned.
+    pub(super) fn is_banned(&self, target: &BanTarget, now: Timestamp) -> bool {
+        self.bans
+            .get(target)
+            .map_or(false, |expires| is_active(*expires, now))
+    }
+
+    /// Returns whether a peer is currently banned, either by its node ID (if known) or by the IP
+    /// of its address.
+    pub(super) fn is_peer_banned(
+        &self,
+        peer_id: Option<NodeId>,
+        peer_address: SocketAddr,
+        now: Timestamp,
+    ) -> bool {
+        peer_id.map_or(false, |peer_id| {
+            self.is_banned(&BanTarget::Node(peer_id), now)
+        }) || self.is_banned(&BanTarget::Ip(peer_address.ip()), now)
+    }
+
+    /// Bans `target` until `expires`, or permanently if `expires` is `None`, replacing any previous
+    /// ban of the same target.
+    ///
+    /// The ban takes effect even if persisting the updated list fails.
+    pub(super) fn ban(
+        &mut self,
+        target: BanTarget,
+        expires: Option<Timestamp>,
+    ) -> Result<(), Error> {
+        let _ = self.bans.insert(target, expires);
+        self.persist()
+    }
+
+    /// Lifts the ban of `target`.  Returns `false` if the target wasn't banned.
+    pub(super) fn unban(&mut self, target: &BanTarget) -> Result<bool, Error> {
+        if self.bans.remove(target).is_none() {
+            return Ok(false);
+        }
+        self.persist()?;
+        Ok(true)
+    }
+
+    fn remove_expired(&mut self, now: Timestamp) {
+        self.bans.retain(|_, expires| is_active(*expires, now));
+    }
+
+    fn persist(&mut self) -> Result<(), Error> {
+        let now = Timestamp::now();
+        self.remove_expired(now);
+        let entries = self.entries(now);
+        let contents = serde_json::to_vec_pretty(&entries)
+            .map_err(|error| Error::BanListParse(error, self.path.clone()))?;
+        fs::write(&self.path, contents).map_err(|error| Error::BanListIo(error, self.path.clone()))
+    }
+}
+
+fn is_active(expires: Option<Timestamp>, now: Timestamp) -> bool {
+    expires.map_or(true, |expires| now < expires)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+    use crate::{testing::TestRng, types::TimeDiff};
+
+    #[test]
+    fn should_persist_bans_across_loads() {
+        let mut rng = TestRng::new();
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("ban_list.json");
+
+        let node = BanTarget::Node(rng.gen());
+        let ip = BanTarget::Ip(IpAddr::from([10, 0, 0, 1]));
+        let expired = BanTarget::Ip(IpAddr::from([10, 0, 0, 2]));
+
+        let mut ban_list = BanList::load(path.clone()).unwrap();
+        assert!(ban_list.entries(Timestamp::now()).is_empty());
+        ban_list.ban(node, None).unwrap();
+        ban_list
+            .ban(ip, Some(Timestamp::now() + TimeDiff::from(3_600_000)))
+            .unwrap();
+        ban_list
+            .ban(expired, Some(Timestamp::now() - TimeDiff::from(1)))
+            .unwrap();
+
+        let reloaded = BanList::load(path.clone()).unwrap();
+        let now = Timestamp::now();
+        assert!(reloaded.is_banned(&node, now));
+        assert!(reloaded.is_banned(&ip, now));
+        assert!(!reloaded.is_banned(&expired, now));
+        assert!(reloaded.is_peer_banned(None, SocketAddr::from(([10, 0, 0, 1], 34553)), now));
+        assert_eq!(reloaded.entries(now).len(), 2);
+
+        let mut ban_list = reloaded;
+        assert!(ban_list.unban(&node).unwrap());
+        assert!(!ban_list.unban(&node).unwrap());
+        let reloaded = BanList::load(path).unwrap();
+        assert!(!reloaded.is_banned(&node, Timestamp::now()));
+    }
+}
@@ -0,0 +1,274 @@
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+    io,
+};
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use thiserror::Error;
+use tokio_util::codec::{Decoder, Encoder};
+
+/// The version of the schema of serialized `Message`s.
+///
+/// Must be incremented whenever the serialized form of `Message` or any payload changes, e.g. when
+/// enum variants are added or reordered, so that nodes running incompatible builds refuse each
+/// other's messages rather than misinterpreting them.
+pub(super) const MESSAGE_SCHEMA_VERSION: u8 = 1;
+
+/// The number of bytes of the length prefix of each frame.
+const LENGTH_PREFIX_SIZE: usize = 4;
+
+/// The number of bytes of the schema version prefix of each frame's contents.
+const VERSION_SIZE: usize = 1;
+
+/// The direction of a connection, for which a codec enforces a maximum message size.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// Messages received from a peer.
+    Incoming,
+    /// Locally originated messages sent to a peer.
+    Outgoing,
+}
+
+impl Display for Direction {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            Direction::Incoming => write!(formatter, "incoming"),
+            Direction::Outgoing => write!(formatter, "outgoing"),
+        }
+    }
+}
+
+/// Error returned by the `MessageCodec`.
+#[derive(Debug, Error)]
+pub enum CodecError {
+    /// The message exceeds the maximum message size.
+    #[error("{direction} message of {size} bytes exceeds the maximum of {max_size} bytes")]
+    MessageTooLarge {
+        /// The direction of the connection.
+        direction: Direction,
+        /// The size of the serialized message in bytes.
+        size: usize,
+        /// The maximum size of a serialized message in bytes.
+        max_size: u32,
+    },
+    /// The frame is too short to hold the schema version.
+    #[error("received empty frame")]
+    EmptyFrame,
+    /// The schema version of a received message doesn't match ours.
+    #[error("message schema version {received} does not match ours ({expected})")]
+    VersionMismatch {
+        /// Our message schema version.
+        expected: u8,
+        /// The message schema version the peer sent.
+        received: u8,
+    },
+    /// Failed to read, write or (de)serialize a message.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// Frames serialized messages on a connection.
+///
+/// Each frame consists of a big-endian `u32` length prefix, followed by the message schema version
+/// and the serialized message.  Frames whose serialized message exceeds `max_message_size` are
+/// rejected without being buffered.
+#[derive(Debug)]
+pub(super) struct MessageCodec {
+    direction: Direction,
+    max_message_size: u32,
+}
+
+impl MessageCodec {
+    /// Creates a new codec for a connection in the given direction.
+    pub(super) fn new(direction: Direction, max_message_size: u32) -> Self {
+        MessageCodec {
+            direction,
+            max_message_size,
+        }
+    }
+
+    fn check_size(&self, size: usize) -> Result<(), CodecError> {
+        if size > self.max_message_size as usize {
+            return Err(CodecError::MessageTooLarge {
+                direction: self.direction,
+                size,
+                max_size: self.max_message_size,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Decoder for MessageCodec {
+    type Item = BytesMut;
+    type Error = CodecError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if src.len() < LENGTH_PREFIX_SIZE {
+            return Ok(None);
+        }
+
+        let mut length_prefix = [0; LENGTH_PREFIX_SIZE];
+        length_prefix.copy_from_slice(&src[..LENGTH_PREFIX_SIZE]);
+        let frame_length = u32::from_be_bytes(length_prefix) as usize;
+        if frame_length < VERSION_SIZE {
+            return Err(CodecError::EmptyFrame);
+        }
+        self.check_size(frame_length - VERSION_SIZE)?;
+
+        if src.len() < LENGTH_PREFIX_SIZE + frame_length {
+            src.reserve(LENGTH_PREFIX_SIZE + frame_length - src.len());
+            return Ok(None);
+        }
+
+        src.advance(LENGTH_PREFIX_SIZE);
+        let mut frame = src.split_to(frame_length);
+        let version = frame[0];
+        if version != MESSAGE_SCHEMA_VERSION {
+            return Err(CodecError::VersionMismatch {
+                expected: MESSAGE_SCHEMA_VERSION,
+                received: version,
+            });
+        }
+        frame.advance(VERSION_SIZE);
+        Ok(Some(frame))
+    }
+}
+
+impl Encoder<Bytes> for MessageCodec {
+    type Error = CodecError;
+
+    fn encode(&mut self, message: Bytes, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        self.check_size(message.len())?;
+        // The version byte may push a message of exactly `u32::MAX` bytes over the limit.
+        let frame_length = u32::try_from(message.len() + VERSION_SIZE).map_err(|_| {
+            CodecError::MessageTooLarge {
+                direction: self.direction,
+                size: message.len(),
+                max_size: self.max_message_size,
+            }
+        })?;
+
+        dst.reserve(LENGTH_PREFIX_SIZE + frame_length as usize);
+        dst.put_u32(frame_length);
+        dst.put_u8(MESSAGE_SCHEMA_VERSION);
+        dst.extend_from_slice(&message);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use tokio_util::codec::FramedRead;
+
+    use super::*;
+
+    const MAX_MESSAGE_SIZE: u32 = 16;
+
+    fn incoming_codec() -> MessageCodec {
+        MessageCodec::new(Direction::Incoming, MAX_MESSAGE_SIZE)
+    }
+
+    /// Returns a frame with the given length prefix, schema version and message.
+    fn frame(frame_length: u32, version: u8, message: &[u8]) -> BytesMut {
+        let mut frame = BytesMut::new();
+        frame.put_u32(frame_length);
+        frame.put_u8(version);
+        frame.extend_from_slice(message);
+        frame
+    }
+
+    #[test]
+    fn should_roundtrip_messages() {
+        let mut buffer = BytesMut::new();
+        let mut outgoing_codec = MessageCodec::new(Direction::Outgoing, MAX_MESSAGE_SIZE);
+        outgoing_codec
+            .encode(Bytes::from_static(b"hello"), &mut buffer)
+            .unwrap();
+        outgoing_codec
+            .encode(Bytes::from_static(b"world"), &mut buffer)
+            .unwrap();
+
+        let mut codec = incoming_codec();
+        assert_eq!(codec.decode(&mut buffer).unwrap().unwrap(), &b"hello"[..]);
+        assert_eq!(codec.decode(&mut buffer).unwrap().unwrap(), &b"world"[..]);
+        assert!(codec.decode(&mut buffer).unwrap().is_none());
+    }
+
+    #[test]
+    fn should_wait_for_complete_frame() {
+        let mut complete = frame(6, MESSAGE_SCHEMA_VERSION, b"hello");
+        let mut buffer = complete.split_to(7);
+
+        let mut codec = incoming_codec();
+        assert!(codec.decode(&mut buffer).unwrap().is_none());
+        buffer.unsplit(complete);
+        assert_eq!(codec.decode(&mut buffer).unwrap().unwrap(), &b"hello"[..]);
+    }
+
+    #[test]
+    fn should_reject_oversized_frame_before_buffering_it() {
+        // Only the header of the frame is available; the announced length alone is rejected.
+        let mut buffer = frame(u32::max_value(), MESSAGE_SCHEMA_VERSION, &[]);
+        match incoming_codec().decode(&mut buffer) {
+            Err(CodecError::MessageTooLarge {
+                direction: Direction::Incoming,
+                size,
+                max_size: MAX_MESSAGE_SIZE,
+            }) => assert_eq!(size, u32::max_value() as usize - VERSION_SIZE),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn should_refuse_to_send_oversized_message() {
+        let mut codec = MessageCodec::new(Direction::Outgoing, MAX_MESSAGE_SIZE);
+        let message = Bytes::from(vec![0; MAX_MESSAGE_SIZE as usize + 1]);
+        let mut buffer = BytesMut::new();
+        match codec.encode(message, &mut buffer) {
+            Err(CodecError::MessageTooLarge {
+                direction: Direction::Outgoing,
+                ..
+            }) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn should_reject_wrong_version() {
+        let mut buffer = frame(6, MESSAGE_SCHEMA_VERSION + 1, b"hello");
+        match incoming_codec().decode(&mut buffer) {
+            Err(CodecError::VersionMismatch { expected, received }) => {
+                assert_eq!(expected, MESSAGE_SCHEMA_VERSION);
+                assert_eq!(received, MESSAGE_SCHEMA_VERSION + 1);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn should_reject_empty_frame() {
+        let mut buffer = BytesMut::new();
+        buffer.put_u32(0);
+        assert!(matches!(
+            incoming_codec().decode(&mut buffer),
+            Err(CodecError::EmptyFrame)
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_end_stream_with_error_on_oversized_frame() {
+        let mut bytes = frame(6, MESSAGE_SCHEMA_VERSION, b"hello");
+        bytes.extend_from_slice(&frame(MAX_MESSAGE_SIZE + 2, MESSAGE_SCHEMA_VERSION, &[]));
+        let mut stream = FramedRead::new(&bytes[..], incoming_codec());
+
+        assert_eq!(stream.next().await.unwrap().unwrap(), &b"hello"[..]);
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(CodecError::MessageTooLarge { .. }))
+        ));
+    }
+}
@@ -18,6 +18,18 @@ const DEFAULT_PUBLIC_ADDRESS: &str = "127.0.0.1:0";
 /// Default interval for gossiping network addresses.
 const DEFAULT_GOSSIP_INTERVAL: Duration = Duration::from_secs(30);
 
+/// Default maximum number of dials of a single IP address learned via gossip per gossip interval.
+const DEFAULT_MAX_DIALS_PER_IP: u32 = 3;
+
+/// Default time for which a gossiped address which could not be dialed is ignored.
+const DEFAULT_DIAL_COOLDOWN: Duration = Duration::from_secs(300);
+
+/// Default maximum size in bytes of a serialized message received from a peer.
+const DEFAULT_MAX_INCOMING_MESSAGE_SIZE: u32 = 16 * 1024 * 1024;
+
+/// Default maximum size in bytes of a serialized message sent to a peer.
+const DEFAULT_MAX_OUTGOING_MESSAGE_SIZE: u32 = 16 * 1024 * 1024;
+
 // Default values for networking configuration:
 impl Default for Config {
     fn default() -> Self {
@@ -26,6 +38,11 @@ impl Default for Config {
             public_address: DEFAULT_PUBLIC_ADDRESS.to_string(),
             known_addresses: Vec::new(),
             gossip_interval: DEFAULT_GOSSIP_INTERVAL,
+            allow_private_addresses: false,
+            max_dials_per_ip: DEFAULT_MAX_DIALS_PER_IP,
+            dial_cooldown: DEFAULT_DIAL_COOLDOWN,
+            max_incoming_message_size: DEFAULT_MAX_INCOMING_MESSAGE_SIZE,
+            max_outgoing_message_size: DEFAULT_MAX_OUTGOING_MESSAGE_SIZE,
             systemd_support: false,
         }
     }
@@ -47,6 +64,20 @@ pub struct Config {
     /// Interval in milliseconds used for gossiping.
     #[serde(with = "crate::utils::milliseconds")]
     pub gossip_interval: Duration,
+    /// Whether gossiped addresses in private, loopback or otherwise reserved IP ranges may be
+    /// dialed.  Should only be enabled for local testnets.
+    pub allow_private_addresses: bool,
+    /// Maximum number of dials of a single IP address learned via gossip per gossip interval.
+    pub max_dials_per_ip: u32,
+    /// Time in milliseconds for which a gossiped address which could not be dialed is ignored.
+    #[serde(with = "crate::utils::milliseconds")]
+    pub dial_cooldown: Duration,
+    /// Maximum size in bytes of a serialized message received from a peer.  Connections sending
+    /// larger messages are closed.
+    pub max_incoming_message_size: u32,
+    /// Maximum size in bytes of a serialized message sent to a peer.  Larger messages are not sent
+    /// and the connection is closed.
+    pub max_outgoing_message_size: u32,
     /// Enable systemd startup notification.
     pub systemd_support: bool,
 }
@@ -55,6 +86,10 @@ pub struct Config {
 /// Reduced gossip interval for local testing.
 const DEFAULT_TEST_GOSSIP_INTERVAL: Duration = Duration::from_secs(1);
 
+#[cfg(test)]
+/// Increased dial limit for local testing, where all nodes share a single IP address.
+const DEFAULT_TEST_MAX_DIALS_PER_IP: u32 = 100;
+
 #[cfg(test)]
 /// Address used to bind all local testing networking to by default.
 const TEST_BIND_INTERFACE: Ipv4Addr = Ipv4Addr::LOCALHOST;
@@ -69,6 +104,11 @@ impl Config {
             public_address: bind_address.to_string(),
             known_addresses: Vec::new(),
             gossip_interval: DEFAULT_TEST_GOSSIP_INTERVAL,
+            allow_private_addresses: true,
+            max_dials_per_ip: DEFAULT_TEST_MAX_DIALS_PER_IP,
+            dial_cooldown: DEFAULT_DIAL_COOLDOWN,
+            max_incoming_message_size: DEFAULT_MAX_INCOMING_MESSAGE_SIZE,
+            max_outgoing_message_size: DEFAULT_MAX_OUTGOING_MESSAGE_SIZE,
             systemd_support: false,
         }
     }
@@ -87,6 +127,11 @@ impl Config {
                 SocketAddr::from((TEST_BIND_INTERFACE, known_peer_port)).to_string()
             ],
             gossip_interval: DEFAULT_TEST_GOSSIP_INTERVAL,
+            allow_private_addresses: true,
+            max_dials_per_ip: DEFAULT_TEST_MAX_DIALS_PER_IP,
+            dial_cooldown: DEFAULT_DIAL_COOLDOWN,
+            max_incoming_message_size: DEFAULT_MAX_INCOMING_MESSAGE_SIZE,
+            max_outgoing_message_size: DEFAULT_MAX_OUTGOING_MESSAGE_SIZE,
             systemd_support: false,
         }
     }
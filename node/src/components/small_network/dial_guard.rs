@@ -0,0 +1,303 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::{self, Display, Formatter},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::{Duration, Instant},
+};
+
+use datasize::DataSize;
+
+use super::Config;
+
+/// The reason a gossiped address may not be dialed.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub(super) enum DialRefusal {
+    /// The address is in a private or otherwise reserved range.
+    ReservedAddress,
+    /// A previous dial of the address failed recently.
+    CoolingDown,
+    /// The IP of the address has been dialed too often recently.
+    RateLimited,
+}
+
+impl Display for DialRefusal {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            DialRefusal::ReservedAddress => write!(formatter, "address is in a reserved range"),
+            DialRefusal::CoolingDown => write!(formatter, "address failed a recent dial"),
+            DialRefusal::RateLimited => write!(formatter, "ip was dialed too often recently"),
+        }
+    }
+}
+
+/// Decides which addresses learned via gossip may be dialed.
+///
+/// Dials are rate-limited per IP address, since a single malicious peer can gossip arbitrarily many
+/// ports of any one host.  Addresses which fail to be dialed are ignored for a cooldown period.
+#[derive(DataSize, Debug)]
+pub(super) struct DialGuard {
+    /// See `Config::allow_private_addresses`.
+    allow_private_addresses: bool,
+    /// See `Config::max_dials_per_ip`.
+    max_dials_per_ip: usize,
+    /// The window in which at most `max_dials_per_ip` dials of a single IP are permitted.
+    rate_limit_window: Duration,
+    /// See `Config::dial_cooldown`.
+    cooldown: Duration,
+    /// The moments of the dials within the rate limit window, oldest first, per IP.
+    #[data_size(skip)]
+    recent_dials: HashMap<IpAddr, VecDeque<Instant>>,
+    /// Addresses which failed to be dialed, mapped to the moment their cooldown ends.
+    #[data_size(skip)]
+    cooling_down: HashMap<SocketAddr, Instant>,
+}
+
+impl DialGuard {
+    /// Constructs a new `DialGuard`, using the gossip interval as the rate limit window.
+    pub(super) fn new(cfg: &Config) -> Self {
+        DialGuard {
+            allow_private_addresses: cfg.allow_private_addresses,
+            max_dials_per_ip: cfg.max_dials_per_ip as usize,
+            rate_limit_window: cfg.gossip_interval,
+            cooldown: cfg.dial_cooldown,
+            recent_dials: HashMap::new(),
+            cooling_down: HashMap::new(),
+        }
+    }
+
+    /// Checks whether `address` may be dialed now, and if so records the dial.
+    pub(super) fn permit_dial(
+        &mut self,
+        address: SocketAddr,
+        now: Instant,
+    ) -> Result<(), DialRefusal> {
+        self.purge(now);
+
+        if !self.allow_private_addresses && is_reserved(address.ip()) {
+            return Err(DialRefusal::ReservedAddress);
+        }
+        if self.cooling_down.contains_key(&address) {
+            return Err(DialRefusal::CoolingDown);
+        }
+        let dials = self.recent_dials.entry(address.ip()).or_default();
+        if dials.len() >= self.max_dials_per_ip {
+            return Err(DialRefusal::RateLimited);
+        }
+        dials.push_back(now);
+        Ok(())
+    }
+
+    /// Records that dialing `address` failed, so that it is not dialed again until the cooldown
+    /// period has passed.
+    pub(super) fn record_failure(&mut self, address: SocketAddr, now: Instant) {
+        let _ = self.cooling_down.insert(address, now + self.cooldown);
+    }
+
+    /// Returns the number of dials of `ip` within the rate limit window.
+    #[cfg(test)]
+    pub(super) fn recent_dial_count(&self, ip: IpAddr) -> usize {
+        self.recent_dials.get(&ip).map_or(0, VecDeque::len)
+    }
+
+    /// Returns whether `address` is in its cooldown period.
+    #[cfg(test)]
+    pub(super) fn is_cooling_down(&self, address: &SocketAddr) -> bool {
+        self.cooling_down.contains_key(address)
+    }
+
+    /// Drops dials which have left the rate limit window and cooldowns which have ended.
+    fn purge(&mut self, now: Instant) {
+        let rate_limit_window = self.rate_limit_window;
+        self.recent_dials.retain(|_, dials| {
+            while let Some(dial) = dials.front() {
+                if now.saturating_duration_since(*dial) < rate_limit_window {
+                    break;
+                }
+                let _ = dials.pop_front();
+            }
+            !dials.is_empty()
+        });
+        self.cooling_down.retain(|_, until| now < *until);
+    }
+}
+
+/// Returns whether `ip` is in a range which is not publicly routable, e.g. loopback, private or
+/// link-local addresses.
+fn is_reserved(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_reserved_v4(ip),
+        IpAddr::V6(ip) => is_reserved_v6(ip),
+    }
+}
+
+fn is_reserved_v4(ip: Ipv4Addr) -> bool {
+    let octets = ip.octets();
+    ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // "This network", 0.0.0.0/8.
+        || octets[0] == 0
+        // Shared address space, 100.64.0.0/10.
+        || (octets[0] == 100 && octets[1] & 0b1100_0000 == 64)
+        // IETF protocol assignments, 192.0.0.0/24.
+        || (octets[0] == 192 && octets[1] == 0 && octets[2] == 0)
+        // Benchmarking, 198.18.0.0/15.
+        || (octets[0] == 198 && octets[1] & 0b1111_1110 == 18)
+        // Reserved for future use, 240.0.0.0/4.
+        || octets[0] & 0b1111_0000 == 240
+}
+
+fn is_reserved_v6(ip: Ipv6Addr) -> bool {
+    if let Some(ipv4) = ip.to_ipv4() {
+        // IPv4-mapped and IPv4-compatible addresses, excluding `::` and `::1` which are handled
+        // below.
+        if !ip.is_unspecified() && !ip.is_loopback() {
+            return is_reserved_v4(ipv4);
+        }
+    }
+    let segments = ip.segments();
+    ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // Unique local, fc00::/7.
+        || segments[0] & 0xfe00 == 0xfc00
+        // Link-local, fe80::/10.
+        || segments[0] & 0xffc0 == 0xfe80
+        // Documentation, 2001:db8::/32.
+        || (segments[0] == 0x2001 && segments[1] == 0x0db8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(30);
+    const COOLDOWN: Duration = Duration::from_secs(300);
+
+    fn guard(allow_private_addresses: bool, max_dials_per_ip: u32) -> DialGuard {
+        DialGuard::new(&Config {
+            allow_private_addresses,
+            max_dials_per_ip,
+            gossip_interval: RATE_LIMIT_WINDOW,
+            dial_cooldown: COOLDOWN,
+            ..Config::default()
+        })
+    }
+
+    #[test]
+    fn should_detect_reserved_addresses() {
+        let reserved = [
+            "0.0.0.0",
+            "0.1.2.3",
+            "10.0.0.1",
+            "100.64.0.1",
+            "100.127.255.254",
+            "127.0.0.1",
+            "169.254.1.1",
+            "172.16.0.1",
+            "192.0.0.8",
+            "192.0.2.1",
+            "192.168.1.1",
+            "198.18.0.1",
+            "198.51.100.1",
+            "203.0.113.1",
+            "224.0.0.1",
+            "254.1.1.1",
+            "255.255.255.255",
+            "::",
+            "::1",
+            "::ffff:10.0.0.1",
+            "fc00::1",
+            "fd12:3456::1",
+            "fe80::1",
+            "ff02::1",
+            "2001:db8::1",
+        ];
+        for ip in reserved.iter() {
+            assert!(
+                is_reserved(ip.parse().unwrap()),
+                "{} should be reserved",
+                ip
+            );
+        }
+
+        let public = [
+            "1.1.1.1",
+            "100.128.0.1",
+            "172.32.0.1",
+            "198.20.0.1",
+            "::ffff:8.8.8.8",
+            "2606:4700::1111",
+        ];
+        for ip in public.iter() {
+            assert!(!is_reserved(ip.parse().unwrap()), "{} should be public", ip);
+        }
+    }
+
+    #[test]
+    fn should_refuse_reserved_addresses_unless_allowed() {
+        let address = SocketAddr::from(([192, 168, 1, 1], 34553));
+        let now = Instant::now();
+
+        assert_eq!(
+            guard(false, 1).permit_dial(address, now),
+            Err(DialRefusal::ReservedAddress)
+        );
+        assert_eq!(guard(true, 1).permit_dial(address, now), Ok(()));
+    }
+
+    #[test]
+    fn should_rate_limit_dials_per_ip() {
+        let mut guard = guard(false, 2);
+        let ip = IpAddr::from([1, 2, 3, 4]);
+        let now = Instant::now();
+
+        assert_eq!(guard.permit_dial((ip, 1).into(), now), Ok(()));
+        assert_eq!(guard.permit_dial((ip, 2).into(), now), Ok(()));
+        assert_eq!(
+            guard.permit_dial((ip, 3).into(), now),
+            Err(DialRefusal::RateLimited)
+        );
+        assert_eq!(guard.recent_dial_count(ip), 2);
+
+        // Other IPs are unaffected.
+        assert_eq!(
+            guard.permit_dial(SocketAddr::from(([1, 2, 3, 5], 1)), now),
+            Ok(())
+        );
+
+        // Once the window has passed, the IP can be dialed again.
+        let later = now + RATE_LIMIT_WINDOW;
+        assert_eq!(guard.permit_dial((ip, 3).into(), later), Ok(()));
+        assert_eq!(guard.recent_dial_count(ip), 1);
+    }
+
+    #[test]
+    fn should_refuse_addresses_cooling_down() {
+        let mut guard = guard(false, 10);
+        let address = SocketAddr::from(([1, 2, 3, 4], 34553));
+        let now = Instant::now();
+
+        assert_eq!(guard.permit_dial(address, now), Ok(()));
+        guard.record_failure(address, now);
+        assert!(guard.is_cooling_down(&address));
+        assert_eq!(
+            guard.permit_dial(address, now + RATE_LIMIT_WINDOW),
+            Err(DialRefusal::CoolingDown)
+        );
+
+        // Other ports of the same IP are unaffected.
+        assert_eq!(
+            guard.permit_dial(SocketAddr::from(([1, 2, 3, 4], 34554)), now),
+            Ok(())
+        );
+
+        // Once the cooldown has passed, the address can be dialed again.
+        assert_eq!(guard.permit_dial(address, now + COOLDOWN), Ok(()));
+        assert!(!guard.is_cooling_down(&address));
+    }
+}
@@ -1,10 +1,11 @@
-use std::{io, net::SocketAddr, result, time::SystemTimeError};
+use std::{io, net::SocketAddr, path::PathBuf, result, time::SystemTimeError};
 
 use openssl::error::ErrorStack;
 use thiserror::Error;
 use tokio::net::TcpStream;
 use tokio_openssl::HandshakeError;
 
+use super::codec::CodecError;
 use crate::tls::ValidationError;
 
 pub(super) type Result<T> = result::Result<T, Error>;
@@ -41,7 +42,7 @@ pub enum Error {
     ResolveAddr(#[source] io::Error),
     /// Failed to send message.
     #[error("failed to send message")]
-    MessageNotSent(#[source] io::Error),
+    MessageNotSent(#[source] CodecError),
     /// Failed to create TLS acceptor.
     #[error("failed to create acceptor")]
     AcceptorCreation(#[source] ErrorStack),
@@ -66,6 +67,15 @@ pub enum Error {
     /// Other error.
     #[error(transparent)]
     Anyhow(#[from] anyhow::Error),
+    /// Failed to read or write the ban list file.
+    #[error("failed to access ban list file {}", .1.display())]
+    BanListIo(#[source] io::Error, PathBuf),
+    /// Failed to parse or serialize the ban list.
+    #[error("failed to parse ban list file {}", .1.display())]
+    BanListParse(#[source] serde_json::Error, PathBuf),
+    /// Failed to register the networking metrics.
+    #[error("failed to register metrics: {0}")]
+    Metrics(#[from] prometheus::Error),
     /// Server has stopped.
     #[error("failed to create outgoing connection as server has stopped")]
     ServerStopped,
@@ -1,13 +1,12 @@
 use std::{
     fmt::{self, Debug, Display, Formatter},
-    io,
     net::SocketAddr,
 };
 
 use derive_more::From;
 use tokio::net::TcpStream;
 
-use super::{Error, GossipedAddress, Message, NodeId, Transport};
+use super::{CodecError, Error, GossipedAddress, Message, NodeId, Transport};
 use crate::effect::requests::{NetworkInfoRequest, NetworkRequest};
 
 #[derive(Debug, From)]
@@ -31,7 +30,7 @@ pub enum Event<P> {
     IncomingMessage { peer_id: NodeId, msg: Message<P> },
     /// Incoming connection closed.
     IncomingClosed {
-        result: io::Result<()>,
+        result: Result<(), CodecError>,
         peer_id: NodeId,
         peer_address: SocketAddr,
     },
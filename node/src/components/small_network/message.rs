@@ -1,12 +1,61 @@
 use std::fmt::{self, Debug, Display, Formatter};
 
-use serde::{Deserialize, Serialize};
+use semver::Version;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-pub struct Message<P>(pub(super) P);
+/// The first protocol version whose nodes are able to decode `Message::Departure`.
+const DEPARTURE_PROTOCOL_VERSION: (u64, u64, u64) = (1, 1, 0);
+
+/// Returns whether all nodes of a network running `protocol_version` can decode
+/// `Message::Departure`.
+pub(super) fn supports_departure(protocol_version: &Version) -> bool {
+    *protocol_version >= Version::from(DEPARTURE_PROTOCOL_VERSION)
+}
+
+#[derive(Clone, Debug)]
+pub enum Message<P> {
+    /// A payload message, handed to the reactor.
+    Payload(P),
+    /// Sent by a node that is shutting down gracefully, before closing its connections.
+    ///
+    /// Only sent once the network runs a protocol version which `supports_departure`, since older
+    /// nodes fail to decode it.
+    Departure,
+}
+
+/// The wire format of a `Message`.
+///
+/// Before departure messages were introduced, a message was a newtype struct `Message(P)`.  With
+/// MessagePack, `Some(payload)` is encoded just like `payload`, so payload messages kept their
+/// encoding, while a departure message is encoded as `nil`.  Compatibility with other builds is
+/// governed by the codec's `MESSAGE_SCHEMA_VERSION`: peers with a different schema version refuse
+/// each other's messages, whatever their contents.
+#[derive(Deserialize, Serialize)]
+#[serde(rename = "Message")]
+struct WireMessage<P>(Option<P>);
+
+impl<P: Serialize> Serialize for Message<P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let payload = match self {
+            Message::Payload(payload) => Some(payload),
+            Message::Departure => None,
+        };
+        WireMessage(payload).serialize(serializer)
+    }
+}
+
+impl<'de, P: Deserialize<'de>> Deserialize<'de> for Message<P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let WireMessage(payload) = WireMessage::deserialize(deserializer)?;
+        Ok(payload.map_or(Message::Departure, Message::Payload))
+    }
+}
 
 impl<P: Display> Display for Message<P> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "payload: {}", self.0)
+        match self {
+            Message::Payload(payload) => write!(f, "payload: {}", payload),
+            Message::Departure => write!(f, "departure"),
+        }
     }
 }
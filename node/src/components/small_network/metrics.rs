@@ -0,0 +1,66 @@
+use prometheus::{IntGauge, Registry};
+
+/// Metrics for the small network component.
+#[derive(Debug)]
+pub struct NetworkMetrics {
+    /// Number of peers we hold an incoming or outgoing connection to.
+    pub(super) connected_peers: IntGauge,
+    /// Number of outgoing connections.
+    pub(super) outgoing_connections: IntGauge,
+    /// Number of incoming connections.
+    pub(super) incoming_connections: IntGauge,
+    /// Number of outgoing connections currently being established.
+    pub(super) pending_connections: IntGauge,
+    /// Reference to the registry for unregistering.
+    registry: Registry,
+}
+
+impl NetworkMetrics {
+    /// Creates a new instance of small network metrics.
+    pub fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
+        let connected_peers = IntGauge::new(
+            "small_network_connected_peers",
+            "number of peers with an incoming or outgoing connection to this node",
+        )?;
+        let outgoing_connections = IntGauge::new(
+            "small_network_outgoing_connections",
+            "number of outgoing connections to peers",
+        )?;
+        let incoming_connections = IntGauge::new(
+            "small_network_incoming_connections",
+            "number of incoming connections from peers",
+        )?;
+        let pending_connections = IntGauge::new(
+            "small_network_pending_connections",
+            "number of outgoing connections currently being established",
+        )?;
+        registry.register(Box::new(connected_peers.clone()))?;
+        registry.register(Box::new(outgoing_connections.clone()))?;
+        registry.register(Box::new(incoming_connections.clone()))?;
+        registry.register(Box::new(pending_connections.clone()))?;
+        Ok(NetworkMetrics {
+            connected_peers,
+            outgoing_connections,
+            incoming_connections,
+            pending_connections,
+            registry: registry.clone(),
+        })
+    }
+}
+
+impl Drop for NetworkMetrics {
+    fn drop(&mut self) {
+        self.registry
+            .unregister(Box::new(self.connected_peers.clone()))
+            .expect("did not expect deregistering connected peers to fail");
+        self.registry
+            .unregister(Box::new(self.outgoing_connections.clone()))
+            .expect("did not expect deregistering outgoing connections to fail");
+        self.registry
+            .unregister(Box::new(self.incoming_connections.clone()))
+            .expect("did not expect deregistering incoming connections to fail");
+        self.registry
+            .unregister(Box::new(self.pending_connections.clone()))
+            .expect("did not expect deregistering pending connections to fail");
+    }
+}
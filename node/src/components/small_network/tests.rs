@@ -6,14 +6,17 @@
 use std::{
     collections::{HashMap, HashSet},
     fmt::{self, Debug, Display, Formatter},
-    net::SocketAddr,
+    net::{IpAddr, SocketAddr},
     time::{Duration, Instant},
 };
 
 use derive_more::From;
 use pnet::datalink;
 use prometheus::Registry;
+use semver::Version;
 use serde::{Deserialize, Serialize};
+use tempfile::TempDir;
+use tokio::time;
 use tracing::{debug, info};
 
 use crate::{
@@ -24,19 +27,22 @@ use crate::{
     },
     effect::{
         announcements::{GossiperAnnouncement, NetworkAnnouncement},
-        requests::{NetworkRequest, StorageRequest},
-        EffectBuilder, Effects,
+        requests::{NetworkInfoRequest, NetworkRequest, StorageRequest},
+        EffectBuilder, EffectExt, Effects,
     },
     protocol,
     reactor::{self, EventQueueHandle, Finalize, Reactor, Runner},
-    small_network::{self, Config, GossipedAddress, NodeId, SmallNetwork},
+    small_network::{
+        self, ban_list::BanList, BanTarget, Config, GossipedAddress, NodeId, SmallNetwork,
+        BAN_LIST_FILENAME,
+    },
     testing::{
         self, init_logging,
         network::{Network, NetworkedReactor},
         ConditionCheckReactor, TestRng,
     },
-    types::CryptoRngCore,
-    utils::Source,
+    types::{CryptoRngCore, Timestamp},
+    utils::{Source, WithDir},
 };
 
 /// Test-reactor event.
@@ -49,6 +55,8 @@ enum Event {
     #[from]
     NetworkRequest(NetworkRequest<NodeId, Message>),
     #[from]
+    NetworkInfoRequest(NetworkInfoRequest<NodeId>),
+    #[from]
     NetworkAnnouncement(NetworkAnnouncement<NodeId, Message>),
     #[from]
     AddressGossiperAnnouncement(GossiperAnnouncement<GossipedAddress>),
@@ -97,6 +105,11 @@ impl Display for Message {
 struct TestReactor {
     net: SmallNetwork<Event, Message>,
     address_gossiper: Gossiper<GossipedAddress, Event>,
+    /// Every address announced by the address gossiper as received via gossip.
+    received_addresses: Vec<GossipedAddress>,
+    /// Every message received from the network, along with its sender.
+    received_messages: Vec<(NodeId, Message)>,
+    root_dir: TempDir,
 }
 
 impl Reactor for TestReactor {
@@ -110,7 +123,14 @@ impl Reactor for TestReactor {
         event_queue: EventQueueHandle<Self::Event>,
         _rng: &mut dyn CryptoRngCore,
     ) -> anyhow::Result<(Self, Effects<Self::Event>)> {
-        let (net, effects) = SmallNetwork::new(event_queue, cfg, false)?;
+        let root_dir = tempfile::tempdir()?;
+        let (net, effects) = SmallNetwork::new(
+            event_queue,
+            WithDir::new(root_dir.path(), cfg),
+            registry,
+            false,
+            &Version::new(1, 0, 0),
+        )?;
         let gossiper_config = gossiper::Config::default();
         let address_gossiper =
             Gossiper::new_for_complete_items("address_gossiper", gossiper_config, registry)?;
@@ -119,6 +139,9 @@ impl Reactor for TestReactor {
             TestReactor {
                 net,
                 address_gossiper,
+                received_addresses: Vec::new(),
+                received_messages: Vec::new(),
+                root_dir,
             },
             reactor::wrap_effects(Event::SmallNet, effects),
         ))
@@ -145,10 +168,16 @@ impl Reactor for TestReactor {
                 rng,
                 Event::SmallNet(small_network::Event::from(req)),
             ),
+            Event::NetworkInfoRequest(req) => self.dispatch_event(
+                effect_builder,
+                rng,
+                Event::SmallNet(small_network::Event::from(req)),
+            ),
             Event::NetworkAnnouncement(NetworkAnnouncement::MessageReceived {
                 sender,
                 payload,
             }) => {
+                self.received_messages.push((sender, payload.clone()));
                 let reactor_event = match payload {
                     Message::AddressGossiper(message) => {
                         Event::AddressGossiper(gossiper::Event::MessageReceived { sender, message })
@@ -156,7 +185,10 @@ impl Reactor for TestReactor {
                 };
                 self.dispatch_event(effect_builder, rng, reactor_event)
             }
-            Event::NetworkAnnouncement(NetworkAnnouncement::GossipOurAddress(gossiped_address)) => {
+            Event::NetworkAnnouncement(NetworkAnnouncement::GossipOurAddress(gossiped_address))
+            | Event::NetworkAnnouncement(NetworkAnnouncement::PeerAddressVerified(
+                gossiped_address,
+            )) => {
                 let event = gossiper::Event::ItemReceived {
                     item_id: gossiped_address,
                     source: Source::<NodeId>::Client,
@@ -169,6 +201,7 @@ impl Reactor for TestReactor {
             }
             Event::AddressGossiperAnnouncement(ann) => {
                 let GossiperAnnouncement::NewCompleteItem(gossiped_address) = ann;
+                self.received_addresses.push(gossiped_address);
                 let reactor_event =
                     Event::SmallNet(small_network::Event::PeerAddressReceived(gossiped_address));
                 self.dispatch_event(effect_builder, rng, reactor_event)
@@ -444,3 +477,401 @@ async fn check_varying_size_network_connects() {
         net.finalize().await;
     }
 }
+
+/// Checks whether the given node has an incoming or outgoing connection to `peer_id`.
+fn is_connected_to(
+    nodes: &HashMap<NodeId, Runner<ConditionCheckReactor<TestReactor>>>,
+    node_id: &NodeId,
+    peer_id: &NodeId,
+) -> bool {
+    let net = &nodes[node_id].reactor().inner().net;
+    net.incoming.contains_key(peer_id) || net.outgoing.contains_key(peer_id)
+}
+
+/// Check that a banned peer is disconnected and cannot reconnect.
+#[tokio::test]
+async fn banned_peer_cannot_reconnect() {
+    init_logging();
+
+    let mut rng = TestRng::new();
+
+    let first_node_port = testing::unused_port_on_localhost();
+
+    let mut net = Network::<TestReactor>::new();
+    let (first_node, _) = net
+        .add_node_with_config(
+            Config::default_local_net_first_node(first_node_port),
+            &mut rng,
+        )
+        .await
+        .unwrap();
+    let (second_node, _) = net
+        .add_node_with_config(Config::default_local_net(first_node_port), &mut rng)
+        .await
+        .unwrap();
+
+    let blocklist = HashSet::new();
+    net.settle_on(
+        &mut rng,
+        |nodes| network_is_complete(&blocklist, nodes),
+        Duration::from_secs(2),
+    )
+    .await;
+
+    // Ban the second node on the first one.
+    net.process_injected_effect_on(&first_node, |effect_builder| {
+        effect_builder
+            .network_ban_peer(BanTarget::Node(second_node), None)
+            .ignore()
+    })
+    .await;
+
+    net.settle_on(
+        &mut rng,
+        |nodes| !is_connected_to(nodes, &first_node, &second_node),
+        Duration::from_secs(2),
+    )
+    .await;
+
+    // Keep running for several gossip rounds, during which the banned node must not reconnect.
+    let deadline = Instant::now() + Duration::from_secs(3);
+    while Instant::now() < deadline {
+        if net.crank_all(&mut rng).await == 0 {
+            time::delay_for(Duration::from_millis(10)).await;
+        }
+        assert!(
+            !is_connected_to(net.nodes(), &first_node, &second_node),
+            "banned peer reconnected"
+        );
+    }
+
+    // The ban should have been persisted to the first node's root directory.
+    let root_dir = net.nodes()[&first_node].reactor().inner().root_dir.path();
+    let ban_list = BanList::load(root_dir.join(BAN_LIST_FILENAME)).unwrap();
+    assert!(ban_list.is_banned(&BanTarget::Node(second_node), Timestamp::now()));
+
+    net.finalize().await;
+}
+
+/// Check that an address gossiped to a node which doesn't listen there is not gossiped onwards, and
+/// that the node dials it only a bounded number of times.
+#[tokio::test]
+async fn bogus_gossiped_address_is_not_regossiped() {
+    init_logging();
+
+    let mut rng = TestRng::new();
+
+    const MAX_DIALS_PER_IP: u32 = 2;
+    const BOGUS_ADDRESS_COUNT: usize = 5;
+
+    let first_node_port = testing::unused_port_on_localhost();
+    let first_node_config = Config {
+        max_dials_per_ip: MAX_DIALS_PER_IP,
+        ..Config::default_local_net_first_node(first_node_port)
+    };
+
+    let mut net = Network::<TestReactor>::new();
+    let (first_node, _) = net
+        .add_node_with_config(first_node_config, &mut rng)
+        .await
+        .unwrap();
+    let (second_node, _) = net
+        .add_node_with_config(Config::default_local_net(first_node_port), &mut rng)
+        .await
+        .unwrap();
+
+    let blocklist = HashSet::new();
+    net.settle_on(
+        &mut rng,
+        |nodes| network_is_complete(&blocklist, nodes),
+        Duration::from_secs(2),
+    )
+    .await;
+
+    // Have the second node gossip several ports of an IP where no node is listening to the first.
+    let bogus_ip = IpAddr::from([127, 0, 0, 2]);
+    let bogus_addresses: Vec<_> = (0..BOGUS_ADDRESS_COUNT)
+        .map(|_| {
+            let port = testing::unused_port_on_localhost();
+            GossipedAddress::new(SocketAddr::new(bogus_ip, port), 1)
+        })
+        .collect();
+    for &bogus_address in &bogus_addresses {
+        net.process_injected_effect_on(&first_node, |effect_builder| {
+            effect_builder.immediately().event(move |_| {
+                Event::AddressGossiper(gossiper::Event::MessageReceived {
+                    sender: second_node,
+                    message: gossiper::Message::Gossip(bogus_address),
+                })
+            })
+        })
+        .await;
+    }
+
+    // Keep running for several gossip rounds.
+    let deadline = Instant::now() + Duration::from_secs(3);
+    while Instant::now() < deadline {
+        if net.crank_all(&mut rng).await == 0 {
+            time::delay_for(Duration::from_millis(10)).await;
+        }
+    }
+
+    // The first node received the bogus addresses, but dialed only as many as permitted, all of
+    // which failed.
+    let first = net.nodes()[&first_node].reactor().inner();
+    assert!(bogus_addresses
+        .iter()
+        .all(|bogus_address| first.received_addresses.contains(bogus_address)));
+    let cooling_down = bogus_addresses
+        .iter()
+        .filter(|&&bogus_address| {
+            first
+                .net
+                .dial_guard
+                .is_cooling_down(&SocketAddr::from(bogus_address))
+        })
+        .count();
+    assert_eq!(cooling_down, MAX_DIALS_PER_IP as usize);
+    assert!(first.net.pending.is_empty());
+    assert!(first.net.unverified.is_empty());
+
+    // The second node never had the bogus addresses gossiped back to it.
+    let second = net.nodes()[&second_node].reactor().inner();
+    assert!(second
+        .received_addresses
+        .iter()
+        .all(|received| !bogus_addresses.contains(received)));
+
+    net.finalize().await;
+}
+
+/// Check that an address in a private range is never dialed unless explicitly allowed.
+#[tokio::test]
+async fn private_gossiped_address_is_not_dialed() {
+    init_logging();
+
+    let mut rng = TestRng::new();
+
+    let port = testing::unused_port_on_localhost();
+    let config = Config {
+        allow_private_addresses: false,
+        ..Config::default_local_net_first_node(port)
+    };
+
+    let mut net = Network::<TestReactor>::new();
+    let (node_id, _) = net.add_node_with_config(config, &mut rng).await.unwrap();
+
+    let private_address = SocketAddr::from(([10, 0, 0, 1], 34553));
+    net.process_injected_effect_on(&node_id, |effect_builder| {
+        effect_builder.immediately().event(move |_| {
+            Event::SmallNet(small_network::Event::PeerAddressReceived(
+                GossipedAddress::new(private_address, 1),
+            ))
+        })
+    })
+    .await;
+    net.crank_until(
+        &node_id,
+        &mut rng,
+        |event| {
+            matches!(
+                event,
+                Event::SmallNet(small_network::Event::PeerAddressReceived(_))
+            )
+        },
+        Duration::from_secs(1),
+    )
+    .await;
+
+    let small_net = &net.nodes()[&node_id].reactor().inner().net;
+    assert!(!small_net.pending.contains(&private_address));
+    assert_eq!(
+        small_net.dial_guard.recent_dial_count(private_address.ip()),
+        0
+    );
+
+    net.finalize().await;
+}
+
+/// Check that the connection metrics of a two-node network reflect the single connected peer.
+#[tokio::test]
+async fn two_node_network_reports_connected_peers() {
+    init_logging();
+
+    let mut rng = TestRng::new();
+
+    let first_node_port = testing::unused_port_on_localhost();
+
+    let mut net = Network::<TestReactor>::new();
+    net.add_node_with_config(
+        Config::default_local_net_first_node(first_node_port),
+        &mut rng,
+    )
+    .await
+    .unwrap();
+    net.add_node_with_config(Config::default_local_net(first_node_port), &mut rng)
+        .await
+        .unwrap();
+
+    let blocklist = HashSet::new();
+    net.settle_on(
+        &mut rng,
+        |nodes| network_is_complete(&blocklist, nodes),
+        Duration::from_secs(2),
+    )
+    .await;
+
+    for runner in net.nodes().values() {
+        let metrics = &runner.reactor().inner().net.metrics;
+        assert_eq!(metrics.connected_peers.get(), 1);
+        assert_eq!(metrics.outgoing_connections.get(), 1);
+        assert_eq!(metrics.incoming_connections.get(), 1);
+    }
+
+    net.finalize().await;
+}
+
+/// Check that a node given the same known address several times dials it only once.
+#[tokio::test]
+async fn duplicate_known_addresses_are_dialed_once() {
+    init_logging();
+
+    let mut rng = TestRng::new();
+
+    let first_node_port = testing::unused_port_on_localhost();
+    let mut second_node_config = Config::default_local_net(first_node_port);
+    let known_address = second_node_config.known_addresses[0].clone();
+    second_node_config
+        .known_addresses
+        .extend(vec![known_address; 2]);
+
+    let mut net = Network::<TestReactor>::new();
+    let (first_node, _) = net
+        .add_node_with_config(
+            Config::default_local_net_first_node(first_node_port),
+            &mut rng,
+        )
+        .await
+        .unwrap();
+    let (second_node, _) = net
+        .add_node_with_config(second_node_config, &mut rng)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        net.nodes()[&second_node]
+            .reactor()
+            .inner()
+            .net
+            .pending
+            .len(),
+        1
+    );
+
+    let blocklist = HashSet::new();
+    net.settle_on(
+        &mut rng,
+        |nodes| network_is_complete(&blocklist, nodes),
+        Duration::from_secs(2),
+    )
+    .await;
+
+    let second = &net.nodes()[&second_node].reactor().inner().net;
+    assert!(second.pending.is_empty());
+    assert_eq!(second.metrics.pending_connections.get(), 0);
+    assert_eq!(second.metrics.outgoing_connections.get(), 1);
+    let first = &net.nodes()[&first_node].reactor().inner().net;
+    assert_eq!(first.metrics.incoming_connections.get(), 1);
+
+    net.finalize().await;
+}
+
+/// Check that a message broadcast with an exclusion list does not reach the excluded peer.
+#[tokio::test]
+async fn broadcast_except_skips_excluded_peer() {
+    init_logging();
+
+    let mut rng = TestRng::new();
+
+    let first_node_port = testing::unused_port_on_localhost();
+
+    let mut net = Network::<TestReactor>::new();
+    let (first_node, _) = net
+        .add_node_with_config(
+            Config::default_local_net_first_node(first_node_port),
+            &mut rng,
+        )
+        .await
+        .unwrap();
+    let (second_node, _) = net
+        .add_node_with_config(Config::default_local_net(first_node_port), &mut rng)
+        .await
+        .unwrap();
+    let (third_node, _) = net
+        .add_node_with_config(Config::default_local_net(first_node_port), &mut rng)
+        .await
+        .unwrap();
+
+    let blocklist = HashSet::new();
+    net.settle_on(
+        &mut rng,
+        |nodes| network_is_complete(&blocklist, nodes),
+        Duration::from_secs(3),
+    )
+    .await;
+
+    // A gossip response for an unknown item is ignored by the receiving gossiper, so it is never
+    // forwarded and can only have been received directly from the first node.
+    let item_id = GossipedAddress::new(SocketAddr::from(([127, 0, 0, 2], 34553)), 1);
+    let message = Message::AddressGossiper(gossiper::Message::GossipResponse {
+        item_id,
+        is_already_held: true,
+    });
+    let is_broadcast_message = move |(sender, message): &(NodeId, Message)| {
+        *sender == first_node
+            && matches!(
+                message,
+                Message::AddressGossiper(gossiper::Message::GossipResponse { item_id: id, .. })
+                    if *id == item_id
+            )
+    };
+
+    net.process_injected_effect_on(&first_node, |effect_builder| {
+        let exclude = [third_node].iter().copied().collect();
+        effect_builder
+            .broadcast_message_except(message, exclude)
+            .ignore()
+    })
+    .await;
+
+    net.settle_on(
+        &mut rng,
+        |nodes| {
+            nodes[&second_node]
+                .reactor()
+                .inner()
+                .received_messages
+                .iter()
+                .any(is_broadcast_message)
+        },
+        Duration::from_secs(2),
+    )
+    .await;
+
+    // Keep running for a while to give a wrongly sent message the chance to arrive.
+    let deadline = Instant::now() + Duration::from_secs(1);
+    while Instant::now() < deadline {
+        if net.crank_all(&mut rng).await == 0 {
+            time::delay_for(Duration::from_millis(10)).await;
+        }
+    }
+
+    assert!(!net.nodes()[&third_node]
+        .reactor()
+        .inner()
+        .received_messages
+        .iter()
+        .any(is_broadcast_message));
+
+    net.finalize().await;
+}
@@ -9,7 +9,10 @@ mod in_mem_store;
 mod lmdb_block_height_store;
 mod lmdb_chainspec_store;
 mod lmdb_store;
+mod metrics;
 mod store;
+#[cfg(test)]
+mod tests;
 
 use std::{
     collections::HashMap,
@@ -17,10 +20,12 @@ use std::{
     fs,
     hash::Hash,
     sync::Arc,
+    time::Duration,
 };
 
 use datasize::DataSize;
-use futures::TryFutureExt;
+use futures::{future::BoxFuture, FutureExt, TryFutureExt};
+use prometheus::Registry;
 use semver::Version;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use smallvec::{smallvec, SmallVec};
@@ -38,8 +43,10 @@ use crate::{
         EffectBuilder, EffectExt, Effects, Responder,
     },
     protocol::Message,
+    reactor::Finalize,
     types::{
-        json_compatibility::ExecutionResult, Block, CryptoRngCore, Deploy, Item, ProtoBlockHash,
+        json_compatibility::ExecutionResult, Block, BlockHash, ChainView, CryptoRngCore, Deploy,
+        Item, ProtoBlockHash, TimeDiff, Timestamp,
     },
     utils::WithDir,
 };
@@ -55,6 +62,7 @@ use in_mem_store::InMemStore;
 use lmdb_block_height_store::LmdbBlockHeightStore;
 use lmdb_chainspec_store::LmdbChainspecStore;
 use lmdb_store::LmdbStore;
+use metrics::StorageMetrics;
 use store::{DeployStore, Multiple, Store};
 
 pub(crate) type Storage = LmdbStorage<Block, Deploy>;
@@ -70,6 +78,10 @@ const BLOCK_HEIGHT_STORE_FILENAME: &str = "block_height_store.db";
 const DEPLOY_STORE_FILENAME: &str = "deploy_store.db";
 const CHAINSPEC_STORE_FILENAME: &str = "chainspec_store.db";
 
+/// How long after expiring a deploy is kept, so that one which is in the process of being included
+/// in a block isn't deleted before that block is stored.
+const EXPIRED_DEPLOY_RETENTION: Duration = Duration::from_secs(60 * 60);
+
 pub trait ValueT: Clone + Serialize + DeserializeOwned + Send + Sync + Debug + Display {}
 impl<T> ValueT for T where T: Clone + Serialize + DeserializeOwned + Send + Sync + Debug + Display {}
 
@@ -111,6 +123,11 @@ pub trait WithBlockHeight: Value {
     fn height(&self) -> u64;
 }
 
+pub trait WithExpiry: Value {
+    /// Returns whether the value had expired by `current_instant`.
+    fn expired(&self, current_instant: Timestamp) -> bool;
+}
+
 /// Metadata associated with a block.
 #[derive(Default, Clone, Serialize, Deserialize, Debug)]
 pub struct BlockMetadata {
@@ -172,13 +189,68 @@ impl LmdbStorage<Block, Deploy> {
     }
 }
 
+/// Deletes the deploys which had expired by `expired_before` without being included in any block
+/// held in `block_store`, along with their metadata.
+///
+/// The blocks including a deploy are those its execution results were stored for, so only these
+/// are looked up rather than every stored block.
+///
+/// Returns the number of deploys deleted and an estimate of the number of bytes reclaimed.
+fn delete_expired_deploys<B: Value, D: WithExpiry>(
+    block_store: &dyn Store<Value = B>,
+    deploy_store: &dyn DeployStore<Block = B, Deploy = D, Value = D>,
+    expired_before: Timestamp,
+) -> Result<(u64, u64)> {
+    let mut deleted_count = 0;
+    let mut reclaimed_bytes = 0;
+    for deploy_hash in deploy_store.ids()? {
+        let (deploy, metadata) = match deploy_store.get_deploy_and_metadata(deploy_hash)? {
+            Some(deploy_and_metadata) => deploy_and_metadata,
+            None => continue,
+        };
+        if !deploy.expired(expired_before) {
+            continue;
+        }
+        let block_hashes: Multiple<B::Id> = metadata.execution_results.keys().copied().collect();
+        if !block_hashes.is_empty() {
+            let block_headers = block_store
+                .get_headers(block_hashes)
+                .into_iter()
+                .collect::<Result<Multiple<_>>>()?;
+            if block_headers.iter().any(Option::is_some) {
+                continue;
+            }
+        }
+        if let Some(size) = deploy_store.delete(deploy_hash)? {
+            deleted_count += 1;
+            reclaimed_bytes += size;
+        }
+    }
+    Ok((deleted_count, reclaimed_bytes))
+}
+
+impl ChainView for LmdbStorage<Block, Deploy> {
+    /// Reads the block from the block store, blocking the calling thread.
+    fn get_parent(&self, block_hash: &BlockHash) -> Option<BlockHash> {
+        self.block_store()
+            .get(smallvec![*block_hash])
+            .pop()
+            .expect("can only contain one result")
+            .unwrap_or_else(|error| {
+                warn!("failed to get {}: {}", block_hash, error);
+                None
+            })
+            .map(|block| *block.header().parent_hash())
+    }
+}
+
 /// Trait which will handle management of the various storage sub-components.
 ///
 /// If this trait is ultimately only used for testing scenarios, we shouldn't need to expose it to
 /// the reactor - it can simply use a concrete type which implements this trait.
 pub trait StorageType {
     type Block: Value + WithBlockHeight;
-    type Deploy: Value + Item;
+    type Deploy: Value + Item + WithExpiry;
 
     fn block_store(&self) -> Arc<dyn Store<Value = Self::Block>>;
 
@@ -190,10 +262,58 @@ pub trait StorageType {
 
     fn chainspec_store(&self) -> Arc<dyn ChainspecStore>;
 
-    fn new(config: WithDir<Config>) -> Result<Self>
+    fn metrics(&self) -> Arc<StorageMetrics>;
+
+    /// Returns the interval between runs of the deploy store cleanup.
+    fn cleanup_interval(&self) -> Duration;
+
+    fn new(config: WithDir<Config>, registry: &Registry) -> Result<Self>
     where
         Self: Sized;
 
+    /// Schedules the first run of the deploy store cleanup, after which each run schedules the
+    /// next.
+    fn schedule_cleanup<REv>(&self, effect_builder: EffectBuilder<REv>) -> Effects<Event<Self>>
+    where
+        REv: Send,
+        Self: Sized,
+    {
+        effect_builder
+            .set_timeout(self.cleanup_interval())
+            .event(|_| Event::Cleanup)
+    }
+
+    /// Deletes expired deploys which aren't included in any stored block, then schedules the next
+    /// cleanup.
+    fn cleanup<REv>(&self, effect_builder: EffectBuilder<REv>) -> Effects<Event<Self>>
+    where
+        REv: Send,
+        Self: Sized,
+    {
+        let block_store = self.block_store();
+        let deploy_store = self.deploy_store();
+        let metrics = self.metrics();
+        let cleanup_interval = self.cleanup_interval();
+        let expired_before = Timestamp::now() - TimeDiff::from(EXPIRED_DEPLOY_RETENTION);
+        async move {
+            let result = task::spawn_blocking(move || {
+                delete_expired_deploys(&*block_store, &*deploy_store, expired_before)
+            })
+            .await
+            .expect("should run");
+            match result {
+                Ok((deleted_count, reclaimed_bytes)) => {
+                    metrics.deleted_deploys.inc_by(deleted_count);
+                    metrics.reclaimed_bytes.inc_by(reclaimed_bytes);
+                    debug!(%deleted_count, %reclaimed_bytes, "deleted expired deploys");
+                }
+                Err(error) => error!(%error, "failed to delete expired deploys"),
+            }
+            effect_builder.set_timeout(cleanup_interval).await
+        }
+        .event(|_| Event::Cleanup)
+    }
+
     fn get_deploy_for_peer<REv>(
         &self,
         effect_builder: EffectBuilder<REv>,
@@ -322,6 +442,57 @@ pub trait StorageType {
         .ignore()
     }
 
+    /// Returns the contiguous run of up to `count` blocks starting at `start_height`, stopping at
+    /// the first height for which no block is stored.
+    fn get_block_range(
+        &self,
+        start_height: u64,
+        count: u64,
+        responder: Responder<Vec<Self::Block>>,
+    ) -> Effects<Event<Self>>
+    where
+        Self: Sized,
+    {
+        let block_height_store = self.block_height_store();
+        let block_store = self.block_store();
+        async move {
+            let result = task::spawn_blocking(move || {
+                let mut blocks = Vec::new();
+                for block_height in start_height..start_height.saturating_add(count) {
+                    let maybe_block_hash =
+                        block_height_store
+                            .get(block_height)
+                            .unwrap_or_else(|error| {
+                                panic!(
+                                    "failed to get entry for block height {}: {}",
+                                    block_height, error
+                                )
+                            });
+                    let block_hash = match maybe_block_hash {
+                        Some(block_hash) => block_hash,
+                        None => break,
+                    };
+                    let maybe_block = block_store
+                        .get(smallvec![block_hash])
+                        .pop()
+                        .expect("can only contain one result")
+                        .unwrap_or_else(|error| {
+                            panic!("failed to get block {}: {}", block_hash, error)
+                        });
+                    match maybe_block {
+                        Some(block) => blocks.push(block),
+                        None => break,
+                    }
+                }
+                blocks
+            })
+            .await
+            .expect("should run");
+            responder.respond(result).await
+        }
+        .ignore()
+    }
+
     fn get_highest_block(&self, responder: Responder<Option<Self::Block>>) -> Effects<Event<Self>>
     where
         Self: Sized,
@@ -564,6 +735,11 @@ where
             Event::Request(StorageRequest::GetBlockAtHeight { height, responder }) => {
                 self.get_block_at_height(height, responder)
             }
+            Event::Request(StorageRequest::GetBlockRange {
+                start_height,
+                count,
+                responder,
+            }) => self.get_block_range(start_height, count, responder),
             Event::Request(StorageRequest::GetHighestBlock { responder }) => {
                 self.get_highest_block(responder)
             }
@@ -598,6 +774,7 @@ where
             Event::Request(StorageRequest::GetChainspec { version, responder }) => {
                 self.get_chainspec(version, responder)
             }
+            Event::Cleanup => self.cleanup(effect_builder),
         }
     }
 }
@@ -609,13 +786,15 @@ pub(crate) struct InMemStorage<B: Value, D: Value> {
     block_height_store: Arc<InMemBlockHeightStore<B::Id>>,
     deploy_store: Arc<InMemStore<D, DeployMetadata<B>>>,
     chainspec_store: Arc<InMemChainspecStore>,
+    metrics: Arc<StorageMetrics>,
+    cleanup_interval: Duration,
 }
 
 #[allow(trivial_casts)]
 impl<B, D> StorageType for InMemStorage<B, D>
 where
     B: Value + WithBlockHeight + 'static,
-    D: Value + Item + 'static,
+    D: Value + Item + WithExpiry + 'static,
 {
     type Block = B;
     type Deploy = D;
@@ -636,12 +815,22 @@ where
         Arc::clone(&self.chainspec_store) as Arc<dyn ChainspecStore>
     }
 
-    fn new(_config: WithDir<Config>) -> Result<Self> {
+    fn metrics(&self) -> Arc<StorageMetrics> {
+        Arc::clone(&self.metrics)
+    }
+
+    fn cleanup_interval(&self) -> Duration {
+        self.cleanup_interval
+    }
+
+    fn new(config: WithDir<Config>, registry: &Registry) -> Result<Self> {
         Ok(InMemStorage {
             block_store: Arc::new(InMemStore::new()),
             block_height_store: Arc::new(InMemBlockHeightStore::new()),
             deploy_store: Arc::new(InMemStore::new()),
             chainspec_store: Arc::new(InMemChainspecStore::new()),
+            metrics: Arc::new(StorageMetrics::new(registry)?),
+            cleanup_interval: config.value().cleanup_interval().into(),
         })
     }
 }
@@ -657,18 +846,21 @@ where
     block_height_store: Arc<LmdbBlockHeightStore>,
     deploy_store: Arc<LmdbStore<D, DeployMetadata<B>>>,
     chainspec_store: Arc<LmdbChainspecStore>,
+    #[data_size(skip)]
+    metrics: Arc<StorageMetrics>,
+    cleanup_interval: Duration,
 }
 
 #[allow(trivial_casts)]
 impl<B, D> StorageType for LmdbStorage<B, D>
 where
     B: Value + WithBlockHeight + 'static,
-    D: Value + Item + 'static,
+    D: Value + Item + WithExpiry + 'static,
 {
     type Block = B;
     type Deploy = D;
 
-    fn new(config: WithDir<Config>) -> Result<Self> {
+    fn new(config: WithDir<Config>, registry: &Registry) -> Result<Self> {
         let root = config.with_dir(config.value().path());
         fs::create_dir_all(&root).map_err(|error| Error::CreateDir {
             dir: root.display().to_string(),
@@ -697,6 +889,8 @@ where
             block_height_store: Arc::new(block_height_store),
             deploy_store: Arc::new(deploy_store),
             chainspec_store: Arc::new(chainspec_store),
+            metrics: Arc::new(StorageMetrics::new(registry)?),
+            cleanup_interval: config.value().cleanup_interval().into(),
         })
     }
 
@@ -715,4 +909,43 @@ where
     fn chainspec_store(&self) -> Arc<dyn ChainspecStore> {
         Arc::clone(&self.chainspec_store) as Arc<dyn ChainspecStore>
     }
+
+    fn metrics(&self) -> Arc<StorageMetrics> {
+        Arc::clone(&self.metrics)
+    }
+
+    fn cleanup_interval(&self) -> Duration {
+        self.cleanup_interval
+    }
+}
+
+impl<B, D> LmdbStorage<B, D>
+where
+    B: Value,
+    D: Value,
+{
+    /// Flushes all stores to disk.
+    fn flush(&self) -> Result<()> {
+        self.block_store.flush()?;
+        self.block_height_store.flush()?;
+        self.deploy_store.flush()?;
+        self.chainspec_store.flush()
+    }
+}
+
+impl<B, D> Finalize for LmdbStorage<B, D>
+where
+    B: Value + 'static,
+    D: Value + 'static,
+{
+    fn finalize(self) -> BoxFuture<'static, ()> {
+        async move {
+            match task::spawn_blocking(move || self.flush()).await {
+                Ok(Ok(())) => debug!("flushed storage"),
+                Ok(Err(error)) => error!(%error, "failed to flush storage"),
+                Err(error) => error!(%error, "failed to join storage flush task"),
+            }
+        }
+        .boxed()
+    }
 }
@@ -9,6 +9,8 @@ use tracing::warn;
 
 use casper_execution_engine::shared::utils;
 
+use crate::types::TimeDiff;
+
 const QUALIFIER: &str = "io";
 const ORGANIZATION: &str = "CasperLabs";
 const APPLICATION: &str = "casper-node";
@@ -17,6 +19,7 @@ const DEFAULT_MAX_BLOCK_STORE_SIZE: usize = 483_183_820_800; // 450 GiB
 const DEFAULT_MAX_DEPLOY_STORE_SIZE: usize = 322_122_547_200; // 300 GiB
 const DEFAULT_MAX_BLOCK_HEIGHT_STORE_SIZE: usize = 10_485_100; // 10 MiB
 const DEFAULT_MAX_CHAINSPEC_STORE_SIZE: usize = 1_073_741_824; // 1 GiB
+const DEFAULT_CLEANUP_INTERVAL_MILLIS: u64 = 3_600_000; // 1 hour
 
 #[cfg(test)]
 const DEFAULT_TEST_MAX_DB_SIZE: usize = 52_428_800; // 50 MiB
@@ -56,6 +59,11 @@ pub struct Config {
     ///
     /// The size should be a multiple of the OS page size.
     max_chainspec_store_size: Option<usize>,
+    /// The interval between runs of the deploy store cleanup, which deletes deploys whose TTL has
+    /// expired without them being included in any stored block.
+    ///
+    /// Defaults to 1 hour.
+    cleanup_interval: Option<TimeDiff>,
 }
 
 impl Config {
@@ -72,6 +80,7 @@ impl Config {
             max_deploy_store_size: Some(DEFAULT_TEST_MAX_DB_SIZE),
             max_block_height_store_size: Some(DEFAULT_TEST_MAX_DB_SIZE),
             max_chainspec_store_size: Some(DEFAULT_TEST_MAX_DB_SIZE),
+            cleanup_interval: None,
         };
         (config, tempdir)
     }
@@ -112,6 +121,11 @@ impl Config {
         value
     }
 
+    pub(crate) fn cleanup_interval(&self) -> TimeDiff {
+        self.cleanup_interval
+            .unwrap_or_else(|| TimeDiff::from(DEFAULT_CLEANUP_INTERVAL_MILLIS))
+    }
+
     fn default_path() -> PathBuf {
         ProjectDirs::from(QUALIFIER, ORGANIZATION, APPLICATION)
             .map(|project_dirs| project_dirs.data_dir().to_path_buf())
@@ -132,6 +146,7 @@ impl Default for Config {
             max_deploy_store_size: Some(DEFAULT_MAX_DEPLOY_STORE_SIZE),
             max_block_height_store_size: Some(DEFAULT_MAX_BLOCK_HEIGHT_STORE_SIZE),
             max_chainspec_store_size: Some(DEFAULT_MAX_CHAINSPEC_STORE_SIZE),
+            cleanup_interval: Some(TimeDiff::from(DEFAULT_CLEANUP_INTERVAL_MILLIS)),
         }
     }
 }
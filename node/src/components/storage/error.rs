@@ -24,6 +24,10 @@ pub enum Error {
     #[error("deserialization: {0}")]
     Deserialization(#[source] bincode::ErrorKind),
 
+    /// Failed to register the storage metrics.
+    #[error("metrics: {0}")]
+    Metrics(#[from] prometheus::Error),
+
     /// Internal storage component error.
     #[error("internal: {0}")]
     Internal(Box<dyn StdError + Send + Sync>),
@@ -15,6 +15,8 @@ pub enum Event<S: StorageType + 'static> {
     },
     #[from]
     Request(StorageRequest<S>),
+    /// The timer for the periodic deploy store cleanup has fired.
+    Cleanup,
 }
 
 impl<S: StorageType + 'static> Display for Event<S> {
@@ -24,6 +26,7 @@ impl<S: StorageType + 'static> Display for Event<S> {
                 write!(formatter, "get deploy {} for {}", deploy_hash, peer)
             }
             Event::Request(request) => write!(formatter, "{}", request),
+            Event::Cleanup => write!(formatter, "cleanup"),
         }
     }
 }
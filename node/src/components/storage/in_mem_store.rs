@@ -4,7 +4,7 @@ use std::{
     sync::RwLock,
 };
 
-use super::{DeployMetadata, DeployStore, Multiple, Result, Store, Value};
+use super::{DeployMetadata, DeployStore, Error, Multiple, Result, Store, Value};
 use crate::types::json_compatibility::ExecutionResult;
 
 #[derive(Debug)]
@@ -130,4 +130,26 @@ impl<D: Value, B: Value> DeployStore for InMemStore<D, DeployMetadata<B>> {
                     .map(|value| (value.clone(), value_and_metadata.metadata.clone()))
             }))
     }
+
+    fn delete(&self, id: D::Id) -> Result<Option<u64>> {
+        let mut inner = self.inner.write().expect("should lock");
+        let value_and_metadata = match inner.remove(&id) {
+            Some(value_and_metadata) => value_and_metadata,
+            None => return Ok(None),
+        };
+        match value_and_metadata.value {
+            Some(ref value) => {
+                let value_size = bincode::serialized_size(value)
+                    .map_err(|error| Error::from_serialization(*error))?;
+                let metadata_size = bincode::serialized_size(&value_and_metadata.metadata)
+                    .map_err(|error| Error::from_serialization(*error))?;
+                Ok(Some(value_size + metadata_size))
+            }
+            None => {
+                // Only metadata was held for this ID, so leave it in place.
+                let _ = inner.insert(id, value_and_metadata);
+                Ok(None)
+            }
+        }
+    }
 }
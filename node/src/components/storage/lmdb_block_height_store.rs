@@ -48,6 +48,11 @@ impl LmdbBlockHeightStore {
 
         Ok(LmdbBlockHeightStore { env, db, highest })
     }
+
+    /// Flushes the data buffers to disk.
+    pub(super) fn flush(&self) -> Result<()> {
+        self.env.sync(true).map_err(Error::from)
+    }
 }
 
 impl<H: Serialize + for<'de> Deserialize<'de>> BlockHeightStore<H> for LmdbBlockHeightStore {
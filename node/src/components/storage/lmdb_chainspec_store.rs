@@ -27,6 +27,11 @@ impl LmdbChainspecStore {
 
         Ok(LmdbChainspecStore { env, db })
     }
+
+    /// Flushes the data buffers to disk.
+    pub(super) fn flush(&self) -> Result<()> {
+        self.env.sync(true).map_err(Error::from)
+    }
 }
 
 impl ChainspecStore for LmdbChainspecStore {
@@ -49,6 +49,11 @@ impl<V: Value, M: Default + Send + Sync> LmdbStore<V, M> {
 }
 
 impl<V: Value, M> LmdbStore<V, M> {
+    /// Flushes the data buffers to disk.
+    pub(super) fn flush(&self) -> Result<()> {
+        self.env.sync(true).map_err(Error::from)
+    }
+
     fn get_values(&self, ids: Multiple<V::Id>) -> Multiple<Result<Option<V>>> {
         let mut serialized_ids = Multiple::new();
         for id in &ids {
@@ -222,4 +227,32 @@ impl<D: Value, B: Value> DeployStore for LmdbStore<D, DeployMetadata<B>> {
         txn.commit().expect("should commit txn");
         Ok(Some((deploy, metadata)))
     }
+
+    fn delete(&self, id: D::Id) -> Result<Option<u64>> {
+        let serialized_deploy_id = Self::serialized_id(&id, None)?;
+        let serialized_metadata_id = Self::serialized_id(&id, Some(Tag::DeployMetadata))?;
+        let mut txn = self.env.begin_rw_txn().expect("should create rw txn");
+
+        let deploy_size = match txn.get(self.db, &serialized_deploy_id) {
+            Ok(serialized_value) => serialized_deploy_id.len() + serialized_value.len(),
+            Err(lmdb::Error::NotFound) => {
+                // Return `None` if the deploy doesn't exist.
+                txn.commit().expect("should commit txn");
+                return Ok(None);
+            }
+            Err(error) => panic!("should get: {:?}", error),
+        };
+        let metadata_size = match txn.get(self.db, &serialized_metadata_id) {
+            Ok(serialized_value) => serialized_metadata_id.len() + serialized_value.len(),
+            Err(lmdb::Error::NotFound) => 0,
+            Err(error) => panic!("should get: {:?}", error),
+        };
+
+        txn.del(self.db, &serialized_deploy_id, None)?;
+        if metadata_size > 0 {
+            txn.del(self.db, &serialized_metadata_id, None)?;
+        }
+        txn.commit().expect("should commit txn");
+        Ok(Some((deploy_size + metadata_size) as u64))
+    }
 }
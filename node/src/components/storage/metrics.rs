@@ -0,0 +1,43 @@
+use prometheus::{IntCounter, Registry};
+
+/// Metrics of the storage component.
+#[derive(Debug)]
+pub struct StorageMetrics {
+    /// Number of expired deploys outside any stored block deleted by the deploy store cleanup.
+    pub(super) deleted_deploys: IntCounter,
+    /// Estimated number of bytes reclaimed by the deploy store cleanup.
+    pub(super) reclaimed_bytes: IntCounter,
+    /// registry component.
+    registry: Registry,
+}
+
+impl StorageMetrics {
+    pub fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
+        let deleted_deploys = IntCounter::new(
+            "storage_deleted_deploys",
+            "number of expired deploys deleted from storage without having been included in a block",
+        )?;
+        let reclaimed_bytes = IntCounter::new(
+            "storage_reclaimed_bytes",
+            "estimated number of bytes reclaimed by deleting expired deploys from storage",
+        )?;
+        registry.register(Box::new(deleted_deploys.clone()))?;
+        registry.register(Box::new(reclaimed_bytes.clone()))?;
+        Ok(StorageMetrics {
+            deleted_deploys,
+            reclaimed_bytes,
+            registry: registry.clone(),
+        })
+    }
+}
+
+impl Drop for StorageMetrics {
+    fn drop(&mut self) {
+        self.registry
+            .unregister(Box::new(self.deleted_deploys.clone()))
+            .expect("did not expect deregistering deleted deploys to fail");
+        self.registry
+            .unregister(Box::new(self.reclaimed_bytes.clone()))
+            .expect("did not expect deregistering reclaimed bytes to fail");
+    }
+}
@@ -40,6 +40,11 @@ pub trait DeployStore: Store {
         &self,
         id: <Self::Deploy as Value>::Id,
     ) -> Result<Option<DeployAndMetadata<Self::Deploy, Self::Block>>>;
+
+    /// Deletes the deploy along with its associated metadata.
+    ///
+    /// Returns an estimate of the number of bytes reclaimed, or `None` if the deploy didn't exist.
+    fn delete(&self, id: <Self::Deploy as Value>::Id) -> Result<Option<u64>>;
 }
 
 #[cfg(test)]
@@ -0,0 +1,303 @@
+use std::{
+    fmt::{self, Formatter},
+    time::Duration,
+};
+
+use casper_execution_engine::core::engine_state::executable_deploy_item::ExecutableDeployItem;
+
+use derive_more::From;
+use prometheus::Registry;
+use rand::Rng;
+use tempfile::TempDir;
+use thiserror::Error;
+
+use super::*;
+use crate::{
+    components::consensus::EraId,
+    crypto::{
+        asymmetric_key::{PublicKey, SecretKey},
+        hash::Digest,
+    },
+    reactor::{self, EventQueueHandle, Runner},
+    testing::TestRng,
+    types::{BlockHash, DeployHash, FinalizedBlock, ProtoBlock},
+};
+
+const BLOCK_COUNT: u64 = 5;
+
+/// Top-level event for the reactor.
+#[derive(Debug, From)]
+#[must_use]
+enum Event {
+    #[from]
+    Storage(super::Event<Storage>),
+    #[from]
+    NetworkRequest(NetworkRequest<NodeId, Message>),
+    /// The highest block read back from storage.
+    HighestBlock(Option<Block>),
+}
+
+impl From<StorageRequest<Storage>> for Event {
+    fn from(request: StorageRequest<Storage>) -> Self {
+        Event::Storage(super::Event::Request(request))
+    }
+}
+
+impl Display for Event {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Storage(event) => write!(formatter, "storage: {}", event),
+            Event::NetworkRequest(req) => write!(formatter, "network request: {}", req),
+            Event::HighestBlock(maybe_block) => {
+                write!(formatter, "highest block: {:?}", maybe_block)
+            }
+        }
+    }
+}
+
+/// Error type returned by the test reactor.
+#[derive(Debug, Error)]
+enum ReactorError {
+    #[error("prometheus (metrics) error: {0}")]
+    Metrics(#[from] prometheus::Error),
+}
+
+struct Reactor {
+    storage: Storage,
+    /// The result of the last highest block query, if any.
+    highest_block: Option<Option<Block>>,
+}
+
+impl reactor::Reactor for Reactor {
+    type Event = Event;
+    type Config = WithDir<Config>;
+    type Error = ReactorError;
+
+    fn new(
+        config: Self::Config,
+        registry: &Registry,
+        _event_queue: EventQueueHandle<Self::Event>,
+        _rng: &mut dyn CryptoRngCore,
+    ) -> Result<(Self, Effects<Self::Event>), Self::Error> {
+        let reactor = Reactor {
+            storage: Storage::new(config, registry).unwrap(),
+            highest_block: None,
+        };
+        Ok((reactor, Effects::new()))
+    }
+
+    fn dispatch_event(
+        &mut self,
+        effect_builder: EffectBuilder<Self::Event>,
+        rng: &mut dyn CryptoRngCore,
+        event: Event,
+    ) -> Effects<Self::Event> {
+        match event {
+            Event::Storage(event) => reactor::wrap_effects(
+                Event::Storage,
+                self.storage.handle_event(effect_builder, rng, event),
+            ),
+            Event::NetworkRequest(req) => panic!("should not get network request {}", req),
+            Event::HighestBlock(maybe_block) => {
+                self.highest_block = Some(maybe_block);
+                Effects::new()
+            }
+        }
+    }
+}
+
+fn block_at_height(rng: &mut TestRng, height: u64) -> Block {
+    let proto_block = ProtoBlock::new(vec![], rng.gen());
+    let finalized_block = FinalizedBlock::new(
+        proto_block,
+        Timestamp::now(),
+        None,
+        EraId(0),
+        height,
+        PublicKey::random(rng),
+    );
+    Block::new(
+        BlockHash::new(Digest::random(rng)),
+        Digest::random(rng),
+        Digest::random(rng),
+        finalized_block,
+    )
+}
+
+/// Stores `blocks`, then queries the highest block, returning the result.
+async fn put_blocks_and_get_highest(
+    runner: &mut Runner<Reactor>,
+    rng: &mut TestRng,
+    blocks: Vec<Block>,
+) -> Option<Block> {
+    runner
+        .process_injected_effects(move |effect_builder| {
+            async move {
+                for block in blocks {
+                    assert!(effect_builder.put_block_to_storage(Box::new(block)).await);
+                }
+                effect_builder.get_highest_block().await
+            }
+            .event(Event::HighestBlock)
+        })
+        .await;
+    while runner.reactor().highest_block.is_none() {
+        runner.crank(rng).await;
+    }
+    runner.reactor_mut().highest_block.take().unwrap()
+}
+
+#[tokio::test]
+async fn should_get_highest_block_after_restart() {
+    let mut rng = TestRng::new();
+    let (storage_config, tempdir): (Config, TempDir) = Config::default_for_tests();
+    let config = WithDir::new(tempdir.path(), storage_config);
+
+    let blocks: Vec<Block> = (0..BLOCK_COUNT)
+        .map(|height| block_at_height(&mut rng, height))
+        .collect();
+
+    let mut runner = Runner::<Reactor>::new(config.clone(), &mut rng)
+        .await
+        .unwrap();
+    assert_eq!(
+        put_blocks_and_get_highest(&mut runner, &mut rng, vec![]).await,
+        None
+    );
+    assert_eq!(
+        put_blocks_and_get_highest(&mut runner, &mut rng, blocks.clone())
+            .await
+            .as_ref(),
+        blocks.last()
+    );
+
+    // Restart the storage component over the same DB dir.
+    drop(runner);
+    let mut runner = Runner::<Reactor>::new(config, &mut rng).await.unwrap();
+    assert_eq!(
+        put_blocks_and_get_highest(&mut runner, &mut rng, vec![])
+            .await
+            .as_ref(),
+        blocks.last()
+    );
+    let block_height_store = runner.reactor().storage.block_height_store();
+    for block in &blocks {
+        assert_eq!(
+            block_height_store.get(block.height()).unwrap().as_ref(),
+            Some(block.hash())
+        );
+    }
+}
+
+/// Creates a deploy with the given timestamp and a TTL of one minute.
+fn deploy_created_at(rng: &mut TestRng, timestamp: Timestamp) -> Deploy {
+    let stored_contract = || ExecutableDeployItem::StoredContractByName {
+        name: "contract".to_string(),
+        entry_point: "call".to_string(),
+        args: vec![],
+    };
+    Deploy::new(
+        timestamp,
+        TimeDiff::from(60_000),
+        1,
+        vec![],
+        "casper-example".to_string(),
+        stored_contract(),
+        stored_contract(),
+        &SecretKey::random(rng),
+        rng,
+    )
+}
+
+/// Creates a block at height 0 which includes the given deploys.
+fn block_with_deploys(rng: &mut TestRng, deploy_hashes: Vec<DeployHash>) -> Block {
+    let proto_block = ProtoBlock::new(deploy_hashes, rng.gen());
+    let finalized_block = FinalizedBlock::new(
+        proto_block,
+        Timestamp::now(),
+        None,
+        EraId(0),
+        0,
+        PublicKey::random(rng),
+    );
+    Block::new(
+        BlockHash::new(Digest::random(rng)),
+        Digest::random(rng),
+        Digest::random(rng),
+        finalized_block,
+    )
+}
+
+#[tokio::test]
+async fn should_delete_expired_unreferenced_deploys_on_cleanup_event() {
+    let mut rng = TestRng::new();
+    let (storage_config, tempdir): (Config, TempDir) = Config::default_for_tests();
+    let config = WithDir::new(tempdir.path(), storage_config);
+    let mut runner = Runner::<Reactor>::new(config, &mut rng).await.unwrap();
+
+    // Expired for longer than the retention period.
+    let long_expired =
+        Timestamp::now() - TimeDiff::from(EXPIRED_DEPLOY_RETENTION) - TimeDiff::from(120_000);
+    let included_deploy = deploy_created_at(&mut rng, long_expired);
+    let executed_deploy = deploy_created_at(&mut rng, long_expired);
+    let unexecuted_deploy = deploy_created_at(&mut rng, long_expired);
+    let current_deploy = deploy_created_at(&mut rng, Timestamp::now());
+    let deploy_store = runner.reactor().storage.deploy_store();
+    for deploy in &[
+        &included_deploy,
+        &executed_deploy,
+        &unexecuted_deploy,
+        &current_deploy,
+    ] {
+        assert!(deploy_store.put((*deploy).clone()).unwrap());
+    }
+
+    // One deploy is included in a stored block, while another has execution results from a block
+    // which was never stored.
+    let block = block_with_deploys(&mut rng, vec![*included_deploy.id()]);
+    let block_hash = *block.hash();
+    assert!(runner.reactor().storage.block_store().put(block).unwrap());
+    assert!(deploy_store
+        .put_execution_result(
+            *included_deploy.id(),
+            block_hash,
+            ExecutionResult::random(&mut rng),
+        )
+        .unwrap());
+    assert!(deploy_store
+        .put_execution_result(
+            *executed_deploy.id(),
+            BlockHash::new(Digest::random(&mut rng)),
+            ExecutionResult::random(&mut rng),
+        )
+        .unwrap());
+
+    runner
+        .process_injected_effects(|effect_builder| {
+            effect_builder
+                .immediately()
+                .event(|_| Event::Storage(super::Event::Cleanup))
+        })
+        .await;
+    runner.crank(&mut rng).await;
+
+    // The cleanup runs on a blocking thread, so wait for it to report its metrics.
+    let metrics = runner.reactor().storage.metrics();
+    for _ in 0..100 {
+        if metrics.deleted_deploys.get() > 0 {
+            break;
+        }
+        tokio::time::delay_for(Duration::from_millis(10)).await;
+    }
+    assert_eq!(metrics.deleted_deploys.get(), 2);
+    assert!(metrics.reclaimed_bytes.get() > 0);
+
+    let ids = deploy_store.ids().unwrap();
+    assert!(ids.contains(included_deploy.id()));
+    assert!(!ids.contains(executed_deploy.id()));
+    assert!(!ids.contains(unexecuted_deploy.id()));
+    assert!(ids.contains(current_deploy.id()));
+
+    // Deleting a deploy which no longer exists is a no-op.
+    assert_eq!(deploy_store.delete(*unexecuted_deploy.id()).unwrap(), None);
+}
@@ -469,7 +469,13 @@ impl PublicKey {
             PublicKey::Ed25519(_) => ED25519_LOWERCASE,
             PublicKey::Secp256k1(_) => SECP256K1_LOWERCASE,
         };
-        let public_key_bytes = self.as_ref();
+        // secp256k1 keys may be held in uncompressed form (e.g. if loaded from a PEM file), but the
+        // account hash is always derived from the compressed form, as in `casper_types`.
+        let mut public_key = *self;
+        if let PublicKey::Secp256k1(secp256k1) = &mut public_key {
+            secp256k1.compress();
+        }
+        let public_key_bytes = public_key.as_ref();
 
         // Prepare preimage based on the public key parameters.
         let preimage = {
@@ -1824,6 +1830,23 @@ kv+kBR5u4ISEAkuc2TFWQHX0Yj9oTB9fx9+vvQdxJOhMtu46kGo0Uw==
             let hash_types: AccountHash = public_key_types.into();
             assert_eq!(hash_types, hash_node)
         }
+
+        #[test]
+        fn account_hash_is_independent_of_encoding() {
+            const UNCOMPRESSED_KEY_HEX: &str =
+                "04408e9526316fd1f8def480dd45b2cc72ffd732771c9ceb5d92ffa4051e6ee08484024b9cd9315640\
+                75f4623f684c1f5fc7dfafbd077124e84cb6ee3a906a3453";
+            let uncompressed_key =
+                PublicKey::secp256k1_from_bytes(hex::decode(UNCOMPRESSED_KEY_HEX).unwrap())
+                    .unwrap();
+            let public_key_types: casper_types::PublicKey = uncompressed_key.into();
+            let compressed_key = PublicKey::try_from(public_key_types).unwrap();
+            assert_ne!(uncompressed_key.as_ref(), compressed_key.as_ref());
+
+            let hash_types: AccountHash = public_key_types.into();
+            assert_eq!(uncompressed_key.to_account_hash(), hash_types);
+            assert_eq!(compressed_key.to_account_hash(), hash_types);
+        }
     }
 
     #[test]
@@ -1834,6 +1857,20 @@ kv+kBR5u4ISEAkuc2TFWQHX0Yj9oTB9fx9+vvQdxJOhMtu46kGo0Uw==
         check_ord_and_hash(ed25519_public_key, secp256k1_public_key);
     }
 
+    #[test]
+    fn casper_types_public_key_roundtrip() {
+        let mut rng = TestRng::new();
+        for public_key in &[
+            PublicKey::random_ed25519(&mut rng),
+            PublicKey::random_secp256k1(&mut rng),
+        ] {
+            let public_key_types: casper_types::PublicKey = (*public_key).into();
+            let decoded = PublicKey::try_from(public_key_types).unwrap();
+            assert_eq!(decoded, *public_key);
+            assert_eq!(decoded.tag(), public_key.tag());
+        }
+    }
+
     #[test]
     fn signature_traits() {
         let signature_low = Signature::new_ed25519([3; Signature::ED25519_LENGTH]).unwrap();
@@ -74,18 +74,19 @@ use datasize::DataSize;
 use futures::{channel::oneshot, future::BoxFuture, FutureExt};
 use semver::Version;
 use smallvec::{smallvec, SmallVec};
+use thiserror::Error;
 use tokio::join;
 use tracing::error;
 
 use casper_execution_engine::{
     core::engine_state::{
         self,
+        dry_run::DryRunResult,
         era_validators::{GetEraValidatorsError, GetEraValidatorsRequest},
         execute_request::ExecuteRequest,
         execution_result::ExecutionResults,
         genesis::GenesisResult,
         step::{StepRequest, StepResult},
-        BalanceRequest, BalanceResult, QueryRequest, QueryResult,
     },
     shared::{additive_map::AdditiveMap, transform::Transform},
     storage::{global_state::CommitResult, protocol_data::ProtocolData},
@@ -94,30 +95,37 @@ use casper_types::{auction::ValidatorWeights, Key, ProtocolVersion};
 
 use crate::{
     components::{
-        chainspec_loader::ChainspecInfo,
-        consensus::BlockContext,
+        block_validator::{InvalidBlock, ProposalContext},
+        consensus::{BlockContext, ConsensusStatus, EraId},
+        deploy_acceptor,
         fetcher::FetchResult,
-        small_network::GossipedAddress,
-        storage::{DeployHashes, DeployMetadata, DeployResults, StorageType, Value},
+        small_network::{self, BanEntry, BanTarget, GossipedAddress, NodeId},
+        storage::{
+            DeployHashes, DeployHeaderResults, DeployMetadata, DeployResults, StorageType, Value,
+        },
+    },
+    crypto::{
+        asymmetric_key::{PublicKey, Signature},
+        hash::Digest,
     },
-    crypto::{asymmetric_key::Signature, hash::Digest},
     effect::requests::LinearChainRequest,
-    reactor::{EventQueueHandle, QueueKind},
+    reactor::{EventQueueHandle, QueueDump, QueueKind},
     types::{
         json_compatibility::ExecutionResult, Block, BlockByHeight, BlockHash, BlockHeader,
-        BlockLike, Deploy, DeployHash, FinalizedBlock, Item, ProtoBlock,
+        BlockLike, BlockRange, BlockRangeId, Deploy, DeployHash, FinalizedBlock, Item, ProtoBlock,
+        Timestamp,
     },
     utils::Source,
     Chainspec,
 };
 use announcements::{
-    ApiServerAnnouncement, BlockExecutorAnnouncement, ConsensusAnnouncement,
+    ApiServerAnnouncement, BlockExecutorAnnouncement, ConsensusAnnouncement, ControlAnnouncement,
     DeployAcceptorAnnouncement, GossiperAnnouncement, LinearChainAnnouncement, NetworkAnnouncement,
 };
 use requests::{
-    BlockExecutorRequest, BlockValidationRequest, ChainspecLoaderRequest, ConsensusRequest,
-    ContractRuntimeRequest, DeployBufferRequest, FetcherRequest, MetricsRequest,
-    NetworkInfoRequest, NetworkRequest, StorageRequest,
+    BlockExecutorRequest, BlockValidationRequest, ConsensusRequest, ContractRuntimeRequest,
+    DeployBufferRequest, DiagnosticsRequest, FetcherRequest, MetricsRequest, NetworkInfoRequest,
+    NetworkRequest, StorageRequest,
 };
 
 /// A pinned, boxed future that produces one or more events.
@@ -183,6 +191,18 @@ impl<T> Drop for Responder<T> {
     }
 }
 
+/// Error returned when a request made with a timeout is not answered.
+#[derive(Clone, Copy, DataSize, Debug, Error)]
+pub enum RequestError {
+    /// The request was not answered within the given timeout.
+    #[error("request not answered within {0:?}")]
+    Timeout(#[data_size(skip)] Duration),
+    /// The handling component dropped the responder without answering, which happens e.g. while
+    /// the node is shutting down.  Retrying the request is pointless.
+    #[error("responder dropped without answering the request")]
+    ResponderDropped,
+}
+
 /// Effect extension for futures, used to convert futures into actual effects.
 pub trait EffectExt: Future + Send {
     /// Finalizes a future into an effect that returns an event.
@@ -314,6 +334,11 @@ impl<REv> EffectBuilder<REv> {
         EffectBuilder(event_queue_handle)
     }
 
+    /// Returns the handle to the event queue which this effect builder schedules events on.
+    pub(crate) fn event_queue(self) -> EventQueueHandle<REv> {
+        self.0
+    }
+
     /// Performs a request.
     ///
     /// Given a request `Q`, that when completed will yield a result of `T`, produces a future
@@ -350,6 +375,59 @@ impl<REv> EffectBuilder<REv> {
         })
     }
 
+    /// Performs a request, giving up if it is not answered within `timeout`.
+    ///
+    /// Behaves like `make_request`, except that a handling component which is stuck or drops the
+    /// responder does not leave the caller waiting forever. Instead, the expiry is logged along
+    /// with the queue kind and the request, and `RequestError::Timeout` is returned. A dropped
+    /// responder is reported immediately as `RequestError::ResponderDropped`, as the request can
+    /// no longer be answered at all.
+    pub(crate) async fn make_request_with_timeout<T, Q, F>(
+        self,
+        f: F,
+        queue_kind: QueueKind,
+        timeout: Duration,
+    ) -> Result<T, RequestError>
+    where
+        T: Send + 'static,
+        Q: Into<REv> + Display,
+        F: FnOnce(Responder<T>) -> Q,
+    {
+        // Prepare a channel.
+        let (sender, receiver) = oneshot::channel();
+
+        // Create response function.
+        let responder = Responder::new(sender);
+
+        // Render the request before it is moved into the event loop, for logging on expiry.
+        let request = f(responder);
+        let request_description = request.to_string();
+        self.0.schedule(request.into(), queue_kind).await;
+
+        match tokio::time::timeout(timeout, receiver).await {
+            Ok(Ok(response)) => Ok(response),
+            Ok(Err(_)) => {
+                error!(
+                    ?queue_kind,
+                    request = %request_description,
+                    "request for {} dropped without being answered",
+                    type_name::<T>()
+                );
+                Err(RequestError::ResponderDropped)
+            }
+            Err(_) => {
+                error!(
+                    ?queue_kind,
+                    request = %request_description,
+                    ?timeout,
+                    "request for {} timed out",
+                    type_name::<T>()
+                );
+                Err(RequestError::Timeout(timeout))
+            }
+        }
+    }
+
     /// Run and end effect immediately.
     ///
     /// Can be used to trigger events from effects when combined with `.event`. Do not use this do
@@ -385,6 +463,22 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Retrieves a snapshot of the events pending in the reactor's queues, rendering at most
+    /// `max_events` events per queue.
+    pub(crate) async fn get_queue_dump(self, max_events: usize) -> QueueDump
+    where
+        REv: From<DiagnosticsRequest>,
+    {
+        self.make_request(
+            |responder| DiagnosticsRequest::DumpQueues {
+                max_events,
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await
+    }
+
     /// Retrieves block at `height` from the Linear Chain component.
     pub(crate) async fn get_block_at_height_local<I>(self, height: u64) -> Option<Block>
     where
@@ -430,6 +524,25 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Broadcasts a network message to all peers except the excluded ones.
+    ///
+    /// Useful for forwarding a message without sending it back to the peers it came from.
+    pub async fn broadcast_message_except<I, P>(self, payload: P, exclude: HashSet<I>)
+    where
+        REv: From<NetworkRequest<I, P>>,
+        I: Send + 'static,
+    {
+        self.make_request(
+            |responder| NetworkRequest::BroadcastExcept {
+                payload,
+                exclude,
+                responder,
+            },
+            QueueKind::Network,
+        )
+        .await
+    }
+
     /// Gossips a network message.
     ///
     /// A low-level "gossip" function, selects `count` randomly chosen nodes on the network,
@@ -472,6 +585,73 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets the number of connected network peers.
+    pub(crate) async fn network_peer_count<I>(self) -> usize
+    where
+        REv: From<NetworkInfoRequest<I>>,
+        I: Send + 'static,
+    {
+        self.make_request(
+            |responder| NetworkInfoRequest::GetPeerCount { responder },
+            QueueKind::Network,
+        )
+        .await
+    }
+
+    /// Gets the network bans which have not yet expired.
+    pub async fn network_bans(self) -> Vec<BanEntry>
+    where
+        REv: From<NetworkInfoRequest<NodeId>>,
+    {
+        self.make_request(
+            |responder| NetworkInfoRequest::GetBans { responder },
+            QueueKind::Api,
+        )
+        .await
+    }
+
+    /// Bans a network peer until `expires`, or permanently if `expires` is `None`.
+    pub async fn network_ban_peer(
+        self,
+        target: BanTarget,
+        expires: Option<Timestamp>,
+    ) -> Result<(), small_network::Error>
+    where
+        REv: From<NetworkInfoRequest<NodeId>>,
+    {
+        self.make_request(
+            |responder| NetworkInfoRequest::BanPeer {
+                target,
+                expires,
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await
+    }
+
+    /// Lifts the ban of a network peer, returning whether it was banned.
+    pub async fn network_unban_peer(self, target: BanTarget) -> Result<bool, small_network::Error>
+    where
+        REv: From<NetworkInfoRequest<NodeId>>,
+    {
+        self.make_request(
+            |responder| NetworkInfoRequest::UnbanPeer { target, responder },
+            QueueKind::Api,
+        )
+        .await
+    }
+
+    /// Announces that the node has been asked to shut down.
+    pub(crate) async fn announce_shutdown_requested(self)
+    where
+        REv: From<ControlAnnouncement>,
+    {
+        self.0
+            .schedule(ControlAnnouncement::ShutdownRequested, QueueKind::Control)
+            .await;
+    }
+
     /// Announces that a network message has been received.
     pub(crate) async fn announce_message_received<I, P>(self, sender: I, payload: P)
     where
@@ -498,6 +678,21 @@ impl<REv> EffectBuilder<REv> {
             .await;
     }
 
+    /// Announces that a gossiped peer address was verified by connecting to it.
+    pub(crate) async fn announce_peer_address_verified<I, P>(
+        self,
+        gossiped_address: GossipedAddress,
+    ) where
+        REv: From<NetworkAnnouncement<I, P>>,
+    {
+        self.0
+            .schedule(
+                NetworkAnnouncement::PeerAddressVerified(gossiped_address),
+                QueueKind::Regular,
+            )
+            .await;
+    }
+
     /// Announces that a new peer has connected.
     pub(crate) async fn announce_new_peer<I, P>(self, peer_id: I)
     where
@@ -530,13 +725,32 @@ impl<REv> EffectBuilder<REv> {
     }
 
     /// Announces that the HTTP API server has received a deploy.
-    pub(crate) async fn announce_deploy_received(self, deploy: Box<Deploy>)
-    where
+    pub(crate) async fn announce_deploy_received(
+        self,
+        deploy: Box<Deploy>,
+        responder: Option<Responder<Result<(), deploy_acceptor::Error>>>,
+    ) where
+        REv: From<ApiServerAnnouncement>,
+    {
+        self.0
+            .schedule(
+                ApiServerAnnouncement::DeployReceived { deploy, responder },
+                QueueKind::Api,
+            )
+            .await;
+    }
+
+    /// Announces that the HTTP API server has received a deploy to dry run.
+    pub(crate) async fn announce_dry_run_deploy_received(
+        self,
+        deploy: Box<Deploy>,
+        responder: Responder<Result<(), deploy_acceptor::Error>>,
+    ) where
         REv: From<ApiServerAnnouncement>,
     {
         self.0
             .schedule(
-                ApiServerAnnouncement::DeployReceived { deploy },
+                ApiServerAnnouncement::DryRunDeployReceived { deploy, responder },
                 QueueKind::Api,
             )
             .await;
@@ -636,6 +850,23 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Requests the contiguous run of up to `count` blocks starting at `start_height`.
+    pub(crate) async fn get_block_range<S>(self, start_height: u64, count: u64) -> Vec<S::Block>
+    where
+        S: StorageType + 'static,
+        REv: From<StorageRequest<S>>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetBlockRange {
+                start_height,
+                count,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Requests the highest block.
     pub(crate) async fn get_highest_block<S>(self) -> Option<S::Block>
     where
@@ -681,6 +912,25 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets the requested deploy headers from the deploy store.
+    pub(crate) async fn get_deploy_headers_from_storage<S>(
+        self,
+        deploy_hashes: DeployHashes<S>,
+    ) -> DeployHeaderResults<S>
+    where
+        S: StorageType + 'static,
+        REv: From<StorageRequest<S>>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetDeployHeaders {
+                deploy_hashes,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Stores the given execution results for the deploys in the given block in the linear block
     /// store.
     pub(crate) async fn put_execution_results_to_storage<S>(
@@ -784,12 +1034,36 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
-    /// Passes the timestamp of a future block for which deploys are to be proposed.
+    /// Requests a run of up to `count` consecutive linear chain blocks starting at `start_height`.
+    pub(crate) async fn fetch_block_range<I>(
+        self,
+        start_height: u64,
+        count: u64,
+        peer: I,
+    ) -> Option<FetchResult<BlockRange>>
+    where
+        REv: From<FetcherRequest<I, BlockRange>>,
+        I: Send + 'static,
+    {
+        self.make_request(
+            |responder| FetcherRequest::Fetch {
+                id: BlockRangeId::new(start_height, count),
+                peer,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Passes the timestamp of a future block for which deploys are to be proposed, along with the
+    /// deploys already finalized in the current era, which must not be proposed again.
     // TODO: The input `BlockContext` will probably be a different type than the context in the
     //       return value in the future.
     pub(crate) async fn request_proto_block(
         self,
         block_context: BlockContext,
+        past_deploys: HashSet<DeployHash>,
         random_bit: bool,
     ) -> (ProtoBlock, BlockContext)
     where
@@ -800,6 +1074,7 @@ impl<REv> EffectBuilder<REv> {
                 |responder| DeployBufferRequest::ListForInclusion {
                     current_instant: block_context.timestamp(),
                     past_blocks: Default::default(), // TODO
+                    past_deploys,
                     responder,
                 },
                 QueueKind::Regular,
@@ -825,7 +1100,35 @@ impl<REv> EffectBuilder<REv> {
     }
 
     /// Checks whether the deploys included in the block exist on the network.
-    pub(crate) async fn validate_block<I, T>(self, sender: I, block: T) -> (bool, T)
+    pub(crate) async fn validate_block<I, T>(
+        self,
+        sender: I,
+        block: T,
+    ) -> (Result<(), InvalidBlock>, T)
+    where
+        REv: From<BlockValidationRequest<T, I>>,
+        T: BlockLike + Send + 'static,
+    {
+        self.make_request(
+            |responder| BlockValidationRequest {
+                block,
+                sender,
+                context: None,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Checks whether a block proposed in consensus is valid: its deploys must be available, and
+    /// it must satisfy the rules of the given era context.
+    pub(crate) async fn validate_proposed_block<I, T>(
+        self,
+        sender: I,
+        block: T,
+        context: ProposalContext,
+    ) -> (Result<(), InvalidBlock>, T)
     where
         REv: From<BlockValidationRequest<T, I>>,
         T: BlockLike + Send + 'static,
@@ -834,6 +1137,7 @@ impl<REv> EffectBuilder<REv> {
             |responder| BlockValidationRequest {
                 block,
                 sender,
+                context: Some(context),
                 responder,
             },
             QueueKind::Regular,
@@ -896,6 +1200,50 @@ impl<REv> EffectBuilder<REv> {
             .await
     }
 
+    /// The linear chain has stored the execution result of a deploy in a stored block.
+    pub(crate) async fn announce_deploy_processed(
+        self,
+        deploy_hash: DeployHash,
+        block_hash: BlockHash,
+        execution_result: ExecutionResult,
+    ) where
+        REv: From<LinearChainAnnouncement>,
+    {
+        self.0
+            .schedule(
+                LinearChainAnnouncement::DeployProcessed {
+                    deploy_hash,
+                    block_hash,
+                    execution_result,
+                },
+                QueueKind::Regular,
+            )
+            .await
+    }
+
+    /// The linear chain has appended a verified finality signature to a stored block.
+    pub(crate) async fn announce_finality_signature(
+        self,
+        block_hash: BlockHash,
+        era_id: EraId,
+        public_key: Box<PublicKey>,
+        signature: Box<Signature>,
+    ) where
+        REv: From<LinearChainAnnouncement>,
+    {
+        self.0
+            .schedule(
+                LinearChainAnnouncement::FinalitySignature {
+                    block_hash,
+                    era_id,
+                    public_key,
+                    signature,
+                },
+                QueueKind::Regular,
+            )
+            .await
+    }
+
     /// Runs the genesis process on the contract runtime.
     pub(crate) async fn commit_genesis(
         self,
@@ -943,15 +1291,6 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
-    /// Gets the requested chainspec info from the chainspec loader.
-    pub(crate) async fn get_chainspec_info(self) -> ChainspecInfo
-    where
-        REv: From<ChainspecLoaderRequest> + Send,
-    {
-        self.make_request(ChainspecLoaderRequest::GetChainspecInfo, QueueKind::Regular)
-            .await
-    }
-
     /// Requests an execution of deploys using Contract Runtime.
     pub(crate) async fn request_execute(
         self,
@@ -970,37 +1309,18 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
-    /// Requests a commit of effects on the Contract Runtime component.
-    pub(crate) async fn request_commit(
+    /// Requests a dry run of an execution of deploys on the Contract Runtime component, whose
+    /// effects are not committed.
+    pub(crate) async fn dry_run_deploy(
         self,
-        state_root_hash: Digest,
-        effects: AdditiveMap<Key, Transform>,
-    ) -> Result<CommitResult, engine_state::Error>
-    where
-        REv: From<ContractRuntimeRequest>,
-    {
-        self.make_request(
-            |responder| ContractRuntimeRequest::Commit {
-                state_root_hash,
-                effects,
-                responder,
-            },
-            QueueKind::Regular,
-        )
-        .await
-    }
-
-    /// Requests a query be executed on the Contract Runtime component.
-    pub(crate) async fn query_global_state(
-        self,
-        query_request: QueryRequest,
-    ) -> Result<QueryResult, engine_state::Error>
+        execute_request: ExecuteRequest,
+    ) -> Result<DryRunResult, engine_state::Error>
     where
         REv: From<ContractRuntimeRequest>,
     {
         self.make_request(
-            |responder| ContractRuntimeRequest::Query {
-                query_request,
+            |responder| ContractRuntimeRequest::DryRunDeploy {
+                execute_request,
                 responder,
             },
             QueueKind::Regular,
@@ -1008,17 +1328,19 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
-    /// Requests a query be executed on the Contract Runtime component.
-    pub(crate) async fn get_balance(
+    /// Requests a commit of effects on the Contract Runtime component.
+    pub(crate) async fn request_commit(
         self,
-        balance_request: BalanceRequest,
-    ) -> Result<BalanceResult, engine_state::Error>
+        state_root_hash: Digest,
+        effects: AdditiveMap<Key, Transform>,
+    ) -> Result<CommitResult, engine_state::Error>
     where
         REv: From<ContractRuntimeRequest>,
     {
         self.make_request(
-            |responder| ContractRuntimeRequest::GetBalance {
-                balance_request,
+            |responder| ContractRuntimeRequest::Commit {
+                state_root_hash,
+                effects,
                 responder,
             },
             QueueKind::Regular,
@@ -1085,13 +1407,17 @@ impl<REv> EffectBuilder<REv> {
     }
 
     /// Gets the set of validators, the booking block and the key block for a new era
+    ///
+    /// Gives up on getting the validators if the contract runtime doesn't answer within
+    /// `get_validators_timeout`.
     pub(crate) async fn create_new_era<S>(
         self,
         request: GetEraValidatorsRequest,
+        get_validators_timeout: Duration,
         booking_block_height: u64,
         key_block_height: u64,
     ) -> (
-        Result<Option<ValidatorWeights>, GetEraValidatorsError>,
+        Result<Result<Option<ValidatorWeights>, GetEraValidatorsError>, RequestError>,
         Option<S::Block>,
         Option<S::Block>,
     )
@@ -1099,14 +1425,26 @@ impl<REv> EffectBuilder<REv> {
         REv: From<ContractRuntimeRequest> + From<StorageRequest<S>>,
         S: StorageType + 'static,
     {
-        let future_validators = self.get_validators(request);
+        let future_validators = self.make_request_with_timeout(
+            |responder| ContractRuntimeRequest::GetEraValidators {
+                get_request: request,
+                responder,
+            },
+            QueueKind::Regular,
+            get_validators_timeout,
+        );
         let future_booking_block = self.get_block_at_height(booking_block_height);
         let future_key_block = self.get_block_at_height(key_block_height);
         join!(future_validators, future_booking_block, future_key_block)
     }
 
     /// Request consensus to sign a block from the linear chain and possibly start a new era.
-    pub(crate) async fn handle_linear_chain_block(self, block_header: BlockHeader) -> Signature
+    ///
+    /// Returns the signer's public key along with the signature.
+    pub(crate) async fn handle_linear_chain_block(
+        self,
+        block_header: BlockHeader,
+    ) -> (PublicKey, Signature)
     where
         REv: From<ConsensusRequest>,
     {
@@ -1116,6 +1454,15 @@ impl<REv> EffectBuilder<REv> {
         )
         .await
     }
+
+    /// Requests the status of the current era's consensus protocol instance.
+    pub(crate) async fn get_consensus_status(self) -> ConsensusStatus
+    where
+        REv: From<ConsensusRequest>,
+    {
+        self.make_request(ConsensusRequest::Status, QueueKind::Api)
+            .await
+    }
 }
 
 /// Construct a fatal error effect.
@@ -1128,3 +1475,91 @@ macro_rules! fatal {
         $effect_builder.fatal(file!(), line!(), &$msg).ignore()
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{reactor::Scheduler, utils};
+
+    /// A request answered with a number.
+    #[derive(Debug)]
+    struct TestRequest(Responder<u32>);
+
+    impl Display for TestRequest {
+        fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+            write!(formatter, "test request")
+        }
+    }
+
+    fn new_effect_builder() -> (&'static Scheduler<TestRequest>, EffectBuilder<TestRequest>) {
+        let scheduler = utils::leak(Scheduler::<TestRequest>::new(QueueKind::weights()));
+        let effect_builder = EffectBuilder::new(EventQueueHandle::new(scheduler));
+        (scheduler, effect_builder)
+    }
+
+    async fn make_test_request(
+        effect_builder: EffectBuilder<TestRequest>,
+        timeout: Duration,
+    ) -> Result<u32, RequestError> {
+        effect_builder
+            .make_request_with_timeout(TestRequest, QueueKind::Api, timeout)
+            .await
+    }
+
+    #[tokio::test]
+    async fn should_return_response_within_timeout() {
+        let (scheduler, effect_builder) = new_effect_builder();
+
+        let respond = async {
+            let (TestRequest(responder), _) = scheduler.pop().await;
+            responder.respond(7).await;
+        };
+        let (result, ()) = join!(
+            make_test_request(effect_builder, Duration::from_secs(10)),
+            respond
+        );
+
+        assert_eq!(result.unwrap(), 7);
+    }
+
+    #[tokio::test]
+    async fn should_time_out_if_request_is_never_answered() {
+        let (scheduler, effect_builder) = new_effect_builder();
+
+        // Hold on to the request without ever answering it, like a stuck component would.
+        let hold = async {
+            let (request, _) = scheduler.pop().await;
+            tokio::time::delay_for(Duration::from_secs(1)).await;
+            request
+        };
+        let (result, request) = join!(
+            make_test_request(effect_builder, Duration::from_millis(100)),
+            hold
+        );
+
+        assert!(matches!(result, Err(RequestError::Timeout(_))));
+        // Answering too late is harmless.
+        request.0.respond(7).await;
+    }
+
+    #[tokio::test]
+    async fn should_fail_promptly_if_responder_is_dropped() {
+        let (scheduler, effect_builder) = new_effect_builder();
+
+        let drop_request = async {
+            let (request, _) = scheduler.pop().await;
+            drop(request);
+        };
+        let request_then_drop = async {
+            join!(
+                make_test_request(effect_builder, Duration::from_secs(60)),
+                drop_request
+            )
+        };
+        let (result, ()) = tokio::time::timeout(Duration::from_secs(5), request_then_drop)
+            .await
+            .expect("request with dropped responder should not hang");
+
+        assert!(matches!(result, Err(RequestError::ResponderDropped)));
+    }
+}
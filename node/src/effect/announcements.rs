@@ -8,8 +8,10 @@ use std::{
     fmt::{self, Display, Formatter},
 };
 
+use super::Responder;
 use crate::{
-    components::small_network::GossipedAddress,
+    components::{consensus::EraId, deploy_acceptor, small_network::GossipedAddress},
+    crypto::asymmetric_key::{PublicKey, Signature},
     types::{
         json_compatibility::ExecutionResult, Block, BlockHash, BlockHeader, Deploy, DeployHash,
         FinalizedBlock, Item, ProtoBlock,
@@ -30,6 +32,9 @@ pub enum NetworkAnnouncement<I, P> {
     },
     /// Our public listening address should be gossiped across the network.
     GossipOurAddress(GossipedAddress),
+    /// A gossiped address of a peer was confirmed by connecting to it, so it can safely be
+    /// gossiped onwards.
+    PeerAddressVerified(GossipedAddress),
     /// A new peer connection was established.
     ///
     /// IMPORTANT NOTE: This announcement is a work-around for some short-term functionality. Do
@@ -49,6 +54,9 @@ where
                 write!(formatter, "received from {}: {}", sender, payload)
             }
             NetworkAnnouncement::GossipOurAddress(_) => write!(formatter, "gossip our address"),
+            NetworkAnnouncement::PeerAddressVerified(gossiped_address) => {
+                write!(formatter, "verified {}", gossiped_address)
+            }
             NetworkAnnouncement::NewPeer(id) => {
                 write!(formatter, "new peer connection established to {}", id)
             }
@@ -56,6 +64,22 @@ where
     }
 }
 
+/// A control announcement, concerning the node itself rather than any component.
+#[derive(Debug)]
+#[must_use]
+pub enum ControlAnnouncement {
+    /// The node was asked to shut down, e.g. by receiving a SIGTERM or SIGINT.
+    ShutdownRequested,
+}
+
+impl Display for ControlAnnouncement {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ControlAnnouncement::ShutdownRequested => write!(formatter, "shutdown requested"),
+        }
+    }
+}
+
 /// An HTTP API server announcement.
 #[derive(Debug)]
 #[must_use]
@@ -64,15 +88,27 @@ pub enum ApiServerAnnouncement {
     DeployReceived {
         /// The received deploy.
         deploy: Box<Deploy>,
+        /// Responder to call with the outcome of validating the deploy.
+        responder: Option<Responder<Result<(), deploy_acceptor::Error>>>,
+    },
+    /// A deploy to be dry run received.  It is to be validated, but neither stored nor gossiped.
+    DryRunDeployReceived {
+        /// The received deploy.
+        deploy: Box<Deploy>,
+        /// Responder to call with the outcome of validating the deploy.
+        responder: Responder<Result<(), deploy_acceptor::Error>>,
     },
 }
 
 impl Display for ApiServerAnnouncement {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
         match self {
-            ApiServerAnnouncement::DeployReceived { deploy } => {
+            ApiServerAnnouncement::DeployReceived { deploy, .. } => {
                 write!(formatter, "api server received {}", deploy.id())
             }
+            ApiServerAnnouncement::DryRunDeployReceived { deploy, .. } => {
+                write!(formatter, "api server received {} to dry run", deploy.id())
+            }
         }
     }
 }
@@ -195,6 +231,28 @@ pub enum LinearChainAnnouncement {
         /// Block header.
         block_header: Box<BlockHeader>,
     },
+    /// The execution result of a deploy has been stored locally, along with the hash of the
+    /// already-stored block containing it.
+    DeployProcessed {
+        /// The hash of the deploy.
+        deploy_hash: DeployHash,
+        /// The hash of the block containing the deploy.
+        block_hash: BlockHash,
+        /// The result of executing the deploy.
+        execution_result: ExecutionResult,
+    },
+    /// A verified finality signature has been appended to a stored block.  Each signer's signature
+    /// of a given block is only announced once.
+    FinalitySignature {
+        /// The hash of the signed block.
+        block_hash: BlockHash,
+        /// The era of the signed block.
+        era_id: EraId,
+        /// The public key of the signer.
+        public_key: Box<PublicKey>,
+        /// The signature.
+        signature: Box<Signature>,
+    },
 }
 
 impl Display for LinearChainAnnouncement {
@@ -203,6 +261,24 @@ impl Display for LinearChainAnnouncement {
             LinearChainAnnouncement::BlockAdded { block_hash, .. } => {
                 write!(f, "block added {}", block_hash)
             }
+            LinearChainAnnouncement::DeployProcessed {
+                deploy_hash,
+                block_hash,
+                ..
+            } => write!(
+                f,
+                "deploy {} processed in block {}",
+                deploy_hash, block_hash
+            ),
+            LinearChainAnnouncement::FinalitySignature {
+                block_hash,
+                public_key,
+                ..
+            } => write!(
+                f,
+                "finality signature for block {} by {}",
+                block_hash, public_key
+            ),
         }
     }
 }
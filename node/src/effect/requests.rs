@@ -16,6 +16,7 @@ use casper_execution_engine::{
     core::engine_state::{
         self,
         balance::{BalanceRequest, BalanceResult},
+        dry_run::DryRunResult,
         era_validators::{GetEraValidatorsError, GetEraValidatorsRequest},
         execute_request::ExecuteRequest,
         execution_result::ExecutionResults,
@@ -27,21 +28,31 @@ use casper_execution_engine::{
     shared::{additive_map::AdditiveMap, transform::Transform},
     storage::{global_state::CommitResult, protocol_data::ProtocolData},
 };
-use casper_types::{auction::ValidatorWeights, Key, ProtocolVersion, URef};
+use casper_types::{auction::ValidatorWeights, Key, ProtocolVersion, U512};
 
-use super::Responder;
+use super::{RequestError, Responder};
 use crate::{
     components::{
-        chainspec_loader::ChainspecInfo,
+        api_server::{GetBalanceError, PurseIdentifier},
+        block_validator::{InvalidBlock, ProposalContext},
+        chainspec_loader::{ChainspecInfo, UpgradeSummary},
+        consensus::ConsensusStatus,
+        deploy_acceptor,
         fetcher::FetchResult,
+        small_network::{self, BanEntry, BanTarget},
         storage::{
             DeployHashes, DeployHeaderResults, DeployMetadata, DeployResults, StorageType, Value,
         },
     },
-    crypto::{asymmetric_key::Signature, hash::Digest},
+    crypto::{
+        asymmetric_key::{PublicKey, Signature},
+        hash::Digest,
+    },
+    reactor::QueueDump,
     types::{
         json_compatibility::ExecutionResult, Block as LinearBlock, Block, BlockHash, BlockHeader,
-        Deploy, DeployHash, FinalizedBlock, Item, ProtoBlockHash, StatusFeed, Timestamp,
+        BlockRangeId, Deploy, DeployHash, FinalizedBlock, Item, ProtoBlockHash, StatusFeed,
+        Timestamp,
     },
     utils::DisplayIter,
     Chainspec,
@@ -70,6 +81,30 @@ impl Display for MetricsRequest {
     }
 }
 
+/// A diagnostics request, served by the reactor itself rather than any component.
+#[derive(Debug)]
+pub enum DiagnosticsRequest {
+    /// Take a snapshot of the events pending in the reactor's queues.
+    DumpQueues {
+        /// The maximum number of events to render per queue.
+        max_events: usize,
+        /// Responder returning the snapshot.
+        responder: Responder<QueueDump>,
+    },
+}
+
+impl Display for DiagnosticsRequest {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            DiagnosticsRequest::DumpQueues { max_events, .. } => write!(
+                formatter,
+                "dump queues, rendering at most {} events each",
+                max_events
+            ),
+        }
+    }
+}
+
 /// A networking request.
 #[derive(Debug)]
 #[must_use]
@@ -92,6 +127,15 @@ pub enum NetworkRequest<I, P> {
         /// Responder to be called when all messages are queued.
         responder: Responder<()>,
     },
+    /// Send a message on the network to all peers except the excluded ones.
+    BroadcastExcept {
+        /// Message payload.
+        payload: P,
+        /// Node IDs of nodes to exclude from the broadcast.
+        exclude: HashSet<I>,
+        /// Responder to be called when all messages are queued.
+        responder: Responder<()>,
+    },
     /// Gossip a message to a random subset of peers.
     Gossip {
         /// Payload to gossip.
@@ -127,6 +171,15 @@ impl<I, P> NetworkRequest<I, P> {
                 payload: wrap_payload(payload),
                 responder,
             },
+            NetworkRequest::BroadcastExcept {
+                payload,
+                exclude,
+                responder,
+            } => NetworkRequest::BroadcastExcept {
+                payload: wrap_payload(payload),
+                exclude,
+                responder,
+            },
             NetworkRequest::Gossip {
                 payload,
                 count,
@@ -155,6 +208,14 @@ where
             NetworkRequest::Broadcast { payload, .. } => {
                 write!(formatter, "broadcast: {}", payload)
             }
+            NetworkRequest::BroadcastExcept {
+                payload, exclude, ..
+            } => write!(
+                formatter,
+                "broadcast excluding {} peers: {}",
+                exclude.len(),
+                payload
+            ),
             NetworkRequest::Gossip { payload, .. } => write!(formatter, "gossip: {}", payload),
         }
     }
@@ -169,6 +230,34 @@ pub enum NetworkInfoRequest<I> {
         /// Responder to be called with all connected peers.
         responder: Responder<HashMap<I, SocketAddr>>,
     },
+    /// Get the number of incoming and outgoing peers.
+    GetPeerCount {
+        /// Responder to be called with the number of connected peers.
+        responder: Responder<usize>,
+    },
+    /// Get the bans which have not yet expired.
+    GetBans {
+        /// Responder to be called with all active bans.
+        responder: Responder<Vec<BanEntry>>,
+    },
+    /// Ban a peer, disconnecting it if currently connected.
+    BanPeer {
+        /// The peer to ban.
+        target: BanTarget,
+        /// The moment the ban expires, or `None` for a permanent ban.
+        expires: Option<Timestamp>,
+        /// Responder to be called once the ban is in effect, with an error if it could not be
+        /// persisted.
+        responder: Responder<Result<(), small_network::Error>>,
+    },
+    /// Lift the ban of a peer.
+    UnbanPeer {
+        /// The peer to unban.
+        target: BanTarget,
+        /// Responder to be called with whether the peer was banned, or with an error if the
+        /// updated ban list could not be persisted.
+        responder: Responder<Result<bool, small_network::Error>>,
+    },
 }
 
 impl<I> Display for NetworkInfoRequest<I>
@@ -178,6 +267,15 @@ where
     fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
         match self {
             NetworkInfoRequest::GetPeers { responder: _ } => write!(formatter, "get peers"),
+            NetworkInfoRequest::GetPeerCount { .. } => write!(formatter, "get peer count"),
+            NetworkInfoRequest::GetBans { .. } => write!(formatter, "get bans"),
+            NetworkInfoRequest::BanPeer {
+                target, expires, ..
+            } => match expires {
+                Some(expires) => write!(formatter, "ban {} until {}", target, expires),
+                None => write!(formatter, "ban {}", target),
+            },
+            NetworkInfoRequest::UnbanPeer { target, .. } => write!(formatter, "unban {}", target),
         }
     }
 }
@@ -209,6 +307,16 @@ pub enum StorageRequest<S: StorageType + 'static> {
         /// Responder.
         responder: Responder<Option<S::Block>>,
     },
+    /// Retrieve the contiguous run of up to `count` blocks starting at `start_height`.
+    GetBlockRange {
+        /// Height of the first block.
+        start_height: u64,
+        /// Maximum number of blocks to retrieve.
+        count: u64,
+        /// Responder to call with the result.  Returns an empty vector if there is no block at
+        /// `start_height` in local storage.
+        responder: Responder<Vec<S::Block>>,
+    },
     /// Retrieve highest block.
     GetHighestBlock {
         /// Responder.
@@ -285,6 +393,15 @@ impl<S: StorageType> Display for StorageRequest<S> {
             StorageRequest::GetBlockAtHeight { height, .. } => {
                 write!(formatter, "get block at height {}", height)
             }
+            StorageRequest::GetBlockRange {
+                start_height,
+                count,
+                ..
+            } => write!(
+                formatter,
+                "get up to {} blocks from height {}",
+                count, start_height
+            ),
             StorageRequest::GetHighestBlock { .. } => write!(formatter, "get highest block"),
             StorageRequest::GetBlockHeader { block_hash, .. } => {
                 write!(formatter, "get {}", block_hash)
@@ -326,6 +443,8 @@ pub enum DeployBufferRequest {
         current_instant: Timestamp,
         /// Set of block hashes pointing to blocks whose deploys should be excluded.
         past_blocks: HashSet<ProtoBlockHash>,
+        /// Set of deploys already finalized in the current era, which should be excluded.
+        past_deploys: HashSet<DeployHash>,
         /// Responder to call with the result.
         responder: Responder<HashSet<DeployHash>>,
     },
@@ -337,12 +456,14 @@ impl Display for DeployBufferRequest {
             DeployBufferRequest::ListForInclusion {
                 current_instant,
                 past_blocks,
+                past_deploys,
                 responder: _,
             } => write!(
                 formatter,
-                "list for inclusion: instant {} past {}",
+                "list for inclusion: instant {} past {} past deploys {}",
                 current_instant,
-                past_blocks.len()
+                past_blocks.len(),
+                past_deploys.len()
             ),
         }
     }
@@ -359,8 +480,24 @@ pub enum ApiRequest<I> {
     SubmitDeploy {
         /// The deploy to be announced.
         deploy: Box<Deploy>,
-        /// Responder to call.
-        responder: Responder<()>,
+        /// Responder to call with the outcome of validating the deploy.
+        responder: Responder<Result<(), deploy_acceptor::Error>>,
+    },
+    /// Validate a deploy as if it had been submitted, without storing or announcing it.
+    VerifyDeploy {
+        /// The deploy to be validated.
+        deploy: Box<Deploy>,
+        /// Responder to call with the outcome of validating the deploy.
+        responder: Responder<Result<(), deploy_acceptor::Error>>,
+    },
+    /// Execute a deploy on top of the given root hash without committing its effects.
+    DryRunDeploy {
+        /// The state root hash.
+        state_root_hash: Digest,
+        /// The deploy to be executed.
+        deploy: Box<Deploy>,
+        /// Responder to call with the result.
+        responder: Responder<Result<DryRunResult, engine_state::Error>>,
     },
     /// If `maybe_hash` is `Some`, return the specified block if it exists, else `None`.  If
     /// `maybe_hash` is `None`, return the latest block.
@@ -370,6 +507,13 @@ pub enum ApiRequest<I> {
         /// Responder to call with the result.
         responder: Responder<Option<LinearBlock>>,
     },
+    /// Return the block at the given height if it exists, else `None`.
+    GetBlockAtHeight {
+        /// The height of the block to be retrieved.
+        height: u64,
+        /// Responder to call with the result.
+        responder: Responder<Option<LinearBlock>>,
+    },
     /// Query the global state at the given root hash.
     QueryGlobalState {
         /// The state root hash.
@@ -378,8 +522,9 @@ pub enum ApiRequest<I> {
         base_key: Key,
         /// The path components starting from the key as base.
         path: Vec<String>,
-        /// Responder to call with the result.
-        responder: Responder<Result<QueryResult, engine_state::Error>>,
+        /// Responder to call with the result, or with an error if the contract runtime didn't
+        /// answer in time.
+        responder: Responder<Result<Result<QueryResult, engine_state::Error>, RequestError>>,
     },
     /// Query the global state at the given root hash.
     QueryEraValidators {
@@ -399,14 +544,14 @@ pub enum ApiRequest<I> {
         /// Responder to call with the result.
         responder: Responder<Result<Option<Box<ProtocolData>>, engine_state::Error>>,
     },
-    /// Query the global state at the given root hash.
+    /// Get the balance of a purse at the given root hash.
     GetBalance {
         /// The state root hash.
         state_root_hash: Digest,
-        /// The purse URef.
-        purse_uref: URef,
+        /// The purse, or the account whose main purse to use.
+        purse_identifier: PurseIdentifier,
         /// Responder to call with the result.
-        responder: Responder<Result<BalanceResult, engine_state::Error>>,
+        responder: Responder<Result<U512, GetBalanceError>>,
     },
     /// Return the specified deploy and metadata if it exists, else `None`.
     GetDeploy {
@@ -420,22 +565,62 @@ pub enum ApiRequest<I> {
         /// Responder to call with the result.
         responder: Responder<HashMap<I, SocketAddr>>,
     },
+    /// Return the network bans which have not yet expired.
+    GetBans {
+        /// Responder to call with the result.
+        responder: Responder<Vec<BanEntry>>,
+    },
+    /// Ban a network peer.
+    BanPeer {
+        /// The peer to ban.
+        target: BanTarget,
+        /// The moment the ban expires, or `None` for a permanent ban.
+        expires: Option<Timestamp>,
+        /// Responder to call with the result.
+        responder: Responder<Result<(), small_network::Error>>,
+    },
+    /// Lift the ban of a network peer.
+    UnbanPeer {
+        /// The peer to unban.
+        target: BanTarget,
+        /// Responder to call with whether the peer was banned.
+        responder: Responder<Result<bool, small_network::Error>>,
+    },
+    /// Return the status of the current era's consensus protocol instance.
+    GetConsensusStatus {
+        /// Responder to call with the result.
+        responder: Responder<ConsensusStatus>,
+    },
     /// Return string formatted status or `None` if an error occurred.
     GetStatus {
-        /// Responder to call with the result.
-        responder: Responder<StatusFeed<I>>,
+        /// Responder to call with the result, or with an error if any of the components providing
+        /// the status didn't answer in time.
+        responder: Responder<Result<StatusFeed<I>, RequestError>>,
     },
     /// Return string formatted, prometheus compatible metrics or `None` if an error occurred.
     GetMetrics {
         /// Responder to call with the result.
         responder: Responder<Option<String>>,
     },
+    /// Return a snapshot of the events pending in the reactor's queues.
+    DumpQueues {
+        /// The maximum number of events to render per queue.
+        max_events: usize,
+        /// Responder to call with the result.
+        responder: Responder<QueueDump>,
+    },
 }
 
 impl<I> Display for ApiRequest<I> {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
         match self {
             ApiRequest::SubmitDeploy { deploy, .. } => write!(formatter, "submit {}", *deploy),
+            ApiRequest::VerifyDeploy { deploy, .. } => write!(formatter, "verify {}", *deploy),
+            ApiRequest::DryRunDeploy {
+                state_root_hash,
+                deploy,
+                ..
+            } => write!(formatter, "dry run {} on {}", *deploy, state_root_hash),
             ApiRequest::GetBlock {
                 maybe_hash: Some(hash),
                 ..
@@ -443,6 +628,9 @@ impl<I> Display for ApiRequest<I> {
             ApiRequest::GetBlock {
                 maybe_hash: None, ..
             } => write!(formatter, "get latest block"),
+            ApiRequest::GetBlockAtHeight { height, .. } => {
+                write!(formatter, "get block at height {}", height)
+            }
             ApiRequest::QueryProtocolData {
                 protocol_version, ..
             } => write!(formatter, "protocol_version {}", protocol_version),
@@ -463,17 +651,22 @@ impl<I> Display for ApiRequest<I> {
             } => write!(formatter, "auction {}, era_id: {}", state_root_hash, era_id),
             ApiRequest::GetBalance {
                 state_root_hash,
-                purse_uref,
+                purse_identifier,
                 ..
             } => write!(
                 formatter,
-                "balance {}, purse_uref: {}",
-                state_root_hash, purse_uref
+                "balance {}, {}",
+                state_root_hash, purse_identifier
             ),
             ApiRequest::GetDeploy { hash, .. } => write!(formatter, "get {}", hash),
             ApiRequest::GetPeers { .. } => write!(formatter, "get peers"),
+            ApiRequest::GetBans { .. } => write!(formatter, "get bans"),
+            ApiRequest::BanPeer { target, .. } => write!(formatter, "ban {}", target),
+            ApiRequest::UnbanPeer { target, .. } => write!(formatter, "unban {}", target),
+            ApiRequest::GetConsensusStatus { .. } => write!(formatter, "get consensus status"),
             ApiRequest::GetStatus { .. } => write!(formatter, "get status"),
             ApiRequest::GetMetrics { .. } => write!(formatter, "get metrics"),
+            ApiRequest::DumpQueues { .. } => write!(formatter, "dump queues"),
         }
     }
 }
@@ -503,6 +696,13 @@ pub enum ContractRuntimeRequest {
         /// Responder to call with the execution result.
         responder: Responder<Result<ExecutionResults, engine_state::RootNotFound>>,
     },
+    /// An `ExecuteRequest` whose deploys are executed without committing their effects.
+    DryRunDeploy {
+        /// Execution request containing deploys.
+        execute_request: ExecuteRequest,
+        /// Responder to call with the dry run result.
+        responder: Responder<Result<DryRunResult, engine_state::Error>>,
+    },
     /// A request to commit existing execution transforms.
     Commit {
         /// A valid state root hash.
@@ -566,6 +766,14 @@ impl Display for ContractRuntimeRequest {
                 execute_request.parent_state_hash
             ),
 
+            ContractRuntimeRequest::DryRunDeploy {
+                execute_request, ..
+            } => write!(
+                formatter,
+                "dry run request: {}",
+                execute_request.parent_state_hash
+            ),
+
             ContractRuntimeRequest::Commit {
                 state_root_hash,
                 effects,
@@ -652,10 +860,13 @@ pub struct BlockValidationRequest<T, I> {
     pub(crate) block: T,
     /// The sender of the block, which will be asked to provide all missing deploys.
     pub(crate) sender: I,
+    /// The era context to check a proposed block against, if any.
+    pub(crate) context: Option<ProposalContext>,
     /// Responder to call with the result.
     ///
-    /// Indicates whether or not validation was successful and returns `block` unchanged.
-    pub(crate) responder: Responder<(bool, T)>,
+    /// Indicates whether or not validation was successful, with the reason if not, and returns
+    /// `block` unchanged.
+    pub(crate) responder: Responder<(Result<(), InvalidBlock>, T)>,
 }
 
 impl<T: Display, I: Display> Display for BlockValidationRequest<T, I> {
@@ -677,6 +888,8 @@ pub enum LinearChainRequest<I> {
     /// Local request for a linear chain block at height.
     /// TODO: Unify `BlockAtHeight` and `BlockAtHeightLocal`.
     BlockAtHeightLocal(BlockHeight, Responder<Option<Block>>),
+    /// Request for a run of consecutive linear chain blocks.
+    BlockRange(BlockRangeId, I),
 }
 
 impl<I: Display> Display for LinearChainRequest<I> {
@@ -691,6 +904,9 @@ impl<I: Display> Display for LinearChainRequest<I> {
             LinearChainRequest::BlockAtHeightLocal(height, _) => {
                 write!(f, "local request for block at height {}", height)
             }
+            LinearChainRequest::BlockRange(block_range_id, sender) => {
+                write!(f, "block request for {} from {}", block_range_id, sender)
+            }
         }
     }
 }
@@ -699,8 +915,11 @@ impl<I: Display> Display for LinearChainRequest<I> {
 #[must_use]
 /// Consensus component requests.
 pub enum ConsensusRequest {
-    /// Request for consensus to sign a new linear chain block and possibly start a new era.
-    HandleLinearBlock(Box<BlockHeader>, Responder<Signature>),
+    /// Request for consensus to sign a new linear chain block and possibly start a new era.  The
+    /// response is the signature along with the public key of the signer.
+    HandleLinearBlock(Box<BlockHeader>, Responder<(PublicKey, Signature)>),
+    /// Request for the status of the current era's consensus protocol instance.
+    Status(Responder<ConsensusStatus>),
 }
 
 /// ChainspecLoader componenent requests.
@@ -708,12 +927,15 @@ pub enum ConsensusRequest {
 pub enum ChainspecLoaderRequest {
     /// Chainspec info request.
     GetChainspecInfo(Responder<ChainspecInfo>),
+    /// Request for the upgrades scheduled in the chainspec, in order of activation.
+    GetUpgradeSchedule(Responder<Vec<UpgradeSummary>>),
 }
 
 impl Display for ChainspecLoaderRequest {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             ChainspecLoaderRequest::GetChainspecInfo(_) => write!(f, "get chainspec info"),
+            ChainspecLoaderRequest::GetUpgradeSchedule(_) => write!(f, "get upgrade schedule"),
         }
     }
 }
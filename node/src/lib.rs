@@ -45,7 +45,9 @@ pub use components::{
     chainspec_loader::{Chainspec, Error as ChainspecError},
     consensus::Config as ConsensusConfig,
     contract_runtime::Config as ContractRuntimeConfig,
-    gossiper::{Config as GossipConfig, Error as GossipError},
+    gossiper::{
+        Config as GossipConfig, Error as GossipError, PerItemConfig as PerItemGossipConfig,
+    },
     small_network::{Config as SmallNetworkConfig, Error as SmallNetworkError},
     storage::{Config as StorageConfig, Error as StorageError},
 };
@@ -1,13 +1,16 @@
 //! Logging via the tracing crate.
 
-use std::{fmt, io};
+use std::{fmt, io, sync::RwLock};
 
 use ansi_term::{Color, Style};
 use datasize::DataSize;
+use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
+use thiserror::Error;
 use tracing::{Event, Level, Subscriber};
 use tracing_subscriber::{
+    filter::ParseError,
     fmt::{
         format,
         time::{FormatTime, SystemTime},
@@ -15,9 +18,31 @@ use tracing_subscriber::{
     },
     prelude::*,
     registry::LookupSpan,
-    EnvFilter,
+    reload, EnvFilter,
 };
 
+/// Replaces the filter of the subscriber it was created for.
+type FilterReloader = Box<dyn Fn(EnvFilter) -> Result<(), reload::Error> + Send + Sync>;
+
+lazy_static! {
+    /// Reloader for the filter of the global logger, set once logging has been initialized.
+    static ref FILTER_RELOADER: RwLock<Option<FilterReloader>> = RwLock::new(None);
+}
+
+/// Error replacing the filter of the global logger.
+#[derive(Debug, Error)]
+pub enum SetLogFilterError {
+    /// The given filter could not be parsed.
+    #[error("invalid log filter: {0}")]
+    Parse(#[from] ParseError),
+    /// The logging system has not been initialized.
+    #[error("logging has not been initialized")]
+    NotInitialized,
+    /// The new filter could not be installed.
+    #[error("failed to reload log filter: {0}")]
+    Reload(#[from] reload::Error),
+}
+
 /// Logging configuration.
 #[derive(DataSize, Debug, Default, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
@@ -228,25 +253,112 @@ pub fn init_with_config(config: &LoggingConfig) -> anyhow::Result<()> {
     })
     .delimited("; ");
 
-    match config.format {
+    // The filter is reloadable so that it can be changed via `set_log_filter` without a restart.
+    let reloader = match config.format {
         // Setup a new tracing-subscriber writing to `stdout` for logging.
-        LoggingFormat::Text => tracing::subscriber::set_global_default(
-            tracing_subscriber::fmt()
+        LoggingFormat::Text => {
+            let builder = tracing_subscriber::fmt()
                 .with_writer(io::stdout)
                 .with_env_filter(EnvFilter::from_default_env())
                 .fmt_fields(formatter)
                 .event_format(FmtEvent::new(config.color, config.abbreviate_modules))
-                .finish(),
-        )?,
+                .with_filter_reloading();
+            let reloader = filter_reloader(builder.reload_handle());
+            tracing::subscriber::set_global_default(builder.finish())?;
+            reloader
+        }
         // JSON logging writes to `stdout` as well but uses the JSON format.
-        LoggingFormat::Json => tracing::subscriber::set_global_default(
-            tracing_subscriber::fmt()
+        LoggingFormat::Json => {
+            let builder = tracing_subscriber::fmt()
                 .with_writer(io::stdout)
                 .with_env_filter(EnvFilter::from_default_env())
                 .json()
-                .finish(),
-        )?,
-    }
+                .with_filter_reloading();
+            let reloader = filter_reloader(builder.reload_handle());
+            tracing::subscriber::set_global_default(builder.finish())?;
+            reloader
+        }
+    };
+
+    *FILTER_RELOADER
+        .write()
+        .expect("filter reloader lock poisoned") = Some(reloader);
 
     Ok(())
 }
+
+/// Replaces the filter of the global logger, without affecting its output format.
+///
+/// `filter` uses the same syntax as the `RUST_LOG` environment variable, e.g.
+/// `info,casper_node::components::small_network=debug`.
+pub fn set_log_filter(filter: &str) -> Result<(), SetLogFilterError> {
+    let reloader = FILTER_RELOADER
+        .read()
+        .expect("filter reloader lock poisoned");
+    let reloader = reloader.as_ref().ok_or(SetLogFilterError::NotInitialized)?;
+    apply_log_filter(reloader, filter)
+}
+
+/// Creates a `FilterReloader` from the reload handle of a subscriber.
+fn filter_reloader<S>(handle: reload::Handle<EnvFilter, S>) -> FilterReloader
+where
+    S: Subscriber + Send + Sync + 'static,
+{
+    Box::new(move |filter| handle.reload(filter))
+}
+
+/// Parses `filter` and installs it using `reloader`.
+fn apply_log_filter(reloader: &FilterReloader, filter: &str) -> Result<(), SetLogFilterError> {
+    let filter = EnvFilter::try_new(filter)?;
+    reloader(filter)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing::{debug, info};
+
+    use super::*;
+    use crate::testing::CaptureWriter;
+
+    #[test]
+    fn should_apply_new_log_filter_at_runtime() {
+        let writer = CaptureWriter::default();
+        let make_writer = writer.clone();
+        let builder = tracing_subscriber::fmt()
+            .with_writer(move || make_writer.clone())
+            .with_env_filter(EnvFilter::new("info"))
+            .with_filter_reloading();
+        let reloader = filter_reloader(builder.reload_handle());
+
+        tracing::subscriber::with_default(builder.finish(), || {
+            debug!("first debug event");
+            info!("first info event");
+
+            apply_log_filter(&reloader, "debug").expect("should set debug filter");
+            debug!("second debug event");
+
+            apply_log_filter(&reloader, "warn").expect("should set warn filter");
+            info!("second info event");
+        });
+
+        let output = writer.contents();
+        assert!(!output.contains("first debug event"));
+        assert!(output.contains("first info event"));
+        assert!(output.contains("second debug event"));
+        assert!(!output.contains("second info event"));
+    }
+
+    #[test]
+    fn should_reject_invalid_log_filter() {
+        let builder = tracing_subscriber::fmt()
+            .with_env_filter(EnvFilter::new("info"))
+            .with_filter_reloading();
+        let reloader = filter_reloader(builder.reload_handle());
+
+        assert!(matches!(
+            apply_log_filter(&reloader, "casper_node=loud"),
+            Err(SetLogFilterError::Parse(_))
+        ));
+    }
+}
@@ -28,10 +28,12 @@ mod event_queue_metrics;
 pub mod initializer;
 pub mod joiner;
 mod queue_kind;
+#[cfg(test)]
+mod tests;
 pub mod validator;
 
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     env,
     fmt::{Debug, Display},
     mem,
@@ -43,17 +45,21 @@ use futures::{future::BoxFuture, FutureExt};
 use lazy_static::lazy_static;
 use prometheus::{self, Histogram, HistogramOpts, IntCounter, Registry};
 use quanta::IntoNanoseconds;
+use serde::{Deserialize, Serialize};
 use tracing::{debug, debug_span, info, trace, warn};
 use tracing_futures::Instrument;
 
 use crate::{
-    effect::{Effect, EffectBuilder, Effects},
+    effect::{
+        announcements::ControlAnnouncement, requests::DiagnosticsRequest, Effect, EffectBuilder,
+        EffectExt, Effects,
+    },
     types::CryptoRngCore,
-    utils::{self, WeightedRoundRobin},
+    utils::{self, QueueSnapshot, WeightedRoundRobin},
 };
 use quanta::Clock;
 pub use queue_kind::QueueKind;
-use tokio::time::{Duration, Instant};
+use tokio::time::{self, Duration, Instant};
 
 /// Default threshold for when an event is considered slow.  Can be overridden by setting the env
 /// var `CL_EVENT_MAX_MICROSECS=<MICROSECONDS>`.
@@ -82,6 +88,13 @@ lazy_static! {
 /// Components rarely use this, but use a bound `EventQueueHandle` instead.
 pub type Scheduler<Ev> = WeightedRoundRobin<Ev, QueueKind>;
 
+/// A snapshot of the events pending in each of the scheduler's queues.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct QueueDump {
+    /// The snapshot of each queue, keyed by the name of its `QueueKind`.
+    pub queues: BTreeMap<String, QueueSnapshot>,
+}
+
 /// Event queue handle
 ///
 /// The event queue handle is how almost all parts of the application interact with the reactor
@@ -118,6 +131,22 @@ impl<REv> EventQueueHandle<REv> {
     pub(crate) fn event_queues_counts(&self) -> HashMap<QueueKind, usize> {
         self.0.event_queues_counts()
     }
+
+    /// Returns a snapshot of the scheduler's queues, rendering at most `max_events` events per
+    /// queue.
+    pub(crate) async fn dump_queues(&self, max_events: usize) -> QueueDump
+    where
+        REv: Display,
+    {
+        let queues = self
+            .0
+            .snapshot(max_events)
+            .await
+            .into_iter()
+            .map(|(queue_kind, snapshot)| (queue_kind.to_string(), snapshot))
+            .collect();
+        QueueDump { queues }
+    }
 }
 
 /// Reactor core.
@@ -169,6 +198,13 @@ pub trait Reactor: Sized {
         false
     }
 
+    /// Indicates that the reactor has no in-flight work that should be completed before shutting
+    /// down.
+    #[inline]
+    fn is_idle(&self) -> bool {
+        true
+    }
+
     /// Instructs the reactor to update performance metrics, if any.
     fn update_metrics(&mut self, _event_queue_handle: EventQueueHandle<Self::Event>) {}
 }
@@ -448,6 +484,48 @@ where
         }
     }
 
+    /// Spawns a task that announces a shutdown request once the process receives `SIGTERM` or
+    /// `SIGINT`.
+    pub fn announce_shutdown_on_signal(&self)
+    where
+        R::Event: From<ControlAnnouncement>,
+    {
+        let effect_builder = EffectBuilder::new(EventQueueHandle::new(self.scheduler));
+        tokio::spawn(async move {
+            match utils::wait_for_shutdown_signal().await {
+                Ok(signal) => {
+                    info!(%signal, "received shutdown signal");
+                    effect_builder.announce_shutdown_requested().await;
+                }
+                Err(err) => warn!(%err, "could not install shutdown signal handlers"),
+            }
+        });
+    }
+
+    /// Shuts down the reactor after `run` has returned.
+    ///
+    /// Keeps dispatching events already on the queue until the reactor reports being idle or
+    /// `drain_timeout` elapses, then finalizes the reactor.
+    pub async fn shutdown(mut self, rng: &mut dyn CryptoRngCore, drain_timeout: Duration)
+    where
+        R: Finalize,
+    {
+        let drain = async {
+            while !self.reactor.is_idle() {
+                self.crank(rng).await;
+            }
+        };
+
+        if time::timeout(drain_timeout, drain).await.is_err() {
+            warn!(
+                ?drain_timeout,
+                "timed out waiting for in-flight work to complete, shutting down anyway"
+            );
+        }
+
+        self.reactor.finalize().await;
+    }
+
     /// Returns a reference to the reactor.
     #[inline]
     pub fn reactor(&self) -> &R {
@@ -467,6 +545,32 @@ where
     }
 }
 
+/// Handles a `DiagnosticsRequest`.
+///
+/// Diagnostics concern the reactor's scheduler rather than any of its components, so reactors
+/// should route these requests here instead of to a component.
+pub(crate) fn handle_diagnostics_request<REv>(
+    effect_builder: EffectBuilder<REv>,
+    request: DiagnosticsRequest,
+) -> Effects<REv>
+where
+    REv: Display + Send + 'static,
+{
+    match request {
+        DiagnosticsRequest::DumpQueues {
+            max_events,
+            responder,
+        } => {
+            let event_queue = effect_builder.event_queue();
+            async move {
+                let queue_dump = event_queue.dump_queues(max_events).await;
+                responder.respond(queue_dump).await
+            }
+            .ignore()
+        }
+    }
+}
+
 /// Spawns tasks that will process the given effects.
 #[inline]
 async fn process_effects<Ev>(scheduler: &'static Scheduler<Ev>, effects: Effects<Ev>)
@@ -135,7 +135,7 @@ impl reactor::Reactor for Reactor {
         let effect_builder = EffectBuilder::new(event_queue);
 
         let storage_config = WithDir::new(&root, config.storage.clone());
-        let storage = Storage::new(storage_config.clone())?;
+        let storage = Storage::new(storage_config.clone(), registry)?;
         let contract_runtime =
             ContractRuntime::new(storage_config, config.contract_runtime, registry)?;
         let (chainspec_loader, chainspec_effects) =
@@ -1,6 +1,9 @@
 //! Reactor used to join the network.
 
-use std::fmt::{self, Display, Formatter};
+use std::{
+    fmt::{self, Display, Formatter},
+    path::PathBuf,
+};
 
 use datasize::DataSize;
 use derive_more::From;
@@ -35,8 +38,8 @@ use crate::{
         },
         requests::{
             BlockExecutorRequest, BlockValidationRequest, ConsensusRequest, ContractRuntimeRequest,
-            DeployBufferRequest, FetcherRequest, LinearChainRequest, NetworkRequest,
-            StorageRequest,
+            DeployBufferRequest, FetcherRequest, LinearChainRequest, NetworkInfoRequest,
+            NetworkRequest, StorageRequest,
         },
         EffectBuilder, Effects,
     },
@@ -48,7 +51,10 @@ use crate::{
         validator::{self, Error, ValidatorInitConfig},
         EventQueueHandle, Finalize,
     },
-    types::{Block, BlockByHeight, BlockHeader, CryptoRngCore, Deploy, ProtoBlock, Tag, Timestamp},
+    types::{
+        Block, BlockByHeight, BlockHeader, BlockRange, CryptoRngCore, Deploy, Item, ProtoBlock,
+        Tag, Timestamp,
+    },
     utils::{Source, WithDir},
 };
 
@@ -72,6 +78,10 @@ pub enum Event {
     #[from]
     BlockByHeightFetcher(fetcher::Event<BlockByHeight>),
 
+    /// Linear chain (by range of heights) fetcher event.
+    #[from]
+    BlockRangeFetcher(fetcher::Event<BlockRange>),
+
     /// Deploy fetcher event.
     #[from]
     DeployFetcher(fetcher::Event<Deploy>),
@@ -117,6 +127,10 @@ pub enum Event {
     #[from]
     BlockByHeightFetcherRequest(FetcherRequest<NodeId, BlockByHeight>),
 
+    /// Linear chain block range fetcher request.
+    #[from]
+    BlockRangeFetcherRequest(FetcherRequest<NodeId, BlockRange>),
+
     /// Deploy fetcher request.
     #[from]
     DeployFetcherRequest(FetcherRequest<NodeId, Deploy>),
@@ -181,6 +195,12 @@ impl From<NetworkRequest<NodeId, Message>> for Event {
     }
 }
 
+impl From<NetworkInfoRequest<NodeId>> for Event {
+    fn from(request: NetworkInfoRequest<NodeId>) -> Self {
+        Event::Network(small_network::Event::from(request))
+    }
+}
+
 impl From<NetworkRequest<NodeId, gossiper::Message<GossipedAddress>>> for Event {
     fn from(request: NetworkRequest<NodeId, gossiper::Message<GossipedAddress>>) -> Self {
         Event::Network(small_network::Event::from(
@@ -219,6 +239,9 @@ impl Display for Event {
             Event::BlockByHeightFetcherRequest(request) => {
                 write!(f, "block by height fetcher request: {}", request)
             }
+            Event::BlockRangeFetcherRequest(request) => {
+                write!(f, "block range fetcher request: {}", request)
+            }
             Event::BlockValidator(event) => write!(f, "block validator event: {}", event),
             Event::DeployFetcher(event) => write!(f, "deploy fetcher event: {}", event),
             Event::BlockExecutor(event) => write!(f, "block executor event: {}", event),
@@ -241,6 +264,7 @@ impl Display for Event {
             Event::BlockByHeightFetcher(event) => {
                 write!(f, "block by height fetcher event: {}", event)
             }
+            Event::BlockRangeFetcher(event) => write!(f, "block range fetcher event: {}", event),
             Event::DeployAcceptorAnnouncement(ann) => {
                 write!(f, "deploy acceptor announcement: {}", ann)
             }
@@ -253,6 +277,7 @@ impl Display for Event {
 /// Joining node reactor.
 #[derive(DataSize)]
 pub struct Reactor {
+    pub(super) root: PathBuf,
     pub(super) net: SmallNetwork<Event, Message>,
     pub(super) address_gossiper: Gossiper<GossipedAddress, Event>,
     pub(super) config: validator::Config,
@@ -274,6 +299,8 @@ pub struct Reactor {
     pub(super) init_consensus_effects: Effects<consensus::Event<NodeId>>,
     // Handles request for linear chain block by height.
     pub(super) block_by_height_fetcher: Fetcher<BlockByHeight>,
+    // Handles request for runs of linear chain blocks.
+    pub(super) block_range_fetcher: Fetcher<BlockRange>,
     #[data_size(skip)]
     pub(super) deploy_acceptor: DeployAcceptor,
     #[data_size(skip)]
@@ -305,38 +332,57 @@ impl reactor::Reactor for Reactor {
 
         let event_queue_metrics = EventQueueMetrics::new(registry.clone(), event_queue)?;
 
-        let (net, net_effects) = SmallNetwork::new(event_queue, config.network.clone(), false)?;
+        let (net, net_effects) = SmallNetwork::new(
+            event_queue,
+            WithDir::new(&root, config.network.clone()),
+            registry,
+            false,
+            &chainspec_loader.chainspec().genesis.protocol_version,
+        )?;
 
-        let linear_chain_fetcher = Fetcher::new(config.gossip);
+        let linear_chain_fetcher = Fetcher::new(config.gossip.common());
         let effects = reactor::wrap_effects(Event::Network, net_effects);
 
-        let address_gossiper =
-            Gossiper::new_for_complete_items("address_gossiper", config.gossip, registry)?;
+        let address_gossiper = Gossiper::new_for_complete_items(
+            "address_gossiper",
+            config.gossip.addresses(),
+            registry,
+        )?;
 
         let effect_builder = EffectBuilder::new(event_queue);
 
         let init_hash = config.node.trusted_hash;
+        let init_height = config.node.trusted_height;
 
-        match init_hash {
-            None => info!("No synchronization of the linear chain will be done."),
-            Some(hash) => info!("Synchronizing linear chain from: {:?}", hash),
+        match (init_hash, init_height) {
+            (None, None) => warn!(
+                "No trusted hash configured, no synchronization of the linear chain will be done."
+            ),
+            (None, Some(height)) => warn!(
+                %height,
+                "Trusted height configured without a trusted hash, no synchronization of the \
+                linear chain will be done."
+            ),
+            (Some(hash), _) => info!("Synchronizing linear chain from: {:?}", hash),
         }
 
-        let linear_chain_sync = LinearChainSync::new(init_hash);
+        let linear_chain_sync = LinearChainSync::new(init_hash, init_height);
 
         let block_validator = BlockValidator::new();
 
-        let deploy_fetcher = Fetcher::new(config.gossip);
+        let deploy_fetcher = Fetcher::new(config.gossip.deploys());
 
-        let block_by_height_fetcher = Fetcher::new(config.gossip);
+        let block_by_height_fetcher = Fetcher::new(config.gossip.common());
 
-        let deploy_acceptor = DeployAcceptor::new();
+        let block_range_fetcher = Fetcher::new(config.gossip.common());
+
+        let deploy_acceptor = DeployAcceptor::new(&config.contract_runtime);
 
         let genesis_state_root_hash = chainspec_loader
             .genesis_state_root_hash()
             .expect("Should have Genesis state root hash");
 
-        let block_executor = BlockExecutor::new(genesis_state_root_hash);
+        let block_executor = BlockExecutor::new(genesis_state_root_hash, registry)?;
 
         let linear_chain = linear_chain::LinearChain::new();
 
@@ -350,7 +396,7 @@ impl reactor::Reactor for Reactor {
 
         let (consensus, init_consensus_effects) = EraSupervisor::new(
             timestamp,
-            WithDir::new(root, config.consensus.clone()),
+            WithDir::new(&root, config.consensus.clone()),
             effect_builder,
             validator_stakes,
             chainspec_loader.chainspec(),
@@ -363,6 +409,7 @@ impl reactor::Reactor for Reactor {
 
         Ok((
             Self {
+                root,
                 net,
                 address_gossiper,
                 config,
@@ -378,6 +425,7 @@ impl reactor::Reactor for Reactor {
                 consensus,
                 init_consensus_effects,
                 block_by_height_fetcher,
+                block_range_fetcher,
                 deploy_acceptor,
                 event_queue_metrics,
             },
@@ -404,7 +452,10 @@ impl reactor::Reactor for Reactor {
                     linear_chain_sync::Event::NewPeerConnected(id),
                 ),
             ),
-            Event::NetworkAnnouncement(NetworkAnnouncement::GossipOurAddress(gossiped_address)) => {
+            Event::NetworkAnnouncement(NetworkAnnouncement::GossipOurAddress(gossiped_address))
+            | Event::NetworkAnnouncement(NetworkAnnouncement::PeerAddressVerified(
+                gossiped_address,
+            )) => {
                 let event = gossiper::Event::ItemReceived {
                     item_id: gossiped_address,
                     source: Source::<NodeId>::Client,
@@ -457,6 +508,31 @@ impl reactor::Reactor for Reactor {
                     };
                     self.dispatch_event(effect_builder, rng, Event::BlockByHeightFetcher(event))
                 }
+                Message::GetResponse {
+                    tag: Tag::BlockRange,
+                    serialized_item,
+                } => {
+                    let block_range: BlockRange = match bincode::deserialize(&serialized_item) {
+                        Ok(block_range) => block_range,
+                        Err(err) => {
+                            error!("failed to decode block range from {}: {}", sender, err);
+                            return Effects::new();
+                        }
+                    };
+
+                    let event = if block_range.is_empty() {
+                        fetcher::Event::AbsentRemotely {
+                            id: block_range.id(),
+                            peer: sender,
+                        }
+                    } else {
+                        fetcher::Event::GotRemotely {
+                            item: Box::new(block_range),
+                            source: Source::Peer(sender),
+                        }
+                    };
+                    self.dispatch_event(effect_builder, rng, Event::BlockRangeFetcher(event))
+                }
                 Message::GetResponse {
                     tag: Tag::Deploy,
                     serialized_item,
@@ -471,6 +547,7 @@ impl reactor::Reactor for Reactor {
                     let event = Event::DeployAcceptor(deploy_acceptor::Event::Accept {
                         deploy,
                         source: Source::Peer(sender),
+                        responder: None,
                     });
                     self.dispatch_event(effect_builder, rng, event)
                 }
@@ -551,6 +628,11 @@ impl reactor::Reactor for Reactor {
                 self.block_by_height_fetcher
                     .handle_event(effect_builder, rng, event),
             ),
+            Event::BlockRangeFetcher(event) => reactor::wrap_effects(
+                Event::BlockRangeFetcher,
+                self.block_range_fetcher
+                    .handle_event(effect_builder, rng, event),
+            ),
             Event::DeployFetcherRequest(request) => {
                 self.dispatch_event(effect_builder, rng, Event::DeployFetcher(request.into()))
             }
@@ -559,6 +641,11 @@ impl reactor::Reactor for Reactor {
                 rng,
                 Event::BlockByHeightFetcher(request.into()),
             ),
+            Event::BlockRangeFetcherRequest(request) => self.dispatch_event(
+                effect_builder,
+                rng,
+                Event::BlockRangeFetcher(request.into()),
+            ),
             Event::BlockExecutor(event) => reactor::wrap_effects(
                 Event::BlockExecutor,
                 self.block_executor.handle_event(effect_builder, rng, event),
@@ -653,6 +740,7 @@ impl Reactor {
         let (net, config) = (
             self.net,
             ValidatorInitConfig {
+                root: self.root,
                 chainspec_loader: self.chainspec_loader,
                 config: self.config,
                 contract_runtime: self.contract_runtime,
@@ -28,6 +28,8 @@ pub enum QueueKind {
     /// Metric events take precedence over most other events since missing a request for metrics
     /// might cause the requester to assume that the node is down and forcefully restart it.
     Api,
+    /// Events controlling the node itself, such as shutdown requests.
+    Control,
 }
 
 impl Display for QueueKind {
@@ -37,6 +39,7 @@ impl Display for QueueKind {
             QueueKind::Network => "Network",
             QueueKind::Regular => "Regular",
             QueueKind::Api => "Api",
+            QueueKind::Control => "Control",
         };
         write!(f, "{}", str_value)
     }
@@ -59,6 +62,7 @@ impl QueueKind {
             QueueKind::Network => 4,
             QueueKind::Regular => 8,
             QueueKind::Api => 16,
+            QueueKind::Control => 1,
         })
         .expect("weight must be positive")
     }
@@ -76,6 +80,7 @@ impl QueueKind {
             QueueKind::Network => "network",
             QueueKind::Regular => "regular",
             QueueKind::Api => "api",
+            QueueKind::Control => "control",
         }
     }
 }
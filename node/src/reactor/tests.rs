@@ -0,0 +1,237 @@
+use std::{
+    fmt::{self, Debug, Display, Formatter},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use derive_more::From;
+use futures::{future::BoxFuture, FutureExt};
+use prometheus::Registry;
+
+use super::{EventQueueHandle, Finalize, Reactor};
+use crate::{
+    components::{
+        in_memory_network::{InMemoryNetwork, NetworkController, NodeId},
+        Component,
+    },
+    effect::{
+        announcements::{ControlAnnouncement, NetworkAnnouncement},
+        requests::NetworkRequest,
+        EffectBuilder, EffectExt, Effects,
+    },
+    reactor,
+    testing::{
+        network::{Network, NetworkedReactor},
+        TestRng,
+    },
+    types::CryptoRngCore,
+};
+
+/// The payload exchanged between test nodes.
+type Message = u64;
+
+/// Records the order in which work completed and components were finalized.
+type Journal = Arc<Mutex<Vec<&'static str>>>;
+
+/// How long a unit of in-flight work takes to complete.
+const WORK_DURATION: Duration = Duration::from_millis(100);
+
+#[derive(Debug, From)]
+enum Event {
+    #[from]
+    NetworkRequest(NetworkRequest<NodeId, Message>),
+    #[from]
+    NetworkAnnouncement(NetworkAnnouncement<NodeId, Message>),
+    #[from]
+    ControlAnnouncement(ControlAnnouncement),
+    /// Starts a unit of work that completes after the given duration.
+    StartWork(Duration),
+    /// A unit of work has completed.
+    WorkDone,
+}
+
+impl Display for Event {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self, formatter)
+    }
+}
+
+/// A stand-in for a component with cleanup, recording when it has been finalized.
+#[derive(Debug)]
+struct Recorder {
+    name: &'static str,
+    journal: Journal,
+}
+
+impl Finalize for Recorder {
+    fn finalize(self) -> BoxFuture<'static, ()> {
+        async move { self.journal.lock().unwrap().push(self.name) }.boxed()
+    }
+}
+
+/// A reactor mimicking the validator's shutdown handling.
+#[derive(Debug)]
+struct TestReactor {
+    net: InMemoryNetwork<Message>,
+    net_recorder: Recorder,
+    storage_recorder: Recorder,
+    journal: Journal,
+    work_in_flight: usize,
+    shutdown_requested: bool,
+}
+
+impl Reactor for TestReactor {
+    type Event = Event;
+    type Config = Journal;
+    type Error = anyhow::Error;
+
+    fn dispatch_event(
+        &mut self,
+        effect_builder: EffectBuilder<Event>,
+        rng: &mut dyn CryptoRngCore,
+        event: Event,
+    ) -> Effects<Event> {
+        match event {
+            Event::NetworkRequest(request) => reactor::wrap_effects(
+                Event::NetworkRequest,
+                self.net.handle_event(effect_builder, rng, request),
+            ),
+            Event::NetworkAnnouncement(_) => Effects::new(),
+            Event::ControlAnnouncement(ControlAnnouncement::ShutdownRequested) => {
+                self.shutdown_requested = true;
+                Effects::new()
+            }
+            Event::StartWork(_) if self.shutdown_requested => Effects::new(),
+            Event::StartWork(duration) => {
+                self.work_in_flight += 1;
+                effect_builder
+                    .set_timeout(duration)
+                    .event(|_| Event::WorkDone)
+            }
+            Event::WorkDone => {
+                self.work_in_flight -= 1;
+                self.journal.lock().unwrap().push("work");
+                Effects::new()
+            }
+        }
+    }
+
+    fn new(
+        journal: Journal,
+        _registry: &Registry,
+        event_queue: EventQueueHandle<Event>,
+        rng: &mut dyn CryptoRngCore,
+    ) -> Result<(Self, Effects<Event>), anyhow::Error> {
+        let reactor = TestReactor {
+            net: NetworkController::create_node(event_queue, rng),
+            net_recorder: Recorder {
+                name: "network",
+                journal: Arc::clone(&journal),
+            },
+            storage_recorder: Recorder {
+                name: "storage",
+                journal: Arc::clone(&journal),
+            },
+            journal,
+            work_in_flight: 0,
+            shutdown_requested: false,
+        };
+        Ok((reactor, Effects::new()))
+    }
+
+    fn is_stopped(&mut self) -> bool {
+        self.shutdown_requested
+    }
+
+    fn is_idle(&self) -> bool {
+        self.work_in_flight == 0
+    }
+}
+
+impl Finalize for TestReactor {
+    fn finalize(self) -> BoxFuture<'static, ()> {
+        let net_finalized = self.net_recorder.finalize();
+        let storage_finalized = self.storage_recorder.finalize();
+        async move {
+            net_finalized.await;
+            storage_finalized.await;
+        }
+        .boxed()
+    }
+}
+
+impl NetworkedReactor for TestReactor {
+    type NodeId = NodeId;
+
+    fn node_id(&self) -> NodeId {
+        self.net.node_id()
+    }
+}
+
+/// Starts a unit of work taking `work_duration` on one of two networked nodes, requests a shutdown
+/// of that node, runs it until stopped and then shuts it down with the given `drain_timeout`.
+///
+/// Returns the journals of the node which was shut down and of its peer.
+async fn run_shutdown(work_duration: Duration, drain_timeout: Duration) -> (Journal, Journal) {
+    NetworkController::<Message>::create_active();
+    let mut rng = TestRng::new();
+    let mut network = Network::<TestReactor>::new();
+
+    let journal = Journal::default();
+    let (node_id, _) = network
+        .add_node_with_config(Arc::clone(&journal), &mut rng)
+        .await
+        .unwrap();
+    let peer_journal = Journal::default();
+    network
+        .add_node_with_config(Arc::clone(&peer_journal), &mut rng)
+        .await
+        .unwrap();
+
+    network
+        .process_injected_effect_on(&node_id, |effect_builder| {
+            effect_builder
+                .immediately()
+                .event(move |_| Event::StartWork(work_duration))
+        })
+        .await;
+    network
+        .crank_until(
+            &node_id,
+            &mut rng,
+            |event| matches!(event, Event::StartWork(_)),
+            Duration::from_secs(1),
+        )
+        .await;
+    network
+        .process_injected_effect_on(&node_id, |effect_builder| {
+            effect_builder.announce_shutdown_requested().ignore()
+        })
+        .await;
+
+    let mut runner = network.remove_node(&node_id).unwrap();
+    runner.run(&mut rng).await;
+    assert!(runner.reactor().inner().shutdown_requested);
+    runner.shutdown(&mut rng, drain_timeout).await;
+
+    network.finalize().await;
+    NetworkController::<Message>::remove_active();
+
+    (journal, peer_journal)
+}
+
+#[tokio::test]
+async fn should_finish_in_flight_work_before_finalizing_in_order() {
+    let (journal, peer_journal) = run_shutdown(WORK_DURATION, Duration::from_secs(10)).await;
+
+    assert_eq!(*journal.lock().unwrap(), vec!["work", "network", "storage"]);
+    // The peer keeps running until the network itself is finalized.
+    assert_eq!(*peer_journal.lock().unwrap(), vec!["network", "storage"]);
+}
+
+#[tokio::test]
+async fn should_finalize_once_drain_timeout_elapses() {
+    let (journal, _) = run_shutdown(Duration::from_secs(60), WORK_DURATION).await;
+
+    assert_eq!(*journal.lock().unwrap(), vec!["network", "storage"]);
+}
@@ -8,12 +8,16 @@ mod memory_metrics;
 #[cfg(test)]
 mod tests;
 
-use std::fmt::{self, Debug, Display, Formatter};
+use std::{
+    fmt::{self, Debug, Display, Formatter},
+    path::PathBuf,
+};
 
 use datasize::DataSize;
 use derive_more::From;
+use futures::{future::BoxFuture, FutureExt};
 use prometheus::Registry;
-use tracing::{debug, error, warn};
+use tracing::{debug, error, info, warn};
 
 use deploy_buffer::ProtoBlockCollection;
 
@@ -34,26 +38,27 @@ use crate::{
         linear_chain,
         metrics::Metrics,
         small_network::{self, GossipedAddress, NodeId, SmallNetwork},
-        storage::{self, Storage},
+        storage::{self, Storage, StorageType},
         Component,
     },
     effect::{
         announcements::{
             ApiServerAnnouncement, BlockExecutorAnnouncement, ConsensusAnnouncement,
-            DeployAcceptorAnnouncement, GossiperAnnouncement, LinearChainAnnouncement,
-            NetworkAnnouncement,
+            ControlAnnouncement, DeployAcceptorAnnouncement, GossiperAnnouncement,
+            LinearChainAnnouncement, NetworkAnnouncement,
         },
         requests::{
             ApiRequest, BlockExecutorRequest, BlockValidationRequest, ChainspecLoaderRequest,
-            ConsensusRequest, ContractRuntimeRequest, DeployBufferRequest, FetcherRequest,
-            LinearChainRequest, MetricsRequest, NetworkInfoRequest, NetworkRequest, StorageRequest,
+            ConsensusRequest, ContractRuntimeRequest, DeployBufferRequest, DiagnosticsRequest,
+            FetcherRequest, LinearChainRequest, MetricsRequest, NetworkInfoRequest, NetworkRequest,
+            StorageRequest,
         },
         EffectBuilder, Effects,
     },
     protocol::Message,
-    reactor::{self, event_queue_metrics::EventQueueMetrics, EventQueueHandle},
+    reactor::{self, event_queue_metrics::EventQueueMetrics, EventQueueHandle, Finalize},
     types::{Block, CryptoRngCore, Deploy, ProtoBlock, Tag},
-    utils::Source,
+    utils::{Source, WithDir},
 };
 pub use config::Config;
 pub use error::Error;
@@ -129,6 +134,9 @@ pub enum Event {
     /// Metrics request.
     #[from]
     MetricsRequest(MetricsRequest),
+    /// Diagnostics request.
+    #[from]
+    DiagnosticsRequest(DiagnosticsRequest),
     /// Chainspec info request
     #[from]
     ChainspecLoaderRequest(ChainspecLoaderRequest),
@@ -158,6 +166,9 @@ pub enum Event {
     /// Linear chain announcement.
     #[from]
     LinearChainAnnouncement(LinearChainAnnouncement),
+    /// Control announcement.
+    #[from]
+    ControlAnnouncement(ControlAnnouncement),
 }
 
 impl From<StorageRequest<Storage>> for Event {
@@ -233,6 +244,7 @@ impl Display for Event {
             Event::BlockExecutorRequest(req) => write!(f, "block executor request: {}", req),
             Event::ProtoBlockValidatorRequest(req) => write!(f, "block validator request: {}", req),
             Event::MetricsRequest(req) => write!(f, "metrics request: {}", req),
+            Event::DiagnosticsRequest(req) => write!(f, "diagnostics request: {}", req),
             Event::NetworkAnnouncement(ann) => write!(f, "network announcement: {}", ann),
             Event::ApiServerAnnouncement(ann) => write!(f, "api server announcement: {}", ann),
             Event::DeployAcceptorAnnouncement(ann) => {
@@ -249,12 +261,14 @@ impl Display for Event {
                 write!(f, "address gossiper announcement: {}", ann)
             }
             Event::LinearChainAnnouncement(ann) => write!(f, "linear chain announcement: {}", ann),
+            Event::ControlAnnouncement(ann) => write!(f, "control announcement: {}", ann),
         }
     }
 }
 
 /// The configuration needed to initialize a Validator reactor
 pub struct ValidatorInitConfig {
+    pub(super) root: PathBuf,
     pub(super) config: Config,
     pub(super) chainspec_loader: ChainspecLoader,
     pub(super) storage: Storage,
@@ -291,6 +305,9 @@ pub struct Reactor {
 
     #[data_size(skip)]
     event_queue_metrics: EventQueueMetrics,
+
+    /// Whether a shutdown has been requested, after which no new work is accepted.
+    shutdown_requested: bool,
 }
 
 #[cfg(test)]
@@ -318,6 +335,7 @@ impl reactor::Reactor for Reactor {
         _rng: &mut dyn CryptoRngCore,
     ) -> Result<(Self, Effects<Event>), Error> {
         let ValidatorInitConfig {
+            root,
             config,
             chainspec_loader,
             storage,
@@ -335,18 +353,30 @@ impl reactor::Reactor for Reactor {
         let metrics = Metrics::new(registry.clone());
 
         let effect_builder = EffectBuilder::new(event_queue);
-        let (net, net_effects) = SmallNetwork::new(event_queue, config.network, true)?;
+        let (net, net_effects) = SmallNetwork::new(
+            event_queue,
+            WithDir::new(root, config.network),
+            registry,
+            true,
+            &chainspec_loader.chainspec().genesis.protocol_version,
+        )?;
 
-        let address_gossiper =
-            Gossiper::new_for_complete_items("address_gossiper", config.gossip, registry)?;
+        let address_gossiper = Gossiper::new_for_complete_items(
+            "address_gossiper",
+            config.gossip.addresses(),
+            registry,
+        )?;
 
         let api_server = ApiServer::new(config.http_server, effect_builder);
-        let deploy_acceptor = DeployAcceptor::new();
-        let deploy_fetcher = Fetcher::new(config.gossip);
+        let deploy_acceptor = DeployAcceptor::new(&config.contract_runtime);
+        let deploy_fetcher = Fetcher::new(config.gossip.deploys());
+        // The deploy gossiper gets the remainder of deploys via the deploy fetcher, so that a
+        // deploy isn't requested twice if it is also being fetched, e.g. for a proposed block.
         let deploy_gossiper = Gossiper::new_for_partial_items(
             "deploy_gossiper",
-            config.gossip,
+            config.gossip.deploys(),
             gossiper::get_deploy_from_storage::<Deploy, Event>,
+            gossiper::fetch_deploy_from_holder::<Event>,
             registry,
         )?;
         let (deploy_buffer, deploy_buffer_effects) =
@@ -356,12 +386,16 @@ impl reactor::Reactor for Reactor {
         let genesis_state_root_hash = chainspec_loader
             .genesis_state_root_hash()
             .expect("should have state root hash");
-        let block_executor = BlockExecutor::new(genesis_state_root_hash)
+        let block_executor = BlockExecutor::new(genesis_state_root_hash, registry)?
             .with_parent_map(linear_chain.last().cloned());
         let proto_block_validator = BlockValidator::new();
         let linear_chain = LinearChain::new();
 
         effects.extend(reactor::wrap_effects(Event::Network, net_effects));
+        effects.extend(reactor::wrap_effects(
+            Event::Storage,
+            storage.schedule_cleanup(effect_builder),
+        ));
         effects.extend(reactor::wrap_effects(
             Event::Consensus,
             init_consensus_effects,
@@ -386,6 +420,7 @@ impl reactor::Reactor for Reactor {
                 linear_chain,
                 memory_metrics,
                 event_queue_metrics,
+                shutdown_requested: false,
             },
             effects,
         ))
@@ -398,6 +433,18 @@ impl reactor::Reactor for Reactor {
         event: Event,
     ) -> Effects<Self::Event> {
         match event {
+            Event::NetworkAnnouncement(NetworkAnnouncement::MessageReceived { sender, .. })
+                if self.shutdown_requested =>
+            {
+                debug!(%sender, "shutting down, dropping incoming message");
+                Effects::new()
+            }
+            Event::ApiServerAnnouncement(ApiServerAnnouncement::DeployReceived { .. })
+                if self.shutdown_requested =>
+            {
+                debug!("shutting down, dropping received deploy");
+                Effects::new()
+            }
             Event::Network(event) => reactor::wrap_effects(
                 Event::Network,
                 self.net.handle_event(effect_builder, rng, event),
@@ -492,6 +539,9 @@ impl reactor::Reactor for Reactor {
                 Event::MetricsRequest,
                 self.metrics.handle_event(effect_builder, rng, req),
             ),
+            Event::DiagnosticsRequest(req) => {
+                reactor::handle_diagnostics_request(effect_builder, req)
+            }
             Event::ChainspecLoaderRequest(req) => {
                 self.dispatch_event(effect_builder, rng, Event::ChainspecLoader(req.into()))
             }
@@ -558,6 +608,21 @@ impl reactor::Reactor for Reactor {
                                 LinearChainRequest::BlockAtHeight(height, sender),
                             ))
                         }
+                        Tag::BlockRange => {
+                            let block_range_id = match bincode::deserialize(&serialized_id) {
+                                Ok(block_range_id) => block_range_id,
+                                Err(error) => {
+                                    error!(
+                                        "failed to decode {:?} from {}: {}",
+                                        serialized_id, sender, error
+                                    );
+                                    return Effects::new();
+                                }
+                            };
+                            Event::LinearChain(linear_chain::Event::Request(
+                                LinearChainRequest::BlockRange(block_range_id, sender),
+                            ))
+                        }
                         Tag::GossipedAddress => {
                             warn!("received get request for gossiped-address from {}", sender);
                             return Effects::new();
@@ -578,10 +643,15 @@ impl reactor::Reactor for Reactor {
                             Event::DeployAcceptor(deploy_acceptor::Event::Accept {
                                 deploy,
                                 source: Source::Peer(sender),
+                                responder: None,
                             })
                         }
-                        Tag::Block => todo!("Handle GET block response"),
-                        Tag::BlockByHeight => todo!("Handle GET BlockByHeight response"),
+                        Tag::Block | Tag::BlockByHeight | Tag::BlockRange => {
+                            // Blocks are only fetched while joining, so any such response is
+                            // unsolicited.
+                            warn!(%tag, "received unsolicited get response from {}", sender);
+                            return Effects::new();
+                        }
                         Tag::GossipedAddress => {
                             warn!("received get request for gossiped-address from {}", sender);
                             return Effects::new();
@@ -590,7 +660,10 @@ impl reactor::Reactor for Reactor {
                 };
                 self.dispatch_event(effect_builder, rng, reactor_event)
             }
-            Event::NetworkAnnouncement(NetworkAnnouncement::GossipOurAddress(gossiped_address)) => {
+            Event::NetworkAnnouncement(NetworkAnnouncement::GossipOurAddress(gossiped_address))
+            | Event::NetworkAnnouncement(NetworkAnnouncement::PeerAddressVerified(
+                gossiped_address,
+            )) => {
                 let event = gossiper::Event::ItemReceived {
                     item_id: gossiped_address,
                     source: Source::<NodeId>::Client,
@@ -601,13 +674,24 @@ impl reactor::Reactor for Reactor {
                 debug!(%peer_id, "new peer announcement event ignored (validator reactor does not care)");
                 Effects::new()
             }
-            Event::ApiServerAnnouncement(ApiServerAnnouncement::DeployReceived { deploy }) => {
+            Event::ApiServerAnnouncement(ApiServerAnnouncement::DeployReceived {
+                deploy,
+                responder,
+            }) => {
                 let event = deploy_acceptor::Event::Accept {
                     deploy,
                     source: Source::<NodeId>::Client,
+                    responder,
                 };
                 self.dispatch_event(effect_builder, rng, Event::DeployAcceptor(event))
             }
+            Event::ApiServerAnnouncement(ApiServerAnnouncement::DryRunDeployReceived {
+                deploy,
+                responder,
+            }) => {
+                let event = deploy_acceptor::Event::Verify { deploy, responder };
+                self.dispatch_event(effect_builder, rng, Event::DeployAcceptor(event))
+            }
             Event::DeployAcceptorAnnouncement(DeployAcceptorAnnouncement::AcceptedNewDeploy {
                 deploy,
                 source,
@@ -676,22 +760,11 @@ impl reactor::Reactor for Reactor {
                 block,
                 execution_results,
             }) => {
-                let block_hash = *block.hash();
                 let reactor_event = Event::LinearChain(linear_chain::Event::LinearChainBlock {
                     block: Box::new(block),
-                    execution_results: execution_results.clone(),
+                    execution_results,
                 });
-                let mut effects = self.dispatch_event(effect_builder, rng, reactor_event);
-
-                for (deploy_hash, execution_result) in execution_results {
-                    let reactor_event = Event::ApiServer(api_server::Event::DeployProcessed {
-                        deploy_hash,
-                        block_hash,
-                        execution_result,
-                    });
-                    effects.extend(self.dispatch_event(effect_builder, rng, reactor_event));
-                }
-                effects
+                self.dispatch_event(effect_builder, rng, reactor_event)
             }
             Event::DeployGossiperAnnouncement(_ann) => {
                 unreachable!("the deploy gossiper should never make an announcement")
@@ -712,9 +785,48 @@ impl reactor::Reactor for Reactor {
                 });
                 self.dispatch_event(effect_builder, rng, reactor_event)
             }
+            Event::LinearChainAnnouncement(LinearChainAnnouncement::DeployProcessed {
+                deploy_hash,
+                block_hash,
+                execution_result,
+            }) => {
+                let reactor_event = Event::ApiServer(api_server::Event::DeployProcessed {
+                    deploy_hash,
+                    block_hash,
+                    execution_result,
+                });
+                self.dispatch_event(effect_builder, rng, reactor_event)
+            }
+            Event::LinearChainAnnouncement(LinearChainAnnouncement::FinalitySignature {
+                block_hash,
+                era_id,
+                public_key,
+                signature,
+            }) => {
+                let reactor_event = Event::ApiServer(api_server::Event::FinalitySignature {
+                    block_hash,
+                    era_id,
+                    public_key,
+                    signature,
+                });
+                self.dispatch_event(effect_builder, rng, reactor_event)
+            }
+            Event::ControlAnnouncement(ControlAnnouncement::ShutdownRequested) => {
+                info!("shutdown requested, no longer accepting new work");
+                self.shutdown_requested = true;
+                Effects::new()
+            }
         }
     }
 
+    fn is_stopped(&mut self) -> bool {
+        self.shutdown_requested
+    }
+
+    fn is_idle(&self) -> bool {
+        self.block_executor.is_idle()
+    }
+
     fn update_metrics(&mut self, event_queue_handle: EventQueueHandle<Self::Event>) {
         self.memory_metrics.estimate(&self);
         self.event_queue_metrics
@@ -722,6 +834,19 @@ impl reactor::Reactor for Reactor {
     }
 }
 
+impl Finalize for Reactor {
+    fn finalize(self) -> BoxFuture<'static, ()> {
+        // Tell our peers we are leaving before closing any connections, then flush storage.
+        let net_finalized = self.net.finalize();
+        let storage_finalized = self.storage.finalize();
+        async move {
+            net_finalized.await;
+            storage_finalized.await;
+        }
+        .boxed()
+    }
+}
+
 #[cfg(test)]
 impl NetworkedReactor for Reactor {
     type NodeId = NodeId;
@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     logging::LoggingConfig, types::NodeConfig, ApiServerConfig, ConsensusConfig,
-    ContractRuntimeConfig, GossipConfig, SmallNetworkConfig, StorageConfig,
+    ContractRuntimeConfig, PerItemGossipConfig, SmallNetworkConfig, StorageConfig,
 };
 
 /// Root configuration.
@@ -24,7 +24,7 @@ pub struct Config {
     /// On-disk storage configuration.
     pub storage: StorageConfig,
     /// Gossip protocol configuration.
-    pub gossip: GossipConfig,
+    pub gossip: PerItemGossipConfig,
     /// Contract runtime configuration.
     pub contract_runtime: ContractRuntimeConfig,
 }
@@ -3,6 +3,7 @@
 //! Contains various parts and components to aid writing tests and simulations using the
 //! `casper-node` library.
 
+mod capture_writer;
 mod condition_check_reactor;
 pub mod network;
 mod test_rng;
@@ -16,6 +17,7 @@ use std::{
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::logging;
+pub(crate) use capture_writer::CaptureWriter;
 pub(crate) use condition_check_reactor::ConditionCheckReactor;
 pub(crate) use test_rng::TestRng;
 
@@ -0,0 +1,26 @@
+use std::{
+    io,
+    sync::{Arc, Mutex},
+};
+
+/// A writer collecting all log output in a shared buffer.
+#[derive(Clone, Default)]
+pub(crate) struct CaptureWriter(Arc<Mutex<Vec<u8>>>);
+
+impl CaptureWriter {
+    /// Returns everything written so far.
+    pub(crate) fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).expect("should be utf8")
+    }
+}
+
+impl io::Write for CaptureWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
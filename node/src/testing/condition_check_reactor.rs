@@ -87,6 +87,14 @@ impl<R: Reactor> Reactor for ConditionCheckReactor<R> {
         }
         self.reactor.dispatch_event(effect_builder, rng, event)
     }
+
+    fn is_stopped(&mut self) -> bool {
+        self.reactor.is_stopped()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.reactor.is_idle()
+    }
 }
 
 impl<R: Reactor + Finalize> Finalize for ConditionCheckReactor<R> {
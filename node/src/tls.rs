@@ -33,6 +33,7 @@ use std::{
 
 use anyhow::Context;
 use datasize::DataSize;
+use hex::FromHexError;
 use hex_fmt::HexFmt;
 use nid::Nid;
 use openssl::{
@@ -143,12 +144,66 @@ impl Debug for CertFingerprint {
 #[derive(Copy, Clone, DataSize, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
 pub struct KeyFingerprint(Sha512);
 
+impl KeyFingerprint {
+    /// Returns the fingerprint encoded as lowercase hex.
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0.bytes())
+    }
+
+    /// Parses a fingerprint from its hex encoding, as produced by `to_hex`.
+    pub fn from_hex(s: &str) -> Result<Self, NodeIdParseError> {
+        let bytes = hex::decode(s).map_err(|error| match error {
+            FromHexError::OddLength => NodeIdParseError::OddLength,
+            FromHexError::InvalidHexCharacter { c, index } => {
+                NodeIdParseError::InvalidChar { c, index }
+            }
+            FromHexError::InvalidStringLength => NodeIdParseError::WrongLength {
+                expected: Sha512::SIZE,
+                actual: s.len() / 2,
+            },
+        })?;
+        if bytes.len() != Sha512::SIZE {
+            return Err(NodeIdParseError::WrongLength {
+                expected: Sha512::SIZE,
+                actual: bytes.len(),
+            });
+        }
+        let mut buf = [0; Sha512::SIZE];
+        buf.copy_from_slice(&bytes);
+        Ok(KeyFingerprint(Sha512(buf)))
+    }
+}
+
 impl Debug for KeyFingerprint {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "KeyFingerprint({:10})", HexFmt(self.0.bytes()))
     }
 }
 
+/// Error parsing a hex-encoded key fingerprint, such as a node ID.
+#[derive(Debug, Error, PartialEq)]
+pub enum NodeIdParseError {
+    /// The string has an odd number of hex digits.
+    #[error("odd number of hex digits")]
+    OddLength,
+    /// The string contains a character which is not a hex digit.
+    #[error("invalid hex character {c:?} at index {index}")]
+    InvalidChar {
+        /// The invalid character.
+        c: char,
+        /// Its index in the string.
+        index: usize,
+    },
+    /// The decoded fingerprint has the wrong number of bytes.
+    #[error("expected {expected} bytes, got {actual}")]
+    WrongLength {
+        /// The number of bytes of a fingerprint.
+        expected: usize,
+        /// The number of bytes decoded.
+        actual: usize,
+    },
+}
+
 #[cfg(test)]
 impl From<[u8; Sha512::SIZE]> for KeyFingerprint {
     fn from(raw_bytes: [u8; Sha512::SIZE]) -> Self {
@@ -722,9 +777,15 @@ impl Display for CertFingerprint {
     }
 }
 
+/// Displays a short prefix of the fingerprint, or with the alternate flag (`{:#}`) its full hex
+/// encoding, as returned by `KeyFingerprint::to_hex`.
 impl Display for KeyFingerprint {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{:10}", HexFmt(self.0.bytes()))
+        if f.alternate() {
+            f.write_str(&self.to_hex())
+        } else {
+            write!(f, "{:10}", HexFmt(self.0.bytes()))
+        }
     }
 }
 
@@ -767,7 +828,13 @@ impl Hash for Sha512 {
 
 #[cfg(test)]
 mod test {
-    use super::{generate_node_cert, mkname, name_to_string, validate_cert, TlsCert};
+    use rand::Rng;
+
+    use super::{
+        generate_node_cert, mkname, name_to_string, validate_cert, KeyFingerprint,
+        NodeIdParseError, TlsCert,
+    };
+    use crate::testing::TestRng;
 
     #[test]
     fn simple_name_to_string() {
@@ -793,4 +860,43 @@ mod test {
 
         assert_eq!(serialized, serialized_again);
     }
+
+    #[test]
+    fn key_fingerprint_hex_roundtrip() {
+        let mut rng = TestRng::new();
+        let fingerprint: KeyFingerprint = rng.gen();
+
+        let hex = fingerprint.to_hex();
+        assert_eq!(hex.len(), 128);
+        assert_eq!(hex, hex.to_lowercase());
+        assert_eq!(format!("{:#}", fingerprint), hex);
+        assert!(fingerprint.to_string().len() < hex.len());
+        assert_eq!(KeyFingerprint::from_hex(&hex), Ok(fingerprint));
+        assert_eq!(
+            KeyFingerprint::from_hex(&hex.to_uppercase()),
+            Ok(fingerprint)
+        );
+    }
+
+    #[test]
+    fn key_fingerprint_from_invalid_hex() {
+        let mut rng = TestRng::new();
+        let hex = rng.gen::<KeyFingerprint>().to_hex();
+
+        assert_eq!(
+            KeyFingerprint::from_hex(&hex[1..]),
+            Err(NodeIdParseError::OddLength)
+        );
+        assert_eq!(
+            KeyFingerprint::from_hex(&format!("x{}", &hex[1..])),
+            Err(NodeIdParseError::InvalidChar { c: 'x', index: 0 })
+        );
+        assert_eq!(
+            KeyFingerprint::from_hex(&hex[2..]),
+            Err(NodeIdParseError::WrongLength {
+                expected: 64,
+                actual: 63
+            })
+        );
+    }
 }
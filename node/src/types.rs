@@ -10,8 +10,11 @@ mod timestamp;
 
 use rand::{CryptoRng, RngCore};
 
-pub use block::{Block, BlockHash, BlockHeader};
-pub(crate) use block::{BlockByHeight, BlockLike, FinalizedBlock, ProtoBlock, ProtoBlockHash};
+pub use block::{Block, BlockHash, BlockHeader, ChainView, DEFAULT_MAX_ANCESTRY_DEPTH};
+pub(crate) use block::{
+    BlockByHeight, BlockLike, BlockRange, BlockRangeId, FinalizedBlock, ProtoBlock, ProtoBlockHash,
+    MAX_BLOCK_RANGE_LENGTH,
+};
 pub use deploy::{Approval, Deploy, DeployHash, DeployHeader, Error as DeployError};
 pub use item::{Item, Tag};
 pub use node_config::NodeConfig;
@@ -5,6 +5,7 @@ use std::{
     error::Error as StdError,
     fmt::{self, Debug, Display, Formatter},
     hash::Hash,
+    mem,
 };
 
 use blake2::{
@@ -23,23 +24,20 @@ use thiserror::Error;
 use casper_types::auction::BLOCK_REWARD;
 
 use super::{Item, Tag, Timestamp};
+#[cfg(test)]
+use crate::testing::TestRng;
 use crate::{
     components::{
         consensus::{self, EraId},
         storage::{Value, WithBlockHeight},
     },
     crypto::{
-        asymmetric_key::{PublicKey, Signature},
+        asymmetric_key::{self, PublicKey, SecretKey, Signature},
         hash::{self, Digest},
     },
-    types::DeployHash,
+    types::{CryptoRngCore, DeployHash},
     utils::DisplayIter,
 };
-#[cfg(test)]
-use crate::{
-    crypto::asymmetric_key::{self, SecretKey},
-    testing::TestRng,
-};
 
 /// Error returned from constructing or validating a `Block`.
 #[derive(Debug, Error)]
@@ -204,7 +202,16 @@ impl Display for EraEnd {
                 .iter()
                 .map(|(public_key, amount)| format!("{}: {}", public_key, amount)),
         );
-        write!(f, "era end: slash {}, reward {}", slashings, rewards)
+        let blocks_proposed = DisplayIter::new(
+            self.blocks_proposed
+                .iter()
+                .map(|(public_key, count)| format!("{}: {}", public_key, count)),
+        );
+        write!(
+            f,
+            "era end: slash {}, reward {}, blocks proposed {}",
+            slashings, rewards, blocks_proposed
+        )
     }
 }
 
@@ -265,12 +272,34 @@ impl FinalizedBlock {
         self.height
     }
 
+    /// Returns the public key of the validator which proposed this block.
+    pub(crate) fn proposer(&self) -> &PublicKey {
+        &self.proposer
+    }
+
     /// Returns true if block is Genesis' child.
     /// Genesis child block is from era 0 and height 0.
     pub(crate) fn is_genesis_child(&self) -> bool {
         self.era_id() == EraId(0) && self.height() == 0
     }
 
+    /// Returns the canonical serialization of this block's content, to be signed identically by
+    /// every node implementation.
+    ///
+    /// Unlike the `rmp_serde` encoding, the format is fixed and consists of, in order:
+    /// * the era ID as a little-endian `u64`
+    /// * the height as a little-endian `u64`
+    /// * the timestamp in milliseconds as a little-endian `u64`
+    /// * the 32 bytes of the proto block hash
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(3 * mem::size_of::<u64>() + Digest::LENGTH);
+        bytes.extend_from_slice(&self.era_id.0.to_le_bytes());
+        bytes.extend_from_slice(&self.height.to_le_bytes());
+        bytes.extend_from_slice(&self.timestamp.millis().to_le_bytes());
+        bytes.extend_from_slice(self.proto_block.hash().inner().as_ref());
+        bytes
+    }
+
     /// Generates a random instance using a `TestRng`.
     #[cfg(test)]
     pub fn random(rng: &mut TestRng) -> Self {
@@ -287,25 +316,29 @@ impl FinalizedBlock {
             let equivocators_count = rng.gen_range(0, 5);
             let rewards_count = rng.gen_range(0, 5);
             Some(EraEnd {
-                equivocators: iter::repeat_with(|| {
-                    PublicKey::from(&SecretKey::new_ed25519(rng.gen()))
-                })
-                .take(equivocators_count)
-                .collect(),
+                equivocators: iter::repeat_with(|| PublicKey::random(rng))
+                    .take(equivocators_count)
+                    .collect(),
                 rewards: iter::repeat_with(|| {
-                    let pub_key = PublicKey::from(&SecretKey::new_ed25519(rng.gen()));
+                    let pub_key = PublicKey::random(rng);
                     let reward = rng.gen_range(1, BLOCK_REWARD + 1);
                     (pub_key, reward)
                 })
                 .take(rewards_count)
                 .collect(),
+                blocks_proposed: iter::repeat_with(|| {
+                    let pub_key = PublicKey::random(rng);
+                    let count = rng.gen_range(1, 100);
+                    (pub_key, count)
+                })
+                .take(rewards_count)
+                .collect(),
             })
         } else {
             None
         };
         let era = rng.gen_range(0, 5);
-        let secret_key: SecretKey = SecretKey::new_ed25519(rng.gen());
-        let public_key = PublicKey::from(&secret_key);
+        let public_key = PublicKey::random(rng);
 
         FinalizedBlock::new(
             proto_block,
@@ -369,6 +402,21 @@ impl BlockHash {
     pub fn inner(&self) -> &Digest {
         &self.0
     }
+
+    /// Signs this block hash, e.g. to attest to the block's finality.
+    pub fn sign(
+        &self,
+        secret_key: &SecretKey,
+        public_key: &PublicKey,
+        rng: &mut dyn CryptoRngCore,
+    ) -> Signature {
+        asymmetric_key::sign(self.inner(), secret_key, public_key, rng)
+    }
+
+    /// Returns `true` if `signature` is a valid signature of this block hash by `public_key`.
+    pub fn verify(&self, signature: &Signature, public_key: &PublicKey) -> bool {
+        asymmetric_key::verify(self.inner(), signature, public_key).is_ok()
+    }
 }
 
 impl Display for BlockHash {
@@ -389,6 +437,17 @@ impl AsRef<[u8]> for BlockHash {
     }
 }
 
+/// The default maximum number of parent links followed by
+/// [`BlockHeader::is_descendant_of`](struct.BlockHeader.html#method.is_descendant_of).
+pub const DEFAULT_MAX_ANCESTRY_DEPTH: u64 = 1000;
+
+/// A view of the linear chain, sufficient to walk from a block back towards genesis.
+pub trait ChainView {
+    /// Returns the hash of the parent of the block identified by `block_hash`, or `None` if the
+    /// block is unknown.
+    fn get_parent(&self, block_hash: &BlockHash) -> Option<BlockHash>;
+}
+
 /// The header portion of a [`Block`](struct.Block.html).
 #[derive(Clone, DataSize, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize, Debug)]
 pub struct BlockHeader {
@@ -483,6 +542,36 @@ impl BlockHeader {
             .unwrap_or_else(|error| panic!("should serialize block header: {}", error));
         BlockHash::new(hash::hash(&serialized_header))
     }
+
+    /// Returns `true` if `ancestor` is found within `DEFAULT_MAX_ANCESTRY_DEPTH` parent links of
+    /// this block in `chain`.
+    pub fn is_descendant_of(&self, ancestor: &BlockHash, chain: &dyn ChainView) -> bool {
+        self.is_descendant_of_within(ancestor, chain, DEFAULT_MAX_ANCESTRY_DEPTH)
+    }
+
+    /// Returns `true` if `ancestor` is found within `max_depth` parent links of this block in
+    /// `chain`, the first link being to this block's parent.
+    ///
+    /// Returns `false` if a block without a known parent is reached, or if the walk exceeds
+    /// `max_depth`, which also guards against cycles in `chain`.
+    pub fn is_descendant_of_within(
+        &self,
+        ancestor: &BlockHash,
+        chain: &dyn ChainView,
+        max_depth: u64,
+    ) -> bool {
+        let mut current = self.parent_hash;
+        for _ in 0..max_depth {
+            if current == *ancestor {
+                return true;
+            }
+            current = match chain.get_parent(&current) {
+                Some(parent_hash) => parent_hash,
+                None => return false,
+            };
+        }
+        false
+    }
 }
 
 impl Display for BlockHeader {
@@ -613,7 +702,7 @@ impl Block {
         for _ in 0..signatures_count {
             let secret_key = SecretKey::random(rng);
             let public_key = PublicKey::from(&secret_key);
-            let signature = asymmetric_key::sign(block.hash.inner(), &secret_key, &public_key, rng);
+            let signature = block.hash.sign(&secret_key, &public_key, rng);
             block.append_proof(signature);
         }
 
@@ -741,11 +830,130 @@ impl Item for BlockByHeight {
     }
 }
 
+/// The maximum number of blocks served in response to a single `BlockRange` request.
+pub const MAX_BLOCK_RANGE_LENGTH: u64 = 32;
+
+/// Identifies a run of up to `count` consecutive linear chain blocks starting at `start_height`.
+#[derive(
+    Copy, Clone, DataSize, Debug, PartialOrd, Ord, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+pub struct BlockRangeId {
+    start_height: u64,
+    count: u64,
+}
+
+impl BlockRangeId {
+    /// Creates a new `BlockRangeId`.
+    pub fn new(start_height: u64, count: u64) -> Self {
+        BlockRangeId {
+            start_height,
+            count,
+        }
+    }
+
+    /// Returns the height of the first block in the range.
+    pub fn start_height(&self) -> u64 {
+        self.start_height
+    }
+
+    /// Returns the number of blocks requested.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns a copy of this ID with the number of blocks capped at `MAX_BLOCK_RANGE_LENGTH`.
+    pub fn capped(&self) -> Self {
+        BlockRangeId::new(self.start_height, self.count.min(MAX_BLOCK_RANGE_LENGTH))
+    }
+}
+
+impl Display for BlockRangeId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} blocks from height {}", self.count, self.start_height)
+    }
+}
+
+/// A run of consecutive linear chain blocks, fetched in a single request.
+///
+/// The run may be shorter than requested (or even empty) if the serving node doesn't have all the
+/// blocks, or if the request exceeded `MAX_BLOCK_RANGE_LENGTH`.
+#[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BlockRange {
+    id: BlockRangeId,
+    blocks: Vec<Block>,
+}
+
+impl BlockRange {
+    /// Creates a new `BlockRange` in response to the request identified by `id`.
+    pub fn new(id: BlockRangeId, blocks: Vec<Block>) -> Self {
+        BlockRange { id, blocks }
+    }
+
+    /// Returns the blocks, in ascending order of height.
+    pub fn blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+
+    /// Returns the blocks, in ascending order of height.
+    pub fn take_blocks(self) -> Vec<Block> {
+        self.blocks
+    }
+
+    /// Returns `true` if the serving node had none of the requested blocks.
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+}
+
+impl Display for BlockRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "block range with {} of {}", self.blocks.len(), self.id)
+    }
+}
+
+impl Item for BlockRange {
+    type Id = BlockRangeId;
+
+    const TAG: Tag = Tag::BlockRange;
+    const ID_IS_COMPLETE_ITEM: bool = false;
+
+    fn id(&self) -> Self::Id {
+        self.id
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use super::*;
     use crate::testing::TestRng;
 
+    /// A `ChainView` holding only the parent links between block hashes.
+    struct MockChainView(HashMap<BlockHash, BlockHash>);
+
+    impl ChainView for MockChainView {
+        fn get_parent(&self, block_hash: &BlockHash) -> Option<BlockHash> {
+            self.0.get(block_hash).copied()
+        }
+    }
+
+    /// Returns the hashes of a chain of `length` blocks, oldest first, along with a view of it.
+    fn mock_chain(rng: &mut TestRng, length: usize) -> (Vec<BlockHash>, MockChainView) {
+        let hashes: Vec<_> = iter::repeat_with(|| BlockHash::new(Digest::random(rng)))
+            .take(length)
+            .collect();
+        let parents = hashes.windows(2).map(|pair| (pair[1], pair[0])).collect();
+        (hashes, MockChainView(parents))
+    }
+
+    /// Returns a random block header whose parent is `parent_hash`.
+    fn header_with_parent(rng: &mut TestRng, parent_hash: BlockHash) -> BlockHeader {
+        let mut header = Block::random(rng).take_header();
+        header.parent_hash = parent_hash;
+        header
+    }
+
     #[test]
     fn json_block_roundtrip() {
         let mut rng = TestRng::new();
@@ -755,6 +963,26 @@ mod tests {
         assert_eq!(block, decoded);
     }
 
+    #[test]
+    fn block_hash_sign_verify_roundtrip() {
+        let mut rng = TestRng::new();
+        let block_hash = BlockHash::new(Digest::random(&mut rng));
+        let other_block_hash = BlockHash::new(Digest::random(&mut rng));
+        let secret_keys = [
+            SecretKey::random_ed25519(&mut rng),
+            SecretKey::random_secp256k1(&mut rng),
+        ];
+        for secret_key in secret_keys.iter() {
+            let public_key = PublicKey::from(secret_key);
+            let signature = block_hash.sign(secret_key, &public_key, &mut rng);
+            assert!(block_hash.verify(&signature, &public_key));
+            assert!(!other_block_hash.verify(&signature, &public_key));
+
+            let other_public_key = PublicKey::from(&SecretKey::random(&mut rng));
+            assert!(!block_hash.verify(&signature, &other_public_key));
+        }
+    }
+
     #[test]
     fn json_finalized_block_roundtrip() {
         let mut rng = TestRng::new();
@@ -763,4 +991,67 @@ mod tests {
         let decoded = serde_json::from_str(&json_string).unwrap();
         assert_eq!(finalized_block, decoded);
     }
+
+    #[test]
+    fn canonical_bytes_matches_spec() {
+        let mut rng = TestRng::new();
+        let proto_block = ProtoBlock {
+            hash: ProtoBlockHash::new(Digest::from([7; Digest::LENGTH])),
+            deploys: vec![],
+            random_bit: false,
+        };
+        let finalized_block = FinalizedBlock::new(
+            proto_block,
+            Timestamp::from(0x0102_0304_0506),
+            None,
+            EraId(3),
+            0x0a0b,
+            PublicKey::random(&mut rng),
+        );
+
+        #[rustfmt::skip]
+        let expected: Vec<u8> = vec![
+            // Era ID.
+            3, 0, 0, 0, 0, 0, 0, 0,
+            // Height.
+            0x0b, 0x0a, 0, 0, 0, 0, 0, 0,
+            // Timestamp.
+            0x06, 0x05, 0x04, 0x03, 0x02, 0x01, 0, 0,
+            // Proto block hash.
+            7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7,
+            7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7,
+        ];
+        assert_eq!(finalized_block.canonical_bytes(), expected);
+    }
+
+    #[test]
+    fn should_find_ancestors_in_chain() {
+        let mut rng = TestRng::new();
+        let (hashes, chain) = mock_chain(&mut rng, 5);
+        let header = header_with_parent(&mut rng, hashes[4]);
+
+        for ancestor in &hashes {
+            assert!(header.is_descendant_of(ancestor, &chain));
+        }
+        assert!(!header.is_descendant_of(&header.hash(), &chain));
+        let unknown = BlockHash::new(Digest::random(&mut rng));
+        assert!(!header.is_descendant_of(&unknown, &chain));
+
+        // The parent is at depth 1, the grandparent at depth 2, and so on.
+        assert!(header.is_descendant_of_within(&hashes[3], &chain, 2));
+        assert!(!header.is_descendant_of_within(&hashes[2], &chain, 2));
+        assert!(!header.is_descendant_of_within(&hashes[4], &chain, 0));
+    }
+
+    #[test]
+    fn should_not_loop_on_cyclic_chain() {
+        let mut rng = TestRng::new();
+        let (hashes, mut chain) = mock_chain(&mut rng, 5);
+        let _ = chain.0.insert(hashes[0], hashes[4]);
+        let header = header_with_parent(&mut rng, hashes[4]);
+
+        assert!(header.is_descendant_of(&hashes[0], &chain));
+        let unknown = BlockHash::new(Digest::random(&mut rng));
+        assert!(!header.is_descendant_of(&unknown, &chain));
+    }
 }
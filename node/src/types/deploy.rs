@@ -15,16 +15,21 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tracing::warn;
 
-use casper_execution_engine::core::engine_state::{
-    executable_deploy_item::ExecutableDeployItem, DeployItem,
+use casper_execution_engine::{
+    core::engine_state::{executable_deploy_item::ExecutableDeployItem, DeployItem},
+    shared::opcode_costs::OpcodeCosts,
+};
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes, U32_SERIALIZED_LENGTH, U8_SERIALIZED_LENGTH},
+    standard_payment::ARG_AMOUNT,
+    U512,
 };
-use casper_types::bytesrepr::{self, FromBytes, ToBytes};
 
 use super::{CryptoRngCore, Item, Tag, TimeDiff, Timestamp};
 #[cfg(test)]
 use crate::testing::TestRng;
 use crate::{
-    components::storage::Value,
+    components::storage::{Value, WithExpiry},
     crypto::{
         asymmetric_key::{self, PublicKey, SecretKey, Signature},
         hash::{self, Digest},
@@ -93,6 +98,15 @@ impl DeployHash {
         DeployHash(hash)
     }
 
+    /// Computes the hash of a deploy from its serialized bytes, without deserializing them.
+    ///
+    /// A deploy is identified by the hash of its `bytesrepr`-serialized header, which commits to
+    /// the payment and session code via the body hash. So `bytes` must be the serialized header,
+    /// e.g. as produced by an external signer.
+    pub fn from_deploy_bytes(bytes: &[u8]) -> Self {
+        DeployHash(hash::hash(bytes))
+    }
+
     /// Returns the wrapped inner hash.
     pub fn inner(&self) -> &Digest {
         &self.0
@@ -324,7 +338,7 @@ impl Deploy {
             chain_name,
         };
         let serialized_header = serialize_header(&header);
-        let hash = DeployHash::new(hash::hash(&serialized_header));
+        let hash = DeployHash::from_deploy_bytes(&serialized_header);
 
         let mut deploy = Deploy {
             hash,
@@ -352,6 +366,11 @@ impl Deploy {
         &self.hash
     }
 
+    /// Returns `true` if `bytes` hash to this deploy's ID, i.e. if they are its serialized header.
+    pub fn hash_bytes(&self, bytes: &[u8]) -> bool {
+        DeployHash::from_deploy_bytes(bytes) == self.hash
+    }
+
     /// Returns a reference to the `DeployHeader` of this `Deploy`.
     pub fn header(&self) -> &DeployHeader {
         &self.header
@@ -372,6 +391,51 @@ impl Deploy {
         &self.session
     }
 
+    /// Returns the number of bytes this deploy occupies when serialized using `bytesrepr`, with
+    /// each approval's signature taken as a tag byte followed by the raw signature bytes.
+    pub fn serialized_length(&self) -> usize {
+        let approvals_length: usize = self
+            .approvals
+            .iter()
+            .map(|approval| {
+                approval.signer.serialized_length()
+                    + U8_SERIALIZED_LENGTH
+                    + approval.signature.as_ref().len()
+            })
+            .sum();
+        self.hash.serialized_length()
+            + self.header.serialized_length()
+            + self.payment.serialized_length()
+            + self.session.serialized_length()
+            + U32_SERIALIZED_LENGTH
+            + approvals_length
+    }
+
+    /// Returns a conservative upper bound on the gas used to execute this deploy, calculated as
+    /// its serialized length multiplied by the cost of a regular opcode.
+    ///
+    /// This is a cheap heuristic available before execution, and is not expected to be accurate.
+    pub fn estimated_gas_cost(&self, opcode_costs: &OpcodeCosts) -> u64 {
+        (self.serialized_length() as u64).saturating_mul(u64::from(opcode_costs.regular))
+    }
+
+    /// Returns the amount of motes offered as payment, i.e. the `amount` runtime arg of the
+    /// payment code, if the payment is `ModuleBytes` with such an arg.
+    pub fn payment_motes(&self) -> Option<U512> {
+        match &self.payment {
+            ExecutableDeployItem::ModuleBytes { .. } => self
+                .payment
+                .clone()
+                .into_runtime_args()
+                .ok()?
+                .get(ARG_AMOUNT)?
+                .clone()
+                .into_t()
+                .ok(),
+            _ => None,
+        }
+    }
+
     /// Returns true iff:
     ///   * the deploy hash is correct (should be the hash of the header), and
     ///   * the body hash is correct (should be the hash of the body), and
@@ -405,8 +469,8 @@ impl Deploy {
         ];
         let chain_name = String::from("casper-example");
 
-        let payment = rng.gen();
-        let session = rng.gen();
+        let payment = random_executable_deploy_item(rng);
+        let session = random_executable_deploy_item(rng);
 
         let secret_key = SecretKey::random(rng);
 
@@ -424,6 +488,19 @@ impl Deploy {
     }
 }
 
+/// Generates a random `ExecutableDeployItem`, with any module bytes replaced by a minimal valid
+/// wasm module so that the deploy passes the `DeployAcceptor`'s validation.
+#[cfg(test)]
+fn random_executable_deploy_item(rng: &mut TestRng) -> ExecutableDeployItem {
+    match rng.gen() {
+        ExecutableDeployItem::ModuleBytes { args, .. } => ExecutableDeployItem::ModuleBytes {
+            module_bytes: wabt::wat2wasm("(module (memory 1))").expect("should parse"),
+            args,
+        },
+        item => item,
+    }
+}
+
 fn serialize_header(header: &DeployHeader) -> Vec<u8> {
     header
         .to_bytes()
@@ -453,7 +530,7 @@ fn validate_deploy(deploy: &Deploy) -> bool {
     }
 
     let serialized_header = serialize_header(&deploy.header);
-    let hash = DeployHash::new(hash::hash(&serialized_header));
+    let hash = DeployHash::from_deploy_bytes(&serialized_header);
     if hash != deploy.hash {
         warn!(?deploy, ?hash, "invalid deploy hash");
         return false;
@@ -489,6 +566,12 @@ impl Value for Deploy {
     }
 }
 
+impl WithExpiry for Deploy {
+    fn expired(&self, current_instant: Timestamp) -> bool {
+        self.header().expired(current_instant)
+    }
+}
+
 impl Item for Deploy {
     type Id = DeployHash;
 
@@ -532,9 +615,32 @@ impl From<Deploy> for DeployItem {
 mod tests {
     use std::time::Duration;
 
+    use casper_types::{runtime_args, RuntimeArgs};
+
     use super::*;
     use crate::testing::TestRng;
 
+    fn deploy_with_items(payment: ExecutableDeployItem, session: ExecutableDeployItem) -> Deploy {
+        Deploy::new(
+            Timestamp::zero(),
+            TimeDiff::from(Duration::default()),
+            1,
+            vec![],
+            String::from("casper-example"),
+            payment,
+            session,
+            &SecretKey::generate_ed25519(),
+            &mut TestRng::new(),
+        )
+    }
+
+    fn module_bytes(module_size: usize, args: RuntimeArgs) -> ExecutableDeployItem {
+        ExecutableDeployItem::ModuleBytes {
+            module_bytes: vec![0; module_size],
+            args: args.to_bytes().unwrap(),
+        }
+    }
+
     #[test]
     fn json_roundtrip() {
         let mut rng = TestRng::new();
@@ -563,6 +669,55 @@ mod tests {
         bytesrepr::test_serialization_roundtrip(deploy.header());
     }
 
+    #[test]
+    fn deploy_hash_from_bytes_should_match_id() {
+        let mut rng = TestRng::new();
+        for _ in 0..10 {
+            let deploy = Deploy::random(&mut rng);
+            let serialized_header = deploy.header().to_bytes().unwrap();
+            assert_eq!(
+                DeployHash::from_deploy_bytes(&serialized_header),
+                *deploy.id()
+            );
+            assert!(deploy.hash_bytes(&serialized_header));
+
+            let other_deploy = Deploy::random(&mut rng);
+            let other_serialized_header = other_deploy.header().to_bytes().unwrap();
+            assert!(!deploy.hash_bytes(&other_serialized_header));
+        }
+    }
+
+    #[test]
+    fn deploy_hash_from_fixed_bytes() {
+        const SERIALIZED_HEADER: &str = "0120000000d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325a\
+            f021a68f707511a00806e877401000080ee3600000000000a0000000000000011111111111111111111111111\
+            111111111111111111111111111111111111110100000022222222222222222222222222222222222222222222\
+            222222222222222222220e0000006361737065722d6578616d706c65";
+        const DEPLOY_HASH: &str =
+            "a281c1cab9f491bb369a9e317e006526efba2d469a0879417130f0a8f564df5b";
+
+        let header = DeployHeader {
+            account: PublicKey::from_hex(
+                "01d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+            )
+            .unwrap(),
+            timestamp: Timestamp::from(1_600_000_000_000),
+            ttl: TimeDiff::from(3_600_000),
+            gas_price: 10,
+            body_hash: Digest::from([0x11; Digest::LENGTH]),
+            dependencies: vec![DeployHash::new(Digest::from([0x22; Digest::LENGTH]))],
+            chain_name: String::from("casper-example"),
+        };
+        let serialized_header = hex::decode(SERIALIZED_HEADER).unwrap();
+        assert_eq!(header.to_bytes().unwrap(), serialized_header);
+
+        let deploy_hash = DeployHash::new(Digest::from_hex(DEPLOY_HASH).unwrap());
+        assert_eq!(
+            DeployHash::from_deploy_bytes(&serialized_header),
+            deploy_hash
+        );
+    }
+
     #[test]
     fn is_valid() {
         let mut rng = TestRng::new();
@@ -593,4 +748,91 @@ mod tests {
         assert!(!deploy.is_valid(), "should not be valid");
         assert_eq!(deploy.is_valid, Some(false), "is valid should be false");
     }
+
+    #[test]
+    fn should_estimate_gas_cost_from_serialized_length() {
+        let opcode_costs = OpcodeCosts::default();
+        let payment = module_bytes(0, RuntimeArgs::new());
+
+        let small = deploy_with_items(payment.clone(), module_bytes(1_000, RuntimeArgs::new()));
+        let large = deploy_with_items(payment, module_bytes(11_000, RuntimeArgs::new()));
+        assert_eq!(
+            large.serialized_length() - small.serialized_length(),
+            10_000
+        );
+
+        let small_cost = small.estimated_gas_cost(&opcode_costs);
+        let large_cost = large.estimated_gas_cost(&opcode_costs);
+        assert_eq!(
+            small_cost,
+            small.serialized_length() as u64 * u64::from(opcode_costs.regular)
+        );
+        assert_eq!(
+            large_cost - small_cost,
+            10_000 * u64::from(opcode_costs.regular)
+        );
+    }
+
+    #[test]
+    fn serialized_length_should_account_for_all_fields() {
+        let deploy = deploy_with_items(
+            module_bytes(0, RuntimeArgs::new()),
+            module_bytes(1_000, RuntimeArgs::new()),
+        );
+        // A single approval by an Ed25519 key.
+        let approvals_length = deploy.header.account.serialized_length()
+            + U8_SERIALIZED_LENGTH
+            + Signature::ED25519_LENGTH;
+        assert_eq!(
+            deploy.serialized_length(),
+            deploy.hash.serialized_length()
+                + deploy.header.serialized_length()
+                + serialize_body(&deploy.payment, &deploy.session).len()
+                + U32_SERIALIZED_LENGTH
+                + approvals_length
+        );
+    }
+
+    #[test]
+    fn should_get_payment_motes() {
+        let amount = U512::from(2_500_000_000u64);
+        let session = ExecutableDeployItem::Transfer { args: vec![] };
+
+        let payment = module_bytes(0, runtime_args! { ARG_AMOUNT => amount });
+        let deploy = deploy_with_items(payment, session.clone());
+        assert_eq!(deploy.payment_motes(), Some(amount));
+
+        let payment = module_bytes(100, runtime_args! { ARG_AMOUNT => amount });
+        let deploy = deploy_with_items(payment, session);
+        assert_eq!(deploy.payment_motes(), Some(amount));
+    }
+
+    #[test]
+    fn should_not_get_payment_motes() {
+        let session = ExecutableDeployItem::Transfer { args: vec![] };
+
+        // No `amount` arg.
+        let payment = module_bytes(0, RuntimeArgs::new());
+        assert_eq!(
+            deploy_with_items(payment, session.clone()).payment_motes(),
+            None
+        );
+
+        // `amount` arg of the wrong type.
+        let payment = module_bytes(0, runtime_args! { ARG_AMOUNT => 1u64 });
+        assert_eq!(
+            deploy_with_items(payment, session.clone()).payment_motes(),
+            None
+        );
+
+        // Not `ModuleBytes`.
+        let payment = ExecutableDeployItem::StoredContractByName {
+            name: String::from("payment"),
+            entry_point: String::from("pay"),
+            args: runtime_args! { ARG_AMOUNT => U512::one() }
+                .to_bytes()
+                .unwrap(),
+        };
+        assert_eq!(deploy_with_items(payment, session).payment_motes(), None);
+    }
 }
@@ -32,6 +32,8 @@ pub enum Tag {
     GossipedAddress,
     /// A block requested by its height in the linear chain.
     BlockByHeight,
+    /// A run of consecutive blocks requested by their heights in the linear chain.
+    BlockRange,
 }
 
 /// A trait which allows an implementing type to be used by the gossiper and fetcher components, and
@@ -24,11 +24,17 @@ pub struct Bid {
     pub staked_amount: U512,
     /// Delegation rate.
     pub delegation_rate: u64,
-    /// A flag that represents a winning entry.
+    /// Whether the bid was created at genesis for one of the founding validators.
+    pub founding_validator: bool,
+    /// The era from which the staked funds are released.
     ///
-    /// `Some` indicates locked funds for a specific era and an autowin status, and `None` case
-    /// means that funds are unlocked and autowin status is removed.
-    pub funds_locked: Option<u64>,
+    /// `Some` indicates locked funds and an autowin status until the given era, and `None` means
+    /// that funds are unlocked and the autowin status is removed.
+    pub release_era: Option<u64>,
+    /// Number of consecutive auctions the bid has been below the minimum bid amount.
+    pub inactive_eras: u64,
+    /// Whether the bid has been deactivated by the validator.
+    pub deactivated: bool,
 }
 
 impl From<AuctionBid> for Bid {
@@ -37,7 +43,10 @@ impl From<AuctionBid> for Bid {
             bonding_purse: bid.bonding_purse.to_formatted_string(),
             staked_amount: bid.staked_amount,
             delegation_rate: bid.delegation_rate,
-            funds_locked: bid.funds_locked,
+            founding_validator: bid.founding_validator,
+            release_era: bid.release_era,
+            inactive_eras: bid.inactive_eras,
+            deactivated: bid.deactivated,
         }
     }
 }
@@ -76,7 +76,7 @@ impl ExecutionResult {
 impl From<&EngineExecutionResult> for ExecutionResult {
     fn from(ee_execution_result: &EngineExecutionResult) -> Self {
         match ee_execution_result {
-            EngineExecutionResult::Success { effect, cost } => ExecutionResult {
+            EngineExecutionResult::Success { effect, cost, .. } => ExecutionResult {
                 effect: effect.into(),
                 cost: cost.value(),
                 error_message: None,
@@ -14,6 +14,10 @@ pub struct NodeConfig {
     pub chainspec_config_path: External<Chainspec>,
     /// Hash used as a trust anchor when joining, if any.
     pub trusted_hash: Option<BlockHash>,
+    /// Expected height of the block identified by `trusted_hash`, if any.
+    ///
+    /// Joining fails if the trusted block downloaded from peers has a different height.
+    pub trusted_height: Option<u64>,
 }
 
 impl Default for NodeConfig {
@@ -21,6 +25,7 @@ impl Default for NodeConfig {
         NodeConfig {
             chainspec_config_path: External::path(DEFAULT_CHAINSPEC_CONFIG_PATH),
             trusted_hash: None,
+            trusted_height: None,
         }
     }
 }
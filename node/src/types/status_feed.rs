@@ -2,7 +2,10 @@ use std::{collections::HashMap, hash::Hash, net::SocketAddr};
 
 use serde::Serialize;
 
-use crate::{components::chainspec_loader::ChainspecInfo, types::Block};
+use crate::{
+    components::chainspec_loader::{ChainspecInfo, UpgradeSummary},
+    types::Block,
+};
 
 /// Data feed for client "info_get_status" endpoint.
 #[derive(Debug, Serialize)]
@@ -14,6 +17,8 @@ pub struct StatusFeed<I> {
     pub peers: HashMap<I, SocketAddr>,
     /// The chainspec info for this node.
     pub chainspec_info: ChainspecInfo,
+    /// The upgrades scheduled in the chainspec, in order of activation.
+    pub upgrade_schedule: Vec<UpgradeSummary>,
     /// The compiled node version.
     pub version: &'static str,
 }
@@ -23,11 +28,13 @@ impl<I> StatusFeed<I> {
         last_added_block: Option<Block>,
         peers: HashMap<I, SocketAddr>,
         chainspec_info: ChainspecInfo,
+        upgrade_schedule: Vec<UpgradeSummary>,
     ) -> Self {
         StatusFeed {
             last_added_block,
             peers,
             chainspec_info,
+            upgrade_schedule,
             version: crate::VERSION_STRING.as_str(),
         }
     }
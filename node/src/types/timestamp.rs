@@ -1,4 +1,5 @@
 use std::{
+    convert::TryFrom,
     fmt::{self, Display, Formatter},
     ops::{Add, AddAssign, Div, Mul, Rem, Sub},
     str::FromStr,
@@ -43,6 +44,23 @@ impl Timestamp {
         self.0
     }
 
+    /// Returns the timestamp as the number of whole seconds since the Unix epoch
+    pub fn to_unix_seconds(&self) -> u64 {
+        self.0 / 1000
+    }
+
+    /// Returns the timestamp as the number of seconds since the Unix epoch, including the
+    /// fractional part
+    pub fn to_unix_seconds_f64(&self) -> f64 {
+        self.0 as f64 / 1000.0
+    }
+
+    /// Returns the timestamp `secs` seconds after the Unix epoch, saturating at the maximum
+    /// representable timestamp
+    pub fn from_unix_seconds(secs: u64) -> Self {
+        Timestamp(secs.saturating_mul(1000))
+    }
+
     /// Returns the difference between `self` and `other`, or `0` if `self` is earlier than `other`.
     pub fn saturating_sub(self, other: Timestamp) -> TimeDiff {
         TimeDiff(self.0.saturating_sub(other.0))
@@ -82,6 +100,30 @@ impl FromStr for Timestamp {
     }
 }
 
+impl TryFrom<SystemTime> for Timestamp {
+    type Error = TimestampError;
+
+    fn try_from(system_time: SystemTime) -> Result<Self, Self::Error> {
+        let millis = system_time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_err(|_| TimestampError::OutOfRange)?
+            .as_millis();
+        u64::try_from(millis)
+            .map(Timestamp)
+            .map_err(|_| TimestampError::OutOfRange)
+    }
+}
+
+impl TryFrom<Timestamp> for SystemTime {
+    type Error = TimestampError;
+
+    fn try_from(timestamp: Timestamp) -> Result<Self, Self::Error> {
+        SystemTime::UNIX_EPOCH
+            .checked_add(Duration::from_millis(timestamp.0))
+            .ok_or(TimestampError::OutOfRange)
+    }
+}
+
 impl Sub<Timestamp> for Timestamp {
     type Output = TimeDiff;
 
@@ -212,6 +254,26 @@ impl TimeDiff {
     pub fn millis(&self) -> u64 {
         self.0
     }
+
+    /// Returns the number of complete rounds of length `2^round_exponent` milliseconds that fit
+    /// into this time difference.
+    ///
+    /// Returns 0 if `round_exponent` is 64 or greater.
+    pub fn as_rounds(&self, round_exponent: u8) -> u64 {
+        self.0.checked_shr(u32::from(round_exponent)).unwrap_or(0)
+    }
+
+    /// Returns the duration of `count` rounds of length `2^round_exponent` milliseconds.
+    ///
+    /// Saturates at the maximum `TimeDiff` on overflow.
+    pub fn from_rounds(count: u64, round_exponent: u8) -> Self {
+        let millis = match u64::max_value().checked_shr(u32::from(round_exponent)) {
+            Some(max_count) if count <= max_count => count << round_exponent,
+            _ if count == 0 => 0,
+            _ => u64::max_value(),
+        };
+        TimeDiff(millis)
+    }
 }
 
 impl Mul<u64> for TimeDiff {
@@ -308,6 +370,81 @@ mod tests {
         bytesrepr::test_serialization_roundtrip(&timestamp);
     }
 
+    #[test]
+    fn should_convert_to_and_from_unix_seconds() {
+        let timestamp = Timestamp(1_596_763_000_999);
+        assert_eq!(timestamp.to_unix_seconds(), 1_596_763_000);
+        assert!((timestamp.to_unix_seconds_f64() - 1_596_763_000.999).abs() < 1e-6);
+        assert_eq!(
+            Timestamp::from_unix_seconds(1_596_763_000),
+            Timestamp(1_596_763_000_000)
+        );
+
+        // The largest number of seconds which can be represented exactly.
+        let max_secs = u64::max_value() / 1000;
+        let timestamp = Timestamp::from_unix_seconds(max_secs);
+        assert_eq!(timestamp.millis(), max_secs * 1000);
+        assert_eq!(timestamp.to_unix_seconds(), max_secs);
+
+        // Beyond that, the timestamp saturates.
+        let timestamp = Timestamp::from_unix_seconds(max_secs + 1);
+        assert_eq!(timestamp.millis(), u64::max_value());
+        assert_eq!(timestamp.to_unix_seconds(), max_secs);
+        assert_eq!(
+            Timestamp::from_unix_seconds(u64::max_value()).millis(),
+            u64::max_value()
+        );
+    }
+
+    #[test]
+    fn should_convert_to_and_from_system_time() {
+        let timestamp = Timestamp::now();
+        let system_time = SystemTime::try_from(timestamp).unwrap();
+        assert_eq!(Timestamp::try_from(system_time).unwrap(), timestamp);
+
+        let max_secs = u64::max_value() / 1000;
+        let timestamp = Timestamp::from_unix_seconds(max_secs);
+        let system_time = SystemTime::try_from(timestamp).unwrap();
+        assert_eq!(Timestamp::try_from(system_time).unwrap(), timestamp);
+
+        let before_epoch = SystemTime::UNIX_EPOCH - Duration::from_secs(1);
+        assert!(Timestamp::try_from(before_epoch).is_err());
+    }
+
+    #[test]
+    fn should_convert_to_and_from_rounds() {
+        assert_eq!(TimeDiff::from_rounds(3, 4), TimeDiff::from(48));
+        assert_eq!(TimeDiff::from(48).as_rounds(4), 3);
+        // Incomplete rounds are not counted.
+        assert_eq!(TimeDiff::from(63).as_rounds(4), 3);
+        assert_eq!(
+            TimeDiff::from(u64::max_value()).as_rounds(0),
+            u64::max_value()
+        );
+        assert_eq!(TimeDiff::from(u64::max_value()).as_rounds(63), 1);
+        assert_eq!(TimeDiff::from(u64::max_value()).as_rounds(64), 0);
+        assert_eq!(
+            TimeDiff::from(u64::max_value()).as_rounds(u8::max_value()),
+            0
+        );
+    }
+
+    #[test]
+    fn from_rounds_should_saturate_on_overflow() {
+        let max = TimeDiff::from(u64::max_value());
+        assert_eq!(TimeDiff::from_rounds(u64::max_value(), 0), max);
+        assert_eq!(TimeDiff::from_rounds(1, 63), TimeDiff::from(1 << 63));
+        assert_eq!(TimeDiff::from_rounds(2, 63), max);
+        assert_eq!(TimeDiff::from_rounds(u64::max_value(), 1), max);
+        assert_eq!(TimeDiff::from_rounds(1, 64), max);
+        assert_eq!(TimeDiff::from_rounds(0, u8::max_value()), TimeDiff::from(0));
+        assert_eq!(
+            TimeDiff::from_rounds((1 << 20) - 1, 44),
+            TimeDiff::from(u64::max_value() - ((1 << 44) - 1))
+        );
+        assert_eq!(TimeDiff::from_rounds(1 << 20, 44), max);
+    }
+
     #[test]
     fn timediff_serialization_roundtrip() {
         let mut rng = TestRng::new();
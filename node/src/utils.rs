@@ -16,11 +16,12 @@ use std::{
 use lazy_static::lazy_static;
 use libc::{c_long, sysconf, _SC_PAGESIZE};
 use thiserror::Error;
+use tokio::signal::unix::{signal, SignalKind};
 
 #[cfg(test)]
 pub use external::RESOURCES_PATH;
 pub use external::{External, LoadError, Loadable};
-pub(crate) use round_robin::WeightedRoundRobin;
+pub(crate) use round_robin::{QueueSnapshot, WeightedRoundRobin};
 
 /// Sensible default for many if not all systems.
 const DEFAULT_PAGE_SIZE: usize = 4096;
@@ -54,6 +55,29 @@ pub(crate) fn leak<T>(value: T) -> &'static T {
     Box::leak(Box::new(value))
 }
 
+/// Compares two byte strings in time independent of their contents, to avoid leaking secrets such
+/// as API tokens through timing.  Only their lengths are compared in variable time.
+pub(crate) fn constant_time_compare(lhs: &[u8], rhs: &[u8]) -> bool {
+    if lhs.len() != rhs.len() {
+        return false;
+    }
+    lhs.iter()
+        .zip(rhs)
+        .fold(0u8, |difference, (left, right)| difference | (left ^ right))
+        == 0
+}
+
+/// Waits until the process receives either `SIGTERM` or `SIGINT`, returning the signal's name.
+pub(crate) async fn wait_for_shutdown_signal() -> io::Result<&'static str> {
+    let mut sigterm = signal(SignalKind::terminate())?;
+    let mut sigint = signal(SignalKind::interrupt())?;
+
+    tokio::select! {
+        _ = sigterm.recv() => Ok("SIGTERM"),
+        _ = sigint.recv() => Ok("SIGINT"),
+    }
+}
+
 /// A display-helper that shows iterators display joined by ",".
 #[derive(Debug)]
 pub(crate) struct DisplayIter<T>(RefCell<Option<T>>);
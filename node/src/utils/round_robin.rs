@@ -6,12 +6,13 @@
 
 use std::{
     collections::{HashMap, VecDeque},
-    fmt::Debug,
+    fmt::{Debug, Display},
     hash::Hash,
     num::NonZeroUsize,
     sync::atomic::{AtomicUsize, Ordering},
 };
 
+use serde::{Deserialize, Serialize};
 use tokio::sync::{Mutex, Semaphore};
 
 /// Weighted round-robin scheduler.
@@ -71,6 +72,15 @@ impl<I> QueueState<I> {
     }
 }
 
+/// A snapshot of the items held by a single queue.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueueSnapshot {
+    /// The number of items in the queue.
+    pub event_count: usize,
+    /// The `Display` rendering of the items at the front of the queue, oldest first.
+    pub events: Vec<String>,
+}
+
 /// The inner state of the queue iteration.
 #[derive(Copy, Clone, Debug)]
 struct IterationState<K> {
@@ -196,6 +206,31 @@ where
             .map(|(key, queue)| (*key, queue.event_count()))
             .collect()
     }
+
+    /// Returns a snapshot of each of the queues, rendering at most `max_items` items per queue.
+    ///
+    /// Each queue is only locked while its items are rendered, so that popping is never blocked
+    /// for long.
+    pub(crate) async fn snapshot(&self, max_items: usize) -> HashMap<K, QueueSnapshot>
+    where
+        I: Display,
+    {
+        let mut snapshots = HashMap::new();
+        for (key, queue_state) in self.queues.iter() {
+            let queue = queue_state.queue.lock().await;
+            let snapshot = QueueSnapshot {
+                event_count: queue.len(),
+                events: queue
+                    .iter()
+                    .take(max_items)
+                    .map(ToString::to_string)
+                    .collect(),
+            };
+            drop(queue);
+            let _ = snapshots.insert(*key, snapshot);
+        }
+        snapshots
+    }
 }
 
 #[cfg(test)]
@@ -244,4 +279,27 @@ mod tests {
         assert_eq!(('f', QueueKind::Two), scheduler.pop().await);
         assert_eq!(('c', QueueKind::One), scheduler.pop().await);
     }
+
+    #[tokio::test]
+    async fn should_snapshot_queues() {
+        let scheduler = WeightedRoundRobin::<char, QueueKind>::new(weights());
+        for item in "abc".chars() {
+            scheduler.push(item, QueueKind::One).await;
+        }
+
+        let snapshots = scheduler.snapshot(2).await;
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(
+            snapshots[&QueueKind::One],
+            QueueSnapshot {
+                event_count: 3,
+                events: vec!["a".to_string(), "b".to_string()],
+            }
+        );
+        assert_eq!(snapshots[&QueueKind::Two], QueueSnapshot::default());
+
+        // Taking a snapshot leaves the queues untouched.
+        assert_eq!(scheduler.item_count(), 3);
+        assert_eq!(('a', QueueKind::One), scheduler.pop().await);
+    }
 }
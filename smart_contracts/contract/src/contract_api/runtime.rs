@@ -179,6 +179,16 @@ pub fn get_named_arg<T: FromBytes>(name: &str) -> T {
     bytesrepr::deserialize(arg_bytes).unwrap_or_revert_with(ApiError::InvalidArgument)
 }
 
+/// Returns given named argument passed to the host for the current module invocation, or `None` if
+/// no argument of that name was passed.
+///
+/// Reverts with [`ApiError::InvalidArgument`] if the argument is present but cannot be
+/// deserialized as `T`.
+pub fn try_get_named_arg<T: FromBytes>(name: &str) -> Option<T> {
+    get_named_arg_size(name)?;
+    Some(get_named_arg(name))
+}
+
 /// Returns the caller of the current context, i.e. the [`AccountHash`] of the account which made
 /// the deploy request.
 pub fn get_caller() -> AccountHash {
@@ -3,10 +3,7 @@
 
 extern crate alloc;
 
-use casper_contract::{
-    contract_api::{account, runtime, system},
-    unwrap_or_revert::UnwrapOrRevert,
-};
+use casper_contract::contract_api::{account, runtime, system};
 use casper_types::{auction, runtime_args, PublicKey, RuntimeArgs, URef, U512};
 
 const ARG_AMOUNT: &str = "amount";
@@ -22,28 +19,19 @@ fn delegate(delegator: PublicKey, validator: PublicKey, source_purse: URef, amou
         auction::ARG_SOURCE_PURSE => source_purse,
         auction::ARG_AMOUNT => amount,
     };
-    runtime::call_contract::<U512>(contract_hash, auction::METHOD_DELEGATE, args);
+    runtime::call_contract::<(URef, U512)>(contract_hash, auction::METHOD_DELEGATE, args);
 }
 
 // Delegate contract.
 //
-// Accepts a delegator's public key, validator's public key, amount and a delgation rate.
-// Issues an delegation request to the auction contract.
+// Accepts a delegator's public key, validator's public key and amount.
+// Issues an delegation request to the auction contract, which moves the amount from the caller's
+// main purse to the delegation's bonding purse.
 #[no_mangle]
 pub extern "C" fn call() {
     let delegator = runtime::get_named_arg(ARG_DELEGATOR);
     let validator = runtime::get_named_arg(ARG_VALIDATOR);
     let amount = runtime::get_named_arg(ARG_AMOUNT);
 
-    // provision bonding purse
-    let delegating_purse = {
-        let source_purse = account::get_main_purse();
-        let bonding_purse = system::create_purse();
-        // transfer amount to a bidding purse
-        system::transfer_from_purse_to_purse(source_purse, bonding_purse, amount)
-            .unwrap_or_revert();
-        bonding_purse
-    };
-
-    delegate(delegator, validator, delegating_purse, amount);
+    delegate(delegator, validator, account::get_main_purse(), amount);
 }
@@ -10,13 +10,18 @@ use casper_contract::{
 };
 use casper_types::{
     auction::{
-        Bid, BidPurses, Bids, DelegatorRewardMap, Delegators, EraValidators, SeigniorageRecipient,
-        SeigniorageRecipients, SeigniorageRecipientsSnapshot, UnbondingPurses, ValidatorRewardMap,
-        ValidatorWeights, ARG_GENESIS_VALIDATORS, ARG_MINT_CONTRACT_PACKAGE_HASH,
-        ARG_VALIDATOR_SLOTS, AUCTION_DELAY, BIDS_KEY, BID_PURSES_KEY, DEFAULT_LOCKED_FUNDS_PERIOD,
-        DELEGATORS_KEY, DELEGATOR_REWARD_MAP, DELEGATOR_REWARD_PURSE, ERA_ID_KEY,
-        ERA_VALIDATORS_KEY, INITIAL_ERA_ID, SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY,
-        UNBONDING_PURSES_KEY, VALIDATOR_REWARD_MAP, VALIDATOR_REWARD_PURSE, VALIDATOR_SLOTS_KEY,
+        AuctionEvents, Bid, BidPurses, Bids, DelegatorBondingPurses, DelegatorRewardMap,
+        Delegators, EraSummary, EraValidators, SeigniorageRecipient, SeigniorageRecipients,
+        SeigniorageRecipientsSnapshot, UnbondingPurses, ValidatorRewardMap, ValidatorWeights,
+        ARG_AUCTION_DELAY, ARG_BID_EVICTION_DELAY, ARG_GENESIS_VALIDATORS, ARG_LOCKED_FUNDS_PERIOD,
+        ARG_MINT_CONTRACT_PACKAGE_HASH, ARG_UNBONDING_DELAY, ARG_VALIDATOR_SLOTS,
+        AUCTION_DELAY_KEY, AUCTION_EVENTS_KEY, BIDS_KEY, BID_EVICTION_DELAY_KEY, BID_PURSES_KEY,
+        DELEGATORS_KEY, DELEGATOR_ACCRUED_REWARD_MAP, DELEGATOR_BONDING_PURSES_KEY,
+        DELEGATOR_REWARD_MAP, DELEGATOR_REWARD_PURSE, DELEGATOR_WITHDRAWN_REWARD_MAP, ERA_ID_KEY,
+        ERA_SUMMARY_KEY, ERA_VALIDATORS_KEY, INITIAL_ERA_ID, LOCKED_FUNDS_PERIOD_KEY,
+        SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY, SLASHED_FUNDS_PURSE, UNBONDING_DELAY_KEY,
+        UNBONDING_PURSES_KEY, VALIDATOR_ACCRUED_REWARD_MAP, VALIDATOR_REWARD_MAP,
+        VALIDATOR_REWARD_PURSE, VALIDATOR_SLOTS_KEY, VALIDATOR_WITHDRAWN_REWARD_MAP,
     },
     contracts::{NamedKeys, CONTRACT_INITIAL_VERSION},
     runtime_args,
@@ -35,6 +40,10 @@ pub extern "C" fn install() {
         runtime::get_named_arg(ARG_MINT_CONTRACT_PACKAGE_HASH);
 
     let validator_slots: u32 = runtime::get_named_arg(ARG_VALIDATOR_SLOTS);
+    let auction_delay: u64 = runtime::get_named_arg(ARG_AUCTION_DELAY);
+    let unbonding_delay: u64 = runtime::get_named_arg(ARG_UNBONDING_DELAY);
+    let bid_eviction_delay: u64 = runtime::get_named_arg(ARG_BID_EVICTION_DELAY);
+    let locked_funds_period: u64 = runtime::get_named_arg(ARG_LOCKED_FUNDS_PERIOD);
 
     let entry_points = auction::get_entry_points();
     let (contract_package_hash, access_uref) = storage::create_contract_package_at_hash();
@@ -55,16 +64,18 @@ pub extern "C" fn install() {
         // List of validators for initial era.
         let mut initial_validator_weights = ValidatorWeights::new();
 
+        // Founding validators' funds are locked until the end of the locked funds period.
+        let release_era = INITIAL_ERA_ID + locked_funds_period;
+
         for (validator_public_key, amount) in genesis_validators {
             let bonding_purse = create_purse(mint_package_hash, amount);
-            let founding_validator =
-                Bid::new_locked(bonding_purse, amount, DEFAULT_LOCKED_FUNDS_PERIOD);
+            let founding_validator = Bid::new_founding(bonding_purse, amount, release_era);
             validators.insert(validator_public_key, founding_validator);
             initial_validator_weights.insert(validator_public_key, amount);
             bid_purses.insert(validator_public_key, bonding_purse);
         }
 
-        let initial_snapshot_range = INITIAL_ERA_ID..=INITIAL_ERA_ID + AUCTION_DELAY;
+        let initial_snapshot_range = INITIAL_ERA_ID..=INITIAL_ERA_ID + auction_delay;
 
         // Starting era validators
         named_keys.insert(ERA_ID_KEY.into(), storage::new_uref(INITIAL_ERA_ID).into());
@@ -74,6 +85,13 @@ pub extern "C" fn install() {
             era_validators.insert(era_index, initial_validator_weights.clone());
         }
 
+        // Summary of the last era covered by the initial snapshot, as if chosen by an auction.
+        let era_summary = EraSummary::new(
+            INITIAL_ERA_ID + auction_delay,
+            initial_validator_weights.clone(),
+            validators.len() as u64,
+        );
+
         let seigniorage_recipients = compute_seigniorage_recipients(&validators);
 
         let mut initial_seigniorage_recipients = SeigniorageRecipientsSnapshot::new();
@@ -89,10 +107,22 @@ pub extern "C" fn install() {
             DELEGATORS_KEY.into(),
             storage::new_uref(Delegators::new()).into(),
         );
+        named_keys.insert(
+            DELEGATOR_BONDING_PURSES_KEY.into(),
+            storage::new_uref(DelegatorBondingPurses::new()).into(),
+        );
         named_keys.insert(
             ERA_VALIDATORS_KEY.into(),
             storage::new_uref(era_validators).into(),
         );
+        named_keys.insert(
+            ERA_SUMMARY_KEY.into(),
+            storage::new_uref(era_summary).into(),
+        );
+        named_keys.insert(
+            AUCTION_EVENTS_KEY.into(),
+            storage::new_uref(AuctionEvents::new()).into(),
+        );
         named_keys.insert(BID_PURSES_KEY.into(), storage::new_uref(bid_purses).into());
         named_keys.insert(
             UNBONDING_PURSES_KEY.into(),
@@ -106,6 +136,10 @@ pub extern "C" fn install() {
             VALIDATOR_REWARD_PURSE.into(),
             create_purse(mint_package_hash, U512::zero()).into(),
         );
+        named_keys.insert(
+            SLASHED_FUNDS_PURSE.into(),
+            create_purse(mint_package_hash, U512::zero()).into(),
+        );
         named_keys.insert(
             DELEGATOR_REWARD_MAP.into(),
             storage::new_uref(DelegatorRewardMap::new()).into(),
@@ -114,10 +148,42 @@ pub extern "C" fn install() {
             VALIDATOR_REWARD_MAP.into(),
             storage::new_uref(ValidatorRewardMap::new()).into(),
         );
+        named_keys.insert(
+            DELEGATOR_ACCRUED_REWARD_MAP.into(),
+            storage::new_uref(DelegatorRewardMap::new()).into(),
+        );
+        named_keys.insert(
+            VALIDATOR_ACCRUED_REWARD_MAP.into(),
+            storage::new_uref(ValidatorRewardMap::new()).into(),
+        );
+        named_keys.insert(
+            DELEGATOR_WITHDRAWN_REWARD_MAP.into(),
+            storage::new_uref(DelegatorRewardMap::new()).into(),
+        );
+        named_keys.insert(
+            VALIDATOR_WITHDRAWN_REWARD_MAP.into(),
+            storage::new_uref(ValidatorRewardMap::new()).into(),
+        );
         named_keys.insert(
             VALIDATOR_SLOTS_KEY.into(),
             storage::new_uref(validator_slots).into(),
         );
+        named_keys.insert(
+            AUCTION_DELAY_KEY.into(),
+            storage::new_uref(auction_delay).into(),
+        );
+        named_keys.insert(
+            UNBONDING_DELAY_KEY.into(),
+            storage::new_uref(unbonding_delay).into(),
+        );
+        named_keys.insert(
+            BID_EVICTION_DELAY_KEY.into(),
+            storage::new_uref(bid_eviction_delay).into(),
+        );
+        named_keys.insert(
+            LOCKED_FUNDS_PERIOD_KEY.into(),
+            storage::new_uref(locked_funds_period).into(),
+        );
 
         named_keys
     };
@@ -13,14 +13,18 @@ use casper_contract::{
 use casper_types::{
     account::AccountHash,
     auction::{
-        Auction, DelegationRate, MintProvider, RuntimeProvider, SeigniorageRecipients,
-        StorageProvider, SystemProvider, ValidatorWeights, ARG_AMOUNT, ARG_DELEGATION_RATE,
-        ARG_DELEGATOR, ARG_DELEGATOR_PUBLIC_KEY, ARG_ERA_ID, ARG_PUBLIC_KEY, ARG_REWARD_FACTORS,
-        ARG_SOURCE_PURSE, ARG_TARGET_PURSE, ARG_UNBOND_PURSE, ARG_VALIDATOR,
-        ARG_VALIDATOR_PUBLIC_KEY, ARG_VALIDATOR_PUBLIC_KEYS, METHOD_ADD_BID, METHOD_DELEGATE,
-        METHOD_DISTRIBUTE, METHOD_GET_ERA_VALIDATORS, METHOD_READ_ERA_ID,
-        METHOD_READ_SEIGNIORAGE_RECIPIENTS, METHOD_RUN_AUCTION, METHOD_SLASH, METHOD_UNDELEGATE,
-        METHOD_WITHDRAW_BID, METHOD_WITHDRAW_DELEGATOR_REWARD, METHOD_WITHDRAW_VALIDATOR_REWARD,
+        self, Auction, AuctionEvent, AuctionEvents, BlocksProposed, DelegationRate, EraId,
+        EraSummary, MintProvider, RewardInfo, RuntimeProvider, SeigniorageRecipients,
+        SlashFractions, StorageProvider, SystemProvider, ValidatorWeights, ARG_AMOUNT,
+        ARG_BLOCKS_PROPOSED, ARG_DELEGATION_RATE, ARG_DELEGATOR, ARG_DELEGATOR_PUBLIC_KEY,
+        ARG_ERA_ID, ARG_PUBLIC_KEY, ARG_REWARD_FACTORS, ARG_SLASH_FRACTIONS, ARG_SOURCE_PURSE,
+        ARG_TARGET_PURSE, ARG_UNBOND_PURSE, ARG_VALIDATOR, ARG_VALIDATOR_PUBLIC_KEY,
+        ARG_VALIDATOR_PUBLIC_KEYS, AUCTION_EVENTS_KEY, METHOD_ACTIVATE_BID, METHOD_ADD_BID,
+        METHOD_DEACTIVATE_BID, METHOD_DELEGATE, METHOD_DISTRIBUTE, METHOD_GET_ERA_VALIDATORS,
+        METHOD_READ_DELEGATION, METHOD_READ_ERA_ID, METHOD_READ_ERA_SUMMARY,
+        METHOD_READ_REWARD_INFO, METHOD_READ_SEIGNIORAGE_RECIPIENTS, METHOD_RUN_AUCTION,
+        METHOD_SLASH, METHOD_UNDELEGATE, METHOD_WITHDRAW_BID, METHOD_WITHDRAW_DELEGATOR_REWARD,
+        METHOD_WITHDRAW_VALIDATOR_REWARD,
     },
     bytesrepr::{FromBytes, ToBytes},
     mint::{METHOD_MINT, METHOD_READ_BASE_ROUND_REWARD},
@@ -78,6 +82,20 @@ impl RuntimeProvider for AuctionContract {
     fn blake2b<T: AsRef<[u8]>>(&self, data: T) -> [u8; BLAKE2B_DIGEST_LENGTH] {
         runtime::blake2b(data)
     }
+
+    fn emit_event(&mut self, event: AuctionEvent) -> StdResult<(), Error> {
+        let auction_events_uref =
+            match runtime::get_key(AUCTION_EVENTS_KEY).and_then(Key::into_uref) {
+                Some(uref) => uref,
+                None => return Ok(()),
+            };
+        let mut auction_events: AuctionEvents = storage::read(auction_events_uref)
+            .map_err(|_| Error::Storage)?
+            .unwrap_or_default();
+        auction::push_auction_event(&mut auction_events, event);
+        storage::write(auction_events_uref, auction_events);
+        Ok(())
+    }
 }
 
 impl MintProvider for AuctionContract {
@@ -140,8 +158,10 @@ pub extern "C" fn get_era_validators() {
 
 #[no_mangle]
 pub extern "C" fn read_seigniorage_recipients() {
+    let era_id: Option<EraId> = runtime::try_get_named_arg(ARG_ERA_ID);
+
     let result = AuctionContract
-        .read_seigniorage_recipients()
+        .read_seigniorage_recipients(era_id)
         .unwrap_or_revert();
 
     let cl_value = CLValue::from_t(result).unwrap_or_revert();
@@ -177,6 +197,22 @@ pub extern "C" fn withdraw_bid() {
     runtime::ret(cl_value)
 }
 
+#[no_mangle]
+pub extern "C" fn deactivate_bid() {
+    let public_key = runtime::get_named_arg(ARG_PUBLIC_KEY);
+
+    AuctionContract
+        .deactivate_bid(public_key)
+        .unwrap_or_revert();
+}
+
+#[no_mangle]
+pub extern "C" fn activate_bid() {
+    let public_key = runtime::get_named_arg(ARG_PUBLIC_KEY);
+
+    AuctionContract.activate_bid(public_key).unwrap_or_revert();
+}
+
 #[no_mangle]
 pub extern "C" fn delegate() {
     let delegator = runtime::get_named_arg(ARG_DELEGATOR);
@@ -197,10 +233,10 @@ pub extern "C" fn undelegate() {
     let delegator = runtime::get_named_arg(ARG_DELEGATOR);
     let validator = runtime::get_named_arg(ARG_VALIDATOR);
     let amount = runtime::get_named_arg(ARG_AMOUNT);
-    let unbond_purse = runtime::get_named_arg(ARG_UNBOND_PURSE);
+    let target_purse: Option<URef> = runtime::try_get_named_arg(ARG_TARGET_PURSE);
 
     let result = AuctionContract
-        .undelegate(delegator, validator, amount, unbond_purse)
+        .undelegate(delegator, validator, amount, target_purse)
         .unwrap_or_revert();
 
     let cl_value = CLValue::from_t(result).unwrap_or_revert();
@@ -219,20 +255,31 @@ pub extern "C" fn read_era_id() {
     runtime::ret(cl_value);
 }
 
+#[no_mangle]
+pub extern "C" fn read_era_summary() {
+    let result = AuctionContract.read_era_summary().unwrap_or_revert();
+    let cl_value = CLValue::from_t(result).unwrap_or_revert();
+    runtime::ret(cl_value);
+}
+
 #[no_mangle]
 pub extern "C" fn slash() {
     let validator_public_keys = runtime::get_named_arg(ARG_VALIDATOR_PUBLIC_KEYS);
+    let slash_fractions: SlashFractions =
+        runtime::try_get_named_arg(ARG_SLASH_FRACTIONS).unwrap_or_default();
     AuctionContract
-        .slash(validator_public_keys)
+        .slash(validator_public_keys, slash_fractions)
         .unwrap_or_revert();
 }
 
 #[no_mangle]
 pub fn distribute() {
     let reward_factors: BTreeMap<PublicKey, u64> = runtime::get_named_arg(ARG_REWARD_FACTORS);
+    let blocks_proposed: BlocksProposed =
+        runtime::try_get_named_arg(ARG_BLOCKS_PROPOSED).unwrap_or_default();
 
     AuctionContract
-        .distribute(reward_factors)
+        .distribute(reward_factors, blocks_proposed)
         .unwrap_or_revert();
 
     let cl_value = CLValue::from_t(()).unwrap_or_revert();
@@ -245,11 +292,11 @@ pub fn withdraw_delegator_reward() {
     let delegator_public_key: PublicKey = runtime::get_named_arg(ARG_DELEGATOR_PUBLIC_KEY);
     let target_purse: URef = runtime::get_named_arg(ARG_TARGET_PURSE);
 
-    AuctionContract
+    let result = AuctionContract
         .withdraw_delegator_reward(validator_public_key, delegator_public_key, target_purse)
         .unwrap_or_revert();
 
-    let cl_value = CLValue::from_t(()).unwrap_or_revert();
+    let cl_value = CLValue::from_t(result).unwrap_or_revert();
     runtime::ret(cl_value)
 }
 
@@ -258,11 +305,38 @@ pub fn withdraw_validator_reward() {
     let validator_public_key: PublicKey = runtime::get_named_arg(ARG_VALIDATOR_PUBLIC_KEY);
     let target_purse: URef = runtime::get_named_arg(ARG_TARGET_PURSE);
 
-    AuctionContract
+    let result = AuctionContract
         .withdraw_validator_reward(validator_public_key, target_purse)
         .unwrap_or_revert();
 
-    let cl_value = CLValue::from_t(()).unwrap_or_revert();
+    let cl_value = CLValue::from_t(result).unwrap_or_revert();
+    runtime::ret(cl_value)
+}
+
+#[no_mangle]
+pub extern "C" fn read_reward_info() {
+    let validator_public_key: PublicKey = runtime::get_named_arg(ARG_VALIDATOR_PUBLIC_KEY);
+    let delegator_public_key: Option<PublicKey> =
+        runtime::try_get_named_arg(ARG_DELEGATOR_PUBLIC_KEY);
+
+    let result = AuctionContract
+        .read_reward_info(validator_public_key, delegator_public_key)
+        .unwrap_or_revert();
+
+    let cl_value = CLValue::from_t(result).unwrap_or_revert();
+    runtime::ret(cl_value)
+}
+
+#[no_mangle]
+pub extern "C" fn read_delegation() {
+    let validator: PublicKey = runtime::get_named_arg(ARG_VALIDATOR);
+    let delegator: PublicKey = runtime::get_named_arg(ARG_DELEGATOR);
+
+    let result = AuctionContract
+        .read_delegation(validator, delegator)
+        .unwrap_or_revert();
+
+    let cl_value = CLValue::from_t(result).unwrap_or_revert();
     runtime::ret(cl_value)
 }
 
@@ -314,6 +388,24 @@ pub fn get_entry_points() -> EntryPoints {
     );
     entry_points.add_entry_point(entry_point);
 
+    let entry_point = EntryPoint::new(
+        METHOD_DEACTIVATE_BID,
+        vec![Parameter::new(ARG_PUBLIC_KEY, PublicKey::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+    entry_points.add_entry_point(entry_point);
+
+    let entry_point = EntryPoint::new(
+        METHOD_ACTIVATE_BID,
+        vec![Parameter::new(ARG_PUBLIC_KEY, PublicKey::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+    entry_points.add_entry_point(entry_point);
+
     let entry_point = EntryPoint::new(
         METHOD_DELEGATE,
         vec![
@@ -322,7 +414,7 @@ pub fn get_entry_points() -> EntryPoints {
             Parameter::new(ARG_VALIDATOR, PublicKey::cl_type()),
             Parameter::new(ARG_AMOUNT, U512::cl_type()),
         ],
-        U512::cl_type(),
+        <(URef, U512)>::cl_type(),
         EntryPointAccess::Public,
         EntryPointType::Contract,
     );
@@ -334,7 +426,7 @@ pub fn get_entry_points() -> EntryPoints {
             Parameter::new(ARG_DELEGATOR, AccountHash::cl_type()),
             Parameter::new(ARG_VALIDATOR, AccountHash::cl_type()),
             Parameter::new(ARG_AMOUNT, U512::cl_type()),
-            Parameter::new(ARG_UNBOND_PURSE, URef::cl_type()),
+            Parameter::new(ARG_TARGET_PURSE, URef::cl_type()),
         ],
         U512::cl_type(),
         EntryPointAccess::Public,
@@ -382,7 +474,7 @@ pub fn get_entry_points() -> EntryPoints {
             Parameter::new(ARG_DELEGATOR_PUBLIC_KEY, CLType::PublicKey),
             Parameter::new(ARG_TARGET_PURSE, CLType::URef),
         ],
-        CLType::Unit,
+        CLType::U512,
         EntryPointAccess::Public,
         EntryPointType::Contract,
     );
@@ -394,7 +486,7 @@ pub fn get_entry_points() -> EntryPoints {
             Parameter::new(ARG_VALIDATOR_PUBLIC_KEY, CLType::PublicKey),
             Parameter::new(ARG_TARGET_PURSE, CLType::URef),
         ],
-        CLType::Unit,
+        CLType::U512,
         EntryPointAccess::Public,
         EntryPointType::Contract,
     );
@@ -409,5 +501,35 @@ pub fn get_entry_points() -> EntryPoints {
     );
     entry_points.add_entry_point(entry_point);
 
+    let entry_point = EntryPoint::new(
+        METHOD_READ_ERA_SUMMARY,
+        vec![],
+        EraSummary::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+    entry_points.add_entry_point(entry_point);
+
+    let entry_point = EntryPoint::new(
+        METHOD_READ_REWARD_INFO,
+        vec![Parameter::new(ARG_VALIDATOR_PUBLIC_KEY, CLType::PublicKey)],
+        RewardInfo::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+    entry_points.add_entry_point(entry_point);
+
+    let entry_point = EntryPoint::new(
+        METHOD_READ_DELEGATION,
+        vec![
+            Parameter::new(ARG_VALIDATOR, CLType::PublicKey),
+            Parameter::new(ARG_DELEGATOR, CLType::PublicKey),
+        ],
+        Option::<U512>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+    entry_points.add_entry_point(entry_point);
+
     entry_points
 }
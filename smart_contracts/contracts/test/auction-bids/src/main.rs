@@ -9,10 +9,12 @@ use casper_contract::contract_api::{account, runtime, storage, system};
 
 use casper_types::{
     auction::{
-        SeigniorageRecipients, ARG_DELEGATOR, ARG_DELEGATOR_PUBLIC_KEY, ARG_REWARD_FACTORS,
-        ARG_SOURCE_PURSE, ARG_TARGET_PURSE, ARG_VALIDATOR, ARG_VALIDATOR_PUBLIC_KEY,
-        METHOD_DELEGATE, METHOD_DISTRIBUTE, METHOD_READ_SEIGNIORAGE_RECIPIENTS, METHOD_RUN_AUCTION,
-        METHOD_UNDELEGATE, METHOD_WITHDRAW_DELEGATOR_REWARD, METHOD_WITHDRAW_VALIDATOR_REWARD,
+        EraId, RewardInfo, SeigniorageRecipients, ARG_DELEGATOR, ARG_DELEGATOR_PUBLIC_KEY,
+        ARG_ERA_ID, ARG_PUBLIC_KEY, ARG_REWARD_FACTORS, ARG_SOURCE_PURSE, ARG_TARGET_PURSE,
+        ARG_VALIDATOR, ARG_VALIDATOR_PUBLIC_KEY, METHOD_ACTIVATE_BID, METHOD_DEACTIVATE_BID,
+        METHOD_DELEGATE, METHOD_DISTRIBUTE, METHOD_READ_DELEGATION, METHOD_READ_REWARD_INFO,
+        METHOD_READ_SEIGNIORAGE_RECIPIENTS, METHOD_RUN_AUCTION, METHOD_UNDELEGATE,
+        METHOD_WITHDRAW_DELEGATOR_REWARD, METHOD_WITHDRAW_VALIDATOR_REWARD,
     },
     runtime_args, ApiError, PublicKey, RuntimeArgs, URef, U512,
 };
@@ -25,8 +27,10 @@ const ARG_RUN_AUCTION: &str = "run_auction";
 const ARG_READ_SEIGNIORAGE_RECIPIENTS: &str = "read_seigniorage_recipients";
 
 const REWARD_PURSE: &str = "reward_purse";
+const WITHDRAWN_REWARD: &str = "withdrawn_reward";
 const DELEGATE_PURSE: &str = "delegate_purse";
-const UNDELEGATE_PURSE: &str = "undelegate_purse";
+const REWARD_INFO: &str = "reward_info";
+const DELEGATION: &str = "delegation";
 
 #[repr(u16)]
 enum Error {
@@ -45,6 +49,10 @@ pub extern "C" fn call() {
         METHOD_DISTRIBUTE => distribute(),
         METHOD_WITHDRAW_DELEGATOR_REWARD => withdraw_delegator_reward(),
         METHOD_WITHDRAW_VALIDATOR_REWARD => withdraw_validator_reward(),
+        METHOD_DEACTIVATE_BID => deactivate_bid(),
+        METHOD_ACTIVATE_BID => activate_bid(),
+        METHOD_READ_REWARD_INFO => read_reward_info(),
+        METHOD_READ_DELEGATION => read_delegation(),
         _ => runtime::revert(ApiError::User(Error::UnknownCommand as u16)),
     }
 }
@@ -72,16 +80,18 @@ fn undelegate() {
     let delegator: PublicKey = runtime::get_named_arg(ARG_DELEGATOR);
     let validator: PublicKey = runtime::get_named_arg(ARG_VALIDATOR);
 
-    let args = runtime_args! {
+    let target_purse: Option<URef> = runtime::try_get_named_arg(ARG_TARGET_PURSE);
+
+    let mut args = runtime_args! {
         ARG_AMOUNT => amount,
         ARG_VALIDATOR => validator,
         ARG_DELEGATOR => delegator,
     };
+    if let Some(target_purse) = target_purse {
+        args.insert(ARG_TARGET_PURSE, target_purse);
+    }
 
-    let (purse, _remaining_bid): (URef, U512) =
-        runtime::call_contract(auction, METHOD_UNDELEGATE, args);
-
-    runtime::put_key(UNDELEGATE_PURSE, purse.into());
+    let _remaining_delegation: U512 = runtime::call_contract(auction, METHOD_UNDELEGATE, args);
 }
 
 fn run_auction() {
@@ -92,7 +102,13 @@ fn run_auction() {
 
 fn read_seigniorage_recipients() {
     let auction = system::get_auction();
-    let args = runtime_args! {};
+    let era_id: Option<EraId> = runtime::try_get_named_arg(ARG_ERA_ID);
+    let args = match era_id {
+        Some(era_id) => runtime_args! {
+            ARG_ERA_ID => era_id,
+        },
+        None => runtime_args! {},
+    };
     let result: SeigniorageRecipients =
         runtime::call_contract(auction, METHOD_READ_SEIGNIORAGE_RECIPIENTS, args);
     let uref = storage::new_uref(result);
@@ -122,7 +138,10 @@ fn withdraw_delegator_reward() {
         ARG_DELEGATOR_PUBLIC_KEY => delegator_public_key,
         ARG_TARGET_PURSE => reward_purse,
     };
-    runtime::call_contract::<()>(auction, METHOD_WITHDRAW_DELEGATOR_REWARD, args);
+    let withdrawn_reward: U512 =
+        runtime::call_contract(auction, METHOD_WITHDRAW_DELEGATOR_REWARD, args);
+    let uref = storage::new_uref(withdrawn_reward);
+    runtime::put_key(WITHDRAWN_REWARD, uref.into());
 }
 
 fn withdraw_validator_reward() {
@@ -137,5 +156,58 @@ fn withdraw_validator_reward() {
         ARG_VALIDATOR_PUBLIC_KEY => validator_public_key,
         ARG_TARGET_PURSE => reward_purse,
     };
-    runtime::call_contract::<()>(auction, METHOD_WITHDRAW_VALIDATOR_REWARD, args);
+    let withdrawn_reward: U512 =
+        runtime::call_contract(auction, METHOD_WITHDRAW_VALIDATOR_REWARD, args);
+    let uref = storage::new_uref(withdrawn_reward);
+    runtime::put_key(WITHDRAWN_REWARD, uref.into());
+}
+
+fn deactivate_bid() {
+    let auction = system::get_auction();
+    let public_key: PublicKey = runtime::get_named_arg(ARG_PUBLIC_KEY);
+    let args = runtime_args! {
+        ARG_PUBLIC_KEY => public_key,
+    };
+    runtime::call_contract::<()>(auction, METHOD_DEACTIVATE_BID, args);
+}
+
+fn activate_bid() {
+    let auction = system::get_auction();
+    let public_key: PublicKey = runtime::get_named_arg(ARG_PUBLIC_KEY);
+    let args = runtime_args! {
+        ARG_PUBLIC_KEY => public_key,
+    };
+    runtime::call_contract::<()>(auction, METHOD_ACTIVATE_BID, args);
+}
+
+fn read_reward_info() {
+    let auction = system::get_auction();
+    let validator_public_key: PublicKey = runtime::get_named_arg(ARG_VALIDATOR_PUBLIC_KEY);
+    let delegator_public_key: Option<PublicKey> =
+        runtime::try_get_named_arg(ARG_DELEGATOR_PUBLIC_KEY);
+
+    let mut args = runtime_args! {
+        ARG_VALIDATOR_PUBLIC_KEY => validator_public_key,
+    };
+    if let Some(delegator_public_key) = delegator_public_key {
+        args.insert(ARG_DELEGATOR_PUBLIC_KEY, delegator_public_key);
+    }
+
+    let result: RewardInfo = runtime::call_contract(auction, METHOD_READ_REWARD_INFO, args);
+    let uref = storage::new_uref(result);
+    runtime::put_key(REWARD_INFO, uref.into());
+}
+
+fn read_delegation() {
+    let auction = system::get_auction();
+    let validator: PublicKey = runtime::get_named_arg(ARG_VALIDATOR);
+    let delegator: PublicKey = runtime::get_named_arg(ARG_DELEGATOR);
+    let args = runtime_args! {
+        ARG_VALIDATOR => validator,
+        ARG_DELEGATOR => delegator,
+    };
+
+    let result: Option<U512> = runtime::call_contract(auction, METHOD_READ_DELEGATION, args);
+    let uref = storage::new_uref(result);
+    runtime::put_key(DELEGATION, uref.into());
 }
@@ -5,7 +5,6 @@ import {CLValue} from "../../../../contract_as/assembly/clvalue";
 import {RuntimeArgs} from "../../../../contract_as/assembly/runtime_args";
 import {Pair} from "../../../../contract_as/assembly/pair";
 import {PublicKey} from "../../../../contract_as/assembly/public_key";
-import {createPurse, transferFromPurseToPurse} from "../../../../contract_as/assembly/purse";
 import {getMainPurse} from "../../../../contract_as/assembly/account";
 
 const ARG_DELEGATOR = "delegator";
@@ -56,23 +55,11 @@ export function call(): void {
     }
     let amount = amountResult.value;
 
-    let mainPurse = getMainPurse();
-    let bondingPurse = createPurse();
-
-    let ret = transferFromPurseToPurse(
-        mainPurse,
-        bondingPurse,
-        amount,
-    );
-    if (ret > 0) {
-        Error.fromErrorCode(ErrorCode.Transfer).revert();
-        return;
-    }
-
+    // The auction moves the amount into the delegation's bonding purse.
     let runtimeArgs = RuntimeArgs.fromArray([
         new Pair(ARG_DELEGATOR, CLValue.fromPublicKey(delegator)),
         new Pair(ARG_VALIDATOR, CLValue.fromPublicKey(validator)),
-        new Pair(ARG_SOURCE_PURSE, CLValue.fromURef(bondingPurse)),
+        new Pair(ARG_SOURCE_PURSE, CLValue.fromURef(getMainPurse())),
         new Pair(ARG_AMOUNT, CLValue.fromU512(amount)),
     ]);
     CL.callContract(auction, METHOD_DELEGATE, runtimeArgs);
@@ -6,11 +6,10 @@ import {RuntimeArgs} from "../../../../contract_as/assembly/runtime_args";
 import {Pair} from "../../../../contract_as/assembly/pair";
 import {Option} from "../../../../contract_as/assembly/option";
 import {URef} from "../../../../contract_as/assembly/uref";
-import {getMainPurse} from "../../../../contract_as/assembly/account";
 import {PublicKey} from "../../../../contract_as/assembly/public_key";
 
 const ARG_AMOUNT = "amount";
-const ARG_UNBOND_PURSE = "unbond_purse";
+const ARG_TARGET_PURSE = "target_purse";
 const ARG_DELEGATOR = "delegator";
 const ARG_VALIDATOR = "validator";
 const METHOD_UNDELEGATE = "undelegate";
@@ -57,32 +56,29 @@ export function call(): void {
     }
     let amount = amountResult.value;
 
-    let maybeUnbondPurseBytes = CL.getNamedArg(ARG_UNBOND_PURSE);
-    if (maybeUnbondPurseBytes === null) {
+    let maybeTargetPurseBytes = CL.getNamedArg(ARG_TARGET_PURSE);
+    if (maybeTargetPurseBytes === null) {
         Error.fromErrorCode(ErrorCode.MissingArgument).revert();
         return;
     }
-    let maybeUnbondPurse = Option.fromBytes(maybeUnbondPurseBytes);
+    let maybeTargetPurse = Option.fromBytes(maybeTargetPurseBytes);
 
-    let unbondPurse: URef;
-    if (maybeUnbondPurse.isNone()) {
-        unbondPurse = getMainPurse();
-    }
-    else {
-        let unbondPurseResult = URef.fromBytes(<Uint8Array>maybeUnbondPurse.unwrap());
-        if (unbondPurseResult.hasError()) {
+    let args: Pair<String, CLValue>[] = [
+        new Pair(ARG_AMOUNT, CLValue.fromU512(amount)),
+        new Pair(ARG_DELEGATOR, CLValue.fromPublicKey(delegator)),
+        new Pair(ARG_VALIDATOR, CLValue.fromPublicKey(validator)),
+    ];
+
+    // Without a target purse the auction pays out to the delegator's main purse.
+    if (maybeTargetPurse.isSome()) {
+        let targetPurseResult = URef.fromBytes(<Uint8Array>maybeTargetPurse.unwrap());
+        if (targetPurseResult.hasError()) {
             Error.fromErrorCode(ErrorCode.InvalidArgument).revert();
             return;
         }
-        unbondPurse = unbondPurseResult.value;
+        args.push(new Pair(ARG_TARGET_PURSE, CLValue.fromURef(targetPurseResult.value)));
     }
 
-    let runtimeArgs = RuntimeArgs.fromArray([
-        new Pair(ARG_AMOUNT, CLValue.fromU512(amount)),
-        new Pair(ARG_DELEGATOR, CLValue.fromPublicKey(delegator)),
-        new Pair(ARG_VALIDATOR, CLValue.fromPublicKey(validator)),
-        new Pair(ARG_AMOUNT, CLValue.fromU512(amount)),
-        new Pair(ARG_UNBOND_PURSE, CLValue.fromURef(unbondPurse)),
-    ]);
+    let runtimeArgs = RuntimeArgs.fromArray(args);
     CL.callContract(auction, METHOD_UNDELEGATE, runtimeArgs);
 }
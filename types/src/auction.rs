@@ -1,10 +1,13 @@
 //! Contains implementation of a Auction contract functionality.
+mod auction_event;
 mod bid;
 mod constants;
 mod detail;
+mod era_summary;
 mod era_validators;
 mod internal;
 mod providers;
+mod reward_info;
 mod seigniorage_recipient;
 mod types;
 mod unbonding_purse;
@@ -19,19 +22,23 @@ use crate::{
     Key, PublicKey, URef, U512,
 };
 
-pub use bid::{Bid, Bids};
+pub use auction_event::{push_auction_event, AuctionEvent, AuctionEvents, AUCTION_EVENTS_CAPACITY};
+pub use bid::{Bid, Bids, LegacyBid, LegacyBids};
 pub use constants::*;
+pub use era_summary::EraSummary;
 pub use era_validators::{EraId, EraValidators, ValidatorWeights};
 pub use providers::{MintProvider, RuntimeProvider, StorageProvider, SystemProvider};
+pub use reward_info::RewardInfo;
 pub use seigniorage_recipient::{
     SeigniorageRecipient, SeigniorageRecipients, SeigniorageRecipientsSnapshot,
 };
 pub use types::*;
-pub use unbonding_purse::{UnbondingPurse, UnbondingPurses};
+pub use unbonding_purse::{
+    LegacyUnbondingPurse, LegacyUnbondingPurses, UnbondingPurse, UnbondingPurses, UnbondingTarget,
+};
 
 /// Bidders mapped to their bidding purses and tokens contained therein. Delegators' tokens
-/// are kept in the validator bid purses, available for withdrawal up to the delegated number
-/// of tokens. Withdrawal moves the tokens to a delegator-controlled unbonding purse.
+/// are kept in a separate bonding purse per delegation, see `DelegatorBondingPurses`.
 pub type BidPurses = BTreeMap<PublicKey, URef>;
 
 /// Name of bid purses named key.
@@ -42,6 +49,10 @@ pub const UNBONDING_PURSES_KEY: &str = "unbonding_purses";
 /// Default number of eras that need to pass to be able to withdraw unbonded funds.
 pub const DEFAULT_UNBONDING_DELAY: u64 = 14;
 
+/// Default number of consecutive auctions a bid can stay below `MINIMUM_BID_AMOUNT` before it
+/// is evicted.
+pub const DEFAULT_BID_EVICTION_DELAY: u64 = 3;
+
 /// Bonding auction contract interface
 pub trait Auction:
     StorageProvider + SystemProvider + RuntimeProvider + MintProvider + Sized
@@ -59,15 +70,35 @@ pub trait Auction:
     /// rates and lists of delegators together with their delegated quantities from delegators.
     /// This function is publicly accessible, but intended for system use by the PoS contract,
     /// because this data is necessary for distributing seigniorage.
-    fn read_seigniorage_recipients(&mut self) -> Result<SeigniorageRecipients> {
+    ///
+    /// If `era_id` is given, returns the recipients for that era, which must be covered by the
+    /// seigniorage recipients snapshot, i.e. be between the current era and the current era plus
+    /// the auction delay.  Otherwise returns the recipients for the current era.
+    fn read_seigniorage_recipients(
+        &mut self,
+        era_id: Option<EraId>,
+    ) -> Result<SeigniorageRecipients> {
         // `era_validators` are assumed to be computed already by calling "run_auction" entrypoint.
-        let era_index = internal::get_era_id(self)?;
         let mut seigniorage_recipients_snapshot =
             internal::get_seigniorage_recipients_snapshot(self)?;
-        let seigniorage_recipients = seigniorage_recipients_snapshot
-            .remove(&era_index)
-            .unwrap_or_else(|| panic!("No seigniorage_recipients for era {}", era_index));
-        Ok(seigniorage_recipients)
+        match era_id {
+            Some(era_id) => seigniorage_recipients_snapshot
+                .remove(&era_id)
+                .ok_or(Error::MissingEraInSnapshot),
+            None => {
+                let era_index = internal::get_era_id(self)?;
+                let seigniorage_recipients = seigniorage_recipients_snapshot
+                    .remove(&era_index)
+                    .unwrap_or_else(|| panic!("No seigniorage_recipients for era {}", era_index));
+                Ok(seigniorage_recipients)
+            }
+        }
+    }
+
+    /// Returns the summary of the most recent auction, i.e. the era it selected validators for,
+    /// the winning validator weights, the number of bids considered and the total staked amount.
+    fn read_era_summary(&mut self) -> Result<EraSummary> {
+        internal::get_era_summary(self)
     }
 
     /// For a non-founder validator, this adds, or modifies, an entry in the `bids` collection and
@@ -99,8 +130,7 @@ pub trait Auction:
                 bid.bonding_purse = bonding_purse;
                 bid.delegation_rate = delegation_rate;
                 bid.staked_amount += amount;
-
-                // bid.staked_amount
+                bid.inactive_eras = 0;
             })
             .or_insert_with(|| {
                 // Create new entry.
@@ -108,7 +138,10 @@ pub trait Auction:
                     bonding_purse,
                     staked_amount: amount,
                     delegation_rate,
-                    funds_locked: None,
+                    founding_validator: false,
+                    release_era: None,
+                    inactive_eras: 0,
+                    deactivated: false,
                 }
             });
         let new_amount = bid.staked_amount;
@@ -121,7 +154,8 @@ pub trait Auction:
     /// the number of tokens and calling unbond in lieu of bond.
     ///
     /// For a founding validator, this function first checks whether they are released, and fails
-    /// if they are not.
+    /// with `Error::ValidatorFundsLocked` if the current era is before the end of the stored
+    /// locked funds period.
     ///
     /// The function returns a the new amount of motes remaining in the bid. If the target bid
     /// does not exist, the function call returns an error.
@@ -138,22 +172,22 @@ pub trait Auction:
 
         // Update bids or stakes
         let mut bids = internal::get_bids(self)?;
+        detail::update_release_eras(self, &mut bids)?;
 
         let bid = bids.get_mut(&public_key).ok_or(Error::ValidatorNotFound)?;
 
-        let new_amount = if bid.can_withdraw_funds() {
-            // Carefully decrease bonded funds
-            let new_amount = bid
-                .staked_amount
-                .checked_sub(amount)
-                .ok_or(Error::InvalidAmount)?;
-            bid.staked_amount = new_amount;
-            new_amount
-        } else {
-            // If validator is still locked-up (or with an autowin status), no withdrawals
-            // are allowed.
+        // If validator is still locked-up (or with an autowin status), no withdrawals are allowed.
+        let current_era_id = internal::get_era_id(self)?;
+        if bid.is_locked(current_era_id) {
             return Err(Error::ValidatorFundsLocked);
-        };
+        }
+
+        // Carefully decrease bonded funds
+        let new_amount = bid
+            .staked_amount
+            .checked_sub(amount)
+            .ok_or(Error::InvalidAmount)?;
+        bid.staked_amount = new_amount;
 
         if new_amount.is_zero() {
             bids.remove(&public_key).unwrap();
@@ -166,18 +200,34 @@ pub trait Auction:
         Ok(new_amount)
     }
 
+    /// Marks the validator's bid as deactivated, so that it is not considered by subsequent
+    /// auctions until it is activated again.  The bid keeps its stake and delegators.
+    ///
+    /// Returns an error if the bid does not exist.
+    fn deactivate_bid(&mut self, public_key: PublicKey) -> Result<()> {
+        detail::set_bid_deactivated(self, public_key, true)
+    }
+
+    /// Marks the validator's bid as active again, so that it is considered by subsequent auctions.
+    ///
+    /// Returns an error if the bid does not exist.
+    fn activate_bid(&mut self, public_key: PublicKey) -> Result<()> {
+        detail::set_bid_deactivated(self, public_key, false)
+    }
+
     /// Adds a new delegator to delegators, or tops off a current one. If the target validator is
     /// not in founders, the function call returns an error and does nothing.
     ///
-    /// The function calls bond in the Mint contract to transfer motes to the validator's purse and
-    /// returns a tuple of that purse and the amount of motes contained in it after the transfer.
+    /// Each delegation is held in a single bonding purse recorded for the validator and delegator
+    /// pair, which is created on the first delegation and topped off afterwards. Returns a tuple
+    /// of that purse and the total amount delegated after the transfer.
     fn delegate(
         &mut self,
         delegator_public_key: PublicKey,
         source: URef,
         validator_public_key: PublicKey,
         amount: U512,
-    ) -> Result<U512> {
+    ) -> Result<(URef, U512)> {
         let account_hash = AccountHash::from_public_key(delegator_public_key, |x| self.blake2b(x));
         if self.get_caller() != account_hash {
             return Err(Error::InvalidCaller);
@@ -189,8 +239,13 @@ pub trait Auction:
             return Err(Error::ValidatorNotFound);
         }
 
-        let (_bonding_purse, _total_amount) =
-            detail::bond(self, delegator_public_key, source, amount)?;
+        let (bonding_purse, _total_amount) = detail::bond_delegation(
+            self,
+            validator_public_key,
+            delegator_public_key,
+            source,
+            amount,
+        )?;
 
         let new_delegation_amount =
             detail::update_delegators(self, validator_public_key, delegator_public_key, amount)?;
@@ -206,21 +261,21 @@ pub trait Auction:
             internal::set_delegator_reward_map(self, delegator_reward_map)?;
         }
 
-        Ok(new_delegation_amount)
+        Ok((bonding_purse, new_delegation_amount))
     }
 
     /// Removes an amount of motes (or the entry altogether, if the remaining amount is 0) from
-    /// the entry in delegators and calls unbond in the Mint contract to create a new unbonding
-    /// purse.
+    /// the entry in delegators and creates an unbonding entry for it.
     ///
-    /// The arguments are the delegator’s key, the validator key and quantity of motes and
-    /// returns a tuple of the unbonding purse along with the remaining bid amount.
+    /// Once the unbonding delay has passed, the motes are moved from the delegation's bonding
+    /// purse to `target_purse`, or to the delegator's main purse if it is `None`. Returns the
+    /// remaining delegated amount.
     fn undelegate(
         &mut self,
         delegator_public_key: PublicKey,
         validator_public_key: PublicKey,
         amount: U512,
-        unbonding_purse: URef,
+        target_purse: Option<URef>,
     ) -> Result<U512> {
         let account_hash = AccountHash::from_public_key(delegator_public_key, |x| self.blake2b(x));
         if self.get_caller() != account_hash {
@@ -234,9 +289,6 @@ pub trait Auction:
             return Err(Error::ValidatorNotFound);
         }
 
-        let _unbonding_purse_balance =
-            detail::unbond(self, delegator_public_key, amount, unbonding_purse)?;
-
         let mut delegators = internal::get_delegators(self)?;
         let delegators_map = delegators
             .get_mut(&validator_public_key)
@@ -255,8 +307,6 @@ pub trait Auction:
             new_amount
         };
 
-        debug_assert!(_unbonding_purse_balance > new_amount);
-
         if new_amount.is_zero() {
             let _value = delegators_map
                 .remove(&delegator_public_key)
@@ -278,18 +328,55 @@ pub trait Auction:
 
         internal::set_delegators(self, delegators)?;
 
+        detail::unbond_delegation(
+            self,
+            validator_public_key,
+            delegator_public_key,
+            amount,
+            target_purse,
+        )?;
+
         Ok(new_amount)
     }
 
     /// Slashes each validator.
     ///
+    /// The fraction of a validator's bid purse balance to slash is taken from `slash_fractions`,
+    /// in units of `1 / SLASH_FRACTION_DENOMINATOR`.  A validator with no entry is slashed by
+    /// `DEFAULT_SLASH_FRACTION`, i.e. loses its whole stake.  The slashed motes are moved to the
+    /// slashed funds purse, and the validator's staked amount and pending unbonds are reduced
+    /// proportionally.  The bid is only removed if no stake remains.
+    ///
+    /// Pending undelegations from the validator are slashed by the same fraction, so that
+    /// delegators cannot escape a slash by undelegating once misbehavior has been detected.  With
+    /// the default fraction, delegators therefore lose everything they are undelegating from the
+    /// validator, and their unbonding entries are removed.  Stake which is still delegated is not
+    /// slashed.
+    ///
     /// This can be only invoked through a system call.
-    fn slash(&mut self, validator_public_keys: Vec<PublicKey>) -> Result<()> {
+    fn slash(
+        &mut self,
+        validator_public_keys: Vec<PublicKey>,
+        slash_fractions: SlashFractions,
+    ) -> Result<()> {
         if self.get_caller() != SYSTEM_ACCOUNT {
             return Err(Error::InvalidCaller);
         }
 
-        detail::quash_bid(self, &validator_public_keys)?;
+        if slash_fractions
+            .values()
+            .any(|slash_fraction| *slash_fraction > SLASH_FRACTION_DENOMINATOR)
+        {
+            return Err(Error::InvalidSlashFraction);
+        }
+
+        let slashed_funds_purse = self
+            .get_key(SLASHED_FUNDS_PURSE)
+            .ok_or(Error::MissingKey)?
+            .into_uref()
+            .ok_or(Error::InvalidKeyVariant)?;
+
+        let mut bids = internal::get_bids(self)?;
 
         let bid_purses_uref = self
             .get_key(BID_PURSES_KEY)
@@ -305,27 +392,118 @@ pub trait Auction:
         let mut unbonding_purses: UnbondingPurses =
             self.read(unbonding_purses_uref)?.ok_or(Error::Storage)?;
 
+        let delegator_bonding_purses = internal::get_delegator_bonding_purses(self)?;
+
+        let mut bids_modified = false;
         let mut bid_purses_modified = false;
         let mut unbonding_purses_modified = false;
-        for validator_account_hash in validator_public_keys {
-            if let Some(_bid_purse) = bid_purses.remove(&validator_account_hash) {
-                bid_purses_modified = true;
+        for validator_public_key in validator_public_keys {
+            let slash_fraction = slash_fractions
+                .get(&validator_public_key)
+                .copied()
+                .unwrap_or(DEFAULT_SLASH_FRACTION);
+
+            // Undelegated stake stays in the delegation's bonding purse until the unbonding delay
+            // has passed, so it is slashed from there.
+            for unbonding_purse in unbonding_purses
+                .values_mut()
+                .flat_map(|unbonding_list| unbonding_list.iter_mut())
+                .filter(|unbonding_purse| {
+                    unbonding_purse.is_delegation()
+                        && unbonding_purse.validator_public_key == validator_public_key
+                })
+            {
+                let bonding_purse = delegator_bonding_purses
+                    .get(&validator_public_key)
+                    .and_then(|bonding_purses| bonding_purses.get(&unbonding_purse.origin))
+                    .ok_or(Error::BondNotFound)?;
+                let slashed_amount = detail::scale_amount(
+                    unbonding_purse.amount,
+                    U512::from(slash_fraction),
+                    U512::from(SLASH_FRACTION_DENOMINATOR),
+                )?;
+                if !slashed_amount.is_zero() {
+                    self.transfer_from_purse_to_purse(
+                        *bonding_purse,
+                        slashed_funds_purse,
+                        slashed_amount,
+                    )?;
+                    unbonding_purse.amount -= slashed_amount;
+                    unbonding_purses_modified = true;
+                }
             }
 
-            if let Some(unbonding_list) = unbonding_purses.get_mut(&validator_account_hash) {
-                let size_before = unbonding_list.len();
+            let bid_purse = match bid_purses.get(&validator_public_key) {
+                Some(bid_purse) => *bid_purse,
+                None => {
+                    // Without a bid purse there is nothing to slash from, so the bid is removed.
+                    bids_modified |= bids.remove(&validator_public_key).is_some();
+                    continue;
+                }
+            };
+
+            let balance = self.get_balance(bid_purse)?.unwrap_or_default();
+            let slashed_amount = detail::scale_amount(
+                balance,
+                U512::from(slash_fraction),
+                U512::from(SLASH_FRACTION_DENOMINATOR),
+            )?;
+            let remaining_balance = balance - slashed_amount;
+
+            if !slashed_amount.is_zero() {
+                self.transfer_from_purse_to_purse(bid_purse, slashed_funds_purse, slashed_amount)?;
+            }
+            self.emit_event(AuctionEvent::ValidatorSlashed {
+                validator: validator_public_key,
+            })?;
+
+            // Pending unbonds are still held in the bid purse, so they shrink by the same
+            // proportion as its balance.  The validator's own undelegations are held elsewhere.
+            if let Some(unbonding_list) = unbonding_purses.get_mut(&validator_public_key) {
+                for unbonding_purse in unbonding_list
+                    .iter_mut()
+                    .filter(|unbonding_purse| !unbonding_purse.is_delegation())
+                {
+                    unbonding_purse.amount =
+                        detail::scale_amount(unbonding_purse.amount, remaining_balance, balance)?;
+                }
+                unbonding_list.retain(|unbonding_purse| !unbonding_purse.amount.is_zero());
+                unbonding_purses_modified = true;
+            }
 
-                unbonding_list.retain(|element| element.origin != validator_account_hash);
+            // Bonding rejects a zero amount, so a bid is below the minimum once it has no stake
+            // left.
+            let remaining_stake = match bids.get_mut(&validator_public_key) {
+                Some(bid) => {
+                    bid.staked_amount =
+                        detail::scale_amount(bid.staked_amount, remaining_balance, balance)?;
+                    bid.staked_amount
+                }
+                None => U512::zero(),
+            };
+            if remaining_stake.is_zero() {
+                bids.remove(&validator_public_key);
+            }
+            bids_modified = true;
 
-                unbonding_purses_modified = size_before != unbonding_list.len();
+            if remaining_balance.is_zero() {
+                bid_purses.remove(&validator_public_key);
+                bid_purses_modified = true;
             }
         }
 
+        if bids_modified {
+            internal::set_bids(self, bids)?;
+        }
+
         if bid_purses_modified {
             self.write(bid_purses_uref, bid_purses)?;
         }
 
         if unbonding_purses_modified {
+            for unbonding_list in unbonding_purses.values_mut() {
+                unbonding_list.retain(|unbonding_purse| !unbonding_purse.amount.is_zero());
+            }
             self.write(unbonding_purses_uref, unbonding_purses)?;
         }
 
@@ -347,31 +525,38 @@ pub trait Auction:
         // get allowed validator slots total
         let validator_slots = internal::get_validator_slots(self)?;
 
+        let auction_delay = internal::get_auction_delay(self)?;
+        let snapshot_size = auction_delay as usize + 1;
+
         let mut era_id = internal::get_era_id(self)?;
 
         let mut bids = internal::get_bids(self)?;
         //
         // Process locked bids
         //
-        let mut bids_modified = false;
+        let mut bids_modified = detail::update_release_eras(self, &mut bids)?;
         for bid in bids.values_mut() {
-            if let Some(locked_until) = bid.funds_locked {
-                if era_id >= locked_until {
-                    bid.funds_locked = None;
-                    bids_modified = true;
-                }
+            if bid.release_era.is_some() && !bid.is_locked(era_id) {
+                bid.release_era = None;
+                bids_modified = true;
             }
         }
 
+        //
+        // Evict bids that stayed below the minimum bid amount for too long
+        //
+        bids_modified |= detail::evict_inactive_bids(self, &mut bids)?;
+
         //
         // Compute next auction slots
         //
 
-        // Take winning validators and add them to validator_weights right away.
+        // Take winning validators and add them to validator_weights right away.  Deactivated bids
+        // are skipped altogether.
         let mut bid_weights: ValidatorWeights = {
             bids.iter()
                 .filter(|(_validator_account_hash, founding_validator)| {
-                    founding_validator.funds_locked.is_some()
+                    founding_validator.release_era.is_some() && !founding_validator.deactivated
                 })
                 .map(|(validator_account_hash, amount)| {
                     (*validator_account_hash, amount.staked_amount)
@@ -383,7 +568,7 @@ pub trait Auction:
         let bid_scores = bids
             .iter()
             .filter(|(_validator_account_hash, founding_validator)| {
-                founding_validator.funds_locked.is_none()
+                founding_validator.release_era.is_none() && !founding_validator.deactivated
             })
             .map(|(validator_account_hash, amount)| {
                 (*validator_account_hash, amount.staked_amount)
@@ -416,7 +601,7 @@ pub trait Auction:
         // Era index is assumed to be equal to era id on the consensus side.
         era_id += 1;
 
-        let next_era_id = era_id + AUCTION_DELAY;
+        let next_era_id = era_id + auction_delay;
 
         //
         // Compute seiginiorage recipients for current era
@@ -448,20 +633,24 @@ pub trait Auction:
         let seigniorage_recipients_snapshot = seigniorage_recipients_snapshot
             .into_iter()
             .rev()
-            .take(SNAPSHOT_SIZE)
+            .take(snapshot_size)
             .collect();
         internal::set_seigniorage_recipients_snapshot(self, seigniorage_recipients_snapshot)?;
 
-        // Index for next set of validators: `era_id + AUCTION_DELAY`
-        let previous_era_validators = era_validators.insert(era_id + AUCTION_DELAY, bid_weights);
+        // Overwrite the summary of the previous auction with the outcome of this one.
+        let era_summary = EraSummary::new(next_era_id, bid_weights.clone(), bids.len() as u64);
+        internal::set_era_summary(self, era_summary)?;
+
+        // Index for next set of validators: `era_id + auction_delay`
+        let previous_era_validators = era_validators.insert(next_era_id, bid_weights);
         assert!(previous_era_validators.is_none());
 
         internal::set_era_id(self, era_id)?;
-        // Keep maximum of `AUCTION_DELAY + 1` elements
+        // Keep maximum of `auction_delay + 1` elements
         let era_validators = era_validators
             .into_iter()
             .rev()
-            .take(SNAPSHOT_SIZE)
+            .take(snapshot_size)
             .collect();
 
         internal::set_era_validators(self, era_validators)?;
@@ -474,19 +663,26 @@ pub trait Auction:
     }
 
     /// Mint and distribute seigniorage rewards to validators and their delegators,
-    /// according to `reward_factors` returned by the consensus component.
-    fn distribute(&mut self, reward_factors: BTreeMap<PublicKey, u64>) -> Result<()> {
+    /// according to `reward_factors` returned by the consensus component, weighted by the number
+    /// of blocks each validator proposed in the era.
+    fn distribute(
+        &mut self,
+        reward_factors: BTreeMap<PublicKey, u64>,
+        blocks_proposed: BlocksProposed,
+    ) -> Result<()> {
         if self.get_caller() != SYSTEM_ACCOUNT {
             return Err(Error::InvalidContext);
         }
 
-        let seigniorage_recipients = self.read_seigniorage_recipients()?;
+        let seigniorage_recipients = self.read_seigniorage_recipients(None)?;
         let base_round_reward = self.read_base_round_reward()?;
 
         if reward_factors.keys().ne(seigniorage_recipients.keys()) {
             return Err(Error::MismatchedEraValidators);
         }
 
+        let reward_factors = detail::weight_by_blocks_proposed(reward_factors, &blocks_proposed);
+
         for (public_key, reward_factor) in reward_factors {
             let recipient = seigniorage_recipients
                 .get(&public_key)
@@ -503,28 +699,23 @@ pub trait Auction:
                 reward_rate * base_round_reward
             };
 
-            let delegator_total_stake: U512 = recipient.delegator_total_stake();
-
-            let delegators_part: Ratio<U512> = {
-                let commission_rate = Ratio::new(
-                    U512::from(recipient.delegation_rate),
-                    U512::from(DELEGATION_RATE_DENOMINATOR),
-                );
-                let reward_multiplier: Ratio<U512> = Ratio::new(delegator_total_stake, total_stake);
-                let delegator_reward: Ratio<U512> = total_reward * reward_multiplier;
-                let commission: Ratio<U512> = delegator_reward * commission_rate;
-                delegator_reward - commission
-            };
-
-            let delegator_rewards =
-                recipient
-                    .delegators
-                    .iter()
-                    .map(|(delegator_key, delegator_stake)| {
-                        let reward_multiplier = Ratio::new(*delegator_stake, delegator_total_stake);
-                        let reward = delegators_part * reward_multiplier;
-                        (*delegator_key, reward)
-                    });
+            let commission_rate = Ratio::new(
+                U512::from(recipient.delegation_rate),
+                U512::from(DELEGATION_RATE_DENOMINATOR),
+            );
+            // Shares are computed on the numerator of `total_reward` and then divided by its
+            // denominator so that no precision is lost before truncation.
+            let delegator_rewards = detail::compute_delegator_rewards(
+                recipient.stake,
+                &recipient.delegators,
+                *total_reward.numer(),
+            )
+            .into_iter()
+            .map(|(delegator_key, share)| {
+                let reward = share / Ratio::from(*total_reward.denom());
+                let commission = reward * commission_rate;
+                (delegator_key, reward - commission)
+            });
             let total_delegator_payout: U512 =
                 detail::update_delegator_rewards(self, public_key, delegator_rewards)?;
 
@@ -562,12 +753,18 @@ pub trait Auction:
                 total_delegator_payout,
             )
             .map_err(|_| Error::Transfer)?;
+
+            self.emit_event(AuctionEvent::RewardDistributed {
+                validator: public_key,
+                amount: validator_reward + total_delegator_payout,
+            })?;
         }
         Ok(())
     }
 
     /// Allows delegators to withdraw the seigniorage rewards they have earned.
-    /// Pays out the entire accumulated amount to the destination purse.
+    /// Pays out the entire accumulated amount to the destination purse and returns that amount, or
+    /// returns [`Error::NoRewardsToWithdraw`] if there is nothing to pay out.
     fn withdraw_delegator_reward(
         &mut self,
         validator_public_key: PublicKey,
@@ -589,27 +786,37 @@ pub trait Auction:
             .ok_or(Error::DelegatorNotFound)?;
 
         let ret = *reward_amount;
+        if ret.is_zero() {
+            return Err(Error::NoRewardsToWithdraw);
+        }
 
-        if !ret.is_zero() {
-            let source_purse = self
-                .get_key(DELEGATOR_REWARD_PURSE)
-                .ok_or(Error::MissingKey)?
-                .into_uref()
-                .ok_or(Error::InvalidKeyVariant)?;
+        let source_purse = self
+            .get_key(DELEGATOR_REWARD_PURSE)
+            .ok_or(Error::MissingKey)?
+            .into_uref()
+            .ok_or(Error::InvalidKeyVariant)?;
 
-            self.transfer_purse_to_purse(source_purse, target_purse, *reward_amount)
-                .map_err(|_| Error::Transfer)?;
+        self.transfer_purse_to_purse(source_purse, target_purse, *reward_amount)
+            .map_err(|_| Error::Transfer)?;
 
-            *reward_amount = U512::zero();
-        }
+        *reward_amount = U512::zero();
 
         outer.insert(validator_public_key, inner);
         internal::set_delegator_reward_map(self, outer)?;
+
+        let mut withdrawn_map = internal::get_delegator_withdrawn_reward_map(self)?;
+        *withdrawn_map
+            .entry(validator_public_key)
+            .or_default()
+            .entry(delegator_public_key)
+            .or_default() += ret;
+        internal::set_delegator_withdrawn_reward_map(self, withdrawn_map)?;
         Ok(ret)
     }
 
     /// Allows validators to withdraw the seigniorage rewards they have earned.
-    /// Pays out the entire accumulated amount to the destination purse.
+    /// Pays out the entire accumulated amount to the destination purse and returns that amount, or
+    /// returns [`Error::NoRewardsToWithdraw`] if there is nothing to pay out.
     fn withdraw_validator_reward(
         &mut self,
         validator_public_key: PublicKey,
@@ -627,24 +834,75 @@ pub trait Auction:
             .ok_or(Error::ValidatorNotFound)?;
 
         let ret = *reward_amount;
+        if ret.is_zero() {
+            return Err(Error::NoRewardsToWithdraw);
+        }
 
-        if !ret.is_zero() {
-            let source_purse = self
-                .get_key(VALIDATOR_REWARD_PURSE)
-                .ok_or(Error::MissingKey)?
-                .into_uref()
-                .ok_or(Error::InvalidKeyVariant)?;
+        let source_purse = self
+            .get_key(VALIDATOR_REWARD_PURSE)
+            .ok_or(Error::MissingKey)?
+            .into_uref()
+            .ok_or(Error::InvalidKeyVariant)?;
 
-            self.transfer_purse_to_purse(source_purse, target_purse, *reward_amount)
-                .map_err(|_| Error::Transfer)?;
+        self.transfer_purse_to_purse(source_purse, target_purse, *reward_amount)
+            .map_err(|_| Error::Transfer)?;
 
-            *reward_amount = U512::zero();
-        }
+        *reward_amount = U512::zero();
 
         internal::set_validator_reward_map(self, validator_reward_map)?;
+
+        let mut withdrawn_map = internal::get_validator_withdrawn_reward_map(self)?;
+        *withdrawn_map.entry(validator_public_key).or_default() += ret;
+        internal::set_validator_withdrawn_reward_map(self, withdrawn_map)?;
         Ok(ret)
     }
 
+    /// Returns the seigniorage rewards accrued, withdrawn and pending for the given validator, or
+    /// for the given delegator of that validator if `delegator_public_key` is `Some`.
+    ///
+    /// Amounts which have never been distributed or withdrawn are reported as zero, as are the
+    /// accrued and withdrawn amounts on an auction contract installed before they were tracked.
+    fn read_reward_info(
+        &mut self,
+        validator_public_key: PublicKey,
+        delegator_public_key: Option<PublicKey>,
+    ) -> Result<RewardInfo> {
+        let (accrued, withdrawn, pending) = match delegator_public_key {
+            Some(delegator_public_key) => {
+                let lookup = |map: DelegatorRewardMap| {
+                    map.get(&validator_public_key)
+                        .and_then(|inner| inner.get(&delegator_public_key))
+                        .copied()
+                        .unwrap_or_default()
+                };
+                let accrued = lookup(internal::get_delegator_accrued_reward_map(self)?);
+                let withdrawn = lookup(internal::get_delegator_withdrawn_reward_map(self)?);
+                let pending = lookup(internal::get_delegator_reward_map(self)?);
+                (accrued, withdrawn, pending)
+            }
+            None => {
+                let lookup = |map: ValidatorRewardMap| {
+                    map.get(&validator_public_key).copied().unwrap_or_default()
+                };
+                let accrued = lookup(internal::get_validator_accrued_reward_map(self)?);
+                let withdrawn = lookup(internal::get_validator_withdrawn_reward_map(self)?);
+                let pending = lookup(internal::get_validator_reward_map(self)?);
+                (accrued, withdrawn, pending)
+            }
+        };
+        Ok(RewardInfo::new(accrued, withdrawn, pending))
+    }
+
+    /// Returns the amount `delegator_public_key` currently has delegated to
+    /// `validator_public_key`, or `None` if there is no such delegation.
+    fn read_delegation(
+        &mut self,
+        validator_public_key: PublicKey,
+        delegator_public_key: PublicKey,
+    ) -> Result<Option<U512>> {
+        detail::read_delegation(self, validator_public_key, delegator_public_key)
+    }
+
     /// Reads current era id.
     fn read_era_id(&mut self) -> Result<EraId> {
         internal::get_era_id(self)
@@ -0,0 +1,260 @@
+use alloc::vec::Vec;
+
+use crate::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    CLType, CLTyped, PublicKey, U512,
+};
+
+const BID_ADDED_TAG: u8 = 0;
+const BID_WITHDRAWN_TAG: u8 = 1;
+const DELEGATED_TAG: u8 = 2;
+const UNDELEGATED_TAG: u8 = 3;
+const VALIDATOR_SLASHED_TAG: u8 = 4;
+const REWARD_DISTRIBUTED_TAG: u8 = 5;
+
+/// Maximum number of events kept under `AUCTION_EVENTS_KEY`.  Once reached, the oldest event is
+/// dropped for each new one.
+pub const AUCTION_EVENTS_CAPACITY: usize = 100;
+
+/// A change to the state of the auction, recorded for contract developers to observe.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AuctionEvent {
+    /// A validator added to its bid.
+    BidAdded {
+        /// The validator which placed the bid.
+        validator: PublicKey,
+        /// The amount added to the bid.
+        amount: U512,
+    },
+    /// A validator withdrew from its bid.
+    BidWithdrawn {
+        /// The validator which withdrew.
+        validator: PublicKey,
+        /// The amount withdrawn, which is locked until the unbonding delay has passed.
+        amount: U512,
+    },
+    /// A delegator delegated to a validator.
+    Delegated {
+        /// The validator delegated to.
+        validator: PublicKey,
+        /// The delegator.
+        delegator: PublicKey,
+        /// The amount delegated.
+        amount: U512,
+    },
+    /// A delegator undelegated from a validator.
+    Undelegated {
+        /// The validator undelegated from.
+        validator: PublicKey,
+        /// The delegator.
+        delegator: PublicKey,
+        /// The amount undelegated, which is locked until the unbonding delay has passed.
+        amount: U512,
+    },
+    /// A validator's stake was slashed.
+    ValidatorSlashed {
+        /// The slashed validator.
+        validator: PublicKey,
+    },
+    /// The seigniorage earned by a validator and its delegators was distributed.
+    RewardDistributed {
+        /// The rewarded validator.
+        validator: PublicKey,
+        /// The total reward of the validator and its delegators.
+        amount: U512,
+    },
+}
+
+impl AuctionEvent {
+    fn tag(&self) -> u8 {
+        match self {
+            AuctionEvent::BidAdded { .. } => BID_ADDED_TAG,
+            AuctionEvent::BidWithdrawn { .. } => BID_WITHDRAWN_TAG,
+            AuctionEvent::Delegated { .. } => DELEGATED_TAG,
+            AuctionEvent::Undelegated { .. } => UNDELEGATED_TAG,
+            AuctionEvent::ValidatorSlashed { .. } => VALIDATOR_SLASHED_TAG,
+            AuctionEvent::RewardDistributed { .. } => REWARD_DISTRIBUTED_TAG,
+        }
+    }
+}
+
+impl ToBytes for AuctionEvent {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut result = bytesrepr::allocate_buffer(self)?;
+        result.push(self.tag());
+        match self {
+            AuctionEvent::BidAdded { validator, amount }
+            | AuctionEvent::BidWithdrawn { validator, amount }
+            | AuctionEvent::RewardDistributed { validator, amount } => {
+                result.extend(&validator.to_bytes()?);
+                result.extend(&amount.to_bytes()?);
+            }
+            AuctionEvent::Delegated {
+                validator,
+                delegator,
+                amount,
+            }
+            | AuctionEvent::Undelegated {
+                validator,
+                delegator,
+                amount,
+            } => {
+                result.extend(&validator.to_bytes()?);
+                result.extend(&delegator.to_bytes()?);
+                result.extend(&amount.to_bytes()?);
+            }
+            AuctionEvent::ValidatorSlashed { validator } => {
+                result.extend(&validator.to_bytes()?);
+            }
+        }
+        Ok(result)
+    }
+
+    fn serialized_length(&self) -> usize {
+        let fields_length = match self {
+            AuctionEvent::BidAdded { validator, amount }
+            | AuctionEvent::BidWithdrawn { validator, amount }
+            | AuctionEvent::RewardDistributed { validator, amount } => {
+                validator.serialized_length() + amount.serialized_length()
+            }
+            AuctionEvent::Delegated {
+                validator,
+                delegator,
+                amount,
+            }
+            | AuctionEvent::Undelegated {
+                validator,
+                delegator,
+                amount,
+            } => {
+                validator.serialized_length()
+                    + delegator.serialized_length()
+                    + amount.serialized_length()
+            }
+            AuctionEvent::ValidatorSlashed { validator } => validator.serialized_length(),
+        };
+        bytesrepr::U8_SERIALIZED_LENGTH + fields_length
+    }
+}
+
+impl FromBytes for AuctionEvent {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (tag, bytes): (u8, _) = FromBytes::from_bytes(bytes)?;
+        match tag {
+            BID_ADDED_TAG | BID_WITHDRAWN_TAG | REWARD_DISTRIBUTED_TAG => {
+                let (validator, bytes) = FromBytes::from_bytes(bytes)?;
+                let (amount, bytes) = FromBytes::from_bytes(bytes)?;
+                let event = match tag {
+                    BID_ADDED_TAG => AuctionEvent::BidAdded { validator, amount },
+                    BID_WITHDRAWN_TAG => AuctionEvent::BidWithdrawn { validator, amount },
+                    _ => AuctionEvent::RewardDistributed { validator, amount },
+                };
+                Ok((event, bytes))
+            }
+            DELEGATED_TAG | UNDELEGATED_TAG => {
+                let (validator, bytes) = FromBytes::from_bytes(bytes)?;
+                let (delegator, bytes) = FromBytes::from_bytes(bytes)?;
+                let (amount, bytes) = FromBytes::from_bytes(bytes)?;
+                let event = if tag == DELEGATED_TAG {
+                    AuctionEvent::Delegated {
+                        validator,
+                        delegator,
+                        amount,
+                    }
+                } else {
+                    AuctionEvent::Undelegated {
+                        validator,
+                        delegator,
+                        amount,
+                    }
+                };
+                Ok((event, bytes))
+            }
+            VALIDATOR_SLASHED_TAG => {
+                let (validator, bytes) = FromBytes::from_bytes(bytes)?;
+                Ok((AuctionEvent::ValidatorSlashed { validator }, bytes))
+            }
+            _ => Err(bytesrepr::Error::Formatting),
+        }
+    }
+}
+
+impl CLTyped for AuctionEvent {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+/// The most recent auction events, oldest first, holding at most `AUCTION_EVENTS_CAPACITY`.
+pub type AuctionEvents = Vec<AuctionEvent>;
+
+/// Appends `event` to `events`, dropping the oldest events if `AUCTION_EVENTS_CAPACITY` would
+/// otherwise be exceeded.
+pub fn push_auction_event(events: &mut AuctionEvents, event: AuctionEvent) {
+    if events.len() >= AUCTION_EVENTS_CAPACITY {
+        let excess = events.len() + 1 - AUCTION_EVENTS_CAPACITY;
+        let _ = events.drain(..excess);
+    }
+    events.push(event);
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::{push_auction_event, AuctionEvent, AUCTION_EVENTS_CAPACITY};
+    use crate::{bytesrepr, PublicKey, U512};
+
+    const VALIDATOR: PublicKey = PublicKey::Ed25519([42; 32]);
+    const DELEGATOR: PublicKey = PublicKey::Ed25519([43; 32]);
+
+    #[test]
+    fn serialization_roundtrip() {
+        let amount = U512::from(u64::max_value());
+        let events = [
+            AuctionEvent::BidAdded {
+                validator: VALIDATOR,
+                amount,
+            },
+            AuctionEvent::BidWithdrawn {
+                validator: VALIDATOR,
+                amount,
+            },
+            AuctionEvent::Delegated {
+                validator: VALIDATOR,
+                delegator: DELEGATOR,
+                amount,
+            },
+            AuctionEvent::Undelegated {
+                validator: VALIDATOR,
+                delegator: DELEGATOR,
+                amount,
+            },
+            AuctionEvent::ValidatorSlashed {
+                validator: VALIDATOR,
+            },
+            AuctionEvent::RewardDistributed {
+                validator: VALIDATOR,
+                amount,
+            },
+        ];
+        for event in events.iter() {
+            bytesrepr::test_serialization_roundtrip(event);
+        }
+    }
+
+    #[test]
+    fn should_drop_oldest_events_once_full() {
+        let bid_added = |amount: usize| AuctionEvent::BidAdded {
+            validator: VALIDATOR,
+            amount: U512::from(amount),
+        };
+        let mut events = Vec::new();
+        for amount in 0..AUCTION_EVENTS_CAPACITY + 2 {
+            push_auction_event(&mut events, bid_added(amount));
+        }
+        assert_eq!(events.len(), AUCTION_EVENTS_CAPACITY);
+        assert_eq!(events.first(), Some(&bid_added(2)));
+        assert_eq!(events.last(), Some(&bid_added(AUCTION_EVENTS_CAPACITY + 1)));
+    }
+}
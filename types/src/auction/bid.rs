@@ -6,6 +6,13 @@ use crate::{
     CLType, CLTyped, PublicKey, URef, U512,
 };
 
+/// The version of the encoding `Bid` is serialized with.
+///
+/// Every serialized bid starts with this version, so that its layout can change without breaking
+/// the decoding of bids already in global state.  Bids written before the encoding was versioned
+/// are read with `LegacyBid` when upgrading.
+const BID_VERSION: u8 = 1;
+
 /// An entry in a founding validator map.
 #[derive(PartialEq, Debug)]
 pub struct Bid {
@@ -15,32 +22,44 @@ pub struct Bid {
     pub staked_amount: U512,
     /// Delegation rate
     pub delegation_rate: DelegationRate,
-    /// A flag that represents a winning entry.
+    /// Whether this bid was created at genesis for one of the founding validators.
+    pub founding_validator: bool,
+    /// The era from which the staked funds are released.
     ///
-    /// `Some` indicates locked funds for a specific era and an autowin status, and `None` case
-    /// means that funds are unlocked and autowin status is removed.
-    pub funds_locked: Option<EraId>,
+    /// `Some` indicates locked funds and an autowin status until the given era, and `None` means
+    /// that funds are unlocked and the autowin status is removed.
+    pub release_era: Option<EraId>,
+    /// Number of consecutive auctions this bid has been below the minimum bid amount.
+    ///
+    /// Reset on every top-up made through `add_bid`.
+    pub inactive_eras: u64,
+    /// Whether the validator has deactivated this bid, e.g. to take their node down for
+    /// maintenance.
+    ///
+    /// Deactivated bids keep their stake and delegators, but are not considered by the auction.
+    pub deactivated: bool,
 }
 
 impl Bid {
-    /// Creates new instance of a bid with locked funds.
-    pub fn new_locked(bonding_purse: URef, staked_amount: U512, funds_locked: EraId) -> Self {
+    /// Creates new instance of a founding validator's bid with funds locked until `release_era`.
+    pub fn new_founding(bonding_purse: URef, staked_amount: U512, release_era: EraId) -> Self {
         Self {
             bonding_purse,
             staked_amount,
             delegation_rate: 0,
-            funds_locked: Some(funds_locked),
+            founding_validator: true,
+            release_era: Some(release_era),
+            inactive_eras: 0,
+            deactivated: false,
         }
     }
 
-    /// Checks if a given founding validator can release its funds.
-    pub fn can_release_funds(&self) -> bool {
-        self.funds_locked.is_some()
-    }
-
-    /// Checks if a given founding validator can withdraw its funds.
-    pub fn can_withdraw_funds(&self) -> bool {
-        self.funds_locked.is_none()
+    /// Checks if the funds of this bid are still locked in the era `era_id`.
+    pub fn is_locked(&self, era_id: EraId) -> bool {
+        match self.release_era {
+            Some(release_era) => era_id < release_era,
+            None => false,
+        }
     }
 }
 
@@ -53,29 +72,86 @@ impl CLTyped for Bid {
 impl ToBytes for Bid {
     fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
         let mut result = bytesrepr::allocate_buffer(self)?;
+        result.extend(BID_VERSION.to_bytes()?);
         result.extend(self.bonding_purse.to_bytes()?);
         result.extend(self.staked_amount.to_bytes()?);
         result.extend(self.delegation_rate.to_bytes()?);
-        result.extend(self.funds_locked.to_bytes()?);
+        result.extend(self.founding_validator.to_bytes()?);
+        result.extend(self.release_era.to_bytes()?);
+        result.extend(self.inactive_eras.to_bytes()?);
+        result.extend(self.deactivated.to_bytes()?);
         Ok(result)
     }
 
     fn serialized_length(&self) -> usize {
-        self.bonding_purse.serialized_length()
+        BID_VERSION.serialized_length()
+            + self.bonding_purse.serialized_length()
             + self.staked_amount.serialized_length()
             + self.delegation_rate.serialized_length()
-            + self.funds_locked.serialized_length()
+            + self.founding_validator.serialized_length()
+            + self.release_era.serialized_length()
+            + self.inactive_eras.serialized_length()
+            + self.deactivated.serialized_length()
     }
 }
 
 impl FromBytes for Bid {
     fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (version, bytes) = u8::from_bytes(bytes)?;
+        if version != BID_VERSION {
+            return Err(bytesrepr::Error::Formatting);
+        }
         let (bonding_purse, bytes) = FromBytes::from_bytes(bytes)?;
         let (staked_amount, bytes) = FromBytes::from_bytes(bytes)?;
         let (delegation_rate, bytes) = FromBytes::from_bytes(bytes)?;
-        let (funds_locked, bytes) = FromBytes::from_bytes(bytes)?;
+        let (founding_validator, bytes) = FromBytes::from_bytes(bytes)?;
+        let (release_era, bytes) = FromBytes::from_bytes(bytes)?;
+        let (inactive_eras, bytes) = FromBytes::from_bytes(bytes)?;
+        let (deactivated, bytes) = FromBytes::from_bytes(bytes)?;
         Ok((
             Bid {
+                bonding_purse,
+                staked_amount,
+                delegation_rate,
+                founding_validator,
+                release_era,
+                inactive_eras,
+                deactivated,
+            },
+            bytes,
+        ))
+    }
+}
+
+/// A bid as serialized before the `Bid` encoding was versioned.
+///
+/// Only used to migrate the bids in global state to the current encoding on upgrade.
+#[derive(PartialEq, Debug)]
+pub struct LegacyBid {
+    /// The purse that was used for bonding.
+    pub bonding_purse: URef,
+    /// The total amount of staked tokens.
+    pub staked_amount: U512,
+    /// Delegation rate
+    pub delegation_rate: DelegationRate,
+    /// The era until which the staked funds are locked, if any.
+    pub funds_locked: Option<EraId>,
+}
+
+impl CLTyped for LegacyBid {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+impl FromBytes for LegacyBid {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (bonding_purse, bytes) = FromBytes::from_bytes(bytes)?;
+        let (staked_amount, bytes) = FromBytes::from_bytes(bytes)?;
+        let (delegation_rate, bytes) = FromBytes::from_bytes(bytes)?;
+        let (funds_locked, bytes) = FromBytes::from_bytes(bytes)?;
+        Ok((
+            LegacyBid {
                 bonding_purse,
                 staked_amount,
                 delegation_rate,
@@ -86,6 +162,25 @@ impl FromBytes for Bid {
     }
 }
 
+impl From<LegacyBid> for Bid {
+    /// Only founding validators had locked funds, so a legacy bid is taken to be a founding
+    /// validator's while its funds are still locked.
+    fn from(legacy_bid: LegacyBid) -> Self {
+        Bid {
+            bonding_purse: legacy_bid.bonding_purse,
+            staked_amount: legacy_bid.staked_amount,
+            delegation_rate: legacy_bid.delegation_rate,
+            founding_validator: legacy_bid.funds_locked.is_some(),
+            release_era: legacy_bid.funds_locked,
+            inactive_eras: 0,
+            deactivated: false,
+        }
+    }
+}
+
+/// Bids as serialized before the `Bid` encoding was versioned.
+pub type LegacyBids = BTreeMap<PublicKey, LegacyBid>;
+
 /// Founding validators' public keys mapped to their staked
 /// amount, bid purse held by the mint contract, delegation rate and
 /// whether they are to be considered for the auction, or automatically
@@ -94,15 +189,16 @@ impl FromBytes for Bid {
 /// node software and would, presumably, expire after a fixed number of eras.
 ///
 /// This structure also contains bids, and founding validator and a bid is
-/// differentiated by the `is_founding_validator` attribute.
+/// differentiated by the `founding_validator` attribute.
 pub type Bids = BTreeMap<PublicKey, Bid>;
 
 #[cfg(test)]
 mod tests {
-    use super::Bid;
+    use super::{Bid, LegacyBid, BID_VERSION};
     use crate::{
         auction::{DelegationRate, EraId},
-        bytesrepr, AccessRights, URef, U512,
+        bytesrepr::{self, FromBytes, ToBytes},
+        AccessRights, URef, U512,
     };
 
     #[test]
@@ -111,8 +207,60 @@ mod tests {
             bonding_purse: URef::new([42; 32], AccessRights::READ_ADD_WRITE),
             staked_amount: U512::one(),
             delegation_rate: DelegationRate::max_value(),
-            funds_locked: Some(EraId::max_value() - 1),
+            founding_validator: true,
+            release_era: Some(EraId::max_value() - 1),
+            inactive_eras: u64::max_value(),
+            deactivated: true,
         };
         bytesrepr::test_serialization_roundtrip(&founding_validator);
     }
+
+    #[test]
+    fn should_reject_unknown_version() {
+        let bid = Bid::new_founding(
+            URef::new([42; 32], AccessRights::READ_ADD_WRITE),
+            U512::one(),
+            1,
+        );
+        let mut bytes = bid.to_bytes().unwrap();
+        assert_eq!(bytes[0], BID_VERSION);
+        bytes[0] = BID_VERSION + 1;
+        assert_eq!(
+            Bid::from_bytes(&bytes).unwrap_err(),
+            bytesrepr::Error::Formatting
+        );
+    }
+
+    #[test]
+    fn should_convert_legacy_bid() {
+        let bonding_purse = URef::new([42; 32], AccessRights::READ_ADD_WRITE);
+        let mut legacy_bytes = bonding_purse.to_bytes().unwrap();
+        legacy_bytes.extend(U512::one().to_bytes().unwrap());
+        legacy_bytes.extend(DelegationRate::max_value().to_bytes().unwrap());
+        let funds_locked: Option<EraId> = Some(2);
+        legacy_bytes.extend(funds_locked.to_bytes().unwrap());
+
+        let legacy_bid: LegacyBid = bytesrepr::deserialize(legacy_bytes).unwrap();
+        let bid = Bid::from(legacy_bid);
+        assert_eq!(bid.bonding_purse, bonding_purse);
+        assert_eq!(bid.staked_amount, U512::one());
+        assert_eq!(bid.delegation_rate, DelegationRate::max_value());
+        assert!(bid.founding_validator);
+        assert_eq!(bid.release_era, Some(2));
+        assert_eq!(bid.inactive_eras, 0);
+        assert!(!bid.deactivated);
+    }
+
+    #[test]
+    fn should_lock_funds_until_release_era() {
+        let purse = URef::new([42; 32], AccessRights::READ_ADD_WRITE);
+        let mut founding_validator = Bid::new_founding(purse, U512::one(), 2);
+        assert!(founding_validator.founding_validator);
+        assert!(founding_validator.is_locked(0));
+        assert!(founding_validator.is_locked(1));
+        assert!(!founding_validator.is_locked(2));
+
+        founding_validator.release_era = None;
+        assert!(!founding_validator.is_locked(0));
+    }
 }
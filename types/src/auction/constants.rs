@@ -4,16 +4,16 @@ use crate::account::AccountHash;
 /// System account hash.
 pub const SYSTEM_ACCOUNT: AccountHash = AccountHash::new([0; 32]);
 
-/// Number of eras before an auction actually defines the set of validators.
+/// Default number of eras before an auction actually defines the set of validators.
 pub const AUCTION_DELAY: u64 = 3;
 
-/// Number of eras to keep track of in past.
+/// Number of eras to keep track of in past, given the default auction delay.
 pub const SNAPSHOT_SIZE: usize = AUCTION_DELAY as usize + 1;
 
 /// Initial value of era id we start at genesis.
 pub const INITIAL_ERA_ID: EraId = 0;
 
-/// Default lock period for new bid entries represented in eras.
+/// Default number of eras after genesis during which the genesis validators' stakes are locked.
 pub const DEFAULT_LOCKED_FUNDS_PERIOD: EraId = 15;
 
 /// Delegation rate is a fraction between 0-1. Validator sets the delegation rate
@@ -24,8 +24,26 @@ pub const DELEGATION_RATE_DENOMINATOR: u64 = 1_000_000_000_000;
 /// fractions, and small enough for many block rewards to fit into a u64.
 pub const BLOCK_REWARD: u64 = 1_000_000_000_000;
 
+/// Slash fractions are expressed in basis points, i.e. as a fraction of this denominator.
+pub const SLASH_FRACTION_DENOMINATOR: u64 = 10_000;
+
+/// The fraction slashed from a validator with no entry in the slash fractions passed to `slash`:
+/// the whole stake.
+pub const DEFAULT_SLASH_FRACTION: u64 = SLASH_FRACTION_DENOMINATOR;
+
+/// Minimum amount of motes an unlocked bid has to hold to be considered active.
+pub const MINIMUM_BID_AMOUNT: u64 = 1_000;
+
 /// Total validator slots allowed.
 pub const VALIDATOR_SLOTS_KEY: &str = "validator_slots";
+/// Number of eras before an auction actually defines the set of validators.
+pub const AUCTION_DELAY_KEY: &str = "auction_delay";
+/// Number of eras that need to pass to be able to withdraw unbonded funds.
+pub const UNBONDING_DELAY_KEY: &str = "unbonding_delay";
+/// Number of consecutive auctions a bid can stay below the minimum amount before it is evicted.
+pub const BID_EVICTION_DELAY_KEY: &str = "bid_eviction_delay";
+/// Number of eras for which the funds of founding validators are locked.
+pub const LOCKED_FUNDS_PERIOD_KEY: &str = "locked_funds_period";
 
 /// Named constant for `amount`.
 pub const ARG_AMOUNT: &str = "amount";
@@ -45,10 +63,14 @@ pub const ARG_VALIDATOR_PURSE: &str = "validator_purse";
 pub const ARG_VALIDATOR_KEYS: &str = "validator_keys";
 /// Named constant for `validator_public_keys`.
 pub const ARG_VALIDATOR_PUBLIC_KEYS: &str = "validator_public_keys";
+/// Named constant for `slash_fractions`.
+pub const ARG_SLASH_FRACTIONS: &str = "slash_fractions";
 /// Named constant for `era_id`.
 pub const ARG_ERA_ID: &str = "era_id";
 /// Named constant for `reward_factors`.
 pub const ARG_REWARD_FACTORS: &str = "reward_factors";
+/// Named constant for `blocks_proposed`.
+pub const ARG_BLOCKS_PROPOSED: &str = "blocks_proposed";
 /// Named constant for `validator_public_key`.
 pub const ARG_VALIDATOR_PUBLIC_KEY: &str = "validator_public_key";
 /// Named constant for `delegator_public_key`.
@@ -59,6 +81,14 @@ pub const ARG_TARGET_PURSE: &str = "target_purse";
 pub const ARG_UNBOND_PURSE: &str = "unbond_purse";
 /// Named constant for `validator_slots` argument.
 pub const ARG_VALIDATOR_SLOTS: &str = VALIDATOR_SLOTS_KEY;
+/// Named constant for `auction_delay` argument.
+pub const ARG_AUCTION_DELAY: &str = AUCTION_DELAY_KEY;
+/// Named constant for `unbonding_delay` argument.
+pub const ARG_UNBONDING_DELAY: &str = UNBONDING_DELAY_KEY;
+/// Named constant for `bid_eviction_delay` argument.
+pub const ARG_BID_EVICTION_DELAY: &str = BID_EVICTION_DELAY_KEY;
+/// Named constant for `locked_funds_period` argument.
+pub const ARG_LOCKED_FUNDS_PERIOD: &str = LOCKED_FUNDS_PERIOD_KEY;
 /// Named constant for `mint_contract_package_hash`
 pub const ARG_MINT_CONTRACT_PACKAGE_HASH: &str = "mint_contract_package_hash";
 /// Named constant for `genesis_validators`
@@ -72,6 +102,10 @@ pub const METHOD_READ_SEIGNIORAGE_RECIPIENTS: &str = "read_seigniorage_recipient
 pub const METHOD_ADD_BID: &str = "add_bid";
 /// Named constant for method `withdraw_bid`.
 pub const METHOD_WITHDRAW_BID: &str = "withdraw_bid";
+/// Named constant for method `deactivate_bid`.
+pub const METHOD_DEACTIVATE_BID: &str = "deactivate_bid";
+/// Named constant for method `activate_bid`.
+pub const METHOD_ACTIVATE_BID: &str = "activate_bid";
 /// Named constant for method `delegate`.
 pub const METHOD_DELEGATE: &str = "delegate";
 /// Named constant for method `undelegate`.
@@ -90,22 +124,44 @@ pub const METHOD_WITHDRAW_DELEGATOR_REWARD: &str = "withdraw_delegator_reward";
 pub const METHOD_WITHDRAW_VALIDATOR_REWARD: &str = "withdraw_validator_reward";
 /// Named constant for method `read_era_id`.
 pub const METHOD_READ_ERA_ID: &str = "read_era_id";
+/// Named constant for method `read_era_summary`.
+pub const METHOD_READ_ERA_SUMMARY: &str = "read_era_summary";
+/// Named constant for method `read_reward_info`.
+pub const METHOD_READ_REWARD_INFO: &str = "read_reward_info";
+/// Named constant for method `read_delegation`.
+pub const METHOD_READ_DELEGATION: &str = "read_delegation";
 
 /// Storage for `Bids`.
 pub const BIDS_KEY: &str = "bids";
 /// Storage for `Delegators`.
 pub const DELEGATORS_KEY: &str = "delegators";
+/// Storage for `DelegatorBondingPurses`.
+pub const DELEGATOR_BONDING_PURSES_KEY: &str = "delegator_bonding_purses";
 /// Storage for `EraValidators`.
 pub const ERA_VALIDATORS_KEY: &str = "era_validators";
 /// Storage for `EraId`.
 pub const ERA_ID_KEY: &str = "era_id";
 /// Storage for `SeigniorageRecipientsSnapshot`.
 pub const SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY: &str = "seigniorage_recipients_snapshot";
+/// Storage for `EraSummary`.
+pub const ERA_SUMMARY_KEY: &str = "era_summary";
+/// Storage for `AuctionEvents`.
+pub const AUCTION_EVENTS_KEY: &str = "auction_events";
 /// Storage for delegator reward purse
 pub const DELEGATOR_REWARD_PURSE: &str = "delegator_reward_purse";
 /// Storage for validator reward purse
 pub const VALIDATOR_REWARD_PURSE: &str = "validator_reward_purse";
+/// Storage for slashed funds purse
+pub const SLASHED_FUNDS_PURSE: &str = "slashed_funds_purse";
 /// Storage for `DelegatorRewardMap`.
 pub const DELEGATOR_REWARD_MAP: &str = "delegator_reward_map";
 /// Storage for `ValidatorRewardMap`.
 pub const VALIDATOR_REWARD_MAP: &str = "validator_reward_map";
+/// Storage for the `DelegatorRewardMap` of all rewards distributed so far.
+pub const DELEGATOR_ACCRUED_REWARD_MAP: &str = "delegator_accrued_reward_map";
+/// Storage for the `ValidatorRewardMap` of all rewards distributed so far.
+pub const VALIDATOR_ACCRUED_REWARD_MAP: &str = "validator_accrued_reward_map";
+/// Storage for the `DelegatorRewardMap` of rewards already withdrawn.
+pub const DELEGATOR_WITHDRAWN_REWARD_MAP: &str = "delegator_withdrawn_reward_map";
+/// Storage for the `ValidatorRewardMap` of rewards already withdrawn.
+pub const VALIDATOR_WITHDRAWN_REWARD_MAP: &str = "validator_withdrawn_reward_map";
@@ -1,12 +1,15 @@
-use alloc::vec::Vec;
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::convert::TryFrom;
 
 use num_rational::Ratio;
 
 use super::{
-    Auction, BidPurses, UnbondingPurse, UnbondingPurses, BID_PURSES_KEY, DEFAULT_UNBONDING_DELAY,
+    Auction, AuctionEvent, BidPurses, Bids, BlocksProposed, UnbondingPurse, UnbondingPurses,
+    UnbondingTarget, BID_PURSES_KEY, INITIAL_ERA_ID, LOCKED_FUNDS_PERIOD_KEY, MINIMUM_BID_AMOUNT,
     SYSTEM_ACCOUNT, UNBONDING_PURSES_KEY,
 };
 use crate::{
+    account::AccountHash,
     auction::{internal, MintProvider, RuntimeProvider, StorageProvider, SystemProvider},
     system_contract_errors::auction::{Error, Result},
     Key, PublicKey, URef, U512,
@@ -26,6 +29,7 @@ pub(crate) fn process_unbond_requests<P: Auction + ?Sized>(provider: &mut P) ->
         .ok_or(Error::MissingKey)?;
 
     let bid_purses: BidPurses = provider.read(bid_purses_uref)?.ok_or(Error::Storage)?;
+    let delegator_bonding_purses = internal::get_delegator_bonding_purses(provider)?;
 
     // Update `unbonding_purses` data
     let unbonding_purses_uref = provider
@@ -41,19 +45,41 @@ pub(crate) fn process_unbond_requests<P: Auction + ?Sized>(provider: &mut P) ->
     for unbonding_list in unbonding_purses.values_mut() {
         let mut new_unbonding_list = Vec::new();
         for unbonding_purse in unbonding_list.iter() {
-            let source = bid_purses
-                .get(&unbonding_purse.origin)
-                .ok_or(Error::BondNotFound)?;
+            let source = if unbonding_purse.is_delegation() {
+                delegator_bonding_purses
+                    .get(&unbonding_purse.validator_public_key)
+                    .and_then(|bonding_purses| bonding_purses.get(&unbonding_purse.origin))
+            } else {
+                bid_purses.get(&unbonding_purse.origin)
+            }
+            .ok_or(Error::BondNotFound)?;
             // Since `process_unbond_requests` is run before `run_auction`, we should check
             // if current era id is equal or greater than the `era_of_withdrawal` that was
             // calculated on `unbond` attempt.
-            if current_era_id >= unbonding_purse.era_of_withdrawal as u64 {
-                // Move funds from bid purse to unbonding purse
-                provider.transfer_from_purse_to_purse(
-                    *source,
-                    unbonding_purse.purse,
-                    unbonding_purse.amount,
-                )?;
+            if unbonding_purse.is_matured(current_era_id) {
+                match unbonding_purse.target {
+                    UnbondingTarget::Purse(target_purse) => {
+                        // Move funds from bid purse to unbonding purse
+                        provider.transfer_from_purse_to_purse(
+                            *source,
+                            target_purse,
+                            unbonding_purse.amount,
+                        )?;
+                    }
+                    UnbondingTarget::OriginAccount => {
+                        let account_hash =
+                            AccountHash::from_public_key(unbonding_purse.origin, |x| {
+                                provider.blake2b(x)
+                            });
+                        provider
+                            .transfer_purse_to_account(
+                                *source,
+                                account_hash,
+                                unbonding_purse.amount,
+                            )
+                            .map_err(|_| Error::Transfer)?;
+                    }
+                }
             } else {
                 new_unbonding_list.push(*unbonding_purse);
             }
@@ -103,6 +129,10 @@ pub(crate) fn bond<P: Auction + ?Sized>(
     };
 
     provider.transfer_from_purse_to_purse(source, target, amount)?;
+    provider.emit_event(AuctionEvent::BidAdded {
+        validator: public_key,
+        amount,
+    })?;
 
     let total_amount = provider.get_balance(target)?.unwrap();
 
@@ -143,10 +173,12 @@ pub(crate) fn unbond<P: Auction + ?Sized>(
         .ok_or(Error::Storage)?;
 
     let current_era_id = provider.read_era_id()?;
+    let unbonding_delay = internal::get_unbonding_delay(provider)?;
     let new_unbonding_purse = UnbondingPurse {
-        purse: unbond_purse,
+        target: UnbondingTarget::Purse(unbond_purse),
         origin: public_key,
-        era_of_withdrawal: current_era_id + DEFAULT_UNBONDING_DELAY,
+        validator_public_key: public_key,
+        era_of_withdrawal: current_era_id + unbonding_delay,
         amount,
     };
     unbonding_purses
@@ -154,12 +186,267 @@ pub(crate) fn unbond<P: Auction + ?Sized>(
         .or_default()
         .push(new_unbonding_purse);
     provider.write(unbonding_purses_uref, unbonding_purses)?;
+    provider.emit_event(AuctionEvent::BidWithdrawn {
+        validator: public_key,
+        amount,
+    })?;
 
     // Remaining motes in the validator's bid purse
     let remaining_bond = provider.get_balance(bid_purse)?.unwrap_or_default();
     Ok(remaining_bond)
 }
 
+/// Creates the bonding purse holding `delegator_public_key`'s delegation to
+/// `validator_public_key`, or tops off the existing one.
+///
+/// Returns the bonding purse's key and current amount of motes.
+pub(crate) fn bond_delegation<P: Auction + ?Sized>(
+    provider: &mut P,
+    validator_public_key: PublicKey,
+    delegator_public_key: PublicKey,
+    source: URef,
+    amount: U512,
+) -> Result<(URef, U512)> {
+    if amount.is_zero() {
+        return Err(Error::BondTooSmall);
+    }
+
+    let mut delegator_bonding_purses = internal::get_delegator_bonding_purses(provider)?;
+
+    let existing_purse = delegator_bonding_purses
+        .get(&validator_public_key)
+        .and_then(|bonding_purses| bonding_purses.get(&delegator_public_key))
+        .copied();
+    let target = match existing_purse {
+        Some(purse) => purse,
+        None => {
+            let new_purse = provider.create_purse();
+            delegator_bonding_purses
+                .entry(validator_public_key)
+                .or_default()
+                .insert(delegator_public_key, new_purse);
+            internal::set_delegator_bonding_purses(provider, delegator_bonding_purses)?;
+            new_purse
+        }
+    };
+
+    provider.transfer_from_purse_to_purse(source, target, amount)?;
+    provider.emit_event(AuctionEvent::Delegated {
+        validator: validator_public_key,
+        delegator: delegator_public_key,
+        amount,
+    })?;
+
+    let total_amount = provider.get_balance(target)?.unwrap_or_default();
+
+    Ok((target, total_amount))
+}
+
+/// Creates an unbonding entry for `amount` motes of `delegator_public_key`'s delegation to
+/// `validator_public_key`.
+///
+/// The motes are paid out to `target_purse` once the unbonding delay has passed, or to the
+/// delegator's main purse if it is `None`.
+pub(crate) fn unbond_delegation<P: Auction + ?Sized>(
+    provider: &mut P,
+    validator_public_key: PublicKey,
+    delegator_public_key: PublicKey,
+    amount: U512,
+    target_purse: Option<URef>,
+) -> Result<()> {
+    let delegator_bonding_purses = internal::get_delegator_bonding_purses(provider)?;
+    delegator_bonding_purses
+        .get(&validator_public_key)
+        .and_then(|bonding_purses| bonding_purses.get(&delegator_public_key))
+        .ok_or(Error::DelegatorNotFound)?;
+
+    let unbonding_purses_uref = provider
+        .get_key(UNBONDING_PURSES_KEY)
+        .and_then(Key::into_uref)
+        .ok_or(Error::MissingKey)?;
+    let mut unbonding_purses: UnbondingPurses = provider
+        .read(unbonding_purses_uref)?
+        .ok_or(Error::Storage)?;
+
+    let current_era_id = provider.read_era_id()?;
+    let unbonding_delay = internal::get_unbonding_delay(provider)?;
+    let new_unbonding_purse = UnbondingPurse {
+        target: target_purse.map_or(UnbondingTarget::OriginAccount, UnbondingTarget::Purse),
+        origin: delegator_public_key,
+        validator_public_key,
+        era_of_withdrawal: current_era_id + unbonding_delay,
+        amount,
+    };
+    unbonding_purses
+        .entry(delegator_public_key)
+        .or_default()
+        .push(new_unbonding_purse);
+    provider.write(unbonding_purses_uref, unbonding_purses)?;
+    provider.emit_event(AuctionEvent::Undelegated {
+        validator: validator_public_key,
+        delegator: delegator_public_key,
+        amount,
+    })?;
+
+    Ok(())
+}
+
+/// Returns the amount `delegator_public_key` currently has delegated to `validator_public_key`.
+pub(crate) fn read_delegation<P: Auction + ?Sized>(
+    provider: &mut P,
+    validator_public_key: PublicKey,
+    delegator_public_key: PublicKey,
+) -> Result<Option<U512>> {
+    let amount = internal::get_delegators(provider)?
+        .get(&validator_public_key)
+        .and_then(|delegators| delegators.get(&delegator_public_key))
+        .copied();
+    Ok(amount)
+}
+
+/// Sets whether the bid of the validator with `public_key` is deactivated, i.e. excluded from
+/// subsequent auctions.
+///
+/// The caller must be the validator's account.
+pub(crate) fn set_bid_deactivated<P: Auction + ?Sized>(
+    provider: &mut P,
+    public_key: PublicKey,
+    deactivated: bool,
+) -> Result<()> {
+    let account_hash = AccountHash::from_public_key(public_key, |x| provider.blake2b(x));
+    if provider.get_caller() != account_hash {
+        return Err(Error::InvalidCaller);
+    }
+
+    let mut bids = internal::get_bids(provider)?;
+    let bid = bids.get_mut(&public_key).ok_or(Error::ValidatorNotFound)?;
+    if bid.deactivated == deactivated {
+        return Ok(());
+    }
+    bid.deactivated = deactivated;
+    internal::set_bids(provider, bids)
+}
+
+/// Sets the release era of every still locked founding validator's bid to the end of the stored
+/// locked funds period, which upgrades may have changed since genesis.
+///
+/// Auctions installed before the period was stored keep the release eras set at genesis. Returns
+/// `true` if `bids` was modified.
+pub(crate) fn update_release_eras<P: Auction + ?Sized>(
+    provider: &mut P,
+    bids: &mut Bids,
+) -> Result<bool> {
+    if provider.get_key(LOCKED_FUNDS_PERIOD_KEY).is_none() {
+        return Ok(false);
+    }
+    let locked_funds_period = internal::get_locked_funds_period(provider)?;
+    let release_era = INITIAL_ERA_ID.saturating_add(locked_funds_period);
+
+    let mut bids_modified = false;
+    for bid in bids.values_mut() {
+        if bid.release_era.is_some() && bid.release_era != Some(release_era) {
+            bid.release_era = Some(release_era);
+            bids_modified = true;
+        }
+    }
+    Ok(bids_modified)
+}
+
+/// Tracks unlocked bids holding less than `MINIMUM_BID_AMOUNT` and evicts the ones that stayed
+/// below it for more than the configured bid eviction delay.
+///
+/// The remaining stake of an evicted bid is turned into an unbonding entry which pays out to the
+/// bidder's main purse once the unbonding delay has passed. Returns `true` if `bids` was modified.
+pub(crate) fn evict_inactive_bids<P: Auction + ?Sized>(
+    provider: &mut P,
+    bids: &mut Bids,
+) -> Result<bool> {
+    let minimum_bid_amount = U512::from(MINIMUM_BID_AMOUNT);
+    let bid_eviction_delay = internal::get_bid_eviction_delay(provider)?;
+
+    let mut bids_modified = false;
+    let mut evicted = Vec::new();
+    for (public_key, bid) in bids.iter_mut() {
+        if bid.release_era.is_some() {
+            continue;
+        }
+        if bid.staked_amount >= minimum_bid_amount {
+            if bid.inactive_eras != 0 {
+                bid.inactive_eras = 0;
+                bids_modified = true;
+            }
+            continue;
+        }
+        bid.inactive_eras += 1;
+        bids_modified = true;
+        if bid.inactive_eras > bid_eviction_delay {
+            evicted.push(*public_key);
+        }
+    }
+
+    if evicted.is_empty() {
+        return Ok(bids_modified);
+    }
+
+    let unbonding_purses_uref = provider
+        .get_key(UNBONDING_PURSES_KEY)
+        .and_then(Key::into_uref)
+        .ok_or(Error::MissingKey)?;
+    let mut unbonding_purses: UnbondingPurses = provider
+        .read(unbonding_purses_uref)?
+        .ok_or(Error::Storage)?;
+
+    let current_era_id = provider.read_era_id()?;
+    let unbonding_delay = internal::get_unbonding_delay(provider)?;
+
+    for public_key in evicted.iter().copied() {
+        let bid = bids.remove(&public_key).ok_or(Error::ValidatorNotFound)?;
+        if bid.staked_amount.is_zero() {
+            continue;
+        }
+        // Evicted stake is paid out to the validator's account.
+        let new_unbonding_purse = UnbondingPurse {
+            target: UnbondingTarget::OriginAccount,
+            origin: public_key,
+            validator_public_key: public_key,
+            era_of_withdrawal: current_era_id + unbonding_delay,
+            amount: bid.staked_amount,
+        };
+        unbonding_purses
+            .entry(public_key)
+            .or_default()
+            .push(new_unbonding_purse);
+    }
+    provider.write(unbonding_purses_uref, unbonding_purses)?;
+
+    // Delegations to an evicted validator are unbonded as well, while their accrued rewards stay
+    // withdrawable.
+    let mut delegators = internal::get_delegators(provider)?;
+    let mut evicted_delegations = Vec::new();
+    for public_key in evicted {
+        if let Some(delegations) = delegators.remove(&public_key) {
+            evicted_delegations.push((public_key, delegations));
+        }
+    }
+    internal::set_delegators(provider, delegators)?;
+    for (validator_public_key, delegations) in evicted_delegations {
+        for (delegator_public_key, amount) in delegations {
+            if amount.is_zero() {
+                continue;
+            }
+            unbond_delegation(
+                provider,
+                validator_public_key,
+                delegator_public_key,
+                amount,
+                None,
+            )?;
+        }
+    }
+
+    Ok(true)
+}
+
 /// Update delegators entry. Initialize if it doesn't exist.
 pub fn update_delegators<P>(
     provider: &mut P,
@@ -193,6 +480,8 @@ where
     let mut total_delegator_payout = U512::zero();
     let mut outer = internal::get_delegator_reward_map(provider)?;
     let mut inner = outer.remove(&validator_public_key).unwrap_or_default();
+    let mut accrued_outer = internal::get_delegator_accrued_reward_map(provider)?;
+    let accrued_inner = accrued_outer.entry(validator_public_key).or_default();
 
     for (delegator_key, delegator_reward) in rewards {
         let delegator_reward_trunc = delegator_reward.to_integer();
@@ -200,11 +489,13 @@ where
             .entry(delegator_key)
             .and_modify(|sum| *sum += delegator_reward_trunc)
             .or_insert_with(|| delegator_reward_trunc);
+        *accrued_inner.entry(delegator_key).or_default() += delegator_reward_trunc;
         total_delegator_payout += delegator_reward_trunc;
     }
 
     outer.insert(validator_public_key, inner);
     internal::set_delegator_reward_map(provider, outer)?;
+    internal::set_delegator_accrued_reward_map(provider, accrued_outer)?;
     Ok(total_delegator_payout)
 }
 
@@ -223,32 +514,146 @@ where
         .and_modify(|sum| *sum += amount)
         .or_insert_with(|| amount);
     internal::set_validator_reward_map(provider, validator_reward_map)?;
+
+    let mut accrued_map = internal::get_validator_accrued_reward_map(provider)?;
+    *accrued_map.entry(validator_public_key).or_default() += amount;
+    internal::set_validator_accrued_reward_map(provider, accrued_map)?;
     Ok(())
 }
 
-/// Removes validator entries from either founders or validators, wherever they
-/// might be found.
+/// Splits `total_validator_reward` between the validator and its delegators in proportion to
+/// their stakes, returning the delegators' shares.
 ///
-/// This function is intended to be called together with the slash function in the Mint
-/// contract.
-pub(crate) fn quash_bid<P: StorageProvider + RuntimeProvider + ?Sized>(
-    provider: &mut P,
-    validator_public_keys: &[PublicKey],
-) -> Result<()> {
-    // Clean up inside `bids`
-    let mut validators = internal::get_bids(provider)?;
+/// The validator's own share is whatever is left of `total_validator_reward` after the delegators'
+/// shares are deducted.  Each share is returned as an exact ratio so that callers can apply the
+/// validator's commission before truncating.
+pub(crate) fn compute_delegator_rewards(
+    validator_stake: U512,
+    delegator_stakes: &BTreeMap<PublicKey, U512>,
+    total_validator_reward: U512,
+) -> BTreeMap<PublicKey, Ratio<U512>> {
+    let total_stake = validator_stake + delegator_stakes.values().cloned().sum::<U512>();
+    if total_stake.is_zero() {
+        return delegator_stakes
+            .keys()
+            .map(|delegator_key| (*delegator_key, Ratio::from(U512::zero())))
+            .collect();
+    }
+    let total_validator_reward = Ratio::from(total_validator_reward);
+
+    delegator_stakes
+        .iter()
+        .map(|(delegator_key, delegator_stake)| {
+            let reward_multiplier = Ratio::new(*delegator_stake, total_stake);
+            (*delegator_key, total_validator_reward * reward_multiplier)
+        })
+        .collect()
+}
+
+/// Returns `amount` scaled by `numerator / denominator`, rounded down.
+///
+/// Returns zero if `denominator` is zero.
+pub(crate) fn scale_amount(amount: U512, numerator: U512, denominator: U512) -> Result<U512> {
+    if denominator.is_zero() {
+        return Ok(U512::zero());
+    }
+    amount
+        .checked_mul(numerator)
+        .map(|product| product / denominator)
+        .ok_or(Error::InvalidAmount)
+}
 
-    let mut modified_validators = 0usize;
+/// Blends each validator's reward factor with its share of the blocks proposed in the era.
+///
+/// Half of the resulting weight comes from the reward factor and half from the fraction of the
+/// era's blocks the validator proposed, so the sum of the reward factors is preserved.  Returns
+/// `reward_factors` unchanged if no blocks were proposed.
+pub(crate) fn weight_by_blocks_proposed(
+    reward_factors: BTreeMap<PublicKey, u64>,
+    blocks_proposed: &BlocksProposed,
+) -> BTreeMap<PublicKey, u64> {
+    let total_blocks: u128 = blocks_proposed.values().copied().map(u128::from).sum();
+    if total_blocks == 0 {
+        return reward_factors;
+    }
+    let total_reward_factor: u128 = reward_factors.values().copied().map(u128::from).sum();
 
-    for validator_public_key in validator_public_keys {
-        if validators.remove(validator_public_key).is_some() {
-            modified_validators += 1;
-        }
+    reward_factors
+        .into_iter()
+        .map(|(public_key, reward_factor)| {
+            let proposed = blocks_proposed
+                .get(&public_key)
+                .copied()
+                .unwrap_or_default();
+            let proposer_factor = total_reward_factor * u128::from(proposed) / total_blocks;
+            let weighted = (u128::from(reward_factor) + proposer_factor) / 2;
+            (
+                public_key,
+                u64::try_from(weighted).unwrap_or_else(|_| u64::max_value()),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeMap;
+
+    use num_rational::Ratio;
+    use proptest::{collection::btree_map, prelude::*};
+
+    use crate::{PublicKey, U512};
+
+    fn stake_arb() -> impl Strategy<Value = U512> {
+        any::<u64>().prop_map(U512::from)
     }
 
-    if modified_validators > 0 {
-        internal::set_bids(provider, validators)?;
+    fn delegator_stakes_arb() -> impl Strategy<Value = BTreeMap<PublicKey, U512>> {
+        btree_map(
+            any::<[u8; 32]>().prop_map(PublicKey::Ed25519),
+            stake_arb(),
+            0..10,
+        )
     }
 
-    Ok(())
+    proptest! {
+        #[test]
+        fn delegator_rewards_should_not_exceed_total_reward(
+            validator_stake in stake_arb(),
+            delegator_stakes in delegator_stakes_arb(),
+            total_validator_reward in stake_arb(),
+        ) {
+            let rewards = super::compute_delegator_rewards(
+                validator_stake,
+                &delegator_stakes,
+                total_validator_reward,
+            );
+            prop_assert_eq!(rewards.len(), delegator_stakes.len());
+            let total_delegator_reward = rewards
+                .values()
+                .fold(Ratio::from(U512::zero()), |sum, reward| sum + reward);
+            prop_assert!(total_delegator_reward <= Ratio::from(total_validator_reward));
+        }
+
+        #[test]
+        fn delegator_rewards_should_be_proportional_to_stakes(
+            validator_stake in stake_arb(),
+            delegator_stakes in delegator_stakes_arb(),
+            total_validator_reward in stake_arb(),
+        ) {
+            let rewards = super::compute_delegator_rewards(
+                validator_stake,
+                &delegator_stakes,
+                total_validator_reward,
+            );
+            for (key_a, stake_a) in &delegator_stakes {
+                for (key_b, stake_b) in &delegator_stakes {
+                    prop_assert_eq!(
+                        rewards[key_a] * Ratio::from(*stake_b),
+                        rewards[key_b] * Ratio::from(*stake_a)
+                    );
+                }
+            }
+        }
+    }
 }
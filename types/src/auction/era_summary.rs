@@ -0,0 +1,112 @@
+use alloc::vec::Vec;
+
+use super::{EraId, ValidatorWeights};
+use crate::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    CLType, CLTyped, U512,
+};
+
+/// Summary of the outcome of the most recent auction.
+#[cfg_attr(test, derive(Debug))]
+#[derive(Default, PartialEq, Clone)]
+pub struct EraSummary {
+    /// Era for which the winning validators were selected.
+    pub era_id: EraId,
+    /// Winning validators mapped to their weights.
+    pub validator_weights: ValidatorWeights,
+    /// Number of bids considered by the auction.
+    pub bids_considered: u64,
+    /// Sum of the weights of all winning validators.
+    pub total_staked: U512,
+}
+
+impl EraSummary {
+    /// Creates a new `EraSummary`, computing the total staked amount from `validator_weights`.
+    pub fn new(era_id: EraId, validator_weights: ValidatorWeights, bids_considered: u64) -> Self {
+        let total_staked = validator_weights.values().cloned().sum();
+        EraSummary {
+            era_id,
+            validator_weights,
+            bids_considered,
+            total_staked,
+        }
+    }
+}
+
+impl CLTyped for EraSummary {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+impl ToBytes for EraSummary {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut result = bytesrepr::allocate_buffer(self)?;
+        result.extend(self.era_id.to_bytes()?);
+        result.extend(self.validator_weights.to_bytes()?);
+        result.extend(self.bids_considered.to_bytes()?);
+        result.extend(self.total_staked.to_bytes()?);
+        Ok(result)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.era_id.serialized_length()
+            + self.validator_weights.serialized_length()
+            + self.bids_considered.serialized_length()
+            + self.total_staked.serialized_length()
+    }
+}
+
+impl FromBytes for EraSummary {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (era_id, bytes) = FromBytes::from_bytes(bytes)?;
+        let (validator_weights, bytes) = FromBytes::from_bytes(bytes)?;
+        let (bids_considered, bytes) = FromBytes::from_bytes(bytes)?;
+        let (total_staked, bytes) = FromBytes::from_bytes(bytes)?;
+        Ok((
+            EraSummary {
+                era_id,
+                validator_weights,
+                bids_considered,
+                total_staked,
+            },
+            bytes,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeMap;
+    use core::iter::FromIterator;
+
+    use super::EraSummary;
+    use crate::{bytesrepr, PublicKey, U512};
+
+    #[test]
+    fn serialization_roundtrip() {
+        let era_summary = EraSummary::new(
+            u64::max_value(),
+            BTreeMap::from_iter(vec![
+                (PublicKey::Ed25519([42; 32]), U512::one()),
+                (PublicKey::Ed25519([43; 32]), U512::from(u64::max_value())),
+                (PublicKey::Ed25519([44; 32]), U512::zero()),
+            ]),
+            3,
+        );
+        bytesrepr::test_serialization_roundtrip(&era_summary);
+    }
+
+    #[test]
+    fn should_sum_validator_weights() {
+        let era_summary = EraSummary::new(
+            1,
+            BTreeMap::from_iter(vec![
+                (PublicKey::Ed25519([42; 32]), U512::from(10)),
+                (PublicKey::Ed25519([43; 32]), U512::from(32)),
+            ]),
+            5,
+        );
+        assert_eq!(era_summary.total_staked, U512::from(42));
+    }
+}
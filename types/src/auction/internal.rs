@@ -2,10 +2,14 @@ use core::convert::TryInto;
 
 use crate::{
     auction::{
-        providers::StorageProvider, Bids, DelegatorRewardMap, Delegators, EraId, EraValidators,
-        RuntimeProvider, SeigniorageRecipientsSnapshot, ValidatorRewardMap, BIDS_KEY,
-        DELEGATORS_KEY, DELEGATOR_REWARD_MAP, ERA_ID_KEY, ERA_VALIDATORS_KEY,
-        SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY, VALIDATOR_REWARD_MAP, VALIDATOR_SLOTS_KEY,
+        providers::StorageProvider, Bids, DelegatorBondingPurses, DelegatorRewardMap, Delegators,
+        EraId, EraSummary, EraValidators, RuntimeProvider, SeigniorageRecipientsSnapshot,
+        ValidatorRewardMap, AUCTION_DELAY_KEY, BIDS_KEY, BID_EVICTION_DELAY_KEY, DELEGATORS_KEY,
+        DELEGATOR_ACCRUED_REWARD_MAP, DELEGATOR_BONDING_PURSES_KEY, DELEGATOR_REWARD_MAP,
+        DELEGATOR_WITHDRAWN_REWARD_MAP, ERA_ID_KEY, ERA_SUMMARY_KEY, ERA_VALIDATORS_KEY,
+        LOCKED_FUNDS_PERIOD_KEY, SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY, UNBONDING_DELAY_KEY,
+        VALIDATOR_ACCRUED_REWARD_MAP, VALIDATOR_REWARD_MAP, VALIDATOR_SLOTS_KEY,
+        VALIDATOR_WITHDRAWN_REWARD_MAP,
     },
     bytesrepr::{FromBytes, ToBytes},
     system_contract_errors::auction::{Error, Result},
@@ -34,6 +38,32 @@ where
     Ok(())
 }
 
+/// Like [`read_from`], but treats a missing named key as holding `T::default()`.
+///
+/// Used for keys which auction contracts installed before their introduction don't have.
+fn read_from_or_default<P, T>(provider: &mut P, name: &str) -> Result<T>
+where
+    P: StorageProvider + RuntimeProvider + ?Sized,
+    T: FromBytes + CLTyped + Default,
+{
+    if provider.get_key(name).is_none() {
+        return Ok(T::default());
+    }
+    read_from(provider, name)
+}
+
+/// Like [`write_to`], but does nothing if the named key is missing.
+fn write_if_present<P, T>(provider: &mut P, name: &str, value: T) -> Result<()>
+where
+    P: StorageProvider + RuntimeProvider + ?Sized,
+    T: ToBytes + CLTyped,
+{
+    if provider.get_key(name).is_none() {
+        return Ok(());
+    }
+    write_to(provider, name, value)
+}
+
 pub fn get_bids<P>(provider: &mut P) -> Result<Bids>
 where
     P: StorageProvider + RuntimeProvider + ?Sized,
@@ -62,6 +92,27 @@ where
     write_to(provider, DELEGATORS_KEY, delegators)
 }
 
+pub fn get_delegator_bonding_purses<P>(provider: &mut P) -> Result<DelegatorBondingPurses>
+where
+    P: StorageProvider + RuntimeProvider + ?Sized,
+{
+    read_from(provider, DELEGATOR_BONDING_PURSES_KEY)
+}
+
+pub fn set_delegator_bonding_purses<P>(
+    provider: &mut P,
+    delegator_bonding_purses: DelegatorBondingPurses,
+) -> Result<()>
+where
+    P: StorageProvider + RuntimeProvider + ?Sized,
+{
+    write_to(
+        provider,
+        DELEGATOR_BONDING_PURSES_KEY,
+        delegator_bonding_purses,
+    )
+}
+
 pub fn get_delegator_reward_map<P>(provider: &mut P) -> Result<DelegatorRewardMap>
 where
     P: StorageProvider + RuntimeProvider + ?Sized,
@@ -96,6 +147,90 @@ where
     write_to(provider, VALIDATOR_REWARD_MAP, validator_reward_map)
 }
 
+pub fn get_delegator_accrued_reward_map<P>(provider: &mut P) -> Result<DelegatorRewardMap>
+where
+    P: StorageProvider + RuntimeProvider + ?Sized,
+{
+    read_from_or_default(provider, DELEGATOR_ACCRUED_REWARD_MAP)
+}
+
+pub fn set_delegator_accrued_reward_map<P>(
+    provider: &mut P,
+    delegator_accrued_reward_map: DelegatorRewardMap,
+) -> Result<()>
+where
+    P: StorageProvider + RuntimeProvider + ?Sized,
+{
+    write_if_present(
+        provider,
+        DELEGATOR_ACCRUED_REWARD_MAP,
+        delegator_accrued_reward_map,
+    )
+}
+
+pub fn get_validator_accrued_reward_map<P>(provider: &mut P) -> Result<ValidatorRewardMap>
+where
+    P: StorageProvider + RuntimeProvider + ?Sized,
+{
+    read_from_or_default(provider, VALIDATOR_ACCRUED_REWARD_MAP)
+}
+
+pub fn set_validator_accrued_reward_map<P>(
+    provider: &mut P,
+    validator_accrued_reward_map: ValidatorRewardMap,
+) -> Result<()>
+where
+    P: StorageProvider + RuntimeProvider + ?Sized,
+{
+    write_if_present(
+        provider,
+        VALIDATOR_ACCRUED_REWARD_MAP,
+        validator_accrued_reward_map,
+    )
+}
+
+pub fn get_delegator_withdrawn_reward_map<P>(provider: &mut P) -> Result<DelegatorRewardMap>
+where
+    P: StorageProvider + RuntimeProvider + ?Sized,
+{
+    read_from_or_default(provider, DELEGATOR_WITHDRAWN_REWARD_MAP)
+}
+
+pub fn set_delegator_withdrawn_reward_map<P>(
+    provider: &mut P,
+    delegator_withdrawn_reward_map: DelegatorRewardMap,
+) -> Result<()>
+where
+    P: StorageProvider + RuntimeProvider + ?Sized,
+{
+    write_if_present(
+        provider,
+        DELEGATOR_WITHDRAWN_REWARD_MAP,
+        delegator_withdrawn_reward_map,
+    )
+}
+
+pub fn get_validator_withdrawn_reward_map<P>(provider: &mut P) -> Result<ValidatorRewardMap>
+where
+    P: StorageProvider + RuntimeProvider + ?Sized,
+{
+    read_from_or_default(provider, VALIDATOR_WITHDRAWN_REWARD_MAP)
+}
+
+pub fn set_validator_withdrawn_reward_map<P>(
+    provider: &mut P,
+    validator_withdrawn_reward_map: ValidatorRewardMap,
+) -> Result<()>
+where
+    P: StorageProvider + RuntimeProvider + ?Sized,
+{
+    write_if_present(
+        provider,
+        VALIDATOR_WITHDRAWN_REWARD_MAP,
+        validator_withdrawn_reward_map,
+    )
+}
+
 pub fn get_era_validators<P>(provider: &mut P) -> Result<EraValidators>
 where
     P: StorageProvider + RuntimeProvider + ?Sized,
@@ -143,6 +278,20 @@ where
     write_to(provider, SEIGNIORAGE_RECIPIENTS_SNAPSHOT_KEY, snapshot)
 }
 
+pub fn get_era_summary<P>(provider: &mut P) -> Result<EraSummary>
+where
+    P: StorageProvider + RuntimeProvider + ?Sized,
+{
+    read_from(provider, ERA_SUMMARY_KEY)
+}
+
+pub fn set_era_summary<P>(provider: &mut P, era_summary: EraSummary) -> Result<()>
+where
+    P: StorageProvider + RuntimeProvider + ?Sized,
+{
+    write_to(provider, ERA_SUMMARY_KEY, era_summary)
+}
+
 pub fn get_validator_slots<P>(provider: &mut P) -> Result<usize>
 where
     P: StorageProvider + RuntimeProvider + ?Sized,
@@ -153,3 +302,31 @@ where
         .map_err(|_| Error::InvalidValidatorSlotsValue)?;
     Ok(validator_slots)
 }
+
+pub fn get_auction_delay<P>(provider: &mut P) -> Result<u64>
+where
+    P: StorageProvider + RuntimeProvider + ?Sized,
+{
+    read_from(provider, AUCTION_DELAY_KEY)
+}
+
+pub fn get_unbonding_delay<P>(provider: &mut P) -> Result<u64>
+where
+    P: StorageProvider + RuntimeProvider + ?Sized,
+{
+    read_from(provider, UNBONDING_DELAY_KEY)
+}
+
+pub fn get_bid_eviction_delay<P>(provider: &mut P) -> Result<u64>
+where
+    P: StorageProvider + RuntimeProvider + ?Sized,
+{
+    read_from(provider, BID_EVICTION_DELAY_KEY)
+}
+
+pub fn get_locked_funds_period<P>(provider: &mut P) -> Result<EraId>
+where
+    P: StorageProvider + RuntimeProvider + ?Sized,
+{
+    read_from(provider, LOCKED_FUNDS_PERIOD_KEY)
+}
@@ -1,3 +1,4 @@
+use super::AuctionEvent;
 use crate::{
     account::AccountHash,
     bytesrepr::{FromBytes, ToBytes},
@@ -18,6 +19,10 @@ pub trait RuntimeProvider {
 
     /// Returns a 32-byte BLAKE2b digest
     fn blake2b<T: AsRef<[u8]>>(&self, data: T) -> [u8; BLAKE2B_DIGEST_LENGTH];
+
+    /// Records `event` in the ring buffer of recent auction events stored under
+    /// `AUCTION_EVENTS_KEY`.
+    fn emit_event(&mut self, event: AuctionEvent) -> Result<(), Error>;
 }
 
 /// Provides functionality of a contract storage.
@@ -0,0 +1,78 @@
+use alloc::vec::Vec;
+
+use crate::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    CLType, CLTyped, U512,
+};
+
+/// Seigniorage rewards of a validator, or of a delegator of a validator.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub struct RewardInfo {
+    /// Total amount of rewards distributed so far.
+    pub accrued: U512,
+    /// Amount of rewards already withdrawn.
+    pub withdrawn: U512,
+    /// Amount of rewards which can still be withdrawn.
+    pub pending: U512,
+}
+
+impl RewardInfo {
+    /// Creates a new `RewardInfo`.
+    pub fn new(accrued: U512, withdrawn: U512, pending: U512) -> Self {
+        RewardInfo {
+            accrued,
+            withdrawn,
+            pending,
+        }
+    }
+}
+
+impl CLTyped for RewardInfo {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+impl ToBytes for RewardInfo {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut result = bytesrepr::allocate_buffer(self)?;
+        result.extend(self.accrued.to_bytes()?);
+        result.extend(self.withdrawn.to_bytes()?);
+        result.extend(self.pending.to_bytes()?);
+        Ok(result)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.accrued.serialized_length()
+            + self.withdrawn.serialized_length()
+            + self.pending.serialized_length()
+    }
+}
+
+impl FromBytes for RewardInfo {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (accrued, bytes) = FromBytes::from_bytes(bytes)?;
+        let (withdrawn, bytes) = FromBytes::from_bytes(bytes)?;
+        let (pending, bytes) = FromBytes::from_bytes(bytes)?;
+        Ok((
+            RewardInfo {
+                accrued,
+                withdrawn,
+                pending,
+            },
+            bytes,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RewardInfo;
+    use crate::{bytesrepr, U512};
+
+    #[test]
+    fn serialization_roundtrip() {
+        let reward_info = RewardInfo::new(U512::max_value(), U512::max_value() - 1, U512::one());
+        bytesrepr::test_serialization_roundtrip(&reward_info);
+    }
+}
@@ -1,6 +1,6 @@
 use alloc::collections::BTreeMap;
 
-use crate::{PublicKey, U512};
+use crate::{PublicKey, URef, U512};
 
 /// Representation of delegation rate of tokens. Fraction of 1 in trillionths (12 decimal places).
 pub type DelegationRate = u64;
@@ -11,8 +11,19 @@ pub type DelegatedAmounts = BTreeMap<PublicKey, U512>;
 /// Validators, mapped to a list of delegators and associated bid "top-ups".
 pub type Delegators = BTreeMap<PublicKey, DelegatedAmounts>;
 
+/// Validators, mapped to delegators and the bonding purse holding each delegator's stake.
+pub type DelegatorBondingPurses = BTreeMap<PublicKey, BTreeMap<PublicKey, URef>>;
+
 /// Validators mapped to Delegators mapped to their reward amounts.
 pub type DelegatorRewardMap = BTreeMap<PublicKey, BTreeMap<PublicKey, U512>>;
 
 /// Validators mapped to their reward amounts.
 pub type ValidatorRewardMap = BTreeMap<PublicKey, U512>;
+
+/// Validators mapped to the fraction of their stake to slash, in units of
+/// `1 / SLASH_FRACTION_DENOMINATOR`.  Validators without an entry are slashed by
+/// `DEFAULT_SLASH_FRACTION`.
+pub type SlashFractions = BTreeMap<PublicKey, u64>;
+
+/// Validators mapped to the number of blocks they proposed in the era.
+pub type BlocksProposed = BTreeMap<PublicKey, u64>;
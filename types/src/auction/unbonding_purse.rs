@@ -1,37 +1,125 @@
 use alloc::{collections::BTreeMap, vec::Vec};
 
+use super::EraId;
 use crate::{
-    bytesrepr::{self, ToBytes},
+    bytesrepr::{self, ToBytes, U8_SERIALIZED_LENGTH},
     CLType, CLTyped, PublicKey, URef, U512,
 };
 use bytesrepr::FromBytes;
 
+/// Version of the `UnbondingPurse` serialization format, written ahead of its fields.
+const UNBONDING_PURSE_VERSION: u8 = 1;
+
+const PURSE_TAG: u8 = 0;
+const ORIGIN_ACCOUNT_TAG: u8 = 1;
+
+/// Where the amount of an unbonding purse is paid out to once it matures.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum UnbondingTarget {
+    /// The amount is moved to the given purse.
+    Purse(URef),
+    /// The amount is transferred to the main purse of the origin's account.
+    OriginAccount,
+}
+
+impl UnbondingTarget {
+    /// Returns the target purse, or `None` if the amount is paid out to the origin's account.
+    pub fn purse(&self) -> Option<URef> {
+        match self {
+            UnbondingTarget::Purse(purse) => Some(*purse),
+            UnbondingTarget::OriginAccount => None,
+        }
+    }
+}
+
+impl ToBytes for UnbondingTarget {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut result = bytesrepr::allocate_buffer(self)?;
+        match self {
+            UnbondingTarget::Purse(purse) => {
+                result.push(PURSE_TAG);
+                result.extend(&purse.to_bytes()?);
+            }
+            UnbondingTarget::OriginAccount => result.push(ORIGIN_ACCOUNT_TAG),
+        }
+        Ok(result)
+    }
+
+    fn serialized_length(&self) -> usize {
+        U8_SERIALIZED_LENGTH
+            + match self {
+                UnbondingTarget::Purse(purse) => purse.serialized_length(),
+                UnbondingTarget::OriginAccount => 0,
+            }
+    }
+}
+
+impl FromBytes for UnbondingTarget {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (tag, bytes): (u8, &[u8]) = FromBytes::from_bytes(bytes)?;
+        match tag {
+            PURSE_TAG => {
+                let (purse, bytes) = FromBytes::from_bytes(bytes)?;
+                Ok((UnbondingTarget::Purse(purse), bytes))
+            }
+            ORIGIN_ACCOUNT_TAG => Ok((UnbondingTarget::OriginAccount, bytes)),
+            _ => Err(bytesrepr::Error::Formatting),
+        }
+    }
+}
+
 /// Unbonding purse.
 #[cfg_attr(test, derive(Debug))]
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub struct UnbondingPurse {
-    /// Unbonding Purse.
-    pub purse: URef,
+    /// Where the unbonded amount is paid out to.
+    pub target: UnbondingTarget,
     /// Unbonding Origin.
     pub origin: PublicKey,
+    /// Validator the unbonded stake was bonded to.
+    ///
+    /// Equal to `origin` for a validator unbonding its own stake, otherwise `origin` is a
+    /// delegator and the stake is held in its delegator bonding purse.
+    pub validator_public_key: PublicKey,
     /// Unbonding Era.
     pub era_of_withdrawal: u64,
     /// Unbonding Amount.
     pub amount: U512,
 }
 
+impl UnbondingPurse {
+    /// Returns `true` if the unbonded stake was delegated rather than the validator's own.
+    pub fn is_delegation(&self) -> bool {
+        self.origin != self.validator_public_key
+    }
+
+    /// Returns `true` if the unbonding purse can be paid out in `current_era`.
+    pub fn is_matured(&self, current_era: EraId) -> bool {
+        current_era >= self.era_of_withdrawal
+    }
+
+    /// Returns the number of eras left until the unbonding purse matures, or 0 if it already has.
+    pub fn remaining_eras(&self, current_era: EraId) -> u64 {
+        self.era_of_withdrawal.saturating_sub(current_era)
+    }
+}
+
 impl ToBytes for UnbondingPurse {
     fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
         let mut result = bytesrepr::allocate_buffer(self)?;
-        result.extend(&self.purse.to_bytes()?);
+        result.push(UNBONDING_PURSE_VERSION);
+        result.extend(&self.target.to_bytes()?);
         result.extend(&self.origin.to_bytes()?);
+        result.extend(&self.validator_public_key.to_bytes()?);
         result.extend(&self.era_of_withdrawal.to_bytes()?);
         result.extend(&self.amount.to_bytes()?);
         Ok(result)
     }
     fn serialized_length(&self) -> usize {
-        self.purse.serialized_length()
+        U8_SERIALIZED_LENGTH
+            + self.target.serialized_length()
             + self.origin.serialized_length()
+            + self.validator_public_key.serialized_length()
             + self.era_of_withdrawal.serialized_length()
             + self.amount.serialized_length()
     }
@@ -39,14 +127,20 @@ impl ToBytes for UnbondingPurse {
 
 impl FromBytes for UnbondingPurse {
     fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
-        let (purse, bytes) = FromBytes::from_bytes(bytes)?;
+        let (version, bytes): (u8, &[u8]) = FromBytes::from_bytes(bytes)?;
+        if version != UNBONDING_PURSE_VERSION {
+            return Err(bytesrepr::Error::Formatting);
+        }
+        let (target, bytes) = FromBytes::from_bytes(bytes)?;
         let (origin, bytes) = FromBytes::from_bytes(bytes)?;
+        let (validator_public_key, bytes) = FromBytes::from_bytes(bytes)?;
         let (era_of_withdrawal, bytes) = FromBytes::from_bytes(bytes)?;
         let (amount, bytes) = FromBytes::from_bytes(bytes)?;
         Ok((
             UnbondingPurse {
-                purse,
+                target,
                 origin,
+                validator_public_key,
                 era_of_withdrawal,
                 amount,
             },
@@ -65,20 +159,142 @@ impl CLTyped for UnbondingPurse {
 /// withdrawal, tokens and expiration timer in eras.
 pub type UnbondingPurses = BTreeMap<PublicKey, Vec<UnbondingPurse>>;
 
+/// An unbonding purse as serialized before the `UnbondingPurse` encoding was versioned.
+///
+/// Only used to migrate the unbonding purses in global state to the current encoding on upgrade.
+#[derive(PartialEq, Debug)]
+pub struct LegacyUnbondingPurse {
+    /// Unbonding Purse.
+    pub purse: URef,
+    /// Unbonding Origin.
+    pub origin: PublicKey,
+    /// Unbonding Era.
+    pub era_of_withdrawal: u64,
+    /// Unbonding Amount.
+    pub amount: U512,
+}
+
+impl CLTyped for LegacyUnbondingPurse {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+impl FromBytes for LegacyUnbondingPurse {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (purse, bytes) = FromBytes::from_bytes(bytes)?;
+        let (origin, bytes) = FromBytes::from_bytes(bytes)?;
+        let (era_of_withdrawal, bytes) = FromBytes::from_bytes(bytes)?;
+        let (amount, bytes) = FromBytes::from_bytes(bytes)?;
+        Ok((
+            LegacyUnbondingPurse {
+                purse,
+                origin,
+                era_of_withdrawal,
+                amount,
+            },
+            bytes,
+        ))
+    }
+}
+
+impl From<LegacyUnbondingPurse> for UnbondingPurse {
+    /// Legacy unbonding purses were only created by validators withdrawing their own stake into a
+    /// given purse.
+    fn from(legacy_unbonding_purse: LegacyUnbondingPurse) -> Self {
+        UnbondingPurse {
+            target: UnbondingTarget::Purse(legacy_unbonding_purse.purse),
+            origin: legacy_unbonding_purse.origin,
+            validator_public_key: legacy_unbonding_purse.origin,
+            era_of_withdrawal: legacy_unbonding_purse.era_of_withdrawal,
+            amount: legacy_unbonding_purse.amount,
+        }
+    }
+}
+
+/// Unbonding purses as serialized before the `UnbondingPurse` encoding was versioned.
+pub type LegacyUnbondingPurses = BTreeMap<PublicKey, Vec<LegacyUnbondingPurse>>;
+
 #[cfg(test)]
 mod tests {
-    use super::UnbondingPurse;
-    use crate::{bytesrepr, AccessRights, PublicKey, URef, U512};
+    use super::{LegacyUnbondingPurse, UnbondingPurse, UnbondingTarget, UNBONDING_PURSE_VERSION};
+    use crate::{
+        bytesrepr::{self, FromBytes, ToBytes},
+        AccessRights, PublicKey, URef, U512,
+    };
+
+    const ERA_OF_WITHDRAWAL: u64 = 10;
+
+    fn unbonding_purse(era_of_withdrawal: u64) -> UnbondingPurse {
+        UnbondingPurse {
+            target: UnbondingTarget::Purse(URef::new([42; 32], AccessRights::READ_ADD_WRITE)),
+            origin: PublicKey::Ed25519([42; 32]),
+            validator_public_key: PublicKey::Ed25519([43; 32]),
+            era_of_withdrawal,
+            amount: U512::max_value() - 1,
+        }
+    }
 
     #[test]
     fn serialization_roundtrip() {
-        let public_key = PublicKey::Ed25519([42; 32]);
+        let unbonding_purse = unbonding_purse(u64::max_value());
+        bytesrepr::test_serialization_roundtrip(&unbonding_purse);
+    }
+
+    #[test]
+    fn serialization_roundtrip_with_origin_account_target() {
         let unbonding_purse = UnbondingPurse {
-            purse: URef::new([42; 32], AccessRights::READ_ADD_WRITE),
-            origin: public_key,
-            era_of_withdrawal: u64::max_value(),
-            amount: U512::max_value() - 1,
+            target: UnbondingTarget::OriginAccount,
+            ..unbonding_purse(u64::max_value())
         };
         bytesrepr::test_serialization_roundtrip(&unbonding_purse);
     }
+
+    #[test]
+    fn should_reject_unknown_version() {
+        let mut bytes = unbonding_purse(ERA_OF_WITHDRAWAL).to_bytes().unwrap();
+        assert_eq!(bytes[0], UNBONDING_PURSE_VERSION);
+        bytes[0] = UNBONDING_PURSE_VERSION + 1;
+        assert_eq!(
+            UnbondingPurse::from_bytes(&bytes).unwrap_err(),
+            bytesrepr::Error::Formatting
+        );
+    }
+
+    #[test]
+    fn should_convert_legacy_unbonding_purse() {
+        let purse = URef::new([42; 32], AccessRights::READ_ADD_WRITE);
+        let origin = PublicKey::Ed25519([42; 32]);
+        let amount = U512::from(1_000);
+        let mut bytes = purse.to_bytes().unwrap();
+        bytes.extend(origin.to_bytes().unwrap());
+        bytes.extend(ERA_OF_WITHDRAWAL.to_bytes().unwrap());
+        bytes.extend(amount.to_bytes().unwrap());
+
+        let (legacy_unbonding_purse, remainder) = LegacyUnbondingPurse::from_bytes(&bytes).unwrap();
+        assert!(remainder.is_empty());
+        let unbonding_purse = UnbondingPurse::from(legacy_unbonding_purse);
+        assert_eq!(unbonding_purse.target, UnbondingTarget::Purse(purse));
+        assert_eq!(unbonding_purse.origin, origin);
+        assert!(!unbonding_purse.is_delegation());
+        assert_eq!(unbonding_purse.era_of_withdrawal, ERA_OF_WITHDRAWAL);
+        assert_eq!(unbonding_purse.amount, amount);
+    }
+
+    #[test]
+    fn should_be_matured_from_era_of_withdrawal() {
+        let unbonding_purse = unbonding_purse(ERA_OF_WITHDRAWAL);
+        assert!(!unbonding_purse.is_matured(ERA_OF_WITHDRAWAL - 1));
+        assert!(unbonding_purse.is_matured(ERA_OF_WITHDRAWAL));
+        assert!(unbonding_purse.is_matured(ERA_OF_WITHDRAWAL + 1));
+    }
+
+    #[test]
+    fn should_count_remaining_eras_down_to_zero() {
+        let unbonding_purse = unbonding_purse(ERA_OF_WITHDRAWAL);
+        assert_eq!(unbonding_purse.remaining_eras(ERA_OF_WITHDRAWAL - 1), 1);
+        assert_eq!(unbonding_purse.remaining_eras(ERA_OF_WITHDRAWAL), 0);
+        assert_eq!(unbonding_purse.remaining_eras(ERA_OF_WITHDRAWAL + 1), 0);
+        assert_eq!(unbonding_purse.remaining_eras(0), ERA_OF_WITHDRAWAL);
+    }
 }
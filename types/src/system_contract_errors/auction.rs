@@ -94,6 +94,54 @@ pub enum Error {
     /// Invalid number of validator slots.
     #[fail(display = "Invalid number of validator slots")]
     InvalidValidatorSlotsValue = 24,
+    /// Slash fraction exceeds the slash fraction denominator.
+    #[fail(display = "Invalid slash fraction")]
+    InvalidSlashFraction = 25,
+    /// The requested era is not covered by the seigniorage recipients snapshot.
+    #[fail(display = "Era missing from seigniorage recipients snapshot")]
+    MissingEraInSnapshot = 26,
+    /// Raised when a validator or delegator attempts to withdraw rewards, but has none accrued.
+    #[fail(display = "No rewards to withdraw")]
+    NoRewardsToWithdraw = 27,
+}
+
+impl Error {
+    /// Returns a static, human-readable description of the error.
+    ///
+    /// Unlike the `Display` implementation, this doesn't require any formatting machinery, so it
+    /// can be used in `no_std` smart contract code, e.g. before calling `runtime::revert`.
+    pub fn display_message(&self) -> &'static str {
+        match self {
+            Error::MissingKey => "Missing key",
+            Error::InvalidKeyVariant => "Invalid key variant",
+            Error::MissingValue => "Missing value",
+            Error::Serialization => "Serialization error",
+            Error::Transfer => "Transfer error",
+            Error::InvalidAmount => "Invalid amount",
+            Error::BidNotFound => "Bid not found",
+            Error::ValidatorNotFound => "Validator not found",
+            Error::DelegatorNotFound => "Delegator not found",
+            Error::Storage => "Storage error",
+            Error::Bonding => "Bonding error",
+            Error::Unbonding => "Unbonding error",
+            Error::ReleaseFounderStake => "Unable to release founder stake",
+            Error::GetBalance => "Unable to get purse balance",
+            Error::InvalidContext => "Invalid context",
+            Error::ValidatorFundsLocked => "Validator's funds are locked",
+            Error::InvalidCaller => "Not a system account",
+            Error::BondNotFound => "Validator's bond not found",
+            Error::CreatePurseFailed => "Unable to create purse",
+            Error::UnbondTooLarge => "Unbond is too large",
+            Error::BondTooSmall => "Bond is too small",
+            Error::MissingDelegations => "Validators has not received any delegations",
+            Error::MismatchedEraValidators => "Mismatched era validator sets to distribute rewards",
+            Error::MintReward => "Failed to mint rewards",
+            Error::InvalidValidatorSlotsValue => "Invalid number of validator slots",
+            Error::InvalidSlashFraction => "Invalid slash fraction",
+            Error::MissingEraInSnapshot => "Era missing from seigniorage recipients snapshot",
+            Error::NoRewardsToWithdraw => "No rewards to withdraw",
+        }
+    }
 }
 
 impl CLTyped for Error {
@@ -137,6 +185,12 @@ impl TryFrom<u8> for Error {
             d if d == Error::MissingDelegations as u8 => Ok(Error::MissingDelegations),
             d if d == Error::MismatchedEraValidators as u8 => Ok(Error::MismatchedEraValidators),
             d if d == Error::MintReward as u8 => Ok(Error::MintReward),
+            d if d == Error::InvalidValidatorSlotsValue as u8 => {
+                Ok(Error::InvalidValidatorSlotsValue)
+            }
+            d if d == Error::InvalidSlashFraction as u8 => Ok(Error::InvalidSlashFraction),
+            d if d == Error::MissingEraInSnapshot as u8 => Ok(Error::MissingEraInSnapshot),
+            d if d == Error::NoRewardsToWithdraw as u8 => Ok(Error::NoRewardsToWithdraw),
             _ => Err(TryFromU8ForError(())),
         }
     }
@@ -189,3 +243,72 @@ impl From<PurseLookupError> for Error {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+    use core::convert::TryFrom;
+
+    use super::Error;
+
+    #[test]
+    fn display_message_should_describe_each_variant() {
+        let expected = [
+            (Error::MissingKey, "Missing key"),
+            (Error::InvalidKeyVariant, "Invalid key variant"),
+            (Error::MissingValue, "Missing value"),
+            (Error::Serialization, "Serialization error"),
+            (Error::Transfer, "Transfer error"),
+            (Error::InvalidAmount, "Invalid amount"),
+            (Error::BidNotFound, "Bid not found"),
+            (Error::ValidatorNotFound, "Validator not found"),
+            (Error::DelegatorNotFound, "Delegator not found"),
+            (Error::Storage, "Storage error"),
+            (Error::Bonding, "Bonding error"),
+            (Error::Unbonding, "Unbonding error"),
+            (
+                Error::ReleaseFounderStake,
+                "Unable to release founder stake",
+            ),
+            (Error::GetBalance, "Unable to get purse balance"),
+            (Error::InvalidContext, "Invalid context"),
+            (Error::ValidatorFundsLocked, "Validator's funds are locked"),
+            (Error::InvalidCaller, "Not a system account"),
+            (Error::BondNotFound, "Validator's bond not found"),
+            (Error::CreatePurseFailed, "Unable to create purse"),
+            (Error::UnbondTooLarge, "Unbond is too large"),
+            (Error::BondTooSmall, "Bond is too small"),
+            (
+                Error::MissingDelegations,
+                "Validators has not received any delegations",
+            ),
+            (
+                Error::MismatchedEraValidators,
+                "Mismatched era validator sets to distribute rewards",
+            ),
+            (Error::MintReward, "Failed to mint rewards"),
+            (
+                Error::InvalidValidatorSlotsValue,
+                "Invalid number of validator slots",
+            ),
+            (Error::InvalidSlashFraction, "Invalid slash fraction"),
+            (
+                Error::MissingEraInSnapshot,
+                "Era missing from seigniorage recipients snapshot",
+            ),
+            (Error::NoRewardsToWithdraw, "No rewards to withdraw"),
+        ];
+        for (error, message) in expected.iter() {
+            assert_eq!(error.display_message(), *message);
+        }
+    }
+
+    #[test]
+    fn display_message_should_match_display() {
+        for value in 0..=u8::max_value() {
+            if let Ok(error) = Error::try_from(value) {
+                assert_eq!(error.display_message(), error.to_string());
+            }
+        }
+    }
+}